@@ -0,0 +1,196 @@
+//! LRU Cache in a Fixed Arena
+//!
+//! The usual LRU cache is a HashMap plus a linked list of heap nodes. Without
+//! an allocator both halves change: the nodes live in an array of `N` slots
+//! and link to each other by index instead of by pointer, and lookup scans
+//! the occupied slots instead of hashing (`HashMap` is `std`: its random
+//! seed comes from the OS). A scan of a few dozen keys that sit next to each
+//! other in memory is cheap; past that, a fixed-size hash table would be the
+//! next step.
+
+use core::fmt;
+
+/// Index meaning "no slot", the arena's null pointer
+const NIL: usize = usize::MAX;
+
+struct Slot<K, V> {
+    key: K,
+    value: V,
+    /// The slot used more recently than this one, or `NIL`
+    prev: usize,
+    /// The slot used less recently than this one, or `NIL`
+    next: usize,
+}
+
+/// At most `N` entries, evicting the least recently used one when full.
+/// Slots fill in order and are only ever reused, never freed.
+pub struct ArenaLru<K, V, const N: usize> {
+    slots: [Option<Slot<K, V>>; N],
+    /// Most recently used
+    head: usize,
+    /// Least recently used: the next to go
+    tail: usize,
+    len: usize,
+}
+
+impl<K: Eq, V, const N: usize> ArenaLru<K, V, N> {
+    pub const fn new() -> Self {
+        ArenaLru { slots: [const { None }; N], head: NIL, tail: NIL, len: 0 }
+    }
+
+    /// The value for `key`, which becomes the most recently used entry
+    pub fn get(&mut self, key: &K) -> Option<&V> {
+        let index = self.find(key)?;
+        self.touch(index);
+        Some(&self.slot(index).value)
+    }
+
+    /// Insert or update `key`, making it the most recently used entry;
+    /// returns the entry evicted to make room, if any
+    pub fn put(&mut self, key: K, value: V) -> Option<(K, V)> {
+        if let Some(index) = self.find(&key) {
+            self.slot_mut(index).value = value;
+            self.touch(index);
+            return None;
+        }
+        if N == 0 {
+            return Some((key, value));
+        }
+        let (index, evicted) = if self.len < N {
+            self.len += 1;
+            (self.len - 1, None)
+        } else {
+            let lru = self.tail;
+            self.unlink(lru);
+            (lru, self.slots[lru].take().map(|slot| (slot.key, slot.value)))
+        };
+        self.slots[index] = Some(Slot { key, value, prev: NIL, next: NIL });
+        self.push_front(index);
+        evicted
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Entries from most to least recently used
+    pub fn iter(&self) -> Iter<'_, K, V, N> {
+        Iter { lru: self, next: self.head }
+    }
+
+    fn find(&self, key: &K) -> Option<usize> {
+        self.slots[..self.len].iter().position(|slot| slot.as_ref().is_some_and(|slot| slot.key == *key))
+    }
+
+    fn slot(&self, index: usize) -> &Slot<K, V> {
+        self.slots[index].as_ref().expect("linked slots are occupied")
+    }
+
+    fn slot_mut(&mut self, index: usize) -> &mut Slot<K, V> {
+        self.slots[index].as_mut().expect("linked slots are occupied")
+    }
+
+    fn touch(&mut self, index: usize) {
+        if self.head != index {
+            self.unlink(index);
+            self.push_front(index);
+        }
+    }
+
+    fn unlink(&mut self, index: usize) {
+        let Slot { prev, next, .. } = *self.slot(index);
+        match prev {
+            NIL => self.head = next,
+            prev => self.slot_mut(prev).next = next,
+        }
+        match next {
+            NIL => self.tail = prev,
+            next => self.slot_mut(next).prev = prev,
+        }
+    }
+
+    fn push_front(&mut self, index: usize) {
+        let old_head = self.head;
+        let slot = self.slot_mut(index);
+        slot.prev = NIL;
+        slot.next = old_head;
+        match old_head {
+            NIL => self.tail = index,
+            old_head => self.slot_mut(old_head).prev = index,
+        }
+        self.head = index;
+    }
+}
+
+impl<K: Eq, V, const N: usize> Default for ArenaLru<K, V, N> {
+    fn default() -> Self {
+        ArenaLru::new()
+    }
+}
+
+impl<K: Eq + fmt::Debug, V: fmt::Debug, const N: usize> fmt::Debug for ArenaLru<K, V, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+/// Entries of an `ArenaLru`, most recently used first
+pub struct Iter<'a, K, V, const N: usize> {
+    lru: &'a ArenaLru<K, V, N>,
+    next: usize,
+}
+
+impl<'a, K: Eq, V, const N: usize> Iterator for Iter<'a, K, V, N> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next == NIL {
+            return None;
+        }
+        let slot = self.lru.slot(self.next);
+        self.next = slot.next;
+        Some((&slot.key, &slot.value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys<const N: usize>(lru: &ArenaLru<u32, char, N>) -> Vec<u32> {
+        lru.iter().map(|(key, _)| *key).collect()
+    }
+
+    #[test]
+    fn evicts_the_least_recently_used() {
+        let mut lru: ArenaLru<u32, char, 3> = ArenaLru::new();
+        assert_eq!(lru.put(1, 'a'), None);
+        assert_eq!(lru.put(2, 'b'), None);
+        assert_eq!(lru.put(3, 'c'), None);
+        assert_eq!(lru.get(&1), Some(&'a'));
+        assert_eq!(keys(&lru), [1, 3, 2]);
+
+        assert_eq!(lru.put(4, 'd'), Some((2, 'b')));
+        assert_eq!(lru.put(3, 'C'), None);
+        assert_eq!(keys(&lru), [3, 4, 1]);
+        assert_eq!(lru.get(&2), None);
+        assert_eq!(lru.get(&3), Some(&'C'));
+        assert_eq!(lru.len(), 3);
+    }
+
+    #[test]
+    fn zero_capacity_keeps_nothing() {
+        let mut lru: ArenaLru<u32, char, 0> = ArenaLru::new();
+        assert_eq!(lru.put(1, 'a'), Some((1, 'a')));
+        assert!(lru.is_empty());
+        assert_eq!(lru.get(&1), None);
+    }
+}
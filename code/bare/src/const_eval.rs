@@ -0,0 +1,63 @@
+//! Computed by the Compiler
+//!
+//! A `const fn` called in a `const` runs inside the compiler, and its result
+//! is baked into the binary as plain data. The compiler's interpreter has no
+//! heap to hand out and no OS to call, so const evaluation is `core`-only
+//! by nature: loops, arithmetic, arrays and `panic!` (which becomes a
+//! compile error) are all it gets.
+
+/// The CRC-32 (IEEE 802.3) lookup table, one entry per byte value
+pub const CRC32_TABLE: [u32; 256] = crc32_table();
+
+const fn crc32_table() -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut byte = 0;
+    // `for` loops call Iterator::next, which isn't const yet
+    while byte < 256 {
+        let mut crc = byte as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+            bit += 1;
+        }
+        table[byte] = crc;
+        byte += 1;
+    }
+    table
+}
+
+/// CRC-32 of `bytes`; callable at compile time and at run time alike
+pub const fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    let mut i = 0;
+    while i < bytes.len() {
+        crc = (crc >> 8) ^ CRC32_TABLE[((crc ^ bytes[i] as u32) & 0xFF) as usize];
+        i += 1;
+    }
+    !crc
+}
+
+/// The standard check value: if the table were wrong, this wouldn't compile
+pub const CRC32_CHECK: u32 = crc32(b"123456789");
+const _: () = assert!(CRC32_CHECK == 0xCBF4_3926);
+
+/// The first `N` primes, by trial division
+pub const fn primes<const N: usize>() -> [u32; N] {
+    let mut primes = [0u32; N];
+    let mut found = 0;
+    let mut candidate = 2;
+    while found < N {
+        let mut divisor = 2;
+        while divisor * divisor <= candidate && candidate % divisor != 0 {
+            divisor += 1;
+        }
+        if divisor * divisor > candidate {
+            primes[found] = candidate;
+            found += 1;
+        }
+        candidate += 1;
+    }
+    primes
+}
+
+pub const FIRST_PRIMES: [u32; 16] = primes();
@@ -0,0 +1,19 @@
+//! Core-Only Building Blocks
+//!
+//! Data structures that need nothing but `core`: no heap, no threads, no
+//! operating system. Everything lives inline in the value, sized by a const
+//! generic, which is what code for a microcontroller or a kernel gets to
+//! work with. `no-std-demo` compares them with their `std` cousins.
+//!
+//! The crate is `#![no_std]`, so the compiler checks that nothing here
+//! reaches for `std` (its tests use std's harness); the demos see it as
+//! `systems_demos::bare`.
+
+#![cfg_attr(not(test), no_std)]
+
+pub mod arena_lru;
+pub mod const_eval;
+pub mod ring;
+
+pub use arena_lru::ArenaLru;
+pub use ring::RingBuffer;
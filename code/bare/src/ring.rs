@@ -0,0 +1,121 @@
+//! Ring Buffer Without a Heap
+//!
+//! `VecDeque` asks the allocator for its buffer and grows it when full.
+//! This one is `N` slots inside the value itself: pushing to a full ring
+//! either refuses (`push`) or overwrites the oldest entry
+//! (`push_overwriting`), the way a UART driver or a trace log keeps the last
+//! few bytes or events. Slots start uninitialized, so only `len` of them,
+//! starting at `head`, hold values.
+
+use core::mem::MaybeUninit;
+
+pub struct RingBuffer<T, const N: usize> {
+    slots: [MaybeUninit<T>; N],
+    /// Slot of the oldest entry
+    head: usize,
+    len: usize,
+}
+
+impl<T, const N: usize> RingBuffer<T, N> {
+    pub const fn new() -> Self {
+        RingBuffer { slots: [const { MaybeUninit::uninit() }; N], head: 0, len: 0 }
+    }
+
+    /// Append `value`, or hand it back if the ring is full
+    pub fn push(&mut self, value: T) -> Result<(), T> {
+        if self.len == N {
+            return Err(value);
+        }
+        self.slots[(self.head + self.len) % N].write(value);
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Append `value`, dropping the oldest entry to make room; returns it
+    pub fn push_overwriting(&mut self, value: T) -> Option<T> {
+        let oldest = if self.len == N { self.pop() } else { None };
+        match self.push(value) {
+            Ok(()) => oldest,
+            // Only a zero-capacity ring has no room after a pop
+            Err(value) => Some(value),
+        }
+    }
+
+    /// Remove and return the oldest entry
+    pub fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        // SAFETY: the `len` slots from `head` are initialized, and moving
+        // `head` past this one means it's never read again
+        let value = unsafe { self.slots[self.head].assume_init_read() };
+        self.head = (self.head + 1) % N;
+        self.len -= 1;
+        Some(value)
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Entries from oldest to newest
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        // SAFETY: the `len` slots from `head` are initialized
+        (0..self.len).map(move |i| unsafe { self.slots[(self.head + i) % N].assume_init_ref() })
+    }
+}
+
+impl<T, const N: usize> Default for RingBuffer<T, N> {
+    fn default() -> Self {
+        RingBuffer::new()
+    }
+}
+
+impl<T, const N: usize> Drop for RingBuffer<T, N> {
+    fn drop(&mut self) {
+        while self.pop().is_some() {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wraps_around_and_overwrites_the_oldest() {
+        let mut ring: RingBuffer<u32, 3> = RingBuffer::new();
+        assert_eq!(ring.push(1), Ok(()));
+        assert_eq!(ring.push(2), Ok(()));
+        assert_eq!(ring.pop(), Some(1));
+        assert_eq!(ring.push(3), Ok(()));
+        assert_eq!(ring.push(4), Ok(()));
+        assert_eq!(ring.push(5), Err(5));
+        assert_eq!(ring.push_overwriting(5), Some(2));
+        assert_eq!(ring.iter().copied().collect::<Vec<_>>(), [3, 4, 5]);
+        assert!(ring.is_full());
+    }
+
+    #[test]
+    fn drops_what_it_still_holds() {
+        let counter = std::rc::Rc::new(());
+        let mut ring: RingBuffer<std::rc::Rc<()>, 4> = RingBuffer::new();
+        for _ in 0..6 {
+            ring.push_overwriting(counter.clone());
+        }
+        assert_eq!(std::rc::Rc::strong_count(&counter), 5);
+        drop(ring);
+        assert_eq!(std::rc::Rc::strong_count(&counter), 1);
+    }
+}
@@ -0,0 +1,157 @@
+//! Criterion benchmarks for the LRU caches in `cache`, against the hash
+//! map they are built on.
+//!
+//! Run with: cargo bench --bench cache
+//!
+//! An LRU cache is a `HashMap` from key to entry plus the bookkeeping that
+//! orders the entries, so `std::collections::HashMap` on the same keys is
+//! the floor a cache's operations add to. `LruCache` links heap nodes with
+//! raw pointers; `SafeLruCache` keeps its entries in one `Vec` linked by
+//! index. `get` looks up keys that are all present, so every call on a
+//! cache is a hit that moves its entry to the front. `put` inserts a key
+//! never seen before into a full cache, so every call evicts; the map
+//! removes its oldest key to match. `mixed` serves skewed traffic through
+//! the caches, fetching and caching on a miss.
+
+use std::collections::HashMap;
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use systems_demos::cache::{LruCache, SafeLruCache};
+use systems_demos::rng::Rng;
+
+const CAPACITY: usize = 10_000;
+const BATCH: usize = 1_024;
+
+/// `BATCH` keys scattered over `0..CAPACITY` by a multiplicative hash, so
+/// consecutive lookups don't walk the table in order
+fn hits() -> Vec<u64> {
+    (0..BATCH as u64).map(|i| i.wrapping_mul(2_654_435_761) % CAPACITY as u64).collect()
+}
+
+/// 80% of the keys from a hot set that fits in the cache, the rest from
+/// ten times its capacity
+fn skewed(seed: u64) -> Vec<u64> {
+    let mut rng = Rng::new(seed);
+    (0..BATCH)
+        .map(|_| match rng.below(5) {
+            0 => rng.below(10 * CAPACITY) as u64,
+            _ => rng.below(CAPACITY * 8 / 10) as u64,
+        })
+        .collect()
+}
+
+fn get(c: &mut Criterion) {
+    let mut group = c.benchmark_group("get");
+    group.throughput(Throughput::Elements(BATCH as u64));
+    let hits = hits();
+
+    let map: HashMap<u64, u64> = (0..CAPACITY as u64).map(|key| (key, key)).collect();
+    group.bench_function("HashMap", |b| {
+        b.iter(|| {
+            for key in &hits {
+                black_box(map.get(key));
+            }
+        })
+    });
+
+    let mut cache = LruCache::new(CAPACITY);
+    for key in 0..CAPACITY as u64 {
+        cache.put(key, key);
+    }
+    group.bench_function("LruCache", |b| {
+        b.iter(|| {
+            for key in &hits {
+                black_box(cache.get(key));
+            }
+        })
+    });
+
+    let mut safe = SafeLruCache::new(CAPACITY);
+    for key in 0..CAPACITY as u64 {
+        safe.put(key, key);
+    }
+    group.bench_function("SafeLruCache", |b| {
+        b.iter(|| {
+            for key in &hits {
+                black_box(safe.get(key));
+            }
+        })
+    });
+
+    group.finish();
+}
+
+fn put(c: &mut Criterion) {
+    let mut group = c.benchmark_group("put");
+    group.throughput(Throughput::Elements(BATCH as u64));
+
+    let mut map: HashMap<u64, u64> = (0..CAPACITY as u64).map(|key| (key, key)).collect();
+    let mut next = CAPACITY as u64;
+    group.bench_function("HashMap", |b| {
+        b.iter(|| {
+            for _ in 0..BATCH {
+                map.insert(next, next);
+                black_box(map.remove(&(next - CAPACITY as u64)));
+                next += 1;
+            }
+        })
+    });
+
+    let mut cache = LruCache::new(CAPACITY);
+    let mut next = 0u64;
+    group.bench_function("LruCache", |b| {
+        b.iter(|| {
+            for _ in 0..BATCH {
+                black_box(cache.put(next, next));
+                next += 1;
+            }
+        })
+    });
+
+    let mut safe = SafeLruCache::new(CAPACITY);
+    let mut next = 0u64;
+    group.bench_function("SafeLruCache", |b| {
+        b.iter(|| {
+            for _ in 0..BATCH {
+                black_box(safe.put(next, next));
+                next += 1;
+            }
+        })
+    });
+
+    group.finish();
+}
+
+fn mixed(c: &mut Criterion) {
+    let mut group = c.benchmark_group("mixed");
+    group.throughput(Throughput::Elements(BATCH as u64));
+    let requests = skewed(2);
+
+    let mut cache = LruCache::new(CAPACITY);
+    group.bench_function("LruCache", |b| {
+        b.iter(|| {
+            for &key in &requests {
+                if cache.get(&key).is_none() {
+                    cache.put(key, key);
+                }
+            }
+        })
+    });
+
+    let mut safe = SafeLruCache::new(CAPACITY);
+    group.bench_function("SafeLruCache", |b| {
+        b.iter(|| {
+            for &key in &requests {
+                if safe.get(&key).is_none() {
+                    safe.put(key, key);
+                }
+            }
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, get, put, mixed);
+criterion_main!(benches);
@@ -0,0 +1,98 @@
+//! Criterion benchmarks for the locks and the RCU cell in `sync`.
+//!
+//! Run with: cargo bench --bench locks
+//!
+//! `uncontended` is one thread locking and unlocking: the fast path every
+//! lock takes when nobody else wants it. `contended` has two threads
+//! incrementing one shared counter. `read_mostly` compares a read through
+//! `RcuCell` with a read through `std::sync::RwLock`. `std::sync::Mutex` is
+//! the baseline throughout.
+
+use std::hint::black_box;
+use std::sync::{Mutex, RwLock};
+use std::thread;
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+#[cfg(target_os = "linux")]
+use systems_demos::sync::futex::FutexMutex;
+use systems_demos::sync::spinlock::{RawLock, SpinLock, TasLock, TicketLock, TtasLock};
+use systems_demos::sync::RcuCell;
+
+const INCREMENTS: u64 = 10_000;
+
+fn uncontended(c: &mut Criterion) {
+    let mut group = c.benchmark_group("uncontended");
+
+    fn spin<L: RawLock>(group: &mut criterion::BenchmarkGroup<'_, criterion::measurement::WallTime>, name: &str) {
+        let lock = SpinLock::<u64, L>::new(0);
+        group.bench_function(name, |b| b.iter(|| *lock.lock() += 1));
+    }
+    spin::<TasLock>(&mut group, "TasLock");
+    spin::<TtasLock>(&mut group, "TtasLock");
+    spin::<TicketLock>(&mut group, "TicketLock");
+
+    #[cfg(target_os = "linux")]
+    {
+        let lock = FutexMutex::new(0u64);
+        group.bench_function("FutexMutex", |b| b.iter(|| *lock.lock() += 1));
+    }
+
+    let lock = Mutex::new(0u64);
+    group.bench_function("std::sync::Mutex", |b| b.iter(|| *lock.lock().unwrap() += 1));
+
+    group.finish();
+}
+
+/// Two threads, INCREMENTS increments each, on one counter
+fn hammer(increment: impl Fn() + Sync) {
+    thread::scope(|scope| {
+        for _ in 0..2 {
+            scope.spawn(|| {
+                for _ in 0..INCREMENTS {
+                    increment();
+                }
+            });
+        }
+    });
+}
+
+fn contended(c: &mut Criterion) {
+    let mut group = c.benchmark_group("contended");
+    group.throughput(Throughput::Elements(2 * INCREMENTS));
+    group.sample_size(20);
+
+    fn spin<L: RawLock + Sync>(group: &mut criterion::BenchmarkGroup<'_, criterion::measurement::WallTime>, name: &str) {
+        let lock = SpinLock::<u64, L>::new(0);
+        group.bench_function(name, |b| b.iter(|| hammer(|| *lock.lock() += 1)));
+    }
+    spin::<TasLock>(&mut group, "TasLock");
+    spin::<TtasLock>(&mut group, "TtasLock");
+    spin::<TicketLock>(&mut group, "TicketLock");
+
+    #[cfg(target_os = "linux")]
+    {
+        let lock = FutexMutex::new(0u64);
+        group.bench_function("FutexMutex", |b| b.iter(|| hammer(|| *lock.lock() += 1)));
+    }
+
+    let lock = Mutex::new(0u64);
+    group.bench_function("std::sync::Mutex", |b| b.iter(|| hammer(|| *lock.lock().unwrap() += 1)));
+
+    group.finish();
+}
+
+fn read_mostly(c: &mut Criterion) {
+    let mut group = c.benchmark_group("read_mostly");
+    let table: Vec<u64> = (0..1_024).collect();
+
+    let rcu = RcuCell::new(table.clone());
+    group.bench_function("RcuCell::read", |b| b.iter(|| black_box(rcu.read()[black_box(512)])));
+
+    let locked = RwLock::new(table);
+    group.bench_function("RwLock::read", |b| b.iter(|| black_box(locked.read().unwrap()[black_box(512)])));
+
+    group.finish();
+}
+
+criterion_group!(benches, uncontended, contended, read_mostly);
+criterion_main!(benches);
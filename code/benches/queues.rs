@@ -0,0 +1,169 @@
+//! Criterion benchmarks for the queues and stacks in `sync`.
+//!
+//! Run with: cargo bench --bench queues
+//!
+//! `round_trip` pushes and pops on one thread: the cost of the operations
+//! themselves. `transfer` moves items from a producer thread to a consumer
+//! thread through a small buffer, with `std::sync::mpsc::sync_channel` as
+//! the baseline.
+
+use std::hint::black_box;
+use std::sync::mpsc;
+use std::thread;
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use systems_demos::sync::{spsc_ring, BoundedBuffer, MpmcQueue, TreiberStack};
+
+const BATCH: usize = 1_024;
+const ITEMS: u64 = 100_000;
+const CAPACITY: usize = 1_024;
+
+fn round_trip(c: &mut Criterion) {
+    let mut group = c.benchmark_group("round_trip");
+    group.throughput(Throughput::Elements(BATCH as u64));
+
+    let (mut producer, mut consumer) = spsc_ring::channel(BATCH);
+    group.bench_function("spsc_ring", |b| {
+        b.iter(|| {
+            for i in 0..BATCH {
+                producer.push(i).unwrap();
+            }
+            while let Some(value) = consumer.pop() {
+                black_box(value);
+            }
+        })
+    });
+
+    let queue = MpmcQueue::new(BATCH);
+    group.bench_function("MpmcQueue", |b| {
+        b.iter(|| {
+            for i in 0..BATCH {
+                queue.push(i).unwrap();
+            }
+            while let Some(value) = queue.pop() {
+                black_box(value);
+            }
+        })
+    });
+
+    let buffer = BoundedBuffer::new(BATCH);
+    group.bench_function("BoundedBuffer", |b| {
+        b.iter(|| {
+            for i in 0..BATCH {
+                buffer.put(i);
+            }
+            for _ in 0..BATCH {
+                black_box(buffer.take());
+            }
+        })
+    });
+
+    let stack = TreiberStack::new();
+    group.bench_function("TreiberStack", |b| {
+        b.iter(|| {
+            for i in 0..BATCH {
+                stack.push(i);
+            }
+            while let Some(value) = stack.pop() {
+                black_box(value);
+            }
+        })
+    });
+
+    group.finish();
+}
+
+fn transfer(c: &mut Criterion) {
+    let mut group = c.benchmark_group("transfer");
+    group.throughput(Throughput::Elements(ITEMS));
+    group.sample_size(20);
+
+    group.bench_function("spsc_ring", |b| {
+        b.iter(|| {
+            let (mut producer, mut consumer) = spsc_ring::channel(CAPACITY);
+            thread::scope(|scope| {
+                scope.spawn(move || {
+                    for i in 0..ITEMS {
+                        let mut value = i;
+                        while let Err(rejected) = producer.push(value) {
+                            value = rejected;
+                            thread::yield_now();
+                        }
+                    }
+                });
+                for _ in 0..ITEMS {
+                    loop {
+                        if let Some(value) = consumer.pop() {
+                            black_box(value);
+                            break;
+                        }
+                        thread::yield_now();
+                    }
+                }
+            });
+        })
+    });
+
+    group.bench_function("MpmcQueue", |b| {
+        b.iter(|| {
+            let queue = MpmcQueue::new(CAPACITY);
+            thread::scope(|scope| {
+                scope.spawn(|| {
+                    for i in 0..ITEMS {
+                        let mut value = i;
+                        while let Err(rejected) = queue.push(value) {
+                            value = rejected;
+                            thread::yield_now();
+                        }
+                    }
+                });
+                for _ in 0..ITEMS {
+                    loop {
+                        if let Some(value) = queue.pop() {
+                            black_box(value);
+                            break;
+                        }
+                        thread::yield_now();
+                    }
+                }
+            });
+        })
+    });
+
+    group.bench_function("BoundedBuffer", |b| {
+        b.iter(|| {
+            let buffer = BoundedBuffer::new(CAPACITY);
+            thread::scope(|scope| {
+                scope.spawn(|| {
+                    for i in 0..ITEMS {
+                        buffer.put(i);
+                    }
+                });
+                for _ in 0..ITEMS {
+                    black_box(buffer.take());
+                }
+            });
+        })
+    });
+
+    group.bench_function("mpsc::sync_channel", |b| {
+        b.iter(|| {
+            let (sender, receiver) = mpsc::sync_channel(CAPACITY);
+            thread::scope(|scope| {
+                scope.spawn(move || {
+                    for i in 0..ITEMS {
+                        sender.send(i).unwrap();
+                    }
+                });
+                for _ in 0..ITEMS {
+                    black_box(receiver.recv().unwrap());
+                }
+            });
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, round_trip, transfer);
+criterion_main!(benches);
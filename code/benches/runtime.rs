@@ -0,0 +1,90 @@
+//! Criterion benchmarks for the executor in `runtime`.
+//!
+//! Run with: cargo bench --bench runtime
+//!
+//! `spawn` measures the whole life of short tasks: boxing the future,
+//! queueing it, one poll, and dropping it. `yield_now` measures a task
+//! that is woken and re-polled many times, the executor's hot loop.
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::Arc;
+    use std::task::{Context, Poll};
+
+    use criterion::{Criterion, Throughput};
+    use systems_demos::runtime::{self, Runtime};
+
+    const TASKS: u64 = 10_000;
+    const YIELDS: u64 = 10_000;
+
+    /// Returns `Pending` once, waking itself first: the task goes straight
+    /// back on the ready queue
+    struct YieldNow(bool);
+
+    impl Future for YieldNow {
+        type Output = ();
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+            if self.0 {
+                return Poll::Ready(());
+            }
+            self.0 = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+
+    pub fn spawn(c: &mut Criterion) {
+        let mut group = c.benchmark_group("runtime");
+        group.throughput(Throughput::Elements(TASKS));
+
+        let mut rt = Runtime::new().expect("failed to create runtime");
+        group.bench_function("spawn", |b| {
+            b.iter(|| {
+                let done = Arc::new(AtomicU64::new(0));
+                rt.block_on(async {
+                    for _ in 0..TASKS {
+                        let done = Arc::clone(&done);
+                        runtime::spawn(async move {
+                            done.fetch_add(1, Ordering::Relaxed);
+                        });
+                    }
+                    // Let every spawned task run before returning
+                    while done.load(Ordering::Relaxed) < TASKS {
+                        YieldNow(false).await;
+                    }
+                });
+            })
+        });
+        group.finish();
+    }
+
+    pub fn yield_now(c: &mut Criterion) {
+        let mut group = c.benchmark_group("runtime");
+        group.throughput(Throughput::Elements(YIELDS));
+
+        let mut rt = Runtime::new().expect("failed to create runtime");
+        group.bench_function("yield_now", |b| {
+            b.iter(|| {
+                rt.block_on(async {
+                    for _ in 0..YIELDS {
+                        YieldNow(false).await;
+                    }
+                })
+            })
+        });
+        group.finish();
+    }
+}
+
+#[cfg(target_os = "linux")]
+criterion::criterion_group!(benches, linux::spawn, linux::yield_now);
+#[cfg(target_os = "linux")]
+criterion::criterion_main!(benches);
+
+// The runtime is built on epoll
+#[cfg(not(target_os = "linux"))]
+fn main() {}
@@ -0,0 +1,47 @@
+// Compiles the C half of the FFI demo (csrc/) into a static library. Only
+// the search path is emitted here: src/demos/ffi_demo.rs links the library
+// itself with #[link], next to the declarations that use it. The browser
+// build (wasm32) has no C toolchain or FFI demo, so it skips this.
+//
+// On Linux it links csrc/plugin.c into two shared libraries for the dynamic
+// linking demo, one bound lazily and one at load time, and passes their
+// paths in SYSTEMS_DEMOS_PLUGIN_LAZY and SYSTEMS_DEMOS_PLUGIN_NOW.
+//
+// It also records the compiler version and opt-level for `envinfo`, which
+// only a build script can see.
+
+use std::path::Path;
+use std::process::Command;
+
+fn main() {
+    println!("cargo:rerun-if-changed=csrc/ffi_demo.c");
+    println!("cargo:rerun-if-changed=csrc/plugin.c");
+    let rustc = std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    let version = Command::new(rustc).arg("-V").output().ok().and_then(|output| String::from_utf8(output.stdout).ok());
+    println!("cargo:rustc-env=SYSTEMS_DEMOS_RUSTC={}", version.unwrap_or_default().trim());
+    println!("cargo:rustc-env=SYSTEMS_DEMOS_OPT_LEVEL={}", std::env::var("OPT_LEVEL").unwrap_or_default());
+    if std::env::var("CARGO_CFG_TARGET_FAMILY").is_ok_and(|family| family.split(',').any(|f| f == "wasm")) {
+        return;
+    }
+    cc::Build::new().file("csrc/ffi_demo.c").warnings(true).cargo_metadata(false).compile("ffi_demo");
+    println!("cargo:rustc-link-search=native={}", std::env::var("OUT_DIR").unwrap());
+    if std::env::var("CARGO_CFG_TARGET_OS").is_ok_and(|os| os == "linux") {
+        shared_library("lazy", "-Wl,-z,lazy");
+        shared_library("now", "-Wl,-z,now");
+    }
+}
+
+/// csrc/plugin.c as `libplugin_<binding>.so` in OUT_DIR, linked with the
+/// given binding flag
+fn shared_library(binding: &str, flag: &str) {
+    let path = Path::new(&std::env::var("OUT_DIR").unwrap()).join(format!("libplugin_{}.so", binding));
+    let compiler = cc::Build::new().pic(true).warnings(true).cargo_metadata(false).get_compiler();
+    let status = compiler.to_command()
+        .args(["-shared", "-O2", flag, "-o"])
+        .arg(&path)
+        .arg("csrc/plugin.c")
+        .status()
+        .expect("failed to run the C compiler");
+    assert!(status.success(), "building {} failed", path.display());
+    println!("cargo:rustc-env=SYSTEMS_DEMOS_PLUGIN_{}={}", binding.to_uppercase(), path.display());
+}
@@ -0,0 +1,75 @@
+/*
+ * C side of the FFI demo (src/bin/ffi_demo.rs).
+ * Compiled into a static library by build.rs.
+ */
+
+#include <stddef.h>
+#include <stdint.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+/* Must match `#[repr(C)] struct Record` in ffi_demo.rs field for field */
+typedef struct {
+    uint8_t tag;
+    double value;
+    uint16_t id;
+} Record;
+
+/* How this C compiler laid out Record, so Rust can compare */
+typedef struct {
+    size_t size;
+    size_t align;
+    size_t offset_tag;
+    size_t offset_value;
+    size_t offset_id;
+} RecordLayout;
+
+void ffi_record_layout(RecordLayout *out) {
+    out->size = sizeof(Record);
+    out->align = _Alignof(Record);
+    out->offset_tag = offsetof(Record, tag);
+    out->offset_value = offsetof(Record, value);
+    out->offset_id = offsetof(Record, id);
+}
+
+/* Rust -> C: read a Rust-owned buffer (borrowed for the call only) */
+int64_t ffi_sum(const int32_t *data, size_t len) {
+    int64_t total = 0;
+    for (size_t i = 0; i < len; i++) {
+        total += data[i];
+    }
+    return total;
+}
+
+/* C modifies a struct Rust passed by pointer */
+void ffi_scale_record(Record *record, double factor) {
+    record->value *= factor;
+    record->id += 1;
+}
+
+/* C -> Rust: call `step` for every element; `ctx` is opaque to C */
+typedef int64_t (*fold_fn)(int64_t acc, int32_t item, void *ctx);
+
+int64_t ffi_fold(const int32_t *data, size_t len, int64_t init, fold_fn step, void *ctx) {
+    int64_t acc = init;
+    for (size_t i = 0; i < len; i++) {
+        acc = step(acc, data[i], ctx);
+    }
+    return acc;
+}
+
+/* C allocates with malloc, so C must free: never pass this to Rust's allocator */
+char *ffi_greeting(const char *name) {
+    const char *prefix = "Hello from C, ";
+    size_t length = strlen(prefix) + strlen(name) + 2;
+    char *message = malloc(length);
+    if (message != NULL) {
+        snprintf(message, length, "%s%s!", prefix, name);
+    }
+    return message;
+}
+
+void ffi_free_greeting(char *message) {
+    free(message);
+}
@@ -0,0 +1,35 @@
+/*
+ * The shared library the dynamic linking demo loads with dlopen
+ * (src/demos/dynamic_linking_demo.rs). build.rs links it twice from this
+ * one source: with -z lazy, so its calls into libc are bound at the first
+ * call, and with -z now, so they are bound while dlopen runs.
+ */
+
+#include <stddef.h>
+#include <stdlib.h>
+
+/* Data, not code: dlsym finds both */
+int plugin_calls = 0;
+
+int plugin_version(void) {
+    plugin_calls++;
+    return 1;
+}
+
+/* Calls into libc go through the PLT: strtol's address isn't known until
+ * the dynamic linker looks it up */
+long plugin_parse(const char *text) {
+    plugin_calls++;
+    return strtol(text, NULL, 10);
+}
+
+static int compare(const void *a, const void *b) {
+    int x = *(const int *)a, y = *(const int *)b;
+    return (x > y) - (x < y);
+}
+
+/* A second PLT slot, bound separately at its own first call */
+void plugin_sort(int *values, size_t len) {
+    plugin_calls++;
+    qsort(values, len, sizeof(int), compare);
+}
@@ -0,0 +1,121 @@
+//! `#[derive(Demo)]`
+//!
+//! Implements `systems_demos::demo::Demo` for a type from a `#[demo(...)]`
+//! attribute, so a demo only writes its `demonstrate` method:
+//!
+//! ```ignore
+//! #[derive(Demo)]
+//! #[demo(name = "hello", description = "Prints a greeting")]
+//! struct Hello;
+//!
+//! impl Hello {
+//!     fn demonstrate(&self, _config: &Config) -> DemoReport {
+//!         println!("hello");
+//!         DemoReport::default()
+//!     }
+//! }
+//! ```
+//!
+//! `name` defaults to the type name in kebab-case. `quiz = PATH` names a
+//! `&'static [Question]` for `--quiz` to ask, and `verify = PATH` a
+//! `fn() -> Verification` that re-checks the demo's claims. The derive also emits a
+//! hidden `DEMO_EXPANSION` constant holding the generated impl as text, so
+//! the macro demo can print real expansion output.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, DeriveInput, LitStr, Path};
+
+#[proc_macro_derive(Demo, attributes(demo))]
+pub fn derive_demo(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input).unwrap_or_else(|err| err.to_compile_error()).into()
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let mut name = None;
+    let mut description = None;
+    let mut quiz: Option<Path> = None;
+    let mut verify: Option<Path> = None;
+    for attr in input.attrs.iter().filter(|attr| attr.path().is_ident("demo")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                name = Some(meta.value()?.parse::<LitStr>()?.value());
+                Ok(())
+            } else if meta.path.is_ident("description") {
+                description = Some(meta.value()?.parse::<LitStr>()?.value());
+                Ok(())
+            } else if meta.path.is_ident("quiz") {
+                quiz = Some(meta.value()?.parse::<Path>()?);
+                Ok(())
+            } else if meta.path.is_ident("verify") {
+                verify = Some(meta.value()?.parse::<Path>()?);
+                Ok(())
+            } else {
+                Err(meta.error("expected `name = \"...\"`, `description = \"...\"`, `quiz = PATH` or `verify = PATH`"))
+            }
+        })?;
+    }
+
+    let ident = &input.ident;
+    let name = name.unwrap_or_else(|| kebab_case(&ident.to_string()));
+    let description = description.unwrap_or_default();
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let quiz = quiz.map(|quiz| {
+        quote! {
+            fn quiz(&self) -> &'static [::systems_demos::quiz::Question] {
+                #quiz
+            }
+        }
+    });
+    let verify = verify.map(|verify| {
+        quote! {
+            fn verify(&self) -> ::systems_demos::verify::Verification {
+                #verify()
+            }
+        }
+    });
+
+    let trait_impl = quote! {
+        impl #impl_generics ::systems_demos::demo::Demo for #ident #ty_generics #where_clause {
+            fn name(&self) -> &'static str {
+                #name
+            }
+            fn description(&self) -> &'static str {
+                #description
+            }
+            fn run(&self, config: &::systems_demos::demo::Config) -> ::systems_demos::demo::DemoReport {
+                Self::demonstrate(self, config)
+            }
+            #quiz
+            #verify
+        }
+    };
+    let expansion = trait_impl.to_string();
+
+    Ok(quote! {
+        #trait_impl
+
+        impl #impl_generics #ident #ty_generics #where_clause {
+            #[doc(hidden)]
+            pub const DEMO_EXPANSION: &'static str = #expansion;
+        }
+    })
+}
+
+/// `CacheLineDemo` -> `cache-line-demo`
+fn kebab_case(ident: &str) -> String {
+    let mut out = String::new();
+    for (i, c) in ident.chars().enumerate() {
+        if c.is_uppercase() {
+            if i > 0 {
+                out.push('-');
+            }
+            out.extend(c.to_lowercase());
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
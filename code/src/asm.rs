@@ -0,0 +1,249 @@
+//! Assembly Inspection
+//!
+//! Disassembles functions of the running executable so demos can show the
+//! machine code the compiler actually produced instead of describing it.
+//!
+//! The flow:
+//! - `nm -S` finds the symbol's address and size in the binary. Functions to
+//!   inspect must be `#[unsafe(no_mangle)]` (a findable name) and
+//!   `#[inline(never)]` (a body of their own).
+//! - `objdump -d` disassembles exactly that address range.
+//! - `normalized()` rewrites everything that depends on *where* a function
+//!   sits (branch targets, RIP-relative displacements) so two functions can
+//!   be compared instruction by instruction.
+//!
+//! Needs binutils (`nm`, `objdump`) on the PATH, and is only meaningful for
+//! optimized builds.
+
+use std::fmt;
+use std::io;
+use std::path::Path;
+use std::process::Command;
+
+#[derive(Debug)]
+pub enum AsmError {
+    /// A tool couldn't be run (usually: binutils not installed)
+    Tool { tool: &'static str, source: io::Error },
+    /// A tool ran but reported failure
+    ToolFailed { tool: &'static str, stderr: String },
+    /// The symbol isn't in the binary (inlined, mangled, or stripped)
+    SymbolNotFound(String),
+}
+
+impl fmt::Display for AsmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AsmError::Tool { tool, source } => write!(f, "couldn't run `{}`: {}", tool, source),
+            AsmError::ToolFailed { tool, stderr } => write!(f, "`{}` failed: {}", tool, stderr.trim()),
+            AsmError::SymbolNotFound(symbol) => {
+                write!(f, "symbol `{}` not found (is it #[unsafe(no_mangle)] and #[inline(never)]?)", symbol)
+            }
+        }
+    }
+}
+
+impl std::error::Error for AsmError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AsmError::Tool { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// One disassembled instruction
+#[derive(Debug, Clone)]
+pub struct Instruction {
+    /// Offset from the start of the function
+    pub offset: u64,
+    /// Intel-syntax text as printed by objdump, e.g. `add rax,rcx`
+    pub text: String,
+}
+
+impl Instruction {
+    pub fn mnemonic(&self) -> &str {
+        self.text.split_whitespace().next().unwrap_or("")
+    }
+}
+
+/// The machine code of one function
+#[derive(Debug, Clone)]
+pub struct Disassembly {
+    pub symbol: String,
+    pub address: u64,
+    pub size: u64,
+    pub instructions: Vec<Instruction>,
+}
+
+fn run(tool: &'static str, args: &[&str]) -> Result<String, AsmError> {
+    let output = Command::new(tool).args(args).output().map_err(|source| AsmError::Tool { tool, source })?;
+    if !output.status.success() {
+        return Err(AsmError::ToolFailed { tool, stderr: String::from_utf8_lossy(&output.stderr).into_owned() });
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Address and size of `symbol` according to the symbol table
+fn find_symbol(binary: &Path, symbol: &str) -> Result<(u64, u64), AsmError> {
+    let listing = run("nm", &["-S", "--defined-only", &binary.to_string_lossy()])?;
+    listing
+        .lines()
+        .find_map(|line| {
+            // "<address> <size> <type> <name>"; symbols without a size have 3 fields
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            match fields[..] {
+                [address, size, _, name] if name == symbol => {
+                    Some((u64::from_str_radix(address, 16).ok()?, u64::from_str_radix(size, 16).ok()?))
+                }
+                _ => None,
+            }
+        })
+        .ok_or_else(|| AsmError::SymbolNotFound(symbol.to_string()))
+}
+
+/// A sized, defined entry in a binary's symbol table
+#[derive(Debug, Clone)]
+pub struct Symbol {
+    /// Demangled, e.g. `my_demo::describe::<u32>`
+    pub name: String,
+    pub address: u64,
+    pub size: u64,
+}
+
+/// Every sized symbol defined in `binary`, with names demangled (`nm -C`).
+/// Unlike `Disassembly::of`, this also sees generic functions, whose mangled
+/// names can't be predicted.
+pub fn symbols(binary: &Path) -> Result<Vec<Symbol>, AsmError> {
+    let listing = run("nm", &["-S", "-C", "--defined-only", &binary.to_string_lossy()])?;
+    Ok(listing
+        .lines()
+        .filter_map(|line| {
+            // "<address> <size> <type> <name>"; demangled names may contain spaces
+            let mut fields = line.splitn(4, ' ');
+            let address = u64::from_str_radix(fields.next()?, 16).ok()?;
+            let size = u64::from_str_radix(fields.next()?, 16).ok()?;
+            let _kind = fields.next()?;
+            Some(Symbol { name: fields.next()?.to_string(), address, size })
+        })
+        .collect())
+}
+
+impl Disassembly {
+    /// Disassemble `symbol` in the currently running executable
+    pub fn of(symbol: &str) -> Result<Self, AsmError> {
+        let exe = std::env::current_exe().map_err(|source| AsmError::Tool { tool: "current_exe", source })?;
+        Self::from_binary(&exe, symbol)
+    }
+
+    pub fn from_binary(binary: &Path, symbol: &str) -> Result<Self, AsmError> {
+        let (address, size) = find_symbol(binary, symbol)?;
+        let listing = run(
+            "objdump",
+            &[
+                "-d",
+                "-C",
+                "--no-show-raw-insn",
+                "-M",
+                "intel",
+                &format!("--start-address=0x{:x}", address),
+                &format!("--stop-address=0x{:x}", address + size),
+                &binary.to_string_lossy(),
+            ],
+        )?;
+
+        // Instruction lines look like "   1a2b0:\tmov    rax,rdi"
+        let instructions = listing
+            .lines()
+            .filter_map(|line| {
+                let (addr, text) = line.trim_start().split_once(":\t")?;
+                let addr = u64::from_str_radix(addr, 16).ok()?;
+                let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+                Some(Instruction { offset: addr - address, text })
+            })
+            .collect();
+
+        Ok(Disassembly { symbol: symbol.to_string(), address, size, instructions })
+    }
+
+    /// Instructions with position-dependent details replaced, so identical
+    /// code at different addresses compares equal:
+    /// - `jne 1a2c4 <sum+0x14>` becomes `jne +0x14`
+    /// - `call 1b000 <foo>` becomes `call <foo>`
+    /// - `[rip+0x2d4c]` becomes `[rip+?]`, and `# ...` comments are dropped
+    pub fn normalized(&self) -> Vec<String> {
+        self.instructions
+            .iter()
+            .map(|instruction| {
+                let mut text = instruction.text.clone();
+                if let Some(comment) = text.find(" #") {
+                    text.truncate(comment);
+                }
+                if let (Some(open), Some(close)) = (text.find('<'), text.rfind('>')) {
+                    let label = &text[open + 1..close];
+                    let label = match label.strip_prefix(self.symbol.as_str()) {
+                        Some(offset) if offset.starts_with('+') => offset.to_string(),
+                        _ => format!("<{}>", label),
+                    };
+                    // Drop the absolute address printed before the label
+                    let mnemonic = instruction.mnemonic();
+                    text = format!("{} {}", mnemonic, label);
+                }
+                while let Some(start) = text.find("rip+0x") {
+                    let end = text[start + 6..]
+                        .find(|c: char| !c.is_ascii_hexdigit())
+                        .map_or(text.len(), |i| start + 6 + i);
+                    text.replace_range(start..end, "rip+?");
+                }
+                text
+            })
+            .collect()
+    }
+}
+
+/// How two functions' machine code relates
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Equivalence {
+    /// Both symbols point at the same code: the compiler merged them
+    SameAddress,
+    /// Different copies, but instruction-for-instruction identical
+    Identical,
+    /// The same instructions, scheduled in a different order
+    Reordered,
+    Different,
+}
+
+pub fn compare(left: &Disassembly, right: &Disassembly) -> Equivalence {
+    if left.address == right.address {
+        return Equivalence::SameAddress;
+    }
+    let (mut left, mut right) = (left.normalized(), right.normalized());
+    if left == right {
+        return Equivalence::Identical;
+    }
+    left.sort();
+    right.sort();
+    if left == right { Equivalence::Reordered } else { Equivalence::Different }
+}
+
+/// Render two disassemblies as columns, marking lines that differ with `≠`
+pub fn side_by_side(left: &Disassembly, right: &Disassembly, width: usize) -> String {
+    let (left_lines, right_lines) = (left.normalized(), right.normalized());
+    let fit = |s: &str| {
+        if s.chars().count() > width {
+            format!("{}…", s.chars().take(width - 1).collect::<String>())
+        } else {
+            s.to_string()
+        }
+    };
+
+    let mut out = format!("  {:<w$}   {}\n", left.symbol, right.symbol, w = width);
+    let underline = |s: &str| "─".repeat(s.chars().count().min(width));
+    out.push_str(&format!("  {:<w$}   {}\n", underline(&left.symbol), underline(&right.symbol), w = width));
+    for i in 0..left_lines.len().max(right_lines.len()) {
+        let l = left_lines.get(i).map_or("", String::as_str);
+        let r = right_lines.get(i).map_or("", String::as_str);
+        let marker = if l == r { ' ' } else { '≠' };
+        out.push_str(&format!("  {:<w$} {} {}\n", fit(l), marker, fit(r), w = width));
+    }
+    out
+}
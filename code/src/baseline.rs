@@ -0,0 +1,259 @@
+//! Baselines
+//!
+//! A baseline is a saved `Results` file. Comparing a new run against one
+//! matches metrics by demo, name and parameters and reports how each moved,
+//! so a change of machine, compiler or code shows up as a list of deltas
+//! instead of two walls of numbers to eyeball.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::demo::Metric;
+use crate::output::Style;
+use crate::report::display_value;
+use crate::results::Results;
+
+/// Changes smaller than this (in percent) are treated as noise by default
+pub const DEFAULT_THRESHOLD: f64 = 10.0;
+
+/// Whether a bigger number is an improvement: true for rates (`ops/s`) and
+/// speedups (`x`); times, sizes and counts are costs
+pub fn higher_is_better(unit: &str) -> bool {
+    unit.ends_with("/s") || unit == "x"
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Improved,
+    Regressed,
+    /// Within the noise threshold
+    Unchanged,
+}
+
+/// One metric present in both runs
+#[derive(Debug, Clone)]
+pub struct Delta {
+    pub demo: String,
+    pub before: Metric,
+    pub after: Metric,
+    pub verdict: Verdict,
+}
+
+impl Delta {
+    /// Relative change in percent, positive when the value grew
+    pub fn change(&self) -> f64 {
+        if self.before.value == 0.0 {
+            return if self.after.value == 0.0 { 0.0 } else { f64::INFINITY.copysign(self.after.value) };
+        }
+        (self.after.value - self.before.value) / self.before.value.abs() * 100.0
+    }
+
+    /// `demo: metric (k=v, ...)`
+    pub fn label(&self) -> String {
+        label(&self.demo, &self.after.name, &self.after.params)
+    }
+}
+
+/// How a run compares with a baseline
+#[derive(Debug, Clone)]
+pub struct Comparison {
+    pub deltas: Vec<Delta>,
+    /// Metrics only the baseline has, as `Delta::label`-style strings
+    pub missing: Vec<String>,
+    /// Metrics only the new run has
+    pub added: Vec<String>,
+    /// Ways the two runs' environments differ, e.g. `profile: release -> debug`
+    pub environment: Vec<String>,
+}
+
+type Key = (String, String, BTreeMap<String, String>);
+
+fn metrics(results: &Results) -> BTreeMap<Key, &Metric> {
+    results
+        .results
+        .iter()
+        .filter(|result| result.succeeded)
+        .flat_map(|result| {
+            result.metrics.iter().map(|metric| ((result.demo.clone(), metric.name.clone(), metric.params.clone()), metric))
+        })
+        .collect()
+}
+
+fn label(demo: &str, name: &str, params: &BTreeMap<String, String>) -> String {
+    let mut label = format!("{}: {}", demo, name);
+    if !params.is_empty() {
+        let params: Vec<String> = params.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+        label.push_str(&format!(" ({})", params.join(", ")));
+    }
+    label
+}
+
+/// Compare `after` with `before`; a change beyond `threshold` percent in
+/// the worse direction is a regression
+pub fn compare(before: &Results, after: &Results, threshold: f64) -> Comparison {
+    let old = metrics(before);
+    let new = metrics(after);
+
+    // In the new run's order, which is the demos' learning order
+    let mut deltas = Vec::new();
+    let mut added = Vec::new();
+    for result in after.results.iter().filter(|result| result.succeeded) {
+        for metric in &result.metrics {
+            let Some(&baseline) = old.get(&(result.demo.clone(), metric.name.clone(), metric.params.clone())) else {
+                added.push(label(&result.demo, &metric.name, &metric.params));
+                continue;
+            };
+            let mut delta = Delta {
+                demo: result.demo.clone(),
+                before: baseline.clone(),
+                after: metric.clone(),
+                verdict: Verdict::Unchanged,
+            };
+            let change = delta.change();
+            let better = if higher_is_better(&metric.unit) { change } else { -change };
+            delta.verdict = if change.abs() < threshold || metric.unit != baseline.unit {
+                Verdict::Unchanged
+            } else if better > 0.0 {
+                Verdict::Improved
+            } else {
+                Verdict::Regressed
+            };
+            deltas.push(delta);
+        }
+    }
+    let missing = old
+        .keys()
+        .filter(|key| !new.contains_key(*key))
+        .map(|(demo, name, params)| label(demo, name, params))
+        .collect();
+
+    let (was, is) = (&before.environment, &after.environment);
+    let mut environment = Vec::new();
+    let known = |value: &str| Some(value.to_string()).filter(|value| !value.is_empty());
+    for (field, old, new) in [
+        ("os", known(&was.os), known(&is.os)),
+        ("arch", known(&was.arch), known(&is.arch)),
+        ("cpus", Some(was.cpus.to_string()), Some(is.cpus.to_string())),
+        ("cpu", was.cpu_model.clone(), is.cpu_model.clone()),
+        ("profile", known(&was.profile), known(&is.profile)),
+        ("opt-level", known(&was.opt_level), known(&is.opt_level)),
+        ("rustc", known(&was.rustc), known(&is.rustc)),
+        ("governor", was.governor.clone(), is.governor.clone()),
+        ("version", known(&was.version), known(&is.version)),
+    ] {
+        // A field one run didn't record (an older baseline) can't be compared
+        if let (Some(old), Some(new)) = (old, new)
+            && old != new
+        {
+            environment.push(format!("{}: {} -> {}", field, old, new));
+        }
+    }
+    if before.parameters != after.parameters {
+        environment.push(format!("parameters: {:?} -> {:?}", before.parameters, after.parameters));
+    }
+
+    Comparison { deltas, missing, added, environment }
+}
+
+impl Comparison {
+    pub fn count(&self, verdict: Verdict) -> usize {
+        self.deltas.iter().filter(|delta| delta.verdict == verdict).count()
+    }
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for difference in &self.environment {
+            writeln!(f, "⚠️  {} (differences may come from this, not the code)", difference)?;
+        }
+        let width = self.deltas.iter().map(|delta| delta.label().chars().count()).max().unwrap_or(0);
+        for delta in &self.deltas {
+            let (mark, style) = match delta.verdict {
+                Verdict::Improved => ("✅", Style::Faster),
+                Verdict::Regressed => ("❌", Style::Slower),
+                Verdict::Unchanged => ("  ", Style::Dim),
+            };
+            writeln!(f, "{} {:<w$}  {:>14} -> {:<14} {:>+7.1}%", mark, delta.label(), display_value(&delta.before),
+                     display_value(&delta.after), style.paint(delta.change()), w = width)?;
+        }
+        for label in &self.missing {
+            writeln!(f, "   {}: not measured this time", label)?;
+        }
+        for label in &self.added {
+            writeln!(f, "   {}: new, no baseline", label)?;
+        }
+        write!(f, "{} improved, {} regressed, {} within noise", self.count(Verdict::Improved),
+               self.count(Verdict::Regressed), self.count(Verdict::Unchanged))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::demo::{Config, DemoReport};
+    use crate::results::DemoResult;
+
+    fn run(demo: &str, record: impl FnOnce(&mut DemoReport)) -> Results {
+        let mut report = DemoReport::default();
+        record(&mut report);
+        let mut results = Results::new(Config::default());
+        results.results.push(DemoResult { demo: demo.to_string(), succeeded: true, metrics: report.metrics });
+        results
+    }
+
+    #[test]
+    fn direction_depends_on_the_unit() {
+        let before = run("d", |r| {
+            r.record("time", 100.0, "ns");
+            r.record("rate", 100.0, "ops/s").param("threads", 2);
+            r.record("rate", 100.0, "ops/s").param("threads", 4);
+        });
+        let after = run("d", |r| {
+            r.record("time", 150.0, "ns");
+            r.record("rate", 150.0, "ops/s").param("threads", 2);
+            r.record("rate", 105.0, "ops/s").param("threads", 4);
+        });
+        let comparison = compare(&before, &after, DEFAULT_THRESHOLD);
+        let verdicts: Vec<_> = comparison.deltas.iter().map(|delta| (delta.label(), delta.verdict)).collect();
+        assert_eq!(verdicts, [
+            ("d: time".to_string(), Verdict::Regressed),
+            ("d: rate (threads=2)".to_string(), Verdict::Improved),
+            ("d: rate (threads=4)".to_string(), Verdict::Unchanged),
+        ]);
+        assert_eq!(comparison.deltas[0].change(), 50.0);
+    }
+
+    #[test]
+    fn reports_metrics_on_one_side_only() {
+        let before = run("d", |r| {
+            r.record("old", 1.0, "ns");
+        });
+        let after = run("d", |r| {
+            r.record("new", 1.0, "ns");
+        });
+        let comparison = compare(&before, &after, DEFAULT_THRESHOLD);
+        assert!(comparison.deltas.is_empty());
+        assert_eq!(comparison.missing, ["d: old"]);
+        assert_eq!(comparison.added, ["d: new"]);
+    }
+
+    #[test]
+    fn round_trips_through_json() {
+        let results = run("d", |r| {
+            r.record("time", 1.5, "ns").param("size", 64);
+        });
+        assert_eq!(Results::from_json(&results.to_json()).unwrap(), results);
+    }
+
+    #[test]
+    fn fields_an_older_baseline_lacks_are_not_differences() {
+        let after = run("d", |_| {});
+        let mut json: serde_json::Value = serde_json::from_str(&after.to_json()).unwrap();
+        let environment = json["environment"].as_object_mut().unwrap();
+        environment.retain(|field, _| ["os", "arch", "cpus", "profile", "version"].contains(&field.as_str()));
+        environment.insert("version".to_string(), "0.0.1".into());
+        let before = Results::from_json(&json.to_string()).unwrap();
+        let comparison = compare(&before, &after, DEFAULT_THRESHOLD);
+        assert_eq!(comparison.environment, [format!("version: 0.0.1 -> {}", after.environment.version)]);
+    }
+}
@@ -0,0 +1,479 @@
+//! Benchmark Helpers
+//!
+//! One-shot `Instant::now()` timings are noisy. `measure` runs a closure a
+//! few times (after a warmup run) and reports the spread, so demos can print
+//! a median instead of a single lucky or unlucky sample. `Bench` configures
+//! the warmup and run counts; samples far outside the rest (an interrupt, a
+//! page-fault storm) are set aside as outliers before any statistic is taken.
+//!
+//! Each run's CPU time is taken alongside its wall-clock time, from the
+//! measuring thread's own clock (`thread_cpu_time`). A run that computes
+//! the whole time shows about as much CPU as wall time; one that sleeps,
+//! blocks on I/O or waits for a lock shows much less. Work handed to other
+//! threads counts as waiting too: `process_cpu_time` sees every thread.
+
+use std::fmt;
+use std::hint::black_box;
+use std::time::Duration;
+#[cfg(not(target_family = "wasm"))]
+use std::time::Instant;
+
+#[cfg(target_family = "wasm")]
+use self::browser::Instant;
+
+use crate::output::{self, Table, Verbosity};
+
+/// Two-sided 95% critical values of Student's t distribution for 1..=30
+/// degrees of freedom; beyond that the normal distribution's 1.96 is close
+const T_95: [f64; 30] = [
+    12.706, 4.303, 3.182, 2.776, 2.571, 2.447, 2.365, 2.306, 2.262, 2.228, 2.201, 2.179, 2.160, 2.145, 2.131,
+    2.120, 2.110, 2.101, 2.093, 2.086, 2.080, 2.074, 2.069, 2.064, 2.060, 2.056, 2.052, 2.048, 2.045, 2.042,
+];
+
+/// Outlier rejection needs enough samples for quartiles to mean anything
+const MIN_SAMPLES_FOR_OUTLIERS: usize = 5;
+
+/// Timing samples from repeated runs of the same closure
+#[derive(Debug, Clone)]
+pub struct Measurement {
+    /// Wall-clock times, sorted
+    samples: Vec<Duration>,
+    /// The CPU time of each run in `samples`, in the same order; empty where
+    /// there's no CPU clock (the browser)
+    cpu: Vec<Duration>,
+    outliers: Vec<Duration>,
+}
+
+impl Measurement {
+    /// Build a measurement from already-collected samples, keeping all of them
+    pub fn from_samples(mut samples: Vec<Duration>) -> Self {
+        assert!(!samples.is_empty(), "a measurement needs at least one sample");
+        samples.sort();
+        Measurement { samples, cpu: Vec::new(), outliers: Vec::new() }
+    }
+
+    /// Build a measurement from (wall-clock, CPU) time pairs, one per run
+    pub fn from_timed(mut runs: Vec<(Duration, Duration)>) -> Self {
+        assert!(!runs.is_empty(), "a measurement needs at least one sample");
+        runs.sort();
+        let (samples, cpu) = runs.into_iter().unzip();
+        Measurement { samples, cpu, outliers: Vec::new() }
+    }
+
+    /// Set aside samples outside Tukey's fences (more than 1.5 interquartile
+    /// ranges beyond the middle half). With fewer than 5 samples nothing is
+    /// rejected.
+    pub fn reject_outliers(mut self) -> Self {
+        if self.samples.len() < MIN_SAMPLES_FOR_OUTLIERS {
+            return self;
+        }
+        let q1 = self.percentile(25.0).as_secs_f64();
+        let q3 = self.percentile(75.0).as_secs_f64();
+        let fence = 1.5 * (q3 - q1);
+        let kept: Vec<bool> = self.samples.iter().map(|s| (q1 - fence..=q3 + fence).contains(&s.as_secs_f64())).collect();
+        self.outliers.extend(self.samples.iter().zip(&kept).filter(|&(_, &kept)| !kept).map(|(&s, _)| s));
+        let keep = |values: &mut Vec<Duration>| {
+            let mut flags = kept.iter();
+            values.retain(|_| *flags.next().unwrap_or(&true));
+        };
+        keep(&mut self.samples);
+        keep(&mut self.cpu);
+        self
+    }
+
+    /// Samples set aside by `reject_outliers`
+    pub fn outliers(&self) -> &[Duration] {
+        &self.outliers
+    }
+
+    pub fn median(&self) -> Duration {
+        self.samples[self.samples.len() / 2]
+    }
+
+    pub fn mean(&self) -> Duration {
+        self.samples.iter().sum::<Duration>() / self.samples.len() as u32
+    }
+
+    /// Sample standard deviation: how much individual runs wander
+    pub fn std_dev(&self) -> Duration {
+        if self.samples.len() < 2 {
+            return Duration::ZERO;
+        }
+        let mean = self.mean().as_secs_f64();
+        let variance = self.samples.iter().map(|s| (s.as_secs_f64() - mean).powi(2)).sum::<f64>()
+            / (self.samples.len() - 1) as f64;
+        Duration::from_secs_f64(variance.sqrt())
+    }
+
+    pub fn min(&self) -> Duration {
+        self.samples[0]
+    }
+
+    pub fn max(&self) -> Duration {
+        self.samples[self.samples.len() - 1]
+    }
+
+    /// The kept samples, sorted
+    pub fn samples(&self) -> &[Duration] {
+        &self.samples
+    }
+
+    /// The sample below which `p` percent of the samples fall (nearest rank)
+    pub fn percentile(&self, p: f64) -> Duration {
+        let rank = (p / 100.0 * self.samples.len() as f64).ceil() as usize;
+        self.samples[rank.clamp(1, self.samples.len()) - 1]
+    }
+
+    pub fn p95(&self) -> Duration {
+        self.percentile(95.0)
+    }
+
+    /// 95% confidence interval for the mean: where the true average time
+    /// probably lies, given how much the samples wander. With one sample
+    /// the interval is just that sample.
+    pub fn confidence_interval(&self) -> (Duration, Duration) {
+        let n = self.samples.len();
+        let mean = self.mean().as_secs_f64();
+        let t = T_95.get(n.saturating_sub(2)).copied().unwrap_or(1.96);
+        let half_width = if n < 2 { 0.0 } else { t * self.std_dev().as_secs_f64() / (n as f64).sqrt() };
+        (Duration::from_secs_f64((mean - half_width).max(0.0)), Duration::from_secs_f64(mean + half_width))
+    }
+
+    /// The median run's CPU time: the measuring thread computing rather than
+    /// sleeping, blocked or waiting for other threads
+    pub fn cpu_median(&self) -> Option<Duration> {
+        let mut cpu = self.cpu.clone();
+        cpu.sort();
+        cpu.get(cpu.len() / 2).copied()
+    }
+
+    /// CPU time as a fraction of wall-clock time: near 1 for a run that
+    /// computes throughout, near 0 for one that mostly waits
+    pub fn cpu_share(&self) -> Option<f64> {
+        let cpu = self.cpu_median()?;
+        Some(cpu.as_secs_f64() / self.median().as_secs_f64().max(f64::MIN_POSITIVE))
+    }
+
+    /// `1.20ms (97%)`, or nothing without a CPU clock
+    fn cpu_summary(&self) -> Option<String> {
+        Some(format!("{:.2?} ({:.0}%)", self.cpu_median()?, self.cpu_share()? * 100.0))
+    }
+
+    /// Operations per second, given how many operations one run performs
+    pub fn throughput(&self, ops_per_run: u64) -> f64 {
+        ops_per_run as f64 / self.median().as_secs_f64()
+    }
+}
+
+impl fmt::Display for Measurement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "median {:.2?}, p95 {:.2?}", self.median(), self.p95())?;
+        if let Some(cpu) = self.cpu_summary() {
+            write!(f, ", CPU {}", cpu)?;
+        }
+        if output::settings().verbosity == Verbosity::Verbose {
+            let (low, high) = self.confidence_interval();
+            write!(f, ", mean {:.2?} ± {:.2?}, range {:.2?}..{:.2?}", self.mean(), (high - low) / 2, self.min(), self.max())?;
+        }
+        write!(f, " (n={}", self.samples.len())?;
+        if !self.outliers.is_empty() {
+            let plural = if self.outliers.len() == 1 { "" } else { "s" };
+            write!(f, ", {} outlier{} dropped", self.outliers.len(), plural)?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// How to benchmark a closure: untimed warmup runs to fill caches, train
+/// branch predictors and fault in memory, then timed runs
+#[derive(Debug, Clone, Copy)]
+pub struct Bench {
+    warmup: usize,
+    runs: usize,
+    reject_outliers: bool,
+}
+
+impl Bench {
+    /// One warmup run, ten timed runs, outliers rejected
+    pub const fn new() -> Self {
+        Bench { warmup: 1, runs: 10, reject_outliers: true }
+    }
+
+    pub const fn warmup(mut self, runs: usize) -> Self {
+        self.warmup = runs;
+        self
+    }
+
+    pub const fn runs(mut self, runs: usize) -> Self {
+        self.runs = runs;
+        self
+    }
+
+    /// Keep every sample, e.g. when the slow runs are the point
+    pub const fn keep_outliers(mut self) -> Self {
+        self.reject_outliers = false;
+        self
+    }
+
+    pub fn measure<T>(&self, mut f: impl FnMut() -> T) -> Measurement {
+        for _ in 0..self.warmup {
+            black_box(f());
+        }
+        let runs: Vec<(Duration, Option<Duration>)> = (0..self.runs.max(1))
+            .map(|_| {
+                let cpu_start = cpu_clock();
+                let start = Instant::now();
+                black_box(f());
+                let wall = start.elapsed();
+                (wall, cpu_start.zip(cpu_clock()).map(|(start, end)| end.saturating_sub(start)))
+            })
+            .collect();
+        let measurement = match runs.iter().map(|&(wall, cpu)| Some((wall, cpu?))).collect() {
+            Some(timed) => Measurement::from_timed(timed),
+            None => Measurement::from_samples(runs.into_iter().map(|(wall, _)| wall).collect()),
+        };
+        if self.reject_outliers { measurement.reject_outliers() } else { measurement }
+    }
+}
+
+impl Default for Bench {
+    fn default() -> Self {
+        Bench::new()
+    }
+}
+
+/// Run `f` once to warm up, then `runs` more times, timing each run
+pub fn measure<T>(runs: usize, f: impl FnMut() -> T) -> Measurement {
+    Bench::new().runs(runs).measure(f)
+}
+
+/// Print labelled measurements as a table, each relative to the first
+pub fn print_comparison(results: &[(&str, Measurement)]) {
+    let Some((_, baseline)) = results.first() else { return };
+    let medians: Vec<Duration> = results.iter().map(|(_, measurement)| measurement.median()).collect();
+    let medians = output::rank(&medians, false, |median| format!("{:.2?}", median));
+    let with_cpu = results.iter().all(|(_, measurement)| measurement.cpu_median().is_some());
+    let mut header = vec!["version", "median", "p95", "mean ± 95% CI", "relative"];
+    if with_cpu {
+        header.insert(2, "CPU");
+    }
+    let mut table = Table::new(header);
+    for ((label, measurement), median) in results.iter().zip(medians) {
+        let (low, high) = measurement.confidence_interval();
+        let mut row = vec![
+            label.to_string(),
+            median,
+            format!("{:.2?}", measurement.p95()),
+            format!("{:.2?} ± {:.2?}", measurement.mean(), (high - low) / 2),
+            format!("{:.2}x", measurement.median().as_secs_f64() / baseline.median().as_secs_f64()),
+        ];
+        if let Some(cpu) = measurement.cpu_summary().filter(|_| with_cpu) {
+            row.insert(2, cpu);
+        }
+        table.row(row);
+    }
+    crate::out!("{}", table);
+}
+
+/// Measure several expressions with `measure` and print them with
+/// `print_comparison`; evaluates to the `Vec<(&str, Measurement)>`.
+///
+/// ```ignore
+/// let results = bench_compare!(runs: 5;
+///     "index loop" => sum_loop(&data),
+///     "iterator" => sum_iter(&data),
+/// );
+/// ```
+#[macro_export]
+macro_rules! bench_compare {
+    (runs: $runs:expr; $($label:literal => $body:expr),+ $(,)?) => {{
+        let results: ::std::vec::Vec<(&'static str, $crate::bench::Measurement)> =
+            ::std::vec![$(($label, $crate::bench::measure($runs, || $body))),+];
+        $crate::bench::print_comparison(&results);
+        results
+    }};
+}
+
+/// Format an operations-per-second figure with a K/M/G suffix
+pub fn format_rate(ops_per_sec: f64) -> String {
+    if ops_per_sec >= 1e9 {
+        format!("{:.2} G/s", ops_per_sec / 1e9)
+    } else if ops_per_sec >= 1e6 {
+        format!("{:.2} M/s", ops_per_sec / 1e6)
+    } else if ops_per_sec >= 1e3 {
+        format!("{:.2} K/s", ops_per_sec / 1e3)
+    } else {
+        format!("{:.0} /s", ops_per_sec)
+    }
+}
+
+/// CPU time consumed by the whole process so far (all threads, user + system).
+/// Unlike wall-clock time this doesn't advance while threads are blocked.
+#[cfg(unix)]
+pub fn process_cpu_time() -> Duration {
+    let mut ts = libc::timespec { tv_sec: 0, tv_nsec: 0 };
+    // Safety: `ts` is a valid, writable timespec
+    unsafe { libc::clock_gettime(libc::CLOCK_PROCESS_CPUTIME_ID, &mut ts) };
+    Duration::new(ts.tv_sec as u64, ts.tv_nsec as u32)
+}
+
+/// CPU time consumed by the whole process so far (all threads, user + kernel).
+/// Unlike wall-clock time this doesn't advance while threads are blocked.
+#[cfg(windows)]
+pub fn process_cpu_time() -> Duration {
+    use windows_sys::Win32::Foundation::FILETIME;
+    use windows_sys::Win32::System::Threading::{GetCurrentProcess, GetProcessTimes};
+
+    let mut times = [FILETIME { dwLowDateTime: 0, dwHighDateTime: 0 }; 4];
+    let [creation, exit, kernel, user] = &mut times;
+    // Safety: the pseudo-handle is always valid, and each FILETIME is writable
+    unsafe { GetProcessTimes(GetCurrentProcess(), creation, exit, kernel, user) };
+    // FILETIMEs count 100 ns ticks
+    let ticks = |time: &FILETIME| (time.dwHighDateTime as u64) << 32 | time.dwLowDateTime as u64;
+    Duration::from_nanos((ticks(&times[2]) + ticks(&times[3])) * 100)
+}
+
+/// CPU time consumed by the calling thread so far (user + system). Unlike
+/// wall-clock time this doesn't advance while the thread sleeps or blocks.
+#[cfg(unix)]
+pub fn thread_cpu_time() -> Duration {
+    let mut ts = libc::timespec { tv_sec: 0, tv_nsec: 0 };
+    // Safety: `ts` is a valid, writable timespec
+    unsafe { libc::clock_gettime(libc::CLOCK_THREAD_CPUTIME_ID, &mut ts) };
+    Duration::new(ts.tv_sec as u64, ts.tv_nsec as u32)
+}
+
+/// CPU time consumed by the calling thread so far (user + kernel). Unlike
+/// wall-clock time this doesn't advance while the thread sleeps or blocks.
+#[cfg(windows)]
+pub fn thread_cpu_time() -> Duration {
+    use windows_sys::Win32::Foundation::FILETIME;
+    use windows_sys::Win32::System::Threading::{GetCurrentThread, GetThreadTimes};
+
+    let mut times = [FILETIME { dwLowDateTime: 0, dwHighDateTime: 0 }; 4];
+    let [creation, exit, kernel, user] = &mut times;
+    // Safety: the pseudo-handle is always valid, and each FILETIME is writable
+    unsafe { GetThreadTimes(GetCurrentThread(), creation, exit, kernel, user) };
+    // FILETIMEs count 100 ns ticks
+    let ticks = |time: &FILETIME| (time.dwHighDateTime as u64) << 32 | time.dwLowDateTime as u64;
+    Duration::from_nanos((ticks(&times[2]) + ticks(&times[3])) * 100)
+}
+
+/// The measuring thread's CPU time, where there is a clock for it
+#[cfg(any(unix, windows))]
+fn cpu_clock() -> Option<Duration> {
+    Some(thread_cpu_time())
+}
+
+#[cfg(not(any(unix, windows)))]
+fn cpu_clock() -> Option<Duration> {
+    None
+}
+
+/// The browser build (wasm32) has no clock of its own: `Instant::now` panics
+/// there. This one reads the page's `performance.now()`, which the web
+/// harness passes in as `env.now`. Browsers coarsen it (to 5 µs at best, 100
+/// µs or more without cross-origin isolation), so only the slower timings
+/// mean anything.
+#[cfg(target_family = "wasm")]
+mod browser {
+    use std::time::Duration;
+
+    #[link(wasm_import_module = "env")]
+    unsafe extern "C" {
+        /// Milliseconds since the page loaded, fractional
+        fn now() -> f64;
+    }
+
+    #[derive(Debug, Clone, Copy)]
+    pub struct Instant(f64);
+
+    impl Instant {
+        pub fn now() -> Instant {
+            // SAFETY: the harness provides `env.now`, which takes nothing and returns a number
+            Instant(unsafe { now() })
+        }
+
+        pub fn elapsed(&self) -> Duration {
+            Duration::from_secs_f64((Instant::now().0 - self.0).max(0.0) / 1e3)
+        }
+    }
+}
+
+/// Context switches of the whole process so far: (voluntary, involuntary).
+/// Voluntary ones are blocking calls (sleep, I/O, futex waits); involuntary
+/// ones are the scheduler preempting a thread.
+#[cfg(unix)]
+pub fn context_switches() -> (u64, u64) {
+    // Safety: all-zero is a valid rusage, and getrusage only writes to it
+    let mut usage: libc::rusage = unsafe { std::mem::zeroed() };
+    unsafe { libc::getrusage(libc::RUSAGE_SELF, &mut usage) };
+    (usage.ru_nvcsw as u64, usage.ru_nivcsw as u64)
+}
+
+/// A numeric field of /proc/self/status (e.g. "VmRSS:" in KiB, "Threads:"),
+/// or 0 if it can't be read
+#[cfg(target_os = "linux")]
+pub fn proc_status_field(name: &str) -> u64 {
+    let status = std::fs::read_to_string("/proc/self/status").unwrap_or_default();
+    status
+        .lines()
+        .find(|line| line.starts_with(name))
+        .and_then(|line| line.split_whitespace().nth(1))
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn millis(values: &[u64]) -> Measurement {
+        Measurement::from_samples(values.iter().map(|&ms| Duration::from_millis(ms)).collect())
+    }
+
+    #[test]
+    fn percentiles_use_nearest_rank() {
+        let m = millis(&[1, 2, 3, 4, 5, 6, 7, 8, 9, 10]);
+        assert_eq!(m.percentile(50.0), Duration::from_millis(5));
+        assert_eq!(m.p95(), Duration::from_millis(10));
+        assert_eq!(m.percentile(0.0), Duration::from_millis(1));
+    }
+
+    #[test]
+    fn outliers_outside_tukey_fences_are_dropped() {
+        let m = millis(&[10, 11, 10, 12, 11, 10, 95]).reject_outliers();
+        assert_eq!(m.outliers(), &[Duration::from_millis(95)]);
+        assert_eq!(m.max(), Duration::from_millis(12));
+
+        let few = millis(&[10, 11, 95]).reject_outliers();
+        assert!(few.outliers().is_empty());
+    }
+
+    #[test]
+    fn cpu_times_follow_their_runs_through_outlier_rejection() {
+        let ms = Duration::from_millis;
+        let runs = [(10, 9), (11, 10), (10, 9), (12, 11), (11, 10), (10, 9), (95, 1)];
+        let m = Measurement::from_timed(runs.iter().map(|&(wall, cpu)| (ms(wall), ms(cpu))).collect()).reject_outliers();
+        assert_eq!(m.outliers(), &[ms(95)]);
+        assert_eq!(m.cpu_median(), Some(ms(10)));
+        assert!((m.cpu_share().unwrap() - 10.0 / 11.0).abs() < 1e-9);
+        assert_eq!(millis(&[7]).cpu_median(), None);
+    }
+
+    #[cfg(any(unix, windows))]
+    #[test]
+    fn sleeping_takes_wall_time_but_no_cpu_time() {
+        let m = Bench::new().warmup(0).runs(3).measure(|| std::thread::sleep(Duration::from_millis(20)));
+        assert!(m.median() >= Duration::from_millis(20));
+        assert!(m.cpu_share().unwrap() < 0.5, "{}", m);
+    }
+
+    #[test]
+    fn confidence_interval_brackets_the_mean() {
+        let m = millis(&[9, 10, 11, 10, 9, 11, 10, 10]);
+        let (low, high) = m.confidence_interval();
+        assert!(low < m.mean() && m.mean() < high);
+        assert_eq!(millis(&[7]).confidence_interval(), (Duration::from_millis(7), Duration::from_millis(7)));
+    }
+}
@@ -0,0 +1,11 @@
+//! Aliasing & noalias Demo
+//!
+//! The demo lives in `systems_demos::demos::aliasing_demo`.
+//! Run with: cargo run --release --bin aliasing-demo
+
+use systems_demos::demo;
+use systems_demos::demos::aliasing_demo::AliasingDemo;
+
+fn main() {
+    demo::run_from_env(&AliasingDemo);
+}
@@ -0,0 +1,11 @@
+//! Amdahl's Law Demo
+//!
+//! The demo lives in `systems_demos::demos::amdahl_demo`.
+//! Run with: cargo run --release --bin amdahl-demo [-- --serial 0.1]
+
+use systems_demos::demo;
+use systems_demos::demos::amdahl_demo::AmdahlDemo;
+
+fn main() {
+    demo::run_from_env(&AmdahlDemo);
+}
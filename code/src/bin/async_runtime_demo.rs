@@ -0,0 +1,21 @@
+//! Mini Async Runtime Demo
+//!
+//! The demo lives in `systems_demos::demos::async_runtime_demo`.
+//! Run with: cargo run --release --bin async-runtime-demo
+
+#[cfg(target_os = "linux")]
+use systems_demos::demo;
+#[cfg(target_os = "linux")]
+use systems_demos::demos::async_runtime_demo::{self, AsyncRuntimeDemo};
+
+#[cfg(target_os = "linux")]
+fn main() {
+    if !async_runtime_demo::run_as_child() {
+        demo::run_from_env(&AsyncRuntimeDemo);
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn main() {
+    eprintln!("async-runtime-demo needs Linux");
+}
@@ -0,0 +1,11 @@
+//! Atomics Memory Ordering Demo
+//!
+//! The demo lives in `systems_demos::demos::atomics_ordering_demo`.
+//! Run with: cargo run --release --bin atomics-ordering-demo
+
+use systems_demos::demo;
+use systems_demos::demos::atomics_ordering_demo::AtomicsOrderingDemo;
+
+fn main() {
+    demo::run_from_env(&AtomicsOrderingDemo);
+}
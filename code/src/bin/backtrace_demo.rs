@@ -0,0 +1,19 @@
+//! Backtrace Demo
+//!
+//! The demo lives in `systems_demos::demos::backtrace_demo`.
+//! Run with: cargo run --bin backtrace-demo
+
+#[cfg(all(target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64")))]
+use systems_demos::demo;
+#[cfg(all(target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64")))]
+use systems_demos::demos::backtrace_demo::BacktraceDemo;
+
+#[cfg(all(target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64")))]
+fn main() {
+    demo::run_from_env(&BacktraceDemo);
+}
+
+#[cfg(not(all(target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64"))))]
+fn main() {
+    eprintln!("backtrace-demo needs Linux on x86-64 or AArch64 (it reads the frame pointer register)");
+}
@@ -0,0 +1,11 @@
+//! Binary Inspector Demo
+//!
+//! The demo lives in `systems_demos::demos::binary_inspector_demo`.
+//! Run with: cargo run --bin binary-inspector-demo
+
+use systems_demos::demo;
+use systems_demos::demos::binary_inspector_demo::BinaryInspectorDemo;
+
+fn main() {
+    demo::run_from_env(&BinaryInspectorDemo);
+}
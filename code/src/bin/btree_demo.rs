@@ -0,0 +1,11 @@
+//! B-Tree Demo
+//!
+//! The demo lives in `systems_demos::demos::btree_demo`.
+//! Run with: cargo run --release --bin btree-demo
+
+use systems_demos::demo;
+use systems_demos::demos::btree_demo::BTreeDemo;
+
+fn main() {
+    demo::run_from_env(&BTreeDemo);
+}
@@ -0,0 +1,11 @@
+//! Bytecode VM Dispatch Demo
+//!
+//! The demo lives in `systems_demos::demos::bytecode_vm_demo`.
+//! Run with: cargo run --release --bin bytecode-vm-demo
+
+use systems_demos::demo;
+use systems_demos::demos::bytecode_vm_demo::BytecodeVmDemo;
+
+fn main() {
+    demo::run_from_env(&BytecodeVmDemo);
+}
@@ -0,0 +1,11 @@
+//! Clock Demo
+//!
+//! The demo lives in `systems_demos::demos::clock_demo`.
+//! Run with: cargo run --bin clock-demo
+
+use systems_demos::demo;
+use systems_demos::demos::clock_demo::ClockDemo;
+
+fn main() {
+    demo::run_from_env(&ClockDemo);
+}
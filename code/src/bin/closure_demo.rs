@@ -0,0 +1,11 @@
+//! Closure Internals Demo
+//!
+//! The demo lives in `systems_demos::demos::closure_demo`.
+//! Run with: cargo run --release --bin closure-demo
+
+use systems_demos::demo;
+use systems_demos::demos::closure_demo::ClosureDemo;
+
+fn main() {
+    demo::run_from_env(&ClosureDemo);
+}
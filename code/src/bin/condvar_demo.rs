@@ -0,0 +1,11 @@
+//! Producer-Consumer with Condvar Demo
+//!
+//! The demo lives in `systems_demos::demos::condvar_demo`.
+//! Run with: cargo run --release --bin condvar-demo
+
+use systems_demos::demo;
+use systems_demos::demos::condvar_demo::CondvarDemo;
+
+fn main() {
+    demo::run_from_env(&CondvarDemo);
+}
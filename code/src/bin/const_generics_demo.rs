@@ -0,0 +1,11 @@
+//! Const Generics Matrix Demo
+//!
+//! The demo lives in `systems_demos::demos::const_generics_demo`.
+//! Run with: cargo run --release --bin const-generics-demo
+
+use systems_demos::demo;
+use systems_demos::demos::const_generics_demo::ConstGenericsDemo;
+
+fn main() {
+    demo::run_from_env(&ConstGenericsDemo);
+}
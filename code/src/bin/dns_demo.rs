@@ -0,0 +1,11 @@
+//! DNS Resolution Demo
+//!
+//! The demo lives in `systems_demos::demos::dns_demo`.
+//! Run with: cargo run --release --bin dns-demo
+
+use systems_demos::demo;
+use systems_demos::demos::dns_demo::DnsDemo;
+
+fn main() {
+    demo::run_from_env(&DnsDemo);
+}
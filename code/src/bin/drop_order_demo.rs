@@ -0,0 +1,11 @@
+//! Drop Order & RAII Demo
+//!
+//! The demo lives in `systems_demos::demos::drop_order_demo`.
+//! Run with: cargo run --bin drop-order-demo
+
+use systems_demos::demo;
+use systems_demos::demos::drop_order_demo::DropOrderDemo;
+
+fn main() {
+    demo::run_from_env(&DropOrderDemo);
+}
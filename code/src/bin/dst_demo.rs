@@ -0,0 +1,11 @@
+//! Dynamically Sized Types & Fat Pointers Demo
+//!
+//! The demo lives in `systems_demos::demos::dst_demo`.
+//! Run with: cargo run --bin dst-demo
+
+use systems_demos::demo;
+use systems_demos::demos::dst_demo::DstDemo;
+
+fn main() {
+    demo::run_from_env(&DstDemo);
+}
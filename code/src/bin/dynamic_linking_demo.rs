@@ -0,0 +1,19 @@
+//! Dynamic Linking Demo
+//!
+//! The demo lives in `systems_demos::demos::dynamic_linking_demo`.
+//! Run with: cargo run --release --bin dynamic-linking-demo
+
+#[cfg(target_os = "linux")]
+use systems_demos::demo;
+#[cfg(target_os = "linux")]
+use systems_demos::demos::dynamic_linking_demo::DynamicLinkingDemo;
+
+#[cfg(target_os = "linux")]
+fn main() {
+    demo::run_from_env(&DynamicLinkingDemo);
+}
+
+#[cfg(not(target_os = "linux"))]
+fn main() {
+    eprintln!("dynamic-linking-demo needs Linux (its plugin is built as an ELF shared library)");
+}
@@ -0,0 +1,11 @@
+//! Endianness Demo
+//!
+//! The demo lives in `systems_demos::demos::endianness_demo`.
+//! Run with: cargo run --bin endianness-demo
+
+use systems_demos::demo;
+use systems_demos::demos::endianness_demo::EndiannessDemo;
+
+fn main() {
+    demo::run_from_env(&EndiannessDemo);
+}
@@ -0,0 +1,11 @@
+//! Layered Error Handling Demo
+//!
+//! The demo lives in `systems_demos::demos::error_handling_demo`.
+//! Run with: cargo run --release --bin error-handling-demo
+
+use systems_demos::demo;
+use systems_demos::demos::error_handling_demo::ErrorHandlingDemo;
+
+fn main() {
+    demo::run_from_env(&ErrorHandlingDemo);
+}
@@ -0,0 +1,13 @@
+//! C FFI Round-Trip Demo
+//!
+//! The demo lives in `systems_demos::demos::ffi_demo`.
+//! Run with: cargo run --bin ffi-demo
+
+use systems_demos::demo;
+use systems_demos::demos::ffi_demo::{self, FfiDemo};
+
+fn main() {
+    if !ffi_demo::run_as_child() {
+        demo::run_from_env(&FfiDemo);
+    }
+}
@@ -0,0 +1,19 @@
+//! Filesystem Demo
+//!
+//! The demo lives in `systems_demos::demos::filesystem_demo`.
+//! Run with: cargo run --bin filesystem-demo
+
+#[cfg(unix)]
+use systems_demos::demo;
+#[cfg(unix)]
+use systems_demos::demos::filesystem_demo::FilesystemDemo;
+
+#[cfg(unix)]
+fn main() {
+    demo::run_from_env(&FilesystemDemo);
+}
+
+#[cfg(not(unix))]
+fn main() {
+    eprintln!("filesystem-demo needs a Unix filesystem API (inode numbers, link counts)");
+}
@@ -0,0 +1,11 @@
+//! Floating-Point Demo
+//!
+//! The demo lives in `systems_demos::demos::floating_point_demo`.
+//! Run with: cargo run --bin floating-point-demo
+
+use systems_demos::demo;
+use systems_demos::demos::floating_point_demo::FloatingPointDemo;
+
+fn main() {
+    demo::run_from_env(&FloatingPointDemo);
+}
@@ -0,0 +1,19 @@
+//! Raw Futex Demo
+//!
+//! The demo lives in `systems_demos::demos::futex_demo`.
+//! Run with: cargo run --release --bin futex-demo
+
+#[cfg(target_os = "linux")]
+use systems_demos::demo;
+#[cfg(target_os = "linux")]
+use systems_demos::demos::futex_demo::FutexDemo;
+
+#[cfg(target_os = "linux")]
+fn main() {
+    demo::run_from_env(&FutexDemo);
+}
+
+#[cfg(not(target_os = "linux"))]
+fn main() {
+    eprintln!("futex-demo needs Linux");
+}
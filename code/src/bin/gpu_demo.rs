@@ -0,0 +1,11 @@
+//! GPU Demo
+//!
+//! The demo lives in `systems_demos::demos::gpu_demo`.
+//! Run with: cargo run --release --features gpu --bin gpu-demo
+
+use systems_demos::demo;
+use systems_demos::demos::gpu_demo::GpuDemo;
+
+fn main() {
+    demo::run_from_env(&GpuDemo);
+}
@@ -0,0 +1,11 @@
+//! HashDoS Demo
+//!
+//! The demo lives in `systems_demos::demos::hashdos_demo`.
+//! Run with: cargo run --release --bin hashdos-demo
+
+use systems_demos::demo;
+use systems_demos::demos::hashdos_demo::HashDosDemo;
+
+fn main() {
+    demo::run_from_env(&HashDosDemo);
+}
@@ -0,0 +1,11 @@
+//! HTTP Server Demo
+//!
+//! The demo lives in `systems_demos::demos::http_server_demo`.
+//! Run with: cargo run --release --bin http-server-demo
+
+use systems_demos::demo;
+use systems_demos::demos::http_server_demo::HttpServerDemo;
+
+fn main() {
+    demo::run_from_env(&HttpServerDemo);
+}
@@ -0,0 +1,21 @@
+//! Async vs Threads for I/O-Bound Work
+//!
+//! The demo lives in `systems_demos::demos::io_bound_demo`.
+//! Run with: cargo run --release --bin io-bound-demo
+
+#[cfg(target_os = "linux")]
+use systems_demos::demo;
+#[cfg(target_os = "linux")]
+use systems_demos::demos::io_bound_demo::{self, IoBoundDemo};
+
+#[cfg(target_os = "linux")]
+fn main() {
+    if !io_bound_demo::run_as_child() {
+        demo::run_from_env(&IoBoundDemo);
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn main() {
+    eprintln!("io-bound-demo needs Linux");
+}
@@ -0,0 +1,11 @@
+//! Key-Value Store Demo
+//!
+//! The demo lives in `systems_demos::demos::kv_store_demo`.
+//! Run with: cargo run --release --bin kv-store-demo
+
+use systems_demos::demo;
+use systems_demos::demos::kv_store_demo::KvStoreDemo;
+
+fn main() {
+    demo::run_from_env(&KvStoreDemo);
+}
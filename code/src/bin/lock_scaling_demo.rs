@@ -0,0 +1,11 @@
+//! Lock Scaling Demo
+//!
+//! The demo lives in `systems_demos::demos::lock_scaling_demo`.
+//! Run with: cargo run --release --bin lock-scaling-demo
+
+use systems_demos::demo;
+use systems_demos::demos::lock_scaling_demo::LockScalingDemo;
+
+fn main() {
+    demo::run_from_env(&LockScalingDemo);
+}
@@ -0,0 +1,14 @@
+//! LRU Benchmark
+//!
+//! The demo lives in `systems_demos::demos::lru_benchmark`.
+//! Run with: cargo run --release --bin lru-benchmark
+
+use systems_demos::demo;
+use systems_demos::demos::lru_benchmark::{ByteCountingAllocator, LruBenchmark};
+
+#[global_allocator]
+static GLOBAL: ByteCountingAllocator = ByteCountingAllocator;
+
+fn main() {
+    demo::run_from_env(&LruBenchmark);
+}
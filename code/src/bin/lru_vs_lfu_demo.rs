@@ -0,0 +1,11 @@
+//! LRU vs LFU Demo
+//!
+//! The demo lives in `systems_demos::demos::lru_vs_lfu_demo`.
+//! Run with: cargo run --release --bin lru-vs-lfu-demo
+
+use systems_demos::demo;
+use systems_demos::demos::lru_vs_lfu_demo::LruVsLfuDemo;
+
+fn main() {
+    demo::run_from_env(&LruVsLfuDemo);
+}
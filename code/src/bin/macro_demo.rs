@@ -0,0 +1,11 @@
+//! Macro Code Generation Demo
+//!
+//! The demo lives in `systems_demos::demos::macro_demo`.
+//! Run with: cargo run --release --bin macro-demo
+
+use systems_demos::demo;
+use systems_demos::demos::macro_demo::MacroDemo;
+
+fn main() {
+    demo::run_from_env(&MacroDemo);
+}
@@ -0,0 +1,11 @@
+//! Pattern Match Compilation Demo
+//!
+//! The demo lives in `systems_demos::demos::match_demo`.
+//! Run with: cargo run --release --bin match-demo
+
+use systems_demos::demo;
+use systems_demos::demos::match_demo::MatchDemo;
+
+fn main() {
+    demo::run_from_env(&MatchDemo);
+}
@@ -0,0 +1,11 @@
+//! MaybeUninit Demo
+//!
+//! The demo lives in `systems_demos::demos::maybe_uninit_demo`.
+//! Run with: cargo run --release --bin maybe-uninit-demo
+
+use systems_demos::demo;
+use systems_demos::demos::maybe_uninit_demo::MaybeUninitDemo;
+
+fn main() {
+    demo::run_from_env(&MaybeUninitDemo);
+}
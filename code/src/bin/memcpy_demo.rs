@@ -0,0 +1,11 @@
+//! memcpy Demo
+//!
+//! The demo lives in `systems_demos::demos::memcpy_demo`.
+//! Run with: cargo run --release --bin memcpy-demo
+
+use systems_demos::demo;
+use systems_demos::demos::memcpy_demo::MemcpyDemo;
+
+fn main() {
+    demo::run_from_env(&MemcpyDemo);
+}
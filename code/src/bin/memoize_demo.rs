@@ -0,0 +1,11 @@
+//! Memoize Demo
+//!
+//! The demo lives in `systems_demos::demos::memoize_demo`.
+//! Run with: cargo run --release --bin memoize-demo
+
+use systems_demos::demo;
+use systems_demos::demos::memoize_demo::MemoizeDemo;
+
+fn main() {
+    demo::run_from_env(&MemoizeDemo);
+}
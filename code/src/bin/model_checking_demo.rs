@@ -0,0 +1,11 @@
+//! Model Checking Demo
+//!
+//! The demo lives in `systems_demos::demos::model_checking_demo`.
+//! Run with: cargo run --release --bin model-checking-demo
+
+use systems_demos::demo;
+use systems_demos::demos::model_checking_demo::ModelCheckingDemo;
+
+fn main() {
+    demo::run_from_env(&ModelCheckingDemo);
+}
@@ -0,0 +1,11 @@
+//! Monomorphization Cost Explorer
+//!
+//! The demo lives in `systems_demos::demos::monomorphization_demo`.
+//! Run with: cargo run --release --bin monomorphization-demo
+
+use systems_demos::demo;
+use systems_demos::demos::monomorphization_demo::MonomorphizationDemo;
+
+fn main() {
+    demo::run_from_env(&MonomorphizationDemo);
+}
@@ -0,0 +1,11 @@
+//! Bounded MPMC Queue Demo
+//!
+//! The demo lives in `systems_demos::demos::mpmc_queue_demo`.
+//! Run with: cargo run --release --bin mpmc-queue-demo
+
+use systems_demos::demo;
+use systems_demos::demos::mpmc_queue_demo::MpmcQueueDemo;
+
+fn main() {
+    demo::run_from_env(&MpmcQueueDemo);
+}
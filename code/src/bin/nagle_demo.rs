@@ -0,0 +1,11 @@
+//! Nagle's Algorithm Demo
+//!
+//! The demo lives in `systems_demos::demos::nagle_demo`.
+//! Run with: cargo run --release --bin nagle-demo
+
+use systems_demos::demo;
+use systems_demos::demos::nagle_demo::NagleDemo;
+
+fn main() {
+    demo::run_from_env(&NagleDemo);
+}
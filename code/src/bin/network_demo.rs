@@ -0,0 +1,11 @@
+//! TCP Round-Trip Demo
+//!
+//! The demo lives in `systems_demos::demos::network_demo`.
+//! Run with: cargo run --release --bin network-demo
+
+use systems_demos::demo;
+use systems_demos::demos::network_demo::NetworkDemo;
+
+fn main() {
+    demo::run_from_env(&NetworkDemo);
+}
@@ -0,0 +1,11 @@
+//! no_std Demo
+//!
+//! The demo lives in `systems_demos::demos::no_std_demo`.
+//! Run with: cargo run --release --bin no-std-demo
+
+use systems_demos::demo;
+use systems_demos::demos::no_std_demo::NoStdDemo;
+
+fn main() {
+    demo::run_from_env(&NoStdDemo);
+}
@@ -0,0 +1,19 @@
+//! Page Cache Demo
+//!
+//! The demo lives in `systems_demos::demos::page_cache_demo`.
+//! Run with: cargo run --release --bin page-cache-demo
+
+#[cfg(target_os = "linux")]
+use systems_demos::demo;
+#[cfg(target_os = "linux")]
+use systems_demos::demos::page_cache_demo::PageCacheDemo;
+
+#[cfg(target_os = "linux")]
+fn main() {
+    demo::run_from_env(&PageCacheDemo);
+}
+
+#[cfg(not(target_os = "linux"))]
+fn main() {
+    eprintln!("page-cache-demo needs Linux (posix_fadvise and mincore)");
+}
@@ -0,0 +1,13 @@
+//! Persistent Cache Demo
+//!
+//! The demo lives in `systems_demos::demos::persistent_cache_demo`.
+//! Run with: cargo run --release --bin persistent-cache-demo
+
+use systems_demos::demo;
+use systems_demos::demos::persistent_cache_demo::{self, PersistentCacheDemo};
+
+fn main() {
+    if !persistent_cache_demo::run_as_child() {
+        demo::run_from_env(&PersistentCacheDemo);
+    }
+}
@@ -0,0 +1,11 @@
+//! PRNG Demo
+//!
+//! The demo lives in `systems_demos::demos::prng_demo`.
+//! Run with: cargo run --release --bin prng-demo
+
+use systems_demos::demo;
+use systems_demos::demos::prng_demo::PrngDemo;
+
+fn main() {
+    demo::run_from_env(&PrngDemo);
+}
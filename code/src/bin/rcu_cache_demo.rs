@@ -0,0 +1,11 @@
+//! RCU Cache Demo
+//!
+//! The demo lives in `systems_demos::demos::rcu_cache_demo`.
+//! Run with: cargo run --release --bin rcu-cache-demo
+
+use systems_demos::demo;
+use systems_demos::demos::rcu_cache_demo::RcuCacheDemo;
+
+fn main() {
+    demo::run_from_env(&RcuCacheDemo);
+}
@@ -0,0 +1,11 @@
+//! Read-Copy-Update Demo
+//!
+//! The demo lives in `systems_demos::demos::rcu_demo`.
+//! Run with: cargo run --release --bin rcu-demo
+
+use systems_demos::demo;
+use systems_demos::demos::rcu_demo::RcuDemo;
+
+fn main() {
+    demo::run_from_env(&RcuDemo);
+}
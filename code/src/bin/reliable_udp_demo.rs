@@ -0,0 +1,11 @@
+//! Reliable UDP Demo
+//!
+//! The demo lives in `systems_demos::demos::reliable_udp_demo`.
+//! Run with: cargo run --release --bin reliable-udp-demo
+
+use systems_demos::demo;
+use systems_demos::demos::reliable_udp_demo::ReliableUdpDemo;
+
+fn main() {
+    demo::run_from_env(&ReliableUdpDemo);
+}
@@ -0,0 +1,11 @@
+//! Scoped Threads & Parallel Map-Reduce
+//!
+//! The demo lives in `systems_demos::demos::scoped_threads_demo`.
+//! Run with: cargo run --release --bin scoped-threads-demo
+
+use systems_demos::demo;
+use systems_demos::demos::scoped_threads_demo::ScopedThreadsDemo;
+
+fn main() {
+    demo::run_from_env(&ScopedThreadsDemo);
+}
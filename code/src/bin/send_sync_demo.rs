@@ -0,0 +1,11 @@
+//! Send & Sync Demo
+//!
+//! The demo lives in `systems_demos::demos::send_sync_demo`.
+//! Run with: cargo run --bin send-sync-demo
+
+use systems_demos::demo;
+use systems_demos::demos::send_sync_demo::SendSyncDemo;
+
+fn main() {
+    demo::run_from_env(&SendSyncDemo);
+}
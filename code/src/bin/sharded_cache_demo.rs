@@ -0,0 +1,11 @@
+//! Sharded Cache Demo
+//!
+//! The demo lives in `systems_demos::demos::sharded_cache_demo`.
+//! Run with: cargo run --release --bin sharded-cache-demo
+
+use systems_demos::demo;
+use systems_demos::demos::sharded_cache_demo::ShardedCacheDemo;
+
+fn main() {
+    demo::run_from_env(&ShardedCacheDemo);
+}
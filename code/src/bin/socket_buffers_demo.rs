@@ -0,0 +1,19 @@
+//! Socket Buffers Demo
+//!
+//! The demo lives in `systems_demos::demos::socket_buffers_demo`.
+//! Run with: cargo run --release --bin socket-buffers-demo
+
+#[cfg(target_os = "linux")]
+use systems_demos::demo;
+#[cfg(target_os = "linux")]
+use systems_demos::demos::socket_buffers_demo::SocketBuffersDemo;
+
+#[cfg(target_os = "linux")]
+fn main() {
+    demo::run_from_env(&SocketBuffersDemo);
+}
+
+#[cfg(not(target_os = "linux"))]
+fn main() {
+    eprintln!("socket-buffers-demo needs Linux");
+}
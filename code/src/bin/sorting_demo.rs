@@ -0,0 +1,11 @@
+//! Sorting Demo
+//!
+//! The demo lives in `systems_demos::demos::sorting_demo`.
+//! Run with: cargo run --release --bin sorting-demo
+
+use systems_demos::demo;
+use systems_demos::demos::sorting_demo::SortingDemo;
+
+fn main() {
+    demo::run_from_env(&SortingDemo);
+}
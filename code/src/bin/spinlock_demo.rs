@@ -0,0 +1,11 @@
+//! Spinlock Demo
+//!
+//! The demo lives in `systems_demos::demos::spinlock_demo`.
+//! Run with: cargo run --release --bin spinlock-demo
+
+use systems_demos::demo;
+use systems_demos::demos::spinlock_demo::SpinlockDemo;
+
+fn main() {
+    demo::run_from_env(&SpinlockDemo);
+}
@@ -0,0 +1,14 @@
+//! String Memory Layout & Cow Demo
+//!
+//! The demo lives in `systems_demos::demos::string_layout_demo`.
+//! Run with: cargo run --release --bin string-layout-demo
+
+use systems_demos::demo;
+use systems_demos::demos::string_layout_demo::{CountingAllocator, StringLayoutDemo};
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn main() {
+    demo::run_from_env(&StringLayoutDemo);
+}
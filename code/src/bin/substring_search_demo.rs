@@ -0,0 +1,11 @@
+//! Substring Search Demo
+//!
+//! The demo lives in `systems_demos::demos::substring_search_demo`.
+//! Run with: cargo run --release --bin substring-search-demo
+
+use systems_demos::demo;
+use systems_demos::demos::substring_search_demo::SubstringSearchDemo;
+
+fn main() {
+    demo::run_from_env(&SubstringSearchDemo);
+}
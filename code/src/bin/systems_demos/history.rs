@@ -0,0 +1,212 @@
+//! Result History
+//!
+//! `run` and `all` append their results to `history.sqlite` in the target
+//! directory (`--history FILE` for another file, `--no-history` for none),
+//! and `systems-demos history` reads it back: every metric it holds, or one
+//! metric run by run, with the change from each run to the next and a plot.
+
+use std::fs;
+use std::path::PathBuf;
+
+use clap::Args;
+use systems_demos::baseline::{self, higher_is_better};
+use systems_demos::demo::Metric;
+use systems_demos::history::{Error, History, Point};
+use systems_demos::output::{Style, Table};
+use systems_demos::plot::LinePlot;
+use systems_demos::report::{display_value, utc_timestamp};
+use systems_demos::results::Results;
+
+use super::{target_dir, Prose};
+
+/// Where the history lives unless `--history` says otherwise
+fn default_path() -> PathBuf {
+    target_dir().join("history.sqlite")
+}
+
+#[derive(Args)]
+pub struct Recording {
+    /// Append this run's results to FILE instead of history.sqlite in the
+    /// target directory
+    #[arg(long, value_name = "FILE")]
+    history: Option<PathBuf>,
+    /// Don't append this run's results to the history
+    #[arg(long, conflicts_with = "history")]
+    no_history: bool,
+}
+
+impl Recording {
+    /// Append `results` to the history. A history that can't be written
+    /// only earns a warning: the run itself went fine.
+    pub fn record(&self, results: &Results, prose: Prose) {
+        if self.no_history {
+            return;
+        }
+        let path = self.history.clone().unwrap_or_else(default_path);
+        if let Err(err) = path.parent().map_or(Ok(()), fs::create_dir_all) {
+            eprintln!("⚠️  couldn't create the history in {}: {}", path.display(), err);
+            return;
+        }
+        match History::open(&path).and_then(|mut history| history.record(results)) {
+            Ok(0) => {}
+            Ok(count) => progress!(prose, "🗄️  Added {} measurements to the history in {}", count, path.display()),
+            Err(err) => eprintln!("⚠️  couldn't add the results to the history in {}: {}", path.display(), err),
+        }
+    }
+}
+
+#[derive(Args)]
+pub struct Query {
+    /// Demo to look at; without one, list every metric in the history
+    demo: Option<String>,
+    /// Metric to follow across runs; without one, list the demo's metrics
+    metric: Option<String>,
+    /// Only measurements taken with this parameter, e.g. threads=4
+    #[arg(long = "param", value_name = "KEY=VALUE")]
+    params: Vec<String>,
+    /// Only the most recent N runs
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+    last: Option<u64>,
+    /// The history file to read (default: history.sqlite in the target
+    /// directory)
+    #[arg(long, value_name = "FILE")]
+    history: Option<PathBuf>,
+}
+
+/// `systems-demos history`: returns 1 if there was nothing to show
+pub fn show(query: &Query) -> usize {
+    let path = query.history.clone().unwrap_or_else(default_path);
+    if !path.is_file() {
+        eprintln!("error: no history at {} yet", path.display());
+        eprintln!("  `systems-demos run` and `all` start one");
+        return 1;
+    }
+    let history = match History::open(&path) {
+        Ok(history) => history,
+        Err(err) => {
+            eprintln!("error: couldn't read the history in {}: {}", path.display(), err);
+            return 1;
+        }
+    };
+    let shown = match (&query.demo, &query.metric) {
+        (demo, None) => list(&history, demo.as_deref()),
+        (Some(demo), Some(metric)) => trend(&history, demo, metric, query),
+        (None, Some(_)) => unreachable!("clap fills positionals in order"),
+    };
+    match shown {
+        Ok(true) => 0,
+        Ok(false) => 1,
+        Err(err) => {
+            eprintln!("error: couldn't read the history in {}: {}", path.display(), err);
+            1
+        }
+    }
+}
+
+/// The metrics the history holds, for one demo or all of them
+fn list(history: &History, demo: Option<&str>) -> Result<bool, Error> {
+    let recorded = history.recorded(demo)?;
+    if recorded.is_empty() {
+        match demo {
+            Some(demo) => eprintln!("The history has no measurements of {}", demo),
+            None => eprintln!("The history is empty: none of the runs so far measured anything"),
+        }
+        return Ok(false);
+    }
+    let mut table = Table::new(["demo", "metric", "runs", "last run"]).left(1);
+    for metric in &recorded {
+        table.row([metric.demo.clone(), metric.name.clone(), metric.runs.to_string(), utc_timestamp(metric.last)]);
+    }
+    print!("{}", table);
+    println!("\nFollow one across runs with: systems-demos history <DEMO> <METRIC>");
+    Ok(true)
+}
+
+/// Every measurement of one metric, a table per parameter set, then a plot
+/// of all of them against the run number
+fn trend(history: &History, demo: &str, metric: &str, query: &Query) -> Result<bool, Error> {
+    let points = history.trend(demo, metric)?;
+    if points.is_empty() {
+        eprintln!("error: the history has no '{}' measurements of {}", metric, demo);
+        let names: Vec<String> = history.recorded(Some(demo))?.into_iter().map(|recorded| recorded.name).collect();
+        if !names.is_empty() {
+            eprintln!("  it has: {}", names.join(", "));
+        }
+        return Ok(false);
+    }
+
+    // One series per parameter set: threads=2 and threads=4 are different numbers
+    let mut series: Vec<(String, Vec<Point>)> = Vec::new();
+    for point in points {
+        let given: Vec<&str> = point.params.split(", ").collect();
+        if !query.params.iter().all(|wanted| given.contains(&wanted.as_str())) {
+            continue;
+        }
+        match series.iter_mut().find(|(params, _)| *params == point.params) {
+            Some((_, points)) => points.push(point),
+            None => series.push((point.params.clone(), vec![point])),
+        }
+    }
+    if series.is_empty() {
+        eprintln!("error: no '{}' measurements of {} were taken with {}", metric, demo, query.params.join(", "));
+        return Ok(false);
+    }
+    if let Some(last) = query.last {
+        for (_, points) in &mut series {
+            let skip = points.len().saturating_sub(last as usize);
+            points.drain(..skip);
+        }
+    }
+
+    for (params, points) in &series {
+        let mut title = format!("📈 {}: {}", demo, metric);
+        if !params.is_empty() {
+            title.push_str(&format!(" ({})", params));
+        }
+        println!("{}\n{}", Style::Heading.paint(&title), Style::Dim.paint("=".repeat(title.chars().count())));
+        let mut table = Table::new(["run", "date", "machine", "profile", "value", "change"]).left(1).left(2).left(3);
+        for (i, point) in points.iter().enumerate() {
+            let change = if i == 0 { String::new() } else { change_from(&points[i - 1], point) };
+            table.row([format!("#{}", point.run), utc_timestamp(point.timestamp), point.machine.clone(),
+                       point.profile.clone(), display_value(&as_metric(point)), change]);
+        }
+        println!("{}", table);
+        if let [first, .., last] = points.as_slice() {
+            println!("First to last: {} -> {} ({})\n", display_value(&as_metric(first)), display_value(&as_metric(last)),
+                     change_from(first, last));
+        }
+    }
+
+    if series.iter().any(|(_, points)| points.len() > 1) {
+        let unit = &series[0].1[0].unit;
+        let mut plot = LinePlot::new("run", unit.as_str()).format_x(|run| format!("#{:.0}", run));
+        for (params, points) in &series {
+            let name = if params.is_empty() { metric.to_string() } else { params.clone() };
+            plot = plot.series(name, points.iter().map(|point| (point.run as f64, point.value)).collect());
+        }
+        println!("{}", plot);
+    }
+    Ok(true)
+}
+
+fn as_metric(point: &Point) -> Metric {
+    Metric { name: String::new(), value: point.value, unit: point.unit.clone(), params: Default::default() }
+}
+
+/// `+12.5%`, green when it's an improvement and red when it's a
+/// regression, left plain within the baseline comparison's noise threshold
+fn change_from(before: &Point, after: &Point) -> String {
+    if before.value == 0.0 || before.unit != after.unit {
+        return String::new();
+    }
+    let change = (after.value - before.value) / before.value.abs() * 100.0;
+    let better = if higher_is_better(&after.unit) { change } else { -change };
+    let text = format!("{:+.1}%", change);
+    if change.abs() < baseline::DEFAULT_THRESHOLD {
+        text
+    } else if better > 0.0 {
+        Style::Faster.paint(text).to_string()
+    } else {
+        Style::Slower.paint(text).to_string()
+    }
+}
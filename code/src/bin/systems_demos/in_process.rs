@@ -0,0 +1,201 @@
+//! In-Process Run
+//!
+//! `systems-demos run-all`: every demo (or one category) run inside this
+//! process through the `Demo` trait, each followed by its `verify()` checks,
+//! then one table with a row per demo: whether it ran, how long it took,
+//! what it measured and how its checks went. A quick way to exercise the
+//! whole crate on a new machine without building forty binaries first.
+//!
+//! Unlike `all`, a demo that panics doesn't take the others down, but one
+//! that crashes the process does; `all` runs each in its own.
+
+use std::panic::{self, AssertUnwindSafe};
+use std::time::{Duration, Instant};
+
+use clap::Args;
+use systems_demos::catalog;
+use systems_demos::demo::{Demo, DemoReport};
+use systems_demos::output::{self, ColorChoice, Style, Table, Verbosity};
+use systems_demos::report::display_value;
+use systems_demos::results::{DemoResult, Results};
+use systems_demos::verify::{Check, Outcome, Verification};
+use systems_demos::{demos, envinfo};
+
+use super::{resolve, ParamArgs, Prose, Recording, CATEGORIES};
+
+#[derive(Args)]
+pub struct RunAllArgs {
+    #[arg(long, value_parser = clap::builder::PossibleValuesParser::new(CATEGORIES))]
+    category: Option<String>,
+    /// Only this demo; repeat for more
+    #[arg(long = "demo", value_name = "NAME")]
+    demos: Vec<String>,
+    #[command(flatten)]
+    params: ParamArgs,
+    /// Print each demo's full output instead of only what it measured
+    #[arg(long)]
+    show_output: bool,
+    /// Don't run the demos' self-checks after them
+    #[arg(long)]
+    no_verify: bool,
+    /// Color the output: auto colors a terminal unless NO_COLOR is set
+    #[arg(long, value_name = "WHEN", default_value = "auto", value_parser = ["auto", "always", "never"])]
+    color: String,
+    #[command(flatten)]
+    recording: Recording,
+}
+
+/// One row of the summary
+struct Row {
+    demo: &'static str,
+    category: &'static str,
+    /// `None` if the demo panicked
+    report: Option<DemoReport>,
+    elapsed: Duration,
+    /// `None` with `--no-verify`, or if `verify` panicked
+    verification: Option<Verification>,
+}
+
+impl Row {
+    fn failed(&self) -> bool {
+        self.report.is_none() || self.verification.as_ref().is_some_and(|verification| !verification.passed())
+    }
+}
+
+/// Run `f`, turning a panic into `None`; the panic hook has already
+/// printed the message
+fn survive<T>(f: impl FnOnce() -> T) -> Option<T> {
+    panic::catch_unwind(AssertUnwindSafe(f)).ok()
+}
+
+/// `systems-demos run-all`: returns how many demos panicked or failed a
+/// check, or `None` if a `--demo` names no demo
+pub fn run(args: &RunAllArgs) -> Option<usize> {
+    let wanted = resolve(&args.demos)?;
+    let verbosity = if args.show_output { Verbosity::Normal } else { Verbosity::Quiet };
+    let color = ColorChoice::from_name(&args.color).unwrap_or_default();
+    output::configure(output::Settings { verbosity, only: Vec::new(), color });
+
+    let demos: Vec<Box<dyn Demo>> = demos::all()
+        .into_iter()
+        .filter(|demo| args.category.as_deref().is_none_or(|wanted| category(demo.as_ref()) == wanted))
+        .filter(|demo| wanted.is_empty() || wanted.iter().any(|entry| entry.name == demo.name()))
+        .collect();
+    let config = args.params.config();
+    let mut results = Results::new(config);
+    envinfo::print(&results.environment);
+    if cfg!(debug_assertions) {
+        eprintln!("⚠️  a debug build: run the runner with --release for meaningful timings");
+    }
+
+    let mut rows = Vec::new();
+    for (i, demo) in demos.iter().enumerate() {
+        let banner = format!("▶ [{}/{}] {}", i + 1, demos.len(), demo.name());
+        println!("{}\n{}\n", Style::Title.paint(&banner), Style::Dim.paint("━".repeat(banner.chars().count())));
+        let start = Instant::now();
+        let report = survive(|| demo.run(&config));
+        let elapsed = start.elapsed();
+        match &report {
+            Some(report) => output::finish(report),
+            None => eprintln!("❌ {} panicked", demo.name()),
+        }
+        let verification = if args.no_verify || report.is_none() { None } else { survive(|| demo.verify()) };
+        if let Some(verification) = &verification {
+            for check in verification.checks() {
+                println!("{}", check);
+            }
+        }
+        println!();
+        results.results.push(DemoResult {
+            demo: demo.name().to_string(),
+            succeeded: report.is_some(),
+            metrics: report.as_ref().map(|report| report.metrics.clone()).unwrap_or_default(),
+        });
+        rows.push(Row { demo: demo.name(), category: category(demo.as_ref()), report, elapsed, verification });
+    }
+
+    summarize(&rows, !args.no_verify);
+    args.recording.record(&results, Prose::Terminal);
+    Some(rows.iter().filter(|row| row.failed()).count())
+}
+
+fn category(demo: &dyn Demo) -> &'static str {
+    catalog::find(demo.name()).map_or("", |entry| entry.category)
+}
+
+/// The matrix: a row per demo, then the checks that failed and the totals
+fn summarize(rows: &[Row], verified: bool) {
+    let title = "📋 Summary";
+    println!("{}\n{}", Style::Heading.paint(title), Style::Dim.paint("=".repeat(title.chars().count())));
+    let mut table = Table::new(["demo", "category", "result", "time", "metrics", "first metric", "checks"])
+        .left(1)
+        .left(2)
+        .left(5)
+        .left(6);
+    for row in rows {
+        let result = match row.report {
+            Some(_) => Style::Faster.paint("✅ ran").to_string(),
+            None => Style::Slower.paint("❌ panicked").to_string(),
+        };
+        let metrics = row.report.as_ref().map_or(&[][..], |report| &report.metrics[..]);
+        let first = metrics.first().map_or(String::new(), |metric| format!("{}: {}", metric.name, display_value(metric)));
+        table.row([row.demo.to_string(), row.category.to_string(), result, format!("{:.2?}", row.elapsed),
+                   metrics.len().to_string(), first, checks(row.verification.as_ref())]);
+    }
+    print!("{}", table);
+
+    let failed: Vec<String> = rows
+        .iter()
+        .filter_map(|row| Some((row.demo, row.verification.as_ref()?)))
+        .flat_map(|(demo, verification)| verification.failures().map(move |check| format!("{}: {}", demo, check)))
+        .collect();
+    if !failed.is_empty() {
+        println!("\nClaims that didn't hold:");
+        for failure in &failed {
+            println!("  {}", failure);
+        }
+    }
+
+    let panicked = rows.iter().filter(|row| row.report.is_none()).count();
+    let total: Duration = rows.iter().map(|row| row.elapsed).sum();
+    let mut totals = format!("\n{} of {} demos ran in {:.1?}", rows.len() - panicked, rows.len(), total);
+    if verified {
+        let checks = rows.iter().filter_map(|row| row.verification.as_ref()).flat_map(Verification::checks);
+        let [held, failed, skipped] = tally(checks);
+        totals.push_str(&format!("; checks: {} held, {} failed, {} skipped", held, failed, skipped));
+    }
+    println!("{}", totals);
+}
+
+/// How many checks held, failed and were skipped
+fn tally<'a>(checks: impl IntoIterator<Item = &'a Check>) -> [usize; 3] {
+    let mut tally = [0; 3];
+    for check in checks {
+        match check.outcome {
+            Outcome::Held => tally[0] += 1,
+            Outcome::Failed(_) => tally[1] += 1,
+            Outcome::Skipped(_) => tally[2] += 1,
+        }
+    }
+    tally
+}
+
+/// `3 held, 1 failed` for the summary's checks column
+fn checks(verification: Option<&Verification>) -> String {
+    let Some(verification) = verification else { return String::new() };
+    if verification.checks().is_empty() {
+        return Style::Dim.paint("none").to_string();
+    }
+    let [held, failed, skipped] = tally(verification.checks());
+    let mut parts = Vec::new();
+    if held > 0 {
+        parts.push(Style::Faster.paint(format!("{} held", held)).to_string());
+    }
+    if failed > 0 {
+        parts.push(Style::Slower.paint(format!("{} failed", failed)).to_string());
+    }
+    if skipped > 0 {
+        parts.push(format!("{} skipped", skipped));
+    }
+    parts.join(", ")
+}
@@ -0,0 +1,619 @@
+//! Systems Demos Runner
+//!
+//! One entry point for every demo: list them, run one or several by name, or
+//! run a whole category in learning order. Parameters like `--size`,
+//! `--threads` and `--seed` (for random access patterns) are forwarded to
+//! demos that understand them, and `--format json|csv` collects what the
+//! demos measured for scripts.
+//! `--quiz` follows each demo with a few questions about what it showed,
+//! and `--quiet`, `--verbose` and `--only SECTION` trim or extend what the
+//! demos print; `--color` overrides whether it is colored.
+//! `--save-baseline NAME` keeps a run's results, and `--compare-baseline
+//! NAME` reports how a later run moved against them. `run --profile`
+//! samples each demo's stacks into a flamegraph under `profiles/`.
+//! Every `run` and `all` is also added to a SQLite history, and `history
+//! DEMO METRIC` shows how a metric moved across all of them.
+//! `run-all` runs every demo inside the runner instead, checks what each
+//! claims and ends with one summary table.
+//! `report` renders a set of demos into one Markdown or HTML document, and
+//! `tui` browses and runs them interactively.
+//! Run with: cargo run --release --bin systems-demos -- list
+
+use std::path::{Path, PathBuf};
+use std::process::{self, Command, ExitCode, ExitStatus, Stdio};
+use std::{env, fs, io};
+
+use clap::error::ErrorKind;
+use clap::{Args, CommandFactory, Parser, Subcommand, ValueEnum};
+use systems_demos::catalog::{self, Entry, Param, CATEGORIES, DEMOS};
+use systems_demos::demo::{Config, DemoReport};
+use systems_demos::output::{self, ColorChoice, Style, Verbosity};
+use systems_demos::quiz::{self, Score};
+#[cfg(target_os = "linux")]
+use systems_demos::demos::{async_runtime_demo, io_bound_demo};
+use systems_demos::demos::ffi_demo;
+use systems_demos::demos::persistent_cache_demo;
+use systems_demos::{baseline, demos, envinfo, profile, report};
+use systems_demos::results::{DemoResult, Results};
+
+#[cfg(feature = "tui")]
+mod tui;
+
+/// Progress output: stdout alongside the demos' own output, stderr when
+/// stdout carries results
+macro_rules! progress {
+    ($prose:expr, $($arg:tt)*) => {
+        if $prose == Prose::Terminal { println!($($arg)*) } else { eprintln!($($arg)*) }
+    };
+}
+
+// After `progress!`, which it uses
+#[cfg(feature = "history")]
+mod history;
+#[cfg(feature = "history")]
+use history::Recording;
+
+mod in_process;
+
+/// Without the `history` feature there is nothing to record to
+#[cfg(not(feature = "history"))]
+#[derive(Args)]
+struct Recording {}
+
+#[cfg(not(feature = "history"))]
+impl Recording {
+    fn record(&self, _results: &Results, _prose: Prose) {}
+}
+
+#[derive(Parser)]
+#[command(name = "systems-demos", version, about = "Run the Computer Systems Through Rust demos")]
+struct Cli {
+    #[command(subcommand)]
+    command: Action,
+}
+
+#[derive(Subcommand)]
+enum Action {
+    /// List the demos with their category and the parameters they accept
+    List {
+        /// Only this category (hardware, memory, compilation, rust-features, os, concurrency, networking, advanced)
+        #[arg(long, value_parser = clap::builder::PossibleValuesParser::new(CATEGORIES))]
+        category: Option<String>,
+    },
+    /// Run one or more demos by name
+    Run {
+        #[arg(required = true, value_name = "DEMO")]
+        names: Vec<String>,
+        #[command(flatten)]
+        params: ParamArgs,
+        #[command(flatten)]
+        output: OutputArgs,
+        #[command(flatten)]
+        baselines: BaselineArgs,
+        #[command(flatten)]
+        recording: Recording,
+        /// Sample each demo's call stacks and write profiles/<demo>.svg (a
+        /// flamegraph) and profiles/<demo>.folded
+        #[arg(long)]
+        profile: bool,
+    },
+    /// Run every demo, or every demo in one category, in learning order
+    All {
+        #[arg(long, value_parser = clap::builder::PossibleValuesParser::new(CATEGORIES))]
+        category: Option<String>,
+        /// Continue with the next demo when one fails
+        #[arg(long)]
+        keep_going: bool,
+        #[command(flatten)]
+        params: ParamArgs,
+        #[command(flatten)]
+        output: OutputArgs,
+        #[command(flatten)]
+        baselines: BaselineArgs,
+        #[command(flatten)]
+        recording: Recording,
+    },
+    /// Run every demo (or one category) inside this process, check what
+    /// each claims, and summarize it all in one table
+    RunAll(in_process::RunAllArgs),
+    /// Run demos and render their output and measurements as one Markdown
+    /// or HTML document
+    Report {
+        /// Demos to include; default: every demo (in --category, if given)
+        #[arg(value_name = "DEMO")]
+        names: Vec<String>,
+        #[arg(long, value_parser = clap::builder::PossibleValuesParser::new(CATEGORIES), conflicts_with = "names")]
+        category: Option<String>,
+        #[arg(long, value_enum, default_value_t = ReportFormat::Markdown)]
+        format: ReportFormat,
+        /// Write the report to this file instead of stdout
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+        #[command(flatten)]
+        params: ParamArgs,
+    },
+    /// List the metrics in the result history, or show how one moved
+    /// across the runs that measured it
+    #[cfg(feature = "history")]
+    History(history::Query),
+    /// Browse the demos by topic, run them, tweak their parameters and chart
+    /// what they measure
+    #[cfg(feature = "tui")]
+    Tui,
+}
+
+#[derive(Args)]
+struct ParamArgs {
+    /// Problem size (elements, bytes or operations) for demos that take one
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+    size: Option<u64>,
+    /// Thread count, or the maximum for demos that sweep 1..N threads
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+    threads: Option<u64>,
+    /// Repetitions of the timed loop
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+    iterations: Option<u64>,
+    /// Seed for random access patterns, so a run can be repeated exactly
+    #[arg(long)]
+    seed: Option<u64>,
+}
+
+#[derive(Args)]
+struct OutputArgs {
+    /// text: the demos' own output. json/csv: their metrics on stdout, with
+    /// the demos' output moved to stderr
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+    /// After each demo, answer a few questions about what it showed
+    #[arg(long)]
+    quiz: bool,
+    /// Print only each demo's title and what it measured
+    #[arg(long, short, conflicts_with = "verbose")]
+    quiet: bool,
+    /// Also print sample spreads and the section names --only accepts
+    #[arg(long, short)]
+    verbose: bool,
+    /// Show only the sections whose heading matches, e.g. false-sharing
+    #[arg(long, value_name = "SECTION", value_delimiter = ',')]
+    only: Vec<String>,
+    /// Color headings and comparisons: auto colors a terminal unless
+    /// NO_COLOR is set
+    #[arg(long, value_name = "WHEN", default_value = "auto", value_parser = ["auto", "always", "never"])]
+    color: String,
+}
+
+impl OutputArgs {
+    /// What the demos should print, passed on to each one
+    fn output(&self) -> output::Settings {
+        let verbosity = if self.quiet {
+            Verbosity::Quiet
+        } else if self.verbose {
+            Verbosity::Verbose
+        } else {
+            Verbosity::Normal
+        };
+        output::Settings {
+            verbosity,
+            only: self.only.iter().map(|name| output::slug(name)).collect(),
+            color: ColorChoice::from_name(&self.color).unwrap_or_default(),
+        }
+    }
+
+    /// `--quiz`, which talks on stdout and so only goes with text output
+    fn quiz(&self) -> bool {
+        if self.quiz && self.format != Format::Text {
+            Cli::command().error(ErrorKind::ArgumentConflict,
+                                 "--quiz asks its questions on stdout, so it only works with --format text").exit();
+        }
+        self.quiz
+    }
+}
+
+#[derive(Args)]
+struct BaselineArgs {
+    /// Save this run's results as baseline NAME: target/baselines/NAME.json,
+    /// or NAME itself if it ends in .json
+    #[arg(long, value_name = "NAME")]
+    save_baseline: Option<String>,
+    /// Compare this run with a saved baseline and report what moved
+    #[arg(long, value_name = "NAME")]
+    compare_baseline: Option<String>,
+    /// Changes smaller than this many percent count as noise when comparing
+    #[arg(long, value_name = "PERCENT", default_value_t = baseline::DEFAULT_THRESHOLD)]
+    threshold: f64,
+}
+
+impl BaselineArgs {
+    /// The baseline to compare with, read before the run so a typo doesn't
+    /// cost a whole run; `Err` once the problem has been printed
+    fn load(&self) -> Result<Option<Results>, ()> {
+        let Some(name) = &self.compare_baseline else { return Ok(None) };
+        let path = baseline_path(name);
+        let json = fs::read_to_string(&path).map_err(|err| {
+            eprintln!("error: couldn't read baseline '{}' ({}): {}", name, path.display(), err);
+            eprintln!("  save one first with: --save-baseline {}", name);
+        })?;
+        Results::from_json(&json).map(Some).map_err(|err| {
+            eprintln!("error: {} isn't a saved baseline: {}", path.display(), err);
+        })
+    }
+
+    /// Print the comparison with `baseline` and save `results`, as asked;
+    /// `false` if saving failed
+    fn finish(&self, baseline: Option<&Results>, results: &Results, prose: Prose) -> bool {
+        if let (Some(name), Some(baseline)) = (&self.compare_baseline, baseline) {
+            let title = format!("📊 Compared with baseline '{}'", name);
+            let rule = "=".repeat(title.chars().count());
+            if prose == Prose::Terminal {
+                println!("{}\n{}", Style::Heading.paint(&title), Style::Dim.paint(rule));
+            } else {
+                eprintln!("{}\n{}", title, rule);
+            }
+            progress!(prose, "{}\n", baseline::compare(baseline, results, self.threshold));
+        }
+        let Some(name) = &self.save_baseline else { return true };
+        let path = baseline_path(name);
+        let saved = path.parent().map_or(Ok(()), fs::create_dir_all).and_then(|()| fs::write(&path, results.to_json()));
+        match saved {
+            Ok(()) => {
+                progress!(prose, "💾 Saved baseline '{}' to {}", name, path.display());
+                true
+            }
+            Err(err) => {
+                eprintln!("❌ couldn't save baseline to {}: {}", path.display(), err);
+                false
+            }
+        }
+    }
+}
+
+/// Where baseline `name` lives: `baselines/NAME.json` in the target
+/// directory this runner was built into, or `name` itself for a .json path
+fn baseline_path(name: &str) -> PathBuf {
+    if name.ends_with(".json") {
+        return PathBuf::from(name);
+    }
+    target_dir().join("baselines").join(format!("{}.json", name))
+}
+
+/// The target directory this runner was built into, where it keeps what
+/// outlives a run
+fn target_dir() -> PathBuf {
+    let target = env::current_exe().ok().and_then(|exe| Some(exe.parent()?.parent()?.to_path_buf()));
+    target.unwrap_or_default()
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    Text,
+    Json,
+    Csv,
+}
+
+impl Format {
+    fn prose(self) -> Prose {
+        if self == Format::Text { Prose::Terminal } else { Prose::Stderr }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ReportFormat {
+    Markdown,
+    Html,
+}
+
+/// Where a demo's own output goes
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Prose {
+    Terminal,
+    /// Out of the way of structured results on stdout
+    Stderr,
+    /// Kept, to be embedded in a report
+    Captured,
+}
+
+impl ParamArgs {
+    fn config(&self) -> Config {
+        let convert = |value: Option<u64>| value.map(|v| v as usize);
+        Config {
+            size: convert(self.size),
+            threads: convert(self.threads),
+            iterations: convert(self.iterations),
+            seed: self.seed,
+        }
+    }
+
+    fn given(&self) -> Vec<Param> {
+        [(Param::Size, self.size), (Param::Threads, self.threads), (Param::Iterations, self.iterations), (Param::Seed, self.seed)]
+            .into_iter()
+            .filter_map(|(param, value)| value.map(|_| param))
+            .collect()
+    }
+}
+
+fn list(category: Option<&str>) {
+    for &current in CATEGORIES.iter().filter(|&&c| category.is_none_or(|wanted| wanted == c)) {
+        println!("{}:", current);
+        for entry in DEMOS.iter().filter(|entry| entry.category == current) {
+            let params: Vec<String> = entry.params.iter().map(|p| format!("--{}", p.flag())).collect();
+            println!("  {:<26} {}{}", entry.name, entry.description,
+                     if params.is_empty() { String::new() } else { format!(" [{}]", params.join(" ")) });
+        }
+        println!();
+    }
+    println!("Run one with: systems-demos run <DEMO> [--size N] [--threads N] [--iterations N] [--seed N]");
+}
+
+/// The demo's own executable, built next to this one by `cargo build --bins`
+fn sibling_binary(name: &str) -> Option<PathBuf> {
+    let path = env::current_exe().ok()?.with_file_name(format!("{}{}", name, env::consts::EXE_SUFFIX));
+    path.is_file().then_some(path)
+}
+
+/// The command that runs a demo: its prebuilt binary if there is one,
+/// otherwise through cargo. The demo writes its report to `report_path`.
+fn demo_command(entry: &Entry, config: &Config, report_path: &Path) -> Command {
+    let mut command = match sibling_binary(entry.name) {
+        Some(path) => Command::new(path),
+        None => {
+            let mut cargo = Command::new(env::var("CARGO").unwrap_or_else(|_| "cargo".into()));
+            cargo.args(["run", "--quiet", "--manifest-path", concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml")]);
+            if !cfg!(debug_assertions) {
+                cargo.arg("--release");
+            }
+            if cfg!(feature = "gpu") {
+                cargo.args(["--features", "gpu"]);
+            }
+            cargo.args(["--bin", entry.name]);
+            cargo
+        }
+    };
+    command.envs(config.env_vars()).envs(output::settings().env_vars()).env(DemoReport::PATH_VAR, report_path);
+    command
+}
+
+/// Where a demo launched by this process writes its report
+fn report_path(entry: &Entry) -> PathBuf {
+    env::temp_dir().join(format!("systems-demos-{}-{}.json", process::id(), entry.name))
+}
+
+/// Read and remove a demo's report. A demo that crashed before writing it
+/// just has no metrics.
+fn take_report(path: &Path) -> DemoReport {
+    let report = fs::read(path).ok().and_then(|json| serde_json::from_slice(&json).ok()).unwrap_or_default();
+    let _ = fs::remove_file(path);
+    report
+}
+
+/// Run a demo and collect its report; returns the exit status, the report
+/// and, for `Prose::Captured`, what the demo printed
+fn launch(entry: &Entry, config: &Config, prose: Prose, profile: bool)
+    -> io::Result<(ExitStatus, DemoReport, String)> {
+    let report_path = report_path(entry);
+    let mut command = demo_command(entry, config, &report_path);
+    if profile {
+        command.env(profile::VAR, "1");
+    }
+    let (status, printed) = match prose {
+        Prose::Terminal => (command.status()?, String::new()),
+        Prose::Stderr => (command.stdout(Stdio::from(io::stderr())).status()?, String::new()),
+        Prose::Captured => {
+            // Escape sequences have no place in a Markdown or HTML report
+            let output = command.env(output::Settings::COLOR_VAR, "never").stdout(Stdio::piped()).stderr(Stdio::inherit()).output()?;
+            (output.status, String::from_utf8_lossy(&output.stdout).into_owned())
+        }
+    };
+    Ok((status, take_report(&report_path), printed))
+}
+
+/// Ask the quiz of the demo called `name`
+fn ask_quiz(name: &str) -> Score {
+    demos::all().iter().find(|demo| demo.name() == name).map_or_else(Score::default, |demo| quiz::run(name, demo.quiz()))
+}
+
+/// Run demos in order, collecting each one's outcome into `results` and,
+/// with `quiz`, quizzing the reader after each one that succeeds, and with
+/// `profile`, profiling each one; returns what each printed, for
+/// `Prose::Captured`
+fn run_all(entries: &[&Entry], params: &ParamArgs, prose: Prose, keep_going: bool, quiz: bool, profile: bool,
+           results: &mut Results) -> Vec<String> {
+    let config = params.config();
+    let given = params.given();
+    let mut outputs = Vec::new();
+    let mut score = Score::default();
+    // The JSON, CSV and report formats carry the environment in the results
+    if prose == Prose::Terminal && entries.iter().any(|entry| entry.release) {
+        envinfo::print(&results.environment);
+    }
+    for (i, entry) in entries.iter().enumerate() {
+        let banner = format!("▶ [{}/{}] {}", i + 1, entries.len(), entry.name);
+        if prose == Prose::Captured {
+            eprintln!("{}", banner);
+        } else if entries.len() > 1 {
+            let rule = "━".repeat(banner.chars().count());
+            if prose == Prose::Terminal {
+                println!("{}\n{}\n", Style::Title.paint(&banner), Style::Dim.paint(rule));
+            } else {
+                eprintln!("{}\n{}\n", banner, rule);
+            }
+        }
+        if entries.len() == 1 {
+            for param in given.iter().filter(|&&p| !entry.accepts(p)) {
+                eprintln!("⚠️  {} doesn't take --{}; ignoring it", entry.name, param.flag());
+            }
+        }
+        if entry.release && cfg!(debug_assertions) {
+            eprintln!("⚠️  {} is timing-sensitive: run the runner with --release for meaningful numbers", entry.name);
+        }
+
+        let (ok, report, printed) = match launch(entry, &config, prose, profile) {
+            Ok((status, report, printed)) if status.success() => (true, report, printed),
+            Ok((status, report, printed)) => {
+                eprintln!("❌ {} exited with {}", entry.name, status);
+                (false, report, printed)
+            }
+            Err(err) => {
+                eprintln!("❌ couldn't start {}: {}", entry.name, err);
+                (false, DemoReport::default(), String::new())
+            }
+        };
+        results.results.push(DemoResult { demo: entry.name.to_string(), succeeded: ok, metrics: report.metrics });
+        outputs.push(printed);
+        if quiz && ok {
+            score += ask_quiz(entry.name);
+        }
+        if prose != Prose::Captured {
+            progress!(prose, "");
+        }
+        if !ok && !keep_going {
+            break;
+        }
+    }
+    if quiz && entries.len() > 1 {
+        progress!(prose, "🧠 Quiz total: {}\n", score);
+    }
+    outputs
+}
+
+fn failures(results: &Results) -> usize {
+    results.results.iter().filter(|result| !result.succeeded).count()
+}
+
+/// Look up demos by name, explaining on stderr if one doesn't exist
+fn resolve(names: &[String]) -> Option<Vec<&'static Entry>> {
+    let mut entries = Vec::new();
+    for name in names {
+        match catalog::find(name) {
+            Some(entry) => entries.push(entry),
+            None => {
+                eprintln!("error: no demo called '{}'", name);
+                let suggestions = catalog::suggest(name);
+                if !suggestions.is_empty() {
+                    eprintln!("  did you mean: {}", suggestions.join(", "));
+                }
+                eprintln!("  see all of them with: systems-demos list");
+                return None;
+            }
+        }
+    }
+    Some(entries)
+}
+
+fn in_category(category: Option<&str>) -> Vec<&'static Entry> {
+    DEMOS.iter().filter(|entry| category.is_none_or(|c| entry.category == c)).collect()
+}
+
+/// Run demos with their output captured and render it all as one document.
+/// Failing demos are kept in the report, marked as failed.
+fn write_report(entries: &[&Entry], params: &ParamArgs, format: ReportFormat, path: Option<&Path>) -> usize {
+    let mut results = Results::new(params.config());
+    let outputs = run_all(entries, params, Prose::Captured, true, false, false, &mut results);
+    let document = match format {
+        ReportFormat::Markdown => report::markdown(&results, &outputs),
+        ReportFormat::Html => report::html(&results, &outputs),
+    };
+    match path {
+        Some(path) => match fs::write(path, document) {
+            Ok(()) => eprintln!("📄 Report written to {}", path.display()),
+            Err(err) => {
+                eprintln!("❌ couldn't write {}: {}", path.display(), err);
+                return failures(&results) + 1;
+            }
+        },
+        None => print!("{}", document),
+    }
+    failures(&results)
+}
+
+/// Print the collected results in a structured format; text output has
+/// already been printed by the demos themselves
+fn print_results(results: &Results, format: Format) {
+    match format {
+        Format::Text => {}
+        Format::Json => println!("{}", results.to_json()),
+        Format::Csv => print!("{}", results.to_csv()),
+    }
+}
+
+fn main() -> ExitCode {
+    // Demos that re-run their own executable as a child re-run this one
+    // under `run-all`
+    #[cfg(target_os = "linux")]
+    if async_runtime_demo::run_as_child() {
+        return ExitCode::SUCCESS;
+    }
+    #[cfg(target_os = "linux")]
+    if io_bound_demo::run_as_child() {
+        return ExitCode::SUCCESS;
+    }
+    if ffi_demo::run_as_child() {
+        return ExitCode::SUCCESS;
+    }
+    if persistent_cache_demo::run_as_child() {
+        return ExitCode::SUCCESS;
+    }
+    let cli = Cli::parse();
+    let failures = match cli.command {
+        Action::List { category } => {
+            list(category.as_deref());
+            0
+        }
+        Action::Run { names, params, output, baselines, recording, profile } => {
+            let Some(entries) = resolve(&names) else { return ExitCode::FAILURE };
+            let Ok(baseline) = baselines.load() else { return ExitCode::FAILURE };
+            output::configure(output.output());
+            let mut results = Results::new(params.config());
+            run_all(&entries, &params, output.format.prose(), false, output.quiz(), profile, &mut results);
+            print_results(&results, output.format);
+            recording.record(&results, output.format.prose());
+            if !baselines.finish(baseline.as_ref(), &results, output.format.prose()) {
+                return ExitCode::FAILURE;
+            }
+            failures(&results)
+        }
+        Action::All { category, keep_going, params, output, baselines, recording } => {
+            let format = output.format;
+            let entries = in_category(category.as_deref());
+            let Ok(baseline) = baselines.load() else { return ExitCode::FAILURE };
+            output::configure(output.output());
+            let mut results = Results::new(params.config());
+            run_all(&entries, &params, format.prose(), keep_going, output.quiz(), false, &mut results);
+            let failures = failures(&results);
+            if failures == 0 || keep_going {
+                progress!(format.prose(), "{} of {} demos completed", entries.len() - failures, entries.len());
+            } else {
+                progress!(format.prose(), "Stopped at the first failure (--keep-going runs the rest anyway)");
+            }
+            print_results(&results, format);
+            recording.record(&results, format.prose());
+            if !baselines.finish(baseline.as_ref(), &results, format.prose()) {
+                return ExitCode::FAILURE;
+            }
+            failures
+        }
+        Action::RunAll(args) => {
+            let Some(failures) = in_process::run(&args) else { return ExitCode::FAILURE };
+            failures
+        }
+        Action::Report { names, category, format, output, params } => {
+            let entries = if names.is_empty() {
+                in_category(category.as_deref())
+            } else {
+                let Some(entries) = resolve(&names) else { return ExitCode::FAILURE };
+                entries
+            };
+            write_report(&entries, &params, format, output.as_deref())
+        }
+        #[cfg(feature = "history")]
+        Action::History(query) => history::show(&query),
+        #[cfg(feature = "tui")]
+        Action::Tui => match tui::run() {
+            Ok(()) => 0,
+            Err(err) => {
+                eprintln!("error: {}", err);
+                1
+            }
+        },
+    };
+    if failures == 0 { ExitCode::SUCCESS } else { ExitCode::FAILURE }
+}
@@ -0,0 +1,458 @@
+//! Interactive Explorer
+//!
+//! `systems-demos tui`: the demos listed by topic, a parameter bar, the
+//! selected demo's output streaming in as it runs, and a bar chart of what
+//! it measured (e.g. latency per working-set size, throughput per thread
+//! count) once it finishes. Adjust a parameter and run again to see how
+//! the numbers move.
+
+use std::io::{self, BufRead, BufReader, IsTerminal, Read};
+use std::path::PathBuf;
+use std::process::{Child, Stdio};
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Modifier, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Bar, BarChart, BarGroup, Block, List, ListItem, ListState, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+use systems_demos::catalog::{Entry, Param, CATEGORIES, DEMOS};
+use systems_demos::demo::{Config, DemoReport, Metric};
+use systems_demos::report;
+
+use super::{demo_command, report_path, take_report};
+
+const PARAMS: [Param; 4] = [Param::Size, Param::Threads, Param::Iterations, Param::Seed];
+const HELP: &str = "↑↓ select  ⏎ run  c cancel  tab parameter  +/- adjust  0 default  m metric  PgUp/PgDn scroll  q quit";
+
+/// A line of the demo list: a topic heading or a demo under it
+enum Row {
+    Category(&'static str),
+    Demo(&'static Entry),
+}
+
+/// The demo currently running, if any
+struct Running {
+    entry: &'static Entry,
+    child: Child,
+    report_path: PathBuf,
+    started: Instant,
+}
+
+/// The outcome of the last run, which the chart shows
+struct Finished {
+    entry: &'static Entry,
+    report: DemoReport,
+    succeeded: bool,
+}
+
+struct App {
+    rows: Vec<Row>,
+    list: ListState,
+    config: Config,
+    focus: usize,
+    running: Option<Running>,
+    output: Vec<String>,
+    lines: Option<Receiver<String>>,
+    /// First output line shown; `None` follows the end
+    scroll: Option<usize>,
+    output_height: usize,
+    finished: Option<Finished>,
+    metric: usize,
+    status: String,
+}
+
+/// Run the explorer until the user quits
+pub fn run() -> io::Result<()> {
+    if !io::stdout().is_terminal() {
+        return Err(io::Error::other("the explorer needs an interactive terminal"));
+    }
+    let mut terminal = ratatui::init();
+    let result = App::new().event_loop(&mut terminal);
+    ratatui::restore();
+    result
+}
+
+impl App {
+    fn new() -> App {
+        let mut rows = Vec::new();
+        for category in CATEGORIES {
+            rows.push(Row::Category(category));
+            rows.extend(DEMOS.iter().filter(|entry| entry.category == category).map(Row::Demo));
+        }
+        let mut list = ListState::default();
+        list.select(rows.iter().position(|row| matches!(row, Row::Demo(_))));
+        App {
+            rows,
+            list,
+            config: Config::default(),
+            focus: 0,
+            running: None,
+            output: Vec::new(),
+            lines: None,
+            scroll: None,
+            output_height: 0,
+            finished: None,
+            metric: 0,
+            status: "Pick a demo and press Enter".to_string(),
+        }
+    }
+
+    fn event_loop(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+        loop {
+            self.drain_output();
+            self.check_finished();
+            terminal.draw(|frame| self.draw(frame))?;
+            if !event::poll(Duration::from_millis(50))? {
+                continue;
+            }
+            let Event::Key(key) = event::read()? else { continue };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => break,
+                KeyCode::Up | KeyCode::Char('k') => self.move_selection(-1),
+                KeyCode::Down | KeyCode::Char('j') => self.move_selection(1),
+                KeyCode::Enter => self.start(),
+                KeyCode::Char('c') => self.cancel(),
+                KeyCode::Tab => self.focus = (self.focus + 1) % PARAMS.len(),
+                KeyCode::BackTab => self.focus = (self.focus + PARAMS.len() - 1) % PARAMS.len(),
+                KeyCode::Char('+') | KeyCode::Char('=') | KeyCode::Right => self.adjust(true),
+                KeyCode::Char('-') | KeyCode::Left => self.adjust(false),
+                KeyCode::Char('0') => self.set_param(PARAMS[self.focus], None),
+                KeyCode::Char('m') => self.metric += 1,
+                KeyCode::PageUp => self.scroll_by(-(self.output_height as isize)),
+                KeyCode::PageDown => self.scroll_by(self.output_height as isize),
+                KeyCode::Home => self.scroll = Some(0),
+                KeyCode::End => self.scroll = None,
+                _ => {}
+            }
+        }
+        self.cancel();
+        Ok(())
+    }
+
+    fn selected(&self) -> Option<&'static Entry> {
+        match self.rows.get(self.list.selected()?) {
+            Some(Row::Demo(entry)) => Some(entry),
+            _ => None,
+        }
+    }
+
+    /// Move to the next demo up or down, skipping the topic headings
+    fn move_selection(&mut self, step: isize) {
+        let Some(mut index) = self.list.selected() else { return };
+        loop {
+            let Some(next) = index.checked_add_signed(step).filter(|&next| next < self.rows.len()) else { return };
+            index = next;
+            if matches!(self.rows[index], Row::Demo(_)) {
+                self.list.select(Some(index));
+                return;
+            }
+        }
+    }
+
+    fn param(&self, param: Param) -> Option<u64> {
+        match param {
+            Param::Size => self.config.size.map(|v| v as u64),
+            Param::Threads => self.config.threads.map(|v| v as u64),
+            Param::Iterations => self.config.iterations.map(|v| v as u64),
+            Param::Seed => self.config.seed,
+        }
+    }
+
+    fn set_param(&mut self, param: Param, value: Option<u64>) {
+        let size = value.map(|v| v as usize);
+        match param {
+            Param::Size => self.config.size = size,
+            Param::Threads => self.config.threads = size,
+            Param::Iterations => self.config.iterations = size,
+            Param::Seed => self.config.seed = value,
+        }
+    }
+
+    /// Double or halve the focused parameter, or step the seed by one; the
+    /// first press leaves the demo's default for a typical starting value
+    fn adjust(&mut self, up: bool) {
+        let param = PARAMS[self.focus];
+        let value = match (self.param(param), up) {
+            _ if param == Param::Seed => {
+                let seed = self.config.seed();
+                if up { seed.wrapping_add(1) } else { seed.wrapping_sub(1) }
+            }
+            (None, _) => match param {
+                Param::Size => 1 << 16,
+                Param::Threads => thread::available_parallelism().map_or(1, |n| n.get() as u64),
+                _ => 1_000,
+            },
+            (Some(v), true) => v.saturating_mul(2),
+            (Some(v), false) => (v / 2).max(1),
+        };
+        self.set_param(param, Some(value));
+    }
+
+    fn scroll_by(&mut self, lines: isize) {
+        let bottom = self.output.len().saturating_sub(self.output_height);
+        let top = self.scroll.unwrap_or(bottom).saturating_add_signed(lines);
+        self.scroll = if top >= bottom { None } else { Some(top) };
+    }
+
+    fn start(&mut self) {
+        let Some(entry) = self.selected() else { return };
+        if self.running.is_some() {
+            self.status = "A demo is already running (c cancels it)".to_string();
+            return;
+        }
+        let report_path = report_path(entry);
+        let spawned = demo_command(entry, &self.config, &report_path)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn();
+        let mut child = match spawned {
+            Ok(child) => child,
+            Err(err) => {
+                self.status = format!("❌ couldn't start {}: {}", entry.name, err);
+                return;
+            }
+        };
+
+        // Both streams feed one channel, so stderr lines (and cargo's build
+        // output when there's no prebuilt binary) appear in order of arrival
+        let (sender, receiver) = mpsc::channel();
+        forward_lines(child.stdout.take(), sender.clone());
+        forward_lines(child.stderr.take(), sender);
+        self.lines = Some(receiver);
+        self.output.clear();
+        self.scroll = None;
+        self.status = if entry.release && cfg!(debug_assertions) {
+            format!("Running {}: timing-sensitive, run the runner with --release for meaningful numbers", entry.name)
+        } else {
+            format!("Running {}…", entry.name)
+        };
+        self.running = Some(Running { entry, child, report_path, started: Instant::now() });
+    }
+
+    fn cancel(&mut self) {
+        if let Some(mut running) = self.running.take() {
+            let _ = running.child.kill();
+            let _ = running.child.wait();
+            take_report(&running.report_path);
+            self.status = format!("Cancelled {}", running.entry.name);
+        }
+    }
+
+    fn drain_output(&mut self) {
+        let Some(lines) = &self.lines else { return };
+        loop {
+            match lines.try_recv() {
+                Ok(line) => self.output.push(line),
+                Err(TryRecvError::Empty) => return,
+                Err(TryRecvError::Disconnected) => {
+                    self.lines = None;
+                    return;
+                }
+            }
+        }
+    }
+
+    fn check_finished(&mut self) {
+        let Some(running) = &mut self.running else { return };
+        let status = match running.child.try_wait() {
+            Ok(Some(status)) => status,
+            Ok(None) => return,
+            Err(err) => {
+                self.status = format!("❌ lost track of {}: {}", running.entry.name, err);
+                self.running = None;
+                return;
+            }
+        };
+        let Some(running) = self.running.take() else { return };
+        let report = take_report(&running.report_path);
+        self.status = if status.success() {
+            format!("✅ {} finished in {:.1?}: {} metrics", running.entry.name, running.started.elapsed(),
+                    report.metrics.len())
+        } else {
+            format!("❌ {} exited with {}", running.entry.name, status)
+        };
+        self.finished = Some(Finished { entry: running.entry, report, succeeded: status.success() });
+        self.metric = 0;
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [body, footer] = Layout::vertical([Constraint::Min(0), Constraint::Length(2)]).areas(frame.area());
+        let [sidebar, main] = Layout::horizontal([Constraint::Length(30), Constraint::Min(0)]).areas(body);
+        let [header, output, chart] =
+            Layout::vertical([Constraint::Length(4), Constraint::Min(5), Constraint::Length(14)]).areas(main);
+
+        self.draw_list(frame, sidebar);
+        self.draw_header(frame, header);
+        self.draw_output(frame, output);
+        self.draw_chart(frame, chart);
+        frame.render_widget(Paragraph::new(vec![Line::from(self.status.as_str()), Line::from(HELP).dim()]), footer);
+    }
+
+    fn draw_list(&mut self, frame: &mut Frame, area: Rect) {
+        let items: Vec<ListItem> = self
+            .rows
+            .iter()
+            .map(|row| match row {
+                Row::Category(category) => ListItem::new(Line::from(*category).bold().underlined()),
+                Row::Demo(entry) => ListItem::new(format!("  {}", entry.name)),
+            })
+            .collect();
+        let list = List::new(items)
+            .block(Block::bordered().title(" Demos "))
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, area, &mut self.list);
+    }
+
+    fn draw_header(&self, frame: &mut Frame, area: Rect) {
+        let Some(entry) = self.selected() else { return };
+        let mut params = vec![Span::raw("Parameters: ")];
+        for (i, &param) in PARAMS.iter().enumerate() {
+            let value = self.param(param).map_or("default".to_string(), |v| v.to_string());
+            let text = format!("{} {}", param.flag(), value);
+            let mut style = Style::new();
+            if i == self.focus {
+                style = style.add_modifier(Modifier::REVERSED);
+            }
+            if !entry.accepts(param) {
+                style = style.add_modifier(Modifier::DIM | Modifier::CROSSED_OUT);
+            }
+            params.push(Span::styled(text, style));
+            params.push(Span::raw("   "));
+        }
+        let lines = vec![Line::from(entry.description), Line::from(params)];
+        let title = format!(" {} ({}) ", entry.name, entry.category);
+        frame.render_widget(Paragraph::new(lines).block(Block::bordered().title(title)), area);
+    }
+
+    fn draw_output(&mut self, frame: &mut Frame, area: Rect) {
+        self.output_height = area.height.saturating_sub(2) as usize;
+        let bottom = self.output.len().saturating_sub(self.output_height);
+        let top = self.scroll.unwrap_or(bottom).min(bottom);
+        let lines: Vec<Line> =
+            self.output[top..].iter().take(self.output_height).map(|line| Line::from(line.as_str())).collect();
+        let title = match &self.running {
+            Some(running) => format!(" Output: {} (running {:.0?}) ", running.entry.name, running.started.elapsed()),
+            None if self.scroll.is_some() => format!(" Output (line {} of {}) ", top + 1, self.output.len()),
+            None => " Output ".to_string(),
+        };
+        frame.render_widget(Paragraph::new(lines).block(Block::bordered().title(title)), area);
+    }
+
+    fn draw_chart(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::bordered();
+        let Some(finished) = &self.finished else {
+            let hint = Paragraph::new("Run a demo to chart what it measured").dim();
+            frame.render_widget(hint.block(block.title(" Metrics ")), area);
+            return;
+        };
+        let series = series(&finished.report.metrics);
+        if series.is_empty() {
+            let note = if finished.succeeded { "reported no metrics" } else { "failed before reporting metrics" };
+            let text = Paragraph::new(format!("{} {}", finished.entry.name, note)).dim();
+            frame.render_widget(text.block(block.title(" Metrics ")), area);
+            return;
+        }
+
+        let shown = &series[self.metric % series.len()];
+        let max = shown.metrics.iter().map(|metric| metric.value).fold(0.0, f64::max);
+        // Bars are integers: scale so the largest is 1000
+        let bars: Vec<Bar> = shown
+            .metrics
+            .iter()
+            .zip(&shown.labels)
+            .map(|(metric, label)| {
+                let scaled = if max > 0.0 { (metric.value / max * 1000.0).round() as u64 } else { 0 };
+                Bar::default()
+                    .value(scaled)
+                    .text_value(report::display_value(metric))
+                    .label(Line::from(label.as_str()))
+            })
+            .collect();
+        let title = format!(" {}: {} [{}/{}, m: next] ", finished.entry.name, shown.title,
+                            self.metric % series.len() + 1, series.len());
+        let chart = BarChart::default()
+            .block(block.title(title))
+            .direction(Direction::Horizontal)
+            .bar_width(1)
+            .bar_gap(0)
+            .value_style(Style::new().reversed())
+            .data(BarGroup::default().bars(&bars));
+        frame.render_widget(chart, area);
+    }
+}
+
+/// Read `stream` line by line on a thread of its own, sending each line
+/// until the stream closes or the receiver goes away
+fn forward_lines(stream: Option<impl Read + Send + 'static>, sender: Sender<String>) {
+    let Some(stream) = stream else { return };
+    thread::spawn(move || {
+        for line in BufReader::new(stream).lines() {
+            let Ok(line) = line else { break };
+            if sender.send(line).is_err() {
+                break;
+            }
+        }
+    });
+}
+
+/// One chart: metrics in the same unit, measured over the same parameters
+struct Series<'a> {
+    title: String,
+    metrics: Vec<&'a Metric>,
+    /// Bar labels: whichever of name and parameters differ between bars
+    labels: Vec<String>,
+}
+
+/// A unit and a set of parameter names: what makes two metrics comparable
+type ChartKey<'a> = (&'a str, Vec<&'a String>);
+
+/// Metrics grouped into charts, in the order the demo first recorded them.
+/// A sweep (`threads=1, 2, 4...`) and the variants it compares (padded vs
+/// unpadded) end up in one chart, since they share a unit and parameters.
+fn series(metrics: &[Metric]) -> Vec<Series<'_>> {
+    let mut groups: Vec<(ChartKey, Vec<&Metric>)> = Vec::new();
+    for metric in metrics {
+        let key = (metric.unit.as_str(), metric.params.keys().collect());
+        match groups.iter_mut().find(|(existing, _)| *existing == key) {
+            Some((_, group)) => group.push(metric),
+            None => groups.push((key, vec![metric])),
+        }
+    }
+
+    groups
+        .into_iter()
+        .map(|((unit, keys), metrics)| {
+            let varies = |value: &dyn Fn(&Metric) -> &str| metrics.iter().any(|m| value(m) != value(metrics[0]));
+            let names_vary = varies(&|m| m.name.as_str());
+            let varying_keys: Vec<&String> =
+                keys.into_iter().filter(|&key| varies(&|m| m.params[key].as_str())).collect();
+            let labels = metrics
+                .iter()
+                .map(|metric| {
+                    let mut parts: Vec<String> = varying_keys
+                        .iter()
+                        .map(|&key| format!("{}={}", key, metric.params[key]))
+                        .collect();
+                    if names_vary || parts.is_empty() {
+                        parts.insert(0, metric.name.clone());
+                    }
+                    parts.join(" ")
+                })
+                .collect();
+            let title = match (names_vary, unit) {
+                (false, _) => metrics[0].name.clone(),
+                (true, "ns") => "times".to_string(),
+                (true, unit) => unit.to_string(),
+            };
+            Series { title, metrics, labels }
+        })
+        .collect()
+}
@@ -0,0 +1,11 @@
+//! Timing Attack Demo
+//!
+//! The demo lives in `systems_demos::demos::timing_attack_demo`.
+//! Run with: cargo run --release --bin timing-attack-demo
+
+use systems_demos::demo;
+use systems_demos::demos::timing_attack_demo::TimingAttackDemo;
+
+fn main() {
+    demo::run_from_env(&TimingAttackDemo);
+}
@@ -0,0 +1,11 @@
+//! Toy CPU Emulator Demo
+//!
+//! The demo lives in `systems_demos::demos::toy_cpu`.
+//! Run with: cargo run --bin toy-cpu
+
+use systems_demos::demo;
+use systems_demos::demos::toy_cpu::ToyCpu;
+
+fn main() {
+    demo::run_from_env(&ToyCpu);
+}
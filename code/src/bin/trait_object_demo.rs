@@ -0,0 +1,11 @@
+//! Trait Object Internals Demo
+//!
+//! The demo lives in `systems_demos::demos::trait_object_demo`.
+//! Run with: cargo run --release --bin trait-object-demo
+
+use systems_demos::demo;
+use systems_demos::demos::trait_object_demo::TraitObjectDemo;
+
+fn main() {
+    demo::run_from_env(&TraitObjectDemo);
+}
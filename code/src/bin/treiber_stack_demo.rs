@@ -0,0 +1,11 @@
+//! Treiber Lock-Free Stack Demo
+//!
+//! The demo lives in `systems_demos::demos::treiber_stack_demo`.
+//! Run with: cargo run --release --bin treiber-stack-demo
+
+use systems_demos::demo;
+use systems_demos::demos::treiber_stack_demo::TreiberStackDemo;
+
+fn main() {
+    demo::run_from_env(&TreiberStackDemo);
+}
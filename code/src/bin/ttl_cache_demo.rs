@@ -0,0 +1,11 @@
+//! TTL Cache Demo
+//!
+//! The demo lives in `systems_demos::demos::ttl_cache_demo`.
+//! Run with: cargo run --bin ttl-cache-demo
+
+use systems_demos::demo;
+use systems_demos::demos::ttl_cache_demo::TtlCacheDemo;
+
+fn main() {
+    demo::run_from_env(&TtlCacheDemo);
+}
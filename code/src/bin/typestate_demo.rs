@@ -0,0 +1,11 @@
+//! PhantomData & Typestate Demo
+//!
+//! The demo lives in `systems_demos::demos::typestate_demo`.
+//! Run with: cargo run --bin typestate-demo
+
+use systems_demos::demo;
+use systems_demos::demos::typestate_demo::TypestateDemo;
+
+fn main() {
+    demo::run_from_env(&TypestateDemo);
+}
@@ -0,0 +1,11 @@
+//! UDP vs TCP Demo
+//!
+//! The demo lives in `systems_demos::demos::udp_tcp_demo`.
+//! Run with: cargo run --release --bin udp-tcp-demo
+
+use systems_demos::demo;
+use systems_demos::demos::udp_tcp_demo::UdpTcpDemo;
+
+fn main() {
+    demo::run_from_env(&UdpTcpDemo);
+}
@@ -0,0 +1,11 @@
+//! Weighted Cache Demo
+//!
+//! The demo lives in `systems_demos::demos::weighted_cache_demo`.
+//! Run with: cargo run --release --bin weighted-cache-demo
+
+use systems_demos::demo;
+use systems_demos::demos::weighted_cache_demo::WeightedCacheDemo;
+
+fn main() {
+    demo::run_from_env(&WeightedCacheDemo);
+}
@@ -0,0 +1,11 @@
+//! Write-Back Demo
+//!
+//! The demo lives in `systems_demos::demos::write_back_demo`.
+//! Run with: cargo run --release --bin write-back-demo
+
+use systems_demos::demo;
+use systems_demos::demos::write_back_demo::WriteBackDemo;
+
+fn main() {
+    demo::run_from_env(&WriteBackDemo);
+}
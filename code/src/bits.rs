@@ -0,0 +1,300 @@
+//! Bit-Level Formatting
+//!
+//! Binary with the fields of a value set apart, and the IEEE 754 view of
+//! `f32` and `f64`: a sign bit, a biased exponent and a mantissa (the
+//! fraction after an implicit leading 1). `Parts` splits a float into its
+//! fields and classifies it, `ulps` counts the representable values between
+//! two floats, and `exact_decimal` prints the number a float really holds,
+//! every digit of it: `0.1_f64` is
+//! 0.1000000000000000055511151231257827021181583404541015625.
+
+use std::fmt;
+
+/// `f32` and `f64`, described by their field widths
+pub trait Float: Copy + PartialOrd + fmt::Debug {
+    const BITS: u32;
+    const EXPONENT_BITS: u32;
+    /// Stored fraction bits; precision is one more, for the implicit 1
+    const MANTISSA_BITS: u32;
+
+    fn to_raw(self) -> u64;
+    fn from_raw(bits: u64) -> Self;
+    /// Exact: every `f32` is an `f64`
+    fn to_f64(self) -> f64;
+
+    fn bias() -> i32 {
+        (1 << (Self::EXPONENT_BITS - 1)) - 1
+    }
+}
+
+impl Float for f32 {
+    const BITS: u32 = 32;
+    const EXPONENT_BITS: u32 = 8;
+    const MANTISSA_BITS: u32 = 23;
+
+    fn to_raw(self) -> u64 {
+        self.to_bits() as u64
+    }
+
+    fn from_raw(bits: u64) -> Self {
+        f32::from_bits(bits as u32)
+    }
+
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+}
+
+impl Float for f64 {
+    const BITS: u32 = 64;
+    const EXPONENT_BITS: u32 = 11;
+    const MANTISSA_BITS: u32 = 52;
+
+    fn to_raw(self) -> u64 {
+        self.to_bits()
+    }
+
+    fn from_raw(bits: u64) -> Self {
+        f64::from_bits(bits)
+    }
+
+    fn to_f64(self) -> f64 {
+        self
+    }
+}
+
+/// What the exponent field says the other bits mean
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Class {
+    /// Exponent and mantissa all zeros (either sign)
+    Zero,
+    /// Exponent all zeros: no implicit 1, the smallest exponent, and
+    /// precision lost gradually towards zero
+    Subnormal,
+    Normal,
+    /// Exponent all ones, mantissa zero
+    Infinite,
+    /// Exponent all ones, mantissa not zero (the payload)
+    Nan,
+}
+
+impl fmt::Display for Class {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Class::Zero => "zero",
+            Class::Subnormal => "subnormal",
+            Class::Normal => "normal",
+            Class::Infinite => "infinite",
+            Class::Nan => "NaN",
+        };
+        f.pad(name)
+    }
+}
+
+/// A float's three fields
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Parts {
+    pub negative: bool,
+    /// The stored (biased) exponent
+    pub exponent: u64,
+    /// The stored fraction, without the implicit 1
+    pub mantissa: u64,
+    pub class: Class,
+    exponent_bits: u32,
+    mantissa_bits: u32,
+    bias: i32,
+}
+
+impl Parts {
+    pub fn of<F: Float>(x: F) -> Parts {
+        let bits = x.to_raw();
+        let mantissa = bits & ((1 << F::MANTISSA_BITS) - 1);
+        let exponent = (bits >> F::MANTISSA_BITS) & ((1 << F::EXPONENT_BITS) - 1);
+        let class = match (exponent, mantissa) {
+            (0, 0) => Class::Zero,
+            (0, _) => Class::Subnormal,
+            (e, 0) if e == (1 << F::EXPONENT_BITS) - 1 => Class::Infinite,
+            (e, _) if e == (1 << F::EXPONENT_BITS) - 1 => Class::Nan,
+            _ => Class::Normal,
+        };
+        Parts { negative: bits >> (F::BITS - 1) == 1, exponent, mantissa, class,
+                exponent_bits: F::EXPONENT_BITS, mantissa_bits: F::MANTISSA_BITS, bias: F::bias() }
+    }
+
+    /// The power of two the significand is scaled by, for finite nonzero
+    /// values: subnormals share the smallest normal exponent
+    pub fn unbiased_exponent(&self) -> Option<i32> {
+        match self.class {
+            Class::Normal => Some(self.exponent as i32 - self.bias),
+            Class::Subnormal => Some(1 - self.bias),
+            _ => None,
+        }
+    }
+
+    /// The significand as an integer, implicit 1 included for normal values:
+    /// the value is `significand * 2^(unbiased_exponent - mantissa bits)`
+    pub fn significand(&self) -> u64 {
+        match self.class {
+            Class::Normal => self.mantissa | 1 << self.mantissa_bits,
+            _ => self.mantissa,
+        }
+    }
+
+    /// Sign, exponent and mantissa in binary, separated by spaces
+    pub fn fields(&self) -> String {
+        format!("{} {} {}", self.negative as u8, binary(self.exponent, self.exponent_bits, 0),
+                binary(self.mantissa, self.mantissa_bits, 0))
+    }
+}
+
+impl fmt::Display for Parts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(&self.fields())
+    }
+}
+
+/// The low `width` bits of `value`, most significant first, with `_` every
+/// `group` bits counting from the right (0 for no grouping)
+pub fn binary(value: u64, width: u32, group: u32) -> String {
+    let mut text = String::with_capacity(width as usize * 2);
+    for bit in (0..width).rev() {
+        text.push(if value >> bit & 1 == 1 { '1' } else { '0' });
+        if group > 0 && bit > 0 && bit.is_multiple_of(group) {
+            text.push('_');
+        }
+    }
+    text
+}
+
+/// The float's bits as a signed integer that orders the same way the
+/// floats do, with -0.0 and 0.0 both at 0
+fn ordered<F: Float>(x: F) -> i128 {
+    let bits = x.to_raw();
+    let magnitude = (bits & !(1 << (F::BITS - 1))) as i128;
+    if bits >> (F::BITS - 1) == 1 { -magnitude } else { magnitude }
+}
+
+/// How many representable values apart `a` and `b` are: 0 for equal
+/// values, 1 for neighbours. Meaningless for NaN.
+pub fn ulps<F: Float>(a: F, b: F) -> u64 {
+    (ordered(a) - ordered(b)).unsigned_abs() as u64
+}
+
+/// The gap between `x` and the next float further from zero: the unit in
+/// the last place at `x`'s magnitude
+pub fn ulp<F: Float>(x: F) -> f64 {
+    let magnitude = x.to_raw() & !(1 << (F::BITS - 1));
+    F::from_raw(magnitude + 1).to_f64() - F::from_raw(magnitude).to_f64()
+}
+
+/// The exact value of a float in decimal, without rounding: every finite
+/// float is an integer times a power of two, so its decimal expansion ends
+pub fn exact_decimal<F: Float>(x: F) -> String {
+    let parts = Parts::of(x);
+    let sign = if parts.negative { "-" } else { "" };
+    let exponent = match parts.class {
+        Class::Nan => return "NaN".to_string(),
+        Class::Infinite => return format!("{}inf", sign),
+        Class::Zero => return format!("{}0", sign),
+        _ => parts.unbiased_exponent().expect("finite") - F::MANTISSA_BITS as i32,
+    };
+    // significand * 2^exponent; for a negative exponent, multiply by
+    // 5^-exponent instead and put the point -exponent digits from the end
+    let mut number = Decimal::from(parts.significand());
+    let multiplier = if exponent >= 0 { 2 } else { 5 };
+    for _ in 0..exponent.unsigned_abs() {
+        number.multiply(multiplier);
+    }
+    let digits = number.to_string();
+    if exponent >= 0 {
+        return format!("{}{}", sign, digits);
+    }
+    let point = exponent.unsigned_abs() as usize;
+    let digits = format!("{:0>width$}", digits, width = point + 1);
+    let (whole, fraction) = digits.split_at(digits.len() - point);
+    format!("{}{}.{}", sign, whole, fraction.trim_end_matches('0'))
+}
+
+/// An unsigned integer of any size, in base 10^9 limbs, least significant
+/// first: all `exact_decimal` needs is multiplying by small numbers
+struct Decimal(Vec<u32>);
+
+const LIMB: u64 = 1_000_000_000;
+
+impl Decimal {
+    fn from(value: u64) -> Decimal {
+        Decimal(vec![(value % LIMB) as u32, (value / LIMB % LIMB) as u32, (value / LIMB / LIMB) as u32])
+    }
+
+    fn multiply(&mut self, factor: u32) {
+        let mut carry = 0u64;
+        for limb in &mut self.0 {
+            let product = *limb as u64 * factor as u64 + carry;
+            *limb = (product % LIMB) as u32;
+            carry = product / LIMB;
+        }
+        if carry > 0 {
+            self.0.push(carry as u32);
+        }
+    }
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut limbs = self.0.iter().rev().skip_while(|&&limb| limb == 0);
+        match limbs.next() {
+            None => write!(f, "0"),
+            Some(first) => {
+                write!(f, "{}", first)?;
+                limbs.try_for_each(|limb| write!(f, "{:09}", limb))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_the_fields() {
+        let one = Parts::of(1.0f32);
+        assert_eq!((one.negative, one.exponent, one.mantissa, one.class), (false, 127, 0, Class::Normal));
+        assert_eq!(one.unbiased_exponent(), Some(0));
+        assert_eq!(Parts::of(-2.5f64).fields(),
+                   format!("1 10000000000 01{}", "0".repeat(50)));
+        assert_eq!(Parts::of(-0.0f64).class, Class::Zero);
+        assert!(Parts::of(-0.0f64).negative);
+        assert_eq!(Parts::of(f64::from_bits(1)).class, Class::Subnormal);
+        assert_eq!(Parts::of(f32::INFINITY).class, Class::Infinite);
+        assert_eq!(Parts::of(f64::NAN).class, Class::Nan);
+    }
+
+    #[test]
+    fn groups_binary() {
+        assert_eq!(binary(0xA5, 8, 4), "1010_0101");
+        assert_eq!(binary(5, 3, 0), "101");
+        assert_eq!(binary(1, 10, 4), "00_0000_0001");
+    }
+
+    #[test]
+    fn counts_ulps() {
+        assert_eq!(ulps(0.1 + 0.2, 0.3), 1);
+        assert_eq!(ulps(-0.0f32, 0.0), 0);
+        assert_eq!(ulps(-f64::from_bits(1), f64::from_bits(1)), 2);
+        assert_eq!(ulp(1.0f64), f64::EPSILON);
+        assert_eq!(ulp(1.0f32), f32::EPSILON as f64);
+        assert_eq!(ulp(16_777_216.0f32), 2.0);
+    }
+
+    #[test]
+    fn prints_exact_decimals() {
+        assert_eq!(exact_decimal(0.1f64), "0.1000000000000000055511151231257827021181583404541015625");
+        assert_eq!(exact_decimal(0.1f32), "0.100000001490116119384765625");
+        assert_eq!(exact_decimal(-2.5f64), "-2.5");
+        assert_eq!(exact_decimal(1e22f64), "10000000000000000000000");
+        assert_eq!(exact_decimal(f64::MAX).len(), 309);
+        assert!(exact_decimal(f64::from_bits(1)).starts_with("0.000000"));
+        assert!(exact_decimal(f64::from_bits(1)).ends_with("625"));
+    }
+}
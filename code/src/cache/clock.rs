@@ -0,0 +1,148 @@
+//! Clock (Second-Chance) Cache
+//!
+//! LRU reorders a list on every hit. An operating system can't: a hit is a
+//! memory access, done by the MMU without the kernel seeing it, and all the
+//! hardware leaves behind is the accessed bit in the page table entry. Clock
+//! is LRU approximated with that one bit. The frames sit in a circle; a hit
+//! sets the frame's reference bit, and nothing else. To evict, a hand sweeps
+//! the circle: a frame with its bit set loses the bit and is passed over (its
+//! second chance), and the first frame found without one goes. Hits are as
+//! cheap as they get, and the hand only moves on a miss.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+struct Frame<K, V> {
+    key: K,
+    value: V,
+    /// Used since the hand last passed
+    referenced: bool,
+}
+
+/// At most `capacity` entries in a circle of frames, evicting the first
+/// frame the hand finds unreferenced
+pub struct ClockCache<K, V> {
+    capacity: usize,
+    /// Each key's frame
+    map: HashMap<K, usize>,
+    frames: Vec<Frame<K, V>>,
+    /// The next frame to consider for eviction
+    hand: usize,
+}
+
+impl<K: Eq + Hash + Clone, V> ClockCache<K, V> {
+    /// An empty cache of `capacity` frames
+    pub fn new(capacity: usize) -> Self {
+        ClockCache { capacity, map: HashMap::with_capacity(capacity), frames: Vec::with_capacity(capacity), hand: 0 }
+    }
+
+    /// The value for `key`, setting its frame's reference bit
+    pub fn get(&mut self, key: &K) -> Option<&V> {
+        let frame = &mut self.frames[*self.map.get(key)?];
+        frame.referenced = true;
+        Some(&frame.value)
+    }
+
+    /// Insert or update `key` with its reference bit set; returns the entry
+    /// evicted to make room, if any
+    pub fn put(&mut self, key: K, value: V) -> Option<(K, V)> {
+        if let Some(&index) = self.map.get(&key) {
+            let frame = &mut self.frames[index];
+            frame.value = value;
+            frame.referenced = true;
+            return None;
+        }
+        if self.capacity == 0 {
+            return Some((key, value));
+        }
+        let frame = Frame { key: key.clone(), value, referenced: true };
+        if self.frames.len() < self.capacity {
+            self.map.insert(key, self.frames.len());
+            self.frames.push(frame);
+            return None;
+        }
+        let victim = self.advance();
+        let old = std::mem::replace(&mut self.frames[victim], frame);
+        self.map.remove(&old.key);
+        self.map.insert(key, victim);
+        Some((old.key, old.value))
+    }
+
+    /// Number of entries
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Most entries the cache holds before it starts evicting
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The frame the hand points at: the first the next eviction considers
+    pub fn hand(&self) -> usize {
+        self.hand
+    }
+
+    /// Each frame's key, value and reference bit, in frame order
+    pub fn frames(&self) -> impl Iterator<Item = (&K, &V, bool)> {
+        self.frames.iter().map(|frame| (&frame.key, &frame.value, frame.referenced))
+    }
+
+    /// Sweep to the first unreferenced frame, clearing bits on the way, and
+    /// leave the hand just past it. Ends within one full turn: by then every
+    /// bit it passed is clear.
+    fn advance(&mut self) -> usize {
+        loop {
+            let index = self.hand;
+            self.hand = (self.hand + 1) % self.frames.len();
+            let frame = &mut self.frames[index];
+            if !frame.referenced {
+                return index;
+            }
+            frame.referenced = false;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bits(cache: &ClockCache<u32, char>) -> Vec<(u32, bool)> {
+        cache.frames().map(|(&key, _, referenced)| (key, referenced)).collect()
+    }
+
+    #[test]
+    fn a_referenced_frame_gets_a_second_chance() {
+        let mut cache = ClockCache::new(3);
+        cache.put(1, 'a');
+        cache.put(2, 'b');
+        cache.put(3, 'c');
+        // Every bit set: the hand clears all three and comes back to frame 0
+        assert_eq!(cache.put(4, 'd'), Some((1, 'a')));
+        assert_eq!(bits(&cache), [(4, true), (2, false), (3, false)]);
+        assert_eq!(cache.hand(), 1);
+
+        cache.get(&2);
+        assert_eq!(cache.put(5, 'e'), Some((3, 'c')));
+        assert_eq!(bits(&cache), [(4, true), (2, false), (5, true)]);
+        assert_eq!(cache.hand(), 0);
+        assert_eq!(cache.get(&3), None);
+        assert_eq!(cache.get(&2), Some(&'b'));
+    }
+
+    #[test]
+    fn updates_keep_their_frame() {
+        let mut cache = ClockCache::new(2);
+        cache.put(1, 'a');
+        cache.put(2, 'b');
+        assert_eq!(cache.put(1, 'A'), None);
+        assert_eq!(cache.get(&1), Some(&'A'));
+        assert_eq!(cache.len(), 2);
+        assert_eq!(ClockCache::new(0).put(1, 'a'), Some((1, 'a')));
+    }
+}
@@ -0,0 +1,269 @@
+//! LFU Cache in O(1)
+//!
+//! Least-frequently-used eviction keeps the entries read most often, which
+//! a naive version finds by scanning every count or keeping a heap: O(n) or
+//! O(log n) per operation. The O(1) version (Shah, Mitra and Matani, 2010)
+//! groups entries into one list per use count instead. A hit moves an entry
+//! from the list for count f to the front of the list for f + 1, and the
+//! victim is the back of the lowest non-empty list, whose count the cache
+//! tracks as `min_count`: it only ever resets to 1 on an insert or moves up
+//! by one when a hit empties its list. Within a count, the least recently
+//! used entry goes first.
+//!
+//! Like [`SafeLruCache`](super::SafeLruCache), the entries sit in one `Vec`
+//! and link by index, so there is no `unsafe`.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Index meaning "no entry", the slab's null pointer
+const NIL: usize = usize::MAX;
+
+struct Entry<K, V> {
+    key: K,
+    value: V,
+    /// Uses so far, counting the insert
+    count: u64,
+    /// The entry with the same count used more recently, or `NIL`
+    prev: usize,
+    /// The entry with the same count used less recently, or `NIL`
+    next: usize,
+}
+
+/// The entries used exactly some number of times
+#[derive(Clone, Copy)]
+struct Bucket {
+    /// Most recently used
+    head: usize,
+    /// Least recently used
+    tail: usize,
+}
+
+/// At most `capacity` entries, evicting the least frequently used one when a
+/// new key would go over, and of those the least recently used
+pub struct LfuCache<K, V> {
+    capacity: usize,
+    /// Each key's index in `entries`
+    map: HashMap<K, usize>,
+    entries: Vec<Entry<K, V>>,
+    /// The non-empty lists, by use count
+    buckets: HashMap<u64, Bucket>,
+    /// The lowest count with a bucket; meaningless while empty
+    min_count: u64,
+}
+
+impl<K: Eq + Hash + Clone, V> LfuCache<K, V> {
+    /// An empty cache that holds at most `capacity` entries
+    pub fn new(capacity: usize) -> Self {
+        LfuCache {
+            capacity,
+            map: HashMap::new(),
+            entries: Vec::new(),
+            buckets: HashMap::new(),
+            min_count: 0,
+        }
+    }
+
+    /// The value for `key`, whose use count goes up by one
+    pub fn get(&mut self, key: &K) -> Option<&V> {
+        let index = *self.map.get(key)?;
+        self.bump(index);
+        Some(&self.entries[index].value)
+    }
+
+    /// Insert `key` with a count of one, or update it and count a use;
+    /// returns the entry evicted to make room, if any
+    pub fn put(&mut self, key: K, value: V) -> Option<(K, V)> {
+        if let Some(&index) = self.map.get(&key) {
+            self.entries[index].value = value;
+            self.bump(index);
+            return None;
+        }
+        if self.capacity == 0 {
+            return Some((key, value));
+        }
+        let entry = Entry { key: key.clone(), value, count: 1, prev: NIL, next: NIL };
+        let (index, evicted) = if self.entries.len() < self.capacity {
+            self.entries.push(entry);
+            (self.entries.len() - 1, None)
+        } else {
+            let victim = self.buckets[&self.min_count].tail;
+            self.unlink(victim);
+            let old = std::mem::replace(&mut self.entries[victim], entry);
+            self.map.remove(&old.key);
+            (victim, Some((old.key, old.value)))
+        };
+        self.map.insert(key, index);
+        self.push_front(index);
+        self.min_count = 1;
+        evicted
+    }
+
+    /// Take out the entry `put` would evict next: the least frequently used,
+    /// and of those the least recently used. O(1) unless it empties the
+    /// lowest count's list, when finding the next lowest visits every list.
+    pub fn pop_lfu(&mut self) -> Option<(K, V)> {
+        let victim = self.buckets.get(&self.min_count)?.tail;
+        self.unlink(victim);
+        let entry = self.entries.swap_remove(victim);
+        self.map.remove(&entry.key);
+        if victim < self.entries.len() {
+            self.relocated(victim);
+        }
+        if !self.buckets.contains_key(&self.min_count) {
+            self.min_count = self.buckets.keys().copied().min().unwrap_or(0);
+        }
+        Some((entry.key, entry.value))
+    }
+
+    /// How many times `key` has been used, counting its insert
+    pub fn count(&self, key: &K) -> Option<u64> {
+        self.map.get(key).map(|&index| self.entries[index].count)
+    }
+
+    /// Number of entries
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Most entries the cache holds before it starts evicting
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Move an entry up to the next count's list
+    fn bump(&mut self, index: usize) {
+        let count = self.entries[index].count;
+        self.unlink(index);
+        if count == self.min_count && !self.buckets.contains_key(&count) {
+            self.min_count += 1;
+        }
+        self.entries[index].count += 1;
+        self.push_front(index);
+    }
+
+    /// Take an entry out of its count's list, dropping the list if empty
+    fn unlink(&mut self, index: usize) {
+        let Entry { count, prev, next, .. } = self.entries[index];
+        let bucket = self.buckets.get_mut(&count).expect("linked entries have a bucket");
+        match prev {
+            NIL => bucket.head = next,
+            prev => self.entries[prev].next = next,
+        }
+        match next {
+            NIL => bucket.tail = prev,
+            next => self.entries[next].prev = prev,
+        }
+        if bucket.head == NIL {
+            self.buckets.remove(&count);
+        }
+    }
+
+    /// Point everything that linked to the last entry at `index`, where
+    /// `swap_remove` just moved it
+    fn relocated(&mut self, index: usize) {
+        let Entry { count, prev, next, .. } = self.entries[index];
+        let bucket = self.buckets.get_mut(&count).expect("linked entries have a bucket");
+        match prev {
+            NIL => bucket.head = index,
+            prev => self.entries[prev].next = index,
+        }
+        match next {
+            NIL => bucket.tail = index,
+            next => self.entries[next].prev = index,
+        }
+        *self.map.get_mut(&self.entries[index].key).expect("entries are in the map") = index;
+    }
+
+    /// Make an entry the most recent in its count's list
+    fn push_front(&mut self, index: usize) {
+        let count = self.entries[index].count;
+        let bucket = self.buckets.entry(count).or_insert(Bucket { head: NIL, tail: NIL });
+        let old_head = bucket.head;
+        bucket.head = index;
+        match old_head {
+            NIL => bucket.tail = index,
+            old_head => self.entries[old_head].prev = index,
+        }
+        let entry = &mut self.entries[index];
+        entry.prev = NIL;
+        entry.next = old_head;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Rng;
+
+    #[test]
+    fn evicts_the_least_frequently_used() {
+        let mut cache = LfuCache::new(3);
+        cache.put(1, 'a');
+        cache.put(2, 'b');
+        cache.put(3, 'c');
+        cache.get(&1);
+        cache.get(&1);
+        cache.get(&3);
+        assert_eq!(cache.count(&1), Some(3));
+
+        assert_eq!(cache.put(4, 'd'), Some((2, 'b')));
+        // 4 is new with a count of one: the next to go, however recent
+        assert_eq!(cache.put(5, 'e'), Some((4, 'd')));
+        cache.get(&5);
+        cache.put(3, 'C');
+        assert_eq!(cache.put(6, 'f'), Some((5, 'e')));
+        assert_eq!(cache.get(&3), Some(&'C'));
+        assert_eq!(cache.len(), 3);
+    }
+
+    #[test]
+    fn ties_go_to_the_least_recently_used() {
+        let mut cache = LfuCache::new(2);
+        cache.put(1, 'a');
+        cache.put(2, 'b');
+        cache.get(&1);
+        cache.get(&2);
+        assert_eq!(cache.put(3, 'c'), Some((1, 'a')));
+        assert_eq!(LfuCache::new(0).put(1, 'a'), Some((1, 'a')));
+    }
+
+    /// Against a scan for the lowest (count, last use) on every eviction and
+    /// pop
+    #[test]
+    fn agrees_with_a_linear_scan() {
+        let mut rng = Rng::new(3);
+        let mut cache = LfuCache::new(6);
+        // (key, count, last use)
+        let mut model: Vec<(usize, u64, usize)> = Vec::new();
+        for step in 0..20_000 {
+            let key = rng.below(12);
+            if rng.below(10) == 0 {
+                let victim = model.iter().enumerate().min_by_key(|(_, e)| (e.1, e.2)).map(|(i, _)| i);
+                assert_eq!(cache.pop_lfu().map(|(k, _)| k), victim.map(|i| model.remove(i).0));
+                assert_eq!(cache.len(), model.len());
+                continue;
+            }
+            match model.iter_mut().find(|entry| entry.0 == key) {
+                Some(entry) => {
+                    entry.1 += 1;
+                    entry.2 = step;
+                    assert_eq!(cache.get(&key), Some(&key));
+                }
+                None => {
+                    assert_eq!(cache.get(&key), None);
+                    let victim = (model.len() == 6).then(|| {
+                        let (i, _) = model.iter().enumerate().min_by_key(|(_, e)| (e.1, e.2)).unwrap();
+                        model.remove(i).0
+                    });
+                    model.push((key, 1, step));
+                    assert_eq!(cache.put(key, key).map(|(k, _)| k), victim);
+                }
+            }
+        }
+    }
+}
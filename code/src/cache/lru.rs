@@ -0,0 +1,404 @@
+//! LRU Cache
+//!
+//! The textbook least-recently-used cache: a `HashMap` finds an entry's node
+//! in O(1), and a doubly linked list of those nodes keeps them in order of
+//! use, so moving one to the front and evicting from the back are O(1) too.
+//!
+//! Each node is pointed at by the map and by both of its neighbours, so no
+//! one of them can own it as a `Box`. Instead the cache owns every node as a
+//! whole: a node is leaked with `Box::into_raw` when its key is inserted and
+//! rebuilt with `Box::from_raw` exactly once, when the entry is evicted or
+//! the cache is dropped. In between, the list only ever stores and follows
+//! raw pointers. `make miri` runs the tests below under Miri, which checks
+//! that no pointer outlives its node and no node is freed twice.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+use std::marker::PhantomData;
+use std::ptr;
+
+/// At most `capacity` entries, evicting the least recently used one when a
+/// new key would go over.
+pub struct LruCache<K, V> {
+    capacity: usize,
+    /// Every node in the list, by key
+    map: HashMap<K, *mut LruNode<K, V>>,
+    /// Most recently used, or null when empty
+    head: *mut LruNode<K, V>,
+    /// Least recently used: the next to go
+    tail: *mut LruNode<K, V>,
+}
+
+struct LruNode<K, V> {
+    key: K,
+    value: V,
+    /// The node used more recently than this one, or null
+    prev: *mut LruNode<K, V>,
+    /// The node used less recently than this one, or null
+    next: *mut LruNode<K, V>,
+}
+
+// SAFETY: the nodes are owned by the cache alone, like the elements of a
+// `Vec`, so sending the cache sends them and sharing it shares them
+unsafe impl<K: Send, V: Send> Send for LruCache<K, V> {}
+unsafe impl<K: Sync, V: Sync> Sync for LruCache<K, V> {}
+
+impl<K: Eq + Hash + Clone, V> LruCache<K, V> {
+    /// An empty cache that holds at most `capacity` entries
+    pub fn new(capacity: usize) -> Self {
+        LruCache {
+            capacity,
+            map: HashMap::new(),
+            head: ptr::null_mut(),
+            tail: ptr::null_mut(),
+        }
+    }
+
+    /// The value for `key`, which becomes the most recently used entry
+    pub fn get(&mut self, key: &K) -> Option<&V> {
+        let node = *self.map.get(key)?;
+        self.unlink(node);
+        self.push_front(node);
+        // SAFETY: nodes in the map are live, and the borrow of `self` keeps
+        // the entry from being evicted while the reference exists
+        Some(unsafe { &(*node).value })
+    }
+
+    /// The value for `key`, leaving the recency order alone
+    pub fn peek(&self, key: &K) -> Option<&V> {
+        let node = *self.map.get(key)?;
+        // SAFETY: nodes in the map are live
+        Some(unsafe { &(*node).value })
+    }
+
+    /// Whether `key` is cached, without counting as a use
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.map.contains_key(key)
+    }
+
+    /// Insert or update `key`, making it the most recently used entry;
+    /// returns the entry evicted to make room, if any
+    pub fn put(&mut self, key: K, value: V) -> Option<(K, V)> {
+        if let Some(&node) = self.map.get(&key) {
+            // SAFETY: nodes in the map are live
+            unsafe { (*node).value = value };
+            self.unlink(node);
+            self.push_front(node);
+            return None;
+        }
+        if self.capacity == 0 {
+            return Some((key, value));
+        }
+        let evicted = if self.map.len() == self.capacity { self.pop_lru() } else { None };
+        let node = Box::into_raw(Box::new(LruNode {
+            key: key.clone(),
+            value,
+            prev: ptr::null_mut(),
+            next: ptr::null_mut(),
+        }));
+        self.map.insert(key, node);
+        self.push_front(node);
+        evicted
+    }
+
+    /// Take `key` out of the cache, returning its value
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let node = self.map.remove(key)?;
+        self.unlink(node);
+        // SAFETY: the node came from `Box::into_raw` and is now in neither
+        // the list nor the map, so this is its only owner
+        Some(unsafe { Box::from_raw(node) }.value)
+    }
+
+    /// Take out the least recently used entry, the one `put` would evict next
+    pub fn pop_lru(&mut self) -> Option<(K, V)> {
+        if self.tail.is_null() {
+            return None;
+        }
+        let node = self.tail;
+        self.unlink(node);
+        // SAFETY: as in `remove`, once the key is out of the map below
+        let node = unsafe { Box::from_raw(node) };
+        self.map.remove(&node.key);
+        Some((node.key, node.value))
+    }
+
+    /// Number of entries
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Most entries the cache holds before it starts evicting
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Entries from most to least recently used, without counting as uses
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter { next: self.head, remaining: self.len(), cache: PhantomData }
+    }
+
+    /// Like [`iter`](Self::iter), with the values mutable
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        IterMut { next: self.head, remaining: self.len(), cache: PhantomData }
+    }
+
+    /// Detach `node` from its neighbours, leaving it out of the list but
+    /// still in the map
+    fn unlink(&mut self, node: *mut LruNode<K, V>) {
+        // SAFETY: `node` and its neighbours are live nodes of this cache
+        unsafe {
+            let LruNode { prev, next, .. } = *node;
+            match prev.is_null() {
+                true => self.head = next,
+                false => (*prev).next = next,
+            }
+            match next.is_null() {
+                true => self.tail = prev,
+                false => (*next).prev = prev,
+            }
+        }
+    }
+
+    /// Link a detached `node` in as the most recently used
+    fn push_front(&mut self, node: *mut LruNode<K, V>) {
+        // SAFETY: `node` is live and detached, and the head is live or null
+        unsafe {
+            (*node).prev = ptr::null_mut();
+            (*node).next = self.head;
+            match self.head.is_null() {
+                true => self.tail = node,
+                false => (*self.head).prev = node,
+            }
+        }
+        self.head = node;
+    }
+}
+
+impl<K: Eq + Hash + Clone + fmt::Debug, V: fmt::Debug> fmt::Debug for LruCache<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+/// Entries of an `LruCache`, most recently used first
+pub struct Iter<'a, K, V> {
+    next: *const LruNode<K, V>,
+    remaining: usize,
+    cache: PhantomData<&'a LruCache<K, V>>,
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next.is_null() {
+            return None;
+        }
+        // SAFETY: the shared borrow of the cache keeps every node alive and
+        // unchanged for 'a
+        let node = unsafe { &*self.next };
+        self.next = node.next;
+        self.remaining -= 1;
+        Some((&node.key, &node.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> {}
+
+/// Entries of an `LruCache` with mutable values, most recently used first
+pub struct IterMut<'a, K, V> {
+    next: *mut LruNode<K, V>,
+    remaining: usize,
+    cache: PhantomData<&'a mut LruCache<K, V>>,
+}
+
+impl<'a, K, V> Iterator for IterMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next.is_null() {
+            return None;
+        }
+        // SAFETY: the exclusive borrow of the cache keeps every node alive
+        // for 'a, and each node is visited once, so the `&mut V`s never alias
+        let node = unsafe { &mut *self.next };
+        self.next = node.next;
+        self.remaining -= 1;
+        Some((&node.key, &mut node.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<K, V> ExactSizeIterator for IterMut<'_, K, V> {}
+
+impl<K, V> Drop for LruCache<K, V> {
+    fn drop(&mut self) {
+        let mut node = self.head;
+        while !node.is_null() {
+            // SAFETY: each node in the list is freed once, here, after its
+            // successor has been read
+            let boxed = unsafe { Box::from_raw(node) };
+            node = boxed.next;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Rng;
+    use std::rc::Rc;
+
+    #[test]
+    fn evicts_the_least_recently_used() {
+        let mut cache = LruCache::new(3);
+        assert_eq!(cache.put(1, 'a'), None);
+        assert_eq!(cache.put(2, 'b'), None);
+        assert_eq!(cache.put(3, 'c'), None);
+        assert_eq!(cache.get(&1), Some(&'a'));
+
+        assert_eq!(cache.put(4, 'd'), Some((2, 'b')));
+        assert_eq!(cache.put(3, 'C'), None);
+        assert_eq!(cache.put(5, 'e'), Some((1, 'a')));
+        assert_eq!(cache.get(&2), None);
+        assert_eq!(cache.get(&3), Some(&'C'));
+        assert_eq!(cache.len(), 3);
+        assert_eq!(cache.capacity(), 3);
+    }
+
+    /// Empty the cache with `pop_lru`, least recently used first
+    fn drain(cache: &mut LruCache<u32, char>) -> Vec<u32> {
+        std::iter::from_fn(|| cache.pop_lru().map(|(key, _)| key)).collect()
+    }
+
+    #[test]
+    fn remove_relinks_head_middle_and_tail() {
+        let mut cache = LruCache::new(5);
+        for (key, value) in (1..=5).zip('a'..) {
+            cache.put(key, value);
+        }
+        assert_eq!(cache.remove(&5), Some('e'));
+        assert_eq!(cache.remove(&3), Some('c'));
+        assert_eq!(cache.remove(&1), Some('a'));
+        assert_eq!(cache.remove(&1), None);
+        assert_eq!(cache.len(), 2);
+
+        cache.put(6, 'f');
+        cache.get(&2);
+        assert_eq!(drain(&mut cache), [4, 6, 2]);
+        assert!(cache.is_empty());
+        assert_eq!(cache.pop_lru(), None);
+
+        cache.put(7, 'g');
+        assert_eq!(drain(&mut cache), [7]);
+    }
+
+    #[test]
+    fn peek_and_contains_key_leave_the_order_alone() {
+        let mut cache = LruCache::new(3);
+        for (key, value) in (1..=3).zip('a'..) {
+            cache.put(key, value);
+        }
+        assert_eq!(cache.peek(&1), Some(&'a'));
+        assert!(cache.contains_key(&2));
+        assert_eq!(cache.peek(&4), None);
+        assert_eq!(cache.put(4, 'd'), Some((1, 'a')));
+        assert!(!cache.contains_key(&1));
+        assert_eq!(drain(&mut cache), [2, 3, 4]);
+    }
+
+    #[test]
+    fn iterates_from_most_to_least_recent() {
+        let mut cache = LruCache::new(4);
+        for (key, value) in (1..=4).zip('a'..) {
+            cache.put(key, value);
+        }
+        cache.get(&2);
+        let keys: Vec<u32> = cache.iter().map(|(&key, _)| key).collect();
+        assert_eq!(keys, [2, 4, 3, 1]);
+        assert_eq!(cache.iter().len(), 4);
+        assert_eq!(format!("{:?}", cache), "{2: 'b', 4: 'd', 3: 'c', 1: 'a'}");
+
+        for (_, value) in cache.iter_mut() {
+            *value = value.to_ascii_uppercase();
+        }
+        assert_eq!(format!("{:?}", cache), "{2: 'B', 4: 'D', 3: 'C', 1: 'A'}");
+        assert_eq!(format!("{:?}", LruCache::<u32, char>::new(1)), "{}");
+    }
+
+    #[test]
+    fn zero_capacity_keeps_nothing() {
+        let mut cache = LruCache::new(0);
+        assert_eq!(cache.put(1, 'a'), Some((1, 'a')));
+        assert!(cache.is_empty());
+        assert_eq!(cache.get(&1), None);
+    }
+
+    #[test]
+    fn values_are_dropped_exactly_once() {
+        let value = Rc::new(());
+        {
+            let mut cache = LruCache::new(4);
+            for key in 0..10 {
+                cache.put(key % 6, Rc::clone(&value));
+            }
+            assert_eq!(Rc::strong_count(&value), 1 + 4);
+        }
+        assert_eq!(Rc::strong_count(&value), 1);
+    }
+
+    /// Random gets, puts, removes and pops against a `Vec` kept in recency
+    /// order, the workout that Miri checks every pointer of
+    #[test]
+    fn stress_against_a_model() {
+        let steps = if cfg!(miri) { 2_000 } else { 100_000 };
+        let mut rng = Rng::new(7);
+        for capacity in [1, 2, 5, 16] {
+            let mut cache = LruCache::new(capacity);
+            // Most recently used first
+            let mut model: Vec<(u8, u32)> = Vec::new();
+            for step in 0..steps {
+                let key = rng.below(capacity * 2) as u8;
+                let position = model.iter().position(|&(k, _)| k == key);
+                let op = rng.below(8);
+                if op == 0 {
+                    let expected = position.map(|i| model.remove(i).1);
+                    assert_eq!(cache.remove(&key), expected);
+                } else if op == 1 {
+                    assert_eq!(cache.pop_lru(), model.pop());
+                } else if op < 5 {
+                    let expected = position.map(|i| {
+                        let entry = model.remove(i);
+                        model.insert(0, entry);
+                        entry.1
+                    });
+                    assert_eq!(cache.get(&key).copied(), expected);
+                } else {
+                    if let Some(i) = position {
+                        model.remove(i);
+                    }
+                    model.insert(0, (key, step));
+                    let expected = if model.len() > capacity { model.pop() } else { None };
+                    assert_eq!(cache.put(key, step), expected);
+                }
+                assert_eq!(cache.len(), model.len());
+                if step % 64 == 0 {
+                    assert!(cache.iter().map(|(&k, &v)| (k, v)).eq(model.iter().copied()));
+                }
+            }
+        }
+    }
+}
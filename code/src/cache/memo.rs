@@ -0,0 +1,90 @@
+//! Memoization
+//!
+//! A pure function's result depends only on its argument, so a cache keyed
+//! by the argument can stand in for the call. `memoize` wraps a function in
+//! an [`LruCache`] of its results. A recursive function needs more: its
+//! calls to itself happen inside the body, out of the wrapper's reach, so
+//! `memoize_recursive` hands the body the memoized function to recurse
+//! through. Then every subproblem is solved once, which is top-down
+//! dynamic programming, and the capacity decides how many solved
+//! subproblems are remembered.
+
+use std::hash::Hash;
+
+use super::LruCache;
+
+/// `f` with the results of the last `capacity` distinct arguments cached
+pub fn memoize<A, R>(capacity: usize, mut f: impl FnMut(A) -> R) -> impl FnMut(A) -> R
+where
+    A: Eq + Hash + Clone,
+    R: Clone,
+{
+    let mut cache = LruCache::<A, R>::new(capacity);
+    move |arg| {
+        if let Some(result) = cache.get(&arg) {
+            return result.clone();
+        }
+        let result = f(arg.clone());
+        cache.put(arg, result.clone());
+        result
+    }
+}
+
+/// A recursive `f` whose recursive calls go through the cache too: `f`
+/// takes the memoized function as its first argument and calls that
+/// instead of itself
+pub fn memoize_recursive<A, R, F>(capacity: usize, f: F) -> impl FnMut(A) -> R
+where
+    A: Eq + Hash + Clone,
+    R: Clone,
+    F: Fn(&mut dyn FnMut(A) -> R, A) -> R,
+{
+    let mut cache = LruCache::new(capacity);
+    move |arg| call(&mut cache, &f, arg)
+}
+
+/// One call of the memoized function: the cached result, or `f`'s, cached
+fn call<A, R, F>(cache: &mut LruCache<A, R>, f: &F, arg: A) -> R
+where
+    A: Eq + Hash + Clone,
+    R: Clone,
+    F: Fn(&mut dyn FnMut(A) -> R, A) -> R,
+{
+    if let Some(result) = cache.get(&arg) {
+        return result.clone();
+    }
+    let result = f(&mut |arg| call(cache, f, arg), arg.clone());
+    cache.put(arg, result.clone());
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[test]
+    fn repeated_arguments_skip_the_call() {
+        let calls = Cell::new(0);
+        let mut square = memoize(2, |x: u64| {
+            calls.set(calls.get() + 1);
+            x * x
+        });
+        assert_eq!([square(3), square(3), square(4), square(5), square(3)], [9, 9, 16, 25, 9]);
+        // 3 was evicted by 4 and 5, so its last call ran again
+        assert_eq!(calls.get(), 4);
+    }
+
+    #[test]
+    fn recursion_goes_through_the_cache() {
+        let calls = Cell::new(0);
+        let mut fibonacci = memoize_recursive(100, |fib: &mut dyn FnMut(u64) -> u64, n: u64| {
+            calls.set(calls.get() + 1);
+            if n <= 1 { n } else { fib(n - 1) + fib(n - 2) }
+        });
+        assert_eq!(fibonacci(90), 2_880_067_194_370_816_120);
+        assert_eq!(calls.get(), 91);
+        assert_eq!(fibonacci(50), 12_586_269_025);
+        assert_eq!(calls.get(), 91);
+    }
+}
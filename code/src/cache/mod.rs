@@ -0,0 +1,30 @@
+//! Caches
+//!
+//! Bounded maps that decide for themselves what to forget. The demos build
+//! them from scratch to show what a cache costs and what it buys; they are
+//! public so any demo, or code outside this crate, can use them.
+
+pub mod clock;
+pub mod lfu;
+pub mod lru;
+pub mod memo;
+mod persist;
+pub mod policy;
+pub mod rcu;
+pub mod safe_lru;
+pub mod sharded;
+pub mod store;
+pub mod ttl;
+pub mod weighted;
+
+pub use clock::ClockCache;
+pub use lfu::LfuCache;
+pub use lru::LruCache;
+pub use memo::{memoize, memoize_recursive};
+pub use policy::{EvictionPolicy, FifoPolicy, LfuPolicy, LruPolicy, PolicyCache, RandomPolicy};
+pub use rcu::RcuCache;
+pub use safe_lru::SafeLruCache;
+pub use sharded::ShardedLruCache;
+pub use store::{BackingStore, CachedStore, SimulatedStore, WritePolicy};
+pub use ttl::TtlCache;
+pub use weighted::WeightedLruCache;
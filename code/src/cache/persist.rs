@@ -0,0 +1,180 @@
+//! Saving an LRU Cache
+//!
+//! A cache lives in memory, so a restarted process starts with an empty one
+//! and pays a miss for everything the last run had already fetched. Saving
+//! the entries at shutdown and loading them at startup lets the next run
+//! start warm. Order matters as much as contents: entries are written from
+//! least to most recently used, so putting them back in file order rebuilds
+//! the same recency list, and the entry next in line for eviction is the
+//! same one it was before the save.
+//!
+//! - File: `"LRU1" | capacity | count`, u64s little-endian, then each entry
+//!   as `key length | value length | key | value`, lengths little-endian
+//!   u32, keys and values as JSON.
+//! - Saving writes a temporary file beside the target, syncs it and renames
+//!   it over the target, so a crash mid-save leaves the old file or the new
+//!   one, never half of each.
+
+use std::ffi::OsString;
+use std::fs::{self, File};
+use std::hash::Hash;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use super::LruCache;
+
+/// The first bytes of every saved cache
+const MAGIC: &[u8; 4] = b"LRU1";
+
+impl<K: Eq + Hash + Clone, V> LruCache<K, V> {
+    /// Write the capacity and every entry, least recently used first
+    pub fn write_to(&self, mut out: impl Write) -> io::Result<()>
+    where
+        K: Serialize,
+        V: Serialize,
+    {
+        out.write_all(MAGIC)?;
+        out.write_all(&(self.capacity() as u64).to_le_bytes())?;
+        out.write_all(&(self.len() as u64).to_le_bytes())?;
+        let entries: Vec<(&K, &V)> = self.iter().collect();
+        for (key, value) in entries.into_iter().rev() {
+            let key = serde_json::to_vec(key)?;
+            let value = serde_json::to_vec(value)?;
+            out.write_all(&(key.len() as u32).to_le_bytes())?;
+            out.write_all(&(value.len() as u32).to_le_bytes())?;
+            out.write_all(&key)?;
+            out.write_all(&value)?;
+        }
+        out.flush()
+    }
+
+    /// A cache rebuilt from what [`write_to`](Self::write_to) wrote, with the
+    /// same capacity, entries and recency order
+    pub fn read_from(mut input: impl Read) -> io::Result<Self>
+    where
+        K: DeserializeOwned,
+        V: DeserializeOwned,
+    {
+        let mut magic = [0; 4];
+        input.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "not a saved LruCache"));
+        }
+        let capacity = read_u64(&mut input)? as usize;
+        let count = read_u64(&mut input)?;
+        let mut cache = LruCache::new(capacity);
+        let mut bytes = Vec::new();
+        for _ in 0..count {
+            let key_len = read_u32(&mut input)? as usize;
+            let value_len = read_u32(&mut input)? as usize;
+            // Read through `take`, so a damaged length can't allocate gigabytes
+            bytes.clear();
+            input.by_ref().take((key_len + value_len) as u64).read_to_end(&mut bytes)?;
+            if bytes.len() < key_len + value_len {
+                return Err(io::ErrorKind::UnexpectedEof.into());
+            }
+            let key = serde_json::from_slice(&bytes[..key_len])?;
+            let value = serde_json::from_slice(&bytes[key_len..])?;
+            cache.put(key, value);
+        }
+        Ok(cache)
+    }
+
+    /// Save the cache to `path`, replacing any older save in one rename
+    pub fn save_to(&self, path: impl AsRef<Path>) -> io::Result<()>
+    where
+        K: Serialize,
+        V: Serialize,
+    {
+        let path = path.as_ref();
+        let temporary = temporary_path(path);
+        let mut file = BufWriter::new(File::create(&temporary)?);
+        self.write_to(&mut file)?;
+        file.into_inner().map_err(io::IntoInnerError::into_error)?.sync_all()?;
+        fs::rename(&temporary, path)
+    }
+
+    /// The cache saved at `path` by [`save_to`](Self::save_to)
+    pub fn load_from(path: impl AsRef<Path>) -> io::Result<Self>
+    where
+        K: DeserializeOwned,
+        V: DeserializeOwned,
+    {
+        Self::read_from(BufReader::new(File::open(path)?))
+    }
+}
+
+/// `path` with `.tmp` added, in the same directory so the rename stays on
+/// one filesystem
+fn temporary_path(path: &Path) -> PathBuf {
+    let mut name = OsString::from(path.as_os_str());
+    name.push(".tmp");
+    PathBuf::from(name)
+}
+
+fn read_u64(input: &mut impl Read) -> io::Result<u64> {
+    let mut bytes = [0; 8];
+    input.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+fn read_u32(input: &mut impl Read) -> io::Result<u32> {
+    let mut bytes = [0; 4];
+    input.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scratch::Scratch;
+
+    fn entries(cache: &LruCache<u32, String>) -> Vec<(u32, String)> {
+        cache.iter().map(|(&key, value)| (key, value.clone())).collect()
+    }
+
+    #[test]
+    fn a_round_trip_keeps_recency() {
+        let mut cache = LruCache::new(3);
+        for key in 1..=3 {
+            cache.put(key, format!("value {}", key));
+        }
+        cache.get(&1);
+        let mut bytes = Vec::new();
+        cache.write_to(&mut bytes).unwrap();
+        let mut loaded: LruCache<u32, String> = LruCache::read_from(bytes.as_slice()).unwrap();
+        assert_eq!(entries(&loaded), entries(&cache));
+        assert_eq!(loaded.capacity(), 3);
+        // 2 was least recently used before the save, so it goes first after
+        assert_eq!(loaded.put(4, "value 4".to_string()).map(|(key, _)| key), Some(2));
+    }
+
+    #[test]
+    fn damaged_input_is_an_error() {
+        let mut cache = LruCache::new(2);
+        cache.put(1, "one".to_string());
+        let mut bytes = Vec::new();
+        cache.write_to(&mut bytes).unwrap();
+        let truncated = LruCache::<u32, String>::read_from(&bytes[..bytes.len() - 1]);
+        assert_eq!(truncated.err().map(|err| err.kind()), Some(io::ErrorKind::UnexpectedEof));
+        bytes[0] = b'X';
+        let garbage = LruCache::<u32, String>::read_from(bytes.as_slice());
+        assert_eq!(garbage.err().map(|err| err.kind()), Some(io::ErrorKind::InvalidData));
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "Miri isolates the filesystem")]
+    fn saves_to_and_loads_from_a_file() {
+        let scratch = Scratch::new("lru-persist-test", "save").unwrap();
+        let path = scratch.path("cache.lru");
+        let mut cache = LruCache::new(2);
+        cache.put(7, "seven".to_string());
+        cache.save_to(&path).unwrap();
+        let loaded: LruCache<u32, String> = LruCache::load_from(&path).unwrap();
+        assert_eq!(entries(&loaded), [(7, "seven".to_string())]);
+        assert!(!temporary_path(&path).exists());
+    }
+}
@@ -0,0 +1,289 @@
+//! Pluggable Eviction Policies
+//!
+//! Every cache in this module is a map plus a rule for what to forget, and
+//! the rule is the only part that differs. [`PolicyCache`] is the map, with
+//! the rule split out behind [`EvictionPolicy`]: the cache tells its policy
+//! about each insert and each hit, and asks it for a victim when full. The
+//! policy only ever sees keys, so one cache body serves them all, and a new
+//! policy is three methods. Evicting the most recently used key, which
+//! suits a loop one larger than the cache, looks like this:
+//!
+//! ```
+//! use systems_demos::cache::{EvictionPolicy, PolicyCache};
+//!
+//! #[derive(Default)]
+//! struct Mru(Option<u32>);
+//!
+//! impl EvictionPolicy<u32> for Mru {
+//!     fn on_insert(&mut self, key: &u32) {
+//!         self.0 = Some(*key);
+//!     }
+//!     fn on_access(&mut self, key: &u32) {
+//!         self.0 = Some(*key);
+//!     }
+//!     fn choose_victim(&mut self) -> Option<u32> {
+//!         self.0.take()
+//!     }
+//! }
+//!
+//! let mut cache = PolicyCache::new(2, Mru::default());
+//! cache.put(1, 'a');
+//! cache.put(2, 'b');
+//! assert_eq!(cache.put(3, 'c'), Some((2, 'b')));
+//! ```
+//!
+//! The policies here are built on the caches themselves: [`LruPolicy`] is an
+//! [`LruCache`] of keys with no values, [`LfuPolicy`] an [`LfuCache`] of keys.
+
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+
+use super::{LfuCache, LruCache};
+use crate::rng::Rng;
+
+/// Decides which key a full cache evicts, from the inserts and hits it is
+/// told about. The cache calls `choose_victim` only while it holds keys, and
+/// forgets the returned key; the policy should forget it too.
+pub trait EvictionPolicy<K> {
+    /// `key` has just been inserted
+    fn on_insert(&mut self, key: &K);
+
+    /// `key` was read or updated
+    fn on_access(&mut self, key: &K);
+
+    /// The key to evict, which leaves the cache
+    fn choose_victim(&mut self) -> Option<K>;
+}
+
+/// A map of at most `capacity` entries that lets `P` pick what to evict
+pub struct PolicyCache<K, V, P> {
+    capacity: usize,
+    map: HashMap<K, V>,
+    policy: P,
+}
+
+impl<K: Eq + Hash + Clone, V, P: EvictionPolicy<K>> PolicyCache<K, V, P> {
+    /// An empty cache of at most `capacity` entries, evicting as `policy` says
+    pub fn new(capacity: usize, policy: P) -> Self {
+        PolicyCache { capacity, map: HashMap::with_capacity(capacity), policy }
+    }
+
+    /// The value for `key`, reported to the policy as an access
+    pub fn get(&mut self, key: &K) -> Option<&V> {
+        let value = self.map.get(key)?;
+        self.policy.on_access(key);
+        Some(value)
+    }
+
+    /// Insert or update `key`; returns the entry evicted to make room, if any
+    pub fn put(&mut self, key: K, value: V) -> Option<(K, V)> {
+        if let Some(stored) = self.map.get_mut(&key) {
+            *stored = value;
+            self.policy.on_access(&key);
+            return None;
+        }
+        if self.capacity == 0 {
+            return Some((key, value));
+        }
+        let evicted = match self.map.len() == self.capacity {
+            true => {
+                let victim = self.policy.choose_victim().expect("a full cache's policy has a victim");
+                let value = self.map.remove(&victim).expect("the policy chose a cached key");
+                Some((victim, value))
+            }
+            false => None,
+        };
+        self.policy.on_insert(&key);
+        self.map.insert(key, value);
+        evicted
+    }
+
+    /// Number of entries
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Most entries the cache holds before it starts evicting
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn policy(&self) -> &P {
+        &self.policy
+    }
+}
+
+/// Evict the key unused for the longest
+pub struct LruPolicy<K> {
+    order: LruCache<K, ()>,
+}
+
+impl<K: Eq + Hash + Clone> Default for LruPolicy<K> {
+    fn default() -> Self {
+        LruPolicy { order: LruCache::new(usize::MAX) }
+    }
+}
+
+impl<K: Eq + Hash + Clone> EvictionPolicy<K> for LruPolicy<K> {
+    fn on_insert(&mut self, key: &K) {
+        self.order.put(key.clone(), ());
+    }
+
+    fn on_access(&mut self, key: &K) {
+        self.order.get(key);
+    }
+
+    fn choose_victim(&mut self) -> Option<K> {
+        self.order.pop_lru().map(|(key, ())| key)
+    }
+}
+
+/// Evict the key inserted longest ago, however often it is used
+pub struct FifoPolicy<K> {
+    queue: VecDeque<K>,
+}
+
+impl<K> Default for FifoPolicy<K> {
+    fn default() -> Self {
+        FifoPolicy { queue: VecDeque::new() }
+    }
+}
+
+impl<K: Clone> EvictionPolicy<K> for FifoPolicy<K> {
+    fn on_insert(&mut self, key: &K) {
+        self.queue.push_back(key.clone());
+    }
+
+    fn on_access(&mut self, _key: &K) {}
+
+    fn choose_victim(&mut self) -> Option<K> {
+        self.queue.pop_front()
+    }
+}
+
+/// Evict the key used least often, and of those the least recently used
+pub struct LfuPolicy<K> {
+    counts: LfuCache<K, ()>,
+}
+
+impl<K: Eq + Hash + Clone> Default for LfuPolicy<K> {
+    fn default() -> Self {
+        LfuPolicy { counts: LfuCache::new(usize::MAX) }
+    }
+}
+
+impl<K: Eq + Hash + Clone> EvictionPolicy<K> for LfuPolicy<K> {
+    fn on_insert(&mut self, key: &K) {
+        self.counts.put(key.clone(), ());
+    }
+
+    fn on_access(&mut self, key: &K) {
+        self.counts.get(key);
+    }
+
+    fn choose_victim(&mut self) -> Option<K> {
+        self.counts.pop_lfu().map(|(key, ())| key)
+    }
+}
+
+/// Evict any key, uniformly at random: no bookkeeping on a hit, and no
+/// access pattern that is its worst case
+pub struct RandomPolicy<K> {
+    keys: Vec<K>,
+    /// Each key's index in `keys`
+    index: HashMap<K, usize>,
+    rng: Rng,
+}
+
+impl<K> RandomPolicy<K> {
+    /// Victims drawn from a generator seeded with `seed`
+    pub fn new(seed: u64) -> Self {
+        RandomPolicy { keys: Vec::new(), index: HashMap::new(), rng: Rng::new(seed) }
+    }
+}
+
+impl<K: Eq + Hash + Clone> EvictionPolicy<K> for RandomPolicy<K> {
+    fn on_insert(&mut self, key: &K) {
+        self.index.insert(key.clone(), self.keys.len());
+        self.keys.push(key.clone());
+    }
+
+    fn on_access(&mut self, _key: &K) {}
+
+    fn choose_victim(&mut self) -> Option<K> {
+        if self.keys.is_empty() {
+            return None;
+        }
+        let slot = self.rng.below(self.keys.len());
+        let victim = self.keys.swap_remove(slot);
+        self.index.remove(&victim);
+        // The last key moved into the victim's slot
+        if let Some(moved) = self.keys.get(slot) {
+            self.index.insert(moved.clone(), slot);
+        }
+        Some(victim)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Keys evicted when 1, 2, 3 fill a cache of three, 1 is read twice and
+    /// 2 once, and 4, 5, 6 arrive
+    fn evictions<P: EvictionPolicy<u32>>(policy: P) -> Vec<u32> {
+        let mut cache = PolicyCache::new(3, policy);
+        for key in [1, 2, 3] {
+            cache.put(key, ());
+        }
+        cache.get(&1);
+        cache.get(&1);
+        cache.get(&2);
+        [4, 5, 6].into_iter().filter_map(|key| cache.put(key, ()).map(|(key, ())| key)).collect()
+    }
+
+    #[test]
+    fn each_policy_picks_its_own_victims() {
+        assert_eq!(evictions(LruPolicy::default()), [3, 1, 2]);
+        assert_eq!(evictions(FifoPolicy::default()), [1, 2, 3]);
+        assert_eq!(evictions(LfuPolicy::default()), [3, 4, 5]);
+        let mut random = evictions(RandomPolicy::new(1));
+        random.sort();
+        assert_eq!(random.len(), 3);
+        random.dedup();
+        assert_eq!(random.len(), 3);
+    }
+
+    #[test]
+    fn lru_policy_matches_lru_cache() {
+        let mut rng = Rng::new(9);
+        let mut pluggable = PolicyCache::new(8, LruPolicy::default());
+        let mut built_in = LruCache::new(8);
+        for step in 0..10_000 {
+            let key = rng.below(20);
+            match rng.below(2) {
+                0 => assert_eq!(pluggable.get(&key), built_in.get(&key)),
+                _ => assert_eq!(pluggable.put(key, step), built_in.put(key, step)),
+            }
+        }
+    }
+
+    #[test]
+    fn random_policy_keeps_its_index_straight() {
+        let mut cache = PolicyCache::new(16, RandomPolicy::new(4));
+        let mut rng = Rng::new(4);
+        for step in 0..10_000 {
+            let key = rng.below(40);
+            if let Some((victim, _)) = cache.put(key, step) {
+                assert_ne!(victim, key);
+            }
+            let policy = cache.policy();
+            assert_eq!(policy.keys.len(), cache.len());
+            assert!(policy.keys.iter().enumerate().all(|(i, key)| policy.index[key] == i));
+        }
+    }
+}
@@ -0,0 +1,211 @@
+//! Read-Mostly Cache
+//!
+//! [`LruCache`] and [`ShardedLruCache`](super::ShardedLruCache) make every
+//! read a write: a hit relinks the entry, so readers take a lock like
+//! writers do. `RcuCache` gives that up to keep reads off every lock. The
+//! map is an immutable snapshot in an [`RcuCell`]: a reader pins, looks the
+//! key up and clones the value, writing nothing the other threads read.
+//! A writer copies the whole map, changes the copy and publishes it, so a
+//! `put` costs O(n). That is the right trade only when reads far outnumber
+//! writes: configuration, routing tables, DNS answers.
+//!
+//! With no recency list, eviction is second chance (Clock without the
+//! ring): each entry carries a referenced bit a reader sets on its first
+//! hit, and a writer needing room walks the insertion queue, clearing set
+//! bits and sending those entries round again, until it finds one nobody
+//! read since its last pass. A reader only stores to the bit when it is
+//! clear, so a hot entry's cache line stays shared between cores.
+
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use crate::sync::RcuCell;
+
+/// A value and whether it was read since the last eviction pass. Shared by
+/// every snapshot that holds it, so a bit set in an old snapshot still
+/// counts.
+struct Entry<V> {
+    value: V,
+    referenced: AtomicBool,
+}
+
+/// At most `capacity` entries, read without locks and written by copying
+pub struct RcuCache<K, V> {
+    capacity: usize,
+    map: RcuCell<HashMap<K, Arc<Entry<V>>>>,
+    /// Keys in the order the eviction pass visits them. The lock also
+    /// serializes writers, so none copies a snapshot another is replacing.
+    queue: Mutex<VecDeque<K>>,
+}
+
+impl<K: Eq + Hash + Clone, V> RcuCache<K, V> {
+    /// An empty cache of at most `capacity` entries
+    pub fn new(capacity: usize) -> Self {
+        RcuCache { capacity, map: RcuCell::new(HashMap::new()), queue: Mutex::new(VecDeque::new()) }
+    }
+
+    /// A copy of the value for `key`, marking the entry referenced. Takes
+    /// no lock and never waits for a writer.
+    pub fn get(&self, key: &K) -> Option<V>
+    where
+        V: Clone,
+    {
+        let map = self.map.read();
+        let entry = map.get(key)?;
+        if !entry.referenced.load(Ordering::Relaxed) {
+            entry.referenced.store(true, Ordering::Relaxed);
+        }
+        Some(entry.value.clone())
+    }
+
+    /// Insert or update `key` by publishing a new snapshot; returns the key
+    /// evicted to make room, if any. Only the key: readers may still hold
+    /// the old snapshot, and the value with it.
+    ///
+    /// A new entry starts unreferenced, so it has to be read before it
+    /// earns a second chance.
+    pub fn put(&self, key: K, value: V) -> Option<K> {
+        if self.capacity == 0 {
+            return Some(key);
+        }
+        let mut queue = self.queue.lock().unwrap();
+        let mut map = self.map.read().clone();
+        let evicted = self.insert(&mut map, &mut queue, key, value);
+        self.map.store(map);
+        evicted
+    }
+
+    /// Put every entry with one copy of the map, publishing them together;
+    /// returns the keys evicted, in order. Batching is how an RCU structure
+    /// makes bulk writes affordable.
+    pub fn extend(&self, entries: impl IntoIterator<Item = (K, V)>) -> Vec<K> {
+        let mut queue = self.queue.lock().unwrap();
+        let mut map = self.map.read().clone();
+        let evicted = entries
+            .into_iter()
+            .filter_map(|(key, value)| match self.capacity {
+                0 => Some(key),
+                _ => self.insert(&mut map, &mut queue, key, value),
+            })
+            .collect();
+        self.map.store(map);
+        evicted
+    }
+
+    /// Insert into an unpublished copy, evicting if it grows past capacity
+    fn insert(&self, map: &mut HashMap<K, Arc<Entry<V>>>, queue: &mut VecDeque<K>, key: K, value: V) -> Option<K> {
+        let entry = Arc::new(Entry { value, referenced: AtomicBool::new(false) });
+        if map.insert(key.clone(), entry).is_some() {
+            return None;
+        }
+        let evicted = (map.len() > self.capacity).then(|| second_chance(map, queue));
+        queue.push_back(key);
+        evicted
+    }
+
+    /// Take `key` out of the cache; returns whether it was there
+    pub fn remove(&self, key: &K) -> bool {
+        let mut queue = self.queue.lock().unwrap();
+        let mut map = self.map.read().clone();
+        if map.remove(key).is_none() {
+            return false;
+        }
+        queue.retain(|queued| queued != key);
+        self.map.store(map);
+        true
+    }
+
+    /// Whether `key` is cached, without marking it referenced
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.map.read().contains_key(key)
+    }
+
+    /// Number of entries in the current snapshot
+    pub fn len(&self) -> usize {
+        self.map.read().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+}
+
+/// Walk the queue from the front, giving each referenced entry its second
+/// chance, and remove the first unreferenced one from `map`; returns its key
+fn second_chance<K: Eq + Hash, V>(map: &mut HashMap<K, Arc<Entry<V>>>, queue: &mut VecDeque<K>) -> K {
+    loop {
+        let key = queue.pop_front().expect("a full cache has keys queued");
+        if map[&key].referenced.swap(false, Ordering::Relaxed) {
+            queue.push_back(key);
+        } else {
+            map.remove(&key);
+            return key;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn a_read_buys_a_second_chance() {
+        let cache = RcuCache::new(2);
+        cache.put(1, "one");
+        cache.put(2, "two");
+        assert_eq!(cache.get(&1), Some("one"));
+        // 1 was read, so it goes round again and 2 is evicted
+        assert_eq!(cache.put(3, "three"), Some(2));
+        // 1's bit was cleared on that pass: now it goes
+        assert_eq!(cache.put(4, "four"), Some(1));
+        assert!(cache.contains_key(&3) && cache.contains_key(&4));
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn updates_and_removes_keep_the_queue_straight() {
+        let cache = RcuCache::new(2);
+        cache.put(1, 10);
+        assert_eq!(cache.put(1, 11), None);
+        assert_eq!(cache.get(&1), Some(11));
+        assert!(cache.remove(&1));
+        assert!(!cache.remove(&1));
+        cache.put(2, 20);
+        cache.put(3, 30);
+        assert_eq!(cache.put(4, 40), Some(2));
+        assert_eq!(RcuCache::new(0).put(1, 10), Some(1));
+        // Within one batch a key can be evicted and come back
+        assert_eq!(cache.extend([(5, 50), (3, 31), (6, 60)]), vec![3, 4, 5]);
+        assert_eq!(cache.get(&3), Some(31));
+    }
+
+    #[test]
+    fn readers_only_see_published_values() {
+        let cache = RcuCache::new(16);
+        let rounds = if cfg!(miri) { 40 } else { 1000 };
+        thread::scope(|scope| {
+            for _ in 0..3 {
+                scope.spawn(|| {
+                    for round in 0..rounds {
+                        let key = round % 32;
+                        if let Some(value) = cache.get(&key) {
+                            assert_eq!(value % 100, key);
+                        }
+                    }
+                });
+            }
+            for round in 0..rounds {
+                let key = round % 32;
+                cache.put(key, 100 * round + key);
+            }
+        });
+        assert_eq!(cache.len(), 16);
+    }
+}
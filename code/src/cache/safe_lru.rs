@@ -0,0 +1,168 @@
+//! LRU Cache Without `unsafe`
+//!
+//! [`LruCache`](super::LruCache) links heap nodes with raw pointers. Here the
+//! entries live in one `Vec` and link to each other by index, so the borrow
+//! checker sees a single owner and there is nothing to audit: a stale index
+//! can at worst name the wrong entry, never freed memory. The price is a
+//! bounds check on every hop, and the map stores a `usize` instead of a
+//! pointer. Once the cache is full an eviction reuses the evicted entry's
+//! slot, so the `Vec` never grows past `capacity`.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Index meaning "no entry", the slab's null pointer
+const NIL: usize = usize::MAX;
+
+struct Entry<K, V> {
+    key: K,
+    value: V,
+    /// The entry used more recently than this one, or `NIL`
+    prev: usize,
+    /// The entry used less recently than this one, or `NIL`
+    next: usize,
+}
+
+/// At most `capacity` entries, evicting the least recently used one when a
+/// new key would go over. The same API as [`LruCache`](super::LruCache).
+pub struct SafeLruCache<K, V> {
+    capacity: usize,
+    /// Each key's index in `entries`
+    map: HashMap<K, usize>,
+    entries: Vec<Entry<K, V>>,
+    /// Most recently used
+    head: usize,
+    /// Least recently used: the next to go
+    tail: usize,
+}
+
+impl<K: Eq + Hash + Clone, V> SafeLruCache<K, V> {
+    /// An empty cache that holds at most `capacity` entries
+    pub fn new(capacity: usize) -> Self {
+        SafeLruCache {
+            capacity,
+            map: HashMap::with_capacity(capacity),
+            entries: Vec::with_capacity(capacity),
+            head: NIL,
+            tail: NIL,
+        }
+    }
+
+    /// The value for `key`, which becomes the most recently used entry
+    pub fn get(&mut self, key: &K) -> Option<&V> {
+        let index = *self.map.get(key)?;
+        self.touch(index);
+        Some(&self.entries[index].value)
+    }
+
+    /// Insert or update `key`, making it the most recently used entry;
+    /// returns the entry evicted to make room, if any
+    pub fn put(&mut self, key: K, value: V) -> Option<(K, V)> {
+        if let Some(&index) = self.map.get(&key) {
+            self.entries[index].value = value;
+            self.touch(index);
+            return None;
+        }
+        if self.capacity == 0 {
+            return Some((key, value));
+        }
+        let entry = Entry { key: key.clone(), value, prev: NIL, next: NIL };
+        let (index, evicted) = if self.entries.len() < self.capacity {
+            self.entries.push(entry);
+            (self.entries.len() - 1, None)
+        } else {
+            let lru = self.tail;
+            self.unlink(lru);
+            let old = std::mem::replace(&mut self.entries[lru], entry);
+            self.map.remove(&old.key);
+            (lru, Some((old.key, old.value)))
+        };
+        self.map.insert(key, index);
+        self.push_front(index);
+        evicted
+    }
+
+    /// Number of entries
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Most entries the cache holds before it starts evicting
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    fn touch(&mut self, index: usize) {
+        if self.head != index {
+            self.unlink(index);
+            self.push_front(index);
+        }
+    }
+
+    fn unlink(&mut self, index: usize) {
+        let Entry { prev, next, .. } = self.entries[index];
+        match prev {
+            NIL => self.head = next,
+            prev => self.entries[prev].next = next,
+        }
+        match next {
+            NIL => self.tail = prev,
+            next => self.entries[next].prev = prev,
+        }
+    }
+
+    fn push_front(&mut self, index: usize) {
+        let old_head = self.head;
+        let entry = &mut self.entries[index];
+        entry.prev = NIL;
+        entry.next = old_head;
+        match old_head {
+            NIL => self.tail = index,
+            old_head => self.entries[old_head].prev = index,
+        }
+        self.head = index;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::LruCache;
+    use crate::rng::Rng;
+
+    #[test]
+    fn evicts_the_least_recently_used() {
+        let mut cache = SafeLruCache::new(3);
+        assert_eq!(cache.put(1, 'a'), None);
+        assert_eq!(cache.put(2, 'b'), None);
+        assert_eq!(cache.put(3, 'c'), None);
+        assert_eq!(cache.get(&1), Some(&'a'));
+
+        assert_eq!(cache.put(4, 'd'), Some((2, 'b')));
+        assert_eq!(cache.put(3, 'C'), None);
+        assert_eq!(cache.put(5, 'e'), Some((1, 'a')));
+        assert_eq!(cache.get(&2), None);
+        assert_eq!(cache.get(&3), Some(&'C'));
+        assert_eq!(cache.len(), 3);
+    }
+
+    #[test]
+    fn agrees_with_the_pointer_version() {
+        let mut rng = Rng::new(11);
+        let mut safe = SafeLruCache::new(8);
+        let mut pointers = LruCache::new(8);
+        for step in 0..10_000 {
+            let key = rng.below(20);
+            if rng.below(2) == 0 {
+                assert_eq!(safe.get(&key), pointers.get(&key));
+            } else {
+                assert_eq!(safe.put(key, step), pointers.put(key, step));
+            }
+        }
+        assert_eq!(SafeLruCache::<u8, u8>::new(0).put(1, 2), Some((1, 2)));
+    }
+}
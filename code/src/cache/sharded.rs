@@ -0,0 +1,161 @@
+//! Sharded LRU Cache
+//!
+//! An [`LruCache`] behind one `Mutex` serializes every thread, readers
+//! included: even a `get` moves the entry to the front of the list, so it
+//! needs the lock exclusively. `ShardedLruCache` hashes each key to one of
+//! N independent caches, each with its own lock, so threads working on
+//! different keys mostly take different locks.
+//!
+//! Two costs come with it. Eviction is per shard: the entry evicted is the
+//! least recently used of its shard, not of the whole cache, and a shard
+//! whose keys run hot evicts while another has room. And the shards sit in
+//! one array, so each is wrapped in [`CachePadded`]: unpadded, two
+//! neighbouring mutexes would share a cache line, and threads locking
+//! different shards would still bounce that line between their cores.
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hash};
+use std::sync::{Mutex, MutexGuard};
+
+use super::LruCache;
+use crate::sync::CachePadded;
+
+/// One slice of the keys with its own lock
+type Shard<K, V> = Mutex<LruCache<K, V>>;
+
+/// At most `capacity` entries split over independently locked LRU shards
+pub struct ShardedLruCache<K, V> {
+    shards: Box<[CachePadded<Shard<K, V>>]>,
+    hasher: RandomState,
+}
+
+impl<K: Eq + Hash + Clone, V> ShardedLruCache<K, V> {
+    /// An empty cache of `shards` shards holding `capacity` entries between
+    /// them, `capacity / shards` (rounded up) each. Panics if `shards` is 0.
+    pub fn new(capacity: usize, shards: usize) -> Self {
+        assert!(shards > 0, "a sharded cache needs at least one shard");
+        let per_shard = capacity.div_ceil(shards);
+        ShardedLruCache {
+            shards: (0..shards).map(|_| CachePadded::new(Mutex::new(LruCache::new(per_shard)))).collect(),
+            hasher: RandomState::new(),
+        }
+    }
+
+    /// A copy of the value for `key`, which becomes the most recently used
+    /// entry of its shard. A copy, since a reference can't outlive the lock.
+    pub fn get(&self, key: &K) -> Option<V>
+    where
+        V: Clone,
+    {
+        self.shard(key).get(key).cloned()
+    }
+
+    /// Insert or update `key`, making it the most recently used entry of its
+    /// shard; returns the entry that shard evicted to make room, if any
+    pub fn put(&self, key: K, value: V) -> Option<(K, V)> {
+        self.shard(&key).put(key, value)
+    }
+
+    /// Take `key` out of the cache, returning its value
+    pub fn remove(&self, key: &K) -> Option<V> {
+        self.shard(key).remove(key)
+    }
+
+    /// Whether `key` is cached, without touching the recency order
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.shard(key).contains_key(key)
+    }
+
+    /// Number of entries. Each shard is locked in turn, so with other threads
+    /// writing this is a snapshot of no single moment.
+    pub fn len(&self) -> usize {
+        self.shard_lens().into_iter().sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Entries the shards hold between them, which rounding may put a little
+    /// above the capacity asked for
+    pub fn capacity(&self) -> usize {
+        self.shards.iter().map(|shard| lock(shard).capacity()).sum()
+    }
+
+    /// Number of shards
+    pub fn shard_count(&self) -> usize {
+        self.shards.len()
+    }
+
+    /// Entries in each shard, to see how evenly the keys spread
+    pub fn shard_lens(&self) -> Vec<usize> {
+        self.shards.iter().map(|shard| lock(shard).len()).collect()
+    }
+
+    /// The locked shard `key` hashes to
+    fn shard(&self, key: &K) -> MutexGuard<'_, LruCache<K, V>> {
+        let index = self.hasher.hash_one(key) as usize % self.shards.len();
+        lock(&self.shards[index])
+    }
+}
+
+/// Lock a shard. A thread that panicked while holding it (in a key's `Hash`
+/// or `Eq`) poisoned it, and the shard may be mid-update, so that panic
+/// spreads.
+fn lock<K, V>(shard: &Shard<K, V>) -> MutexGuard<'_, LruCache<K, V>> {
+    shard.lock().expect("a thread panicked while holding this shard")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn one_shard_is_a_plain_lru() {
+        let cache = ShardedLruCache::new(2, 1);
+        cache.put(1, "one");
+        cache.put(2, "two");
+        assert_eq!(cache.get(&1), Some("one"));
+        assert_eq!(cache.put(3, "three"), Some((2, "two")));
+        assert!(!cache.contains_key(&2));
+        assert_eq!(cache.remove(&1), Some("one"));
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn shards_split_the_capacity() {
+        let cache = ShardedLruCache::new(10, 4);
+        assert_eq!(cache.shard_count(), 4);
+        assert_eq!(cache.capacity(), 12);
+        for key in 0..1000 {
+            cache.put(key, key);
+        }
+        assert!(cache.shard_lens().iter().all(|&len| len == 3));
+        assert_eq!(cache.len(), 12);
+    }
+
+    #[test]
+    fn threads_writing_disjoint_keys_lose_nothing() {
+        let threads = 4;
+        let per_thread = if cfg!(miri) { 20 } else { 1000 };
+        // Each shard could hold every key, so none evicts however they hash
+        let cache = Arc::new(ShardedLruCache::new(threads * per_thread * 8, 8));
+        let handles: Vec<_> = (0..threads)
+            .map(|t| {
+                let cache = Arc::clone(&cache);
+                thread::spawn(move || {
+                    for key in t * per_thread..(t + 1) * per_thread {
+                        assert_eq!(cache.put(key, key * 2), None);
+                    }
+                })
+            })
+            .collect();
+        handles.into_iter().for_each(|handle| handle.join().unwrap());
+        assert_eq!(cache.len(), threads * per_thread);
+        for key in 0..threads * per_thread {
+            assert_eq!(cache.get(&key), Some(key * 2));
+        }
+    }
+}
@@ -0,0 +1,221 @@
+//! Cache in Front of a Store
+//!
+//! A cache is usually a copy of something slower: a database, a disk, a
+//! service across the network. Reads are easy, a miss fetches from the
+//! store. Writes force a choice. Write-through sends every write on to the
+//! store before returning, so the store is always current and every write
+//! pays its latency. Write-back only marks the cached entry dirty and
+//! writes it when it is evicted or flushed: repeated writes to one key cost
+//! one store write, but until then the store is stale, and a crash loses
+//! whatever was dirty. CPU caches, page caches and disk controllers all
+//! make this choice; most make it write-back.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::thread;
+use std::time::Duration;
+
+use super::LruCache;
+
+/// The slow, authoritative copy a [`CachedStore`] sits in front of
+pub trait BackingStore<K, V> {
+    /// The stored value for `key`
+    fn read(&mut self, key: &K) -> Option<V>;
+
+    /// Store `value` for `key`, replacing any older value
+    fn write(&mut self, key: K, value: V);
+}
+
+/// A `HashMap` that sleeps for `latency` on every read and write, and
+/// counts them
+pub struct SimulatedStore<K, V> {
+    data: HashMap<K, V>,
+    latency: Duration,
+    reads: usize,
+    writes: usize,
+}
+
+impl<K: Eq + Hash, V> SimulatedStore<K, V> {
+    /// An empty store whose every access takes `latency`
+    pub fn new(latency: Duration) -> Self {
+        SimulatedStore { data: HashMap::new(), latency, reads: 0, writes: 0 }
+    }
+
+    /// The stored value for `key`, without the latency or the count: what a
+    /// check of the store's contents sees, not an access
+    pub fn peek(&self, key: &K) -> Option<&V> {
+        self.data.get(key)
+    }
+
+    /// Reads served so far
+    pub fn reads(&self) -> usize {
+        self.reads
+    }
+
+    /// Writes accepted so far
+    pub fn writes(&self) -> usize {
+        self.writes
+    }
+
+    fn wait(&self) {
+        if !self.latency.is_zero() {
+            thread::sleep(self.latency);
+        }
+    }
+}
+
+impl<K: Eq + Hash, V: Clone> BackingStore<K, V> for SimulatedStore<K, V> {
+    fn read(&mut self, key: &K) -> Option<V> {
+        self.wait();
+        self.reads += 1;
+        self.data.get(key).cloned()
+    }
+
+    fn write(&mut self, key: K, value: V) {
+        self.wait();
+        self.writes += 1;
+        self.data.insert(key, value);
+    }
+}
+
+/// When a [`CachedStore`] passes writes on to its store
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WritePolicy {
+    /// Every `put` writes the store before it returns
+    WriteThrough,
+    /// A `put` only dirties the cached entry; the store sees it on eviction
+    /// or `flush`
+    WriteBack,
+}
+
+/// A cached value and whether the store has it yet
+struct Slot<V> {
+    value: V,
+    dirty: bool,
+}
+
+/// An LRU cache of at most `capacity` entries in front of a store `S`
+pub struct CachedStore<K, V, S> {
+    cache: LruCache<K, Slot<V>>,
+    store: S,
+    policy: WritePolicy,
+}
+
+impl<K: Eq + Hash + Clone, V: Clone, S: BackingStore<K, V>> CachedStore<K, V, S> {
+    pub fn new(store: S, capacity: usize, policy: WritePolicy) -> Self {
+        CachedStore { cache: LruCache::new(capacity), store, policy }
+    }
+
+    /// The value for `key`, from the cache if it is there and from the store
+    /// otherwise; a value read from the store is cached clean
+    pub fn get(&mut self, key: &K) -> Option<V> {
+        if let Some(slot) = self.cache.get(key) {
+            return Some(slot.value.clone());
+        }
+        let value = self.store.read(key)?;
+        self.insert(key.clone(), Slot { value: value.clone(), dirty: false });
+        Some(value)
+    }
+
+    /// Set `key` to `value` in the cache and, under write-through, in the
+    /// store; under write-back the entry stays dirty until it leaves
+    pub fn put(&mut self, key: K, value: V) {
+        let dirty = match self.policy {
+            WritePolicy::WriteThrough => {
+                self.store.write(key.clone(), value.clone());
+                false
+            }
+            WritePolicy::WriteBack => true,
+        };
+        self.insert(key, Slot { value, dirty });
+    }
+
+    /// Cache `slot`, writing the evicted entry to the store if it is dirty
+    fn insert(&mut self, key: K, slot: Slot<V>) {
+        if let Some((key, evicted)) = self.cache.put(key, slot)
+            && evicted.dirty
+        {
+            self.store.write(key, evicted.value);
+        }
+    }
+
+    /// Write every dirty entry to the store, leaving them cached and clean;
+    /// returns how many were written
+    pub fn flush(&mut self) -> usize {
+        let mut written = 0;
+        for (key, slot) in self.cache.iter_mut() {
+            if slot.dirty {
+                self.store.write(key.clone(), slot.value.clone());
+                slot.dirty = false;
+                written += 1;
+            }
+        }
+        written
+    }
+
+    /// Cached entries the store hasn't seen yet: lost if the process dies now
+    pub fn dirty(&self) -> usize {
+        self.cache.iter().filter(|(_, slot)| slot.dirty).count()
+    }
+
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    pub fn policy(&self) -> WritePolicy {
+        self.policy
+    }
+
+    /// The store, as it stands: under write-back, without the dirty entries
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cached(policy: WritePolicy) -> CachedStore<u32, u32, SimulatedStore<u32, u32>> {
+        CachedStore::new(SimulatedStore::new(Duration::ZERO), 2, policy)
+    }
+
+    #[test]
+    fn write_through_keeps_the_store_current() {
+        let mut cache = cached(WritePolicy::WriteThrough);
+        for value in 0..3 {
+            cache.put(1, value);
+        }
+        assert_eq!(cache.store().writes(), 3);
+        assert_eq!(cache.store().peek(&1), Some(&2));
+        assert_eq!(cache.dirty(), 0);
+        assert_eq!(cache.get(&1), Some(2));
+        assert_eq!(cache.store().reads(), 0);
+    }
+
+    #[test]
+    fn write_back_coalesces_and_writes_on_eviction() {
+        let mut cache = cached(WritePolicy::WriteBack);
+        for value in 0..3 {
+            cache.put(1, value);
+        }
+        cache.put(2, 20);
+        assert_eq!(cache.store().writes(), 0);
+        assert_eq!(cache.store().peek(&1), None);
+        // 1 is the least recently used, so 3 pushes it, dirty, to the store
+        cache.put(3, 30);
+        assert_eq!(cache.store().writes(), 1);
+        assert_eq!(cache.store().peek(&1), Some(&2));
+        // A miss reads it back clean; evicting 2 writes only 2
+        assert_eq!(cache.get(&1), Some(2));
+        assert_eq!(cache.store().writes(), 2);
+        assert_eq!(cache.dirty(), 1);
+        assert_eq!(cache.flush(), 1);
+        assert_eq!(cache.store().peek(&3), Some(&30));
+        assert_eq!((cache.dirty(), cache.store().writes()), (0, 3));
+    }
+}
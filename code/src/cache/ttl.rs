@@ -0,0 +1,157 @@
+//! Cache With Expiring Entries
+//!
+//! An [`LruCache`] whose entries also go stale: each one is stamped with a
+//! deadline `ttl` after it was written. Nothing watches the clock. An expired
+//! entry is dropped when a `get` finds it, or by [`TtlCache::purge_expired`],
+//! which sweeps the whole cache; until then it takes up a slot like any
+//! other, and LRU eviction may push out a fresh entry while a stale one sits
+//! further up the list. That is the trade every lazy-expiry cache makes:
+//! no timer thread, at the price of dead entries holding capacity.
+
+use std::hash::Hash;
+use std::time::{Duration, Instant};
+
+use super::LruCache;
+
+struct Expiring<V> {
+    value: V,
+    deadline: Instant,
+}
+
+/// At most `capacity` entries, each readable for `ttl` after its last `put`
+pub struct TtlCache<K, V> {
+    entries: LruCache<K, Expiring<V>>,
+    ttl: Duration,
+}
+
+impl<K: Eq + Hash + Clone, V> TtlCache<K, V> {
+    /// An empty cache of at most `capacity` entries that expire `ttl` after
+    /// they are written
+    pub fn new(capacity: usize, ttl: Duration) -> Self {
+        TtlCache { entries: LruCache::new(capacity), ttl }
+    }
+
+    /// The value for `key` if it hasn't expired, which becomes the most
+    /// recently used entry. An expired entry is removed.
+    pub fn get(&mut self, key: &K) -> Option<&V> {
+        self.get_at(key, Instant::now())
+    }
+
+    /// Insert or update `key` with a fresh deadline, making it the most
+    /// recently used entry; returns the entry evicted to make room, expired
+    /// or not
+    pub fn put(&mut self, key: K, value: V) -> Option<(K, V)> {
+        self.put_at(key, value, Instant::now())
+    }
+
+    /// Take `key` out of the cache, returning its value even if expired
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        self.entries.remove(key).map(|entry| entry.value)
+    }
+
+    /// Remove every expired entry; returns how many there were
+    pub fn purge_expired(&mut self) -> usize {
+        self.purge_at(Instant::now())
+    }
+
+    /// Number of entries, counting expired ones not yet removed
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Most entries the cache holds before it starts evicting
+    pub fn capacity(&self) -> usize {
+        self.entries.capacity()
+    }
+
+    /// How long an entry stays readable after it is written
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Entries from most to least recently used with the time each has
+    /// left, zero once expired
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V, Duration)> {
+        let now = Instant::now();
+        self.entries.iter().map(move |(key, entry)| (key, &entry.value, entry.deadline.saturating_duration_since(now)))
+    }
+
+    fn get_at(&mut self, key: &K, now: Instant) -> Option<&V> {
+        if self.entries.peek(key)?.deadline <= now {
+            self.entries.remove(key);
+            return None;
+        }
+        self.entries.get(key).map(|entry| &entry.value)
+    }
+
+    fn put_at(&mut self, key: K, value: V, now: Instant) -> Option<(K, V)> {
+        let entry = Expiring { value, deadline: now + self.ttl };
+        self.entries.put(key, entry).map(|(key, entry)| (key, entry.value))
+    }
+
+    fn purge_at(&mut self, now: Instant) -> usize {
+        let expired: Vec<K> = self.entries.iter()
+            .filter(|(_, entry)| entry.deadline <= now)
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in &expired {
+            self.entries.remove(key);
+        }
+        expired.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TTL: Duration = Duration::from_secs(10);
+
+    fn after(start: Instant, secs: u64) -> Instant {
+        start + Duration::from_secs(secs)
+    }
+
+    #[test]
+    fn expires_lazily_on_get() {
+        let start = Instant::now();
+        let mut cache = TtlCache::new(4, TTL);
+        cache.put_at(1, 'a', start);
+        assert_eq!(cache.get_at(&1, after(start, 9)), Some(&'a'));
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.get_at(&1, after(start, 10)), None);
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn purge_removes_only_expired_entries() {
+        let start = Instant::now();
+        let mut cache = TtlCache::new(4, TTL);
+        cache.put_at(1, 'a', start);
+        cache.put_at(2, 'b', after(start, 5));
+        cache.put_at(3, 'c', start);
+        cache.put_at(1, 'A', after(start, 5));
+        assert_eq!(cache.purge_at(after(start, 12)), 1);
+        assert_eq!(cache.get_at(&1, after(start, 12)), Some(&'A'));
+        assert_eq!(cache.get_at(&2, after(start, 12)), Some(&'b'));
+        assert_eq!(cache.get_at(&3, after(start, 12)), None);
+    }
+
+    #[test]
+    fn a_stale_entry_holds_its_slot_until_purged() {
+        let start = Instant::now();
+        let mut cache = TtlCache::new(2, TTL);
+        cache.put_at(1, 'a', start);
+        cache.put_at(2, 'b', after(start, 8));
+        cache.get_at(&1, after(start, 9));
+        // 1 has expired, but it was used more recently, so fresh 2 goes
+        assert_eq!(cache.put_at(3, 'c', after(start, 11)), Some((2, 'b')));
+
+        assert_eq!(cache.purge_at(after(start, 11)), 1);
+        assert_eq!(cache.put_at(4, 'd', after(start, 12)), None);
+        assert_eq!(cache.len(), 2);
+    }
+}
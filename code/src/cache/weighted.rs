@@ -0,0 +1,130 @@
+//! Cache With a Weight Budget
+//!
+//! An [`LruCache`] counts entries, which only bounds memory if entries are
+//! all about the same size. An HTTP cache holds 200-byte redirects next to
+//! 2 MB images, so it counts bytes instead: each entry has a weight, given
+//! by a function of the key and value, and `put` evicts least recently used
+//! entries until the total fits the budget again. One large insert can push
+//! out dozens of small entries, and an entry heavier than the whole budget
+//! is never cached at all.
+
+use std::hash::Hash;
+
+use super::LruCache;
+
+struct Weighted<V> {
+    value: V,
+    weight: usize,
+}
+
+/// Entries whose weights, per `weigh`, add up to at most `budget`
+pub struct WeightedLruCache<K, V, F = fn(&K, &V) -> usize> {
+    entries: LruCache<K, Weighted<V>>,
+    budget: usize,
+    total: usize,
+    weigh: F,
+}
+
+impl<K: Eq + Hash + Clone, V, F: Fn(&K, &V) -> usize> WeightedLruCache<K, V, F> {
+    /// An empty cache holding at most `budget` worth of entries, each
+    /// weighed once by `weigh` when it is put
+    pub fn new(budget: usize, weigh: F) -> Self {
+        WeightedLruCache { entries: LruCache::new(usize::MAX), budget, total: 0, weigh }
+    }
+
+    /// The value for `key`, which becomes the most recently used entry
+    pub fn get(&mut self, key: &K) -> Option<&V> {
+        self.entries.get(key).map(|entry| &entry.value)
+    }
+
+    /// The value for `key` without counting as a use
+    pub fn peek(&self, key: &K) -> Option<&V> {
+        self.entries.peek(key).map(|entry| &entry.value)
+    }
+
+    /// Insert or update `key`, making it the most recently used entry, then
+    /// evict from the least recently used end until the total fits the
+    /// budget; returns the evicted entries, oldest first. An entry heavier
+    /// than the whole budget is not cached and comes straight back, and any
+    /// older value for its key is dropped.
+    pub fn put(&mut self, key: K, value: V) -> Vec<(K, V)> {
+        let weight = (self.weigh)(&key, &value);
+        self.remove(&key);
+        if weight > self.budget {
+            return vec![(key, value)];
+        }
+        let mut evicted = Vec::new();
+        while self.total + weight > self.budget {
+            let (key, entry) = self.entries.pop_lru().expect("an over-budget cache has entries");
+            self.total -= entry.weight;
+            evicted.push((key, entry.value));
+        }
+        self.total += weight;
+        self.entries.put(key, Weighted { value, weight });
+        evicted
+    }
+
+    /// Take `key` out of the cache, returning its value
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let entry = self.entries.remove(key)?;
+        self.total -= entry.weight;
+        Some(entry.value)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Combined weight of the entries, never more than the budget
+    pub fn weight(&self) -> usize {
+        self.total
+    }
+
+    /// Most combined weight the cache holds before it starts evicting
+    pub fn budget(&self) -> usize {
+        self.budget
+    }
+
+    /// Entries from most to least recently used with their weights
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V, usize)> {
+        self.entries.iter().map(|(key, entry)| (key, &entry.value, entry.weight))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evicts_until_the_budget_fits() {
+        let mut cache = WeightedLruCache::new(10, |_: &u32, value: &String| value.len());
+        for (key, size) in [(1, 3), (2, 3), (3, 3)] {
+            assert!(cache.put(key, "x".repeat(size)).is_empty());
+        }
+        cache.get(&1);
+        // 7 bytes: 2 and 3 must both go, 1 was used more recently
+        let evicted: Vec<u32> = cache.put(4, "y".repeat(7)).into_iter().map(|(key, _)| key).collect();
+        assert_eq!(evicted, [2, 3]);
+        assert_eq!(cache.weight(), 10);
+        assert_eq!(cache.iter().map(|(&key, _, weight)| (key, weight)).collect::<Vec<_>>(), [(4, 7), (1, 3)]);
+    }
+
+    #[test]
+    fn updates_reweigh_and_giants_are_refused() {
+        let mut cache = WeightedLruCache::new(10, |_: &u32, value: &String| value.len());
+        cache.put(1, "aaaa".to_string());
+        cache.put(2, "bb".to_string());
+        assert!(cache.put(1, "a".to_string()).is_empty());
+        assert_eq!(cache.weight(), 3);
+        let refused = cache.put(2, "z".repeat(11));
+        assert_eq!(refused, [(2, "z".repeat(11))]);
+        assert_eq!(cache.peek(&2), None);
+        assert_eq!(cache.weight(), 1);
+        assert_eq!(cache.remove(&1).as_deref(), Some("a"));
+        assert!(cache.is_empty() && cache.weight() == 0);
+    }
+}
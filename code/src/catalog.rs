@@ -0,0 +1,186 @@
+//! Demo Catalog
+//!
+//! Every demo binary in learning order, with the topic it belongs to and the
+//! runner parameters it understands. The `systems-demos` runner uses this to
+//! list demos and launch them by name; the categories match the Makefile
+//! targets.
+
+/// A parameter a demo reads from [`crate::demo::Config`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Param {
+    Size,
+    Threads,
+    Iterations,
+    Seed,
+}
+
+impl Param {
+    /// The runner's flag name, without the leading `--`
+    pub fn flag(self) -> &'static str {
+        match self {
+            Param::Size => "size",
+            Param::Threads => "threads",
+            Param::Iterations => "iterations",
+            Param::Seed => "seed",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Entry {
+    /// The `[[bin]]` name in Cargo.toml
+    pub name: &'static str,
+    pub category: &'static str,
+    pub description: &'static str,
+    /// Timing-sensitive: the numbers only mean something in a release build
+    pub release: bool,
+    pub params: &'static [Param],
+}
+
+impl Entry {
+    pub fn accepts(&self, param: Param) -> bool {
+        self.params.contains(&param)
+    }
+}
+
+/// Category names in learning order
+pub const CATEGORIES: [&str; 8] =
+    ["hardware", "memory", "compilation", "rust-features", "os", "concurrency", "networking", "advanced"];
+
+const fn entry(name: &'static str, category: &'static str, description: &'static str, release: bool, params: &'static [Param]) -> Entry {
+    Entry { name, category, description, release, params }
+}
+
+pub const DEMOS: &[Entry] = &[
+    entry("hardware-fundamentals", "hardware", "CPU registers, cache systems and hardware threads", false, &[Param::Seed]),
+    entry("cache-line-demo", "hardware", "Cache line size, false sharing and prefetching", false, &[]),
+    entry("register-demo", "hardware", "Register widths, byte access and registers vs cache lines", false, &[]),
+    entry("endianness-demo", "hardware", "Byte order in memory and on the wire", false, &[]),
+    entry("floating-point-demo", "hardware", "IEEE 754 bit by bit: fields, special values, ULPs and rounding", false, &[Param::Size]),
+    entry("toy-cpu", "hardware", "An 8-register CPU emulator tracing fetch-decode-execute", false, &[]),
+    #[cfg(feature = "gpu")]
+    entry("gpu-demo", "hardware", "A vector multiply-add on the CPU (scalar, SIMD, threads) and as a wgpu compute shader", true, &[Param::Size]),
+    entry("memory-management", "memory", "Virtual memory, stack vs heap, access patterns", false, &[Param::Seed]),
+    entry("memory-access-demo", "memory", "How variables are reached: stack layout, page tables, isolation", false, &[]),
+    entry("backtrace-demo", "memory", "Capture and symbolicate our own stack: frame pointers, unwinding, inlining", false, &[]),
+    entry("array-indexing-demo", "memory", "Indexing with usize, and where slices and Vecs keep their data", false, &[]),
+    entry("memcpy-demo", "memory", "copy_from_slice vs byte, u64, AVX2 and streaming copies from 16 B to 256 MiB", true, &[Param::Size]),
+    entry("sorting-demo", "memory", "Insertion, quick, merge, heap and radix sort from L1 to RAM; branchless partition", true, &[Param::Size, Param::Seed]),
+    entry("compilation-optimization", "compilation", "How LLVM optimizations affect generated code", false, &[]),
+    entry("optimization-demo", "compilation", "Constant folding, dead code elimination and other LLVM passes", false, &[]),
+    entry("optimization-levels-demo", "compilation", "The same code at different opt-levels", false, &[]),
+    entry("bytecode-vm-demo", "compilation", "A stack VM three ways: match, fn-pointer table, compiled closures", true, &[Param::Size, Param::Seed]),
+    entry("rust-language-features", "rust-features", "Ownership, borrowing, iterators and error handling", false, &[]),
+    entry("iterator-demo", "rust-features", "Iterators from loops to adapters and custom iterators", false, &[]),
+    entry("pointer-safety-demo", "rust-features", "Memory safety, raw pointers and strict provenance", false, &[]),
+    entry("trait-object-demo", "rust-features", "Fat pointers, vtables and the cost of dynamic dispatch", true, &[Param::Size, Param::Seed]),
+    entry("drop-order-demo", "rust-features", "When destructors run: locals, fields, temporaries, moves", false, &[]),
+    entry("ffi-demo", "rust-features", "Rust and C calling each other through the C ABI", false, &[]),
+    entry("macro-demo", "rust-features", "macro_rules! and a derive macro generating code", true, &[]),
+    entry("const-generics-demo", "rust-features", "A matrix whose dimensions are part of its type", true, &[]),
+    entry("string-layout-demo", "rust-features", "String, &str, Box<str> and Cow<str> in memory", true, &[]),
+    entry("typestate-demo", "rust-features", "PhantomData and protocol states in the type system", false, &[]),
+    entry("maybe-uninit-demo", "rust-features", "Sound handling of uninitialized memory", true, &[]),
+    entry("error-handling-demo", "rust-features", "Layered error enums, source chains, Result vs panic", true, &[]),
+    entry("closure-demo", "rust-features", "Closure captures, Fn traits and call costs", true, &[]),
+    entry("dst-demo", "rust-features", "Dynamically sized types and fat pointer metadata", false, &[]),
+    entry("send-sync-demo", "rust-features", "What may cross a thread boundary, and why", false, &[]),
+    entry("aliasing-demo", "rust-features", "What &mut uniqueness (noalias) buys the optimizer", true, &[]),
+    entry("monomorphization-demo", "rust-features", "Compile time and binary size of generic vs dyn code", true, &[]),
+    entry("match-demo", "rust-features", "Jump tables, comparison trees and HashMap dispatch", true, &[Param::Size, Param::Seed]),
+    entry("no-std-demo", "rust-features", "What survives without std: core-only LRU, ring buffer and const eval", true, &[]),
+    entry("operating-system-concepts", "os", "Processes, threads, scheduling and I/O", false, &[]),
+    entry("filesystem-demo", "os", "Inodes, hard links, symlinks, timestamps and blocks", false, &[]),
+    entry("page-cache-demo", "os", "The OS page cache: cold vs warm reads, mincore and readahead", true, &[Param::Size, Param::Seed]),
+    entry("clock-demo", "os", "Clock (second-chance) eviction stepped through as OS page replacement", false, &[Param::Size, Param::Seed]),
+    entry("binary-inspector-demo", "os", "Parse our own ELF executable: segments, sections, symbols", false, &[]),
+    entry("dynamic-linking-demo", "os", "dlopen, dlsym, and lazy binding through the PLT and GOT", true, &[]),
+    entry("atomics-ordering-demo", "concurrency", "Litmus tests under Relaxed, Acquire/Release and SeqCst", true, &[Param::Iterations]),
+    entry("treiber-stack-demo", "concurrency", "A lock-free stack, the ABA problem and epoch reclamation", true, &[]),
+    entry("mpmc-queue-demo", "concurrency", "A bounded lock-free MPMC queue vs Mutex and channels", true, &[]),
+    entry("lock-scaling-demo", "concurrency", "One shared counter under different locks, 1..N threads", true, &[Param::Threads, Param::Iterations]),
+    entry("spinlock-demo", "concurrency", "Spinlock variants vs std::sync::Mutex", true, &[]),
+    entry("condvar-demo", "concurrency", "A bounded buffer with Mutex and Condvar", true, &[]),
+    entry("model-checking-demo", "concurrency", "Stress testing vs model checking with loom", true, &[]),
+    entry("async-runtime-demo", "concurrency", "A from-scratch executor and epoll reactor", true, &[]),
+    entry("rcu-demo", "concurrency", "Read-copy-update for read-mostly data", true, &[]),
+    entry("futex-demo", "concurrency", "A mutex and an event on the raw futex syscall", true, &[]),
+    entry("amdahl-demo", "concurrency", "Measured speedup vs Amdahl's law", true, &[]),
+    entry("io-bound-demo", "concurrency", "Async vs thread pools for I/O-bound work", true, &[]),
+    entry("scoped-threads-demo", "concurrency", "Scoped threads and rayon for parallel map-reduce", true, &[Param::Size, Param::Threads]),
+    entry("network-demo", "networking", "Loopback TCP echo: round-trip latency, syscalls and copies", true, &[Param::Iterations]),
+    entry("udp-tcp-demo", "networking", "UDP vs TCP: latency, throughput, loss and backpressure", true, &[Param::Size, Param::Iterations]),
+    entry("nagle-demo", "networking", "Nagle's algorithm, delayed ACKs and TCP_NODELAY", true, &[Param::Iterations]),
+    entry("http-server-demo", "networking", "A minimal HTTP/1.1 server: threads vs an epoll event loop", true, &[Param::Threads, Param::Iterations]),
+    entry("dns-demo", "networking", "DNS by hand: the wire format, a query over UDP, and getaddrinfo", true, &[Param::Iterations]),
+    entry("socket-buffers-demo", "networking", "Socket buffer sizes, WouldBlock and backpressure", true, &[Param::Size]),
+    entry("reliable-udp-demo", "networking", "A tiny TCP over UDP: sequence numbers, ACKs, retransmission and a window", true, &[Param::Size, Param::Seed]),
+    entry("kv-store-demo", "advanced", "A log-structured key-value store: fsync, recovery and compaction", true, &[Param::Size, Param::Seed]),
+    entry("btree-demo", "advanced", "An on-disk B-tree: pages, fan-out and a page cache", true, &[Param::Size, Param::Seed]),
+    entry("lru-implementation", "advanced", "An LRU cache built from scratch, with raw pointers and with an index slab", true, &[Param::Size, Param::Seed]),
+    entry("lru-benchmark", "advanced", "LruCache and SafeLruCache vs the lru crate vs a HashMap: speed and bytes per entry", true, &[Param::Size, Param::Seed]),
+    entry("ttl-cache-demo", "advanced", "Entries that expire: lazy TTL expiry meets LRU eviction", false, &[]),
+    entry("lru-vs-lfu-demo", "advanced", "LRU, an O(1) LFU, FIFO and Random as pluggable policies on looping, scanning, Zipf, uniform and shifting traces", true, &[Param::Size, Param::Seed]),
+    entry("weighted-cache-demo", "advanced", "A byte-budget WeightedLruCache vs entry-count LRUs on HTTP responses from 100 B to 300 KB", true, &[Param::Size, Param::Seed]),
+    entry("write-back-demo", "advanced", "Write-through vs write-back caching over a slow store: request latency and store writes per put", true, &[Param::Size, Param::Seed]),
+    entry("memoize-demo", "advanced", "memoize and memoize_recursive on the LRU cache: recursive Fibonacci from exponential to linear", true, &[]),
+    entry("persistent-cache-demo", "advanced", "Save an LruCache with its recency order, and restart warm instead of cold", true, &[Param::Size, Param::Seed]),
+    entry("sharded-cache-demo", "advanced", "One Mutex<LruCache> vs a cache-padded ShardedLruCache under 8 threads", true, &[Param::Threads, Param::Iterations, Param::Seed]),
+    entry("rcu-cache-demo", "advanced", "Lock-free snapshot reads vs locked caches at 1, 4 and 16 readers, and what each write costs", true, &[Param::Iterations]),
+    entry("prng-demo", "advanced", "LCG, xorshift64* and PCG32 from scratch: bit patterns, tests and throughput", true, &[Param::Size, Param::Seed]),
+    entry("timing-attack-demo", "advanced", "Recover a secret from an early-exit comparison's timing; constant-time code", true, &[Param::Size, Param::Seed]),
+    entry("substring-search-demo", "advanced", "Naive, memchr, Horspool and Two-Way substring search on rare, frequent and worst-case needles", true, &[Param::Size, Param::Seed]),
+    entry("hashdos-demo", "advanced", "SipHash vs FxHash vs FNV, and a HashMap flooded with colliding keys", true, &[Param::Size, Param::Seed]),
+];
+
+/// The demo called `name`, if there is one
+pub fn find(name: &str) -> Option<&'static Entry> {
+    DEMOS.iter().find(|entry| entry.name == name)
+}
+
+/// Demo names containing `fragment`, for "did you mean" suggestions
+pub fn suggest(fragment: &str) -> Vec<&'static str> {
+    DEMOS.iter().map(|entry| entry.name).filter(|name| name.contains(fragment)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::demos;
+
+    #[test]
+    fn every_demo_is_cataloged_with_its_description() {
+        for demo in demos::all() {
+            let entry = find(demo.name()).unwrap_or_else(|| panic!("{} is missing from the catalog", demo.name()));
+            assert_eq!(entry.description, demo.description(), "{}", demo.name());
+        }
+    }
+
+    #[test]
+    fn every_demo_has_a_well_formed_quiz() {
+        for demo in demos::all() {
+            assert!(!demo.quiz().is_empty(), "{} has no quiz", demo.name());
+            for question in demo.quiz() {
+                assert!(question.choices.len() >= 2, "{}: {}", demo.name(), question.prompt);
+                assert!(question.answer < question.choices.len(), "{}: {}", demo.name(), question.prompt);
+            }
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn catalog_and_demos_share_learning_order() {
+        let names: Vec<_> = demos::all().iter().map(|demo| demo.name()).collect();
+        let cataloged: Vec<_> = DEMOS.iter().map(|entry| entry.name).collect();
+        assert_eq!(names, cataloged);
+    }
+
+    #[test]
+    fn categories_appear_in_order() {
+        let positions: Vec<_> = DEMOS
+            .iter()
+            .map(|entry| CATEGORIES.iter().position(|&c| c == entry.category).expect("unknown category"))
+            .collect();
+        assert!(positions.is_sorted(), "catalog entries must be grouped by category in learning order");
+    }
+}
@@ -0,0 +1,178 @@
+//! The Demo Trait
+//!
+//! A common interface for demos, so they can be listed and run uniformly
+//! instead of only as separate binaries. Implement it by hand or with
+//! `#[derive(Demo)]` (from the `demo-derive` sub-crate), which reads the name,
+//! description and quiz from a `#[demo(...)]` attribute and forwards `run` to
+//! the type's `demonstrate(&self, &Config) -> DemoReport` method.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+use std::{fs, io};
+
+use serde::{Deserialize, Serialize};
+
+use crate::bench::Measurement;
+use crate::envinfo::{self, Environment};
+use crate::{catalog, output};
+use crate::quiz::{self, Question};
+use crate::rng::{self, Rng};
+use crate::verify::Verification;
+
+pub use demo_derive::Demo;
+
+pub trait Demo {
+    /// Short kebab-case identifier, e.g. `cache-line-demo`
+    fn name(&self) -> &'static str;
+
+    /// One-line summary of what the demo shows
+    fn description(&self) -> &'static str;
+
+    /// Print the demo's sections, using `config` where the demo takes
+    /// parameters, and return what it measured
+    fn run(&self, config: &Config) -> DemoReport;
+
+    /// Questions about what the demo showed, asked with `--quiz`
+    fn quiz(&self) -> &'static [Question] {
+        &[]
+    }
+
+    /// Re-check the demo's central claims without printing, for
+    /// `tests/verify.rs`. Demos with nothing to check return no checks.
+    fn verify(&self) -> Verification {
+        Verification::new()
+    }
+}
+
+/// What a demo run measured, alongside what it printed. Demos without
+/// timed results return an empty report.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DemoReport {
+    pub metrics: Vec<Metric>,
+}
+
+/// One named measurement, e.g. `("Mutex", 12.5e6, "ops/s")` with the
+/// parameter `threads = 4`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Metric {
+    pub name: String,
+    pub value: f64,
+    pub unit: String,
+    /// What the measurement was taken with, beyond the runner's `Config`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub params: BTreeMap<String, String>,
+}
+
+impl Metric {
+    /// Attach a parameter, e.g. `.param("threads", 4)`
+    pub fn param(&mut self, key: impl Into<String>, value: impl ToString) -> &mut Metric {
+        self.params.insert(key.into(), value.to_string());
+        self
+    }
+}
+
+impl DemoReport {
+    /// The environment variable naming the file `run_from_env` writes the
+    /// report to, as JSON
+    pub const PATH_VAR: &'static str = "SYSTEMS_DEMOS_REPORT";
+
+    pub fn record(&mut self, name: impl Into<String>, value: f64, unit: impl Into<String>) -> &mut Metric {
+        self.metrics.push(Metric { name: name.into(), value, unit: unit.into(), params: BTreeMap::new() });
+        self.metrics.last_mut().unwrap()
+    }
+
+    /// Record a duration, in nanoseconds
+    pub fn record_time(&mut self, name: impl Into<String>, duration: Duration) -> &mut Metric {
+        self.record(name, duration.as_nanos() as f64, "ns")
+    }
+
+    /// Record the median of each measurement, as returned by `bench_compare!`
+    pub fn record_comparison(&mut self, results: &[(&str, Measurement)]) {
+        for (label, measurement) in results {
+            self.record_time(*label, measurement.median());
+        }
+    }
+}
+
+/// Run a demo the way its binary does: with the `Config` from the
+/// environment, writing the report to `DemoReport::PATH_VAR` if it's set,
+/// then quizzing the reader if the binary was given `--quiz`. Output follows
+/// `output::settings()`. A timing-sensitive demo started on its own first
+/// prints the machine it runs on; the runner does that once for all demos.
+/// With `--profile` the run is sampled into a flamegraph (see `profile`).
+pub fn run_from_env(demo: &dyn Demo) {
+    let standalone = std::env::var_os(DemoReport::PATH_VAR).is_none();
+    if standalone && catalog::find(demo.name()).is_some_and(|entry| entry.release) {
+        envinfo::print(&Environment::current());
+    }
+    #[cfg(not(target_family = "wasm"))]
+    let profiler = if crate::profile::requested() { crate::profile::Session::start() } else { None };
+    let report = demo.run(&Config::from_env());
+    #[cfg(not(target_family = "wasm"))]
+    if let Some(profiler) = profiler {
+        profiler.finish(demo.name());
+    }
+    output::finish(&report);
+    if std::env::args().skip(1).any(|arg| arg == quiz::FLAG) {
+        quiz::run(demo.name(), demo.quiz());
+    }
+    if let Some(path) = std::env::var_os(DemoReport::PATH_VAR) {
+        let written = serde_json::to_vec(&report).map_err(io::Error::from).and_then(|json| fs::write(&path, json));
+        if let Err(err) = written {
+            eprintln!("⚠️  couldn't write the report to {}: {}", path.to_string_lossy(), err);
+        }
+    }
+}
+
+/// Parameters the `systems-demos` runner forwards to a demo. Each is
+/// optional: a demo that reads one falls back to its own default when unset.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Config {
+    /// Problem size: elements, bytes or operations, depending on the demo
+    pub size: Option<usize>,
+    /// Worker thread count, or the maximum for demos that sweep 1..N
+    pub threads: Option<usize>,
+    /// Repetitions of the timed loop
+    pub iterations: Option<usize>,
+    /// Seed for random access patterns and inputs
+    pub seed: Option<u64>,
+}
+
+impl Config {
+    pub const SIZE_VAR: &'static str = "SYSTEMS_DEMOS_SIZE";
+    pub const THREADS_VAR: &'static str = "SYSTEMS_DEMOS_THREADS";
+    pub const ITERATIONS_VAR: &'static str = "SYSTEMS_DEMOS_ITERATIONS";
+    pub const SEED_VAR: &'static str = "SYSTEMS_DEMOS_SEED";
+
+    /// Read the variables the runner sets on a demo's process. Unset or
+    /// unparseable values are `None`.
+    pub fn from_env() -> Config {
+        let read = |var| std::env::var(var).ok().and_then(|value| value.parse().ok());
+        Config {
+            size: read(Self::SIZE_VAR),
+            threads: read(Self::THREADS_VAR),
+            iterations: read(Self::ITERATIONS_VAR),
+            seed: std::env::var(Self::SEED_VAR).ok().and_then(|value| value.parse().ok()),
+        }
+    }
+
+    /// The seed in effect: `seed`, or else `rng::DEFAULT_SEED`
+    pub fn seed(&self) -> u64 {
+        self.seed.unwrap_or(rng::DEFAULT_SEED)
+    }
+
+    /// A generator seeded with `seed()`; each call starts the same sequence
+    pub fn rng(&self) -> Rng {
+        Rng::new(self.seed())
+    }
+
+    /// The variables to set on a child process so its `from_env` returns
+    /// this config
+    pub fn env_vars(&self) -> Vec<(&'static str, String)> {
+        [(Self::SIZE_VAR, self.size), (Self::THREADS_VAR, self.threads), (Self::ITERATIONS_VAR, self.iterations)]
+            .into_iter()
+            .filter_map(|(var, value)| value.map(|value| (var, value.to_string())))
+            .chain(self.seed.map(|seed| (Self::SEED_VAR, seed.to_string())))
+            .collect()
+    }
+}
@@ -0,0 +1,242 @@
+//! Aliasing & noalias Demo
+//!
+//! A `&mut T` is the only way to reach its target while it lives, and a
+//! `&T` guarantees nobody writes through any other path. Rust passes both
+//! facts to LLVM as `noalias`, which lets it keep values in registers instead
+//! of re-reading memory. Raw pointers promise nothing, so the same code
+//! through `*mut T` must assume every store might change every load. This
+//! demo compares the generated code, measures the difference, and shows
+//! what happens when unsafe code lies about aliasing.
+//! Run with: cargo run --release --bin aliasing-demo
+
+use std::hint::black_box;
+
+use crate::bench_compare;
+use crate::demo::{Config, Demo, DemoReport};
+use crate::quiz::Question;
+use crate::{outln, output};
+
+/// `b` can't change while `a` is written: load it once, add it twice
+#[unsafe(no_mangle)]
+#[inline(never)]
+pub fn add_twice_refs(a: &mut i32, b: &i32) {
+    *a += *b;
+    *a += *b;
+}
+
+/// `a` and `b` may point to the same i32: `*b` must be re-read after the
+/// first store
+///
+/// # Safety
+/// Both pointers must be valid for reads (and `a` for writes)
+#[unsafe(no_mangle)]
+#[inline(never)]
+pub unsafe fn add_twice_ptrs(a: *mut i32, b: *const i32) {
+    unsafe {
+        *a += *b;
+        *a += *b;
+    }
+}
+
+/// Byte histogram plus a running total. `total` is a unique reference, so it
+/// lives in a register for the whole loop and is stored once at the end.
+#[unsafe(no_mangle)]
+#[inline(never)]
+pub fn histogram_refs(counts: &mut [u32; 256], total: &mut u64, data: &[u8]) {
+    for &byte in data {
+        counts[byte as usize] += 1;
+        *total += byte as u64;
+    }
+}
+
+/// The same loop through raw pointers: `total` might overlap `counts`, so
+/// each iteration updates it in memory
+///
+/// # Safety
+/// `counts` must point to 256 u32s, `total` to a u64, `data` to `len` bytes
+#[unsafe(no_mangle)]
+#[inline(never)]
+pub unsafe fn histogram_ptrs(counts: *mut u32, total: *mut u64, data: *const u8, len: usize) {
+    unsafe {
+        for i in 0..len {
+            let byte = *data.add(i);
+            *counts.add(byte as usize) += 1;
+            *total += byte as u64;
+        }
+    }
+}
+
+const BYTES: usize = 4_000_000;
+
+#[cfg(unix)]
+fn show_assembly(left: &str, right: &str) {
+    use crate::asm::{self, Disassembly};
+
+    let disassemble = |symbol| Disassembly::of(symbol).map_err(|err| outln!("⚠️  Can't disassemble: {}\n", err)).ok();
+    let (Some(left), Some(right)) = (disassemble(left), disassemble(right)) else {
+        return;
+    };
+    outln!("{}", asm::side_by_side(&left, &right, 40));
+    let memory_ops = |d: &Disassembly| d.instructions.iter().filter(|i| i.text.contains("PTR [")).count();
+    outln!("  {} instructions ({} touching memory) vs {} instructions ({} touching memory)",
+             left.instructions.len(), memory_ops(&left), right.instructions.len(), memory_ops(&right));
+}
+
+#[cfg(not(unix))]
+fn show_assembly(_left: &str, _right: &str) {
+    outln!("  (disassembly needs nm and objdump; skipped on this platform)");
+}
+
+/// The instructions of `symbol` that access `*total`: the second argument,
+/// passed in rsi under the x86-64 System V calling convention
+#[cfg(all(unix, target_arch = "x86_64"))]
+fn show_total_accesses(symbol: &str) {
+    match crate::asm::Disassembly::of(symbol) {
+        Ok(disassembly) => {
+            let accesses: Vec<_> = disassembly.instructions.iter().filter(|i| i.text.contains("[rsi]")).collect();
+            outln!("  {}: {} of {} instructions touch *total", symbol, accesses.len(), disassembly.instructions.len());
+            for instruction in accesses {
+                outln!("    {:>4x}: {}", instruction.offset, instruction.text);
+            }
+        }
+        Err(err) => outln!("⚠️  Can't disassemble: {}", err),
+    }
+}
+
+#[cfg(not(all(unix, target_arch = "x86_64")))]
+fn show_total_accesses(_symbol: &str) {
+    outln!("  (register-level listing is written for x86-64; skipped on this platform)");
+}
+
+fn demonstrate_redundant_loads() {
+    output::heading("🔬 One Load or Two");
+    outln!("  fn add_twice_refs(a: &mut i32, b: &i32)       {{ *a += *b; *a += *b; }}");
+    outln!("  unsafe fn add_twice_ptrs(a: *mut i32, b: *const i32) {{ same body }}\n");
+    let (mut x, y) = (1, 2);
+    add_twice_refs(&mut x, &y);
+    let (mut p, q) = (1, 2);
+    // SAFETY: distinct, valid locals
+    unsafe { add_twice_ptrs(&mut p, &q) };
+    assert_eq!((x, p), (5, 5));
+
+    show_assembly("add_twice_refs", "add_twice_ptrs");
+    outln!("With references LLVM computes *a + 2 * *b: one load of b, one update of");
+    outln!("a. With pointers, the first store to *a might have changed *b, so it");
+    outln!("must store, reload b and store again.\n");
+}
+
+fn demonstrate_histogram(report: &mut DemoReport) {
+    let title = format!("⏱️  A Loop That Can't Keep Its Total in a Register ({} bytes)", BYTES);
+    output::heading(&title);
+    let data: Vec<u8> = (0..BYTES as u32).map(|i| (i.wrapping_mul(2_654_435_761) >> 13) as u8).collect();
+
+    let (mut ref_counts, mut ref_total) = ([0u32; 256], 0u64);
+    histogram_refs(&mut ref_counts, &mut ref_total, &data);
+    let (mut ptr_counts, mut ptr_total) = ([0u32; 256], 0u64);
+    // SAFETY: 256 counters, one total, `data.len()` bytes, none overlapping
+    unsafe { histogram_ptrs(ptr_counts.as_mut_ptr(), &mut ptr_total, data.as_ptr(), data.len()) };
+    assert_eq!((ref_counts, ref_total), (ptr_counts, ptr_total));
+
+    show_total_accesses("histogram_refs");
+    show_total_accesses("histogram_ptrs");
+    outln!();
+    let mut counts = [0u32; 256];
+    let mut total = 0u64;
+    let results = bench_compare!(runs: 7;
+        "&mut references" => histogram_refs(black_box(&mut counts), black_box(&mut total), black_box(&data)),
+        "raw pointers" => unsafe {
+            histogram_ptrs(black_box(counts.as_mut_ptr()), black_box(&mut total), black_box(data.as_ptr()), data.len())
+        },
+    );
+    report.record_comparison(&results);
+    outln!("The pointer version does `add [rsi], byte` every iteration: a memory");
+    outln!("read-modify-write that each next iteration has to wait for. The reference");
+    outln!("version keeps the total in a register and stores it once. For simple");
+    outln!("loops LLVM can sometimes recover by checking for overlap at runtime and");
+    outln!("picking between two copies of the loop; with data-dependent addresses");
+    outln!("like counts[byte], it can't.\n");
+}
+
+fn demonstrate_broken_rules() {
+    output::heading("💥 Lying About Aliasing");
+    outln!("Unsafe code can manufacture a &mut and a & to the same i32:");
+    outln!("  let p = &mut x as *mut i32;");
+    outln!("  add_twice_refs(&mut *p, &*p);   // undefined behavior\n");
+
+    let mut x = 1;
+    let p = &mut x as *mut i32;
+    // SAFETY: none - this deliberately breaks the aliasing rules to show the
+    // consequence. The function is #[inline(never)], so what it computes is
+    // fixed by its own compiled body.
+    unsafe { add_twice_refs(&mut *p, &*p) };
+    let mut y = 1;
+    let q = &raw mut y; // no references involved, so no uniqueness claimed
+    // SAFETY: raw pointers may alias; the function is written for that
+    unsafe { add_twice_ptrs(q, q) };
+
+    outln!("  starting from 1, step by step: 1 + 1 = 2, then 2 + 2 = 4");
+    outln!("  add_twice_ptrs (may alias):   {}", y);
+    outln!("  add_twice_refs (noalias):     {}", x);
+    if x != y {
+        outln!("The reference version read b once, before the first store, and added");
+        outln!("it twice: 1 + 2 * 1 = 3. Nothing crashed, the answer is just wrong, and");
+        outln!("it would change with the optimization level or compiler version. Miri");
+        outln!("reports this as a Stacked Borrows violation.");
+    } else {
+        outln!("Same answer here, probably because this is a debug build: the optimizer");
+        outln!("didn't use the noalias promise. Rerun with --release. (It's undefined");
+        outln!("behavior either way; Miri reports a Stacked Borrows violation.)");
+    }
+    outln!();
+}
+
+const QUIZ: &[Question] = &[
+    Question::new(
+        "Why could the &mut version reuse a loaded value where the raw-pointer version reloaded it?",
+        &[
+            "&mut is faster to dereference",
+            "&mut guarantees no other pointer aliases it (noalias), so a store elsewhere can't change it",
+            "Raw pointers bypass the cache",
+        ],
+        1,
+        "With raw pointers, any store may have clobbered the value, so LLVM must load it again.",
+    ),
+    Question::new(
+        "What happens if unsafe code creates two &mut to the same data?",
+        &[
+            "It's undefined behavior, and optimized code may silently compute wrong results",
+            "It's fine as long as only one is used at a time",
+            "A compile error",
+        ],
+        0,
+        "The optimizer relies on &mut being unique, so code that breaks that promise can be miscompiled.",
+    ),
+];
+
+#[derive(Demo)]
+#[demo(name = "aliasing-demo", description = "What &mut uniqueness (noalias) buys the optimizer",
+       quiz = QUIZ)]
+pub struct AliasingDemo;
+
+impl AliasingDemo {
+    fn demonstrate(&self, _config: &Config) -> DemoReport {
+        output::title("🪞 Aliasing & noalias Demo");
+        outln!("What &mut uniqueness buys the optimizer.\n");
+
+        demonstrate_redundant_loads();
+        let mut report = DemoReport::default();
+        demonstrate_histogram(&mut report);
+        demonstrate_broken_rules();
+
+        if cfg!(debug_assertions) {
+            outln!("⚠️  This is a debug build: the optimizer hasn't used noalias, so the");
+            outln!("   assembly and timings above don't show the difference.\n");
+        }
+        output::takeaways();
+        outln!("• &mut T is unique and &T is frozen; Rust tells LLVM both (noalias)");
+        outln!("• noalias lets loads be reused and values stay in registers");
+        outln!("• Raw pointers forfeit that: every store may clobber every load");
+        outln!("• Creating aliasing &mut through unsafe is UB with silently wrong results");
+        report
+    }
+}
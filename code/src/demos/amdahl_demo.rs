@@ -0,0 +1,220 @@
+//! Amdahl's Law Demo
+//!
+//! A fixed amount of work with a configurable serial fraction, run on
+//! 1..N threads, with the measured speedup printed and plotted next to
+//! Amdahl's prediction.
+//! Run with: cargo run --release --bin amdahl-demo [-- --serial 0.1]
+
+use std::hint::black_box;
+use std::thread;
+use std::time::Duration;
+
+use crate::bench::measure;
+use crate::demo::{Config, Demo, DemoReport};
+use crate::output::Table;
+use crate::plot::LinePlot;
+use crate::quiz::Question;
+use crate::verify::Verification;
+use crate::{out, outln, output};
+
+/// Total work units per run; one unit is a few nanoseconds of arithmetic
+const TOTAL_UNITS: u64 = 40_000_000;
+const DEFAULT_SERIAL_FRACTION: f64 = 0.1;
+/// Plot columns always go at least this far, predicted-only past the core count
+const PLOT_MIN_THREADS: usize = 16;
+
+/// Pure CPU work that the optimizer can't remove or vectorize away
+fn work(units: u64) -> u64 {
+    let mut x = 0u64;
+    for i in 0..units {
+        x = black_box(x.wrapping_mul(6364136223846793005).wrapping_add(i));
+    }
+    x
+}
+
+/// Run the serial part on this thread, then split the rest across `threads`
+fn run(serial_fraction: f64, threads: usize) -> u64 {
+    let serial_units = (TOTAL_UNITS as f64 * serial_fraction) as u64;
+    let parallel_units = TOTAL_UNITS - serial_units;
+    let mut checksum = work(serial_units);
+    thread::scope(|scope| {
+        let handles: Vec<_> = (0..threads)
+            .map(|_| scope.spawn(move || work(parallel_units / threads as u64)))
+            .collect();
+        for handle in handles {
+            checksum ^= handle.join().unwrap();
+        }
+    });
+    checksum
+}
+
+/// Amdahl: S(n) = 1 / (s + (1 - s) / n)
+fn amdahl(serial_fraction: f64, threads: usize) -> f64 {
+    1.0 / (serial_fraction + (1.0 - serial_fraction) / threads as f64)
+}
+
+/// Karp-Flatt metric: the serial fraction implied by a measured speedup
+fn karp_flatt(speedup: f64, threads: usize) -> f64 {
+    let n = threads as f64;
+    (1.0 / speedup - 1.0 / n) / (1.0 - 1.0 / n)
+}
+
+fn parse_serial_fraction() -> f64 {
+    let args: Vec<String> = std::env::args().collect();
+    match args.iter().position(|arg| arg == "--serial") {
+        Some(i) => match args.get(i + 1).and_then(|value| value.parse::<f64>().ok()) {
+            Some(fraction) if (0.0..=1.0).contains(&fraction) => fraction,
+            _ => {
+                eprintln!("--serial expects a fraction between 0 and 1");
+                std::process::exit(2);
+            }
+        },
+        None => DEFAULT_SERIAL_FRACTION,
+    }
+}
+
+fn demonstrate_law(serial_fraction: f64) {
+    output::heading("📐 The Law");
+    outln!("If a fraction s of the work is serial, n threads can at best reach");
+    outln!("  S(n) = 1 / (s + (1 - s) / n)");
+    if serial_fraction > 0.0 {
+        outln!("and no number of threads beats 1/s. With s = {:.2}: at most {:.1}x.\n",
+                 serial_fraction, 1.0 / serial_fraction);
+    } else {
+        outln!("and no number of threads beats 1/s. With s = 0 there is no ceiling.\n");
+    }
+
+    let mut table = Table::new(["serial", "n=2", "n=8", "n=32", "n=128", "n=1024", "limit"]);
+    for s in [0.01, 0.05, 0.1, 0.25, 0.5] {
+        let speedups = [2, 8, 32, 128, 1024].map(|n| format!("{:.1}x", amdahl(s, n)));
+        table.row([format!("{:.0}%", s * 100.0)].into_iter().chain(speedups).chain([format!("{:.1}x", 1.0 / s)]));
+    }
+    out!("{}", table);
+    outln!("Even 1% serial work caps 1024 threads at about 91x.\n");
+}
+
+fn demonstrate_experiment(serial_fraction: f64, report: &mut DemoReport) {
+    let title = format!("🧪 Measured vs Predicted (s = {:.2})", serial_fraction);
+    output::heading(&title);
+    outln!("{} work units per run, {:.0}% of them on one thread; median of 3 runs.\n",
+             TOTAL_UNITS, serial_fraction * 100.0);
+
+    let cores = thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+    let max_threads = cores.max(2) * 2;
+    let plot_threads = max_threads.max(PLOT_MIN_THREADS);
+
+    let table = Table::new(["threads", "time", "measured", "Amdahl", "efficiency", "implied s"]).widths([7, 12, 9, 9, 11, 12]);
+    outln!("{}", table.header());
+    let mut baseline = Duration::ZERO;
+    let mut columns = Vec::new();
+    let mut threads = 1;
+    while threads <= plot_threads {
+        let predicted = amdahl(serial_fraction, threads);
+        if threads > max_threads {
+            columns.push((threads, None, predicted));
+            threads *= 2;
+            continue;
+        }
+
+        let time = measure(3, || run(serial_fraction, threads)).median();
+        if threads == 1 {
+            baseline = time;
+        }
+        let speedup = baseline.as_secs_f64() / time.as_secs_f64();
+        let implied = if threads == 1 { "-".to_string() } else { format!("{:.2}", karp_flatt(speedup, threads)) };
+        outln!("{}", table.line([threads.to_string(), format!("{:?}", time), format!("{:.2}x", speedup),
+                                 format!("{:.2}x", predicted), format!("{:.0}%", speedup / threads as f64 * 100.0),
+                                 implied]));
+        report.record("speedup", speedup, "x").param("threads", threads).param("serial_fraction", serial_fraction);
+        columns.push((threads, Some(speedup), predicted));
+        threads *= 2;
+    }
+    outln!();
+
+    let predicted = columns.iter().map(|&(n, _, predicted)| (n as f64, predicted)).collect();
+    let measured = columns.iter().filter_map(|&(n, measured, _)| Some((n as f64, measured?))).collect();
+    outln!("{}", LinePlot::new("threads", "speedup")
+        .log_x()
+        .format_y(|speedup| format!("{:.1}x", speedup))
+        .series("Amdahl's prediction", predicted)
+        .series("measured", measured));
+
+    outln!("\"implied s\" (the Karp-Flatt metric) is the serial fraction that would");
+    outln!("explain the measured speedup. If it grows with n, the overhead isn't just");
+    outln!("the serial part: thread startup, memory bandwidth and cache contention");
+    outln!("add serial-like costs that the formula doesn't know about.");
+    if max_threads < plot_threads {
+        outln!("Columns past {} threads are predictions only.", max_threads);
+    }
+    if cores == 1 {
+        outln!("⚠️  Only one hardware thread available: threads just take turns, so the");
+        outln!("   measured speedup stays at 1x however small the serial fraction is.");
+    }
+    outln!();
+}
+
+/// The law's shape, and a real speedup from a second core when there is one
+fn verify() -> Verification {
+    let mut verification = Verification::new();
+    let s = DEFAULT_SERIAL_FRACTION;
+    verification
+        .check("one thread gives no speedup", amdahl(s, 1) == 1.0, || format!("S(1) = {}", amdahl(s, 1)))
+        .check("a 10% serial part caps the speedup below 10x", amdahl(s, 1_000_000) < 1.0 / s,
+               || format!("S(1e6) = {:.3}", amdahl(s, 1_000_000)))
+        .check("Karp-Flatt recovers the serial fraction", (karp_flatt(amdahl(s, 8), 8) - s).abs() < 1e-9,
+               || format!("e = {}", karp_flatt(amdahl(s, 8), 8)));
+
+    let claim = "two threads beat one with a 10% serial part";
+    if thread::available_parallelism().map(|n| n.get()).unwrap_or(1) < 2 {
+        verification.skip(claim, "only one hardware thread");
+    } else {
+        let one = measure(3, || run(s, 1));
+        let two = measure(3, || run(s, 2));
+        verification.faster(claim, &two, &one, 1.2);
+    }
+    verification
+}
+
+const QUIZ: &[Question] = &[
+    Question::new(
+        "With a 10% serial fraction, what is the best possible speedup with unlimited threads?",
+        &["10x", "Unlimited", "90x"],
+        0,
+        "Speedup is bounded by 1 / serial fraction.",
+    ),
+    Question::new(
+        "Why does each doubling of threads buy less?",
+        &[
+            "Threads get slower",
+            "The parallel part shrinks while the serial part stays, so it dominates the total",
+            "The OS limits threads",
+        ],
+        1,
+        "Efficiency (speedup / threads) falls as n grows.",
+    ),
+];
+
+#[derive(Demo)]
+#[demo(name = "amdahl-demo", description = "Measured speedup vs Amdahl's law",
+       quiz = QUIZ, verify = verify)]
+pub struct AmdahlDemo;
+
+impl AmdahlDemo {
+    fn demonstrate(&self, _config: &Config) -> DemoReport {
+        let serial_fraction = parse_serial_fraction();
+
+        output::title("📈 Amdahl's Law Demo");
+        outln!("Why adding threads stops helping. Pass --serial <fraction> to change s.\n");
+
+        demonstrate_law(serial_fraction);
+        let mut report = DemoReport::default();
+        demonstrate_experiment(serial_fraction, &mut report);
+
+        output::takeaways();
+        outln!("• The serial fraction, not the thread count, bounds the achievable speedup");
+        outln!("• Each doubling of threads buys less: efficiency falls as n grows");
+        outln!("• Locks, contended cache lines and allocation behave like extra serial work");
+        outln!("• Shrinking the serial part (see the lock-free and sharding demos) raises the ceiling");
+        report
+    }
+}
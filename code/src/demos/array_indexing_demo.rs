@@ -0,0 +1,263 @@
+// Demonstration of array/vec indexing and usize
+
+use crate::demo::{Config, Demo, DemoReport};
+use crate::output::Table;
+use crate::quiz::Question;
+use crate::{outln, output};
+
+fn demonstrate_array_indexing() {
+    output::heading("Array Indexing Demo");
+    
+    let array: [u32; 5] = [10, 20, 30, 40, 50];
+    
+    outln!("Array: {:?}", array);
+    outln!("Length: {} (stored as usize)", array.len());
+    outln!("usize can hold: 0 to {} (2^64 - 1)", usize::MAX);
+    outln!();
+    
+    outln!("Valid indices: 0, 1, 2, 3, 4");
+    outln!("Maximum index: {} (length - 1)", array.len() - 1);
+    outln!();
+    
+    for i in 0..array.len() {
+        outln!("array[{}] = {}", i, array[i]);
+    }
+    outln!();
+    
+    outln!("Indexing formula:");
+    outln!("  Element address = base_address + (index * element_size)");
+    outln!("  For u32: element_size = 4 bytes");
+    outln!("  array[2] = base + (2 * 4) = base + 8 bytes offset");
+    outln!();
+}
+
+fn demonstrate_vec_indexing() {
+    output::heading("Vec Indexing Demo");
+    
+    let vec: Vec<u8> = vec![1, 2, 3, 4, 5];
+    
+    outln!("Vec: {:?}", vec);
+    outln!("Length: {} (stored as usize)", vec.len());
+    outln!("Capacity: {} (stored as usize)", vec.capacity());
+    outln!();
+    
+    outln!("Vec structure (simplified):");
+    outln!("  struct Vec<T> {{");
+    outln!("      pointer: *mut T,      // Points to heap data");
+    outln!("      length: usize,        // Current elements: {}", vec.len());
+    outln!("      capacity: usize,      // Allocated: {}", vec.capacity());
+    outln!("  }}");
+    outln!();
+    
+    for i in 0..vec.len() {
+        outln!("vec[{}] = {}", i, vec[i]);
+    }
+    outln!();
+}
+
+fn demonstrate_usize_vs_length() {
+    output::heading("usize vs Array Length");
+    
+    outln!("Confusion: 'if usize is 2, this means 2 elements'");
+    outln!("❌ WRONG! usize is a TYPE, not a value!");
+    outln!();
+    
+    outln!("Correct understanding:");
+    outln!("  usize = TYPE (64 bits, can hold 0 to 2^64-1)");
+    outln!("  Array length = VALUE of type usize (e.g., 2, 5, 100)");
+    outln!();
+    
+    let small_array: [u8; 2] = [10, 20];
+    let large_array: [u8; 1000] = [0; 1000];
+    
+    outln!("small_array: length = {} (value of type usize)", small_array.len());
+    outln!("large_array: length = {} (value of type usize)", large_array.len());
+    outln!("Both use usize type, but have different length VALUES");
+    outln!();
+}
+
+fn demonstrate_indexing_calculation() {
+    output::heading("Indexing Calculation");
+    
+    let array: [u8; 5] = [1, 2, 3, 4, 5];
+    
+    outln!("Array: {:?}", array);
+    outln!("Element type: u8 (1 byte)");
+    outln!();
+    
+    outln!("Accessing array[2]:");
+    outln!("  1. Base address: (some address, e.g., 0x1000)");
+    outln!("  2. Index: 2 (value of type usize)");
+    outln!("  3. Element size: 1 byte (u8)");
+    outln!("  4. Offset = index * element_size = 2 * 1 = 2 bytes");
+    outln!("  5. Element address = base + 2");
+    outln!("  6. Read 1 byte from that address");
+    outln!("  7. Result: {}", array[2]);
+    outln!();
+    
+    let array_u32: [u32; 5] = [10, 20, 30, 40, 50];
+    
+    outln!("Array: {:?}", array_u32);
+    outln!("Element type: u32 (4 bytes)");
+    outln!();
+    
+    outln!("Accessing array_u32[2]:");
+    outln!("  1. Base address: (some address, e.g., 0x1000)");
+    outln!("  2. Index: 2 (value of type usize)");
+    outln!("  3. Element size: 4 bytes (u32)");
+    outln!("  4. Offset = index * element_size = 2 * 4 = 8 bytes");
+    outln!("  5. Element address = base + 8");
+    outln!("  6. Read 4 bytes from that address");
+    outln!("  7. Result: {}", array_u32[2]);
+    outln!();
+    
+    outln!("Key point: usize is just the TYPE of the index!");
+    outln!("The calculation uses the INDEX VALUE, not usize itself!");
+    outln!();
+}
+
+fn demonstrate_maximum_capacity() {
+    output::heading("Maximum Capacity");
+    
+    outln!("usize::MAX = {} (2^64 - 1 on 64-bit)", usize::MAX);
+    outln!("isize::MAX = {} (2^63 - 1, Rust's safety limit)", isize::MAX);
+    outln!();
+    
+    outln!("Theoretical maximum Vec capacity:");
+    outln!("  usize::MAX elements (but impractical)");
+    outln!("  Rust limits to isize::MAX for safety");
+    outln!();
+    
+    outln!("Practical limits:");
+    outln!("  - Available RAM (16 GB = ~4 billion u32 elements)");
+    outln!("  - OS address space limits");
+    outln!("  - You'll run out of RAM before hitting usize::MAX!");
+    outln!();
+}
+
+fn demonstrate_layout_breakdown() {
+    output::heading("Stack vs Heap: Where the Elements Live");
+    
+    let array: [u32; 4] = [10, 20, 30, 40];
+    let slice: &[u32] = &array[1..];
+    let mut vec: Vec<u32> = Vec::with_capacity(8);
+    vec.extend_from_slice(&array);
+    let boxed: Box<[u32]> = vec.clone().into_boxed_slice();
+    
+    let mut table = Table::new(["type", "handle", "handle at", "elements at", "len", "cap"]).left(2).left(3);
+    table.row(["[u32; 4]".to_string(), std::mem::size_of::<[u32; 4]>().to_string(), format!("{:p}", &array),
+               format!("{:p}", array.as_ptr()), array.len().to_string(), "-".to_string()]);
+    table.row(["&[u32]".to_string(), std::mem::size_of::<&[u32]>().to_string(), format!("{:p}", &slice),
+               format!("{:p}", slice.as_ptr()), slice.len().to_string(), "-".to_string()]);
+    table.row(["Vec<u32>".to_string(), std::mem::size_of::<Vec<u32>>().to_string(), format!("{:p}", &vec),
+               format!("{:p}", vec.as_ptr()), vec.len().to_string(), vec.capacity().to_string()]);
+    table.row(["Box<[u32]>".to_string(), std::mem::size_of::<Box<[u32]>>().to_string(), format!("{:p}", &boxed),
+               format!("{:p}", boxed.as_ptr()), boxed.len().to_string(), "-".to_string()]);
+    outln!("{}", table);
+    
+    outln!("[u32; 4]:   the handle IS the elements: 4 * 4 = 16 bytes on the stack");
+    outln!("&[u32]:     pointer + length (16 bytes); points into the array, 4 bytes");
+    outln!("            past its start because it begins at element 1");
+    outln!("Vec<u32>:   pointer + capacity + length (24 bytes) on the stack,");
+    outln!("            elements in a heap buffer with room for {} u32s", vec.capacity());
+    outln!("Box<[u32]>: pointer + length (16 bytes); the heap buffer is exactly");
+    outln!("            len elements, so there is no capacity to store");
+    outln!();
+}
+
+fn demonstrate_vec_growth() {
+    output::heading("Vec Growth and Reallocation");
+    
+    let mut vec: Vec<u64> = Vec::new();
+    outln!("Vec::new(): len 0, capacity {}, no heap buffer yet", vec.capacity());
+    outln!();
+    let mut table = Table::new(["len", "capacity", "data pointer", "what happened"]).left(2).left(3);
+    
+    let mut previous = vec.as_ptr();
+    let mut reallocations = 0;
+    for i in 0..1000u64 {
+        let old_capacity = vec.capacity();
+        vec.push(i);
+        if vec.capacity() != old_capacity {
+            let event = if old_capacity == 0 {
+                "first allocation".to_string()
+            } else if vec.as_ptr() == previous {
+                format!("grew in place ({} -> {})", old_capacity, vec.capacity())
+            } else {
+                format!("moved: copied {} elements", old_capacity)
+            };
+            table.row([vec.len().to_string(), vec.capacity().to_string(), format!("{:p}", vec.as_ptr()), event]);
+            previous = vec.as_ptr();
+            reallocations += 1;
+        }
+    }
+    outln!("{}", table);
+    outln!("1000 pushes, {} (re)allocations: the capacity doubles each time it runs", reallocations);
+    outln!("out, so even if every step moved, the total copying would stay under");
+    outln!("2 * len (amortized O(1) per push). Whether realloc can grow in place");
+    outln!("depends on what the allocator has free right after the buffer.");
+    outln!("Any reference into the old buffer would now dangle - which is why the");
+    outln!("borrow checker forbids holding &vec[0] across a push.");
+    outln!();
+    
+    let mut presized: Vec<u64> = Vec::with_capacity(1000);
+    let start = presized.as_ptr();
+    presized.extend(0..1000);
+    outln!("Vec::with_capacity(1000): data pointer unchanged after 1000 pushes: {}",
+             presized.as_ptr() == start);
+    
+    vec.truncate(10);
+    let before = vec.capacity();
+    vec.shrink_to_fit();
+    outln!("truncate(10) + shrink_to_fit(): capacity {} -> {}, pointer {:p}",
+             before, vec.capacity(), vec.as_ptr());
+    outln!();
+}
+
+const QUIZ: &[Question] = &[
+    Question::new(
+        "Where in memory is element i of a [u64; N]?",
+        &["base + i", "base + i * 8", "base + i * 64"],
+        1,
+        "Elements are laid out contiguously, so the address is base plus index times the element size.",
+    ),
+    Question::new(
+        "What happens when you push onto a Vec whose length equals its capacity?",
+        &[
+            "It panics",
+            "It allocates a bigger buffer (about double), moves the elements there and frees the old one",
+            "It always grows in place",
+        ],
+        1,
+        "That's why pointers into a Vec are invalidated by a push, and why with_capacity avoids repeated copies.",
+    ),
+];
+
+#[derive(Demo)]
+#[demo(name = "array-indexing-demo", description = "Indexing with usize, and where slices and Vecs keep their data",
+       quiz = QUIZ)]
+pub struct ArrayIndexingDemo;
+
+impl ArrayIndexingDemo {
+    fn demonstrate(&self, _config: &Config) -> DemoReport {
+        output::title("Array/Vector Indexing and usize");
+
+        demonstrate_array_indexing();
+        demonstrate_vec_indexing();
+        demonstrate_usize_vs_length();
+        demonstrate_indexing_calculation();
+        demonstrate_maximum_capacity();
+        demonstrate_layout_breakdown();
+        demonstrate_vec_growth();
+
+        output::takeaways();
+        outln!("1. usize is a TYPE (64 bits), not a value");
+        outln!("2. Array length is a VALUE of type usize");
+        outln!("3. Indexing formula: address = base + (index * element_size)");
+        outln!("4. Maximum index = length - 1 (0-based indexing)");
+        outln!("5. usize is just the type - calculation uses index value");
+        outln!("6. Arrays hold elements inline; slices, Vec and Box<[T]> point to them");
+        outln!("7. Vec reallocates (and may move) when it outgrows its capacity");
+        DemoReport::default()
+    }
+}
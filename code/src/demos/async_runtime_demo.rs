@@ -0,0 +1,304 @@
+//! Mini Async Runtime Demo
+//!
+//! An executor + epoll reactor built from scratch, serving a TCP echo
+//! server on one thread, and the memory cost of 10k idle connections with
+//! async tasks versus one OS thread per connection.
+//! Run with: cargo run --release --bin async-runtime-demo
+
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::bench::proc_status_field;
+use crate::demo::{Config, Demo, DemoReport};
+use crate::output::Table;
+use crate::quiz::Question;
+use crate::runtime::{self, Runtime, RuntimeStats};
+use crate::runtime::net::TcpListener;
+use crate::{outln, output};
+
+const IDLE_TARGET: usize = 10_000;
+
+/// Echo everything back until the peer closes
+async fn echo(mut stream: runtime::net::TcpStream) {
+    let mut buf = [0u8; 1024];
+    loop {
+        match stream.read(&mut buf).await {
+            Ok(0) | Err(_) => return,
+            Ok(n) => {
+                if stream.write_all(&buf[..n]).await.is_err() {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+/// Start an echo server on its own runtime thread. It stops accepting once
+/// `stop` is set and one more connection arrives to wake the accept loop.
+fn start_echo_server(stop: Arc<AtomicBool>) -> (SocketAddr, thread::JoinHandle<(RuntimeStats, thread::ThreadId)>) {
+    let (addr_tx, addr_rx) = std::sync::mpsc::channel();
+    let handle = thread::spawn(move || {
+        let mut rt = Runtime::new().expect("failed to create runtime");
+        rt.block_on(async {
+            let listener = TcpListener::bind("127.0.0.1:0").expect("bind failed");
+            addr_tx.send(listener.local_addr().unwrap()).unwrap();
+            while let Ok((stream, _)) = listener.accept().await {
+                if stop.load(Ordering::SeqCst) {
+                    break;
+                }
+                runtime::spawn(echo(stream));
+            }
+        });
+        (rt.stats(), thread::current().id())
+    });
+    (addr_rx.recv().unwrap(), handle)
+}
+
+fn demonstrate_architecture() {
+    output::heading("🏗️  Executor + Reactor");
+    outln!("  ┌──────────── executor ─────────────┐      ┌──────── reactor ────────┐");
+    outln!("  │ ready queue → poll(task)          │      │ epoll_wait(all sockets) │");
+    outln!("  │   Ready   → task done             │      │   fd ready → waker.wake │");
+    outln!("  │   Pending → task parked in waker ─┼─────▶│   (task back in queue)  │");
+    outln!("  └───────────────────────────────────┘      └─────────────────────────┘");
+    outln!("A read that would block stores the task's Waker next to the socket,");
+    outln!("arms EPOLLIN, and returns Pending. No thread waits - the task is just data.\n");
+}
+
+fn demonstrate_echo_server(report: &mut DemoReport) {
+    output::heading("🔁 Async TCP Echo Server");
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let (addr, server) = start_echo_server(Arc::clone(&stop));
+
+    let connections = 100;
+    let rounds = 10;
+    let start = Instant::now();
+    let mut clients: Vec<TcpStream> = (0..connections).map(|_| TcpStream::connect(addr).unwrap()).collect();
+    let mut all_echoed = true;
+    for round in 0..rounds {
+        for (i, client) in clients.iter_mut().enumerate() {
+            client.write_all(format!("msg {} from {}", round, i).as_bytes()).unwrap();
+        }
+        for (i, client) in clients.iter_mut().enumerate() {
+            let expected = format!("msg {} from {}", round, i);
+            let mut buf = vec![0u8; expected.len()];
+            client.read_exact(&mut buf).unwrap();
+            all_echoed &= buf == expected.as_bytes();
+        }
+    }
+    let elapsed = start.elapsed();
+    drop(clients);
+
+    stop.store(true, Ordering::SeqCst);
+    let _ = TcpStream::connect(addr); // wake the accept loop so it sees `stop`
+    let (stats, server_thread) = server.join().unwrap();
+
+    outln!("{} concurrent connections × {} round trips in {:?}: all echoed {}",
+             connections, rounds, elapsed, if all_echoed { "✅" } else { "❌" });
+    report.record_time("echo round trips", elapsed).param("connections", connections).param("rounds", rounds);
+    outln!("Server ran on a single OS thread ({:?})", server_thread);
+    outln!("  tasks spawned:     {}", stats.tasks_spawned);
+    outln!("  task polls:        {}", stats.task_polls);
+    outln!("  epoll_wait calls:  {}", stats.reactor.epoll_waits);
+    outln!("  readiness events:  {}", stats.reactor.events);
+    outln!("Each poll either makes progress or re-parks the task - no busy loop.\n");
+}
+
+/// Fields from /proc/self/status, in KiB (and a thread count)
+struct ProcStatus {
+    rss_kib: u64,
+    virtual_kib: u64,
+    threads: u64,
+}
+
+fn proc_status() -> ProcStatus {
+    ProcStatus {
+        rss_kib: proc_status_field("VmRSS:"),
+        virtual_kib: proc_status_field("VmSize:"),
+        threads: proc_status_field("Threads:"),
+    }
+}
+
+/// Raise the open-file limit as far as allowed; returns how many idle
+/// connections fit (each one needs a client and a server descriptor)
+fn max_idle_connections() -> usize {
+    let mut limit = libc::rlimit { rlim_cur: 0, rlim_max: 0 };
+    unsafe {
+        libc::getrlimit(libc::RLIMIT_NOFILE, &mut limit);
+        limit.rlim_cur = limit.rlim_max;
+        libc::setrlimit(libc::RLIMIT_NOFILE, &limit);
+        libc::getrlimit(libc::RLIMIT_NOFILE, &mut limit);
+    }
+    IDLE_TARGET.min((limit.rlim_cur as usize).saturating_sub(64) / 2)
+}
+
+/// Child process: open `n` idle connections against one server model and
+/// report the memory it took. Runs in a fresh process so the models don't
+/// share heap, descriptors, or threads.
+fn run_idle_child(model: &str) {
+    let n = max_idle_connections();
+    let ready = Arc::new(AtomicUsize::new(0));
+    let (addr_tx, addr_rx) = std::sync::mpsc::channel();
+
+    let before = proc_status();
+    match model {
+        "async" => {
+            let ready = Arc::clone(&ready);
+            thread::spawn(move || {
+                Runtime::new().unwrap().block_on(async {
+                    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+                    addr_tx.send(listener.local_addr().unwrap()).unwrap();
+                    while let Ok((stream, _)) = listener.accept().await {
+                        let ready = Arc::clone(&ready);
+                        runtime::spawn(async move {
+                            ready.fetch_add(1, Ordering::SeqCst);
+                            echo(stream).await;
+                        });
+                    }
+                });
+            });
+        }
+        _ => {
+            let ready = Arc::clone(&ready);
+            thread::spawn(move || {
+                let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+                addr_tx.send(listener.local_addr().unwrap()).unwrap();
+                for stream in listener.incoming() {
+                    let mut stream = stream.unwrap();
+                    let counter = Arc::clone(&ready);
+                    let spawned = thread::Builder::new().spawn(move || {
+                        counter.fetch_add(1, Ordering::SeqCst);
+                        let mut buf = [0u8; 1024];
+                        while let Ok(n) = stream.read(&mut buf) {
+                            if n == 0 || stream.write_all(&buf[..n]).is_err() {
+                                break;
+                            }
+                        }
+                    });
+                    if let Err(err) = spawned {
+                        println!("ERROR thread spawn failed after {} threads: {}", ready.load(Ordering::SeqCst), err);
+                        std::process::exit(1);
+                    }
+                }
+            });
+        }
+    }
+
+    let addr = addr_rx.recv().unwrap();
+    let mut clients = Vec::with_capacity(n);
+    let start = Instant::now();
+    while clients.len() < n {
+        clients.push(TcpStream::connect(addr).unwrap());
+        // Don't outrun the accept loop, or the listen backlog overflows
+        while clients.len() - ready.load(Ordering::SeqCst) > 64 {
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+    while ready.load(Ordering::SeqCst) < n && start.elapsed() < Duration::from_secs(60) {
+        thread::sleep(Duration::from_millis(5));
+    }
+    let after = proc_status();
+    println!("RESULT {} {} {} {} {}",
+             ready.load(Ordering::SeqCst),
+             after.rss_kib.saturating_sub(before.rss_kib),
+             after.virtual_kib.saturating_sub(before.virtual_kib),
+             after.threads,
+             start.elapsed().as_millis());
+    std::process::exit(0);
+}
+
+fn demonstrate_idle_connections(report: &mut DemoReport) {
+    output::heading("💤 Idle Connections: Tasks vs Threads");
+    outln!("Open up to {} connections that never send anything; each server model", IDLE_TARGET);
+    outln!("runs in its own child process, which reports its memory growth.\n");
+
+    let exe = std::env::current_exe().expect("can't locate own executable");
+    let mut table = Table::new(["model", "conns", "RSS growth", "per conn", "virtual", "threads"]);
+    for (label, model) in [("async tasks", "async"), ("thread per conn", "threads")] {
+        let output = Command::new(&exe).args(["--idle-child", model]).output();
+        let stdout = output.as_ref().map(|o| String::from_utf8_lossy(&o.stdout).into_owned()).unwrap_or_default();
+        let Some(result) = stdout.lines().find(|line| line.starts_with("RESULT")) else {
+            let reason = stdout.lines().find(|line| line.starts_with("ERROR")).unwrap_or("child failed");
+            table.row([label, reason]);
+            continue;
+        };
+        let fields: Vec<u64> = result.split_whitespace().skip(1).filter_map(|v| v.parse().ok()).collect();
+        let (conns, rss_kib, virtual_kib, threads) = (fields[0], fields[1], fields[2], fields[3]);
+        table.row([label.to_string(), conns.to_string(), format!("{} MiB", rss_kib / 1024),
+                   format!("{} KiB", rss_kib / conns.max(1)), format!("{} MiB", virtual_kib / 1024), threads.to_string()]);
+        report.record("RSS per idle connection", (rss_kib / conns.max(1)) as f64, "KiB").param("model", model);
+    }
+    outln!("{}", table);
+    if max_idle_connections() < IDLE_TARGET {
+        outln!("(Connection count capped by the open-file limit: `ulimit -n` allows {})", max_idle_connections() * 2 + 64);
+    }
+    outln!("An idle task is its future: a state machine holding the 1 KiB buffer and");
+    outln!("the socket. An idle thread is a kernel task plus a stack: the stack reserves");
+    outln!("megabytes of address space and commits at least a page or two of it, and");
+    outln!("every thread costs the scheduler and the kernel (not counted in RSS).\n");
+}
+
+/// If this process is one of the demo's children (`--idle-child <model>`),
+/// do the child's work and return true. The demo re-runs its own executable
+/// for each server model, so the binary's `main` must call this first.
+pub fn run_as_child() -> bool {
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() == 3 && args[1] == "--idle-child" {
+        run_idle_child(&args[2]);
+        return true;
+    }
+    false
+}
+
+const QUIZ: &[Question] = &[
+    Question::new(
+        "What does the reactor do?",
+        &[
+            "Polls every task in a loop",
+            "Waits in epoll and wakes the tasks whose sockets became ready",
+            "Runs tasks on a thread pool",
+        ],
+        1,
+        "It turns kernel readiness events back into task wakeups.",
+    ),
+    Question::new(
+        "Why can one thread serve thousands of idle connections?",
+        &[
+            "Each waiting connection is a small future, not a blocked thread with its own stack",
+            "The kernel handles the connections",
+            "Idle connections are closed",
+        ],
+        0,
+        "Waiting costs memory for the future's state, not a thread.",
+    ),
+];
+
+#[derive(Demo)]
+#[demo(name = "async-runtime-demo", description = "A from-scratch executor and epoll reactor",
+       quiz = QUIZ)]
+pub struct AsyncRuntimeDemo;
+
+impl AsyncRuntimeDemo {
+    fn demonstrate(&self, _config: &Config) -> DemoReport {
+        output::title("⚙️  Mini Async Runtime Demo");
+        outln!("What tokio does, in a few hundred lines: futures, wakers, and epoll.\n");
+
+        demonstrate_architecture();
+        let mut report = DemoReport::default();
+        demonstrate_echo_server(&mut report);
+        demonstrate_idle_connections(&mut report);
+
+        output::takeaways();
+        outln!("• A future is a state machine; a task is a boxed future plus a way to wake it");
+        outln!("• The reactor maps kernel readiness events (epoll) back to task wakers");
+        outln!("• One thread can serve thousands of connections because waiting costs no thread");
+        outln!("• Idle async connections cost kilobytes; idle threads cost stacks and scheduler state");
+        report
+    }
+}
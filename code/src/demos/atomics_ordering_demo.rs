@@ -0,0 +1,312 @@
+//! Atomics Memory Ordering Demo
+//!
+//! Runs classic litmus tests (message passing, store buffering) under
+//! Relaxed, Acquire/Release and SeqCst and counts how often the "impossible"
+//! outcomes actually show up on this machine.
+//! Run with: cargo run --release --bin atomics-ordering-demo
+
+use std::hint;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Instant;
+
+use crate::demo::{Config, Demo, DemoReport};
+use crate::quiz::Question;
+use crate::{outln, output};
+
+/// Rounds per litmus test, unless the config sets `iterations`
+const ITERATIONS: usize = 100_000;
+
+/// Shared state for one litmus test. Two worker threads run one round each
+/// time `round` is bumped, report their observations, then wait again.
+struct Litmus {
+    x: AtomicU64,
+    y: AtomicU64,
+    r1: AtomicU64,
+    r2: AtomicU64,
+    round: AtomicUsize,
+    done: AtomicUsize,
+    stop: AtomicBool,
+}
+
+impl Litmus {
+    fn new() -> Self {
+        Litmus {
+            x: AtomicU64::new(0),
+            y: AtomicU64::new(0),
+            r1: AtomicU64::new(0),
+            r2: AtomicU64::new(0),
+            round: AtomicUsize::new(0),
+            done: AtomicUsize::new(0),
+            stop: AtomicBool::new(false),
+        }
+    }
+}
+
+/// Spin briefly, then give the core away - keeps the demo usable when there
+/// are fewer cores than spinning threads
+fn backoff(spins: &mut u32) {
+    if *spins < 100 {
+        *spins += 1;
+        hint::spin_loop();
+    } else {
+        thread::yield_now();
+    }
+}
+
+/// Spin until the coordinator starts the next round; returns false on shutdown
+fn wait_for_round(litmus: &Litmus, seen: &mut usize) -> bool {
+    let mut spins = 0;
+    loop {
+        if litmus.stop.load(Ordering::SeqCst) {
+            return false;
+        }
+        let round = litmus.round.load(Ordering::SeqCst);
+        if round != *seen {
+            *seen = round;
+            return true;
+        }
+        backoff(&mut spins);
+    }
+}
+
+/// Run `iterations` rounds of a two-thread litmus test and count how many
+/// rounds ended in a state accepted by `is_anomaly(r1, r2)`.
+fn run_litmus(
+    iterations: usize,
+    thread_a: impl Fn(&Litmus) + Send + 'static,
+    thread_b: impl Fn(&Litmus) + Send + 'static,
+    is_anomaly: impl Fn(u64, u64) -> bool,
+) -> usize {
+    let litmus = Arc::new(Litmus::new());
+
+    let spawn = |body: Box<dyn Fn(&Litmus) + Send>| {
+        let litmus = Arc::clone(&litmus);
+        thread::spawn(move || {
+            let mut seen = 0;
+            while wait_for_round(&litmus, &mut seen) {
+                body(&litmus);
+                litmus.done.fetch_add(1, Ordering::SeqCst);
+            }
+        })
+    };
+    let a = spawn(Box::new(thread_a));
+    let b = spawn(Box::new(thread_b));
+
+    let mut anomalies = 0;
+    for _ in 0..iterations {
+        litmus.x.store(0, Ordering::SeqCst);
+        litmus.y.store(0, Ordering::SeqCst);
+        litmus.r1.store(0, Ordering::SeqCst);
+        litmus.r2.store(0, Ordering::SeqCst);
+        litmus.done.store(0, Ordering::SeqCst);
+        litmus.round.fetch_add(1, Ordering::SeqCst);
+
+        let mut spins = 0;
+        while litmus.done.load(Ordering::SeqCst) < 2 {
+            backoff(&mut spins);
+        }
+        if is_anomaly(litmus.r1.load(Ordering::SeqCst), litmus.r2.load(Ordering::SeqCst)) {
+            anomalies += 1;
+        }
+    }
+
+    litmus.stop.store(true, Ordering::SeqCst);
+    a.join().unwrap();
+    b.join().unwrap();
+    anomalies
+}
+
+/// Message passing: A writes data then raises a flag, B waits for the flag
+/// then reads data. Anomaly: B saw the flag but stale data.
+fn message_passing(store_order: Ordering, load_order: Ordering, iterations: usize) -> usize {
+    run_litmus(
+        iterations,
+        move |l| {
+            l.x.store(42, Ordering::Relaxed); // data
+            l.y.store(1, store_order); // flag
+        },
+        move |l| {
+            let mut spins = 0;
+            while l.y.load(load_order) == 0 {
+                backoff(&mut spins);
+            }
+            l.r2.store(l.x.load(Ordering::Relaxed), Ordering::Relaxed);
+        },
+        |_, data| data != 42,
+    )
+}
+
+/// Store buffering (Dekker-style flags): each thread raises its own flag and
+/// then checks the other one. Anomaly: both threads saw the other flag down,
+/// so both would enter the "critical section".
+fn store_buffering(store_order: Ordering, load_order: Ordering, iterations: usize) -> usize {
+    run_litmus(
+        iterations,
+        move |l| {
+            l.x.store(1, store_order);
+            l.r1.store(l.y.load(load_order), Ordering::Relaxed);
+        },
+        move |l| {
+            l.y.store(1, store_order);
+            l.r2.store(l.x.load(load_order), Ordering::Relaxed);
+        },
+        |r1, r2| r1 == 0 && r2 == 0,
+    )
+}
+
+/// Print one result row and record it in the report
+fn report_row(
+    report: &mut DemoReport,
+    test: &str,
+    ordering: &str,
+    anomalies: usize,
+    iterations: usize,
+    elapsed: std::time::Duration,
+) {
+    outln!(
+        "  {:<18} {:<16} {:>9} / {:<9} ({:>6.3}%)  {:?}",
+        test,
+        ordering,
+        anomalies,
+        iterations,
+        anomalies as f64 * 100.0 / iterations as f64,
+        elapsed
+    );
+    report
+        .record(format!("{} anomalies", test), anomalies as f64, "anomalies")
+        .param("ordering", ordering)
+        .param("iterations", iterations);
+    report.record_time(test, elapsed).param("ordering", ordering).param("iterations", iterations);
+}
+
+fn demonstrate_message_passing(iterations: usize, report: &mut DemoReport) {
+    output::heading("📨 Message Passing: data + ready flag");
+    outln!("Thread A: DATA = 42; FLAG = 1");
+    outln!("Thread B: while FLAG == 0 {{}}; read DATA");
+    outln!("Anomaly: B sees FLAG == 1 but DATA == 0\n");
+
+    let cases = [
+        ("Relaxed", Ordering::Relaxed, Ordering::Relaxed),
+        ("Release/Acquire", Ordering::Release, Ordering::Acquire),
+        ("SeqCst", Ordering::SeqCst, Ordering::SeqCst),
+    ];
+    for (name, store, load) in cases {
+        let start = Instant::now();
+        let anomalies = message_passing(store, load, iterations);
+        report_row(report, "message passing", name, anomalies, iterations, start.elapsed());
+    }
+
+    outln!();
+    outln!("Release on the flag store 'publishes' every write before it;");
+    outln!("Acquire on the flag load makes those writes visible after it.");
+    if cfg!(any(target_arch = "x86", target_arch = "x86_64")) {
+        outln!("⚠️  x86 never reorders stores with stores, so Relaxed usually *looks* fine here.");
+        outln!("   The compiler is still allowed to reorder, and ARM/POWER hardware will.");
+    } else {
+        outln!("This CPU has a weak memory model - Relaxed anomalies are real hardware behavior.");
+    }
+    outln!();
+}
+
+fn demonstrate_store_buffering(iterations: usize, report: &mut DemoReport) {
+    output::heading("🚩 Store Buffering: two threads, two flags");
+    outln!("Thread A: X = 1; r1 = Y");
+    outln!("Thread B: Y = 1; r2 = X");
+    outln!("Anomaly: r1 == 0 && r2 == 0 (each thread missed the other's store)\n");
+
+    let cases = [
+        ("Relaxed", Ordering::Relaxed, Ordering::Relaxed),
+        ("Release/Acquire", Ordering::Release, Ordering::Acquire),
+        ("SeqCst", Ordering::SeqCst, Ordering::SeqCst),
+    ];
+    for (name, store, load) in cases {
+        let start = Instant::now();
+        let anomalies = store_buffering(store, load, iterations);
+        report_row(report, "store buffering", name, anomalies, iterations, start.elapsed());
+    }
+
+    outln!();
+    outln!("Every core has a store buffer: a store can sit there while the");
+    outln!("core's next load already reads from cache. Release/Acquire does NOT");
+    outln!("forbid this (a store followed by a load to a *different* address may");
+    outln!("reorder). Only SeqCst adds the full fence (MFENCE / XCHG on x86) that");
+    outln!("drains the store buffer, so r1 == r2 == 0 disappears.\n");
+}
+
+fn demonstrate_ordering_guarantees() {
+    output::heading("📜 What Each Ordering Guarantees");
+    outln!("  Relaxed : atomicity only - no ordering with other memory operations");
+    outln!("            (fine for counters and statistics)");
+    outln!("  Release : earlier reads/writes can't move after this store");
+    outln!("  Acquire : later reads/writes can't move before this load");
+    outln!("            (Release store + Acquire load = happens-before edge)");
+    outln!("  AcqRel  : both, for read-modify-write ops like fetch_add / swap");
+    outln!("  SeqCst  : Acquire/Release plus one global order of all SeqCst ops");
+    outln!("            (needed for Dekker / store-buffering style protocols)");
+    outln!();
+    outln!("Running on {} with {} hardware threads",
+             std::env::consts::ARCH,
+             thread::available_parallelism().map(|n| n.get()).unwrap_or(1));
+    outln!();
+}
+
+const QUIZ: &[Question] = &[
+    Question::new(
+        "In message passing, what stops the reader from seeing the flag set but the data stale?",
+        &[
+            "Relaxed on both sides",
+            "A Release store of the flag paired with an Acquire load of it",
+            "Declaring the data volatile",
+        ],
+        1,
+        "Release publishes every write before it to whoever Acquires the value it stored.",
+    ),
+    Question::new(
+        "Which orderings forbid the store-buffering anomaly (both threads read the old value)?",
+        &["Relaxed", "Release on the stores and Acquire on the loads", "SeqCst on all four operations"],
+        2,
+        "Store-to-load reordering is allowed by Acquire/Release; only a single total order (SeqCst) rules it out.",
+    ),
+    Question::new(
+        "A Relaxed version showed zero anomalies on an x86 machine. Is it correct?",
+        &[
+            "Yes, the test proves it",
+            "Not necessarily: x86's strong memory model hides reorderings that ARM will show",
+            "Only if it ran a million times",
+        ],
+        1,
+        "Zero anomalies in a run is evidence about this hardware, not a proof about the memory model.",
+    ),
+];
+
+#[derive(Demo)]
+#[demo(name = "atomics-ordering-demo", description = "Litmus tests under Relaxed, Acquire/Release and SeqCst",
+       quiz = QUIZ)]
+pub struct AtomicsOrderingDemo;
+
+impl AtomicsOrderingDemo {
+    fn demonstrate(&self, config: &Config) -> DemoReport {
+        output::title("⚛️  Atomics & Memory Ordering Demo");
+        outln!("Counting how often weak orderings produce surprising results.\n");
+
+        if thread::available_parallelism().map(|n| n.get()).unwrap_or(1) < 2 {
+            outln!("⚠️  Only one hardware thread available - anomalies need true parallelism.\n");
+        }
+
+        demonstrate_ordering_guarantees();
+        let iterations = config.iterations.unwrap_or(ITERATIONS);
+        let mut report = DemoReport::default();
+        demonstrate_message_passing(iterations, &mut report);
+        demonstrate_store_buffering(iterations, &mut report);
+
+        output::takeaways();
+        outln!("• Memory ordering is about what OTHER threads can observe, and when");
+        outln!("• Release/Acquire pairs are enough for publishing data behind a flag");
+        outln!("• Store→load reordering (store buffers) needs SeqCst to forbid");
+        outln!("• 'It never failed on my x86 laptop' proves nothing on ARM");
+        outln!("• Zero anomalies in a test run is evidence, not a proof");
+        report
+    }
+}
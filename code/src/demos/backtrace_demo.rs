@@ -0,0 +1,405 @@
+//! Backtrace Demo
+//!
+//! A backtrace is the chain of return addresses on the stack, turned back
+//! into names. This demo captures its own stack three levels down, two
+//! ways: by walking frame pointers (each frame saves the caller's frame
+//! pointer next to its return address, a linked list through the stack)
+//! and with `std::backtrace`, which unwinds with the `.eh_frame` tables
+//! instead. It symbolicates the raw addresses itself against the ELF
+//! symbol table, then compares with what std prints, and shows what the
+//! compiler's choices do to the picture: an inlined function has no frame
+//! of its own, and an optimized build may keep no frame pointers at all.
+//! Run with: cargo run --bin backtrace-demo (and again with --release)
+
+use std::arch::asm;
+use std::backtrace::Backtrace;
+use std::hint::black_box;
+use std::time::Instant;
+
+use crate::demo::{Config, Demo, DemoReport};
+use crate::elf::{Elf, Symbol};
+use crate::output::{Style, Table};
+use crate::quiz::Question;
+use crate::verify::Verification;
+use crate::{outln, output};
+
+/// Frames a frame-pointer walk follows at most
+const MAX_FRAMES: usize = 64;
+
+/// Frames of std's backtrace printed before eliding the rest
+const SHOWN_FRAMES: usize = 12;
+
+/// The build's opt-level, as build.rs saw it
+const OPT_LEVEL: &str = env!("SYSTEMS_DEMOS_OPT_LEVEL");
+
+/// The stack as both walkers saw it, from the innermost frame out
+struct Capture {
+    /// Return addresses found by following frame pointers
+    walked: Vec<usize>,
+    backtrace: Backtrace,
+}
+
+/// The outermost of three frames; `no_mangle` keeps the names predictable
+/// in every build
+#[unsafe(no_mangle)]
+#[inline(never)]
+fn backtrace_outer(depth: u32) -> Capture {
+    let capture = backtrace_middle(black_box(depth + 1));
+    black_box(depth);
+    capture
+}
+
+#[unsafe(no_mangle)]
+#[inline(never)]
+fn backtrace_middle(depth: u32) -> Capture {
+    let capture = inlined_step(black_box(depth + 1));
+    black_box(depth);
+    capture
+}
+
+/// Always inlined into `backtrace_middle`: its code is there, its frame
+/// isn't
+#[inline(always)]
+fn inlined_step(depth: u32) -> Capture {
+    let capture = backtrace_inner(black_box(depth + 1));
+    black_box(depth);
+    capture
+}
+
+#[unsafe(no_mangle)]
+#[inline(never)]
+fn backtrace_inner(depth: u32) -> Capture {
+    let walked = stack_top().map_or(Vec::new(), walk_frame_pointers);
+    let backtrace = Backtrace::force_capture();
+    black_box(depth);
+    Capture { walked, backtrace }
+}
+
+/// This function's frame pointer: `rbp` on x86-64, `x29` on AArch64
+#[inline(always)]
+fn frame_pointer() -> usize {
+    let fp: usize;
+    // Safety: copies a register, touching no memory
+    #[cfg(target_arch = "x86_64")]
+    unsafe {
+        asm!("mov {}, rbp", out(reg) fp, options(nomem, nostack, preserves_flags));
+    }
+    #[cfg(target_arch = "aarch64")]
+    unsafe {
+        asm!("mov {}, x29", out(reg) fp, options(nomem, nostack, preserves_flags));
+    }
+    fp
+}
+
+fn stack_pointer() -> usize {
+    let marker = 0u8;
+    black_box(&marker) as *const u8 as usize
+}
+
+/// The top (highest address) of this thread's stack
+fn stack_top() -> Option<usize> {
+    // Safety: pthread_attr_t is filled in by pthread_getattr_np before use
+    // and destroyed after; the out-parameters are plain integers
+    unsafe {
+        let mut attr: libc::pthread_attr_t = std::mem::zeroed();
+        if libc::pthread_getattr_np(libc::pthread_self(), &mut attr) != 0 {
+            return None;
+        }
+        let (mut low, mut size) = (std::ptr::null_mut(), 0);
+        let ok = libc::pthread_attr_getstack(&attr, &mut low, &mut size) == 0;
+        libc::pthread_attr_destroy(&mut attr);
+        ok.then_some(low as usize + size)
+    }
+}
+
+/// Follow the chain of saved frame pointers: `[fp]` is the caller's frame
+/// pointer, `[fp + 8]` the return address into it. Stops at the first link
+/// that doesn't point further up this thread's stack, which is where the
+/// chain ends, or where a function that keeps no frame pointer broke it
+#[inline(never)]
+fn walk_frame_pointers(high: usize) -> Vec<usize> {
+    let low = stack_pointer();
+    let word = size_of::<usize>();
+    let mut fp = frame_pointer();
+    let mut addresses = Vec::new();
+    while addresses.len() < MAX_FRAMES && fp >= low && fp + 2 * word <= high && fp.is_multiple_of(word) {
+        // Safety: fp lies between this frame and the top of the stack,
+        // memory that is mapped and readable
+        let (next, ret) = unsafe { (*(fp as *const usize), *((fp + word) as *const usize)) };
+        if ret == 0 {
+            break;
+        }
+        addresses.push(ret);
+        if next <= fp {
+            break;
+        }
+        fp = next;
+    }
+    addresses
+}
+
+/// Function symbols of our own executable, sorted by address, and the
+/// offset it was loaded at
+struct Symbolizer {
+    functions: Vec<Symbol>,
+    bias: usize,
+}
+
+impl Symbolizer {
+    fn new() -> Option<Symbolizer> {
+        let elf = Elf::open(std::env::current_exe().ok()?).ok()?;
+        let mut functions: Vec<Symbol> = elf.symbols().ok()?.into_iter()
+            .filter(|symbol| symbol.is_function() && symbol.size > 0)
+            .collect();
+        functions.sort_by_key(|symbol| symbol.address);
+        let anchor = functions.iter().find(|symbol| symbol.name == "backtrace_inner")?;
+        let bias = (backtrace_inner as *const () as usize).wrapping_sub(anchor.address as usize);
+        Some(Symbolizer { functions, bias })
+    }
+
+    /// The function containing `address`, and the offset into it
+    fn lookup(&self, address: usize) -> Option<(&Symbol, usize)> {
+        let address = address.wrapping_sub(self.bias) as u64;
+        let index = self.functions.partition_point(|symbol| symbol.address <= address).checked_sub(1)?;
+        let symbol = &self.functions[index];
+        (address < symbol.address + symbol.size).then(|| (symbol, (address - symbol.address) as usize))
+    }
+
+    /// A return address names the instruction after the call: step back
+    /// one byte to land in the call itself
+    fn name_of_return(&self, address: usize) -> Option<String> {
+        self.lookup(address - 1).map(|(symbol, _)| symbol.demangled())
+    }
+}
+
+/// Function names in std's rendering of a backtrace, innermost first,
+/// each with its source location if debug info supplied one
+fn backtrace_frames(backtrace: &Backtrace) -> Vec<(String, Option<String>)> {
+    let mut frames: Vec<(String, Option<String>)> = Vec::new();
+    for line in backtrace.to_string().lines().map(str::trim) {
+        if let Some(location) = line.strip_prefix("at ") {
+            if let Some(frame) = frames.last_mut() {
+                frame.1.get_or_insert_with(|| location.to_string());
+            }
+        } else if let Some((index, name)) = line.split_once(": ")
+            && index.chars().all(|c| c.is_ascii_digit()) {
+            frames.push((name.to_string(), None));
+        }
+    }
+    frames
+}
+
+fn short(name: &str) -> String {
+    let name = name.strip_prefix("systems_demos::demos::").unwrap_or(name);
+    if name.chars().count() > 60 { format!("{}…", name.chars().take(59).collect::<String>()) } else { name.to_string() }
+}
+
+fn location(location: &Option<String>) -> String {
+    location.as_deref().map_or(String::new(), |at| {
+        let at = at.rsplit_once("/src/").map_or(at, |(_, rest)| rest);
+        format!("src/{}", at)
+    })
+}
+
+fn demonstrate_walk(capture: &Capture, symbolizer: Option<&Symbolizer>) {
+    output::heading("🪜 Walking Frame Pointers");
+    outln!("Each call pushes a return address; a function that keeps a frame pointer");
+    outln!("then pushes the caller's frame pointer and points its own at it. Starting");
+    outln!("from the current frame pointer, the chain reads back like a linked list:\n");
+    let mut table = Table::new(["#", "return address", "symbol (from .symtab)"]).left(2);
+    for (i, &address) in capture.walked.iter().enumerate() {
+        let name = symbolizer.and_then(|symbolizer| symbolizer.name_of_return(address));
+        table.row([i.to_string(), format!("{:#x}", address), name.map_or("(not in this executable)".to_string(), |name| short(&name))]);
+    }
+    outln!("{}", table);
+    let found = symbolizer.map_or(0, |symbolizer| named_frames(symbolizer, &capture.walked).len());
+    if found >= 3 {
+        outln!("{} frames, each return address looked up in the symbol table: the", found);
+        outln!("address minus the load bias, then the function whose range holds it.");
+    } else {
+        outln!("{}", Style::Slower.paint("The walk never found backtrace_inner, or any of our frames."));
+        outln!("rustc omits frame pointers by default on this target, at every opt-level:");
+        outln!("none of our functions set rbp up, so it still held whatever the nearest");
+        outln!("caller that did (if any) left there, and the walk started from that frame,");
+        outln!("skipping everything in between. Rebuild with RUSTFLAGS=\"-C");
+        outln!("force-frame-pointers=yes\" to keep the chain; std's unwinder doesn't need it.");
+    }
+    outln!();
+}
+
+/// The names the walk resolved, up to the first it couldn't; none unless
+/// it began where it must, returning into `backtrace_inner`
+fn named_frames(symbolizer: &Symbolizer, walked: &[usize]) -> Vec<String> {
+    let names: Vec<String> = walked.iter().map_while(|&address| symbolizer.name_of_return(address)).collect();
+    if names.first().is_some_and(|name| name == "backtrace_inner") { names } else { Vec::new() }
+}
+
+fn demonstrate_std(capture: &Capture) {
+    output::heading("📜 std::backtrace: Unwinding with .eh_frame");
+    let frames = backtrace_frames(&capture.backtrace);
+    let start = frames.iter().position(|(name, _)| name.contains("backtrace_inner")).unwrap_or(0);
+    let mut table = Table::new(["#", "function", "location"]).left(1).left(2);
+    for (i, (name, at)) in frames.iter().enumerate().skip(start).take(SHOWN_FRAMES) {
+        let name = if name.contains("inlined_step") { Style::Faster.paint(short(name)).to_string() } else { short(name) };
+        table.row([i.to_string(), name, location(at)]);
+    }
+    outln!("{}", table);
+    if frames.len() > start + SHOWN_FRAMES {
+        outln!("... {} more, out to the runtime's start-up code", frames.len() - start - SHOWN_FRAMES);
+    }
+    outln!("The unwinder doesn't trust rbp: for each return address it finds the");
+    outln!("function's .eh_frame entry, the table of where that function keeps its");
+    outln!("return address and saved registers at every instruction, and computes the");
+    outln!("caller's frame from it. The same tables let a panic unwind and run Drops.\n");
+}
+
+fn demonstrate_inlining(capture: &Capture, symbolizer: Option<&Symbolizer>, has_debug_info: bool) {
+    output::heading("🫥 Where Did inlined_step Go?");
+    let frames = backtrace_frames(&capture.backtrace);
+    let std_sees = frames.iter().any(|(name, _)| name.contains("inlined_step"));
+    let symtab_sees = symbolizer.is_some_and(|symbolizer| symbolizer.functions.iter().any(|s| s.demangled().contains("inlined_step")));
+    outln!("The source calls outer → middle → inlined_step → inner. inlined_step is");
+    outln!("#[inline(always)]: its body was pasted into middle, so there is no call, no");
+    outln!("return address and no frame for it. Who still reports it?\n");
+    let yes_no = |seen: bool| if seen { Style::Faster.paint("yes").to_string() } else { Style::Slower.paint("no").to_string() };
+    let mut table = Table::new(["", "inlined_step shown?"]).left(0);
+    table.row(["symbol table (.symtab)".to_string(), yes_no(symtab_sees)]);
+    table.row([format!("std::backtrace ({})", if has_debug_info { "with debug info" } else { "no debug info" }), yes_no(std_sees)]);
+    outln!("{}", table);
+    outln!("The symbol table only knows real functions. DWARF debug info also records");
+    outln!("which address ranges came from which inlined call, so with it std can");
+    outln!("list the inlined frame (a debugger does the same); without it, the frame");
+    outln!("silently vanishes and middle appears to call inner directly.");
+    outln!("This build: opt-level {}, debug info {}. Optimized builds inline far more", OPT_LEVEL,
+           if has_debug_info { "present" } else { "absent" });
+    outln!("than this one attribute, and may turn a call in tail position into a jump,");
+    outln!("so whole frames go missing; `debug = \"line-tables-only\"` in a release");
+    outln!("profile keeps names and lines for backtraces at a fraction of full debug info.\n");
+}
+
+fn demonstrate_cost(report: &mut DemoReport) {
+    output::heading("⏱️  What a Backtrace Costs");
+    let Some(top) = stack_top() else { return };
+    let start = Instant::now();
+    let walked = walk_frame_pointers(top);
+    let walk = start.elapsed();
+    let start = Instant::now();
+    let backtrace = Backtrace::force_capture();
+    let capture = start.elapsed();
+    let start = Instant::now();
+    let rendered = backtrace.to_string();
+    let resolve = start.elapsed();
+    let start = Instant::now();
+    black_box(Backtrace::force_capture().to_string());
+    let again = start.elapsed();
+    let mut table = Table::new(["step", "time"]).left(0);
+    table.row([format!("frame-pointer walk ({} frames)", walked.len()), format!("{:.1?}", walk)]);
+    table.row(["unwind with .eh_frame".to_string(), format!("{:.1?}", capture)]);
+    table.row(["symbolicate (first time)".to_string(), format!("{:.1?}", resolve)]);
+    table.row(["capture + symbolicate again".to_string(), format!("{:.1?}", again)]);
+    outln!("{}", table);
+    outln!("Walking frame pointers is a few loads per frame, which is why profilers");
+    outln!("that sample thousands of stacks a second want them. Unwinding interprets");
+    outln!("tables, and symbolicating the first time parses the symbol table and");
+    outln!("debug info ({} characters of output here): fine for a panic, too slow for", rendered.len());
+    outln!("a hot path. RUST_BACKTRACE=1 only turns the printing on.\n");
+    report.record_time("frame-pointer walk", walk);
+    report.record_time("unwind", capture);
+    report.record_time("first symbolication", resolve);
+}
+
+/// The captured stacks name our frames in call order
+fn verify() -> Verification {
+    let mut verification = Verification::new();
+    let capture = backtrace_outer(0);
+    let names: Vec<String> = backtrace_frames(&capture.backtrace).into_iter().map(|(name, _)| name).collect();
+    let position = |wanted: &str| names.iter().position(|name| name.ends_with(wanted));
+    let (inner, middle, outer) = (position("backtrace_inner"), position("backtrace_middle"), position("backtrace_outer"));
+    verification.check("std's backtrace lists inner, middle, outer in that order",
+                       inner.is_some() && inner < middle && middle < outer, || format!("{:?}", names));
+
+    let Some(symbolizer) = Symbolizer::new() else {
+        verification.skip("the symbol table names our functions", "no symbol table (stripped?)");
+        return verification;
+    };
+    let inner_at = backtrace_inner as *const () as usize;
+    verification.equal("a function's address symbolicates to its name",
+                       symbolizer.lookup(inner_at + 1).map(|(symbol, _)| symbol.name.clone()),
+                       Some("backtrace_inner".to_string()));
+    verification.check("an always-inlined function has no symbol of its own",
+                       !symbolizer.functions.iter().any(|symbol| symbol.demangled().contains("inlined_step")),
+                       || "inlined_step is in .symtab".to_string());
+
+    let walked = named_frames(&symbolizer, &capture.walked);
+    if walked.len() >= 3 {
+        let chain: Vec<&str> = walked.iter().map(|name| name.as_str())
+            .filter(|name| name.starts_with("backtrace_")).take(3).collect();
+        verification.equal("the frame-pointer chain returns into inner, middle, outer", chain,
+                           vec!["backtrace_inner", "backtrace_middle", "backtrace_outer"]);
+    } else {
+        verification.skip("the frame-pointer chain returns into inner, middle, outer",
+                          "this build omits frame pointers");
+    }
+    verification
+}
+
+const QUIZ: &[Question] = &[
+    Question::new(
+        "What does a frame-pointer walk follow?",
+        &[
+            "The .eh_frame tables",
+            "Saved frame pointers: each frame stores the caller's, next to its return address",
+            "The symbol table",
+        ],
+        1,
+        "It's a linked list through the stack, which breaks at any function compiled without frame pointers.",
+    ),
+    Question::new(
+        "Why can a release build's backtrace skip a function that the source clearly calls?",
+        &["Backtraces are sampled", "The function was inlined: it has no frame, and without debug info no record", "Release builds strip every symbol"],
+        1,
+        "Debug info records inlined ranges; the symbol table doesn't.",
+    ),
+    Question::new(
+        "How does a symbolizer turn a return address into a function name?",
+        &[
+            "It reads the name from the stack",
+            "It subtracts the load bias and finds the symbol whose address range contains it",
+            "It asks the kernel",
+        ],
+        1,
+        "The stack holds only addresses; names come from the file on disk.",
+    ),
+];
+
+#[derive(Demo)]
+#[demo(name = "backtrace-demo", description = "Capture and symbolicate our own stack: frame pointers, unwinding, inlining",
+       quiz = QUIZ, verify = verify)]
+pub struct BacktraceDemo;
+
+impl BacktraceDemo {
+    fn demonstrate(&self, _config: &Config) -> DemoReport {
+        output::title("🧵 Backtrace Demo");
+        outln!("Capturing this program's own stack and turning the addresses back into names.\n");
+        let mut report = DemoReport::default();
+
+        let capture = backtrace_outer(0);
+        let symbolizer = Symbolizer::new();
+        let has_debug_info = std::env::current_exe().ok().and_then(|exe| Elf::open(exe).ok())
+            .is_some_and(|elf| elf.section(".debug_info").is_some());
+        if symbolizer.is_none() {
+            outln!("{}\n", Style::Slower.paint("No symbol table in this executable (stripped?): addresses stay addresses."));
+        }
+        demonstrate_walk(&capture, symbolizer.as_ref());
+        demonstrate_std(&capture);
+        demonstrate_inlining(&capture, symbolizer.as_ref(), has_debug_info);
+        demonstrate_cost(&mut report);
+
+        output::takeaways();
+        outln!("• A stack is return addresses; names come from the executable's symbol table and debug info");
+        outln!("• Frame-pointer walking is cheap but needs every function to keep a frame pointer");
+        outln!("• Unwinders use .eh_frame tables instead, the same ones panics unwind with");
+        outln!("• Inlined functions have no frame: only debug info can put them back in a backtrace");
+        report
+    }
+}
@@ -0,0 +1,385 @@
+//! Binary Inspector Demo
+//!
+//! The executable running this demo is a file, and the demo opens it: the
+//! ELF header, the segments the kernel maps into memory, the sections the
+//! linker laid out (`.text`, `.rodata`, `.data`, `.bss`), and the symbol
+//! table naming every function and static, all read with the crate's own
+//! `elf` module rather than `readelf` or `nm`. Then it checks the file
+//! against the running process: a function's address in memory is its
+//! address in the file plus one load offset, and the bytes there are the
+//! bytes on disk.
+//! Run with: cargo run --bin binary-inspector-demo
+
+use std::slice;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::demo::{Config, Demo, DemoReport};
+use crate::elf::{self, Elf, ElfError, Symbol, ET_DYN, ET_EXEC, PT_LOAD, SHF_EXECINSTR, SHF_WRITE};
+use crate::output::Table;
+use crate::plot::format_bytes;
+use crate::quiz::Question;
+use crate::verify::Verification;
+use crate::{outln, output};
+
+/// A function to find in the symbol table: `no_mangle` gives it a name
+/// we can predict, `inline(never)` a body of its own
+#[unsafe(no_mangle)]
+#[inline(never)]
+pub fn binary_inspector_anchor(x: u64) -> u64 {
+    x.wrapping_mul(0x9E37_79B9_7F4A_7C15).rotate_left(17)
+}
+
+/// Initialized and writable: `.data`
+#[unsafe(no_mangle)]
+pub static BINARY_INSPECTOR_DATA: AtomicU64 = AtomicU64::new(0x5EED);
+
+/// Zero and writable: `.bss`, which takes no space in the file
+#[unsafe(no_mangle)]
+pub static BINARY_INSPECTOR_BSS: AtomicU64 = AtomicU64::new(0);
+
+/// Immutable: `.rodata`
+#[unsafe(no_mangle)]
+pub static BINARY_INSPECTOR_RODATA: [u8; 16] = *b"read-only bytes!";
+
+/// Sections worth a row, in the order the linker usually places them
+const SECTIONS: &[&str] = &[".text", ".rodata", ".eh_frame", ".data.rel.ro", ".data", ".bss", ".symtab", ".strtab"];
+
+/// Functions whose size the demo looks up by name
+const FUNCTIONS: &[&str] = &["main", "binary_inspector_anchor", "systems_demos::elf::Elf::parse",
+                             "systems_demos::elf::demangle"];
+
+/// Bytes of the anchor compared between the file and memory
+const COMPARED: usize = 16;
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect::<Vec<_>>().join(" ")
+}
+
+fn shorten(name: &str, width: usize) -> String {
+    if name.chars().count() <= width {
+        name.to_string()
+    } else {
+        format!("{}…", name.chars().take(width - 1).collect::<String>())
+    }
+}
+
+fn find<'a>(symbols: &'a [Symbol], name: &str) -> Option<&'a Symbol> {
+    symbols.iter().find(|symbol| symbol.size > 0 && (symbol.name == name || symbol.demangled() == name))
+}
+
+/// Runtime address minus address in the file: where the kernel put a
+/// position-independent executable (0 for one linked at a fixed address)
+fn load_bias(symbols: &[Symbol]) -> Option<u64> {
+    let anchor = find(symbols, "binary_inspector_anchor")?;
+    Some((binary_inspector_anchor as *const () as u64).wrapping_sub(anchor.address))
+}
+
+/// The anchor's first bytes as the CPU sees them
+fn anchor_in_memory(len: usize) -> Vec<u8> {
+    // Safety: the function's code is mapped readable (r-x) for as long as
+    // the program runs, and is at least `len` bytes (checked by the caller
+    // against the symbol's size)
+    unsafe { slice::from_raw_parts(binary_inspector_anchor as *const u8, len).to_vec() }
+}
+
+/// The `/proc/self/maps` lines for the executable's file
+#[cfg(target_os = "linux")]
+fn executable_mappings(anchor: u64) -> Vec<String> {
+    let Ok(maps) = std::fs::read_to_string("/proc/self/maps") else { return Vec::new() };
+    let contains = |line: &str| {
+        let range = line.split_whitespace().next().unwrap_or("");
+        let (start, end) = range.split_once('-').unwrap_or(("", ""));
+        match (u64::from_str_radix(start, 16), u64::from_str_radix(end, 16)) {
+            (Ok(start), Ok(end)) => (start..end).contains(&anchor),
+            _ => false,
+        }
+    };
+    let Some(path) = maps.lines().find(|line| contains(line)).and_then(|line| line.split_whitespace().nth(5)) else {
+        return Vec::new();
+    };
+    maps.lines().filter(|line| line.split_whitespace().nth(5) == Some(path)).map(str::to_string).collect()
+}
+
+fn demonstrate_header(elf: &Elf, path: &str) {
+    output::heading("📋 The ELF Header");
+    outln!("{} ({}B)", path, format_bytes(elf.data().len() as f64));
+    outln!("  magic       {}  (\\x7fELF)", hex(&elf.data()[..4]));
+    outln!("  class       64-bit, {}-endian", if elf.is_big_endian() { "big" } else { "little" });
+    outln!("  type        {}", match elf.kind {
+        ET_EXEC => "EXEC: linked to run at a fixed address",
+        ET_DYN => "DYN: position-independent, loaded at a random address (ASLR)",
+        _ => "other",
+    });
+    outln!("  machine     {} ({})", elf.machine, elf::machine_name(elf.machine));
+    outln!("  entry       {:#x}, where the kernel starts the program (libc's _start, not main)", elf.entry);
+    outln!("  tables      {} program headers, {} section headers\n", elf.segments.len(), elf.sections.len());
+}
+
+fn demonstrate_segments(elf: &Elf) {
+    output::heading("🗺️  Segments: What the Kernel Maps");
+    let mut table = Table::new(["perm", "file offset", "file size", "address", "memory size", "sections"]).left(0).left(5);
+    for segment in elf.segments.iter().filter(|segment| segment.kind == PT_LOAD) {
+        let sections: Vec<&str> = elf.sections.iter()
+            .filter(|section| section.is_loaded() && section.size > 0 && segment.contains(section.address))
+            .map(|section| section.name.as_str())
+            .collect();
+        table.row([
+            segment.permissions(),
+            format!("{:#x}", segment.offset),
+            format!("{}B", format_bytes(segment.file_size as f64)),
+            format!("{:#x}", segment.address),
+            format!("{}B", format_bytes(segment.memory_size as f64)),
+            shorten(&sections.join(" "), 40),
+        ]);
+    }
+    outln!("{}", table);
+    outln!("execve maps each LOAD segment with mmap: file pages, with the segment's");
+    outln!("permissions, at the address it names. Code is read and execute, never");
+    outln!("write; constants are read-only; statics are read-write. Where a segment's");
+    outln!("memory size outgrows its file size, the rest is zero-filled: that is .bss.\n");
+}
+
+fn demonstrate_sections(elf: &Elf) {
+    output::heading("📑 Sections: What the Linker Laid Out");
+    let mut table = Table::new(["section", "size", "address", "in file", "flags"]).left(0).left(4);
+    for section in SECTIONS.iter().filter_map(|name| elf.section(name)) {
+        let mut flags = Vec::new();
+        if section.flags & SHF_EXECINSTR != 0 {
+            flags.push("code");
+        }
+        if section.flags & SHF_WRITE != 0 {
+            flags.push("writable");
+        }
+        if !section.is_loaded() {
+            flags.push("not loaded");
+        }
+        table.row([
+            section.name.clone(),
+            format!("{}B", format_bytes(section.size as f64)),
+            if section.is_loaded() { format!("{:#x}", section.address) } else { "-".to_string() },
+            if section.in_file() { format!("{:#x}", section.offset) } else { "no bytes".to_string() },
+            flags.join(", "),
+        ]);
+    }
+    let debug: u64 = elf.sections.iter().filter(|section| section.name.starts_with(".debug")).map(|section| section.size).sum();
+    if debug > 0 {
+        table.row([".debug_*".to_string(), format!("{}B", format_bytes(debug as f64)), "-".to_string(),
+                   "yes".to_string(), "not loaded".to_string()]);
+    }
+    outln!("{}", table);
+    outln!(".text is the machine code, .rodata string literals and constant tables,");
+    outln!(".eh_frame the unwind tables a panic walks. .symtab, .strtab and the debug");
+    outln!("info are in the file for tools (nm, gdb, backtraces) but never mapped:");
+    outln!("`strip` removes them without changing how the program runs.\n");
+}
+
+fn demonstrate_symbols(elf: &Elf, symbols: &[Symbol]) {
+    output::heading("🔖 Symbols");
+    let functions: Vec<&Symbol> = symbols.iter().filter(|symbol| symbol.is_function() && symbol.size > 0).collect();
+    let code: u64 = functions.iter().map(|symbol| symbol.size).sum();
+    outln!("{} symbols, {} of them functions with a size, {}B of code between them.",
+           symbols.len(), functions.len(), format_bytes(code as f64));
+    let mut table = Table::new(["symbol", "address", "size", "section"]).left(0).left(3);
+    let section_name = |symbol: &Symbol| elf.sections.get(symbol.section as usize).map_or("?".to_string(), |s| s.name.clone());
+    for name in FUNCTIONS.iter().chain(["BINARY_INSPECTOR_RODATA", "BINARY_INSPECTOR_DATA", "BINARY_INSPECTOR_BSS"].iter()) {
+        match find(symbols, name) {
+            Some(symbol) => table.row([shorten(name, 44), format!("{:#x}", symbol.address), format!("{} B", symbol.size),
+                                       section_name(symbol)]),
+            None => table.row([shorten(name, 44), "-".to_string(), "-".to_string(), "not found".to_string()]),
+        };
+    }
+    outln!("{}", table);
+    let mut largest = functions.clone();
+    largest.sort_by_key(|symbol| std::cmp::Reverse(symbol.size));
+    outln!("The largest functions:");
+    for symbol in largest.iter().take(5) {
+        outln!("  {:>7} B  {}", symbol.size, shorten(&symbol.demangled(), 64));
+    }
+    outln!("Rust's own names are mangled (_ZN...17h<hash>E) to fit a flat symbol table;");
+    outln!("the elf module undoes the legacy scheme, while the standard library here uses");
+    outln!("the newer v0 scheme (_R...) and is shown as stored.\n");
+}
+
+fn demonstrate_memory(elf: &Elf, symbols: &[Symbol]) {
+    output::heading("🧠 From File to Memory");
+    let Some(bias) = load_bias(symbols) else {
+        outln!("binary_inspector_anchor isn't in the symbol table (a stripped binary?)\n");
+        return;
+    };
+    let anchor = binary_inspector_anchor as *const () as u64;
+    outln!("The anchor function is at {:#x} in the file and {:#x} in memory: the",
+           anchor.wrapping_sub(bias), anchor);
+    outln!("executable was loaded {:#x} bytes up. Every address shifts by the same bias:", bias);
+    let mut table = Table::new(["", "in the file", "+ bias", "actual address"]).left(0);
+    let runtime: [(&str, u64); 3] = [
+        ("BINARY_INSPECTOR_RODATA", &BINARY_INSPECTOR_RODATA as *const _ as u64),
+        ("BINARY_INSPECTOR_DATA", &BINARY_INSPECTOR_DATA as *const _ as u64),
+        ("BINARY_INSPECTOR_BSS", &BINARY_INSPECTOR_BSS as *const _ as u64),
+    ];
+    for (name, actual) in runtime {
+        if let Some(symbol) = find(symbols, name) {
+            table.row([name.to_string(), format!("{:#x}", symbol.address), format!("{:#x}", symbol.address.wrapping_add(bias)),
+                       format!("{:#x}", actual)]);
+        }
+    }
+    outln!("{}", table);
+    BINARY_INSPECTOR_DATA.fetch_add(1, Ordering::Relaxed);
+    BINARY_INSPECTOR_BSS.fetch_add(1, Ordering::Relaxed);
+
+    if let Some(anchor) = find(symbols, "binary_inspector_anchor") {
+        let len = COMPARED.min(anchor.size as usize);
+        outln!("The anchor's first {} bytes:", len);
+        outln!("  in the file  {}", elf.bytes_at(anchor.address, len as u64).map_or("-".to_string(), hex));
+        outln!("  in memory    {}", hex(&anchor_in_memory(len)));
+    }
+    #[cfg(target_os = "linux")]
+    {
+        let mappings = executable_mappings(binary_inspector_anchor as *const () as u64);
+        if !mappings.is_empty() {
+            outln!();
+            outln!("/proc/self/maps for the executable, one line per mapped segment:");
+            for line in mappings {
+                outln!("  {}", line.split_whitespace().collect::<Vec<_>>().join(" "));
+            }
+        }
+    }
+    outln!("The same file pages, now mapped at the bias: the memory-access demo's");
+    outln!("address space, seen from the file it was built from.\n");
+}
+
+/// The parsed file matches this machine and this process
+fn verify() -> Verification {
+    let mut verification = Verification::new();
+    let elf = match std::env::current_exe().map_err(ElfError::Io).and_then(Elf::open) {
+        Ok(elf) => elf,
+        Err(ElfError::NotElf(format)) => {
+            verification.skip("the executable parses as ELF", format!("a {} executable", format));
+            return verification;
+        }
+        Err(err) => {
+            verification.check("the executable parses as ELF", false, || err.to_string());
+            return verification;
+        }
+    };
+    let expected_machine = match std::env::consts::ARCH {
+        "x86_64" => Some(62),
+        "aarch64" => Some(183),
+        "riscv64" => Some(243),
+        _ => None,
+    };
+    if let Some(machine) = expected_machine {
+        verification.equal("the header names this machine", elf.machine, machine);
+    }
+    verification.equal("the header's byte order is the target's", elf.is_big_endian(), cfg!(target_endian = "big"));
+    let text = elf.section(".text");
+    verification
+        .check("`.text` is code, in an executable segment",
+               text.is_some_and(|text| text.flags & SHF_EXECINSTR != 0
+                   && elf.segment_of(text.address).is_some_and(|segment| segment.permissions() == "r-x")),
+               || format!("{:?}", text))
+        .check("`.bss` takes no bytes in the file", elf.section(".bss").is_some_and(|bss| !bss.in_file()),
+               || format!("{:?}", elf.section(".bss")));
+
+    let symbols = match elf.symbols() {
+        Ok(symbols) => symbols,
+        Err(err) => {
+            verification.check("the symbol table parses", false, || err.to_string());
+            return verification;
+        }
+    };
+    let section_of = |name: &str| find(&symbols, name).and_then(|symbol| elf.sections.get(symbol.section as usize))
+        .map(|section| section.name.clone());
+    let Some(anchor) = find(&symbols, "binary_inspector_anchor") else {
+        verification.skip("symbols match the running process", "no symbol table (stripped?)");
+        return verification;
+    };
+    verification
+        .check("the anchor is a function in .text", anchor.is_function() && section_of("binary_inspector_anchor").as_deref() == Some(".text"),
+               || format!("{:?}", anchor))
+        .equal("an initialized static lives in .data", section_of("BINARY_INSPECTOR_DATA").as_deref(), Some(".data"))
+        .equal("a zeroed static lives in .bss", section_of("BINARY_INSPECTOR_BSS").as_deref(), Some(".bss"))
+        .equal("an immutable static lives in .rodata", section_of("BINARY_INSPECTOR_RODATA").as_deref(), Some(".rodata"));
+
+    let bias = load_bias(&symbols).unwrap_or(0);
+    let data = find(&symbols, "BINARY_INSPECTOR_DATA").map(|symbol| symbol.address.wrapping_add(bias));
+    verification.equal("statics move by the same bias as code", data, Some(&BINARY_INSPECTOR_DATA as *const _ as u64));
+    let len = COMPARED.min(anchor.size as usize);
+    verification.equal("the code in memory is the code in the file", elf.bytes_at(anchor.address, len as u64).map(<[u8]>::to_vec),
+                       Some(anchor_in_memory(len)));
+    verification
+}
+
+const QUIZ: &[Question] = &[
+    Question::new(
+        "A static initialized to zero takes how many bytes in the executable file?",
+        &["Its size", "None: it goes in .bss, which the loader zero-fills", "A page"],
+        1,
+        "The segment's memory size exceeds its file size; the difference is .bss.",
+    ),
+    Question::new(
+        "Why does the same function have a different address in every run of a PIE executable?",
+        &[
+            "The linker randomizes it",
+            "The kernel loads the whole file at a random base (ASLR); every address shifts by that one bias",
+            "Functions move while the program runs",
+        ],
+        1,
+        "Symbol addresses in the file are relative to where it lands; the bias is chosen per execve.",
+    ),
+    Question::new(
+        "What does `strip` remove, and does the program behave differently afterwards?",
+        &[
+            ".text; yes",
+            "The symbol table and debug info, which are never mapped; no, only tools and backtraces notice",
+            "Unused functions; sometimes",
+        ],
+        1,
+        "Unloaded sections exist for nm, gdb and symbolication only.",
+    ),
+];
+
+#[derive(Demo)]
+#[demo(name = "binary-inspector-demo", description = "Parse our own ELF executable: segments, sections, symbols",
+       quiz = QUIZ, verify = verify)]
+pub struct BinaryInspectorDemo;
+
+impl BinaryInspectorDemo {
+    fn demonstrate(&self, _config: &Config) -> DemoReport {
+        output::title("🔍 Binary Inspector Demo");
+        outln!("Opening the file this program was loaded from, and finding ourselves in it.\n");
+
+        let exe = match std::env::current_exe() {
+            Ok(exe) => exe,
+            Err(err) => {
+                outln!("❌ can't locate the executable: {}", err);
+                return DemoReport::default();
+            }
+        };
+        let elf = match Elf::open(&exe) {
+            Ok(elf) => elf,
+            Err(err) => {
+                outln!("❌ {}: {}", exe.display(), err);
+                outln!("This parser reads ELF only; macOS (Mach-O) and Windows (PE) executables");
+                outln!("have the same ideas (segments, sections, a symbol table) in other layouts.\n");
+                return DemoReport::default();
+            }
+        };
+        demonstrate_header(&elf, &exe.display().to_string());
+        demonstrate_segments(&elf);
+        demonstrate_sections(&elf);
+        match elf.symbols() {
+            Ok(symbols) => {
+                demonstrate_symbols(&elf, &symbols);
+                demonstrate_memory(&elf, &symbols);
+            }
+            Err(err) => outln!("❌ {}\n", err),
+        }
+
+        output::takeaways();
+        outln!("• An executable is a file of segments (what gets mapped) and sections (what the linker made)");
+        outln!("• .text is code, .rodata constants, .data initialized statics, .bss zeroed statics with no file bytes");
+        outln!("• The symbol table names functions and their sizes; it isn't loaded, and strip removes it");
+        outln!("• A PIE runs at its file addresses plus one random bias");
+        DemoReport::default()
+    }
+}
@@ -0,0 +1,449 @@
+//! Bytecode VM Dispatch Demo
+//!
+//! An interpreter spends much of its time deciding what to do next. This
+//! demo runs one small stack-based bytecode VM three ways: a `match` in a
+//! loop (one shared indirect jump through a jump table), a table of
+//! function pointers indexed by opcode (an indirect call and return per
+//! instruction), and closures compiled from the bytecode ahead of time, each
+//! with its operand and jump target baked in (no decoding left at run
+//! time). Two programs are timed: a tight loop whose opcode sequence repeats
+//! every few instructions, and a loop over thousands of randomly chosen
+//! instructions, too long a pattern for the branch predictor to learn.
+//! Run with: cargo run --release --bin bytecode-vm-demo
+
+use std::fmt;
+use std::hint::black_box;
+
+use crate::bench::{format_rate, measure};
+use crate::demo::{Config, Demo, DemoReport};
+use crate::output::Table;
+use crate::quiz::Question;
+use crate::rng::Rng;
+use crate::verify::Verification;
+use crate::{outln, output};
+
+/// Instructions executed per program, roughly, unless the config sets `size`
+const INSTRUCTIONS: usize = 20_000_000;
+
+/// Statements in the random program's loop body (about 4 instructions each)
+const STATEMENTS: usize = 1_000;
+
+/// Operand stack depth
+const STACK: usize = 64;
+
+/// Local variable slots
+const LOCALS: usize = 8;
+
+/// Runs per strategy
+const RUNS: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Opcode {
+    /// Push the operand
+    Push,
+    /// Push local `arg`
+    Load,
+    /// Pop into local `arg`
+    Store,
+    Dup,
+    Add,
+    Sub,
+    Mul,
+    Xor,
+    And,
+    Or,
+    /// Pop; jump to `arg` if it was non-zero
+    Jnz,
+    Halt,
+}
+
+/// Handlers in the function-pointer table, one per opcode
+const OPCODES: usize = 12;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Instruction {
+    pub op: Opcode,
+    pub arg: i32,
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.op {
+            Opcode::Push | Opcode::Load | Opcode::Store | Opcode::Jnz => write!(f, "{:?} {}", self.op, self.arg),
+            op => write!(f, "{:?}", op),
+        }
+    }
+}
+
+const fn ins(op: Opcode, arg: i32) -> Instruction {
+    Instruction { op, arg }
+}
+
+/// Bytecode, and how many instructions a run of it executes
+pub struct Program {
+    pub code: Vec<Instruction>,
+    pub executed: u64,
+}
+
+/// Sum of `n..=1` into local 1, counting local 0 down
+pub fn sum_loop(n: u64) -> Program {
+    use Opcode::*;
+    let code = vec![
+        ins(Push, n as i32), ins(Store, 0), ins(Push, 0), ins(Store, 1),
+        // loop: acc += i; i -= 1; while i != 0
+        ins(Load, 1), ins(Load, 0), ins(Add, 0), ins(Store, 1),
+        ins(Load, 0), ins(Push, 1), ins(Sub, 0), ins(Dup, 0), ins(Store, 0), ins(Jnz, 4),
+        ins(Halt, 0),
+    ];
+    Program { code, executed: 4 + 10 * n + 1 }
+}
+
+/// `iterations` passes over `statements` random assignments like
+/// `l3 = l1 * 7` or `l5 = (l2 ^ l4) + l2`: thousands of instructions whose
+/// opcode order never repeats within one pass
+pub fn random_program(statements: usize, iterations: u64, rng: &mut Rng) -> Program {
+    use Opcode::*;
+    const BINARY: [Opcode; 6] = [Add, Sub, Mul, Xor, And, Or];
+    let local = |rng: &mut Rng| 1 + rng.below(LOCALS - 1) as i32;
+    let mut code = vec![ins(Push, iterations as i32), ins(Store, 0)];
+    let start = code.len() as i32;
+    for _ in 0..statements {
+        code.push(ins(Load, local(rng)));
+        match rng.below(3) {
+            0 => code.push(ins(Push, rng.below(100) as i32)),
+            1 => code.push(ins(Load, local(rng))),
+            _ => code.extend([ins(Dup, 0), ins(BINARY[rng.below(6)], 0), ins(Load, local(rng))]),
+        }
+        code.push(ins(BINARY[rng.below(6)], 0));
+        code.push(ins(Store, local(rng)));
+    }
+    let body = code.len() as i32 - start;
+    code.extend([ins(Load, 0), ins(Push, 1), ins(Sub, 0), ins(Dup, 0), ins(Store, 0), ins(Jnz, start), ins(Halt, 0)]);
+    Program { code, executed: 2 + (body as u64 + 6) * iterations + 1 }
+}
+
+/// The machine state every strategy shares
+pub struct Vm {
+    stack: [i64; STACK],
+    sp: usize,
+    locals: [i64; LOCALS],
+    pc: usize,
+}
+
+impl Default for Vm {
+    fn default() -> Self {
+        Vm { stack: [0; STACK], sp: 0, locals: [0; LOCALS], pc: 0 }
+    }
+}
+
+impl Vm {
+    #[inline(always)]
+    fn push(&mut self, value: i64) {
+        self.stack[self.sp] = value;
+        self.sp += 1;
+    }
+
+    #[inline(always)]
+    fn pop(&mut self) -> i64 {
+        self.sp -= 1;
+        self.stack[self.sp]
+    }
+
+    #[inline(always)]
+    fn binary(&mut self, f: impl FnOnce(i64, i64) -> i64) {
+        let b = self.pop();
+        let a = self.pop();
+        self.push(f(a, b));
+    }
+
+    /// The locals when the program halted: its result
+    pub fn locals(&self) -> [i64; LOCALS] {
+        self.locals
+    }
+}
+
+/// Strategy 1: fetch, then `match` on the opcode. Compiles to a jump table
+/// and a single indirect jump that every instruction goes through
+pub fn run_match(code: &[Instruction]) -> Vm {
+    let mut vm = Vm::default();
+    loop {
+        let Instruction { op, arg } = code[vm.pc];
+        vm.pc += 1;
+        match op {
+            Opcode::Push => vm.push(arg as i64),
+            Opcode::Load => vm.push(vm.locals[arg as usize]),
+            Opcode::Store => vm.locals[arg as usize] = vm.pop(),
+            Opcode::Dup => {
+                let top = vm.pop();
+                vm.push(top);
+                vm.push(top);
+            }
+            Opcode::Add => vm.binary(i64::wrapping_add),
+            Opcode::Sub => vm.binary(i64::wrapping_sub),
+            Opcode::Mul => vm.binary(i64::wrapping_mul),
+            Opcode::Xor => vm.binary(|a, b| a ^ b),
+            Opcode::And => vm.binary(|a, b| a & b),
+            Opcode::Or => vm.binary(|a, b| a | b),
+            Opcode::Jnz => {
+                if vm.pop() != 0 {
+                    vm.pc = arg as usize;
+                }
+            }
+            Opcode::Halt => return vm,
+        }
+    }
+}
+
+/// A handler returns false to stop
+type Handler = fn(&mut Vm, i32) -> bool;
+
+const HANDLERS: [Handler; OPCODES] = [
+    |vm, arg| { vm.push(arg as i64); true },
+    |vm, arg| { vm.push(vm.locals[arg as usize]); true },
+    |vm, arg| { vm.locals[arg as usize] = vm.pop(); true },
+    |vm, _| { let top = vm.pop(); vm.push(top); vm.push(top); true },
+    |vm, _| { vm.binary(i64::wrapping_add); true },
+    |vm, _| { vm.binary(i64::wrapping_sub); true },
+    |vm, _| { vm.binary(i64::wrapping_mul); true },
+    |vm, _| { vm.binary(|a, b| a ^ b); true },
+    |vm, _| { vm.binary(|a, b| a & b); true },
+    |vm, _| { vm.binary(|a, b| a | b); true },
+    |vm, arg| { if vm.pop() != 0 { vm.pc = arg as usize; } true },
+    |_, _| false,
+];
+
+/// Strategy 2: index a table of function pointers by opcode and call
+/// through it: an indirect call and a return per instruction, and no
+/// inlining of the handlers into the loop
+pub fn run_table(code: &[Instruction]) -> Vm {
+    let mut vm = Vm::default();
+    loop {
+        let Instruction { op, arg } = code[vm.pc];
+        vm.pc += 1;
+        if !HANDLERS[op as usize](&mut vm, arg) {
+            return vm;
+        }
+    }
+}
+
+/// An instruction compiled to a closure: runs, and returns the index of
+/// the next one (or `HALT`)
+type Compiled = Box<dyn Fn(&mut Vm) -> usize>;
+
+const HALT: usize = usize::MAX;
+
+/// Turn bytecode into closures once, ahead of running it: operands and
+/// jump targets become captured constants, and the opcode is gone
+pub fn compile(code: &[Instruction]) -> Vec<Compiled> {
+    code.iter().enumerate().map(|(pc, &Instruction { op, arg })| -> Compiled {
+        let next = pc + 1;
+        let (value, slot, target) = (arg as i64, arg as usize, arg as usize);
+        match op {
+            Opcode::Push => Box::new(move |vm| { vm.push(value); next }),
+            Opcode::Load => Box::new(move |vm| { vm.push(vm.locals[slot]); next }),
+            Opcode::Store => Box::new(move |vm| { vm.locals[slot] = vm.pop(); next }),
+            Opcode::Dup => Box::new(move |vm| { let top = vm.pop(); vm.push(top); vm.push(top); next }),
+            Opcode::Add => Box::new(move |vm| { vm.binary(i64::wrapping_add); next }),
+            Opcode::Sub => Box::new(move |vm| { vm.binary(i64::wrapping_sub); next }),
+            Opcode::Mul => Box::new(move |vm| { vm.binary(i64::wrapping_mul); next }),
+            Opcode::Xor => Box::new(move |vm| { vm.binary(|a, b| a ^ b); next }),
+            Opcode::And => Box::new(move |vm| { vm.binary(|a, b| a & b); next }),
+            Opcode::Or => Box::new(move |vm| { vm.binary(|a, b| a | b); next }),
+            Opcode::Jnz => Box::new(move |vm| if vm.pop() != 0 { target } else { next }),
+            Opcode::Halt => Box::new(|_| HALT),
+        }
+    }).collect()
+}
+
+/// Strategy 3: call the compiled closures, each handing back the next
+pub fn run_compiled(program: &[Compiled]) -> Vm {
+    let mut vm = Vm::default();
+    while vm.pc != HALT {
+        vm.pc = program[vm.pc](&mut vm);
+    }
+    vm
+}
+
+/// The sum loop in plain Rust, for the cost of interpreting it; black_box
+/// keeps LLVM from replacing the loop with n(n+1)/2
+fn native_sum(n: u64) -> i64 {
+    let (mut i, mut acc) = (n as i64, 0i64);
+    loop {
+        acc = black_box(acc.wrapping_add(i));
+        i -= 1;
+        if i == 0 {
+            return acc;
+        }
+    }
+}
+
+fn demonstrate_bytecode() {
+    output::heading("📜 The Bytecode");
+    let program = sum_loop(100);
+    outln!("A stack machine: instructions pop operands off a stack and push results;");
+    outln!("Load and Store move values between the stack and {} local slots. The sum", LOCALS);
+    outln!("of 100..=1:\n");
+    for (pc, instruction) in program.code.iter().enumerate() {
+        let note = match pc {
+            0 => "  ; i = 100",
+            2 => "  ; acc = 0",
+            4 => "  ; loop: acc = acc + i",
+            8 => "  ;       i = i - 1",
+            13 => "  ;       while i != 0",
+            _ => "",
+        };
+        outln!("  {:>2}: {:<10}{}", pc, instruction.to_string(), note);
+    }
+    let vm = run_match(&program.code);
+    outln!("\nlocal 1 after Halt: {} ({} instructions executed)\n", vm.locals()[1], program.executed);
+}
+
+fn demonstrate_dispatch(name: &str, program: &Program, report: &mut DemoReport) -> Vec<(&'static str, f64)> {
+    let compiled = compile(&program.code);
+    let expected = run_match(&program.code).locals();
+    assert_eq!(run_table(&program.code).locals(), expected);
+    assert_eq!(run_compiled(&compiled).locals(), expected);
+    let results = [
+        ("match (jump table)", measure(RUNS, || run_match(black_box(&program.code)).locals())),
+        ("fn pointer table", measure(RUNS, || run_table(black_box(&program.code)).locals())),
+        ("compiled closures", measure(RUNS, || run_compiled(black_box(&compiled)).locals())),
+    ];
+    let mut rates = Vec::new();
+    let mut table = Table::new(["strategy", "median", "instructions/s", "ns/instruction"]).left(0);
+    for (label, measurement) in &results {
+        let seconds = measurement.median().as_secs_f64();
+        let rate = program.executed as f64 / seconds;
+        table.row([label.to_string(), format!("{:.2?}", measurement.median()), format_rate(rate),
+                   format!("{:.2}", seconds * 1e9 / program.executed as f64)]);
+        report.record(*label, rate, "instructions/s").param("program", name);
+        rates.push((*label, rate));
+    }
+    outln!("{}", table);
+    rates
+}
+
+fn demonstrate_programs(config: &Config, report: &mut DemoReport) {
+    let instructions = config.size.unwrap_or(INSTRUCTIONS) as u64;
+    let n = (instructions / 10).clamp(1, i32::MAX as u64);
+    let tight = sum_loop(n);
+    output::heading(&format!("⏱️  A Tight Loop: {} Instructions", tight.executed));
+    outln!("The same 10 opcodes over and over: after a few iterations the predictor");
+    outln!("knows which handler comes next.\n");
+    let tight_rates = demonstrate_dispatch("tight loop", &tight, report);
+    let native = measure(RUNS, || native_sum(black_box(n)));
+    let native_ns = native.median().as_secs_f64() * 1e9 / n as f64;
+    let match_ns = 10.0 * 1e9 / tight_rates[0].1;
+    outln!("The same loop compiled by rustc: {:.2} ns per iteration; interpreted, one iteration", native_ns);
+    outln!("is 10 instructions, {:.1} ns with the match: {:.0}x slower.\n", match_ns, match_ns / native_ns);
+
+    let mut rng = config.rng();
+    let probe = random_program(STATEMENTS, 1, &mut rng.clone());
+    let iterations = (instructions / probe.executed.max(1)).clamp(1, i32::MAX as u64);
+    let random = random_program(STATEMENTS, iterations, &mut rng);
+    output::heading(&format!("🎲 A Long Random Loop: {} Instructions", random.executed));
+    outln!("{} random statements, {} instructions per pass: the next opcode depends",
+           STATEMENTS, random.code.len());
+    outln!("on a pattern thousands of steps long, more than the predictor can hold.\n");
+    let random_rates = demonstrate_dispatch("random loop", &random, report);
+
+    let mut table = Table::new(["strategy", "tight loop", "random loop", "slowdown"]).left(0);
+    for ((label, tight), (_, random)) in tight_rates.iter().zip(&random_rates) {
+        table.row([label.to_string(), format_rate(*tight), format_rate(*random), format!("{:.2}x", tight / random)]);
+    }
+    outln!("{}", table);
+    outln!("Each handler is a few loads and stores; what differs is the jump to the");
+    outln!("next one. It is an indirect branch (its target is data), and the CPU");
+    outln!("guesses the target from recent history before the opcode is even loaded.");
+    outln!("A right guess costs nearly nothing; a wrong one flushes the pipeline,");
+    outln!("15 to 20 cycles. The tight loop is guessed right every time; in the random");
+    outln!("loop, mispredictions slow every strategy down alike. The program, not the");
+    outln!("shape of the dispatch code, sets most of the price.\n");
+    let (matched, table, closures) = (tight_rates[0].1, tight_rates[1].1, tight_rates[2].1);
+    outln!("Between strategies: the match inlines every handler into one function, the");
+    outln!("table makes a real call and return per instruction. Here the table runs at");
+    outln!("{:.2}x the match's speed: an indirect call costs about what the match's", table / matched);
+    outln!("indirect jump does, and the handlers are too small for inlining to matter");
+    outln!("much. The closures skip decoding (operand and target are captured) but run");
+    outln!("at {:.2}x: a Box<dyn Fn> is a call through a vtable, one more pointer to", closures / matched);
+    outln!("load, per instruction. Classic threaded code, each handler jumping");
+    outln!("straight to the next, gives every handler its own indirect jump to learn");
+    outln!("from; Rust can't express it without guaranteed tail calls, and modern");
+    outln!("predictors, with long global histories, get most of that benefit from a");
+    outln!("single shared jump anyway.\n");
+}
+
+/// All three strategies compute the same thing, and the right thing
+fn verify() -> Verification {
+    let mut verification = Verification::new();
+    let n = 10_000;
+    let program = sum_loop(n);
+    let expected = (n * (n + 1) / 2) as i64;
+    verification
+        .equal("the match interpreter sums the loop", run_match(&program.code).locals()[1], expected)
+        .equal("the function table agrees", run_table(&program.code).locals()[1], expected)
+        .equal("the compiled closures agree", run_compiled(&compile(&program.code)).locals()[1], expected)
+        .equal("rustc's own loop agrees", native_sum(n), expected);
+
+    let random = random_program(STATEMENTS, 20, &mut Config::default().rng());
+    let reference = run_match(&random.code).locals();
+    verification
+        .equal("on the random program, the function table matches", run_table(&random.code).locals(), reference)
+        .equal("on the random program, the closures match", run_compiled(&compile(&random.code)).locals(), reference);
+    verification
+}
+
+const QUIZ: &[Question] = &[
+    Question::new(
+        "What makes the jump to the next instruction's handler expensive in an interpreter?",
+        &[
+            "Decoding the opcode",
+            "It is an indirect branch: when the predictor guesses its target wrong, the pipeline is flushed",
+            "The handlers' arithmetic",
+        ],
+        1,
+        "The cost tracks how predictable the opcode sequence is.",
+    ),
+    Question::new(
+        "Why is a function-pointer table usually slower than a match here?",
+        &[
+            "Tables are bigger than jump tables",
+            "Every instruction becomes a real call and return, and the handlers can't be inlined into the loop",
+            "Function pointers need bounds checks",
+        ],
+        1,
+        "The match keeps all handlers in one function with the VM state in registers.",
+    ),
+    Question::new(
+        "What does compiling bytecode to closures ahead of time save?",
+        &["Nothing", "Decoding: operands and jump targets are baked into each closure", "The stack"],
+        1,
+        "It's a step towards a JIT: do the decode work once, not on every execution.",
+    ),
+];
+
+#[derive(Demo)]
+#[demo(name = "bytecode-vm-demo", description = "A stack VM three ways: match, fn-pointer table, compiled closures",
+       quiz = QUIZ, verify = verify)]
+pub struct BytecodeVmDemo;
+
+impl BytecodeVmDemo {
+    fn demonstrate(&self, config: &Config) -> DemoReport {
+        output::title("🧮 Bytecode VM Dispatch Demo");
+        outln!("One interpreter, three ways of getting from one instruction to the next.\n");
+        let mut report = DemoReport::default();
+
+        demonstrate_bytecode();
+        demonstrate_programs(config, &mut report);
+
+        if cfg!(debug_assertions) {
+            outln!("⚠️  This is a debug build: rerun with --release for meaningful timings.\n");
+        }
+        output::takeaways();
+        outln!("• An interpreter's inner loop is fetch, decode, and an indirect jump to a handler");
+        outln!("• That jump's predictability, set by the program, dominates the cost per instruction");
+        outln!("• A match keeps handlers inlined; fn tables and closures pay a call per instruction");
+        outln!("• Decoding ahead of time (closures, threaded code, a JIT) removes work from every step");
+        report
+    }
+}
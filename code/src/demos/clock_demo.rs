@@ -0,0 +1,301 @@
+//! Clock Demo
+//!
+//! Page replacement is caching with pages: RAM holds a few frames of a much
+//! larger virtual address space, and on a page fault the kernel must pick a
+//! frame to reuse. It can't run LRU, because a hit is an ordinary memory
+//! access that the kernel never sees; the MMU only sets the accessed bit in
+//! the page table entry. The library's `ClockCache` is the classic answer
+//! built on that bit. This demo steps through the textbook reference string
+//! with three frames, printing the hand and every reference bit as each
+//! fault sweeps the clock, then counts faults for FIFO, Clock, LRU and
+//! Belady's optimal policy on that string and on a trace with locality.
+//! Run with: cargo run --bin clock-demo
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt::Write;
+
+use crate::cache::{ClockCache, EvictionPolicy, FifoPolicy, LruPolicy, PolicyCache};
+use crate::demo::{Config, Demo, DemoReport};
+use crate::output::Table;
+use crate::quiz::Question;
+use crate::rng::Rng;
+use crate::verify::Verification;
+use crate::{outln, output};
+
+/// The reference string of Silberschatz's Operating System Concepts
+const REFERENCES: [u32; 20] = [7, 0, 1, 2, 0, 3, 0, 4, 2, 3, 0, 3, 2, 1, 2, 0, 1, 7, 0, 1];
+
+/// Page frames for the walkthrough
+const FRAMES: usize = 3;
+
+/// Pages in the working set of the longer trace, which gets 256 frames
+const WINDOW: usize = 200;
+
+/// The frames in order, `*` marking a set reference bit and `>` the hand
+fn show(clock: &ClockCache<u32, ()>) -> String {
+    let mut line = String::new();
+    for (index, (page, _, referenced)) in clock.frames().enumerate() {
+        let hand = if index == clock.hand() && clock.len() == clock.capacity() { '>' } else { ' ' };
+        let bit = if referenced { '*' } else { ' ' };
+        let _ = write!(line, "{}{}{} ", hand, page, bit);
+    }
+    line
+}
+
+/// The hand's sweep for the next fault, read off the frames before it
+/// happens: each referenced frame passed over, then the victim
+fn sweep(clock: &ClockCache<u32, ()>) -> String {
+    let frames: Vec<(u32, bool)> = clock.frames().map(|(&page, _, referenced)| (page, referenced)).collect();
+    let mut steps = Vec::new();
+    let mut index = clock.hand();
+    let mut cleared = HashSet::new();
+    loop {
+        let (page, referenced) = frames[index];
+        if referenced && cleared.insert(index) {
+            steps.push(format!("{} R=1→0", page));
+            index = (index + 1) % frames.len();
+        } else {
+            steps.push(format!("{} R=0 out", page));
+            return steps.join(", ");
+        }
+    }
+}
+
+fn demonstrate_mapping() {
+    output::heading("🗺️  A Cache Is a Page Table");
+    let mut table = Table::new(["cache", "virtual memory"]).left(0).left(1);
+    for (cache, os) in [
+        ("key", "virtual page number"),
+        ("entry", "page frame in RAM"),
+        ("capacity", "physical frames"),
+        ("hit", "access to a resident page: the MMU sets the accessed bit"),
+        ("miss", "page fault: the kernel loads the page"),
+        ("eviction", "reclaiming a frame, writing it back first if dirty"),
+        ("reference bit", "accessed bit in the page table entry"),
+        ("clock hand", "the kernel's reclaim scan"),
+    ] {
+        table.row([cache.to_string(), os.to_string()]);
+    }
+    outln!("{}", table);
+    outln!("LRU needs to move an entry on every hit. Every memory access is a hit");
+    outln!("here, and no kernel can run code on each one, so it settles for the one");
+    outln!("bit the hardware leaves behind.\n");
+}
+
+fn demonstrate_walkthrough() -> usize {
+    output::heading("🕐 The Clock, Step by Step");
+    outln!("{} frames, references {:?}.", FRAMES, REFERENCES);
+    outln!("Each frame shows its page, * for a set reference bit and > for the hand:\n");
+    let mut clock = ClockCache::new(FRAMES);
+    let mut faults = 0;
+    for page in REFERENCES {
+        let hit = clock.get(&page).is_some();
+        let detail = if hit {
+            "hit, sets R".to_string()
+        } else if clock.len() < clock.capacity() {
+            "fault, free frame".to_string()
+        } else {
+            format!("fault: {}", sweep(&clock))
+        };
+        if !hit {
+            faults += 1;
+            clock.put(page, ());
+        }
+        outln!("  {:>2}  {:<14} {}", page, show(&clock), detail);
+    }
+    outln!("\n{} faults in {} references.\n", faults, REFERENCES.len());
+    faults
+}
+
+/// Faults for `frames` pages evicted by `policy`: FIFO is Clock with the
+/// bit ignored, LRU is what Clock approximates
+fn policy_faults(trace: &[u32], frames: usize, policy: impl EvictionPolicy<u32>) -> usize {
+    let mut cache = PolicyCache::new(frames, policy);
+    let mut faults = 0;
+    for &page in trace {
+        if cache.get(&page).is_none() {
+            faults += 1;
+            cache.put(page, ());
+        }
+    }
+    faults
+}
+
+fn clock_faults(trace: &[u32], frames: usize) -> usize {
+    let mut clock = ClockCache::new(frames);
+    let mut faults = 0;
+    for &page in trace {
+        if clock.get(&page).is_none() {
+            faults += 1;
+            clock.put(page, ());
+        }
+    }
+    faults
+}
+
+/// Belady's optimal policy: evict the page whose next use is furthest away.
+/// It needs the future, so it is a yardstick rather than an algorithm.
+fn optimal_faults(trace: &[u32], frames: usize) -> usize {
+    let mut next_use = vec![usize::MAX; trace.len()];
+    let mut seen: HashMap<u32, usize> = HashMap::new();
+    for (i, &page) in trace.iter().enumerate().rev() {
+        next_use[i] = seen.insert(page, i).unwrap_or(usize::MAX);
+    }
+    // (next use, page) for every resident page
+    let mut resident: BTreeSet<(usize, u32)> = BTreeSet::new();
+    let mut when: HashMap<u32, usize> = HashMap::new();
+    let mut faults = 0;
+    for (i, &page) in trace.iter().enumerate() {
+        match when.get(&page) {
+            Some(&at) => {
+                resident.remove(&(at, page));
+            }
+            None => {
+                faults += 1;
+                if resident.len() == frames {
+                    let (_, victim) = resident.pop_last().expect("frames are full");
+                    when.remove(&victim);
+                }
+            }
+        }
+        resident.insert((next_use[i], page));
+        when.insert(page, next_use[i]);
+    }
+    faults
+}
+
+/// A program's references: 90% fall in a working set of `window` pages that
+/// slides one page every 100 references, the rest anywhere in `pages`
+fn working_set_trace(references: usize, pages: usize, window: usize, rng: &mut Rng) -> Vec<u32> {
+    (0..references)
+        .map(|i| {
+            let base = i / 100;
+            match rng.below(10) {
+                0 => rng.below(pages) as u32,
+                _ => ((base + rng.below(window)) % pages) as u32,
+            }
+        })
+        .collect()
+}
+
+/// Fault counts for FIFO, Clock, LRU and optimal
+fn fault_counts(trace: &[u32], frames: usize) -> [usize; 4] {
+    [
+        policy_faults(trace, frames, FifoPolicy::default()),
+        clock_faults(trace, frames),
+        policy_faults(trace, frames, LruPolicy::default()),
+        optimal_faults(trace, frames),
+    ]
+}
+
+fn demonstrate_policies(config: &Config, report: &mut DemoReport) {
+    output::heading("📊 Clock Among the Policies");
+    let references = config.size.unwrap_or(200_000).max(1000);
+    let trace = working_set_trace(references, 4096, WINDOW, &mut config.rng());
+    outln!("Page faults for the string above with {} frames, and for {} references", FRAMES, references);
+    outln!("with locality: 90% within a {}-page working set that slides one page", WINDOW);
+    outln!("every 100 references, the rest anywhere in 4096 pages, with 256 frames:\n");
+
+    let mut table = Table::new(["policy", "textbook string", "working set", "fault rate"]).left(0);
+    let small = fault_counts(&REFERENCES, FRAMES);
+    let large = fault_counts(&trace, 256);
+    for (i, name) in ["FIFO", "Clock", "LRU", "optimal"].into_iter().enumerate() {
+        report.record(format!("{} faults", name), large[i] as f64, "faults").param("frames", 256).param("references", references);
+        table.row([
+            name.to_string(),
+            small[i].to_string(),
+            large[i].to_string(),
+            format!("{:.2}%", 100.0 * large[i] as f64 / references as f64),
+        ]);
+    }
+    outln!("{}", table);
+    let [fifo, clock, lru, optimal] = large;
+    if lru <= clock && clock <= fifo {
+        outln!("Clock lands between FIFO and LRU on the working set ({} vs {} and {}),", clock, fifo, lru);
+        outln!("for the price of a bit per frame instead of a list update per access.");
+    } else {
+        outln!("On the working set Clock took {} faults, FIFO {} and LRU {}: with this", clock, fifo, lru);
+        outln!("seed the second chances didn't fall between the two.");
+    }
+    outln!("Optimal ({}) needs to know the future; it only measures how far the", optimal);
+    outln!("others are from perfect.\n");
+
+    outln!("Linux's classic reclaim refines the idea with two lists, active and");
+    outln!("inactive: a page with its accessed bit set when the scan reaches it is");
+    outln!("promoted, one without is demoted and eventually reclaimed.");
+    #[cfg(target_os = "linux")]
+    if let Ok(meminfo) = std::fs::read_to_string("/proc/meminfo") {
+        outln!("This machine's lists right now, from /proc/meminfo:");
+        for line in meminfo.lines().filter(|line| line.starts_with("Active(") || line.starts_with("Inactive(")) {
+            outln!("  {}", line);
+        }
+    }
+    outln!();
+}
+
+/// FIFO, LRU and optimal match the textbook's fault counts, and on a trace
+/// with locality Clock sits nearer LRU than FIFO
+fn verify() -> Verification {
+    let mut verification = Verification::new();
+    let [fifo, _, lru, optimal] = fault_counts(&REFERENCES, FRAMES);
+    verification
+        .equal("FIFO takes the textbook's 15 faults", fifo, 15)
+        .equal("LRU takes the textbook's 12 faults", lru, 12)
+        .equal("optimal takes the textbook's 9 faults", optimal, 9);
+    let trace = working_set_trace(50_000, 4096, WINDOW, &mut Rng::new(5));
+    let [fifo, clock, lru, optimal] = fault_counts(&trace, 256);
+    verification
+        .check("Clock faults less than FIFO", clock < fifo, || format!("Clock {} vs FIFO {}", clock, fifo))
+        .check("Clock recovers most of LRU's lead over FIFO", 2 * (clock - lru.min(clock)) < fifo - lru,
+               || format!("FIFO {}, Clock {}, LRU {}", fifo, clock, lru))
+        .check("optimal beats them all", optimal < lru.min(clock), || format!("optimal {} vs LRU {}", optimal, lru));
+    verification
+}
+
+const QUIZ: &[Question] = &[
+    Question::new(
+        "Why doesn't the kernel use exact LRU for page replacement?",
+        &[
+            "LRU needs work on every memory access, and the hardware only sets an accessed bit",
+            "LRU performs worse than FIFO",
+            "Pages have no keys",
+        ],
+        0,
+        "Hits never reach the kernel, so it approximates recency from the bits the MMU sets.",
+    ),
+    Question::new(
+        "What happens when the clock hand reaches a frame with its reference bit set?",
+        &[
+            "The frame is evicted",
+            "The bit is cleared and the hand moves on: the page's second chance",
+            "The hand stops and waits",
+        ],
+        1,
+        "Only a page not used since the hand's last visit is evicted.",
+    ),
+];
+
+#[derive(Demo)]
+#[demo(name = "clock-demo", description = "Clock (second-chance) eviction stepped through as OS page replacement",
+       quiz = QUIZ, verify = verify)]
+pub struct ClockDemo;
+
+impl ClockDemo {
+    fn demonstrate(&self, config: &Config) -> DemoReport {
+        output::title("🕐 Clock Demo");
+        outln!("The page replacement algorithm behind every virtual memory system, as a cache.\n");
+
+        let mut report = DemoReport::default();
+        demonstrate_mapping();
+        demonstrate_walkthrough();
+        demonstrate_policies(config, &mut report);
+
+        output::takeaways();
+        outln!("• Page replacement is a cache: frames hold pages, faults are misses");
+        outln!("• The kernel never sees hits, only the accessed bits the MMU sets");
+        outln!("• Clock gives referenced pages a second chance instead of reordering a list");
+        outln!("• For one bit per frame it recovers most of LRU's lead over FIFO");
+        outln!("• Optimal needs the future; it is the yardstick, not an option");
+        report
+    }
+}
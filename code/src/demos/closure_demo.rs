@@ -0,0 +1,232 @@
+//! Closure Internals Demo
+//!
+//! A closure is an anonymous struct holding its captures, plus an impl of
+//! `Fn`, `FnMut` or `FnOnce` whose body is the closure body. This demo
+//! measures those structs, shows which traits each kind of capture allows,
+//! and compares calling a closure directly, through a function pointer, and
+//! through `dyn Fn`.
+//! Run with: cargo run --release --bin closure-demo
+
+use std::hint::black_box;
+use std::mem;
+
+use crate::bench_compare;
+use crate::demo::{Config, Demo, DemoReport};
+use crate::output::Table;
+use crate::quiz::Question;
+use crate::{out, outln, output};
+
+struct Point {
+    x: u64,
+    #[allow(dead_code)]
+    label: [u8; 64],
+}
+
+fn demonstrate_sizes() {
+    output::heading("📏 A Closure Is Its Captures");
+    let small: u8 = 1;
+    let count: u64 = 2;
+    let name = String::from("ferris");
+    let buffer = [0u8; 100];
+    let point = Point { x: 3, label: [0; 64] };
+
+    let no_capture = |a: u64| a + 1;
+    let ref_one = || count + 1;
+    let ref_two = || count + name.len() as u64;
+    let move_u64 = move || count + 1;
+    let move_string = { let name = name.clone(); move || name.len() };
+    let move_array = move || buffer[0];
+    let move_mixed = move || small as u64 + count;
+    let field_only = || point.x;
+    let fn_pointer: fn(u64) -> u64 = no_capture;
+
+    let mut table = Table::new(["closure", "bytes"]);
+    for (text, size) in [
+        ("|a| a + 1                  (captures nothing)", mem::size_of_val(&no_capture)),
+        ("|| count + 1               (&count)", mem::size_of_val(&ref_one)),
+        ("|| count + name.len()      (&count, &name)", mem::size_of_val(&ref_two)),
+        ("move || count + 1          (count: u64)", mem::size_of_val(&move_u64)),
+        ("move || name.len()         (name: String)", mem::size_of_val(&move_string)),
+        ("move || buffer[0]          (buffer: [u8; 100])", mem::size_of_val(&move_array)),
+        ("move || small + count      (u8 + u64, padded)", mem::size_of_val(&move_mixed)),
+        ("|| point.x                 (&point.x only)", mem::size_of_val(&field_only)),
+        ("fn(u64) -> u64 pointer", mem::size_of_val(&fn_pointer)),
+    ] {
+        table.row([text.to_string(), size.to_string()]);
+    }
+    out!("{}", table);
+    black_box((no_capture(0), ref_one(), ref_two(), move_u64(), move_string(), move_array(), move_mixed(), field_only()));
+    outln!("Capturing by reference stores a pointer per variable; `move` stores the");
+    outln!("values themselves, with normal struct padding. A closure that captures");
+    outln!("nothing is zero-sized and coerces to a plain fn pointer. Since edition");
+    outln!("2021 closures capture disjoint fields: `|| point.x` borrows just the u64,");
+    outln!("not the whole {}-byte Point.\n", mem::size_of::<Point>());
+}
+
+fn call_fn(f: impl Fn() -> usize) -> usize {
+    f() + f()
+}
+
+fn call_fn_mut(mut f: impl FnMut() -> usize) -> usize {
+    f() + f()
+}
+
+fn call_fn_once(f: impl FnOnce() -> usize) -> usize {
+    f()
+}
+
+/// What the compiler generates for `move || { count += 1; count }`, by hand
+struct Counter {
+    count: usize,
+}
+
+impl Counter {
+    /// `&mut self`: the FnMut signature, because the body mutates a capture
+    fn call_mut(&mut self) -> usize {
+        self.count += 1;
+        self.count
+    }
+}
+
+fn demonstrate_traits() {
+    output::heading("🔑 Fn, FnMut, FnOnce");
+    outln!("The body decides the trait: reading captures needs &self (Fn), mutating");
+    outln!("them needs &mut self (FnMut), moving them out consumes self (FnOnce).\n");
+
+    let greeting = String::from("hello");
+    let reads = || greeting.len();
+    let mut count = 0;
+    let mut mutates = || { count += 1; count };
+    let owned = String::from("consumed");
+    let consumes = move || { let taken = owned; taken.len() };
+
+    let mark = |implemented: bool| if implemented { "✅" } else { "❌" };
+    let mut table = Table::new(["closure", "Fn", "FnMut", "FnOnce"]);
+    for (text, traits) in [
+        ("|| greeting.len()", [true, true, true]),
+        ("|| { count += 1; count }", [false, true, true]),
+        ("move || { let taken = owned; .. }", [false, false, true]),
+    ] {
+        table.row([text, mark(traits[0]), mark(traits[1]), mark(traits[2])]);
+    }
+    outln!("{}", table);
+    outln!("  call_fn(reads) = {}, call_fn_mut(reads) = {}, call_fn_once(reads) = {}",
+             call_fn(reads), call_fn_mut(reads), call_fn_once(reads));
+    outln!("  call_fn_mut(&mut mutates) = {}, call_fn_once(mutates) = {}",
+             call_fn_mut(&mut mutates), call_fn_once(&mut mutates));
+    outln!("  call_fn_once(consumes) = {}", call_fn_once(consumes));
+    outln!("Every Fn is also FnMut and FnOnce, and every FnMut is FnOnce: each");
+    outln!("trait asks less of the caller than the one before.\n");
+
+    outln!("The ❌ cells are compile errors:");
+    outln!("  let increment = || count += 1; call_fn(increment);");
+    outln!("  error[E0525]: expected a closure that implements the `Fn` trait,");
+    outln!("                but this closure only implements `FnMut`");
+    outln!("  let consume = move || owned; consume(); consume();");
+    outln!("  error[E0382]: use of moved value: `consume`\n");
+
+    outln!("`move || {{ count += 1; count }}` is, underneath, this struct:");
+    outln!("  struct Counter {{ count: usize }}");
+    outln!("  impl Counter {{ fn call_mut(&mut self) -> usize {{ self.count += 1; self.count }} }}");
+    let mut by_hand = Counter { count: 0 };
+    let mut start = 0;
+    let mut closure = move || { start += 1; start };
+    outln!("  hand-written: {}, {}, {}    closure: {}, {}, {}\n",
+             by_hand.call_mut(), by_hand.call_mut(), by_hand.call_mut(), closure(), closure(), closure());
+}
+
+const CALLS: u64 = 10_000_000;
+
+/// Generic over the closure type: monomorphized, so the call can be inlined
+#[inline(never)]
+fn run_generic<F: Fn(u64) -> u64>(f: F) -> u64 {
+    (0..CALLS).fold(0, |acc, i| acc ^ f(i))
+}
+
+/// Through a function pointer: an indirect call the optimizer can't see past
+#[inline(never)]
+fn run_fn_pointer(f: fn(u64) -> u64) -> u64 {
+    (0..CALLS).fold(0, |acc, i| acc ^ f(i))
+}
+
+/// Through a trait object: an indirect call via the vtable
+#[inline(never)]
+fn run_dyn(f: &dyn Fn(u64) -> u64) -> u64 {
+    (0..CALLS).fold(0, |acc, i| acc ^ f(i))
+}
+
+fn mix(x: u64) -> u64 {
+    x.wrapping_mul(0x9E37_79B9_7F4A_7C15).rotate_left(17)
+}
+
+fn demonstrate_call_cost(report: &mut DemoReport) {
+    let title = format!("⏱️  Call Cost ({} calls)", CALLS);
+    output::heading(&title);
+    let closure = |x: u64| mix(x);
+    let boxed: Box<dyn Fn(u64) -> u64> = Box::new(closure);
+    assert_eq!(run_generic(closure), run_fn_pointer(mix));
+    assert_eq!(run_generic(closure), run_dyn(&*boxed));
+
+    let results = bench_compare!(runs: 7;
+        "closure, generic F" => run_generic(black_box(closure)),
+        "fn pointer" => run_fn_pointer(black_box(mix as fn(u64) -> u64)),
+        "&dyn Fn" => run_dyn(black_box(&closure)),
+        "Box<dyn Fn>" => run_dyn(black_box(&*boxed)),
+    );
+    report.record_comparison(&results);
+    outln!("A generic F is a distinct type per closure, so run_generic is compiled");
+    outln!("for this exact body and the call is inlined into the loop. A fn pointer");
+    outln!("or dyn Fn is a call through an address known only at runtime: no");
+    outln!("inlining, one indirect call per element. Box<dyn Fn> makes the same");
+    outln!("vtable call as &dyn Fn (any gap between those rows is noise); the box");
+    outln!("only changes who owns the closure.\n");
+}
+
+const QUIZ: &[Question] = &[
+    Question::new(
+        "How large is a closure that captures nothing?",
+        &["0 bytes", "8 bytes", "16 bytes"],
+        0,
+        "It's a struct with no fields, and that's why it can also coerce to a plain fn pointer.",
+    ),
+    Question::new(
+        "A closure moves a captured String out of its body. Which traits does it implement?",
+        &["Fn, FnMut and FnOnce", "FnMut and FnOnce", "Only FnOnce"],
+        2,
+        "After moving the String out, the closure can't be called again.",
+    ),
+    Question::new(
+        "Why can impl Fn be faster than &dyn Fn?",
+        &[
+            "It's statically dispatched, so the call can be inlined",
+            "It avoids capturing",
+            "It runs on the stack",
+        ],
+        0,
+        "Each generic instantiation knows the exact closure type; dyn Fn calls through a vtable.",
+    ),
+];
+
+#[derive(Demo)]
+#[demo(name = "closure-demo", description = "Closure captures, Fn traits and call costs",
+       quiz = QUIZ)]
+pub struct ClosureDemo;
+
+impl ClosureDemo {
+    fn demonstrate(&self, _config: &Config) -> DemoReport {
+        output::title("🎁 Closure Internals Demo");
+        outln!("What the compiler builds when you write |x| ...\n");
+
+        demonstrate_sizes();
+        demonstrate_traits();
+        let mut report = DemoReport::default();
+        demonstrate_call_cost(&mut report);
+
+        output::takeaways();
+        outln!("• A closure is a struct of its captures: by reference or, with move, by value");
+        outln!("• Non-capturing closures are zero-sized and coerce to fn pointers");
+        outln!("• Fn / FnMut / FnOnce follow from whether the body reads, mutates or moves");
+        outln!("• impl Fn / generic F calls inline; fn pointers and dyn Fn are indirect");
+        report
+    }
+}
@@ -0,0 +1,405 @@
+//! Compilation & Optimization Demo
+//!
+//! Shows how LLVM optimizations affect performance and code generation.
+//! Run with: cargo run --bin compilation-optimization
+
+use std::hint::black_box;
+
+use crate::bench::measure;
+use crate::demo::{Config, Demo, DemoReport};
+use crate::output::Style;
+use crate::quiz::Question;
+use crate::verify::Verification;
+use crate::{outln, output};
+
+#[inline(never)] // Prevent inlining for demonstration
+pub(crate) fn fibonacci_recursive(n: u64) -> u64 {
+    if n <= 1 {
+        n
+    } else {
+        fibonacci_recursive(n - 1) + fibonacci_recursive(n - 2)
+    }
+}
+
+fn fibonacci_iterative(n: u64) -> u64 {
+    if n <= 1 {
+        return n;
+    }
+
+    let mut a = 0;
+    let mut b = 1;
+
+    for _ in 2..=n {
+        let temp = a + b;
+        a = b;
+        b = temp;
+    }
+
+    b
+}
+
+/// Sum of squares of the even elements, written as a C-style index loop...
+#[unsafe(no_mangle)]
+#[inline(never)]
+pub fn sum_even_squares_loop(data: &[u32]) -> u64 {
+    let mut total = 0u64;
+    for i in 0..data.len() {
+        if data[i].is_multiple_of(2) {
+            total += data[i] as u64 * data[i] as u64;
+        }
+    }
+    total
+}
+
+/// ...and as an iterator chain
+#[unsafe(no_mangle)]
+#[inline(never)]
+pub fn sum_even_squares_iter(data: &[u32]) -> u64 {
+    data.iter().filter(|&&x| x.is_multiple_of(2)).map(|&x| x as u64 * x as u64).sum()
+}
+
+/// Arithmetic on constants, for constant folding to collapse into one
+/// `mov eax, 62`
+#[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+#[unsafe(no_mangle)]
+#[inline(never)]
+fn constant_folding_example() -> i32 {
+    let width = 5;
+    let height = 10;
+    width * height + 3 * 4
+}
+
+#[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+#[inline(always)]
+fn add_one(x: u32) -> u32 {
+    x.wrapping_add(1)
+}
+
+/// Calls `add_one`, which is always inlined: the listing has no `call`
+#[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+#[unsafe(no_mangle)]
+#[inline(never)]
+fn inlining_example(x: u32) -> u32 {
+    add_one(x).wrapping_mul(3)
+}
+
+/// Decode one of the functions above with the built-in decoder (no objdump
+/// needed) and print it
+#[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+fn show_decoded(symbol: &str) -> Option<crate::disasm::Listing> {
+    match crate::disasm::function(symbol) {
+        Ok(listing) => {
+            outln!("{}", listing);
+            Some(listing)
+        }
+        Err(err) => {
+            outln!("⚠️  Can't decode {}: {}\n", symbol, err);
+            None
+        }
+    }
+}
+
+/// Whether the listing materializes `value` as an immediate, with no
+/// arithmetic left to do
+#[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+fn is_folded(listing: &crate::disasm::Listing, value: i32) -> bool {
+    let immediate = format!(",{:#x}", value);
+    let arithmetic = ["add", "imul", "lea", "sub"];
+    listing.instructions.iter().any(|instruction| instruction.operands.ends_with(&immediate))
+        && !listing.instructions.iter().any(|instruction| arithmetic.contains(&instruction.mnemonic.as_str()))
+}
+
+fn demonstrate_optimization_levels(report: &mut DemoReport) {
+    output::heading("⚡ Optimization Level Comparison");
+
+    outln!("Calculating Fibonacci(35)...\n");
+
+    // Test recursive version (optimization helps a lot here); each call
+    // takes long enough that a few runs suffice
+    let recursive_result = fibonacci_recursive(black_box(35));
+    let recursive = measure(3, || fibonacci_recursive(black_box(35)));
+
+    // Test iterative version (already optimized)
+    let iterative_result = fibonacci_iterative(black_box(35));
+    let iterative = measure(1_000, || fibonacci_iterative(black_box(35)));
+
+    outln!("Recursive Fibonacci(35) = {}: {}", recursive_result, recursive);
+    outln!("Iterative Fibonacci(35) = {}: {}", iterative_result, iterative);
+    outln!("Recursive is ~{:.0}x slower (medians)",
+             Style::Slower.paint(recursive.median().as_secs_f64() / iterative.median().as_secs_f64()));
+    report.record_time("recursive fibonacci", recursive.median()).param("n", 35);
+    report.record_time("iterative fibonacci", iterative.median()).param("n", 35);
+    outln!("(With optimization, LLVM can optimize tail recursion)\n");
+}
+
+fn demonstrate_constant_folding() {
+    output::heading("🔢 Constant Folding");
+
+    // LLVM will pre-calculate these at compile time
+    const COMPILE_TIME_COMPUTATION: i32 = 5 * 10 + 3 * 4;
+
+    outln!("Compile-time constant: {}", COMPILE_TIME_COMPUTATION);
+    outln!("This was calculated when you compiled, not when you run!");
+    outln!("Check the assembly: it just loads {}", COMPILE_TIME_COMPUTATION);
+    outln!();
+
+    #[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+    {
+        outln!("The same arithmetic on local variables, in a function of its own:\n");
+        // Called through a pointer so the call isn't folded too, which would
+        // leave the function unreferenced and the linker would drop it
+        let folded: fn() -> i32 = black_box(constant_folding_example);
+        assert_eq!(folded(), COMPILE_TIME_COMPUTATION);
+        if let Some(listing) = show_decoded("constant_folding_example") {
+            if is_folded(&listing, COMPILE_TIME_COMPUTATION) {
+                outln!("✅ No multiply, no add: just the answer, {:#x}.\n", COMPILE_TIME_COMPUTATION);
+            } else {
+                outln!("Without optimization the multiply and add are still there (plus");
+                outln!("overflow checks): rerun with --release to see them folded.\n");
+            }
+        }
+    }
+}
+
+fn demonstrate_dead_code_elimination() {
+    output::heading("🗑️  Dead Code Elimination");
+
+    let used_variable = 42;
+    let _dead_variable = 999; // This will be removed by LLVM
+
+    outln!("Used variable: {}", used_variable);
+    outln!("Dead variable (_dead_variable) is removed by LLVM");
+    outln!("It won't appear in the final binary\n");
+}
+
+fn demonstrate_loop_optimization(report: &mut DemoReport) {
+    output::heading("🔄 Loop Optimization");
+
+    // This loop can be optimized by LLVM
+    let sum_loop = || {
+        let mut sum = 0i64;
+        for i in 0..black_box(1_000_000) {
+            sum += i as i64;
+        }
+        sum
+    };
+    let sum = sum_loop();
+    let time = measure(20, sum_loop);
+    let expected = (999_999i64 * 1_000_000) / 2; // Gauss formula
+
+    outln!("Sum of 0..1,000,000 = {}", sum);
+    outln!("Expected (Gauss): {}", expected);
+    outln!("Time taken: {}", time);
+    report.record_time("sum loop", time.median()).param("n", 1_000_000);
+    outln!("LLVM may optimize this to: sum = n*(n-1)/2");
+    outln!();
+}
+
+fn demonstrate_vectorization(report: &mut DemoReport) {
+    output::heading("🚀 SIMD Vectorization");
+
+    let size = 100_000;
+    let a = vec![1.0f64; size];
+    let b = vec![2.0f64; size];
+    let mut result = vec![0.0f64; size];
+
+    // This loop can be vectorized by LLVM (if target CPU supports SIMD)
+    let time = measure(20, || {
+        for i in 0..size {
+            result[i] = a[i] + b[i] * 3.0;
+        }
+    });
+    black_box(&result);
+
+    outln!("Vector addition/multiplication of {} elements", size);
+    outln!("Time taken: {}", time);
+    report.record_time("a + b * 3 loop", time.median()).param("elements", size);
+    outln!("With SIMD support, this processes multiple elements per instruction");
+    outln!("Target CPU affects this: sandybridge+ enables AVX instructions\n");
+}
+
+fn demonstrate_function_inlining(report: &mut DemoReport) {
+    output::heading("📦 Function Inlining");
+
+    #[inline(always)]
+    fn small_function(x: i32) -> i32 {
+        x + 1
+    }
+
+    // LLVM may inline small_function call
+    let call_loop = || {
+        let mut result = 0i64;
+        for i in 0..black_box(1_000_000) {
+            result += small_function(i) as i64;
+        }
+        result
+    };
+    let result = call_loop();
+    let time = measure(20, call_loop);
+
+    outln!("Called small_function 1,000,000 times (result: {})", result);
+    outln!("Time taken: {}", time);
+    report.record_time("inlined call loop", time.median()).param("calls", 1_000_000);
+    outln!("#[inline(always)] forces LLVM to replace the call with: x + 1");
+    outln!("No function call overhead!\n");
+
+    #[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+    {
+        outln!("inlining_example(x) returns add_one(x) * 3; add_one is #[inline(always)]:\n");
+        black_box(inlining_example(black_box(1)));
+        if let Some(listing) = show_decoded("inlining_example") {
+            match listing.calls() {
+                0 => outln!("✅ No call instruction: add_one's body was pasted in.\n"),
+                calls => outln!("{} call(s) left: overflow checks or an unoptimized build.\n", calls),
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+fn demonstrate_zero_cost_assembly() {
+    use crate::asm::{self, Disassembly, Equivalence};
+
+    output::heading("🔬 Zero-Cost Abstractions: The Assembly");
+    outln!("The same computation, as an index loop and as filter().map().sum(),");
+    outln!("disassembled straight out of this binary:\n");
+
+    // Use the results so both functions stay in the binary
+    let data: Vec<u32> = (0..1_000).collect();
+    assert_eq!(sum_even_squares_loop(&data), sum_even_squares_iter(&data));
+
+    let disassemble = |symbol| Disassembly::of(symbol).map_err(|err| outln!("⚠️  Can't disassemble: {}\n", err)).ok();
+    let (Some(index_loop), Some(iterator)) = (disassemble("sum_even_squares_loop"), disassemble("sum_even_squares_iter"))
+    else {
+        return;
+    };
+
+    let equivalence = asm::compare(&index_loop, &iterator);
+    if equivalence == Equivalence::SameAddress {
+        outln!("Both symbols are at 0x{:x}: LLVM saw identical code and kept one copy.", index_loop.address);
+    }
+    outln!("{}", asm::side_by_side(&index_loop, &iterator, 40));
+    outln!("  {} bytes / {} instructions vs {} bytes / {} instructions",
+             index_loop.size, index_loop.instructions.len(), iterator.size, iterator.instructions.len());
+    match equivalence {
+        Equivalence::SameAddress => outln!("  Verdict: ✅ one function body - the abstraction cost nothing"),
+        Equivalence::Identical => outln!("  Verdict: ✅ identical instructions (jump targets relative to each function)"),
+        Equivalence::Reordered => {
+            outln!("  Verdict: ✅ the same instructions; the ≠ lines are just scheduled in a");
+            outln!("           different order");
+        }
+        Equivalence::Different => outln!("  Verdict: ❌ the code differs (≠ lines) - compare the hot loops above"),
+    }
+    if cfg!(debug_assertions) {
+        outln!("  ⚠️  This is a debug build: iterator adapters aren't inlined here, so");
+        outln!("     rerun with --release to see the zero-cost result.");
+    }
+    outln!();
+}
+
+/// Both ways of writing each function give the same answers, the linear
+/// Fibonacci beats the exponential one by a wide margin, and (in release
+/// builds) the decoded examples show folding and inlining
+fn verify() -> Verification {
+    let mut verification = Verification::new();
+    let recursive: Vec<u64> = (0..25).map(fibonacci_recursive).collect();
+    let iterative: Vec<u64> = (0..25).map(fibonacci_iterative).collect();
+    verification.equal("recursive and iterative Fibonacci agree", recursive, iterative);
+    let mut rng = Config::default().rng();
+    let data: Vec<u32> = (0..100_000).map(|_| rng.below(1 << 16) as u32).collect();
+    verification.equal("the index loop and the iterator chain agree",
+                       sum_even_squares_loop(&data), sum_even_squares_iter(&data));
+
+    let exponential = measure(3, || fibonacci_recursive(black_box(25)));
+    let linear = measure(3, || fibonacci_iterative(black_box(25)));
+    verification.faster("iterative Fibonacci beats recursive at n = 25", &linear, &exponential, 10.0);
+
+    #[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+    match (crate::disasm::function("constant_folding_example"), crate::disasm::function("inlining_example")) {
+        (Ok(folded), Ok(inlined)) => {
+            verification.check("the built-in decoder reads both example functions to the end",
+                               folded.is_complete() && inlined.is_complete(),
+                               || format!("{} of {} and {} of {} bytes decoded", folded.decoded(), folded.size,
+                                          inlined.decoded(), inlined.size));
+            if cfg!(debug_assertions) {
+                verification.skip("constant folding and inlining show in the machine code", "debug build");
+            } else {
+                verification.check("constant folding and inlining show in the machine code",
+                                   is_folded(&folded, 62) && inlined.calls() == 0,
+                                   || format!("{}\n{}", folded, inlined));
+            }
+        }
+        (Err(err), _) | (_, Err(err)) => {
+            verification.skip("the built-in decoder reads the example functions", err.to_string());
+        }
+    }
+    verification
+}
+
+const QUIZ: &[Question] = &[
+    Question::new(
+        "Why is recursive Fibonacci(35) so much slower than the iterative version?",
+        &[
+            "Function calls are slow in Rust",
+            "The recursion recomputes the same subproblems, making tens of millions of calls, while the loop does 35 additions",
+            "The recursive version overflows and retries",
+        ],
+        1,
+        "The gap is algorithmic (exponential vs linear), not something an optimizer can close.",
+    ),
+    Question::new(
+        "In a release build, summing 0..1,000,000 took nanoseconds. Why?",
+        &[
+            "LLVM replaced the loop with a closed-form computation",
+            "The result was cached by the CPU",
+            "The CPU executes a million additions per nanosecond",
+        ],
+        0,
+        "Loop idiom recognition turns the sum into n*(n-1)/2: the loop never runs.",
+    ),
+    Question::new(
+        "What did the disassembly of the index loop and the iterator chain show?",
+        &[
+            "The iterator version makes extra function calls",
+            "The index loop avoids bounds checks the iterator needs",
+            "Both compile to the same (or equivalent) machine code",
+        ],
+        2,
+        "Iterator adapters inline away: that's the 'zero-cost' in zero-cost abstractions.",
+    ),
+];
+
+#[derive(Demo)]
+#[demo(name = "compilation-optimization", description = "How LLVM optimizations affect generated code",
+       quiz = QUIZ, verify = verify)]
+pub struct CompilationOptimization;
+
+impl CompilationOptimization {
+    fn demonstrate(&self, _config: &Config) -> DemoReport {
+        output::title("⚙️  Compilation & Optimization Demo");
+        outln!("How LLVM makes your Rust code faster.\n");
+
+        let mut report = DemoReport::default();
+        demonstrate_optimization_levels(&mut report);
+        demonstrate_constant_folding();
+        demonstrate_dead_code_elimination();
+        demonstrate_loop_optimization(&mut report);
+        demonstrate_vectorization(&mut report);
+        demonstrate_function_inlining(&mut report);
+        #[cfg(unix)]
+        demonstrate_zero_cost_assembly();
+
+        output::takeaways();
+        outln!("• LLVM performs extensive optimizations at compile time");
+        outln!("• Optimization level affects compilation speed vs runtime speed");
+        outln!("• Target CPU architecture enables better instructions (AVX, SIMD)");
+        outln!("• Many optimizations happen regardless of --release flag");
+        outln!("• Profile your code to see what optimizations help most");
+        outln!("• Iterator chains compile to the same machine code as hand-written loops");
+
+        outln!("\n💡 Try running with different optimization levels:");
+        outln!("   cargo run --release --bin compilation-optimization  # Optimized");
+        outln!("   cargo run --bin compilation-optimization           # Debug");
+        report
+    }
+}
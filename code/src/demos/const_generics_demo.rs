@@ -0,0 +1,249 @@
+//! Const Generics Matrix Demo
+//!
+//! A `Matrix<R, C>` whose dimensions are part of its type: multiplying a
+//! 2x3 by a 2x3 doesn't compile, and the compiler knows every loop bound.
+//! Compared with a Vec-of-Vec matrix that checks dimensions at runtime.
+//! Run with: cargo run --release --bin const-generics-demo
+
+use std::hint::black_box;
+use std::mem;
+use std::ops::{Add, Mul};
+
+use crate::bench::measure;
+use crate::demo::{Config, Demo, DemoReport};
+use crate::output::Table;
+use crate::quiz::Question;
+use crate::{out, outln, output};
+
+/// R rows by C columns, stored inline: no heap, no length fields
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Matrix<const R: usize, const C: usize> {
+    data: [[f64; C]; R],
+}
+
+impl<const R: usize, const C: usize> Matrix<R, C> {
+    fn zero() -> Self {
+        Matrix { data: [[0.0; C]; R] }
+    }
+
+    fn from_fn(f: impl Fn(usize, usize) -> f64) -> Self {
+        let mut m = Self::zero();
+        for r in 0..R {
+            for c in 0..C {
+                m.data[r][c] = f(r, c);
+            }
+        }
+        m
+    }
+
+    /// An R x C matrix becomes C x R: the return type says so
+    fn transpose(&self) -> Matrix<C, R> {
+        Matrix::from_fn(|r, c| self.data[c][r])
+    }
+}
+
+impl<const N: usize> Matrix<N, N> {
+    /// Only exists for square matrices
+    fn identity() -> Self {
+        Self::from_fn(|r, c| if r == c { 1.0 } else { 0.0 })
+    }
+}
+
+/// (R x K) * (K x C) = (R x C); the shared K is enforced by the type system
+impl<const R: usize, const K: usize, const C: usize> Mul<Matrix<K, C>> for Matrix<R, K> {
+    type Output = Matrix<R, C>;
+
+    fn mul(self, rhs: Matrix<K, C>) -> Matrix<R, C> {
+        let mut out = Matrix::zero();
+        for r in 0..R {
+            for k in 0..K {
+                let a = self.data[r][k];
+                for c in 0..C {
+                    out.data[r][c] += a * rhs.data[k][c];
+                }
+            }
+        }
+        out
+    }
+}
+
+impl<const R: usize, const C: usize> Add for Matrix<R, C> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self::from_fn(|r, c| self.data[r][c] + rhs.data[r][c])
+    }
+}
+
+/// The same matrix with dimensions known only at runtime
+#[derive(Debug, Clone, PartialEq)]
+struct DynMatrix {
+    rows: Vec<Vec<f64>>,
+}
+
+impl DynMatrix {
+    fn from_fn(rows: usize, cols: usize, f: impl Fn(usize, usize) -> f64) -> Self {
+        DynMatrix { rows: (0..rows).map(|r| (0..cols).map(|c| f(r, c)).collect()).collect() }
+    }
+
+    fn shape(&self) -> (usize, usize) {
+        (self.rows.len(), self.rows.first().map_or(0, Vec::len))
+    }
+
+    fn transpose(&self) -> DynMatrix {
+        let (rows, cols) = self.shape();
+        DynMatrix::from_fn(cols, rows, |r, c| self.rows[c][r])
+    }
+
+    /// Every caller has to handle a dimension mismatch, at runtime
+    fn multiply(&self, rhs: &DynMatrix) -> Result<DynMatrix, String> {
+        let ((rows, inner), (rhs_rows, cols)) = (self.shape(), rhs.shape());
+        if inner != rhs_rows {
+            return Err(format!("can't multiply {}x{} by {}x{}", rows, inner, rhs_rows, cols));
+        }
+        let mut out = vec![vec![0.0; cols]; rows];
+        for r in 0..rows {
+            for k in 0..inner {
+                let a = self.rows[r][k];
+                for c in 0..cols {
+                    out[r][c] += a * rhs.rows[k][c];
+                }
+            }
+        }
+        Ok(DynMatrix { rows: out })
+    }
+}
+
+fn demonstrate_type_checked_dimensions() {
+    output::heading("📐 Dimensions in the Type");
+
+    let a: Matrix<2, 3> = Matrix::from_fn(|r, c| (r * 3 + c + 1) as f64);
+    let b: Matrix<3, 2> = a.transpose();
+    let product: Matrix<2, 2> = a * b;
+    outln!("  a: Matrix<2, 3> = {:?}", a.data);
+    outln!("  a.transpose(): Matrix<3, 2> = {:?}", b.data);
+    outln!("  a * a.transpose(): Matrix<2, 2> = {:?}", product.data);
+    outln!("  (a * b) + I = {:?}", (product + Matrix::identity()).data);
+    outln!();
+
+    outln!("Mismatched shapes are rejected before the program ever runs:");
+    outln!("  let bad = a * a;   // Matrix<2, 3> * Matrix<2, 3>");
+    outln!("  error[E0308]: mismatched types");
+    outln!("    expected struct `Matrix<3, _>`");
+    outln!("       found struct `Matrix<2, 3>`");
+    outln!("  Matrix::<2, 3>::identity()");
+    outln!("  error[E0599]: no function or associated item named `identity` found");
+    outln!("                for struct `Matrix<2, 3>`");
+    outln!();
+
+    let dyn_a = DynMatrix::from_fn(2, 3, |r, c| (r * 3 + c + 1) as f64);
+    outln!("The Vec-of-Vec version compiles the same mistake and fails at runtime:");
+    outln!("  dyn_a.multiply(&dyn_a) = {:?}", dyn_a.multiply(&dyn_a).map(|m| m.shape()));
+    outln!("  dyn_a.multiply(&dyn_a.transpose()) = {:?}\n",
+             dyn_a.multiply(&dyn_a.transpose()).map(|m| m.rows));
+}
+
+fn demonstrate_memory_layout() {
+    output::heading("🧱 Memory Layout");
+    let mut table = Table::new(["type", "size_of", "heap allocations"]);
+    table.row(["Matrix<4, 4>".to_string(), mem::size_of::<Matrix<4, 4>>().to_string(), "0".to_string()]);
+    table.row(["Matrix<16, 16>".to_string(), mem::size_of::<Matrix<16, 16>>().to_string(), "0".to_string()]);
+    table.row(["DynMatrix (4x4)".to_string(), mem::size_of::<DynMatrix>().to_string(), "1 + 4 rows".to_string()]);
+    table.row(["DynMatrix (16x16)".to_string(), mem::size_of::<DynMatrix>().to_string(), "1 + 16 rows".to_string()]);
+    out!("{}", table);
+    outln!("The const-generic matrix is just its numbers, contiguous and inline (it");
+    outln!("can live on the stack). The dynamic one is a pointer, length and capacity");
+    outln!("leading to more pointers: each row a separate allocation.\n");
+}
+
+/// Time `reps` multiplications of N x N matrices, both representations
+fn bench_size<const N: usize>(reps: usize) -> (f64, f64) {
+    let a: Matrix<N, N> = Matrix::from_fn(|r, c| ((r * N + c) % 7) as f64 * 0.5);
+    let b: Matrix<N, N> = Matrix::from_fn(|r, c| ((r + c) % 5) as f64 * 0.25);
+    let dyn_a = DynMatrix::from_fn(N, N, |r, c| a.data[r][c]);
+    let dyn_b = DynMatrix::from_fn(N, N, |r, c| b.data[r][c]);
+    assert_eq!((a * b).data.iter().map(|row| row.to_vec()).collect::<Vec<_>>(), dyn_a.multiply(&dyn_b).unwrap().rows);
+
+    let fixed = measure(5, || {
+        for _ in 0..reps {
+            black_box(black_box(a) * black_box(b));
+        }
+    })
+    .median();
+    let dynamic = measure(5, || {
+        for _ in 0..reps {
+            black_box(black_box(&dyn_a).multiply(black_box(&dyn_b)).unwrap());
+        }
+    })
+    .median();
+    (fixed.as_nanos() as f64 / reps as f64, dynamic.as_nanos() as f64 / reps as f64)
+}
+
+fn demonstrate_performance(report: &mut DemoReport) {
+    output::heading("⏱️  Multiply: Const Generic vs Vec<Vec<f64>>");
+    let mut table = Table::new(["size", "Matrix<N, N>", "DynMatrix", "speedup"]);
+    for (size, (fixed, dynamic)) in [
+        ("4x4", bench_size::<4>(200_000)),
+        ("8x8", bench_size::<8>(50_000)),
+        ("16x16", bench_size::<16>(10_000)),
+        ("64x64", bench_size::<64>(200)),
+    ] {
+        table.row([size.to_string(), format!("{:.0} ns", fixed), format!("{:.0} ns", dynamic),
+                   output::speedup(dynamic / fixed)]);
+        report.record("Matrix<N, N> multiply", fixed, "ns").param("size", size);
+        report.record("DynMatrix multiply", dynamic, "ns").param("size", size);
+    }
+    out!("{}", table);
+    outln!("With N a compile-time constant, LLVM fully unrolls small loops, keeps");
+    outln!("values in registers and vectorizes with no bounds checks. The dynamic");
+    outln!("version pays for allocating the result row by row, chasing a pointer per");
+    outln!("row, and loop bounds it can't see. The gap shrinks as matrices grow and");
+    outln!("arithmetic dominates.\n");
+}
+
+const QUIZ: &[Question] = &[
+    Question::new(
+        "What happens when you multiply a Matrix<2, 3> by another Matrix<2, 3>?",
+        &[
+            "A panic at run time",
+            "A compile error: the shapes are part of the types",
+            "It returns an empty matrix",
+        ],
+        1,
+        "Multiplication is only implemented for Matrix<R, K> times Matrix<K, C>, so a shape mismatch can't type-check.",
+    ),
+    Question::new(
+        "Why can const-generic matrices skip the heap?",
+        &[
+            "Their size is known at compile time, so the elements are stored inline in arrays",
+            "They use a global buffer",
+            "They're always tiny",
+        ],
+        0,
+        "A [[f64; C]; R] lives wherever the matrix lives, with no allocation and loops LLVM can unroll.",
+    ),
+];
+
+#[derive(Demo)]
+#[demo(name = "const-generics-demo", description = "A matrix whose dimensions are part of its type",
+       quiz = QUIZ)]
+pub struct ConstGenericsDemo;
+
+impl ConstGenericsDemo {
+    fn demonstrate(&self, _config: &Config) -> DemoReport {
+        output::title("🔢 Const Generics Matrix Demo");
+        outln!("Matrix dimensions as compile-time parameters.\n");
+
+        demonstrate_type_checked_dimensions();
+        demonstrate_memory_layout();
+        let mut report = DemoReport::default();
+        demonstrate_performance(&mut report);
+
+        output::takeaways();
+        outln!("• Const generics put values (like sizes) into types: Matrix<R, C>");
+        outln!("• Shape errors become compile errors, and methods can exist per shape");
+        outln!("• Known sizes mean inline storage, no allocation, unrolled loops");
+        outln!("• Use runtime sizes when dimensions come from input; const when they're fixed");
+        report
+    }
+}
@@ -0,0 +1,605 @@
+//! DNS Resolution Demo
+//!
+//! What happens behind `"example.com:80".to_socket_addrs()`: a DNS query
+//! packet built byte by byte (a 12-byte header, the name as length-prefixed
+//! labels, a type and a class), sent over UDP, and the answer parsed back,
+//! name compression pointers included. Queries go to a tiny authoritative
+//! server the demo runs on loopback, and to the system's nameserver when
+//! one answers. Then the same lookups through `std::net::ToSocketAddrs`,
+//! which hands the name to the C library's `getaddrinfo`.
+//! Run with: cargo run --release --bin dns-demo
+
+use std::fmt;
+use std::io;
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, ToSocketAddrs, UdpSocket};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::bench::Measurement;
+use crate::demo::{Config, Demo, DemoReport};
+use crate::output::Table;
+use crate::quiz::Question;
+use crate::verify::Verification;
+use crate::{outln, output};
+
+/// Queries timed against each server, unless the config sets `iterations`
+const QUERIES: usize = 200;
+
+/// How long to wait for a nameserver that may not be reachable
+const TIMEOUT: Duration = Duration::from_secs(1);
+
+const TYPE_A: u16 = 1;
+const TYPE_CNAME: u16 = 5;
+const TYPE_AAAA: u16 = 28;
+const CLASS_IN: u16 = 1;
+
+/// Recursion desired: "resolve it for me", set by stub resolvers
+const FLAG_RD: u16 = 0x0100;
+/// A response, not a query
+const FLAG_QR: u16 = 0x8000;
+/// The server is authoritative for the name
+const FLAG_AA: u16 = 0x0400;
+const RCODE_NXDOMAIN: u16 = 3;
+
+/// What the loopback server answers for
+const ZONE: &[(&str, Ipv4Addr)] = &[
+    ("example.com", Ipv4Addr::new(93, 184, 215, 14)),
+    ("demo.systems.test", Ipv4Addr::new(10, 0, 0, 7)),
+];
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+// ---- The wire format ----
+
+/// The fixed 12 bytes at the start of every message
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Header {
+    id: u16,
+    flags: u16,
+    questions: u16,
+    answers: u16,
+    authorities: u16,
+    additionals: u16,
+}
+
+impl Header {
+    fn write(&self, out: &mut Vec<u8>) {
+        for field in [self.id, self.flags, self.questions, self.answers, self.authorities, self.additionals] {
+            out.extend_from_slice(&field.to_be_bytes());
+        }
+    }
+
+    fn read(packet: &[u8]) -> io::Result<Header> {
+        let field = |i: usize| -> io::Result<u16> {
+            let bytes = packet.get(i * 2..i * 2 + 2).ok_or_else(|| invalid("shorter than a header"))?;
+            Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+        };
+        Ok(Header {
+            id: field(0)?,
+            flags: field(1)?,
+            questions: field(2)?,
+            answers: field(3)?,
+            authorities: field(4)?,
+            additionals: field(5)?,
+        })
+    }
+
+    fn rcode(&self) -> u16 {
+        self.flags & 0x000F
+    }
+
+    fn rcode_name(&self) -> &'static str {
+        match self.rcode() {
+            0 => "ok",
+            RCODE_NXDOMAIN => "NXDOMAIN",
+            _ => "error",
+        }
+    }
+}
+
+/// `www.example.com` as `3 www 7 example 3 com 0`
+fn write_name(name: &str, out: &mut Vec<u8>) -> io::Result<()> {
+    for label in name.trim_end_matches('.').split('.') {
+        if label.is_empty() || label.len() > 63 {
+            return Err(invalid("a label must be 1 to 63 bytes"));
+        }
+        out.push(label.len() as u8);
+        out.extend_from_slice(label.as_bytes());
+    }
+    out.push(0);
+    Ok(())
+}
+
+/// The name at `pos`, following compression pointers; returns it and the
+/// position just past it (past the first pointer, if there was one)
+fn read_name(packet: &[u8], mut pos: usize) -> io::Result<(String, usize)> {
+    let mut labels = Vec::new();
+    let mut end = None;
+    // Each pointer must point backwards, so a loop can't go on forever,
+    // but a bound keeps a malicious packet from even trying
+    for _ in 0..128 {
+        let &len = packet.get(pos).ok_or_else(|| invalid("name runs past the end"))?;
+        match len {
+            0 => return Ok((labels.join("."), end.unwrap_or(pos + 1))),
+            len if len & 0xC0 == 0xC0 => {
+                let &low = packet.get(pos + 1).ok_or_else(|| invalid("pointer runs past the end"))?;
+                let target = (usize::from(len & 0x3F) << 8) | usize::from(low);
+                if target >= pos {
+                    return Err(invalid("compression pointer doesn't point backwards"));
+                }
+                end.get_or_insert(pos + 2);
+                pos = target;
+            }
+            len if len & 0xC0 == 0 => {
+                let label = packet.get(pos + 1..pos + 1 + usize::from(len)).ok_or_else(|| invalid("label runs past the end"))?;
+                labels.push(String::from_utf8_lossy(label).into_owned());
+                pos += 1 + usize::from(len);
+            }
+            _ => return Err(invalid("reserved label type")),
+        }
+    }
+    Err(invalid("too many labels"))
+}
+
+fn read_u16(packet: &[u8], pos: usize) -> io::Result<u16> {
+    let bytes = packet.get(pos..pos + 2).ok_or_else(|| invalid("record runs past the end"))?;
+    Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+}
+
+/// A query for `name`'s `qtype` records, asking for recursion
+fn build_query(id: u16, name: &str, qtype: u16) -> io::Result<Vec<u8>> {
+    let mut packet = Vec::with_capacity(64);
+    Header { id, flags: FLAG_RD, questions: 1, answers: 0, authorities: 0, additionals: 0 }.write(&mut packet);
+    write_name(name, &mut packet)?;
+    packet.extend_from_slice(&qtype.to_be_bytes());
+    packet.extend_from_slice(&CLASS_IN.to_be_bytes());
+    Ok(packet)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Data {
+    A(Ipv4Addr),
+    Aaaa(Ipv6Addr),
+    Cname(String),
+    Other { rtype: u16, len: usize },
+}
+
+impl fmt::Display for Data {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Data::A(address) => write!(f, "A {}", address),
+            Data::Aaaa(address) => write!(f, "AAAA {}", address),
+            Data::Cname(name) => write!(f, "CNAME {}", name),
+            Data::Other { rtype, len } => write!(f, "type {} ({} bytes)", rtype, len),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Record {
+    name: String,
+    ttl: u32,
+    data: Data,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Response {
+    header: Header,
+    question: Option<(String, u16)>,
+    answers: Vec<Record>,
+}
+
+fn parse_response(packet: &[u8]) -> io::Result<Response> {
+    let header = Header::read(packet)?;
+    if header.flags & FLAG_QR == 0 {
+        return Err(invalid("a query, not a response"));
+    }
+    let mut pos = 12;
+    let mut question = None;
+    for _ in 0..header.questions {
+        let (name, next) = read_name(packet, pos)?;
+        question.get_or_insert((name, read_u16(packet, next)?));
+        pos = next + 4;
+    }
+    let mut answers = Vec::new();
+    for _ in 0..header.answers {
+        let (name, next) = read_name(packet, pos)?;
+        let rtype = read_u16(packet, next)?;
+        let ttl = (u32::from(read_u16(packet, next + 4)?) << 16) | u32::from(read_u16(packet, next + 6)?);
+        let len = usize::from(read_u16(packet, next + 8)?);
+        let start = next + 10;
+        let rdata = packet.get(start..start + len).ok_or_else(|| invalid("record data runs past the end"))?;
+        let data = match (rtype, rdata.len()) {
+            (TYPE_A, 4) => Data::A(Ipv4Addr::new(rdata[0], rdata[1], rdata[2], rdata[3])),
+            (TYPE_AAAA, 16) => Data::Aaaa(Ipv6Addr::from(<[u8; 16]>::try_from(rdata).unwrap())),
+            (TYPE_CNAME, _) => Data::Cname(read_name(packet, start)?.0),
+            _ => Data::Other { rtype, len },
+        };
+        answers.push(Record { name, ttl, data });
+        pos = start + len;
+    }
+    Ok(Response { header, question, answers })
+}
+
+/// Send `query` to `server` and wait for the response with the same ID
+fn exchange(socket: &UdpSocket, server: SocketAddr, query: &[u8]) -> io::Result<Vec<u8>> {
+    socket.send_to(query, server)?;
+    let mut buf = [0u8; 512];
+    loop {
+        let (n, from) = socket.recv_from(&mut buf)?;
+        // Anyone can send a datagram to this port: only the server's reply
+        // to this ID counts (the ID and the random port are all that stop
+        // an off-path attacker from forging answers)
+        if from == server && buf[..n.min(2)] == query[..2] {
+            return Ok(buf[..n].to_vec());
+        }
+    }
+}
+
+// ---- A tiny authoritative server ----
+
+/// The response to `query`: the zone's A record for the name, or NXDOMAIN
+fn answer(query: &[u8]) -> io::Result<Vec<u8>> {
+    let header = Header::read(query)?;
+    let (name, next) = read_name(query, 12)?;
+    let qtype = read_u16(query, next)?;
+    let question = query.get(12..next + 4).ok_or_else(|| invalid("question runs past the end"))?;
+    let address = ZONE.iter().find(|(zone_name, _)| zone_name.eq_ignore_ascii_case(&name)).map(|&(_, address)| address);
+    let found = address.is_some() && qtype == TYPE_A;
+    let flags = FLAG_QR | FLAG_AA | (header.flags & FLAG_RD) | if address.is_some() { 0 } else { RCODE_NXDOMAIN };
+    let mut packet = Vec::with_capacity(64);
+    Header { id: header.id, flags, questions: 1, answers: u16::from(found), authorities: 0, additionals: 0 }
+        .write(&mut packet);
+    packet.extend_from_slice(question);
+    if let (true, Some(address)) = (found, address) {
+        // The owner name is a pointer back to the question's, at offset 12
+        packet.extend_from_slice(&[0xC0, 12]);
+        packet.extend_from_slice(&TYPE_A.to_be_bytes());
+        packet.extend_from_slice(&CLASS_IN.to_be_bytes());
+        packet.extend_from_slice(&300u32.to_be_bytes());
+        packet.extend_from_slice(&4u16.to_be_bytes());
+        packet.extend_from_slice(&address.octets());
+    }
+    Ok(packet)
+}
+
+/// Serve the zone on a loopback port until an empty datagram arrives
+fn start_server() -> io::Result<(SocketAddr, thread::JoinHandle<()>)> {
+    let socket = UdpSocket::bind("127.0.0.1:0")?;
+    let addr = socket.local_addr()?;
+    let server = thread::spawn(move || {
+        let mut buf = [0u8; 512];
+        while let Ok((n, from)) = socket.recv_from(&mut buf) {
+            if n == 0 {
+                return;
+            }
+            if let Ok(response) = answer(&buf[..n]) {
+                let _ = socket.send_to(&response, from);
+            }
+        }
+    });
+    Ok((addr, server))
+}
+
+fn stop_server(addr: SocketAddr, server: thread::JoinHandle<()>) {
+    if let Ok(socket) = UdpSocket::bind("127.0.0.1:0") {
+        let _ = socket.send_to(&[], addr);
+        let _ = server.join();
+    }
+}
+
+/// The first nameserver in /etc/resolv.conf: where the C library sends
+/// its queries
+#[cfg(unix)]
+fn system_nameserver() -> Option<SocketAddr> {
+    let conf = std::fs::read_to_string("/etc/resolv.conf").ok()?;
+    conf.lines()
+        .filter_map(|line| line.trim().strip_prefix("nameserver"))
+        .find_map(|address| address.trim().parse().ok())
+        .map(|ip| SocketAddr::new(ip, 53))
+}
+
+#[cfg(not(unix))]
+fn system_nameserver() -> Option<SocketAddr> {
+    None
+}
+
+// ---- The demo ----
+
+/// Print `bytes` as hex, `width` bytes to a line
+fn hex_lines(bytes: &[u8], width: usize) {
+    for chunk in bytes.chunks(width) {
+        let hex: Vec<String> = chunk.iter().map(|byte| format!("{:02x}", byte)).collect();
+        let text: String = chunk.iter().map(|&byte| if byte.is_ascii_graphic() { byte as char } else { '.' }).collect();
+        outln!("    {:<width$}  {}", hex.join(" "), text, width = width * 3 - 1);
+    }
+}
+
+fn describe(response: &Response) {
+    let header = &response.header;
+    outln!("  id {:#06x}, flags {:#06x}: response{}{}, rcode {} ({})", header.id, header.flags,
+           if header.flags & FLAG_AA != 0 { ", authoritative" } else { "" },
+           if header.flags & FLAG_RD != 0 { ", recursion desired" } else { "" },
+           header.rcode(), header.rcode_name());
+    outln!("  {} question(s), {} answer(s), {} authority, {} additional", header.questions, header.answers,
+           header.authorities, header.additionals);
+    if let Some((name, qtype)) = &response.question {
+        outln!("  question: {} type {} ({})", name, qtype, match *qtype {
+            TYPE_A => "A",
+            TYPE_AAAA => "AAAA",
+            TYPE_CNAME => "CNAME",
+            _ => "other",
+        });
+    }
+    for record in &response.answers {
+        outln!("  answer: {} TTL {}s {}", record.name, record.ttl, record.data);
+    }
+}
+
+/// The first answer, or why there isn't one
+fn summary(response: &Response) -> String {
+    match response.answers.first() {
+        Some(record) => record.data.to_string(),
+        None => response.header.rcode_name().to_string(),
+    }
+}
+
+fn demonstrate_wire_format() -> io::Result<()> {
+    output::heading("📦 A Query, Byte by Byte");
+    let query = build_query(0xBEEF, "example.com", TYPE_A)?;
+    hex_lines(&query, 16);
+    outln!("  be ef                 ID: matches the response to the query");
+    outln!("  01 00                 flags: RD (recursion desired)");
+    outln!("  00 01 00 00 00 00 00 00  one question, no records");
+    outln!("  07 example 03 com 00  the name as length-prefixed labels, no dots");
+    outln!("  00 01 00 01           type A (IPv4 address), class IN (internet)\n");
+    outln!("{} bytes, sent as one UDP datagram to port 53. No connection, no", query.len());
+    outln!("handshake: if it's lost, the resolver simply asks again after a timeout.\n");
+
+    let (addr, server) = start_server()?;
+    let socket = UdpSocket::bind("127.0.0.1:0")?;
+    socket.set_read_timeout(Some(TIMEOUT))?;
+    output::heading("📬 The Answer, Parsed");
+    outln!("From the demo's own authoritative server for {} names, on {}:", ZONE.len(), addr);
+    let response = exchange(&socket, addr, &query);
+    let missing = build_query(0xF00D, "nowhere.systems.test", TYPE_A).and_then(|query| exchange(&socket, addr, &query));
+    stop_server(addr, server);
+    let response = response?;
+    hex_lines(&response, 16);
+    describe(&parse_response(&response)?);
+    outln!("The answer repeats the question, then adds a record whose name is just");
+    outln!("c0 0c: a pointer to offset 12, where the question's name already is.");
+    outln!("Compression keeps responses under the classic 512-byte UDP limit.\n");
+    outln!("And a name the server doesn't have:");
+    describe(&parse_response(&missing?)?);
+    outln!();
+    Ok(())
+}
+
+/// Time `queries` hand-built lookups of `name` against `server`
+fn time_queries(server: SocketAddr, name: &str, queries: usize) -> io::Result<(Measurement, Response)> {
+    let bind = if server.is_ipv4() { "0.0.0.0:0" } else { "[::]:0" };
+    let socket = UdpSocket::bind(bind)?;
+    socket.set_read_timeout(Some(TIMEOUT))?;
+    let mut samples = Vec::with_capacity(queries);
+    let mut last = None;
+    for id in 0..queries {
+        let query = build_query(id as u16, name, TYPE_A)?;
+        let start = Instant::now();
+        let response = exchange(&socket, server, &query)?;
+        samples.push(start.elapsed());
+        last = Some(response);
+    }
+    let response = parse_response(&last.ok_or_else(|| invalid("no queries"))?)?;
+    Ok((Measurement::from_samples(samples), response))
+}
+
+/// Time `queries` lookups of `host` through the standard library
+fn time_std(host: &str, queries: usize) -> (Measurement, io::Result<Vec<SocketAddr>>) {
+    let mut samples = Vec::with_capacity(queries);
+    let mut result = Ok(Vec::new());
+    for _ in 0..queries {
+        let start = Instant::now();
+        result = (host, 80).to_socket_addrs().map(Iterator::collect);
+        samples.push(start.elapsed());
+        if result.is_err() {
+            break;
+        }
+    }
+    (Measurement::from_samples(samples), result)
+}
+
+fn demonstrate_latency(config: &Config, report: &mut DemoReport) {
+    let queries = config.iterations.unwrap_or(QUERIES);
+    output::heading("⏱️  What a Lookup Costs");
+    let mut table = Table::new(["lookup", "median", "p99", "result"]).left(3);
+
+    match start_server() {
+        Ok((addr, server)) => {
+            match time_queries(addr, "example.com", queries) {
+                Ok((latency, response)) => {
+                    table.row(["hand-built query, loopback server".to_string(), format!("{:.2?}", latency.median()),
+                               format!("{:.2?}", latency.percentile(99.0)), summary(&response)]);
+                    report.record_time("lookup", latency.median()).param("via", "raw-loopback");
+                }
+                Err(err) => {
+                    table.row(["hand-built query, loopback server".to_string(), String::new(), String::new(), err.to_string()]);
+                }
+            }
+            stop_server(addr, server);
+        }
+        Err(err) => outln!("⚠️  Can't start the loopback server: {}", err),
+    }
+
+    match system_nameserver() {
+        Some(nameserver) => {
+            let label = format!("hand-built query, {}", nameserver.ip());
+            match time_queries(nameserver, "example.com", queries.min(20)) {
+                Ok((latency, response)) => {
+                    table.row([label, format!("{:.2?}", latency.median()), format!("{:.2?}", latency.percentile(99.0)),
+                               summary(&response)]);
+                    report.record_time("lookup", latency.median()).param("via", "raw-system");
+                }
+                Err(err) => {
+                    table.row([label, String::new(), String::new(), format!("no answer: {}", err)]);
+                }
+            }
+        }
+        None => outln!("No nameserver in /etc/resolv.conf to query directly."),
+    }
+
+    for (host, via) in [("localhost", "std-localhost"), ("example.com", "std-example")] {
+        let (latency, result) = time_std(host, if host == "localhost" { queries } else { queries.min(20) });
+        let result = match result {
+            Ok(addrs) => addrs.iter().map(|addr| addr.ip().to_string()).collect::<Vec<_>>().join(", "),
+            Err(err) => format!("failed: {}", err),
+        };
+        table.row([format!("ToSocketAddrs(\"{}\")", host), format!("{:.2?}", latency.median()),
+                   format!("{:.2?}", latency.percentile(99.0)), result]);
+        report.record_time("lookup", latency.median()).param("via", via);
+    }
+    outln!("{}", table);
+    outln!("ToSocketAddrs calls getaddrinfo, a blocking C call. It reads");
+    outln!("/etc/nsswitch.conf to decide where to look, /etc/hosts (which is where");
+    outln!("\"localhost\" comes from, with no packet sent), then /etc/resolv.conf, and");
+    outln!("asks the nameserver for A and AAAA records, retrying on timeouts. glibc");
+    outln!("caches nothing between calls: a cache needs nscd or systemd-resolved, or");
+    outln!("the nameserver's own (which is why a repeated query gets fast). Async");
+    outln!("runtimes run it on a thread pool, since it can block for seconds.\n");
+}
+
+/// Queries survive a build/parse round trip, compression pointers are
+/// followed but loops refused, and the loopback server answers its zone
+fn verify() -> Verification {
+    let mut verification = Verification::new();
+    let query = build_query(7, "demo.systems.test", TYPE_A).unwrap();
+    verification.equal("a query's name reads back", read_name(&query, 12).ok(),
+                       Some(("demo.systems.test".to_string(), 31)));
+    let looped = [0u8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xC0, 12];
+    verification.check("a pointer to itself is refused", read_name(&looped, 12).is_err(), || "it was followed".to_string());
+
+    let (addr, server) = match start_server() {
+        Ok(server) => server,
+        Err(err) => {
+            verification.skip("the loopback server answers", err.to_string());
+            return verification;
+        }
+    };
+    let lookup = |name: &str| -> io::Result<Response> {
+        let socket = UdpSocket::bind("127.0.0.1:0")?;
+        socket.set_read_timeout(Some(TIMEOUT))?;
+        parse_response(&exchange(&socket, addr, &build_query(42, name, TYPE_A)?)?)
+    };
+    let found = lookup("Example.COM");
+    let missing = lookup("nowhere.test");
+    stop_server(addr, server);
+    verification
+        .equal("a zone name resolves (case-insensitively) through a compressed answer",
+               found.ok().map(|response| response.answers), Some(vec![Record {
+                   name: "Example.COM".to_string(),
+                   ttl: 300,
+                   data: Data::A(ZONE[0].1),
+               }]))
+        .equal("an unknown name gets NXDOMAIN", missing.ok().map(|response| response.header.rcode()), Some(RCODE_NXDOMAIN));
+    verification
+}
+
+const QUIZ: &[Question] = &[
+    Question::new(
+        "How is \"www.example.com\" written in a DNS packet?",
+        &["As the ASCII string with dots", "As length-prefixed labels: 3 www 7 example 3 com 0", "As a 32-bit hash"],
+        1,
+        "Each label is preceded by its length; a zero-length label ends the name.",
+    ),
+    Question::new(
+        "What does a name that is just c0 0c mean in a response?",
+        &[
+            "An error code",
+            "A compression pointer: the name continues at offset 12, the question's name",
+            "The end of the packet",
+        ],
+        1,
+        "The top two bits 11 mark a pointer; the other 14 bits are an offset into the message.",
+    ),
+    Question::new(
+        "Why did resolving \"localhost\" send no packet at all?",
+        &[
+            "It's cached in the kernel",
+            "getaddrinfo found it in /etc/hosts, which nsswitch.conf says to check first",
+            "Loopback names are hard-coded in Rust",
+        ],
+        1,
+        "nsswitch.conf usually says `hosts: files dns`: the hosts file wins before any query.",
+    ),
+];
+
+#[derive(Demo)]
+#[demo(name = "dns-demo", description = "DNS by hand: the wire format, a query over UDP, and getaddrinfo",
+       quiz = QUIZ, verify = verify)]
+pub struct DnsDemo;
+
+impl DnsDemo {
+    fn demonstrate(&self, config: &Config) -> DemoReport {
+        output::title("📇 DNS Resolution Demo");
+        outln!("Turning a name into an address, without a library.\n");
+
+        if let Err(err) = demonstrate_wire_format() {
+            outln!("⚠️  The loopback exchange failed: {}\n", err);
+        }
+        let mut report = DemoReport::default();
+        demonstrate_latency(config, &mut report);
+
+        output::takeaways();
+        outln!("• A DNS query is a few dozen bytes in one UDP datagram: header, name, type");
+        outln!("• Names are length-prefixed labels; responses compress them with pointers");
+        outln!("• ToSocketAddrs is getaddrinfo: hosts file, then DNS, blocking the thread");
+        outln!("• Resolution can cost a network round trip or more: cache, and don't block an event loop on it");
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Rng;
+
+    #[test]
+    fn answer_rejects_truncated_queries() {
+        let query = build_query(7, "example.com", TYPE_A).unwrap();
+        for len in 0..query.len() {
+            assert!(answer(&query[..len]).is_err(), "a {}-byte prefix was answered", len);
+        }
+        let response = parse_response(&answer(&query).unwrap()).unwrap();
+        assert_eq!(response.header.id, 7);
+        assert_eq!(response.answers.len(), 1);
+    }
+
+    #[test]
+    fn answer_survives_garbage() {
+        let mut rng = Rng::new(1);
+        for _ in 0..10_000 {
+            let len = rng.below(64);
+            let packet: Vec<u8> = (0..len).map(|_| rng.next_u64() as u8).collect();
+            let _ = answer(&packet);
+            let _ = parse_response(&packet);
+        }
+    }
+
+    #[test]
+    fn read_name_refuses_pointer_loops() {
+        let header = [0; 12];
+        // A pointer to itself, and one pointing forwards
+        assert!(read_name(&[&header[..], &[0xC0, 12]].concat(), 12).is_err());
+        assert!(read_name(&[&header[..], &[0xC0, 14, 0]].concat(), 12).is_err());
+        // Backwards pointers chain and end
+        let mut packet = vec![0; 12];
+        write_name("example.com", &mut packet).unwrap();
+        let www = packet.len();
+        packet.extend_from_slice(&[3, b'w', b'w', b'w', 0xC0, 12]);
+        let alias = packet.len();
+        packet.extend_from_slice(&[0xC0, www as u8]);
+        assert_eq!(read_name(&packet, alias).unwrap(), ("www.example.com".to_string(), alias + 2));
+    }
+}
@@ -0,0 +1,276 @@
+//! Drop Order & RAII Demo
+//!
+//! Types that announce their own destruction, used to show exactly when Rust
+//! runs destructors: locals, fields, temporaries, moves, `mem::forget` and
+//! `ManuallyDrop`.
+//! Run with: cargo run --bin drop-order-demo
+
+use std::cell::{Cell, RefCell};
+use std::mem::{self, ManuallyDrop};
+
+use crate::demo::{Config, Demo, DemoReport};
+use crate::quiz::Question;
+use crate::verify::Verification;
+use crate::{outln, output};
+
+/// Logs its creation and its drop
+struct Noisy(&'static str);
+
+impl Noisy {
+    fn new(name: &'static str) -> Self {
+        outln!("    create {}", name);
+        Noisy(name)
+    }
+
+    fn name(&self) -> &'static str {
+        self.0
+    }
+}
+
+impl Drop for Noisy {
+    fn drop(&mut self) {
+        outln!("    drop   {}", self.0);
+    }
+}
+
+/// Fields are declared a, b, c
+struct Triple {
+    _a: Noisy,
+    _b: Noisy,
+    _c: Noisy,
+}
+
+fn demonstrate_scope_order() {
+    output::heading("📚 Locals: Reverse Declaration Order");
+    {
+        let _first = Noisy::new("first");
+        let _second = Noisy::new("second");
+        let _third = Noisy::new("third");
+        outln!("    -- end of scope --");
+    }
+    outln!("Locals drop last-declared first, like a stack: later values may");
+    outln!("borrow earlier ones, so the earlier ones must outlive them.\n");
+
+    output::heading("🧱 Fields and Elements: Declaration Order");
+    {
+        let _triple = Triple { _a: Noisy::new("field a"), _b: Noisy::new("field b"), _c: Noisy::new("field c") };
+        let _array = [Noisy::new("array[0]"), Noisy::new("array[1]")];
+        outln!("    -- end of scope --");
+    }
+    outln!("Inside a value the order flips: struct fields, tuple and array elements");
+    outln!("and Vec items drop first to last. (The array, declared last, goes first.)\n");
+}
+
+fn demonstrate_temporaries() {
+    output::heading("⏳ Temporaries");
+
+    outln!("  let len = Noisy::new(\"temp\").name().len();");
+    let len = Noisy::new("temp").name().len();
+    outln!("    statement done, len = {}", len);
+    outln!("A temporary dies at the end of the statement that created it.\n");
+
+    outln!("  let _ = Noisy::new(\"ignored\");   vs   let _kept = Noisy::new(\"kept\");");
+    {
+        let _ = Noisy::new("ignored");
+        let _kept = Noisy::new("kept");
+        outln!("    -- end of scope --");
+    }
+    outln!("`_` is not a variable: nothing owns the value, so it drops at once.");
+    outln!("`_kept` is a real binding that lives to the end of the scope. This matters");
+    outln!("for guards: `let _ = mutex.lock();` unlocks immediately.\n");
+
+    outln!("  match Noisy::new(\"scrutinee\").name().len() {{ ... }}");
+    match Noisy::new("scrutinee").name().len() {
+        0 => outln!("    inside the match: empty name"),
+        n => outln!("    inside the match arm: the {}-letter temporary is still alive", n),
+    }
+    outln!("Temporaries in a match scrutinee live until the whole match ends, so a");
+    outln!("`match mutex.lock().unwrap().len()` holds the lock through every arm.\n");
+}
+
+fn consume(value: Noisy) {
+    outln!("    consume() got {}", value.name());
+}
+
+fn demonstrate_moves() {
+    output::heading("📦 Moves Transfer the Drop");
+    {
+        let moved = Noisy::new("moved into consume()");
+        let early = Noisy::new("dropped early");
+        let _stays = Noisy::new("stays");
+        consume(moved);
+        outln!("    back from consume()");
+        drop(early);
+        outln!("    after drop(early)");
+        outln!("    -- end of scope --");
+    }
+    outln!("A moved-from variable is not dropped again: the new owner is responsible.");
+    outln!("`drop(x)` is just a function that takes ownership and returns.\n");
+}
+
+fn demonstrate_opt_outs() {
+    output::heading("🚫 mem::forget and ManuallyDrop");
+    {
+        let forgotten = Noisy::new("forgotten");
+        mem::forget(forgotten);
+        outln!("    mem::forget called");
+
+        let mut manual = ManuallyDrop::new(Noisy::new("manual"));
+        outln!("    ManuallyDrop created; deref still works: {}", manual.name());
+        outln!("    -- dropping `manual` explicitly --");
+        // SAFETY: `manual` is not used again after this
+        unsafe { ManuallyDrop::drop(&mut manual) };
+
+        let _skipped = ManuallyDrop::new(Noisy::new("never dropped"));
+        outln!("    -- end of scope --");
+    }
+    outln!("`forget` and an un-dropped ManuallyDrop both leak: no destructor runs.");
+    outln!("Leaking is safe in Rust (no use-after-free), just wasteful. ManuallyDrop");
+    outln!("is how unsafe code takes over cleanup, e.g. to control field drop order");
+    outln!("or to hand ownership to C.\n");
+}
+
+/// Marks a resource as held for as long as the guard lives
+struct Guard<'a> {
+    name: &'static str,
+    held: &'a Cell<u32>,
+}
+
+impl<'a> Guard<'a> {
+    fn acquire(name: &'static str, held: &'a Cell<u32>) -> Self {
+        held.set(held.get() + 1);
+        outln!("    acquire {} (held: {})", name, held.get());
+        Guard { name, held }
+    }
+}
+
+impl Drop for Guard<'_> {
+    fn drop(&mut self) {
+        self.held.set(self.held.get() - 1);
+        outln!("    release {} (held: {})", self.name, self.held.get());
+    }
+}
+
+fn work_with_resources(held: &Cell<u32>, fail: bool) -> Result<(), String> {
+    let _connection = Guard::acquire("connection", held);
+    let _file = Guard::acquire("file", held);
+    if fail {
+        return Err("early return".to_string());
+    }
+    let _lock = Guard::acquire("lock", held);
+    Ok(())
+}
+
+fn demonstrate_raii() {
+    output::heading("🔒 RAII: Cleanup on Every Path");
+    let held = Cell::new(0);
+
+    outln!("  success path:");
+    let _ = work_with_resources(&held, false);
+    outln!("  early-return path:");
+    let _ = work_with_resources(&held, true);
+    outln!("  panicking path:");
+    #[cfg(panic = "unwind")]
+    {
+        let previous_hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(|_| {})); // keep the expected panic quiet
+        let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let _file = Guard::acquire("file", &held);
+            panic!("something failed");
+        }));
+        std::panic::set_hook(previous_hook);
+    }
+    #[cfg(not(panic = "unwind"))]
+    outln!("    (this build aborts on panic instead of unwinding, as wasm32 does: skipped)");
+
+    outln!("Resources still held afterwards: {}", held.get());
+    outln!("Cleanup is tied to scope, not to remembering to call close(): every");
+    outln!("return, `?` and panic unwinds through the same destructors, in reverse");
+    outln!("acquisition order. No finally blocks, no garbage collector.\n");
+}
+
+/// Appends its name to a shared log when dropped, quietly, for `verify`
+struct Logged<'a>(&'static str, &'a RefCell<Vec<&'static str>>);
+
+impl Drop for Logged<'_> {
+    fn drop(&mut self) {
+        self.1.borrow_mut().push(self.0);
+    }
+}
+
+/// Locals drop in reverse, fields in order, `_` at once, and forgotten
+/// values never
+fn verify() -> Verification {
+    let mut verification = Verification::new();
+    let log = RefCell::new(Vec::new());
+    {
+        let _first = Logged("first", &log);
+        let _second = Logged("second", &log);
+    }
+    verification.equal("locals drop in reverse declaration order", log.take(), vec!["second", "first"]);
+    {
+        let _fields = (Logged("a", &log), Logged("b", &log), Logged("c", &log));
+    }
+    verification.equal("fields drop in declaration order", log.take(), vec!["a", "b", "c"]);
+    {
+        let _ = Logged("ignored", &log);
+        let _kept = Logged("kept", &log);
+        log.borrow_mut().push("end of scope");
+    }
+    verification.equal("`let _` drops at once, a binding at the end of the scope", log.take(),
+                       vec!["ignored", "end of scope", "kept"]);
+    mem::forget(Logged("forgotten", &log));
+    verification.equal("mem::forget skips the destructor", log.take(), Vec::<&str>::new());
+    verification
+}
+
+const QUIZ: &[Question] = &[
+    Question::new(
+        "In what order are a function's local variables dropped?",
+        &["Declaration order", "Reverse declaration order", "Alphabetical order"],
+        1,
+        "Later locals may borrow earlier ones, so they must go first.",
+    ),
+    Question::new(
+        "And the fields of a struct?",
+        &["Declaration order", "Reverse declaration order", "Unspecified"],
+        0,
+        "Fields (and elements of arrays and Vecs) drop first to last.",
+    ),
+    Question::new(
+        "What does mem::forget do to a value?",
+        &[
+            "Drops it immediately",
+            "Skips its destructor, leaking whatever it owned, and is safe",
+            "Causes undefined behavior",
+        ],
+        1,
+        "Leaking is memory-safe, which is why forget isn't unsafe; RAII guards can't rely on their destructor running.",
+    ),
+];
+
+#[derive(Demo)]
+#[demo(name = "drop-order-demo", description = "When destructors run: locals, fields, temporaries, moves",
+       quiz = QUIZ, verify = verify)]
+pub struct DropOrderDemo;
+
+impl DropOrderDemo {
+    fn demonstrate(&self, _config: &Config) -> DemoReport {
+        output::title("🗑️  Drop Order & RAII Demo");
+        outln!("When exactly does Rust run destructors?\n");
+
+        demonstrate_scope_order();
+        demonstrate_temporaries();
+        demonstrate_moves();
+        demonstrate_opt_outs();
+        demonstrate_raii();
+
+        output::takeaways();
+        outln!("• Locals drop in reverse declaration order; fields and elements in order");
+        outln!("• Temporaries drop at the end of their statement (or of the whole match)");
+        outln!("• Moving a value moves the responsibility to drop it");
+        outln!("• mem::forget and ManuallyDrop opt out of destructors (a safe leak)");
+        outln!("• RAII ties resource cleanup to ownership, on every exit path");
+        DemoReport::default()
+    }
+}
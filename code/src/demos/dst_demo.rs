@@ -0,0 +1,208 @@
+//! Dynamically Sized Types & Fat Pointers Demo
+//!
+//! `[T]`, `str` and `dyn Trait` have no size known at compile time, so they
+//! only ever live behind a pointer, and that pointer carries the missing
+//! information: a length for slices and strings, a vtable for trait objects.
+//! This demo opens those pointers up, builds a custom DST, and compares
+//! `Box<[T]>` with `Vec<T>`.
+//! Run with: cargo run --bin dst-demo
+
+use std::fmt::{Debug, Display};
+use std::mem;
+use std::rc::Rc;
+
+use crate::demo::{Config, Demo, DemoReport};
+use crate::output::Table;
+use crate::quiz::Question;
+use crate::verify::Verification;
+use crate::{out, outln, output};
+
+/// The two words of a fat pointer (data address, metadata)
+fn fat_words<T: ?Sized>(pointer: &T) -> [usize; 2] {
+    assert_eq!(mem::size_of::<&T>(), 2 * mem::size_of::<usize>(), "not a fat pointer");
+    // SAFETY: checked above that &T is exactly two words
+    unsafe { mem::transmute_copy(&pointer) }
+}
+
+fn demonstrate_pointer_sizes() {
+    output::heading("📏 Thin and Fat Pointers");
+    let mut table = Table::new(["pointer type", "bytes", "metadata"]).left(2);
+    for (name, size, metadata) in [
+        ("&u64", mem::size_of::<&u64>(), "none: the type says how big the target is"),
+        ("&[u64; 4]", mem::size_of::<&[u64; 4]>(), "none: the length is in the type"),
+        ("&[u64]", mem::size_of::<&[u64]>(), "element count"),
+        ("&str", mem::size_of::<&str>(), "length in bytes"),
+        ("&dyn Debug", mem::size_of::<&dyn Debug>(), "vtable pointer"),
+        ("*const [u8]", mem::size_of::<*const [u8]>(), "element count (raw pointers too)"),
+        ("Box<[u64]>", mem::size_of::<Box<[u64]>>(), "element count"),
+        ("Box<dyn Display>", mem::size_of::<Box<dyn Display>>(), "vtable pointer"),
+        ("Rc<str>", mem::size_of::<Rc<str>>(), "length in bytes"),
+        ("Option<&[u64]>", mem::size_of::<Option<&[u64]>>(), "null data pointer = None"),
+    ] {
+        table.row([name.to_string(), size.to_string(), metadata.to_string()]);
+    }
+    out!("{}", table);
+    outln!("Any pointer to an unsized type is two words wide, whatever the pointer");
+    outln!("kind: &, &mut, *const, Box, Rc, Arc.\n");
+}
+
+fn demonstrate_metadata() {
+    output::heading("🔍 What the Second Word Holds");
+
+    let numbers: [u32; 6] = [10, 20, 30, 40, 50, 60];
+    let slice: &[u32] = &numbers[1..4];
+    let [address, length] = fat_words(slice);
+    outln!("  &numbers[1..4]: [{:#x}, {}]", address, length);
+    outln!("    address = numbers + 1 * 4 bytes: {}", address == numbers.as_ptr() as usize + 4);
+    outln!("    length = 3 elements, size_of_val = {} bytes", mem::size_of_val(slice));
+
+    let text = "héllo";
+    let [address, length] = fat_words(text);
+    outln!("  \"héllo\": [{:#x}, {}]", address, length);
+    outln!("    {} bytes but {} chars: the metadata counts UTF-8 bytes", length, text.chars().count());
+
+    let value: u16 = 7;
+    let object: &dyn Debug = &value;
+    let [address, vtable] = fat_words(object);
+    outln!("  &7u16 as &dyn Debug: [{:#x}, {:#x}]", address, vtable);
+    outln!("    address = &value: {}, size_of_val = {} (read from the vtable)",
+             address == &value as *const u16 as usize, mem::size_of_val(object));
+    // SAFETY: vtables start with drop_in_place, size, align (see trait-object-demo)
+    let vtable_size = unsafe { *(vtable as *const usize).add(1) };
+    outln!("    vtable[1] = {} = size_of::<u16>()", vtable_size);
+    outln!("size_of_val follows the metadata: length * element size for slices, the");
+    outln!("vtable's size entry for trait objects. An unsized value can't sit in a local:");
+    outln!("  let owned: str = *text;");
+    outln!("  error[E0277]: the size for values of type `str` cannot be known at compilation time\n");
+}
+
+/// A custom DST: a fixed header followed by an unsized tail.
+/// Only the last field may be unsized.
+struct Packet<T: ?Sized> {
+    id: u32,
+    flags: u16,
+    payload: T,
+}
+
+/// Takes any packet: a &Packet<[u8; N]> coerces to &Packet<[u8]>
+fn describe(packet: &Packet<[u8]>) -> String {
+    format!("id {} flags {:#06b}, {} payload bytes {:?}", packet.id, packet.flags, packet.payload.len(), &packet.payload)
+}
+
+fn demonstrate_custom_dst() {
+    output::heading("🧱 A Custom DST");
+    outln!("  struct Packet<T: ?Sized> {{ id: u32, flags: u16, payload: T }}\n");
+
+    let small: Packet<[u8; 3]> = Packet { id: 1, flags: 0b01, payload: [1, 2, 3] };
+    let large: Packet<[u8; 9]> = Packet { id: 2, flags: 0b10, payload: [9; 9] };
+    // Unsizing coercion: the array length moves from the type into the pointer
+    let packets: [&Packet<[u8]>; 2] = [&small, &large];
+    for packet in packets {
+        let [_, length] = fat_words(packet);
+        outln!("  &Packet<[u8]>: metadata {}, size_of_val {:>2}  {}", length, mem::size_of_val(packet), describe(packet));
+    }
+
+    let boxed: Box<Packet<[u8]>> = Box::new(Packet { id: 3, flags: 0, payload: [0xAB; 5] });
+    outln!("  Box<Packet<[u8]>>: {} bytes of pointer, {} bytes on the heap",
+             mem::size_of_val(&boxed), mem::size_of_val(&*boxed));
+    outln!("Packets of different lengths share one type, Packet<[u8]>, and one slot");
+    outln!("in an array of pointers. The header (4 + 2 bytes) is followed by the");
+    outln!("payload inline: one allocation, no separate Vec.\n");
+}
+
+fn demonstrate_box_slice_vs_vec() {
+    output::heading("📦 Box<[T]> vs Vec<T>");
+    let mut vec: Vec<u64> = Vec::with_capacity(100);
+    vec.extend(1..=10);
+    outln!("  Vec<u64>:   {} bytes (ptr, capacity, len), len {} capacity {}",
+             mem::size_of_val(&vec), vec.len(), vec.capacity());
+
+    let boxed: Box<[u64]> = vec.into_boxed_slice();
+    outln!("  Box<[u64]>: {} bytes (ptr, len), len {}: into_boxed_slice shrank the",
+             mem::size_of_val(&boxed), boxed.len());
+    outln!("              allocation to exactly fit, dropping 90 unused slots");
+
+    let mut boxed = boxed;
+    boxed[0] = 100;
+    boxed.sort_unstable_by(|a, b| b.cmp(a));
+    outln!("  still a mutable slice: boxed[0] = 100, sorted -> {:?}", boxed);
+    outln!("  but no push: Box<[T]> has no capacity to grow into");
+    let mut vec = boxed.into_vec();
+    vec.push(11);
+    outln!("  into_vec() (no copy) then push -> len {} capacity {}", vec.len(), vec.capacity());
+
+    let shared: Rc<[u64]> = Rc::from(vec);
+    outln!("  Rc<[u64]>: {} bytes, counts and elements in one allocation", mem::size_of_val(&shared));
+    outln!("Use Vec while building, Box<[T]> (or Rc<[T]>/Arc<str>) for data that's");
+    outln!("done changing size: one word smaller and no wasted capacity.\n");
+}
+
+/// Pointers to unsized types are two words, and the second word is the
+/// length or the vtable that `size_of_val` reads
+fn verify() -> Verification {
+    let mut verification = Verification::new();
+    let word = mem::size_of::<usize>();
+    verification
+        .equal("thin pointers are one word", [mem::size_of::<&u64>(), mem::size_of::<&[u64; 4]>()], [word; 2])
+        .equal("pointers to unsized types are two",
+               [mem::size_of::<&[u64]>(), mem::size_of::<&str>(), mem::size_of::<&dyn Debug>(), mem::size_of::<Box<[u64]>>()],
+               [2 * word; 4]);
+
+    let numbers: [u32; 6] = [10, 20, 30, 40, 50, 60];
+    let slice: &[u32] = &numbers[1..4];
+    let value: u16 = 7;
+    let object: &dyn Debug = &value;
+    verification
+        .equal("a slice's metadata is its element count", fat_words(slice), [numbers.as_ptr() as usize + 4, 3])
+        .equal("a str's metadata counts bytes, not chars", fat_words("héllo")[1], 6)
+        .equal("size_of_val reads a trait object's size from the vtable", mem::size_of_val(object), 2);
+
+    let small: Packet<[u8; 3]> = Packet { id: 1, flags: 0, payload: [1, 2, 3] };
+    let packet: &Packet<[u8]> = &small;
+    verification.equal("a custom DST carries its tail's length", fat_words(packet)[1], 3);
+    verification
+}
+
+const QUIZ: &[Question] = &[
+    Question::new(
+        "How large is a &[u8] on a 64-bit target?",
+        &["8 bytes", "16 bytes: a pointer and a length", "The length of the slice"],
+        1,
+        "Pointers to unsized types are fat: they carry the metadata needed to know the size.",
+    ),
+    Question::new(
+        "Why can't a local variable have type [u8] or str?",
+        &[
+            "Their size isn't known at compile time, so they can only sit behind a pointer",
+            "They're always on the heap",
+            "They're reserved for the standard library",
+        ],
+        0,
+        "The compiler needs a fixed stack slot size, which an unsized type can't provide.",
+    ),
+];
+
+#[derive(Demo)]
+#[demo(name = "dst-demo", description = "Dynamically sized types and fat pointer metadata",
+       quiz = QUIZ, verify = verify)]
+pub struct DstDemo;
+
+impl DstDemo {
+    fn demonstrate(&self, _config: &Config) -> DemoReport {
+        output::title("📐 Dynamically Sized Types Demo");
+        outln!("Types without a compile-time size, and the fat pointers that carry it.\n");
+
+        demonstrate_pointer_sizes();
+        demonstrate_metadata();
+        demonstrate_custom_dst();
+        demonstrate_box_slice_vs_vec();
+
+        output::takeaways();
+        outln!("• [T], str and dyn Trait are unsized: they can only sit behind a pointer");
+        outln!("• Pointers to them are fat: address + length, or address + vtable");
+        outln!("• size_of_val reads the runtime size from that metadata");
+        outln!("• A struct's last field may be unsized, giving a custom DST");
+        outln!("• Box<[T]> is a Vec that's finished growing: two words, exact fit");
+        DemoReport::default()
+    }
+}
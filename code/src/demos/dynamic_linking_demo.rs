@@ -0,0 +1,411 @@
+//! Dynamic Linking Demo
+//!
+//! A shared library is an ELF file the dynamic linker maps into a running
+//! process, here on demand: `dlopen` loads a small C library built next to
+//! this crate (csrc/plugin.c), `dlsym` turns a function's name into an
+//! address, and the demo calls it. The library's own calls into libc go
+//! through the PLT, one indirect jump per function via a GOT slot, and the
+//! demo reads those slots before and after the first call: with lazy
+//! binding the dynamic linker fills each one only when it is first used,
+//! which the timing of first against later calls shows too. The process's
+//! loaded libraries come from /proc/self/maps, before and after.
+//! Run with: cargo run --release --bin dynamic-linking-demo
+
+use std::ffi::{c_char, c_int, c_long, c_void, CStr, CString};
+use std::hint::black_box;
+use std::ptr;
+use std::time::Instant;
+
+use crate::bench::Measurement;
+use crate::demo::{Config, Demo, DemoReport};
+use crate::elf::{Elf, Relocation, Symbol};
+use crate::output::{Style, Table};
+use crate::quiz::Question;
+use crate::verify::Verification;
+use crate::{outln, output};
+
+/// The plugin linked with `-z lazy`: PLT slots bound at the first call
+const LAZY: &str = env!("SYSTEMS_DEMOS_PLUGIN_LAZY");
+
+/// The same source linked with `-z now`: every slot bound inside `dlopen`
+const NOW: &str = env!("SYSTEMS_DEMOS_PLUGIN_NOW");
+
+/// Load, first call, unload cycles per library in the timing section
+const ROUNDS: usize = 21;
+
+/// Calls after the first, timed together
+const LATER_CALLS: u32 = 1_000;
+
+type Parse = unsafe extern "C" fn(*const c_char) -> c_long;
+type Sort = unsafe extern "C" fn(*mut c_int, usize);
+type Version = unsafe extern "C" fn() -> c_int;
+
+fn dlerror() -> String {
+    // Safety: dlerror returns null or a NUL-terminated string valid until
+    // the next dl* call on this thread, copied out at once
+    unsafe {
+        let message = libc::dlerror();
+        if message.is_null() { "unknown error".to_string() } else { CStr::from_ptr(message).to_string_lossy().into_owned() }
+    }
+}
+
+/// A `dlopen` handle, closed on drop
+struct Library {
+    handle: *mut c_void,
+    path: &'static str,
+}
+
+impl Library {
+    fn open(path: &'static str, flags: c_int) -> Result<Library, String> {
+        let name = CString::new(path).map_err(|err| err.to_string())?;
+        // Safety: a NUL-terminated path; the library's initializers are
+        // plugin.c's, which has none
+        let handle = unsafe { libc::dlopen(name.as_ptr(), flags) };
+        if handle.is_null() { Err(dlerror()) } else { Ok(Library { handle, path }) }
+    }
+
+    /// The address `dlsym` finds for `name`, if any
+    fn address(&self, name: &str) -> Option<usize> {
+        let name = CString::new(name).ok()?;
+        // Safety: a live handle and a NUL-terminated name
+        let address = unsafe { libc::dlsym(self.handle, name.as_ptr()) };
+        (!address.is_null()).then_some(address as usize)
+    }
+
+    /// `name` as a function of type `F`, which must be its C signature
+    fn function<F: Copy>(&self, name: &str) -> Option<F> {
+        assert_eq!(size_of::<F>(), size_of::<usize>());
+        // Safety: F is a function pointer type (same size as an address),
+        // and the caller names the symbol's real signature
+        self.address(name).map(|address| unsafe { std::mem::transmute_copy(&address) })
+    }
+
+    /// Where the library was mapped: its file addresses plus this
+    fn base(&self) -> usize {
+        self.address("plugin_version").and_then(|address| object_of(address).map(|(base, _)| base)).unwrap_or(0)
+    }
+}
+
+impl Drop for Library {
+    fn drop(&mut self) {
+        // Safety: the handle came from dlopen and is closed once; nothing
+        // resolved from it is used after the Library is gone
+        unsafe {
+            libc::dlclose(self.handle);
+        }
+    }
+}
+
+/// The load base and file of the shared object containing `address`
+fn object_of(address: usize) -> Option<(usize, String)> {
+    // Safety: Dl_info is plain old data, all zeroes a valid value; dladdr
+    // fills it in and its strings stay valid while the object is loaded
+    unsafe {
+        let mut info: libc::Dl_info = std::mem::zeroed();
+        if libc::dladdr(address as *const c_void, &mut info) == 0 || info.dli_fname.is_null() {
+            return None;
+        }
+        Some((info.dli_fbase as usize, CStr::from_ptr(info.dli_fname).to_string_lossy().into_owned()))
+    }
+}
+
+/// The address the default search order (every global library) gives `name`
+fn global_address(name: &str) -> Option<usize> {
+    let name = CString::new(name).ok()?;
+    // Safety: RTLD_DEFAULT is a valid pseudo-handle; the name is NUL-terminated
+    let address = unsafe { libc::dlsym(libc::RTLD_DEFAULT, name.as_ptr()) };
+    (!address.is_null()).then_some(address as usize)
+}
+
+/// What a GOT slot of a loaded library holds right now
+fn read_slot(base: usize, relocation: &Relocation) -> usize {
+    // Safety: the slot is 8 bytes of the library's mapped, readable data
+    // segment, at its file address plus the load base
+    unsafe { ptr::read_volatile((base + relocation.address as usize) as *const usize) }
+}
+
+/// Every shared object mapped into this process, in address order
+fn loaded_libraries() -> Vec<String> {
+    let maps = std::fs::read_to_string("/proc/self/maps").unwrap_or_default();
+    let mut libraries: Vec<String> = Vec::new();
+    for path in maps.lines().filter_map(|line| line.split_whitespace().nth(5)) {
+        if path.contains(".so") && !libraries.iter().any(|library| library == path) {
+            libraries.push(path.to_string());
+        }
+    }
+    libraries
+}
+
+fn file_name(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+/// `LD_BIND_NOW` makes every library bind at load time, lazy flag or not
+fn bind_now_forced() -> bool {
+    std::env::var_os("LD_BIND_NOW").is_some_and(|value| !value.is_empty())
+}
+
+fn demonstrate_dlopen() -> Option<Library> {
+    output::heading("📦 dlopen: Loading a Library at Run Time");
+    let before = loaded_libraries();
+    outln!("Shared objects in this process before (from /proc/self/maps):");
+    for library in &before {
+        outln!("  {}", library);
+    }
+    let start = Instant::now();
+    let library = match Library::open(LAZY, libc::RTLD_LAZY | libc::RTLD_LOCAL) {
+        Ok(library) => library,
+        Err(err) => {
+            outln!("❌ dlopen failed: {}\n", err);
+            return None;
+        }
+    };
+    let elapsed = start.elapsed();
+    outln!();
+    outln!("dlopen(\"{}\", RTLD_LAZY) took {:.1?}.", file_name(library.path), elapsed);
+    outln!("It opened the file, mapped its LOAD segments, applied its data relocations");
+    outln!("and ran its initializers. Loaded at {:#x}; now also mapped:", library.base());
+    for added in loaded_libraries().iter().filter(|library| !before.contains(library)) {
+        outln!("  {}", Style::Faster.paint(added));
+    }
+    outln!();
+    Some(library)
+}
+
+fn demonstrate_dlsym(library: &Library, symbols: &[Symbol]) {
+    output::heading("🔎 dlsym: From a Name to an Address");
+    let base = library.base();
+    let mut table = Table::new(["name", "dlsym", "- base", "in the file"]).left(0);
+    for name in ["plugin_version", "plugin_parse", "plugin_sort", "plugin_calls"] {
+        let file = symbols.iter().find(|symbol| symbol.name == name).map_or("-".to_string(), |s| format!("{:#x}", s.address));
+        match library.address(name) {
+            Some(address) => table.row([name.to_string(), format!("{:#x}", address), format!("{:#x}", address - base), file]),
+            None => table.row([name.to_string(), "-".to_string(), "-".to_string(), file]),
+        };
+    }
+    outln!("{}", table);
+    outln!("dlsym looks the name up in the library's .dynsym (through its hash table)");
+    outln!("and adds the load base. plugin_calls is an int, not a function: data");
+    outln!("symbols resolve the same way.");
+    let missing = library.address("plugin_missing");
+    outln!("dlsym(\"plugin_missing\") = {:?}: {}", missing, dlerror().replace(library.path, file_name(library.path)));
+
+    let Some(version) = library.function::<Version>("plugin_version") else { return };
+    // Safety: the signature matches plugin.c
+    let version = unsafe { version() };
+    // Safety: plugin_calls is a C int, written only by calls on this thread
+    let calls = library.address("plugin_calls").map(|address| unsafe { ptr::read_volatile(address as *const c_int) });
+    outln!("Called through its pointer, plugin_version() = {}, and plugin_calls now reads {}.\n", version,
+           calls.unwrap_or(0));
+}
+
+fn demonstrate_plt(library: &Library, relocations: &[Relocation]) {
+    output::heading("🪤 Lazy Binding: the PLT and the GOT");
+    if relocations.is_empty() {
+        outln!("The library has no .rela.plt: nothing to bind lazily.\n");
+        return;
+    }
+    let base = library.base();
+    let inside = |address: usize| object_of(address).map_or("?".to_string(), |(_, file)| file_name(&file).to_string());
+    let mut table = Table::new(["slot", "for", "holds", "which is in"]).left(1).left(3);
+    let row = |when: &str, table: &mut Table| {
+        for relocation in relocations {
+            let value = read_slot(base, relocation);
+            table.row([format!("{:#x}", base + relocation.address as usize), format!("{} ({})", relocation.symbol, when),
+                       format!("{:#x}", value), inside(value)]);
+        }
+    };
+    row("before", &mut table);
+    if let (Some(parse), Some(sort)) = (library.function::<Parse>("plugin_parse"), library.function::<Sort>("plugin_sort")) {
+        let mut values = [3, 1, 2];
+        // Safety: the signatures match plugin.c; the pointer and length
+        // describe `values`
+        unsafe {
+            black_box(parse(c"42".as_ptr()));
+            sort(values.as_mut_ptr(), values.len());
+        }
+    }
+    row("after", &mut table);
+    outln!("{}", table);
+    outln!("plugin_parse calls strtol, which lives in libc: the compiler can't know");
+    outln!("where, so the call jumps to a PLT stub, which jumps through a GOT slot.");
+    outln!("Lazily linked, the slot starts out pointing back into the library's PLT,");
+    outln!("at code that calls the dynamic linker. The first call resolves the name,");
+    outln!("writes the real address into the slot and continues there; from then on");
+    outln!("it's one indirect jump.");
+    if bind_now_forced() {
+        outln!("{}", Style::Slower.paint("LD_BIND_NOW is set, so the slots were filled during dlopen anyway."));
+    }
+    outln!();
+}
+
+/// Per round: dlopen, the first plugin_parse call, and the average of the
+/// calls after it
+fn time_binding(path: &'static str, flags: c_int) -> Option<(Measurement, Measurement, Measurement)> {
+    let (mut opens, mut firsts, mut laters) = (Vec::new(), Vec::new(), Vec::new());
+    for _ in 0..ROUNDS {
+        let start = Instant::now();
+        let library = Library::open(path, flags).ok()?;
+        opens.push(start.elapsed());
+        let parse = library.function::<Parse>("plugin_parse")?;
+        // Safety: the signature matches plugin.c; the strings are NUL-terminated
+        unsafe {
+            let start = Instant::now();
+            black_box(parse(black_box(c"7".as_ptr())));
+            firsts.push(start.elapsed());
+            let start = Instant::now();
+            for _ in 0..LATER_CALLS {
+                black_box(parse(black_box(c"7".as_ptr())));
+            }
+            laters.push(start.elapsed() / LATER_CALLS);
+        }
+    }
+    Some((Measurement::from_samples(opens), Measurement::from_samples(firsts), Measurement::from_samples(laters)))
+}
+
+fn demonstrate_timing(report: &mut DemoReport) {
+    output::heading("⏱️  First Call vs the Rest");
+    let (Some(lazy), Some(now)) = (time_binding(LAZY, libc::RTLD_LAZY), time_binding(NOW, libc::RTLD_NOW)) else {
+        outln!("❌ couldn't load the plugins: {}\n", dlerror());
+        return;
+    };
+    let mut table = Table::new(["library", "dlopen", "first call", "later calls", "first / later"]).left(0);
+    for (name, (open, first, later)) in [("-z lazy", &lazy), ("-z now", &now)] {
+        let ratio = first.median().as_secs_f64() / later.median().as_secs_f64().max(1e-12);
+        table.row([name.to_string(), format!("{:.1?}", open.median()), format!("{:.1?}", first.median()),
+                   format!("{:.1?}", later.median()), format!("{:.0}x", ratio)]);
+    }
+    outln!("{}", table);
+    outln!("Medians of {} load, call, unload rounds. The lazy library's first call pays", ROUNDS);
+    outln!("for a symbol lookup across every loaded library; the other paid for both of");
+    outln!("its slots inside dlopen instead, whether or not they'd ever be called. Its");
+    outln!("first call is still slower than the rest: a cold page and cold caches.");
+    outln!("Rust executables link with -z now (full RELRO: the GOT is made read-only");
+    outln!("after binding), trading startup work for slots that can't be overwritten.\n");
+    report.record_time("lazy first call", lazy.1.median());
+    report.record_time("now first call", now.1.median());
+    report.record_time("later call", lazy.2.median());
+}
+
+fn demonstrate_dlclose(library: Library) {
+    output::heading("🧹 dlclose");
+    let path = library.path;
+    drop(library);
+    let still = loaded_libraries().iter().any(|library| library == path);
+    outln!("After dlclose, {} is {} /proc/self/maps: the reference count hit", file_name(path),
+           if still { "still in" } else { "gone from" });
+    outln!("zero, so its finalizers ran and its segments were unmapped. Any pointer from");
+    outln!("dlsym now dangles: calling it would jump into unmapped memory.\n");
+}
+
+/// What the dynamic linker did matches the file
+fn verify() -> Verification {
+    let mut verification = Verification::new();
+    let elf = match Elf::open(LAZY) {
+        Ok(elf) => elf,
+        Err(err) => {
+            verification.check("the plugin parses as ELF", false, || err.to_string());
+            return verification;
+        }
+    };
+    let (symbols, relocations) = (elf.symbols().unwrap_or_default(), elf.plt_relocations().unwrap_or_default());
+    let strtol = relocations.iter().find(|relocation| relocation.symbol == "strtol");
+    verification.check("the plugin calls strtol through the PLT", strtol.is_some(), || format!("{:?}", relocations));
+
+    let library = match Library::open(LAZY, libc::RTLD_LAZY | libc::RTLD_LOCAL) {
+        Ok(library) => library,
+        Err(err) => {
+            verification.check("dlopen loads the plugin", false, || err);
+            return verification;
+        }
+    };
+    verification.check("the plugin appears in /proc/self/maps", loaded_libraries().iter().any(|path| path == LAZY),
+                       || format!("{:?}", loaded_libraries()));
+    let base = library.base();
+    let file = symbols.iter().find(|symbol| symbol.name == "plugin_parse").map(|symbol| symbol.address as usize);
+    verification
+        .equal("dlsym gives the file's address plus the load base", library.address("plugin_parse").map(|a| a - base), file)
+        .equal("dlsym finds nothing for a missing name", library.address("plugin_missing"), None);
+
+    if let (Some(strtol), Some(parse)) = (strtol, library.function::<Parse>("plugin_parse")) {
+        let before = read_slot(base, strtol);
+        // Safety: the signature matches plugin.c; the string is NUL-terminated
+        let parsed = unsafe { parse(c"1234".as_ptr()) };
+        let after = read_slot(base, strtol);
+        verification.equal("calling through the pointer works", parsed, 1234);
+        if bind_now_forced() {
+            verification.skip("a lazy slot starts inside the library", "LD_BIND_NOW is set");
+        } else {
+            verification.check("a lazy slot starts inside the library", object_of(before).is_some_and(|(b, _)| b == base),
+                               || format!("{:#x}", before));
+        }
+        verification.equal("the first call fills the slot with strtol's address", Some(after), global_address("strtol"));
+    }
+    drop(library);
+    verification.check("dlclose unmaps it", !loaded_libraries().iter().any(|path| path == LAZY),
+                       || format!("{:?}", loaded_libraries()));
+
+    if let Ok(library) = Library::open(NOW, libc::RTLD_NOW | libc::RTLD_LOCAL) {
+        let slots = Elf::open(NOW).and_then(|elf| elf.plt_relocations()).unwrap_or_default();
+        let base = library.base();
+        verification.check("with -z now every slot is filled by dlopen",
+                           !slots.is_empty() && slots.iter().all(|slot| Some(read_slot(base, slot)) == global_address(&slot.symbol)),
+                           || format!("{:?}", slots));
+    }
+    verification
+}
+
+const QUIZ: &[Question] = &[
+    Question::new(
+        "With lazy binding, when does the dynamic linker look up a function a library calls?",
+        &["At compile time", "When the library is loaded", "The first time the call is made"],
+        2,
+        "The GOT slot first points at a resolver stub; the first call resolves the name and patches the slot.",
+    ),
+    Question::new(
+        "What does dlsym return for a function?",
+        &["Its offset in the file", "Its address in this process: the file address plus the load base", "A PLT stub"],
+        1,
+        "The library was mapped at some base; every address in its symbol table is shifted by it.",
+    ),
+    Question::new(
+        "Why do many builds link with -z now despite the slower startup?",
+        &[
+            "Calls are faster",
+            "All slots are bound before main, so the GOT can be made read-only (full RELRO)",
+            "Libraries load in parallel",
+        ],
+        1,
+        "A writable GOT is a classic target for hijacking control flow; binding early lets it be locked.",
+    ),
+];
+
+#[derive(Demo)]
+#[demo(name = "dynamic-linking-demo", description = "dlopen, dlsym, and lazy binding through the PLT and GOT",
+       quiz = QUIZ, verify = verify)]
+pub struct DynamicLinkingDemo;
+
+impl DynamicLinkingDemo {
+    fn demonstrate(&self, _config: &Config) -> DemoReport {
+        output::title("🔗 Dynamic Linking Demo");
+        outln!("Loading a shared library into a running process and calling into it by name.\n");
+        let mut report = DemoReport::default();
+
+        let elf = Elf::open(LAZY);
+        let symbols = elf.as_ref().ok().and_then(|elf| elf.symbols().ok()).unwrap_or_default();
+        let relocations = elf.as_ref().ok().and_then(|elf| elf.plt_relocations().ok()).unwrap_or_default();
+        if let Some(library) = demonstrate_dlopen() {
+            demonstrate_dlsym(&library, &symbols);
+            demonstrate_plt(&library, &relocations);
+            demonstrate_dlclose(library);
+        }
+        demonstrate_timing(&mut report);
+
+        output::takeaways();
+        outln!("• dlopen maps a library into the running process; dlsym turns a name into an address");
+        outln!("• Calls into another library go through a PLT stub and a GOT slot");
+        outln!("• Lazy binding fills each slot at its first call; -z now fills them all at load time");
+        outln!("• dlclose unmaps the library, leaving every pointer into it dangling");
+        report
+    }
+}
@@ -0,0 +1,304 @@
+//! Endianness Demo
+//!
+//! A `u32` is four bytes, and the CPU decides which of them comes first in
+//! memory: the least significant (little-endian: x86, most ARM) or the most
+//! significant (big-endian: network protocols, older SPARC and PowerPC).
+//! This demo looks at the bytes of a value in memory, reads the same bytes
+//! both ways, and builds an IPv4 header field by field in network byte
+//! order, showing why copying a struct's memory onto the wire goes wrong
+//! on a little-endian host.
+//! Run with: cargo run --bin endianness-demo
+
+use std::mem::{align_of, offset_of, size_of};
+use std::slice;
+
+use crate::demo::{Config, Demo, DemoReport};
+use crate::output::Table;
+use crate::quiz::Question;
+use crate::verify::Verification;
+use crate::{outln, output};
+
+/// A value whose four bytes are easy to tell apart
+const VALUE: u32 = 0x0A0B_0C0D;
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect::<Vec<_>>().join(" ")
+}
+
+/// Which end of a `u32` the CPU stores first, found by looking at memory
+/// rather than asking the compiler
+fn host_is_little_endian() -> bool {
+    let value: u32 = 1;
+    // Safety: reading the first of the four initialized bytes of `value`
+    let first = unsafe { *(&value as *const u32 as *const u8) };
+    first == 1
+}
+
+/// The bytes of `value` as they sit in memory
+fn memory_bytes(value: &u32) -> [u8; 4] {
+    // Safety: a u32 is four initialized bytes, borrowed for the copy
+    let bytes = unsafe { slice::from_raw_parts(value as *const u32 as *const u8, size_of::<u32>()) };
+    bytes.try_into().expect("four bytes")
+}
+
+/// An IPv4 header with every field in the host's representation. The
+/// fields happen to fall on their natural alignment, so `repr(C)` adds no
+/// padding and the struct is exactly the 20 bytes of the wire format.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Ipv4Header {
+    version_ihl: u8,
+    tos: u8,
+    total_length: u16,
+    identification: u16,
+    flags_fragment: u16,
+    ttl: u8,
+    protocol: u8,
+    checksum: u16,
+    source: [u8; 4],
+    destination: [u8; 4],
+}
+
+/// The example header from the Wikipedia article on the IPv4 checksum:
+/// a UDP packet from 192.168.0.1 to 192.168.0.199
+const HEADER: Ipv4Header = Ipv4Header {
+    version_ihl: 0x45,
+    tos: 0,
+    total_length: 0x0073,
+    identification: 0,
+    flags_fragment: 0x4000,
+    ttl: 0x40,
+    protocol: 0x11,
+    checksum: 0,
+    source: [192, 168, 0, 1],
+    destination: [192, 168, 0, 199],
+};
+
+impl Ipv4Header {
+    /// The wire format: multi-byte fields big-endian, whatever the host
+    fn encode(&self) -> [u8; 20] {
+        let mut bytes = [0; 20];
+        bytes[0] = self.version_ihl;
+        bytes[1] = self.tos;
+        bytes[2..4].copy_from_slice(&self.total_length.to_be_bytes());
+        bytes[4..6].copy_from_slice(&self.identification.to_be_bytes());
+        bytes[6..8].copy_from_slice(&self.flags_fragment.to_be_bytes());
+        bytes[8] = self.ttl;
+        bytes[9] = self.protocol;
+        bytes[10..12].copy_from_slice(&self.checksum.to_be_bytes());
+        bytes[12..16].copy_from_slice(&self.source);
+        bytes[16..20].copy_from_slice(&self.destination);
+        bytes
+    }
+
+    fn decode(bytes: &[u8; 20]) -> Ipv4Header {
+        let u16_at = |at: usize| u16::from_be_bytes([bytes[at], bytes[at + 1]]);
+        Ipv4Header {
+            version_ihl: bytes[0],
+            tos: bytes[1],
+            total_length: u16_at(2),
+            identification: u16_at(4),
+            flags_fragment: u16_at(6),
+            ttl: bytes[8],
+            protocol: bytes[9],
+            checksum: u16_at(10),
+            source: bytes[12..16].try_into().expect("four bytes"),
+            destination: bytes[16..20].try_into().expect("four bytes"),
+        }
+    }
+
+    /// The struct's own memory, as a careless `write(&header)` would send it
+    fn memory(&self) -> [u8; 20] {
+        // Safety: repr(C) with no padding, so all 20 bytes are initialized
+        let bytes = unsafe { slice::from_raw_parts(self as *const Ipv4Header as *const u8, size_of::<Ipv4Header>()) };
+        bytes.try_into().expect("20 bytes")
+    }
+}
+
+/// RFC 1071: the ones' complement of the ones' complement sum of the
+/// header's 16-bit words
+fn checksum(bytes: &[u8]) -> u16 {
+    let mut sum: u32 = bytes.chunks(2).map(|word| u16::from_be_bytes([word[0], *word.get(1).unwrap_or(&0)]) as u32).sum();
+    while sum > 0xFFFF {
+        sum = (sum & 0xFFFF) + (sum >> 16);
+    }
+    !(sum as u16)
+}
+
+/// A wire record with a one-byte tag before a four-byte length
+#[repr(C)]
+struct Aligned {
+    kind: u8,
+    length: u32,
+}
+
+/// The same fields with no padding: five bytes, and `length` misaligned
+#[repr(C, packed)]
+struct Packed {
+    kind: u8,
+    length: u32,
+}
+
+fn demonstrate_host() {
+    output::heading("🖥️  This Machine's Byte Order");
+    let little = host_is_little_endian();
+    outln!("Target: {} ({}-endian per the compiler); storing 1 in a u32 puts {} first,",
+           std::env::consts::ARCH, if cfg!(target_endian = "little") { "little" } else { "big" },
+           if little { "01" } else { "00" });
+    outln!("so this CPU is {}-endian.\n", if little { "little" } else { "big" });
+
+    let mut table = Table::new(["0x0A0B0C0D", "byte 0", "byte 1", "byte 2", "byte 3"]).left(0);
+    let row = |label: &str, bytes: [u8; 4]| {
+        let mut cells = vec![label.to_string()];
+        cells.extend(bytes.iter().map(|byte| format!("{:02x}", byte)));
+        cells
+    };
+    table.row(row("in memory", memory_bytes(&VALUE)));
+    table.row(row("to_le_bytes()", VALUE.to_le_bytes()));
+    table.row(row("to_be_bytes()", VALUE.to_be_bytes()));
+    table.row(row("to_ne_bytes()", VALUE.to_ne_bytes()));
+    outln!("{}", table);
+    outln!("Big-endian writes the number the way we do, most significant digit first;");
+    outln!("little-endian stores the low byte at the lowest address, so a u8, u16 or u32");
+    outln!("load from the same address agrees on the low bits. Neither is faster today.\n");
+}
+
+fn demonstrate_misreading() {
+    output::heading("🔀 The Same Bytes, Two Numbers");
+    let bytes = [0x1F, 0x90];
+    outln!("Port 8080 in network byte order is the bytes [{}].", hex(&bytes));
+    outln!("  u16::from_be_bytes → {}", u16::from_be_bytes(bytes));
+    outln!("  u16::from_le_bytes → {}   (what a little-endian host sees if it just loads them)", u16::from_le_bytes(bytes));
+    let length = [0x00, 0x00, 0x01, 0x00];
+    outln!("A length prefix [{}]: {} bytes read big-endian, {} read little-endian.",
+           hex(&length), u32::from_be_bytes(length), u32::from_le_bytes(length));
+    outln!("Nothing in the bytes says which is meant: the format's specification does.\n");
+}
+
+fn demonstrate_header() {
+    output::heading("📦 An IPv4 Header, Field by Field");
+    outln!("Ipv4Header is repr(C): {} bytes, align {}; total_length at offset {}, source at {}.",
+           size_of::<Ipv4Header>(), align_of::<Ipv4Header>(), offset_of!(Ipv4Header, total_length),
+           offset_of!(Ipv4Header, source));
+    let mut header = HEADER;
+    header.checksum = checksum(&header.encode());
+    let wire = header.encode();
+    let memory = header.memory();
+    outln!("  encoded, big-endian:  {}", hex(&wire));
+    outln!("  the struct's memory:  {}", hex(&memory));
+    let differ: Vec<String> = (0..20).filter(|&i| wire[i] != memory[i]).map(|i| i.to_string()).collect();
+    if differ.is_empty() {
+        outln!("Identical: on a big-endian host the struct's memory is already the wire");
+        outln!("format. Code that relies on it breaks when ported to a little-endian one.");
+    } else {
+        outln!("They differ at bytes {}: every u16 field is swapped. Sending the struct's", differ.join(", "));
+        outln!("memory would announce a total length of {} and a checksum no router",
+               u16::from_be_bytes([memory[2], memory[3]]));
+        outln!("accepts. The u8 fields and the addresses, kept as byte arrays, come out");
+        outln!("right either way.");
+    }
+    outln!();
+    outln!("Checksum 0x{:04x}; summing the header with it in place gives 0x{:04x} (valid),",
+           header.checksum, checksum(&wire));
+    outln!("and decoding the bytes gives the same header back: {}.", Ipv4Header::decode(&wire) == header);
+    outln!("The ones' complement sum has a curious property (RFC 1071): computed over");
+    outln!("byte-swapped words it comes out byte-swapped, so a checksum can be summed");
+    outln!("in native order and swapped once at the end.\n");
+
+    outln!("Field order decides padding, too. A u8 tag then a u32 length:");
+    outln!("  repr(C):         {} bytes (length at offset {}, 3 bytes of padding before it)",
+           size_of::<Aligned>(), offset_of!(Aligned, length));
+    outln!("  repr(C, packed): {} bytes (length at offset {}, misaligned)",
+           size_of::<Packed>(), offset_of!(Packed, length));
+    let packed = Packed { kind: 1, length: 0x0102_0304 };
+    // A reference to `packed.length` would be misaligned: Rust refuses to
+    // make one, so the field is copied out by value
+    let length = { packed.length };
+    outln!("Reading a packed field copies it (kind {}, length 0x{:08x}); borrowing it is a", packed.kind, length);
+    outln!("compile error. Packed structs still hold native-endian fields: explicit");
+    outln!("to_be_bytes/from_be_bytes, as above, is the portable way to a wire format.\n");
+}
+
+fn demonstrate_network_order() {
+    output::heading("🌐 Why Network Byte Order Is Big-Endian");
+    outln!("The Internet's protocols were written in the late 1970s, when much of the");
+    outln!("network was big-endian (the Honeywell IMP routers, IBM mainframes), and the");
+    outln!("RFCs' \"data notation\" fixed most-significant-byte-first as network byte");
+    outln!("order. Every multi-byte field in IP, TCP, UDP and DNS headers follows it.");
+    outln!("C's htons/htonl (Rust: u16::to_be, u32::to_be) are no-ops on a big-endian");
+    outln!("host and a single byte swap (x86 bswap or movbe, ARM rev) on a little-endian");
+    outln!("one: 0x{:08x}.to_be() = 0x{:08x} here.", VALUE, VALUE.to_be());
+    outln!("File formats choose too: PNG and Java class files are big-endian; ELF and");
+    outln!("many newer formats declare or choose little-endian, now the common case.\n");
+}
+
+/// The byte-order claims hold on this host, and the header round-trips
+fn verify() -> Verification {
+    let mut verification = Verification::new();
+    let little = host_is_little_endian();
+    let mut header = HEADER;
+    header.checksum = checksum(&header.encode());
+    let wire = header.encode();
+    verification
+        .equal("memory shows the byte order the compiler targets", little, cfg!(target_endian = "little"))
+        .equal("to_be_bytes puts the most significant byte first", VALUE.to_be_bytes(), [0x0A, 0x0B, 0x0C, 0x0D])
+        .equal("to_le_bytes puts the least significant byte first", VALUE.to_le_bytes(), [0x0D, 0x0C, 0x0B, 0x0A])
+        .equal("native bytes are the memory bytes", VALUE.to_ne_bytes(), memory_bytes(&VALUE))
+        .equal("port 8080 misread is 36895", u16::from_le_bytes(8080u16.to_be_bytes()), 36895)
+        .equal("the header struct is exactly 20 bytes", size_of::<Ipv4Header>(), 20)
+        .equal("the example header's checksum is 0xb861", header.checksum, 0xB861)
+        .equal("a header with its checksum sums to zero", checksum(&wire), 0)
+        .equal("decoding the wire bytes gives the header back", Ipv4Header::decode(&wire), header)
+        .equal("the struct's memory is the wire format only on a big-endian host", header.memory() == wire, !little)
+        .equal("packing removes the padding", (size_of::<Aligned>(), size_of::<Packed>()), (8, 5));
+    verification
+}
+
+const QUIZ: &[Question] = &[
+    Question::new(
+        "On a little-endian machine, which byte of 0x0A0B0C0D is at the lowest address?",
+        &["0x0A", "0x0D", "It depends on the compiler"],
+        1,
+        "Little-endian: the little end, the least significant byte, comes first.",
+    ),
+    Question::new(
+        "A program sends a struct with `write(&header)` and works between two x86 machines. What breaks it?",
+        &[
+            "Nothing: x86 to x86 is fine, and so is everything else",
+            "A big-endian peer, or any implementation following the protocol's big-endian spec",
+            "Only a change of compiler",
+        ],
+        1,
+        "Both x86 ends agree with each other, but not with the wire format everyone else reads.",
+    ),
+    Question::new(
+        "What does u32::to_be() compile to on x86-64?",
+        &["A loop over the bytes", "A single byte-swap instruction (bswap)", "Nothing"],
+        1,
+        "On a big-endian target it would be nothing at all.",
+    ),
+];
+
+#[derive(Demo)]
+#[demo(name = "endianness-demo", description = "Byte order in memory and on the wire",
+       quiz = QUIZ, verify = verify)]
+pub struct EndiannessDemo;
+
+impl EndiannessDemo {
+    fn demonstrate(&self, _config: &Config) -> DemoReport {
+        output::title("🔢 Endianness Demo");
+        outln!("Which byte comes first, and why the network insists on one answer.\n");
+
+        demonstrate_host();
+        demonstrate_misreading();
+        demonstrate_header();
+        demonstrate_network_order();
+
+        output::takeaways();
+        outln!("• Endianness is the order of a value's bytes in memory: little-endian stores the low byte first");
+        outln!("• Bytes carry no byte order; the format that defines them does");
+        outln!("• Network protocols are big-endian; convert each field with to_be_bytes/from_be_bytes");
+        outln!("• A struct's memory is not a wire format: byte order and padding are the host's");
+        DemoReport::default()
+    }
+}
@@ -0,0 +1,347 @@
+//! Layered Error Handling Demo
+//!
+//! A small program split into layers (storage, config parsing, application)
+//! where each layer has its own error enum. Lower-level errors convert into
+//! higher-level ones with `From`, so `?` carries them up, and `source()`
+//! keeps the whole chain available for reporting. Ends with what a failing
+//! Result costs compared with a caught panic.
+//! Run with: cargo run --release --bin error-handling-demo
+
+use std::error::Error;
+use std::hint::black_box;
+use std::path::Path;
+use std::{fs, panic};
+
+use crate::bench::measure;
+use crate::demo::{Config, Demo, DemoReport};
+use crate::output::Table;
+use crate::quiz::Question;
+use crate::scratch::Scratch;
+use crate::{outln, output};
+
+/// Lowest layer: reading raw text from disk
+mod storage {
+    use std::fmt;
+    use std::io;
+    use std::path::{Path, PathBuf};
+
+    #[derive(Debug)]
+    pub enum StorageError {
+        NotFound(PathBuf),
+        Io { path: PathBuf, source: io::Error },
+    }
+
+    impl fmt::Display for StorageError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                StorageError::NotFound(path) => write!(f, "{} does not exist", path.display()),
+                StorageError::Io { path, .. } => write!(f, "could not read {}", path.display()),
+            }
+        }
+    }
+
+    impl std::error::Error for StorageError {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            match self {
+                StorageError::NotFound(_) => None,
+                StorageError::Io { source, .. } => Some(source),
+            }
+        }
+    }
+
+    pub fn read(path: &Path) -> Result<String, StorageError> {
+        std::fs::read_to_string(path).map_err(|source| match source.kind() {
+            io::ErrorKind::NotFound => StorageError::NotFound(path.to_path_buf()),
+            _ => StorageError::Io { path: path.to_path_buf(), source },
+        })
+    }
+}
+
+/// Middle layer: turning `key = value` lines into a typed config
+mod config {
+    use std::fmt;
+    use std::num::ParseIntError;
+    use std::path::Path;
+
+    use super::storage::{self, StorageError};
+
+    #[derive(Debug)]
+    pub struct Config {
+        pub workers: u32,
+        pub port: u16,
+    }
+
+    #[derive(Debug)]
+    pub enum ConfigError {
+        Storage(StorageError),
+        Syntax { line: usize, text: String },
+        BadNumber { line: usize, key: String, source: ParseIntError },
+        MissingKey(&'static str),
+    }
+
+    impl fmt::Display for ConfigError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                ConfigError::Storage(_) => write!(f, "could not load config"),
+                ConfigError::Syntax { line, text } => write!(f, "line {}: expected `key = value`, got {:?}", line, text),
+                ConfigError::BadNumber { line, key, .. } => write!(f, "line {}: `{}` is not a valid number", line, key),
+                ConfigError::MissingKey(key) => write!(f, "missing required key `{}`", key),
+            }
+        }
+    }
+
+    impl std::error::Error for ConfigError {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            match self {
+                ConfigError::Storage(source) => Some(source),
+                ConfigError::BadNumber { source, .. } => Some(source),
+                ConfigError::Syntax { .. } | ConfigError::MissingKey(_) => None,
+            }
+        }
+    }
+
+    /// Lets `?` turn a StorageError into a ConfigError automatically
+    impl From<StorageError> for ConfigError {
+        fn from(error: StorageError) -> Self {
+            ConfigError::Storage(error)
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Config, ConfigError> {
+        let text = storage::read(path)?; // StorageError -> ConfigError via From
+        let (mut workers, mut port) = (None, None);
+        for (index, raw) in text.lines().enumerate() {
+            let line = index + 1;
+            let raw = raw.trim();
+            if raw.is_empty() || raw.starts_with('#') {
+                continue;
+            }
+            let (key, value) = raw
+                .split_once('=')
+                .ok_or_else(|| ConfigError::Syntax { line, text: raw.to_string() })?;
+            let (key, value) = (key.trim(), value.trim());
+            // ParseIntError has no line number, so add context with map_err
+            let bad_number = |source| ConfigError::BadNumber { line, key: key.to_string(), source };
+            match key {
+                "workers" => workers = Some(value.parse().map_err(bad_number)?),
+                "port" => port = Some(value.parse().map_err(bad_number)?),
+                _ => {} // unknown keys are ignored
+            }
+        }
+        Ok(Config {
+            workers: workers.ok_or(ConfigError::MissingKey("workers"))?,
+            port: port.ok_or(ConfigError::MissingKey("port"))?,
+        })
+    }
+}
+
+/// Top layer: validating the config and starting the "server"
+mod app {
+    use std::fmt;
+    use std::path::Path;
+
+    use super::config::{self, Config, ConfigError};
+
+    #[derive(Debug)]
+    pub enum AppError {
+        Config(ConfigError),
+        Invalid { field: &'static str, reason: String },
+    }
+
+    impl fmt::Display for AppError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                AppError::Config(_) => write!(f, "failed to start server"),
+                AppError::Invalid { field, reason } => write!(f, "invalid `{}`: {}", field, reason),
+            }
+        }
+    }
+
+    impl std::error::Error for AppError {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            match self {
+                AppError::Config(source) => Some(source),
+                AppError::Invalid { .. } => None,
+            }
+        }
+    }
+
+    impl From<ConfigError> for AppError {
+        fn from(error: ConfigError) -> Self {
+            AppError::Config(error)
+        }
+    }
+
+    pub fn start(path: &Path) -> Result<Config, AppError> {
+        let config = config::load(path)?; // ConfigError -> AppError via From
+        if config.workers == 0 || config.workers > 1024 {
+            return Err(AppError::Invalid { field: "workers", reason: format!("{} is outside 1..=1024", config.workers) });
+        }
+        if config.port < 1024 {
+            return Err(AppError::Invalid { field: "port", reason: format!("{} is a privileged port", config.port) });
+        }
+        Ok(config)
+    }
+}
+
+/// Print an error and every `source()` beneath it
+fn report(error: &dyn Error) {
+    outln!("    error: {}", error);
+    let mut cause = error.source();
+    while let Some(inner) = cause {
+        outln!("      caused by: {}", inner);
+        cause = inner.source();
+    }
+}
+
+fn demonstrate_layers(dir: &Path) {
+    output::heading("🧅 Errors Through Three Layers");
+    outln!("  app::start ──?──> config::load ──?──> storage::read");
+    outln!("  AppError  <─From─ ConfigError  <─From─ StorageError <── io::Error\n");
+
+    let cases: [(&str, Option<&str>); 6] = [
+        ("good.conf", Some("# server settings\nworkers = 8\nport = 8080\n")),
+        ("missing.conf", None),
+        ("syntax.conf", Some("workers 8\nport = 8080\n")),
+        ("number.conf", Some("workers = eight\nport = 8080\n")),
+        ("incomplete.conf", Some("workers = 8\n")),
+        ("privileged.conf", Some("workers = 8\nport = 80\n")),
+    ];
+    for (name, contents) in cases {
+        let path = dir.join(name);
+        if let Some(contents) = contents {
+            fs::write(&path, contents).expect("temp dir is writable");
+        }
+        outln!("  app::start({:?})", name);
+        match app::start(&path) {
+            Ok(config) => outln!("    ok: {:?}", config),
+            Err(error) => report(&error),
+        }
+    }
+
+    let directory_as_file = dir.to_path_buf();
+    outln!("  app::start(<a directory>)");
+    if let Err(error) = app::start(&directory_as_file) {
+        report(&error);
+    }
+    outln!();
+    outln!("Each layer words the error at its own level of abstraction and keeps the");
+    outln!("lower one as source(), so the top can print the whole story without any");
+    outln!("layer knowing about the others' internals.\n");
+}
+
+/// With Box<dyn Error>, `?` converts any error type; handy at the top of a program
+fn first_port(dir: &Path) -> Result<u16, Box<dyn Error>> {
+    let config = app::start(&dir.join("good.conf"))?;
+    let override_port: u16 = "9090".parse()?;
+    Ok(config.port.max(override_port))
+}
+
+fn demonstrate_boxed(dir: &Path) {
+    output::heading("📦 Box<dyn Error> at the Edges");
+    outln!("Libraries return precise enums callers can match on; binaries often");
+    outln!("just need to report. Box<dyn Error> accepts any error through `?`:");
+    outln!("  fn first_port(dir) -> Result<u16, Box<dyn Error>> {{ app::start(..)?; \"9090\".parse()?; .. }}");
+    match first_port(dir) {
+        Ok(port) => outln!("  first_port(..) = Ok({})", port),
+        Err(error) => report(error.as_ref()),
+    }
+    outln!("The price: callers can only inspect it by downcasting.\n");
+}
+
+/// Parse that fails by returning Err
+fn parse_result(text: &str) -> Result<u32, std::num::ParseIntError> {
+    text.parse()
+}
+
+/// Parse that fails by panicking, recovered with catch_unwind
+fn parse_panic(text: &str) -> Option<u32> {
+    panic::catch_unwind(|| text.parse::<u32>().expect("valid number")).ok()
+}
+
+fn demonstrate_performance(report: &mut DemoReport) {
+    output::heading("⏱️  Result vs Panic: Cost of the Error Path");
+    const INPUTS: usize = 20_000;
+    let previous_hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {})); // thousands of expected panics, printed by nobody
+
+    let table = Table::new(["failures", "Result", "panic", "ratio"]).widths([8, 14, 14, 10]);
+    outln!("{}", table.header());
+    for failure_percent in [0, 1, 10, 50] {
+        let inputs: Vec<String> = (0..INPUTS)
+            .map(|i| if i % 100 < failure_percent { format!("x{}", i) } else { i.to_string() })
+            .collect();
+        let ok_result = inputs.iter().filter(|s| parse_result(s).is_ok()).count();
+        let ok_panic = inputs.iter().filter(|s| parse_panic(s).is_some()).count();
+        assert_eq!(ok_result, ok_panic);
+
+        let result_time = measure(5, || black_box(&inputs).iter().filter(|s| parse_result(s).is_ok()).count()).median();
+        let panic_time = measure(5, || black_box(&inputs).iter().filter(|s| parse_panic(s).is_some()).count()).median();
+        outln!("{}", table.line([format!("{}%", failure_percent), format!("{:?}", result_time),
+                                 format!("{:?}", panic_time),
+                                 format!("{:.1}x", panic_time.as_secs_f64() / result_time.as_secs_f64())]));
+        report.record_time("Result", result_time).param("failure_percent", failure_percent);
+        report.record_time("panic", panic_time).param("failure_percent", failure_percent);
+    }
+    panic::set_hook(previous_hook);
+    outln!("On the happy path the two are close: catch_unwind costs little when");
+    outln!("nothing unwinds. Each failure is different: an Err is an ordinary return");
+    outln!("value, while a panic formats a message, walks the stack through the");
+    outln!("unwinder and runs every destructor on the way. Panics are for bugs;");
+    outln!("expected failures belong in Result.\n");
+}
+
+const QUIZ: &[Question] = &[
+    Question::new(
+        "What lets ? turn a lower layer's error into your layer's error type?",
+        &["impl Display for the lower error", "impl From<Lower> for Upper", "Deriving Clone"],
+        1,
+        "? calls From::from on the error before returning it.",
+    ),
+    Question::new(
+        "What does Error::source() provide?",
+        &[
+            "The underlying cause, so the chain of errors keeps its context",
+            "The source file and line of the error",
+            "A copy of the error",
+        ],
+        0,
+        "Walking source() from the top-level error prints the whole story.",
+    ),
+    Question::new(
+        "Why keep panics for bugs rather than expected failures?",
+        &[
+            "Panics are slower to write",
+            "Err is an ordinary, cheap return value the caller can handle; a panic unwinds the stack",
+            "Panics can't carry a message",
+        ],
+        1,
+        "Expected failures belong in the type signature where callers see them.",
+    ),
+];
+
+#[derive(Demo)]
+#[demo(name = "error-handling-demo", description = "Layered error enums, source chains, Result vs panic",
+       quiz = QUIZ)]
+pub struct ErrorHandlingDemo;
+
+impl ErrorHandlingDemo {
+    fn demonstrate(&self, _config: &Config) -> DemoReport {
+        output::title("🧯 Layered Error Handling Demo");
+        outln!("Custom error types, From conversions, ? and source() chains.\n");
+
+        let scratch = Scratch::new("error-handling-demo", "configs").expect("temp dir is writable");
+        demonstrate_layers(scratch.dir());
+        demonstrate_boxed(scratch.dir());
+        let mut report = DemoReport::default();
+        demonstrate_performance(&mut report);
+
+        output::takeaways();
+        outln!("• One error enum per layer, each implementing Display and Error");
+        outln!("• source() links an error to its cause, so context is never lost");
+        outln!("• impl From<Lower> for Upper lets ? convert errors as they propagate");
+        outln!("• map_err adds context (like a line number) the lower error lacks");
+        outln!("• Err is a cheap return value; a panic unwinds, so keep it for bugs");
+        report
+    }
+}
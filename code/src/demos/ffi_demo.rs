@@ -0,0 +1,292 @@
+//! C FFI Round-Trip Demo
+//!
+//! Rust and C calling each other through the C ABI: Rust calls into a small
+//! C library (csrc/ffi_demo.c, built by build.rs), C calls back into Rust,
+//! and the demo shows the rules that keep this sound: `#[repr(C)]` layout,
+//! who frees what, and what happens when a panic meets a C frame.
+//! Run with: cargo run --bin ffi-demo
+
+use std::ffi::{c_char, c_void, CStr, CString};
+use std::mem;
+use std::panic;
+use std::process::Command;
+
+use crate::demo::{Config, Demo, DemoReport};
+use crate::output::Table;
+use crate::quiz::Question;
+use crate::{out, outln, output};
+
+/// Same fields as `Record` in ffi_demo.c, in the same order
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+struct Record {
+    tag: u8,
+    value: f64,
+    id: u16,
+}
+
+/// The same fields with Rust's default layout, which may reorder them
+#[allow(dead_code)]
+struct RustRecord {
+    tag: u8,
+    value: f64,
+    id: u16,
+}
+
+#[repr(C)]
+#[derive(Default)]
+struct RecordLayout {
+    size: usize,
+    align: usize,
+    offset_tag: usize,
+    offset_value: usize,
+    offset_id: usize,
+}
+
+type FoldFn = extern "C" fn(acc: i64, item: i32, ctx: *mut c_void) -> i64;
+
+// Declarations only: the compiler trusts these signatures blindly, which is
+// why calling any of them is unsafe
+#[link(name = "ffi_demo", kind = "static")]
+unsafe extern "C" {
+    fn ffi_record_layout(out: *mut RecordLayout);
+    fn ffi_sum(data: *const i32, len: usize) -> i64;
+    fn ffi_scale_record(record: *mut Record, factor: f64);
+    fn ffi_fold(data: *const i32, len: usize, init: i64, step: FoldFn, ctx: *mut c_void) -> i64;
+    fn ffi_greeting(name: *const c_char) -> *mut c_char;
+    fn ffi_free_greeting(message: *mut c_char);
+}
+
+fn demonstrate_layout() {
+    output::heading("📐 #[repr(C)] Struct Layout");
+
+    let mut c = RecordLayout::default();
+    unsafe { ffi_record_layout(&mut c) };
+
+    outln!("  struct {{ tag: u8, value: f64, id: u16 }}");
+    let mut table = Table::new(["layout", "size", "align", "tag", "value", "id"]);
+    for (layout, numbers) in [
+        ("C (sizeof/offsetof)", [c.size, c.align, c.offset_tag, c.offset_value, c.offset_id]),
+        ("Rust #[repr(C)]", [mem::size_of::<Record>(), mem::align_of::<Record>(), mem::offset_of!(Record, tag),
+                             mem::offset_of!(Record, value), mem::offset_of!(Record, id)]),
+        ("Rust default layout", [mem::size_of::<RustRecord>(), mem::align_of::<RustRecord>(),
+                                 mem::offset_of!(RustRecord, tag), mem::offset_of!(RustRecord, value),
+                                 mem::offset_of!(RustRecord, id)]),
+    ] {
+        table.row([layout.to_string()].into_iter().chain(numbers.iter().map(|n| n.to_string())));
+    }
+    out!("{}", table);
+
+    let agrees = c.size == mem::size_of::<Record>()
+        && c.offset_value == mem::offset_of!(Record, value)
+        && c.offset_id == mem::offset_of!(Record, id);
+    outln!("{} #[repr(C)] matches the C compiler byte for byte", if agrees { "✅" } else { "❌" });
+    outln!("#[repr(C)] keeps declaration order and C's padding rules. Without it Rust");
+    outln!("may reorder fields to save padding, and C would read garbage.\n");
+}
+
+fn demonstrate_rust_calls_c() {
+    output::heading("➡️  Rust Calls C");
+
+    let data: Vec<i32> = (1..=100).collect();
+    // The Vec stays owned by Rust; C only borrows it for the duration of the call
+    let total = unsafe { ffi_sum(data.as_ptr(), data.len()) };
+    outln!("ffi_sum(&[1..=100]) = {} (Rust computes {})", total, data.iter().map(|&x| x as i64).sum::<i64>());
+
+    let mut record = Record { tag: 7, value: 1.5, id: 41 };
+    unsafe { ffi_scale_record(&mut record, 2.0) };
+    outln!("ffi_scale_record(&mut record, 2.0) -> {:?}", record);
+    outln!("Slices cross as (pointer, length) pairs; C sees no Vec, no bounds and no");
+    outln!("lifetimes, so the pointer must stay valid until C returns.\n");
+}
+
+/// Adapts any Rust closure to C's (function pointer, void *ctx) convention:
+/// the closure travels as `ctx`, and this monomorphized function casts it back
+extern "C" fn trampoline<F: FnMut(i64, i32) -> i64>(acc: i64, item: i32, ctx: *mut c_void) -> i64 {
+    let closure = unsafe { &mut *(ctx as *mut F) };
+    closure(acc, item)
+}
+
+fn fold_in_c<F: FnMut(i64, i32) -> i64>(data: &[i32], init: i64, mut closure: F) -> i64 {
+    unsafe { ffi_fold(data.as_ptr(), data.len(), init, trampoline::<F>, &mut closure as *mut F as *mut c_void) }
+}
+
+extern "C" fn add_square(acc: i64, item: i32, _ctx: *mut c_void) -> i64 {
+    acc + item as i64 * item as i64
+}
+
+fn demonstrate_c_calls_rust() {
+    output::heading("⬅️  C Calls Back into Rust");
+
+    let data = [3, 1, 4, 1, 5, 9, 2, 6];
+    let sum_of_squares = unsafe { ffi_fold(data.as_ptr(), data.len(), 0, add_square, std::ptr::null_mut()) };
+    outln!("ffi_fold(data, add_square)  = {} (plain extern \"C\" fn)", sum_of_squares);
+
+    let mut calls = 0;
+    let max = fold_in_c(&data, i64::MIN, |acc, item| {
+        calls += 1; // captured state, reached through C's void *ctx
+        acc.max(item as i64)
+    });
+    outln!("ffi_fold(data, |acc, x| ..) = {} (closure via trampoline, called {} times)", max, calls);
+    outln!("C only knows function pointers. A closure's captured state is smuggled");
+    outln!("through the `void *ctx` argument that well-designed C APIs provide.\n");
+}
+
+fn demonstrate_ownership() {
+    output::heading("🤝 Ownership Across the Boundary");
+
+    // Rust -> C: CString adds the NUL terminator C expects; Rust keeps ownership
+    let name = CString::new("Rust").unwrap();
+    let message = unsafe { ffi_greeting(name.as_ptr()) };
+    if message.is_null() {
+        outln!("ffi_greeting returned NULL (out of memory)");
+        return;
+    }
+    // C -> Rust: borrow the C string, copy it into Rust-owned memory...
+    let text = unsafe { CStr::from_ptr(message) }.to_string_lossy().into_owned();
+    // ...then hand the allocation back to the allocator that made it
+    unsafe { ffi_free_greeting(message) };
+    outln!("ffi_greeting(\"Rust\") = {:?}", text);
+    outln!("Rules of thumb:");
+    outln!("  • Memory is freed by the side that allocated it: malloc'd pointers go back");
+    outln!("    to C's free(), never into Box::from_raw or CString::from_raw");
+    outln!("  • Rust strings aren't NUL-terminated: convert with CString / CStr");
+    outln!("  • A pointer passed to C is borrowed unless the API documents otherwise\n");
+}
+
+extern "C" fn panicking_step(_acc: i64, _item: i32, _ctx: *mut c_void) -> i64 {
+    panic!("panic inside a callback called from C");
+}
+
+/// Child process: let a panic reach the C frame and see what happens
+fn run_panic_child() {
+    let data = [1, 2, 3];
+    let result = unsafe { ffi_fold(data.as_ptr(), data.len(), 0, panicking_step, std::ptr::null_mut()) };
+    println!("unreachable: fold returned {}", result);
+}
+
+/// The sound pattern: catch the panic before it reaches C
+extern "C" fn guarded_step(acc: i64, item: i32, ctx: *mut c_void) -> i64 {
+    let panicked = unsafe { &mut *(ctx as *mut bool) };
+    match panic::catch_unwind(|| {
+        if item == 3 {
+            panic!("bad item");
+        }
+        acc + item as i64
+    }) {
+        Ok(value) => value,
+        Err(_) => {
+            *panicked = true;
+            acc // report through ctx instead of unwinding
+        }
+    }
+}
+
+fn demonstrate_panics() {
+    output::heading("💥 Panics Must Not Cross into C");
+
+    let exe = std::env::current_exe().expect("current_exe");
+    match Command::new(exe).arg("--panic-child").output() {
+        Ok(output) => {
+            #[cfg(unix)]
+            let signal = std::os::unix::process::ExitStatusExt::signal(&output.status);
+            #[cfg(not(unix))]
+            let signal: Option<i32> = None;
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let reason = stderr.lines().find(|line| line.contains("panic in a function that cannot unwind"));
+            outln!("Child whose extern \"C\" callback panics: {}{}",
+                     match signal {
+                         Some(6) => "killed by SIGABRT".to_string(),
+                         Some(n) => format!("killed by signal {}", n),
+                         None => format!("exited with {}", output.status),
+                     },
+                     reason.map(|r| format!("\n  stderr: {}", r.trim())).unwrap_or_default());
+        }
+        Err(err) => outln!("couldn't start child: {}", err),
+    }
+    outln!("Unwinding through C frames is undefined behavior (C has no unwind info");
+    outln!("and skips its own cleanup), so Rust aborts when a panic tries to leave an");
+    outln!("`extern \"C\"` function. `extern \"C-unwind\"` opts in to unwinding for C++");
+    outln!("or C code compiled with unwind tables.\n");
+
+    let data = [1, 2, 3, 4];
+    let mut panicked = false;
+    let previous_hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {})); // keep the expected panic quiet
+    let result =
+        unsafe { ffi_fold(data.as_ptr(), data.len(), 0, guarded_step, &mut panicked as *mut bool as *mut c_void) };
+    panic::set_hook(previous_hook);
+    outln!("With catch_unwind inside the callback: fold = {}, panic caught = {}", result, panicked);
+    outln!("The callback converts the panic into data (a flag or error code) that");
+    outln!("crosses the boundary safely.\n");
+}
+
+/// If this process is the demo's `--panic-child`, run it and return true.
+/// The demo re-runs its own executable to watch a panic abort it, so the
+/// binary's `main` must call this first.
+pub fn run_as_child() -> bool {
+    if std::env::args().any(|arg| arg == "--panic-child") {
+        run_panic_child();
+        return true;
+    }
+    false
+}
+
+const QUIZ: &[Question] = &[
+    Question::new(
+        "Why must a struct passed to C be #[repr(C)]?",
+        &[
+            "Rust may reorder and pad fields differently; repr(C) guarantees C's layout",
+            "It makes the struct faster",
+            "C can't read Rust integers otherwise",
+        ],
+        0,
+        "The default Rust layout is unspecified, so both sides must agree on one explicitly.",
+    ),
+    Question::new(
+        "What happens if a Rust panic unwinds into an extern \"C\" function?",
+        &[
+            "C catches it as an error code",
+            "The process aborts, so catch_unwind before crossing the boundary",
+            "It is silently ignored",
+        ],
+        1,
+        "Unwinding through foreign frames isn't allowed; extern \"C\" functions abort instead.",
+    ),
+    Question::new(
+        "How is a Rust closure passed as a C callback?",
+        &[
+            "As a function pointer plus a void* context that points at the closure",
+            "C can call Rust closures directly",
+            "By converting it to a string",
+        ],
+        0,
+        "A closure is data plus code; C gets a plain trampoline function and the data as an opaque pointer.",
+    ),
+];
+
+#[derive(Demo)]
+#[demo(name = "ffi-demo", description = "Rust and C calling each other through the C ABI",
+       quiz = QUIZ)]
+pub struct FfiDemo;
+
+impl FfiDemo {
+    fn demonstrate(&self, _config: &Config) -> DemoReport {
+        output::title("🔗 C FFI Round-Trip Demo");
+        outln!("Rust and C calling each other through the C ABI.\n");
+
+        demonstrate_layout();
+        demonstrate_rust_calls_c();
+        demonstrate_c_calls_rust();
+        demonstrate_ownership();
+        demonstrate_panics();
+
+        output::takeaways();
+        outln!("• extern \"C\" gives both languages one calling convention to agree on");
+        outln!("• #[repr(C)] is required for any struct that crosses the boundary");
+        outln!("• Closures cross as a function pointer plus a void* context");
+        outln!("• Whoever allocates frees; strings convert via CString/CStr");
+        outln!("• A panic reaching an extern \"C\" frame aborts: catch it first");
+        DemoReport::default()
+    }
+}
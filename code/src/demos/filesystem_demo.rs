@@ -0,0 +1,367 @@
+//! Filesystem Demo
+//!
+//! A file is two things: an inode, holding its size, owner, permissions,
+//! timestamps and where its data blocks are, and one or more names in
+//! directories that point at that inode. This demo creates files, hard
+//! links and symbolic links in a scratch directory and reads their
+//! metadata back with `stat` (`std::fs::metadata` plus the Unix
+//! `MetadataExt` fields): inode numbers, link counts, timestamps and the
+//! blocks actually allocated. A hard link is one more name for the same
+//! inode; a symlink is a small file of its own that holds a path.
+//! Run with: cargo run --bin filesystem-demo
+
+use std::fs::{self, Metadata, OpenOptions};
+use std::io::{self, Seek, SeekFrom, Write};
+use std::os::unix::fs::{symlink, DirEntryExt, MetadataExt, PermissionsExt};
+use std::thread;
+use std::time::{Duration, UNIX_EPOCH};
+
+use crate::demo::{Config, Demo, DemoReport};
+use crate::output::Table;
+use crate::plot::format_bytes;
+use crate::quiz::Question;
+use crate::scratch::Scratch;
+use crate::verify::Verification;
+use crate::{outln, output};
+
+/// Long enough for the kernel's file clock, which only advances once per
+/// scheduler tick, to move between steps
+const TICK: Duration = Duration::from_millis(20);
+
+/// `st_blocks` counts 512-byte units, whatever the filesystem's block size
+const STAT_BLOCK: u64 = 512;
+
+/// `st_mode` as `ls -l` shows it: the file type, then read, write and
+/// execute for owner, group and others
+fn mode_string(mode: u32) -> String {
+    let kind = match mode & 0o170000 {
+        0o040000 => 'd',
+        0o120000 => 'l',
+        0o100000 => '-',
+        _ => '?',
+    };
+    let permissions = "rwxrwxrwx".chars().enumerate().map(|(i, c)| if mode & (1 << (8 - i)) != 0 { c } else { '-' });
+    std::iter::once(kind).chain(permissions).collect()
+}
+
+/// Bytes the filesystem has allocated to the inode
+fn allocated(meta: &Metadata) -> u64 {
+    meta.blocks() * STAT_BLOCK
+}
+
+/// A table for `stat_row`s
+fn stat_table() -> Table {
+    Table::new(["name", "inode", "links", "mode", "size", "allocated"]).left(0)
+}
+
+fn stat_row(table: &mut Table, name: &str, meta: &Metadata) {
+    table.row([
+        name.to_string(),
+        meta.ino().to_string(),
+        meta.nlink().to_string(),
+        mode_string(meta.mode()),
+        format!("{}B", format_bytes(meta.size() as f64)),
+        format!("{}B", format_bytes(allocated(meta) as f64)),
+    ]);
+}
+
+fn demonstrate_inode(dir: &Scratch) -> io::Result<()> {
+    output::heading("📄 A File and Its Inode");
+    let path = dir.path("notes.txt");
+    fs::write(&path, "x".repeat(10_000))?;
+    let meta = fs::metadata(&path)?;
+    outln!("stat notes.txt (10 000 bytes written):");
+    outln!("  device     {:#x}", meta.dev());
+    outln!("  inode      {}", meta.ino());
+    outln!("  mode       {:04o} ({})", meta.mode() & 0o7777, mode_string(meta.mode()));
+    outln!("  links      {}", meta.nlink());
+    outln!("  owner      uid {}, gid {}", meta.uid(), meta.gid());
+    outln!("  size       {} bytes", meta.size());
+    outln!("  blocks     {} × {} B = {} bytes allocated", meta.blocks(), STAT_BLOCK, allocated(&meta));
+    outln!("  I/O block  {} bytes", meta.blksize());
+    outln!();
+    outln!("The one thing stat doesn't report is the name. A directory is a list of");
+    outln!("(name, inode number) entries, and readdir returns exactly that, with no");
+    outln!("stat needed:");
+    for entry in fs::read_dir(dir.dir())? {
+        let entry = entry?;
+        outln!("  {:<12} → inode {}", entry.file_name().to_string_lossy(), entry.ino());
+    }
+    outln!("Opening a path walks those entries component by component down to an");
+    outln!("inode; everything else about the file lives there.\n");
+    Ok(())
+}
+
+fn demonstrate_hard_links(dir: &Scratch, report: &mut DemoReport) -> io::Result<()> {
+    output::heading("🔗 Hard Links: Two Names, One Inode");
+    let original = dir.path("original.txt");
+    let link = dir.path("hardlink.txt");
+    fs::write(&original, "written through original.txt\n")?;
+    let mut table = stat_table();
+    stat_row(&mut table, "original.txt (before)", &fs::metadata(&original)?);
+    fs::hard_link(&original, &link)?;
+    let linked = fs::metadata(&link)?;
+    stat_row(&mut table, "original.txt", &fs::metadata(&original)?);
+    stat_row(&mut table, "hardlink.txt", &linked);
+    outln!("{}", table);
+    report.record("links after ln", linked.nlink() as f64, "links");
+
+    OpenOptions::new().append(true).open(&link)?.write_all(b"appended through hardlink.txt\n")?;
+    outln!("Append through hardlink.txt, then read original.txt:");
+    for line in fs::read_to_string(&original)?.lines() {
+        outln!("  {}", line);
+    }
+    fs::remove_file(&original)?;
+    let survivor = fs::metadata(&link)?;
+    outln!("rm original.txt: hardlink.txt is still inode {} with {} link, {} bytes.", survivor.ino(),
+           survivor.nlink(), survivor.size());
+    outln!("Both names were equal; neither was \"the\" file. `ln` added a directory");
+    outln!("entry and bumped the inode's link count; `rm` (unlink) removes an entry");
+    outln!("and decrements it. The data is freed only when the count reaches zero and");
+    outln!("no process still has the file open. Hard links can't cross filesystems:");
+    outln!("an inode number only means something on its own device.\n");
+    Ok(())
+}
+
+fn demonstrate_symlinks(dir: &Scratch) -> io::Result<()> {
+    output::heading("🪢 Symbolic Links: A File Holding a Path");
+    let target = dir.path("target.txt");
+    let link = dir.path("symlink.txt");
+    fs::write(&target, "the data\n".repeat(1_000))?;
+    symlink("target.txt", &link)?;
+    let mut table = stat_table();
+    stat_row(&mut table, "target.txt", &fs::metadata(&target)?);
+    stat_row(&mut table, "symlink.txt (lstat)", &fs::symlink_metadata(&link)?);
+    stat_row(&mut table, "symlink.txt (stat)", &fs::metadata(&link)?);
+    outln!("{}", table);
+    outln!("readlink symlink.txt: {:?}", fs::read_link(&link)?);
+    outln!("lstat describes the link itself: its own inode, one link, and a size that");
+    outln!("is the length of the path it holds. A path that short is kept inside the");
+    outln!("inode (a \"fast symlink\"), so it needs no data block at all. stat follows");
+    outln!("the link and describes target.txt; the link shares nothing with it.\n");
+
+    fs::remove_file(&target)?;
+    outln!("rm target.txt:");
+    match fs::metadata(&link) {
+        Ok(_) => outln!("  stat symlink.txt:  still resolves?"),
+        Err(err) => outln!("  stat symlink.txt:  {}", err),
+    }
+    let dangling = fs::symlink_metadata(&link)?;
+    outln!("  lstat symlink.txt: inode {}, {}", dangling.ino(), mode_string(dangling.mode()));
+    outln!("The link dangles: it names a path, not an inode, and nothing keeps the");
+    outln!("target alive. In return it can point anywhere: at a directory, across");
+    outln!("filesystems, or at a file that doesn't exist yet.\n");
+    Ok(())
+}
+
+/// A timestamp as nanoseconds since the epoch
+fn nanos(secs: i64, nsec: i64) -> i128 {
+    secs as i128 * 1_000_000_000 + nsec as i128
+}
+
+fn demonstrate_timestamps(dir: &Scratch) -> io::Result<()> {
+    output::heading("🕰️  Three Timestamps");
+    let path = dir.path("times.txt");
+    fs::write(&path, "version 1\n")?;
+    let created = fs::metadata(&path)?;
+    let epoch = nanos(created.mtime(), created.mtime_nsec());
+    let mut steps = vec![("create", created)];
+    for step in ["read", "read again", "append", "chmod 600", "ln"] {
+        thread::sleep(TICK);
+        match step {
+            "read" | "read again" => {
+                fs::read(&path)?;
+            }
+            "append" => OpenOptions::new().append(true).open(&path)?.write_all(b"version 2\n")?,
+            "chmod 600" => fs::set_permissions(&path, fs::Permissions::from_mode(0o600))?,
+            _ => fs::hard_link(&path, dir.path("times-link.txt"))?,
+        }
+        steps.push((step, fs::metadata(&path)?));
+    }
+
+    let mut table = Table::new(["after", "atime", "mtime", "ctime"]).left(0);
+    let mut previous: Option<&Metadata> = None;
+    for (step, meta) in &steps {
+        let cell = |now: i128, before: Option<i128>| {
+            let text = format!("+{:.1}ms", (now - epoch) as f64 / 1e6);
+            if before.is_some_and(|before| before != now) { format!("{} ←", text) } else { text }
+        };
+        table.row([
+            step.to_string(),
+            cell(nanos(meta.atime(), meta.atime_nsec()), previous.map(|p| nanos(p.atime(), p.atime_nsec()))),
+            cell(nanos(meta.mtime(), meta.mtime_nsec()), previous.map(|p| nanos(p.mtime(), p.mtime_nsec()))),
+            cell(nanos(meta.ctime(), meta.ctime_nsec()), previous.map(|p| nanos(p.ctime(), p.ctime_nsec()))),
+        ]);
+        previous = Some(meta);
+    }
+    outln!("Each time relative to the file's creation; ← marks a change.\n");
+    outln!("{}", table);
+    outln!("mtime changes when the data does. ctime changes when the inode does:");
+    outln!("every write, but also chmod and ln, which leave the data alone; no call");
+    outln!("can set it back, which is why backup tools trust it. atime is the last");
+    outln!("read, but updating it would turn every read into a write, so Linux's");
+    outln!("default `relatime` only moves it when it's older than mtime or a day old:");
+    outln!("the first read may move it, the second doesn't (and `noatime` never does).");
+    if let Ok(birth) = steps[0].1.created()
+        && let Ok(birth) = birth.duration_since(UNIX_EPOCH)
+    {
+        outln!("Newer filesystems also keep a birth time that nothing changes (statx's");
+        outln!("btime): {:+.1}ms here.", (birth.as_nanos() as i128 - epoch) as f64 / 1e6);
+    }
+    outln!();
+    Ok(())
+}
+
+fn demonstrate_blocks(dir: &Scratch, report: &mut DemoReport) -> io::Result<()> {
+    output::heading("🧱 Size vs Blocks Allocated");
+    let mut table = Table::new(["file", "size", "allocated", "blocks"]).left(0);
+    let files: [(&str, u64); 4] = [("empty", 0), ("one byte", 1), ("4 KiB + 1", 4097), ("1 MiB", 1 << 20)];
+    for (name, size) in files {
+        let path = dir.path(&format!("blocks-{}", size));
+        fs::write(&path, vec![b'x'; size as usize])?;
+        let meta = fs::metadata(&path)?;
+        table.row([name.to_string(), format!("{}B", format_bytes(meta.size() as f64)),
+                   format!("{}B", format_bytes(allocated(&meta) as f64)), meta.blocks().to_string()]);
+        if size == 1 {
+            report.record("allocated for 1 byte", allocated(&meta) as f64, "bytes");
+        }
+    }
+    let sparse = dir.path("sparse");
+    let mut file = OpenOptions::new().create(true).truncate(true).write(true).open(&sparse)?;
+    file.set_len(1 << 30)?;
+    let meta = fs::metadata(&sparse)?;
+    table.row(["1 GiB, set_len only".to_string(), format!("{}B", format_bytes(meta.size() as f64)),
+               format!("{}B", format_bytes(allocated(&meta) as f64)), meta.blocks().to_string()]);
+    file.seek(SeekFrom::Start(512 << 20))?;
+    file.write_all(b"x")?;
+    file.sync_all()?;
+    let meta = fs::metadata(&sparse)?;
+    table.row(["1 GiB, one byte at 512 MiB".to_string(), format!("{}B", format_bytes(meta.size() as f64)),
+               format!("{}B", format_bytes(allocated(&meta) as f64)), meta.blocks().to_string()]);
+    report.record("allocated for a sparse 1 GiB file", allocated(&meta) as f64, "bytes");
+    outln!("{}", table);
+    outln!("size is what read() sees; blocks is what the disk holds. Space is handed");
+    outln!("out in whole filesystem blocks, so one byte costs a block, and 4 KiB + 1");
+    outln!("costs two. Extending a file without writing makes a hole: the range reads");
+    outln!("as zeros but owns no blocks until something is written into it, so a");
+    outln!("\"1 GiB\" file can occupy one block. `du` reports blocks, `ls -l` size.\n");
+    Ok(())
+}
+
+/// Hard links share an inode and outlive each other, symlinks have their
+/// own and dangle, chmod moves only ctime, and holes take no space
+fn verify() -> Verification {
+    let mut verification = Verification::new();
+    let checked = (|| -> io::Result<()> {
+        let dir = Scratch::new("filesystem-demo", "verify")?;
+        let (original, link) = (dir.path("a"), dir.path("b"));
+        fs::write(&original, "one")?;
+        fs::hard_link(&original, &link)?;
+        let (a, b) = (fs::metadata(&original)?, fs::metadata(&link)?);
+        verification
+            .equal("a hard link shares its original's inode", a.ino(), b.ino())
+            .equal("the link count counts both names", b.nlink(), 2);
+        OpenOptions::new().append(true).open(&link)?.write_all(b" two")?;
+        verification.equal("a write through one name shows through the other", fs::read_to_string(&original)?,
+                           "one two".to_string());
+        fs::remove_file(&original)?;
+        verification.equal("the data survives removing the original name", fs::read_to_string(&link)?,
+                           "one two".to_string());
+
+        let (target, sym) = (dir.path("target"), dir.path("sym"));
+        fs::write(&target, "data")?;
+        symlink("target", &sym)?;
+        let lstat = fs::symlink_metadata(&sym)?;
+        verification
+            .check("a symlink has an inode of its own", lstat.ino() != fs::metadata(&target)?.ino(),
+                   || format!("both inode {}", lstat.ino()))
+            .equal("a symlink's size is its target path's length", lstat.size(), "target".len() as u64);
+        fs::remove_file(&target)?;
+        verification.check("a symlink to a removed file dangles",
+                           fs::metadata(&sym).is_err() && fs::symlink_metadata(&sym).is_ok(),
+                           || "stat still resolves it".to_string());
+
+        let path = dir.path("times");
+        fs::write(&path, "x")?;
+        let before = fs::metadata(&path)?;
+        thread::sleep(TICK);
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o600))?;
+        let after = fs::metadata(&path)?;
+        verification
+            .equal("chmod leaves mtime alone", (after.mtime(), after.mtime_nsec()), (before.mtime(), before.mtime_nsec()))
+            .check("chmod moves ctime", (after.ctime(), after.ctime_nsec()) > (before.ctime(), before.ctime_nsec()),
+                   || "ctime unchanged".to_string());
+
+        let sparse = dir.path("sparse");
+        OpenOptions::new().create(true).truncate(true).write(true).open(&sparse)?.set_len(1 << 30)?;
+        let meta = fs::metadata(&sparse)?;
+        verification.check("a file extended without writing takes less space than its size",
+                           allocated(&meta) < meta.size(), || format!("{} allocated", allocated(&meta)));
+        Ok(())
+    })();
+    if let Err(err) = checked {
+        verification.check("the scratch files can be created and inspected", false, || err.to_string());
+    }
+    verification
+}
+
+const QUIZ: &[Question] = &[
+    Question::new(
+        "a.txt has a hard link b.txt. You delete a.txt. What happens to b.txt?",
+        &["It dangles", "Nothing: same inode, and its link count drops to 1", "It's deleted too"],
+        1,
+        "Both names point at the inode; the data goes only when no name (or open file) is left.",
+    ),
+    Question::new(
+        "What is the size of a symlink pointing at \"../data/file.txt\"?",
+        &["The size of file.txt", "16 bytes: the length of the path it holds", "0"],
+        1,
+        "A symlink's content is its target path; lstat reports that length.",
+    ),
+    Question::new(
+        "Which timestamp changes on chmod but not on a read?",
+        &["atime", "mtime", "ctime"],
+        2,
+        "ctime tracks changes to the inode itself, permissions included; mtime only the data.",
+    ),
+];
+
+#[derive(Demo)]
+#[demo(name = "filesystem-demo", description = "Inodes, hard links, symlinks, timestamps and blocks",
+       quiz = QUIZ, verify = verify)]
+pub struct FilesystemDemo;
+
+impl FilesystemDemo {
+    fn demonstrate(&self, _config: &Config) -> DemoReport {
+        output::title("🗂️  Filesystem Demo");
+        outln!("What stat says about files, links and the blocks behind them.\n");
+
+        let mut report = DemoReport::default();
+        let dir = match Scratch::new("filesystem-demo", "run") {
+            Ok(dir) => dir,
+            Err(err) => {
+                outln!("⚠️  Can't create a scratch directory: {}\n", err);
+                return report;
+            }
+        };
+        outln!("Working in {}\n", dir.dir().display());
+        let check = |result: io::Result<()>| {
+            if let Err(err) = result {
+                outln!("❌ {}\n", err);
+            }
+        };
+        check(demonstrate_inode(&dir));
+        check(demonstrate_hard_links(&dir, &mut report));
+        check(demonstrate_symlinks(&dir));
+        check(demonstrate_timestamps(&dir));
+        check(demonstrate_blocks(&dir, &mut report));
+
+        output::takeaways();
+        outln!("• An inode holds everything about a file except its name; directories map names to inodes");
+        outln!("• A hard link is another name for the same inode; data lives until the last name goes");
+        outln!("• A symlink is a separate tiny file holding a path, and can dangle");
+        outln!("• mtime is the data, ctime the inode, atime (lazily) the last read");
+        outln!("• size and allocated blocks differ: whole blocks, and holes that take none");
+        report
+    }
+}
@@ -0,0 +1,309 @@
+//! Floating-Point Demo
+//!
+//! An `f64` is 64 bits: a sign, an 11-bit exponent and a 52-bit fraction,
+//! meaning ±1.fraction × 2^exponent. This demo takes floats apart field by
+//! field, prints the exact values `0.1`, `0.2` and `0.3` really hold to show
+//! why `0.1 + 0.2 != 0.3`, walks through the special values (infinities,
+//! NaN, negative zero, subnormals), measures the gap between neighbouring
+//! floats at different magnitudes, and sums 0.1 ten million times to watch
+//! rounding errors pile up, and Kahan summation take most of them back.
+//! Run with: cargo run --bin floating-point-demo
+
+use std::hint::black_box;
+use std::time::Duration;
+
+use crate::bench::measure;
+use crate::bits::{self, Class, Float, Parts};
+use crate::demo::{Config, Demo, DemoReport};
+use crate::output::{Style, Table};
+use crate::quiz::Question;
+use crate::verify::Verification;
+use crate::{outln, output};
+
+/// Terms in the accumulation experiment (`--size`)
+const TERMS: usize = 10_000_000;
+const RUNS: usize = 5;
+
+/// 2^24: above it, an `f32` can't hold every integer
+const F32_INTEGERS: f32 = 16_777_216.0;
+/// 2^53, the same limit for `f64`
+const F64_INTEGERS: f64 = 9_007_199_254_740_992.0;
+
+/// The value a float's fields describe, recomputed from them:
+/// significand × 2^(exponent − mantissa bits), in `f64`, where this is
+/// exact for every finite `f32`
+fn reassemble(parts: &Parts) -> f64 {
+    let exponent = parts.unbiased_exponent().unwrap_or(0) - f32::MANTISSA_BITS as i32;
+    let magnitude = parts.significand() as f64 * 2f64.powi(exponent);
+    if parts.negative { -magnitude } else { magnitude }
+}
+
+/// Add `term` to itself `n` times, one rounding per addition
+fn naive_sum<F: Float + std::ops::Add<Output = F>>(term: F, n: usize, zero: F) -> F {
+    let mut sum = zero;
+    for _ in 0..n {
+        sum = sum + black_box(term);
+    }
+    sum
+}
+
+/// Kahan summation: keep the low bits each addition rounds away in
+/// `compensation` and feed them back into the next term
+fn kahan_sum<F>(term: F, n: usize, zero: F) -> F
+where
+    F: Float + std::ops::Add<Output = F> + std::ops::Sub<Output = F>,
+{
+    let (mut sum, mut compensation) = (zero, zero);
+    for _ in 0..n {
+        let corrected = black_box(term) - compensation;
+        let next = sum + corrected;
+        // (next - sum) is what was actually added; subtracting the
+        // intended amount leaves the rounding error
+        compensation = (next - sum) - corrected;
+        sum = next;
+    }
+    sum
+}
+
+fn demonstrate_anatomy() {
+    output::heading("🔬 Anatomy of an f32");
+    outln!("1 sign bit, 8 exponent bits (biased by 127), 23 mantissa bits after an");
+    outln!("implicit leading 1: value = (−1)^sign × 1.mantissa × 2^(exponent − 127)\n");
+
+    let values: [(&str, f32); 12] = [
+        ("1.0", 1.0),
+        ("-2.5", -2.5),
+        ("0.1", 0.1),
+        ("1/3", 1.0 / 3.0),
+        ("2^24", F32_INTEGERS),
+        ("f32::MAX", f32::MAX),
+        ("MIN_POSITIVE", f32::MIN_POSITIVE),
+        ("smallest", f32::from_bits(1)),
+        ("0.0", 0.0),
+        ("-0.0", -0.0),
+        ("INFINITY", f32::INFINITY),
+        ("NAN", f32::NAN),
+    ];
+    let mut table = Table::new(["value", "s", "exponent", "mantissa", "2^e", "class"]).left(2).left(3).left(5);
+    for (name, value) in values {
+        let parts = Parts::of(value);
+        let power = parts.unbiased_exponent().map_or("-".to_string(), |exponent| exponent.to_string());
+        table.row([name.to_string(), (parts.negative as u8).to_string(), bits::binary(parts.exponent, 8, 0),
+                   bits::binary(parts.mantissa, 23, 0), power, parts.class.to_string()]);
+    }
+    outln!("{}", table);
+    let tenth = Parts::of(0.1f32);
+    outln!("0.1 is 1.{}... × 2^{}: in binary, 1/10 repeats 0011 forever,", bits::binary(tenth.mantissa, 23, 0),
+           tenth.unbiased_exponent().unwrap_or(0));
+    outln!("so it's cut off after 23 bits and rounded (up, here: the last bits are 1101).");
+    outln!("An exponent of all zeros means zero or a subnormal (no implicit 1, losing");
+    outln!("precision gradually towards 0); all ones means infinity or NaN.\n");
+}
+
+fn demonstrate_point_three() {
+    output::heading("➕ Why 0.1 + 0.2 != 0.3");
+    let sum = black_box(0.1f64) + black_box(0.2);
+    outln!("The exact values these f64s hold:");
+    for (name, value) in [("0.1", 0.1f64), ("0.2", 0.2), ("0.1 + 0.2", sum), ("0.3", 0.3)] {
+        outln!("  {:<10} = {}", name, bits::exact_decimal(value));
+    }
+    outln!();
+    outln!("0.1 and 0.2 are each stored a little high; their sum rounds to the float just");
+    outln!("above the one nearest 0.3. They are {} ULP apart, differing in the last bit:", bits::ulps(sum, 0.3));
+    outln!("  0.1 + 0.2  {}", Parts::of(sum));
+    outln!("  0.3        {}", Parts::of(0.3f64));
+    outln!("println! shows the shortest decimal that reads back as the same float, so the");
+    outln!("sum prints as {} and 0.3 as {}.", sum, 0.3f64);
+    outln!("Compare with a tolerance scaled to the values: (a - b).abs() <= 4.0 * f64::EPSILON * a.abs()");
+    outln!("is {} here; or count ULPs apart, as bits::ulps does.\n", (sum - 0.3).abs() <= 4.0 * f64::EPSILON * sum.abs());
+}
+
+fn demonstrate_special_values() {
+    output::heading("♾️  Special Values");
+    let zero = black_box(0.0f64);
+    let rows: [(&str, f64); 10] = [
+        ("1.0 / 0.0", 1.0 / zero),
+        ("-1.0 / 0.0", -1.0 / zero),
+        ("0.0 / 0.0", zero / black_box(0.0)),
+        ("inf - inf", f64::INFINITY - black_box(f64::INFINITY)),
+        ("f64::MAX * 2.0", f64::MAX * black_box(2.0)),
+        ("-0.0", -zero),
+        ("1.0 / -0.0", 1.0 / -zero),
+        ("MIN_POSITIVE / 2", f64::MIN_POSITIVE / black_box(2.0)),
+        ("smallest / 2", f64::from_bits(1) / black_box(2.0)),
+        ("(-1.0).sqrt()", black_box(-1.0f64).sqrt()),
+    ];
+    let mut table = Table::new(["expression", "result", "bits", "class"]).left(2).left(3);
+    for (expression, value) in rows {
+        table.row([expression.to_string(), format!("{:?}", value), format!("{:016x}", value.to_bits()),
+                   Parts::of(value).class.to_string()]);
+    }
+    outln!("{}", table);
+    let nan = zero / black_box(0.0);
+    let same = nan;
+    outln!("Nothing traps: overflow gives infinity, 0/0 gives NaN, and NaN spreads");
+    outln!("through every later operation. Comparisons are where it bites:");
+    outln!("  NaN == NaN: {}   NaN < 1.0: {}   NaN > 1.0: {}   NaN != NaN: {}",
+           nan == same, nan < 1.0, nan > 1.0, nan != same);
+    outln!("which is why f64 is PartialOrd and PartialEq but not Ord or Eq, and why sorting");
+    outln!("floats needs total_cmp (it orders -NaN < -inf < ... < -0.0 < 0.0 < ... < inf < NaN).");
+    outln!("-0.0 == 0.0 is {}, yet its sign bit is set, and 1.0 / -0.0 is {}.", -zero == zero, 1.0 / -zero);
+    outln!("Halving the smallest normal gives a subnormal instead of 0: gradual underflow.\n");
+}
+
+fn demonstrate_ulps() {
+    output::heading("📏 Precision Is Relative: the ULP");
+    outln!("A float has a fixed number of significant bits, so the gap to the next");
+    outln!("float (one unit in the last place) grows with the magnitude:\n");
+    let magnitudes = [1.0, 1_000.0, 1e6, F32_INTEGERS as f64, 1e9, F64_INTEGERS, 1e16, 1e300];
+    let mut table = Table::new(["magnitude", "f32 ULP", "f64 ULP"]);
+    for magnitude in magnitudes {
+        let single = magnitude as f32;
+        let single_ulp = if single.is_finite() { format!("{:e}", bits::ulp(single)) } else { "(overflows)".to_string() };
+        table.row([format!("{:e}", magnitude), single_ulp, format!("{:e}", bits::ulp(magnitude))]);
+    }
+    outln!("{}", table);
+    let big = black_box(F32_INTEGERS);
+    let huge = black_box(F64_INTEGERS);
+    outln!("Past 2^24 an f32 can't hold every integer: 16777216f32 + 1.0 = {} (the", big + 1.0);
+    outln!("exact 16777217 is a tie, rounded to even). f64 runs out at 2^53:");
+    outln!("9007199254740992.0 + 1.0 = {}, which is also why JavaScript's", huge + 1.0);
+    outln!("Number.MAX_SAFE_INTEGER is 2^53 − 1. f64::EPSILON ({:e}) is the ULP at 1.0.\n", f64::EPSILON);
+}
+
+fn demonstrate_accumulation(terms: usize, report: &mut DemoReport) {
+    output::heading("🧮 Rounding Errors Add Up");
+    let exact = terms as f64 / 10.0;
+    outln!("Adding 0.1 {} times; the exact answer is {}.\n", terms, exact);
+
+    let naive_f32 = measure(RUNS, || naive_sum(0.1f32, terms, 0.0));
+    let kahan_f32 = measure(RUNS, || kahan_sum(0.1f32, terms, 0.0));
+    let naive_f64 = measure(RUNS, || naive_sum(0.1f64, terms, 0.0));
+    let kahan_f64 = measure(RUNS, || kahan_sum(0.1f64, terms, 0.0));
+    let results: [(&str, f64, Duration); 4] = [
+        ("f32, one add at a time", naive_sum(0.1f32, terms, 0.0) as f64, naive_f32.median()),
+        ("f32, Kahan", kahan_sum(0.1f32, terms, 0.0) as f64, kahan_f32.median()),
+        ("f64, one add at a time", naive_sum(0.1f64, terms, 0.0), naive_f64.median()),
+        ("f64, Kahan", kahan_sum(0.1f64, terms, 0.0), kahan_f64.median()),
+    ];
+    let mut table = Table::new(["method", "sum", "relative error", "time"]).left(0);
+    for (method, sum, time) in results {
+        let error = (sum - exact).abs() / exact;
+        let painted = if error < 1e-6 { Style::Faster.paint(format!("{:.1e}", error)) }
+                      else { Style::Slower.paint(format!("{:.1e}", error)) };
+        table.row([method.to_string(), format!("{}", sum), painted.to_string(), format!("{:.2?}", time)]);
+        report.record(method, error, "relative error").param("terms", terms);
+        report.record_time(format!("{} time", method), time).param("terms", terms);
+    }
+    outln!("{}", table);
+
+    let single = results[0].1 as f32;
+    let step = (single + black_box(0.1f32)) - single;
+    if step == 0.0 {
+        outln!("The plain f32 sum stopped growing at {}: the ULP there is {}, more than", single, bits::ulp(single));
+        outln!("twice 0.1, so every further addition rounds back to the same value.");
+    } else {
+        outln!("The plain f32 sum is off by {:.1}%: near {} the ULP is {}, so each added",
+               (single as f64 - exact).abs() / exact * 100.0, single, bits::ulp(single));
+        outln!("0.1 is rounded to a whole number of ULPs and really adds {}. Past 2^21", step);
+        outln!("(ULP 0.25) it rounds to nothing and the sum sticks at 2097152: try --size 30000000.");
+    }
+    outln!("Each f64 addition is off by up to half an ULP and the errors drift together;");
+    outln!("Kahan summation carries each rounding error into the next addition, at the");
+    outln!("cost of three more dependent operations per term ({:.1}x the time for f64).",
+           kahan_f64.median().as_secs_f64() / naive_f64.median().as_secs_f64());
+    outln!("Reordering helps too (adding small values first, or pairwise), which is why");
+    outln!("compilers may not reassociate float sums without being told they can.\n");
+}
+
+/// The fields describe the value, the 0.1 + 0.2 and special-value claims
+/// hold, and Kahan summation beats the naive sum
+fn verify() -> Verification {
+    let mut verification = Verification::new();
+    let mut rng = Config::default().rng();
+    let mismatched: Vec<f32> = (0..10_000).map(|_| f32::from_bits(rng.next_u64() as u32))
+        .filter(|value| value.is_finite() && reassemble(&Parts::of(*value)) != *value as f64)
+        .collect();
+    verification.check("sign, exponent and mantissa give back every finite f32 tried", mismatched.is_empty(),
+                       || format!("{:?} didn't", &mismatched[..mismatched.len().min(3)]));
+
+    let sum = black_box(0.1f64) + black_box(0.2);
+    let zero = black_box(0.0f64);
+    let nan = zero / black_box(0.0);
+    let same = nan;
+    verification
+        .check("0.1 + 0.2 != 0.3", sum != 0.3, || "they compared equal".to_string())
+        .equal("0.1 + 0.2 and 0.3 are neighbouring floats", bits::ulps(sum, 0.3), 1)
+        .equal("0.1 is stored as slightly more than 0.1", bits::exact_decimal(0.1f64).starts_with("0.10000000000000000555"), true)
+        .equal("NaN isn't equal to itself", nan == same, false)
+        .equal("-0.0 equals 0.0 but has its sign bit set", (-zero == zero, Parts::of(-zero).negative), (true, true))
+        .equal("1 / -0.0 is negative infinity", 1.0 / -zero, f64::NEG_INFINITY)
+        .equal("half the smallest normal is subnormal", Parts::of(f64::MIN_POSITIVE / black_box(2.0)).class, Class::Subnormal)
+        .equal("2^24 + 1 rounds back to 2^24 in f32", black_box(F32_INTEGERS) + 1.0, F32_INTEGERS)
+        .equal("2^53 + 1 rounds back to 2^53 in f64", black_box(F64_INTEGERS) + 1.0, F64_INTEGERS)
+        .equal("the ULP at 1.0 is f64::EPSILON", bits::ulp(1.0f64), f64::EPSILON);
+
+    let terms = 1_000_000;
+    let exact = terms as f64 / 10.0;
+    let naive = (naive_sum(0.1f32, terms, 0.0) as f64 - exact).abs();
+    let kahan = (kahan_sum(0.1f32, terms, 0.0) as f64 - exact).abs();
+    verification.check("Kahan summation is at least 100x closer than naive f32 over 10^6 terms", kahan * 100.0 < naive,
+                       || format!("naive off by {}, Kahan by {}", naive, kahan));
+    verification
+}
+
+const QUIZ: &[Question] = &[
+    Question::new(
+        "Why is 0.1 + 0.2 != 0.3 in f64?",
+        &[
+            "Floating-point addition is implemented incorrectly in hardware",
+            "None of the three is exactly representable in binary; the rounded sum lands one float above the rounded 0.3",
+            "Rust prints floats with too few digits",
+        ],
+        1,
+        "1/10 is a repeating fraction in base 2, the way 1/3 is in base 10. Each value is rounded to the nearest float, and the errors don't cancel.",
+    ),
+    Question::new(
+        "Why can't you sort a Vec<f64> with .sort()?",
+        &[
+            "f64 is too big to compare in one instruction",
+            "NaN compares false with everything, so floats have no total order: f64 isn't Ord",
+            "Sorting floats is not stable",
+        ],
+        1,
+        "Use sort_by(f64::total_cmp), which gives NaN and -0.0 a fixed place.",
+    ),
+    Question::new(
+        "What is the gap between 1e16 and the next f64?",
+        &["About 2.2e-16 (f64::EPSILON)", "2.0", "It's the same gap at every magnitude"],
+        1,
+        "Precision is relative: with 53 significant bits, above 2^53 the ULP is 2 or more, so not every integer fits.",
+    ),
+];
+
+#[derive(Demo)]
+#[demo(name = "floating-point-demo", description = "IEEE 754 bit by bit: fields, special values, ULPs and rounding",
+       quiz = QUIZ, verify = verify)]
+pub struct FloatingPointDemo;
+
+impl FloatingPointDemo {
+    fn demonstrate(&self, config: &Config) -> DemoReport {
+        output::title("🔢 Floating-Point Demo");
+        outln!("What's inside an f32 and an f64, and where the arithmetic stops being exact.\n");
+
+        let mut report = DemoReport::default();
+        demonstrate_anatomy();
+        demonstrate_point_three();
+        demonstrate_special_values();
+        demonstrate_ulps();
+        demonstrate_accumulation(config.size.unwrap_or(TERMS).max(1), &mut report);
+
+        output::takeaways();
+        outln!("• A float is a sign, a biased exponent and a mantissa with an implicit leading 1");
+        outln!("• Decimal fractions like 0.1 aren't representable: compare floats with a tolerance, not ==");
+        outln!("• Infinities, NaN and -0.0 are ordinary results, not errors: NaN isn't even equal to itself");
+        outln!("• The gap between floats grows with magnitude; f64 holds every integer only up to 2^53");
+        outln!("• Rounding errors accumulate; Kahan or pairwise summation keeps long sums accurate");
+        report
+    }
+}
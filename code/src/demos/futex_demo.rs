@@ -0,0 +1,223 @@
+//! Raw Futex Demo
+//!
+//! A mutex and an event built directly on the Linux `futex` syscall, with
+//! counters showing when they stay in userspace (one CAS) and when they
+//! have to ask the kernel to sleep or wake a thread.
+//! Run with: cargo run --release --bin futex-demo
+
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+use crate::bench::{format_rate, measure};
+use crate::demo::{Config, Demo, DemoReport};
+use crate::output::Table;
+use crate::quiz::Question;
+use crate::sync::futex::{FutexEvent, FutexMutex, FutexStats, SPIN_LIMIT};
+use crate::{outln, output};
+
+const UNCONTENDED_OPS: u64 = 1_000_000;
+const CONTENDED_OPS_PER_THREAD: u64 = 100_000;
+/// Work inside the contended critical section, long enough to get preempted in
+const CRITICAL_STEPS: u64 = 50;
+
+fn critical_section(counter: &mut u64) {
+    for step in 0..CRITICAL_STEPS {
+        *counter = std::hint::black_box(counter.wrapping_add(step));
+    }
+    *counter += 1;
+}
+
+fn print_stats(label: &str, stats: FutexStats) {
+    outln!("  {:<28} fast {:>9}  slow {:>7}  FUTEX_WAIT {:>6}  FUTEX_WAKE {:>6}",
+             label, stats.fast_path, stats.slow_path, stats.futex_waits, stats.futex_wakes);
+}
+
+fn demonstrate_futex_word() {
+    output::heading("🧱 One Word, Three States");
+    outln!("  0 = unlocked");
+    outln!("  1 = locked, nobody waiting      → unlock is a plain swap, no syscall");
+    outln!("  2 = locked, maybe someone asleep → unlock must FUTEX_WAKE one waiter");
+    outln!("lock():   CAS 0→1. Fails? spin {} times, then swap in 2 and FUTEX_WAIT(2).", SPIN_LIMIT);
+    outln!("The kernel re-checks the word is still 2 before sleeping, so a wake that");
+    outln!("races with the wait can't be lost.\n");
+}
+
+fn demonstrate_fast_path(report: &mut DemoReport) {
+    output::heading("⚡ Uncontended: Never Leaves Userspace");
+
+    let futex = FutexMutex::new(0u64);
+    let std_mutex = Mutex::new(0u64);
+    let futex_rate = measure(3, || {
+        for _ in 0..UNCONTENDED_OPS {
+            *futex.lock() += 1;
+        }
+    })
+    .throughput(UNCONTENDED_OPS);
+    let std_rate = measure(3, || {
+        for _ in 0..UNCONTENDED_OPS {
+            *std_mutex.lock().unwrap() += 1;
+        }
+    })
+    .throughput(UNCONTENDED_OPS);
+
+    outln!("{} lock/unlock pairs on one thread (median of 3 runs):", UNCONTENDED_OPS);
+    outln!("  FutexMutex:        {}", format_rate(futex_rate));
+    outln!("  std::sync::Mutex:  {}", format_rate(std_rate));
+    report.record("FutexMutex uncontended", futex_rate, "ops/s");
+    report.record("std Mutex uncontended", std_rate, "ops/s");
+
+    // Count the paths of one pass on a fresh mutex
+    let counted = FutexMutex::new(0u64);
+    for _ in 0..UNCONTENDED_OPS {
+        *counted.lock() += 1;
+    }
+    print_stats("FutexMutex paths, one pass:", counted.stats());
+    outln!("Every lock was one CAS and every unlock one swap: zero syscalls.");
+    outln!("(FutexMutex pays for an extra atomic counter bump per lock; that's the gap to std.)\n");
+}
+
+/// `threads` workers hammer one FutexMutex; returns its counters
+fn run_contended_futex(threads: usize) -> FutexStats {
+    let mutex = FutexMutex::new(0u64);
+    thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| {
+                for _ in 0..CONTENDED_OPS_PER_THREAD {
+                    critical_section(&mut mutex.lock());
+                }
+            });
+        }
+    });
+    mutex.stats()
+}
+
+fn run_contended_std(threads: usize) {
+    let mutex = Mutex::new(0u64);
+    thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| {
+                for _ in 0..CONTENDED_OPS_PER_THREAD {
+                    critical_section(&mut mutex.lock().unwrap());
+                }
+            });
+        }
+    });
+}
+
+fn demonstrate_slow_path(report: &mut DemoReport) {
+    output::heading("🔥 Contended: Spinning, Sleeping, Waking");
+    outln!("Each thread locks {} times; median of 3 runs.\n", CONTENDED_OPS_PER_THREAD);
+    let table = Table::new(["threads", "FutexMutex", "std Mutex", "slow %", "FUTEX_WAIT", "FUTEX_WAKE"])
+        .widths([7, 12, 12, 7, 10, 10]);
+    outln!("{}", table.header());
+
+    let max_threads = thread::available_parallelism().map(|n| n.get()).unwrap_or(1).max(2) * 2;
+    let mut threads = 1;
+    while threads <= max_threads {
+        let total_ops = threads as u64 * CONTENDED_OPS_PER_THREAD;
+        let mut stats = FutexStats::default();
+        let futex_rate = measure(3, || stats = run_contended_futex(threads)).throughput(total_ops);
+        let std_rate = measure(3, || run_contended_std(threads)).throughput(total_ops);
+        let rates = output::rank(&[futex_rate, std_rate], true, format_rate);
+        let counters = [format!("{:.2}%", stats.slow_path as f64 * 100.0 / total_ops as f64),
+                        stats.futex_waits.to_string(), stats.futex_wakes.to_string()];
+        outln!("{}", table.line([threads.to_string()].into_iter().chain(rates).chain(counters)));
+        report.record("FutexMutex contended", futex_rate, "ops/s").param("threads", threads);
+        report.record("std Mutex contended", std_rate, "ops/s").param("threads", threads);
+        threads *= 2;
+    }
+
+    outln!();
+    outln!("(counters from the last run of each row)");
+    outln!("Most contended acquisitions still end in the spin loop; only threads that");
+    outln!("outwait {} spins pay for a FUTEX_WAIT, and only unlocks that find state 2", SPIN_LIMIT);
+    outln!("pay for a FUTEX_WAKE. The two columns track each other because std's");
+    outln!("mutex is this same algorithm.");
+    let cores = thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+    if cores == 1 {
+        outln!("⚠️  Only one hardware thread available: contention only happens when the");
+        outln!("   holder is preempted inside the critical section, and spinning can't");
+        outln!("   help because the holder isn't running.");
+    }
+    outln!();
+}
+
+fn demonstrate_event() {
+    output::heading("📣 Event: One Syscall Wakes Everyone");
+
+    let waiters = 4;
+    let event = FutexEvent::new();
+    thread::scope(|scope| {
+        for _ in 0..waiters {
+            scope.spawn(|| event.wait());
+        }
+        // Give the waiters time to fall asleep in the kernel
+        thread::sleep(Duration::from_millis(50));
+        event.set();
+    });
+    print_stats(&format!("{} waiters, then set():", waiters), event.stats());
+
+    let before = event.stats();
+    event.wait();
+    event.set();
+    let after = event.stats();
+    outln!("  wait() + set() while already set: {} extra syscalls",
+             (after.futex_waits + after.futex_wakes) - (before.futex_waits + before.futex_wakes));
+    outln!("set() sees state 1 (\"someone is waiting\") and issues FUTEX_WAKE(all) once;");
+    outln!("with no waiters recorded it's a plain store.\n");
+}
+
+fn demonstrate_tracing() {
+    output::heading("🔎 Watching the Kernel Side");
+    outln!("  strace -f -c -e trace=futex ./target/release/futex-demo");
+    outln!("counts the futex syscalls of the whole run: compare them with the");
+    outln!("FUTEX_WAIT / FUTEX_WAKE columns above. Try it on a program using");
+    outln!("std::sync::Mutex too: the same pattern (nothing when uncontended) appears.\n");
+}
+
+const QUIZ: &[Question] = &[
+    Question::new(
+        "How many syscalls does an uncontended futex lock and unlock make?",
+        &["Two", "Zero", "One"],
+        1,
+        "The fast path is one atomic operation each; the kernel is only entered to sleep or wake.",
+    ),
+    Question::new(
+        "When does unlock call FUTEX_WAKE?",
+        &[
+            "On every unlock",
+            "Only when the lock word records that someone may be asleep",
+            "When a timer fires",
+        ],
+        1,
+        "The three-state word (unlocked, locked, locked with waiters) lets unlock skip the syscall.",
+    ),
+];
+
+#[derive(Demo)]
+#[demo(name = "futex-demo", description = "A mutex and an event on the raw futex syscall",
+       quiz = QUIZ)]
+pub struct FutexDemo;
+
+impl FutexDemo {
+    fn demonstrate(&self, _config: &Config) -> DemoReport {
+        output::title("🧵 Raw Futex Demo");
+        outln!("What std::sync::Mutex does under the hood on Linux.\n");
+
+        demonstrate_futex_word();
+        let mut report = DemoReport::default();
+        demonstrate_fast_path(&mut report);
+        demonstrate_slow_path(&mut report);
+        demonstrate_event();
+        demonstrate_tracing();
+
+        output::takeaways();
+        outln!("• A futex is an integer in your memory; the kernel only keeps the wait queue");
+        outln!("• Uncontended lock/unlock is one atomic op each - no syscall at all");
+        outln!("• The kernel is entered only to sleep (FUTEX_WAIT) or wake (FUTEX_WAKE)");
+        outln!("• Tracking \"someone might be asleep\" in the word lets unlock skip the wake");
+        outln!("• std::sync::Mutex on Linux is this algorithm plus poisoning");
+        report
+    }
+}
@@ -0,0 +1,513 @@
+//! GPU Demo
+//!
+//! One large multiply-add, `out[i] = a[i] × b[i] + c[i]`, four ways on the
+//! CPU (one element at a time, as LLVM vectorizes it, with AVX2 and FMA,
+//! and on every core) and once as a wgpu compute shader. The GPU's time is
+//! split into uploading the inputs, running the kernel and reading the
+//! result back, because the split decides whether offloading is worth it:
+//! a kernel can beat the CPU by a wide margin and still lose once the data
+//! has to cross the bus both ways.
+//!
+//! Built only with the `gpu` feature, which pulls in wgpu.
+//! Run with: cargo run --release --features gpu --bin gpu-demo
+
+use std::hint::black_box;
+use std::time::Duration;
+
+use rayon::prelude::*;
+
+use crate::bench::{measure, Measurement};
+use crate::demo::{Config, Demo, DemoReport};
+use crate::output::Table;
+use crate::plot::{format_bytes, format_si};
+use crate::quiz::Question;
+use crate::verify::Verification;
+use crate::{detailln, outln, output};
+
+/// Largest vector, in elements (`--size`)
+const MAX_ELEMENTS: usize = 16 * 1024 * 1024;
+/// Bytes each element moves: three f32s read, one written
+const BYTES_PER_ELEMENT: usize = 16;
+/// Elements per task when the work is split across threads
+const CHUNK: usize = 64 * 1024;
+
+type MultiplyAdd = fn(&mut [f32], &[f32], &[f32], &[f32]);
+
+/// One element at a time: `black_box` hides each load from the optimizer,
+/// so LLVM can't turn the loop into vector instructions
+fn scalar(out: &mut [f32], a: &[f32], b: &[f32], c: &[f32]) {
+    for i in 0..out.len() {
+        out[i] = black_box(a[i]) * b[i] + c[i];
+    }
+}
+
+/// The plain loop, which LLVM vectorizes for the baseline target (SSE2 on
+/// x86-64: four floats per instruction, and a separate multiply and add)
+fn vectorized(out: &mut [f32], a: &[f32], b: &[f32], c: &[f32]) {
+    for ((out, (&a, &b)), &c) in out.iter_mut().zip(a.iter().zip(b)).zip(c) {
+        *out = a * b + c;
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+mod avx2 {
+    use std::arch::x86_64::*;
+
+    /// Floats in one ymm register
+    const LANES: usize = 8;
+
+    pub fn available() -> bool {
+        is_x86_feature_detected!("avx2") && is_x86_feature_detected!("fma")
+    }
+
+    #[target_feature(enable = "avx2,fma")]
+    fn multiply_add_fma(out: &mut [f32], a: &[f32], b: &[f32], c: &[f32]) {
+        let body = out.len() / LANES * LANES;
+        for i in (0..body).step_by(LANES) {
+            // SAFETY: i + LANES <= len of every slice (the callers pass
+            // equal lengths); loadu/storeu accept any alignment
+            unsafe {
+                let product = _mm256_fmadd_ps(_mm256_loadu_ps(a.as_ptr().add(i)), _mm256_loadu_ps(b.as_ptr().add(i)),
+                                              _mm256_loadu_ps(c.as_ptr().add(i)));
+                _mm256_storeu_ps(out.as_mut_ptr().add(i), product);
+            }
+        }
+        super::vectorized(&mut out[body..], &a[body..], &b[body..], &c[body..]);
+    }
+
+    /// Eight multiply-adds per instruction, each rounded once
+    pub fn multiply_add(out: &mut [f32], a: &[f32], b: &[f32], c: &[f32]) {
+        assert!(available());
+        // SAFETY: the CPU supports AVX2 and FMA, checked above
+        unsafe { multiply_add_fma(out, a, b, c) }
+    }
+}
+
+/// The fastest single-threaded version this CPU runs
+fn simd() -> (&'static str, MultiplyAdd) {
+    #[cfg(target_arch = "x86_64")]
+    if avx2::available() {
+        return ("AVX2 + FMA", avx2::multiply_add);
+    }
+    ("auto-vectorized", vectorized)
+}
+
+/// The SIMD version on every core, a chunk per task
+fn threaded(out: &mut [f32], a: &[f32], b: &[f32], c: &[f32]) {
+    let multiply_add = simd().1;
+    out.par_chunks_mut(CHUNK).enumerate().for_each(|(index, out)| {
+        let start = index * CHUNK;
+        let end = start + out.len();
+        multiply_add(out, &a[start..end], &b[start..end], &c[start..end]);
+    });
+}
+
+fn cpu_methods() -> Vec<(String, MultiplyAdd)> {
+    let mut methods = vec![("scalar".to_string(), scalar as MultiplyAdd), ("auto-vectorized".to_string(), vectorized)];
+    #[cfg(target_arch = "x86_64")]
+    if avx2::available() {
+        methods.push(("AVX2 + FMA".to_string(), avx2::multiply_add));
+    }
+    let threads = rayon::current_num_threads();
+    methods.push((format!("{} thread{}", threads, if threads == 1 { "" } else { "s" }), threaded));
+    methods
+}
+
+mod gpu {
+    use std::time::{Duration, Instant};
+
+    use crate::bench::Measurement;
+
+    const WORKGROUP: u32 = 256;
+
+    /// Each invocation handles every `stride`-th element from its own
+    /// index, so a capped number of workgroups still covers any length
+    const SHADER: &str = r#"
+@group(0) @binding(0) var<storage, read> a: array<f32>;
+@group(0) @binding(1) var<storage, read> b: array<f32>;
+@group(0) @binding(2) var<storage, read> c: array<f32>;
+@group(0) @binding(3) var<storage, read_write> out: array<f32>;
+
+@compute @workgroup_size(256)
+fn main(@builtin(global_invocation_id) id: vec3<u32>, @builtin(num_workgroups) groups: vec3<u32>) {
+    let stride = groups.x * 256u;
+    for (var i = id.x; i < arrayLength(&out); i += stride) {
+        out[i] = fma(a[i], b[i], c[i]);
+    }
+}
+"#;
+
+    pub struct Gpu {
+        device: wgpu::Device,
+        queue: wgpu::Queue,
+        pipeline: wgpu::ComputePipeline,
+        /// Adapter name, type and API, e.g. "NVIDIA GeForce RTX 3060 (DiscreteGpu, Vulkan)"
+        pub description: String,
+        /// A software renderer (llvmpipe, WARP, SwiftShader) running on the CPU
+        pub software: bool,
+        /// The longest vector one storage binding can hold
+        pub max_elements: usize,
+        max_groups: u32,
+    }
+
+    /// Where one offloaded multiply-add spends its time
+    pub struct Run {
+        /// The three inputs, from our memory into buffers on the GPU
+        pub upload: Duration,
+        /// Median of the dispatches, inputs already in place
+        pub kernel: Measurement,
+        /// The result, copied to a mappable buffer and read back
+        pub download: Duration,
+        pub out: Vec<f32>,
+    }
+
+    impl Run {
+        pub fn total(&self) -> Duration {
+            self.upload + self.kernel.median() + self.download
+        }
+    }
+
+    fn as_bytes(values: &[f32]) -> &[u8] {
+        // SAFETY: f32 has no padding and any byte is aligned, so the floats'
+        // memory is a valid [u8] of four times the length
+        unsafe { std::slice::from_raw_parts(values.as_ptr().cast::<u8>(), std::mem::size_of_val(values)) }
+    }
+
+    impl Gpu {
+        /// The system's preferred high-performance adapter, or `None` if
+        /// there is no GPU (or driver) wgpu can use
+        pub fn new() -> Option<Gpu> {
+            let instance = wgpu::Instance::new(wgpu::InstanceDescriptor::new_without_display_handle());
+            let options = wgpu::RequestAdapterOptions { power_preference: wgpu::PowerPreference::HighPerformance, ..Default::default() };
+            let adapter = pollster::block_on(instance.request_adapter(&options)).ok()?;
+            let limits = adapter.limits();
+            let descriptor = wgpu::DeviceDescriptor { label: Some("gpu-demo"), required_limits: limits.clone(), ..Default::default() };
+            let (device, queue) = pollster::block_on(adapter.request_device(&descriptor)).ok()?;
+            let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: Some("multiply-add"),
+                source: wgpu::ShaderSource::Wgsl(SHADER.into()),
+            });
+            let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                label: Some("multiply-add"),
+                layout: None,
+                module: &module,
+                entry_point: Some("main"),
+                compilation_options: Default::default(),
+                cache: None,
+            });
+            let info = adapter.get_info();
+            Some(Gpu {
+                device,
+                queue,
+                pipeline,
+                description: format!("{} ({:?}, {:?})", info.name, info.device_type, info.backend),
+                software: info.device_type == wgpu::DeviceType::Cpu,
+                max_elements: (limits.max_storage_buffer_binding_size.min(limits.max_buffer_size) / 4) as usize,
+                max_groups: limits.max_compute_workgroups_per_dimension,
+            })
+        }
+
+        fn wait(&self) {
+            self.device.poll(wgpu::PollType::wait_indefinitely()).expect("waiting for the GPU");
+        }
+
+        fn buffer(&self, label: &str, bytes: u64, usage: wgpu::BufferUsages) -> wgpu::Buffer {
+            self.device.create_buffer(&wgpu::BufferDescriptor { label: Some(label), size: bytes, usage, mapped_at_creation: false })
+        }
+
+        /// Upload `a`, `b` and `c`, run the kernel `kernels` times, and
+        /// read the result back, timing each stage
+        pub fn multiply_add(&self, a: &[f32], b: &[f32], c: &[f32], kernels: usize) -> Run {
+            let bytes = std::mem::size_of_val(a) as u64;
+            let input = wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST;
+            let inputs = [self.buffer("a", bytes, input), self.buffer("b", bytes, input), self.buffer("c", bytes, input)];
+            let out = self.buffer("out", bytes, wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC);
+            let staging = self.buffer("staging", bytes, wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST);
+            let entries: Vec<_> = inputs.iter().chain([&out]).enumerate()
+                .map(|(binding, buffer)| wgpu::BindGroupEntry { binding: binding as u32, resource: buffer.as_entire_binding() })
+                .collect();
+            let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("multiply-add"),
+                layout: &self.pipeline.get_bind_group_layout(0),
+                entries: &entries,
+            });
+
+            let start = Instant::now();
+            for (buffer, values) in inputs.iter().zip([a, b, c]) {
+                self.queue.write_buffer(buffer, 0, as_bytes(values));
+            }
+            self.queue.submit(std::iter::empty());
+            self.wait();
+            let upload = start.elapsed();
+
+            let groups = (a.len() as u32).div_ceil(WORKGROUP).clamp(1, self.max_groups);
+            let kernel = Measurement::from_samples((0..kernels).map(|_| {
+                let mut encoder = self.device.create_command_encoder(&Default::default());
+                {
+                    let mut pass = encoder.begin_compute_pass(&Default::default());
+                    pass.set_pipeline(&self.pipeline);
+                    pass.set_bind_group(0, &bind_group, &[]);
+                    pass.dispatch_workgroups(groups, 1, 1);
+                }
+                let commands = encoder.finish();
+                let start = Instant::now();
+                self.queue.submit([commands]);
+                self.wait();
+                start.elapsed()
+            }).collect());
+
+            let start = Instant::now();
+            let mut encoder = self.device.create_command_encoder(&Default::default());
+            encoder.copy_buffer_to_buffer(&out, 0, &staging, 0, bytes);
+            self.queue.submit([encoder.finish()]);
+            staging.map_async(wgpu::MapMode::Read, .., |result| result.expect("mapping the result for reading"));
+            self.wait();
+            let result = staging.get_mapped_range(..).chunks_exact(4)
+                .map(|bytes| f32::from_ne_bytes(bytes.try_into().unwrap()))
+                .collect();
+            staging.unmap();
+            Run { upload, kernel, download: start.elapsed(), out: result }
+        }
+    }
+}
+
+/// Multiples of 1/256 below 1: every product and sum is exact in an f32,
+/// so all the versions, fused or not, must agree to the bit
+fn inputs(n: usize, config: &Config) -> [Vec<f32>; 3] {
+    let mut rng = config.rng();
+    let mut vector = || (0..n).map(|_| rng.below(256) as f32 / 256.0).collect();
+    [vector(), vector(), vector()]
+}
+
+fn time_cpu(multiply_add: MultiplyAdd, a: &[f32], b: &[f32], c: &[f32], runs: usize) -> Measurement {
+    let mut out = vec![0.0; a.len()];
+    measure(runs, || {
+        multiply_add(&mut out, a, b, c);
+        black_box(out[out.len() / 2])
+    })
+}
+
+/// 64 Ki elements, then ×4 up to `max`
+fn sizes(max: usize) -> Vec<usize> {
+    std::iter::successors(Some(64 * 1024usize), |n| Some(n * 4)).take_while(|&n| n <= max).collect()
+}
+
+fn runs_for(n: usize) -> usize {
+    (16 * 1024 * 1024 / n).clamp(3, 15)
+}
+
+/// Microseconds, or milliseconds from 10 ms up
+fn format_time(duration: Duration) -> String {
+    let micros = duration.as_secs_f64() * 1e6;
+    if micros < 10_000.0 {
+        format!("{:.0} µs", micros)
+    } else {
+        format!("{:.1} ms", micros / 1000.0)
+    }
+}
+
+fn demonstrate_contenders(gpu: Option<&gpu::Gpu>) {
+    output::heading("🏁 The Contenders");
+    outln!("out[i] = a[i] × b[i] + c[i] over f32 vectors: two floating-point operations for");
+    outln!("every {} bytes read or written, so the CPU versions are limited by memory more", BYTES_PER_ELEMENT);
+    outln!("than arithmetic once the vectors outgrow the caches.");
+    for (name, _) in cpu_methods() {
+        outln!("  • CPU: {}", name);
+    }
+    match gpu {
+        Some(gpu) => {
+            outln!("  • GPU: {}, a WGSL compute shader in workgroups of 256", gpu.description);
+            if gpu.software {
+                outln!("    This adapter is a software renderer: the \"GPU\" is this CPU running the");
+                outln!("    shader, so its columns show the API's overheads, not a GPU's speed.");
+            }
+        }
+        None => {
+            outln!("  • GPU: no adapter found. wgpu needs a Vulkan, Metal, DX12 or OpenGL driver;");
+            outln!("    on this machine only the CPU columns can be measured.");
+        }
+    }
+    outln!();
+}
+
+/// Times per size: the CPU methods' medians, then the GPU run if there is one
+type Timings = Vec<(usize, Vec<Duration>, Option<gpu::Run>)>;
+
+fn demonstrate_sweep(max: usize, gpu: Option<&gpu::Gpu>, config: &Config, report: &mut DemoReport) -> Timings {
+    output::heading("⏱️  Time per Multiply-Add");
+    let methods = cpu_methods();
+    let mut header = vec!["elements".to_string(), "moved".to_string()];
+    header.extend(methods.iter().map(|(name, _)| name.clone()));
+    header.extend(["GPU kernel", "upload", "download", "GPU total"].map(String::from));
+    let mut table = Table::new(header);
+    let mut timings = Vec::new();
+    for n in sizes(max) {
+        let [a, b, c] = inputs(n, config);
+        let runs = runs_for(n);
+        let cpu: Vec<Duration> = methods.iter().map(|(name, multiply_add)| {
+            let time = time_cpu(*multiply_add, &a, &b, &c, runs);
+            detailln!("  {:>9} {:<18} {}", n, name, time);
+            report.record(name.as_str(), time.median().as_secs_f64() * 1e6, "µs").param("elements", n);
+            time.median()
+        }).collect();
+        let run = gpu.filter(|gpu| n <= gpu.max_elements).map(|gpu| gpu.multiply_add(&a, &b, &c, runs));
+        let mut times: Vec<f64> = cpu.iter().map(Duration::as_secs_f64).collect();
+        if let Some(run) = &run {
+            times.push(run.total().as_secs_f64());
+            report.record("GPU kernel", run.kernel.median().as_secs_f64() * 1e6, "µs").param("elements", n);
+            report.record("GPU total", run.total().as_secs_f64() * 1e6, "µs").param("elements", n);
+        }
+        let ranked = output::rank(&times, false, |seconds| format_time(Duration::from_secs_f64(seconds)));
+        let mut row = vec![format_si(n as f64), format!("{}B", format_bytes((n * BYTES_PER_ELEMENT) as f64))];
+        row.extend(ranked[..cpu.len()].iter().cloned());
+        match &run {
+            Some(run) => row.extend([format_time(run.kernel.median()), format_time(run.upload), format_time(run.download), ranked[cpu.len()].clone()]),
+            None => row.extend(["—"; 4].map(String::from)),
+        }
+        table.row(row);
+        timings.push((n, cpu, run));
+    }
+    outln!("{}", table);
+    outln!("The CPU columns and \"GPU total\" are ranked together: each is the time to get");
+    outln!("out[] into this process's memory starting from a, b and c in it.\n");
+    timings
+}
+
+fn demonstrate_offload(timings: &Timings) {
+    output::heading("🚚 When Offloading Pays");
+    let methods = cpu_methods();
+    let (n, cpu, run) = &timings[timings.len() - 1];
+    let (best, best_cpu) = cpu.iter().enumerate().min_by_key(|(_, time)| **time).map(|(i, time)| (&methods[i].0, *time)).unwrap();
+    let Some(run) = run else {
+        outln!("Without a GPU the question can only be put in numbers from the CPU side. At {}", format_si(*n as f64));
+        outln!("elements the best CPU version ({}) takes {}. Offloading has to upload", best, format_time(best_cpu));
+        outln!("{}B and download {}B in that time, before the kernel does any", format_bytes((n * 12) as f64), format_bytes((n * 4) as f64));
+        outln!("work: a PCIe 4.0 x16 link moves about 25 GB/s each way, so the copies alone");
+        outln!("would take {}. For a kernel this light the bus, not", format_time(Duration::from_secs_f64((n * BYTES_PER_ELEMENT) as f64 / 25e9)));
+        outln!("the arithmetic, sets the price.\n");
+        return;
+    };
+    let kernel = run.kernel.median();
+    let transfer = run.upload + run.download;
+    let moved = (n * BYTES_PER_ELEMENT) as f64;
+    let ratio = best_cpu.as_secs_f64() / kernel.as_secs_f64();
+    let versus = if ratio >= 1.0 { format!("{:.1}x faster", ratio) } else { format!("{:.1}x slower", 1.0 / ratio) };
+    outln!("At {} elements the kernel runs in {}, the best CPU version ({})", format_si(*n as f64), format_time(kernel), best);
+    outln!("in {}: the kernel alone is {}. The upload and download take", format_time(best_cpu), versus);
+    outln!("{} more, moving {}B at {:.1} GB/s, so the offloaded multiply-add as a", format_time(transfer), format_bytes(moved),
+           moved / transfer.as_secs_f64() / 1e9);
+    outln!("whole takes {:.1}x the CPU's time.", run.total().as_secs_f64() / best_cpu.as_secs_f64());
+    if kernel < best_cpu {
+        let kernels = (transfer.as_secs_f64() / (best_cpu - kernel).as_secs_f64()).ceil();
+        outln!("Each kernel saves {} over the CPU, so the transfer is paid back once", format_time(best_cpu - kernel));
+        outln!("about {} kernels run on the data while it stays on the GPU.", kernels);
+    } else {
+        outln!("Here even the kernel alone doesn't beat the CPU: there is nothing to pay the");
+        outln!("transfer back with.");
+    }
+    outln!("Two flops per 16 bytes is as light as kernels get. Offloading pays when the");
+    outln!("data stays on the GPU for a chain of kernels, or when each byte feeds enough");
+    outln!("arithmetic (a matrix multiply does O(n) flops per element) that the transfer");
+    outln!("is small beside the compute.\n");
+}
+
+/// The CPU versions agree to the bit, SIMD beats the scalar loop, and the
+/// GPU, when there is one, computes the same vector
+fn verify() -> Verification {
+    let mut verification = Verification::new();
+    let config = Config::default();
+    let n = 100_003;
+    let [a, b, c] = inputs(n, &config);
+    let mut expected = vec![0.0; n];
+    scalar(&mut expected, &a, &b, &c);
+    let mismatched: Vec<String> = cpu_methods().into_iter().filter_map(|(name, multiply_add)| {
+        let mut out = vec![0.0; n];
+        multiply_add(&mut out, &a, &b, &c);
+        (out != expected).then_some(name)
+    }).collect();
+    verification.check("every CPU version computes the same 100003 results as the scalar loop", mismatched.is_empty(),
+                       || mismatched.join(", "));
+
+    let (name, simd) = simd();
+    if cfg!(debug_assertions) {
+        verification.skip("SIMD beats the scalar loop at 64Ki elements", "timings of unoptimized code");
+    } else {
+        let n = 64 * 1024;
+        let scalar_time = time_cpu(scalar, &a[..n], &b[..n], &c[..n], 15);
+        let simd_time = time_cpu(simd, &a[..n], &b[..n], &c[..n], 15);
+        verification.faster(format!("{} beats the scalar loop at 64Ki elements", name), &simd_time, &scalar_time, 1.5);
+    }
+
+    match gpu::Gpu::new() {
+        Some(gpu) => {
+            let run = gpu.multiply_add(&a, &b, &c, 1);
+            verification.check("the compute shader computes the same 100003 results as the CPU", run.out == expected, || {
+                let wrong = run.out.iter().zip(&expected).filter(|(gpu, cpu)| gpu != cpu).count();
+                format!("{} differ", wrong)
+            });
+        }
+        None => {
+            verification.skip("the compute shader computes the same 100003 results as the CPU", "no GPU adapter on this machine");
+        }
+    }
+    verification
+}
+
+const QUIZ: &[Question] = &[
+    Question::new(
+        "The GPU kernel runs 10x faster than the CPU, but the whole offload is slower. Why?",
+        &[
+            "The kernel time is measured wrong",
+            "Copying the inputs to the GPU and the result back takes longer than the CPU takes to compute it",
+            "GPUs round floating-point differently",
+        ],
+        1,
+        "A multiply-add does 2 flops per 16 bytes; the bus moves those bytes slower than the CPU computes on them.",
+    ),
+    Question::new(
+        "When does offloading a computation to the GPU pay off?",
+        &[
+            "Whenever the data is large",
+            "When the data stays on the GPU for many kernels, or each byte feeds a lot of arithmetic",
+            "Only for integer work",
+        ],
+        1,
+        "Transfers cost per byte; they're worth it when enough compute is done per byte moved.",
+    ),
+    Question::new(
+        "Why does the multi-threaded CPU version stop scaling on large vectors?",
+        &[
+            "Rayon has too much overhead",
+            "The cores share the memory bandwidth, and the loop needs 16 bytes per 2 flops",
+            "The threads contend for a lock",
+        ],
+        1,
+        "Beyond the caches every core waits on the same memory controllers.",
+    ),
+];
+
+#[derive(Demo)]
+#[demo(name = "gpu-demo", description = "A vector multiply-add on the CPU (scalar, SIMD, threads) and as a wgpu compute shader",
+       quiz = QUIZ, verify = verify)]
+pub struct GpuDemo;
+
+impl GpuDemo {
+    fn demonstrate(&self, config: &Config) -> DemoReport {
+        output::title("🎮 GPU Demo");
+        outln!("The same vector multiply-add on the CPU and on the GPU, and what the trip to the GPU costs.\n");
+
+        let mut report = DemoReport::default();
+        let gpu = gpu::Gpu::new();
+        demonstrate_contenders(gpu.as_ref());
+        let max = config.size.unwrap_or(MAX_ELEMENTS).max(64 * 1024);
+        let timings = demonstrate_sweep(max, gpu.as_ref(), config, &mut report);
+        demonstrate_offload(&timings);
+
+        output::takeaways();
+        outln!("• A GPU kernel's speed is only part of the cost: the data has to get there and back");
+        outln!("• Light kernels (few flops per byte) are bound by memory and the bus, not arithmetic");
+        outln!("• Keep data on the GPU across many kernels to amortize the transfers");
+        outln!("• On the CPU, SIMD and threads help until memory bandwidth runs out");
+        report
+    }
+}
@@ -0,0 +1,261 @@
+//! Hardware Fundamentals Demo
+//!
+//! This demo explores CPU registers, cache systems, and hardware threads.
+//! Run with: cargo run --bin hardware-fundamentals
+
+use std::hint::black_box;
+
+use crate::bench::measure;
+use crate::demo::{Config, Demo, DemoReport};
+use crate::output::Style;
+use crate::plot::{format_bytes, LinePlot};
+use crate::quiz::Question;
+use crate::rng::Rng;
+use crate::verify::Verification;
+use crate::{detailln, outln, output};
+
+/// Dependent loads per run of the latency sweep
+const CHASE_LOADS: usize = 1_000_000;
+
+fn demonstrate_registers(report: &mut DemoReport) {
+    output::heading("🖥️  CPU Registers & Memory Access");
+
+    // Demonstrate register usage vs memory access
+    // This loop uses registers heavily
+    let register_loop = || {
+        let mut register_var = 0u64;
+        for i in 0..black_box(1_000_000) {
+            register_var += i;
+        }
+        register_var
+    };
+    let register_var = register_loop();
+    let register_time = measure(10, register_loop);
+    outln!("Register-heavy loop: {} (sum: {})", register_time, register_var);
+
+    // This loop accesses memory
+    let mut memory_array = [0u64; 1_000_000];
+    let memory_time = measure(10, || {
+        for i in 0..1_000_000 {
+            memory_array[i % 1000] += i as u64;
+        }
+        black_box(&memory_array);
+    });
+    outln!("Memory access loop: {}", memory_time);
+    outln!("Memory is ~{:.0}x slower than registers (medians)\n",
+             Style::Slower.paint(memory_time.median().as_secs_f64() / register_time.median().as_secs_f64()));
+    report.record_time("register loop", register_time.median());
+    report.record_time("memory loop", memory_time.median());
+}
+
+fn demonstrate_cache_lines(config: &Config, report: &mut DemoReport) {
+    output::heading("📏 Cache Line Size Demonstration");
+
+    const ARRAY_SIZE: usize = 64 * 1024 * 1024; // 64M u64s = 512MB
+    let mut array: Vec<u64> = vec![0; ARRAY_SIZE];
+
+    // Sequential access (good for cache)
+    let sequential_time = measure(5, || {
+        for i in (0..ARRAY_SIZE).step_by(8) {  // Every 8th element (cache line friendly)
+            array[i] += 1;
+        }
+    });
+
+    // Random access (bad for cache): the same elements, one per cache line,
+    // visited in shuffled order. The order itself is read sequentially.
+    let mut lines: Vec<u32> = (0..(ARRAY_SIZE / 8) as u32).collect();
+    config.rng().shuffle(&mut lines);
+    let random_time = measure(5, || {
+        for &line in &lines {
+            array[line as usize * 8] += 1;
+        }
+    });
+
+    outln!("Sequential access: {}", sequential_time);
+    outln!("Random access (the same lines shuffled, seed {}): {}", config.seed(), random_time);
+    outln!("Random access is ~{:.1}x slower (medians)\n",
+             Style::Slower.paint(random_time.median().as_secs_f64() / sequential_time.median().as_secs_f64()));
+    report.record_time("sequential access", sequential_time.median()).param("elements", ARRAY_SIZE);
+    report.record_time("random access", random_time.median()).param("elements", ARRAY_SIZE);
+}
+
+/// One node per cache line, so every hop of the chase is a different line
+#[repr(align(64))]
+struct Node {
+    next: usize,
+}
+
+/// Link `nodes` nodes into one random cycle (Sattolo's algorithm), so each
+/// load's address depends on the previous load and can't be prefetched
+fn random_cycle(nodes: usize, rng: &mut Rng) -> Vec<Node> {
+    let mut order: Vec<usize> = (0..nodes).collect();
+    for i in (1..nodes).rev() {
+        // Unlike Fisher-Yates, never swap with itself: that's what makes one cycle
+        order.swap(i, rng.below(i));
+    }
+    let mut cycle: Vec<Node> = (0..nodes).map(|_| Node { next: 0 }).collect();
+    for pair in order.windows(2) {
+        cycle[pair[0]].next = pair[1];
+    }
+    cycle[order[nodes - 1]].next = order[0];
+    cycle
+}
+
+/// Follow `loads` links around `cycle` from node 0; where it ends up
+fn chase(cycle: &[Node], loads: usize) -> usize {
+    let mut at = 0;
+    for _ in 0..loads {
+        at = cycle[at].next;
+    }
+    at
+}
+
+fn demonstrate_latency_vs_size(config: &Config, report: &mut DemoReport) {
+    output::heading("📶 Load Latency vs Working-Set Size");
+    outln!("Chasing pointers around a random cycle of 64-byte nodes; {} dependent", CHASE_LOADS);
+    outln!("loads per run, median of 3 runs:\n");
+
+    let mut rng = config.rng();
+    let mut curve = Vec::new();
+    let mut bytes = 4 * 1024;
+    while bytes <= 64 * 1024 * 1024 {
+        let cycle = random_cycle(bytes / std::mem::size_of::<Node>(), &mut rng);
+        let time = measure(3, || chase(black_box(&cycle), CHASE_LOADS));
+        let latency = time.median().as_secs_f64() * 1e9 / CHASE_LOADS as f64;
+        outln!("  {:>6}B  {:>7.2} ns/load", format_bytes(bytes as f64), latency);
+        detailln!("           {}", time);
+        report.record("load latency", latency, "ns").param("bytes", bytes);
+        curve.push((bytes as f64, latency));
+        bytes *= 4;
+    }
+    outln!();
+
+    outln!("{}", LinePlot::new("working set", "ns/load")
+        .log_x()
+        .log_y()
+        .format_x(|bytes| format!("{}B", format_bytes(bytes)))
+        .format_y(|ns| format!("{:.1}", ns))
+        .series("latency", curve));
+    outln!("Each plateau is a level of the hierarchy: while the cycle fits in L1 a load");
+    outln!("takes a few cycles; the curve steps up as it outgrows L1, L2 and the last-level");
+    outln!("cache, ending at DRAM latency (often near 100 ns).\n");
+}
+
+fn demonstrate_cpu_threads(report: &mut DemoReport) {
+    output::heading("🧵 Hardware Threads vs Cores");
+
+    outln!("Physical CPU cores: {}", num_cpus::get_physical());
+    outln!("Logical CPU cores: {}", num_cpus::get());
+
+    if num_cpus::get() > num_cpus::get_physical() {
+        outln!("✓ Hyperthreading/SMT detected!");
+        outln!("  Logical cores = {} × physical cores",
+                 num_cpus::get() / num_cpus::get_physical());
+    } else {
+        outln!("✗ No hyperthreading detected");
+    }
+
+    outln!("\nTesting parallel computation...");
+
+    use std::thread;
+    let parallel_sum = || {
+        let handles: Vec<_> = (0..num_cpus::get()).map(|_| {
+            thread::spawn(|| {
+                let mut sum = 0u64;
+                for i in 0..black_box(100_000) {
+                    sum += i;
+                }
+                sum
+            })
+        }).collect();
+
+        let mut total = 0u64;
+        for handle in handles {
+            total += handle.join().unwrap();
+        }
+        total
+    };
+    let total = parallel_sum();
+    let parallel_time = measure(10, parallel_sum);
+    outln!("Parallel computation with {} threads: {}", num_cpus::get(), parallel_time);
+    outln!("Combined sum: {}", total);
+    report.record_time("parallel sum", parallel_time.median()).param("threads", num_cpus::get());
+}
+
+/// The chase visits every node before returning to the start, and a working
+/// set far beyond the caches costs more per load than one that fits in L1
+fn verify() -> Verification {
+    let mut verification = Verification::new();
+    let mut rng = Config::default().rng();
+    let nodes = 1000;
+    let cycle = random_cycle(nodes, &mut rng);
+    let first_return = (1..=nodes).find(|&loads| chase(&cycle, loads) == 0);
+    verification.equal("random_cycle links every node into one cycle", first_return, Some(nodes));
+
+    let loads = CHASE_LOADS / 4;
+    let small = random_cycle(16 * 1024 / std::mem::size_of::<Node>(), &mut rng);
+    let large = random_cycle(64 * 1024 * 1024 / std::mem::size_of::<Node>(), &mut rng);
+    let in_cache = measure(5, || chase(black_box(&small), loads));
+    let in_dram = measure(5, || chase(black_box(&large), loads));
+    verification.faster("a 16 KiB chase beats a 64 MiB one", &in_cache, &in_dram, 1.5);
+    verification
+}
+
+const QUIZ: &[Question] = &[
+    Question::new(
+        "The random-access loop touched as many elements as the sequential one. Why was it slower?",
+        &[
+            "Reading the shuffled order from a second array costs more than the load itself",
+            "Scattered indices land on a new cache line almost every time, so most loads miss and the prefetcher can't help",
+            "Random access runs on a slower core",
+        ],
+        1,
+        "Sequential access uses every byte of each 64-byte line it loads, and the prefetcher fetches the next lines ahead of time.",
+    ),
+    Question::new(
+        "Why could the register-heavy loop finish in nanoseconds while the memory loop took far longer?",
+        &[
+            "The sum lives in a register (LLVM may even fold the loop to a formula); the memory loop loads and stores an array element every iteration",
+            "Registers are bigger than RAM",
+            "The memory loop allocates on each iteration",
+        ],
+        0,
+        "Register operands cost nothing extra to reach; every array access is a load and a store, even when it hits the cache.",
+    ),
+    Question::new(
+        "The demo reports more logical than physical cores. What does that mean?",
+        &[
+            "Some cores are disabled",
+            "The machine is a virtual machine",
+            "SMT (hyperthreading): each core runs two hardware threads that share its execution units and caches",
+        ],
+        2,
+        "Two hardware threads per core hide each other's stalls, but they compete for the same core, so they don't double throughput.",
+    ),
+];
+
+#[derive(Demo)]
+#[demo(name = "hardware-fundamentals", description = "CPU registers, cache systems and hardware threads",
+       quiz = QUIZ, verify = verify)]
+pub struct HardwareFundamentals;
+
+impl HardwareFundamentals {
+    fn demonstrate(&self, config: &Config) -> DemoReport {
+        output::title("🖥️  Hardware Fundamentals Demo");
+        outln!("This demo shows how hardware affects your code performance.\n");
+
+        let mut report = DemoReport::default();
+        demonstrate_registers(&mut report);
+        demonstrate_cache_lines(config, &mut report);
+        demonstrate_latency_vs_size(config, &mut report);
+        demonstrate_cpu_threads(&mut report);
+
+        output::takeaways();
+        outln!("• Registers are ~100x faster than memory");
+        outln!("• Sequential memory access is ~10x faster than random");
+        outln!("• Load latency steps up with each cache level the working set outgrows");
+        outln!("• Hardware threads help with parallel workloads");
+        outln!("• Cache line size (64 bytes) affects data structure performance");
+        report
+    }
+}
@@ -0,0 +1,360 @@
+//! HashDoS Demo
+//!
+//! `HashMap` hashes with SipHash-1-3 under a random key unless told
+//! otherwise, and is slower for it than maps built on FxHash (rustc's
+//! hasher) or FNV. This demo implements both fast hashers from scratch,
+//! measures the three on inserts and lookups of integer and string keys,
+//! then builds keys that all land in the same bucket under FxHash: the
+//! map's inserts degrade from constant time toward O(n), while the same
+//! keys under SipHash spread out as well as random ones do.
+//! Run with: cargo run --release --bin hashdos-demo
+
+use std::collections::hash_map::RandomState;
+use std::collections::{HashMap, HashSet};
+use std::hash::{BuildHasher, BuildHasherDefault, Hash, Hasher};
+use std::hint::black_box;
+
+use crate::bench::{format_rate, measure, Measurement};
+use crate::demo::{Config, Demo, DemoReport};
+use crate::output::Table;
+use crate::quiz::Question;
+use crate::verify::Verification;
+use crate::{outln, output};
+
+/// Keys in each throughput run (`--size`)
+const KEYS: usize = 1 << 20;
+const RUNS: usize = 5;
+/// Largest flood; inserting n colliding keys takes O(n²) probes
+const FLOOD_MAX: usize = 32 * 1024;
+
+/// The hasher rustc used for its own tables (as in rustc-hash 1.x, from
+/// Firefox): each word is xored into the state, which is then multiplied
+/// by a constant. One multiply per word, and no secret.
+#[derive(Default)]
+pub struct FxHasher {
+    hash: u64,
+}
+
+const FX_SEED: u64 = 0x51_7c_c1_b7_27_22_0a_95;
+
+impl FxHasher {
+    fn add(&mut self, word: u64) {
+        self.hash = (self.hash.rotate_left(5) ^ word).wrapping_mul(FX_SEED);
+    }
+}
+
+impl Hasher for FxHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        let mut chunks = bytes.chunks_exact(8);
+        for chunk in &mut chunks {
+            self.add(u64::from_le_bytes(chunk.try_into().unwrap()));
+        }
+        for &byte in chunks.remainder() {
+            self.add(byte as u64);
+        }
+    }
+
+    fn write_u8(&mut self, n: u8) {
+        self.add(n as u64);
+    }
+
+    fn write_u32(&mut self, n: u32) {
+        self.add(n as u64);
+    }
+
+    fn write_u64(&mut self, n: u64) {
+        self.add(n);
+    }
+
+    fn write_usize(&mut self, n: usize) {
+        self.add(n as u64);
+    }
+
+    fn finish(&self) -> u64 {
+        self.hash
+    }
+}
+
+/// 64-bit FNV-1a: for each byte, xor it in, then multiply by the FNV prime.
+/// Simple and well spread for short keys, but a multiply per byte, and, like
+/// FxHash, the same function in every process.
+pub struct FnvHasher(u64);
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+impl Default for FnvHasher {
+    fn default() -> FnvHasher {
+        FnvHasher(FNV_OFFSET)
+    }
+}
+
+impl Hasher for FnvHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 ^ byte as u64).wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+pub type BuildFx = BuildHasherDefault<FxHasher>;
+pub type BuildFnv = BuildHasherDefault<FnvHasher>;
+
+/// Integer keys an attacker can choose: multiples of 2^32. FxHash of one
+/// word is `key × FX_SEED`, and a product's low 32 bits depend only on the
+/// factors' low 32 bits, which here are all zero, so every key hashes to a
+/// value whose low 32 bits are zero. `HashMap` picks the bucket from the
+/// low bits.
+fn colliding_keys(n: usize) -> Vec<u64> {
+    (1..=n as u64).map(|i| i << 32).collect()
+}
+
+fn random_keys(n: usize, config: &Config) -> Vec<u64> {
+    let mut rng = config.rng();
+    (0..n).map(|_| rng.next_u64()).collect()
+}
+
+/// Keys as a web service might see them: short ids in strings
+fn string_keys(n: usize, config: &Config) -> Vec<String> {
+    let mut rng = config.rng();
+    (0..n).map(|_| format!("user-{}", rng.next_u64() % 100_000_000_000)).collect()
+}
+
+/// Insert every key into a fresh map (grown from empty, as it would be
+/// under attack), `runs` times
+fn time_inserts<S: BuildHasher + Default, K: Hash + Eq + Clone>(keys: &[K], runs: usize) -> Measurement {
+    measure(runs, || {
+        let mut map: HashMap<K, u32, S> = HashMap::default();
+        for key in keys {
+            map.insert(key.clone(), 0);
+        }
+        black_box(map.len())
+    })
+}
+
+/// Look every key up, in a shuffled order, in a map that holds them all
+fn time_lookups<S: BuildHasher + Default, K: Hash + Eq + Clone>(keys: &[K], order: &[usize], runs: usize) -> Measurement {
+    let map: HashMap<K, u32, S> = keys.iter().map(|key| (key.clone(), 1)).collect();
+    measure(runs, || {
+        let mut found = 0u32;
+        for &index in order {
+            found += map.get(black_box(&keys[index])).copied().unwrap_or(0);
+        }
+        found
+    })
+}
+
+/// (insert, lookup) in nanoseconds per key, for each hasher
+type Throughput = Vec<(&'static str, f64, f64)>;
+
+fn throughput<K: Hash + Eq + Clone>(keys: &[K], order: &[usize]) -> Throughput {
+    let per_key = |measurement: Measurement| measurement.median().as_secs_f64() * 1e9 / keys.len() as f64;
+    vec![
+        ("SipHash-1-3 (std)", per_key(time_inserts::<RandomState, K>(keys, RUNS)),
+         per_key(time_lookups::<RandomState, K>(keys, order, RUNS))),
+        ("FxHash", per_key(time_inserts::<BuildFx, K>(keys, RUNS)), per_key(time_lookups::<BuildFx, K>(keys, order, RUNS))),
+        ("FNV-1a", per_key(time_inserts::<BuildFnv, K>(keys, RUNS)), per_key(time_lookups::<BuildFnv, K>(keys, order, RUNS))),
+    ]
+}
+
+/// How many distinct buckets `keys` would occupy in a table with one bucket
+/// per key (rounded up to a power of two), taken from the hashes' low bits
+/// as `HashMap` does
+fn buckets_used<S: BuildHasher, K: Hash>(build: &S, keys: &[K]) -> usize {
+    let mask = keys.len().next_power_of_two() as u64 - 1;
+    keys.iter().map(|key| build.hash_one(key) & mask).collect::<HashSet<_>>().len()
+}
+
+fn demonstrate_throughput(keys: usize, config: &Config, report: &mut DemoReport) -> Throughput {
+    output::heading(&format!("⏱️  Throughput: {} Keys", keys));
+    let order = config.rng().permutation(keys);
+    let integers = throughput(&random_keys(keys, config), &order);
+    let string_keys = string_keys(keys, config);
+    let strings = throughput(&string_keys, &order);
+    let spread = [buckets_used(&RandomState::new(), &string_keys), buckets_used(&BuildFx::default(), &string_keys),
+                  buckets_used(&BuildFnv::default(), &string_keys)];
+    let mut table = Table::new(["hasher", "u64 insert", "u64 lookup", "u64 lookups/s", "string insert", "string lookup",
+                                "string buckets"]).left(0);
+    for (((name, insert, lookup), (_, string_insert, string_lookup)), buckets) in integers.iter().zip(&strings).zip(spread) {
+        table.row([name.to_string(), format!("{:.1} ns", insert), format!("{:.1} ns", lookup), format_rate(1e9 / lookup),
+                   format!("{:.1} ns", string_insert), format!("{:.1} ns", string_lookup), buckets.to_string()]);
+        report.record(format!("{} u64 lookup", name), *lookup, "ns/key").param("keys", keys);
+        report.record(format!("{} string lookup", name), *string_lookup, "ns/key").param("keys", keys);
+    }
+    outln!("{}", table);
+    let (sip, fx) = (integers[0].2, integers[1].2);
+    let slots = keys.next_power_of_two() as f64;
+    let expected = slots * (1.0 - (-(keys as f64) / slots).exp());
+    outln!("Per key, including the map's own work (probing, comparing, growing). For a");
+    outln!("u64, FxHash is one multiply against SipHash's rounds of add-rotate-xor over a");
+    outln!("128-bit key: lookups take {:.2}x as long with SipHash here.", sip / fx);
+    outln!("Strings like \"{}\" are another matter. \"string buckets\" counts the", string_keys[0]);
+    outln!("distinct values of each hash's low {} bits, the ones a table of {} slots", slots.log2(), slots);
+    outln!("indexes with; a good hash leaves about {:.0}. FxHash's multiply only carries", expected);
+    outln!("low bits upwards, so its low bits see little besides the low bytes of each");
+    outln!("8-byte word, and with {} buckets for {} keys it probes further than the", spread[1], keys);
+    outln!("cheaper function should. FNV-1a multiplies once per byte: more work, but");
+    outln!("every byte passes through the low bits.\n");
+    integers
+}
+
+/// Nanoseconds per insert of `keys` under each hasher
+fn flood(keys: &[u64]) -> [f64; 2] {
+    let runs = (FLOOD_MAX / keys.len()).clamp(1, 9);
+    let per_key = |measurement: Measurement| measurement.median().as_secs_f64() * 1e9 / keys.len() as f64;
+    [per_key(time_inserts::<BuildFx, u64>(keys, runs)), per_key(time_inserts::<RandomState, u64>(keys, runs))]
+}
+
+fn demonstrate_flood(max: usize, config: &Config, report: &mut DemoReport) -> (f64, f64) {
+    output::heading("🌊 Flooding FxHash");
+    let fx = BuildFx::default();
+    let sip = RandomState::new();
+    let mut table = Table::new(["key", "FxHash", "SipHash (random key)"]).left(0);
+    for key in colliding_keys(4) {
+        table.row([format!("{:#x}", key), format!("{:016x}", fx.hash_one(key)), format!("{:016x}", sip.hash_one(key))]);
+    }
+    outln!("Keys that are multiples of 2^32 (a user id, a timestamp in the high half):");
+    outln!("{}", table);
+    outln!("Every FxHash ends in eight zero hex digits. A HashMap takes the bucket from the");
+    outln!("hash's low bits, so until the table has 2^32 buckets all of these keys want");
+    outln!("the first one, and each insert probes past every key inserted before it.\n");
+
+    let mut table = Table::new(["keys", "FxHash, colliding", "FxHash, random", "SipHash, colliding"]);
+    let mut sizes = Vec::new();
+    let mut n = 1024;
+    while n <= max {
+        sizes.push(n);
+        n *= 2;
+    }
+    let mut last = (0.0, 0.0);
+    for &n in &sizes {
+        let [fx_colliding, sip_colliding] = flood(&colliding_keys(n));
+        let [fx_random, _] = flood(&random_keys(n, config));
+        table.row([n.to_string(), format!("{:.0} ns", fx_colliding), format!("{:.0} ns", fx_random), format!("{:.0} ns", sip_colliding)]);
+        report.record("FxHash colliding insert", fx_colliding, "ns/key").param("keys", n);
+        report.record("SipHash colliding insert", sip_colliding, "ns/key").param("keys", n);
+        last = (fx_colliding, fx_random);
+    }
+    outln!("Nanoseconds per insert, growing the map from empty:");
+    outln!("{}", table);
+    let (colliding, random) = last;
+    outln!("Doubling the colliding keys doubles the time per insert: n inserts cost O(n²)");
+    outln!("in total. At {} keys each one takes {:.0}x as long as a random key's, and a", sizes[sizes.len() - 1], colliding / random);
+    outln!("server that put request parameters in such a map would spend its CPU probing.");
+    outln!("SipHash sees the same keys as noise, because the attacker doesn't know the");
+    outln!("random 128-bit key that `RandomState` draws for each map.\n");
+    last
+}
+
+fn demonstrate_why(throughput: &Throughput, (colliding, random): (f64, f64)) {
+    output::heading("🛡️  Why std Pays for SipHash");
+    outln!("FxHash and FNV are fixed, public functions: anyone can compute them offline");
+    outln!("and search for keys that collide, in the low bits or in all 64. That is the");
+    outln!("2011 \"HashDoS\" attack on PHP, Java, Python and Ruby web servers. SipHash is a");
+    outln!("keyed function designed so that, without the key, its outputs can't be");
+    outln!("predicted or steered, so the best an attacker can do is guess.");
+    outln!("Here the default costs {:.1} ns more per u64 lookup than FxHash, and a flooded", throughput[0].2 - throughput[1].2);
+    outln!("FxHash map costs {:.0} ns more per insert than a healthy one. std chose to pay", colliding - random);
+    outln!("the first everywhere so nobody pays the second by surprise. Where every key");
+    outln!("is trusted (a compiler's symbol table, integer ids you assigned), swap in a");
+    outln!("faster hasher with `HashMap::with_hasher`. rustc-hash 2 also rotates its");
+    outln!("result so the low bits mix, but it is still unkeyed.\n");
+}
+
+/// The hashers match their reference values, the colliding keys collide, and
+/// they slow down an FxHash map but not a SipHash one
+fn verify() -> Verification {
+    let mut verification = Verification::new();
+    let mut fnv = FnvHasher::default();
+    fnv.write(b"a");
+    verification.equal("FNV-1a of \"a\" is the published 0xaf63dc4c8601ec8c", fnv.finish(), 0xaf63_dc4c_8601_ec8c);
+    let fx = BuildFx::default();
+    verification.equal("FxHash of a u64 is the key times the seed", fx.hash_one(12345u64), 12345u64.wrapping_mul(FX_SEED));
+    let keys = colliding_keys(1000);
+    let hashes: Vec<u64> = keys.iter().map(|key| fx.hash_one(key)).collect();
+    let distinct: HashSet<_> = hashes.iter().collect();
+    verification.check("1000 colliding keys have distinct FxHashes whose low 32 bits are all zero",
+                       distinct.len() == 1000 && hashes.iter().all(|hash| hash & 0xffff_ffff == 0),
+                       || format!("{} distinct", distinct.len()));
+
+    let n = 8192;
+    let colliding = time_inserts::<BuildFx, u64>(&keys_for(n, true), 5);
+    let random = time_inserts::<BuildFx, u64>(&keys_for(n, false), 5);
+    verification.faster("8192 colliding keys take FxHash over 10x as long to insert as random ones", &random, &colliding, 10.0);
+    let sip_colliding = time_inserts::<RandomState, u64>(&keys_for(n, true), 5).median().as_secs_f64();
+    let sip_random = time_inserts::<RandomState, u64>(&keys_for(n, false), 5).median().as_secs_f64();
+    verification.check("the same keys insert into a SipHash map within 2x of random ones", sip_colliding < sip_random * 2.0,
+                       || format!("{:.2}x", sip_colliding / sip_random));
+    verification
+}
+
+fn keys_for(n: usize, colliding: bool) -> Vec<u64> {
+    if colliding {
+        colliding_keys(n)
+    } else {
+        random_keys(n, &Config::default())
+    }
+}
+
+const QUIZ: &[Question] = &[
+    Question::new(
+        "Why are all multiples of 2^32 a problem for FxHash in a HashMap?",
+        &[
+            "Their hashes are all equal",
+            "Their hashes share the low 32 bits, and the map picks the bucket from the low bits",
+            "They overflow the multiply",
+        ],
+        1,
+        "The hashes differ, but only in bits the map doesn't use for the bucket until it has 2^32 of them.",
+    ),
+    Question::new(
+        "What makes SipHash resistant to HashDoS?",
+        &[
+            "It produces 128-bit hashes",
+            "It is keyed with a random secret, so an attacker can't compute which keys collide",
+            "It is a cryptographic hash like SHA-256",
+        ],
+        1,
+        "RandomState draws a random key; without it, the outputs can't be predicted.",
+    ),
+    Question::new(
+        "When is it reasonable to replace std's default hasher?",
+        &[
+            "Never",
+            "When the keys can't be chosen by an attacker, and hashing shows up in a profile",
+            "Whenever keys are strings",
+        ],
+        1,
+        "Compilers and other programs whose keys are trusted commonly use FxHash.",
+    ),
+];
+
+#[derive(Demo)]
+#[demo(name = "hashdos-demo", description = "SipHash vs FxHash vs FNV, and a HashMap flooded with colliding keys",
+       quiz = QUIZ, verify = verify)]
+pub struct HashDosDemo;
+
+impl HashDosDemo {
+    fn demonstrate(&self, config: &Config) -> DemoReport {
+        output::title("🌊 HashDoS Demo");
+        outln!("The price of std's default hasher, and what it buys.\n");
+
+        let mut report = DemoReport::default();
+        let keys = config.size.unwrap_or(KEYS).max(1024);
+        let throughput = demonstrate_throughput(keys, config, &mut report);
+        let flooded = demonstrate_flood(keys.min(FLOOD_MAX), config, &mut report);
+        demonstrate_why(&throughput, flooded);
+
+        output::takeaways();
+        outln!("• A HashMap is O(1) only while keys spread over the buckets");
+        outln!("• Fast unkeyed hashes let anyone compute colliding keys offline");
+        outln!("• Colliding keys turn n inserts into O(n²) work: a denial of service");
+        outln!("• std's SipHash with a random key costs a few ns per operation to rule that out");
+        outln!("• Choose FxHash or FNV only for keys no attacker controls");
+        report
+    }
+}
@@ -0,0 +1,576 @@
+//! HTTP Server Demo
+//!
+//! Enough HTTP/1.1 to serve a page: a request line, headers, an optional
+//! body by `Content-Length`, keep-alive and pipelining. The same protocol
+//! code is served two ways: a thread per connection, where each thread
+//! blocks in `read`, and (on Linux) one thread running an epoll event loop
+//! over non-blocking sockets. A bundled load generator opens keep-alive
+//! connections and reports requests per second and tail latency for each.
+//! Run with: cargo run --release --bin http-server-demo
+
+use std::io::{self, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::bench::Measurement;
+use crate::demo::{Config, Demo, DemoReport};
+use crate::output::Table;
+use crate::quiz::Question;
+use crate::verify::Verification;
+use crate::{outln, output};
+
+/// Requests per load level, unless the config sets `iterations`
+const REQUESTS: usize = 20_000;
+
+/// Concurrent connections per load level, unless the config sets `threads`
+const CONNECTIONS: [usize; 3] = [1, 16, 128];
+
+/// A request head longer than this is refused
+const MAX_HEAD: usize = 8 * 1024;
+
+const REQUEST: &[u8] = b"GET / HTTP/1.1\r\nHost: localhost\r\n\r\n";
+const BODY: &str = "Hello from a hand-written HTTP server\n";
+
+// ---- The protocol ----
+
+/// What the start of a connection's buffer holds
+#[derive(Debug, PartialEq)]
+enum Parsed<'a> {
+    /// Not a whole request yet: read more
+    Incomplete,
+    Request {
+        method: &'a str,
+        path: &'a str,
+        keep_alive: bool,
+        /// Bytes of head and body, to drop from the buffer
+        len: usize,
+    },
+    Bad,
+}
+
+fn parse(buf: &[u8]) -> Parsed<'_> {
+    let Some(head_end) = buf.windows(4).position(|window| window == b"\r\n\r\n") else {
+        return if buf.len() > MAX_HEAD { Parsed::Bad } else { Parsed::Incomplete };
+    };
+    let Ok(head) = std::str::from_utf8(&buf[..head_end]) else { return Parsed::Bad };
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next().unwrap_or_default().split(' ');
+    let (Some(method), Some(path), Some(version), None) =
+        (request_line.next(), request_line.next(), request_line.next(), request_line.next())
+    else {
+        return Parsed::Bad;
+    };
+    // HTTP/1.1 keeps the connection open unless told otherwise; 1.0 closes it
+    let mut keep_alive = match version {
+        "HTTP/1.1" => true,
+        "HTTP/1.0" => false,
+        _ => return Parsed::Bad,
+    };
+    let mut content_length = 0;
+    for line in lines {
+        let Some((name, value)) = line.split_once(':') else { return Parsed::Bad };
+        let value = value.trim();
+        if name.eq_ignore_ascii_case("content-length") {
+            let Ok(length) = value.parse() else { return Parsed::Bad };
+            content_length = length;
+        } else if name.eq_ignore_ascii_case("connection") {
+            keep_alive = value.eq_ignore_ascii_case("keep-alive");
+        }
+    }
+    let len = head_end + 4 + content_length;
+    if buf.len() < len {
+        return Parsed::Incomplete;
+    }
+    Parsed::Request { method, path, keep_alive, len }
+}
+
+/// Answer every whole request at the start of `input` into `output` and
+/// drop it from `input`; returns false once the connection should close
+fn handle(input: &mut Vec<u8>, output: &mut Vec<u8>) -> bool {
+    loop {
+        let (status, body, keep_alive, len) = match parse(input) {
+            Parsed::Incomplete => return true,
+            Parsed::Request { method: "GET", path: "/", keep_alive, len } => ("200 OK", BODY, keep_alive, len),
+            Parsed::Request { method: "GET", keep_alive, len, .. } => ("404 Not Found", "Not found\n", keep_alive, len),
+            Parsed::Request { keep_alive, len, .. } => ("405 Method Not Allowed", "GET only\n", keep_alive, len),
+            Parsed::Bad => ("400 Bad Request", "Bad request\n", false, input.len()),
+        };
+        output.extend_from_slice(format!(
+            "HTTP/1.1 {}\r\nContent-Type: text/plain\r\nContent-Length: {}\r\n{}\r\n{}",
+            status, body.len(), if keep_alive { "" } else { "Connection: close\r\n" }, body,
+        ).as_bytes());
+        input.drain(..len);
+        if !keep_alive {
+            return false;
+        }
+    }
+}
+
+// ---- Thread per connection ----
+
+/// A running server: stop it to get its threads back
+struct Server {
+    addr: SocketAddr,
+    stop: Arc<AtomicBool>,
+    handle: JoinHandle<ServerStats>,
+}
+
+/// What a server did, for the comparison table
+#[derive(Debug, Default, Clone, Copy)]
+struct ServerStats {
+    /// Threads serving connections at once, at most
+    threads: usize,
+    /// `epoll_wait` calls; zero for the threaded server
+    waits: u64,
+}
+
+impl Server {
+    fn stop(self) -> ServerStats {
+        self.stop.store(true, Ordering::SeqCst);
+        let _ = TcpStream::connect(self.addr); // wake a blocking accept so it sees `stop`
+        self.handle.join().unwrap_or_default()
+    }
+}
+
+/// Serve one connection, blocking in `read` until the client sends more
+fn serve_connection(mut stream: TcpStream) {
+    let _ = stream.set_nodelay(true);
+    let mut input = Vec::new();
+    let mut output = Vec::new();
+    let mut chunk = [0u8; 4096];
+    loop {
+        match stream.read(&mut chunk) {
+            Ok(0) | Err(_) => return,
+            Ok(n) => input.extend_from_slice(&chunk[..n]),
+        }
+        let open = handle(&mut input, &mut output);
+        if stream.write_all(&output).is_err() || !open {
+            return;
+        }
+        output.clear();
+    }
+}
+
+fn start_threaded() -> io::Result<Server> {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let addr = listener.local_addr()?;
+    let stop = Arc::new(AtomicBool::new(false));
+    let stopping = Arc::clone(&stop);
+    let handle = thread::spawn(move || {
+        let mut connections: Vec<JoinHandle<()>> = Vec::new();
+        let mut threads = 0;
+        for stream in listener.incoming() {
+            if stopping.load(Ordering::SeqCst) {
+                break;
+            }
+            let Ok(stream) = stream else { continue };
+            connections.retain(|connection| !connection.is_finished());
+            connections.push(thread::spawn(move || serve_connection(stream)));
+            // The connections' threads and this one
+            threads = threads.max(connections.len() + 1);
+        }
+        for connection in connections {
+            let _ = connection.join();
+        }
+        ServerStats { threads, waits: 0 }
+    });
+    Ok(Server { addr, stop, handle })
+}
+
+// ---- An epoll event loop ----
+
+#[cfg(target_os = "linux")]
+mod event_loop {
+    use std::io::{self, Read, Write};
+    use std::net::{TcpListener, TcpStream};
+    use std::os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+
+    use super::{handle, Server, ServerStats};
+
+    const LISTENER: u64 = u64::MAX;
+
+    /// A connection's buffers: what arrived but isn't a whole request yet,
+    /// and responses the socket hasn't taken yet
+    struct Connection {
+        stream: TcpStream,
+        input: Vec<u8>,
+        output: Vec<u8>,
+        /// Registered for EPOLLOUT as well as EPOLLIN
+        writing: bool,
+        closing: bool,
+    }
+
+    fn cvt(result: libc::c_int) -> io::Result<libc::c_int> {
+        if result < 0 { Err(io::Error::last_os_error()) } else { Ok(result) }
+    }
+
+    fn control(epoll: &OwnedFd, op: libc::c_int, fd: RawFd, events: u32, token: u64) -> io::Result<()> {
+        let mut event = libc::epoll_event { events, u64: token };
+        cvt(unsafe { libc::epoll_ctl(epoll.as_raw_fd(), op, fd, &mut event) })?;
+        Ok(())
+    }
+
+    /// Read what's there, answer it, write what the socket will take;
+    /// returns false when the connection is done
+    fn service(connection: &mut Connection) -> bool {
+        let mut chunk = [0u8; 4096];
+        loop {
+            match connection.stream.read(&mut chunk) {
+                Ok(0) => return false,
+                Ok(n) => connection.input.extend_from_slice(&chunk[..n]),
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock => break,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(_) => return false,
+            }
+        }
+        if !connection.closing && !handle(&mut connection.input, &mut connection.output) {
+            connection.closing = true;
+        }
+        while !connection.output.is_empty() {
+            match connection.stream.write(&connection.output) {
+                Ok(n) => {
+                    connection.output.drain(..n);
+                }
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock => break,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(_) => return false,
+            }
+        }
+        !(connection.closing && connection.output.is_empty())
+    }
+
+    pub(super) fn start() -> io::Result<Server> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let addr = listener.local_addr()?;
+        listener.set_nonblocking(true)?;
+        let epoll = unsafe { OwnedFd::from_raw_fd(cvt(libc::epoll_create1(libc::EPOLL_CLOEXEC))?) };
+        control(&epoll, libc::EPOLL_CTL_ADD, listener.as_raw_fd(), libc::EPOLLIN as u32, LISTENER)?;
+        let stop = Arc::new(AtomicBool::new(false));
+        let stopping = Arc::clone(&stop);
+        let handle = thread::spawn(move || {
+            let mut connections: Vec<Option<Connection>> = Vec::new();
+            let mut events = [libc::epoll_event { events: 0, u64: 0 }; 256];
+            let mut waits = 0;
+            while !stopping.load(Ordering::SeqCst) {
+                waits += 1;
+                // A timeout, so the loop notices `stop` without a wakeup fd
+                let count = unsafe { libc::epoll_wait(epoll.as_raw_fd(), events.as_mut_ptr(), events.len() as i32, 50) };
+                for event in &events[..count.max(0) as usize] {
+                    let token = event.u64;
+                    if token == LISTENER {
+                        while let Ok((stream, _)) = listener.accept() {
+                            if stream.set_nonblocking(true).is_err() {
+                                continue;
+                            }
+                            let _ = stream.set_nodelay(true);
+                            let slot = connections.iter().position(Option::is_none).unwrap_or(connections.len());
+                            let fd = stream.as_raw_fd();
+                            if control(&epoll, libc::EPOLL_CTL_ADD, fd, libc::EPOLLIN as u32, slot as u64).is_err() {
+                                continue;
+                            }
+                            let connection =
+                                Connection { stream, input: Vec::new(), output: Vec::new(), writing: false, closing: false };
+                            if slot == connections.len() {
+                                connections.push(Some(connection));
+                            } else {
+                                connections[slot] = Some(connection);
+                            }
+                        }
+                        continue;
+                    }
+                    let Some(connection) = connections.get_mut(token as usize).and_then(Option::as_mut) else { continue };
+                    if !service(connection) {
+                        // Closing the fd removes it from the epoll set
+                        connections[token as usize] = None;
+                        continue;
+                    }
+                    // Ask for EPOLLOUT only while responses are waiting: a
+                    // level-triggered writable socket would fire every time
+                    let writing = !connection.output.is_empty();
+                    if writing != connection.writing {
+                        let events = libc::EPOLLIN as u32 | if writing { libc::EPOLLOUT as u32 } else { 0 };
+                        let _ = control(&epoll, libc::EPOLL_CTL_MOD, connection.stream.as_raw_fd(), events, token);
+                        connection.writing = writing;
+                    }
+                }
+            }
+            ServerStats { threads: 1, waits }
+        });
+        Ok(Server { addr, stop, handle })
+    }
+}
+
+// ---- The load generator ----
+
+/// Read one response from `stream` (with `buf` holding what arrived past
+/// the previous one); returns its status code
+fn read_response(stream: &mut TcpStream, buf: &mut Vec<u8>) -> io::Result<u16> {
+    let mut chunk = [0u8; 4096];
+    loop {
+        if let Some(head_end) = buf.windows(4).position(|window| window == b"\r\n\r\n") {
+            let head = String::from_utf8_lossy(&buf[..head_end]).into_owned();
+            let status = head.split(' ').nth(1).and_then(|code| code.parse().ok());
+            let length: usize = head
+                .lines()
+                .find_map(|line| line.strip_prefix("Content-Length:"))
+                .and_then(|value| value.trim().parse().ok())
+                .unwrap_or(0);
+            let (Some(status), total) = (status, head_end + 4 + length) else {
+                return Err(io::Error::new(io::ErrorKind::InvalidData, "malformed response"));
+            };
+            if buf.len() >= total {
+                buf.drain(..total);
+                return Ok(status);
+            }
+        }
+        match stream.read(&mut chunk)? {
+            0 => return Err(io::ErrorKind::UnexpectedEof.into()),
+            n => buf.extend_from_slice(&chunk[..n]),
+        }
+    }
+}
+
+/// What one load level measured
+struct Load {
+    requests: usize,
+    elapsed: Duration,
+    latency: Measurement,
+    /// Requests not answered with 200 OK; a connection gives up at its
+    /// first failure
+    errors: usize,
+}
+
+/// `requests` GETs spread over `connections` keep-alive connections, each
+/// sending its next request as soon as the last response arrives
+fn generate_load(addr: SocketAddr, connections: usize, requests: usize) -> Load {
+    let per_connection = requests.div_ceil(connections).max(1);
+    let start = Instant::now();
+    let clients: Vec<JoinHandle<Vec<Duration>>> = (0..connections)
+        .map(|_| {
+            thread::spawn(move || {
+                let mut samples = Vec::with_capacity(per_connection);
+                let Ok(mut stream) = TcpStream::connect(addr) else { return samples };
+                let _ = stream.set_nodelay(true);
+                let mut buf = Vec::new();
+                for _ in 0..per_connection {
+                    let sent = Instant::now();
+                    let status = stream.write_all(REQUEST).and_then(|_| read_response(&mut stream, &mut buf));
+                    if status.ok() != Some(200) {
+                        break;
+                    }
+                    samples.push(sent.elapsed());
+                }
+                samples
+            })
+        })
+        .collect();
+    let mut samples: Vec<Duration> = clients.into_iter().flat_map(|client| client.join().unwrap_or_default()).collect();
+    let elapsed = start.elapsed();
+    let requests = samples.len();
+    let errors = per_connection * connections - requests;
+    if samples.is_empty() {
+        samples.push(elapsed);
+    }
+    Load { requests, elapsed, latency: Measurement::from_samples(samples), errors }
+}
+
+// ---- The demo ----
+
+#[derive(Debug, Clone, Copy)]
+enum Model {
+    Threads,
+    #[cfg(target_os = "linux")]
+    EventLoop,
+}
+
+impl Model {
+    const ALL: &[Model] = &[
+        Model::Threads,
+        #[cfg(target_os = "linux")]
+        Model::EventLoop,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            Model::Threads => "thread per connection",
+            #[cfg(target_os = "linux")]
+            Model::EventLoop => "epoll event loop",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Model::Threads => "threads",
+            #[cfg(target_os = "linux")]
+            Model::EventLoop => "epoll",
+        }
+    }
+
+    fn start(self) -> io::Result<Server> {
+        match self {
+            Model::Threads => start_threaded(),
+            #[cfg(target_os = "linux")]
+            Model::EventLoop => event_loop::start(),
+        }
+    }
+}
+
+fn demonstrate_protocol() {
+    output::heading("📜 A Request and Its Response");
+    for line in String::from_utf8_lossy(REQUEST).split("\r\n").filter(|line| !line.is_empty()) {
+        outln!("  > {}", line);
+    }
+    let mut input = REQUEST.to_vec();
+    let mut response = Vec::new();
+    handle(&mut input, &mut response);
+    for line in String::from_utf8_lossy(&response).split("\r\n") {
+        outln!("  < {}", line.trim_end());
+    }
+    outln!("Text lines ending in CRLF, a blank line after the headers, then the body,");
+    outln!("its length announced by Content-Length so the next response can follow on");
+    outln!("the same connection (keep-alive). A client may also send several requests");
+    outln!("before reading any response (pipelining); they're answered in order.\n");
+}
+
+fn demonstrate_load(config: &Config, report: &mut DemoReport) {
+    let requests = config.iterations.unwrap_or(REQUESTS);
+    let levels = config.threads.map_or(CONNECTIONS.to_vec(), |threads| vec![threads]);
+    output::heading(&format!("🔥 {} Requests per Load Level", requests));
+    let mut table = Table::new(["server", "connections", "req/s", "median", "p99", "server threads", "epoll_waits"]);
+    for &model in Model::ALL {
+        for &connections in &levels {
+            let server = match model.start() {
+                Ok(server) => server,
+                Err(err) => {
+                    table.row([model.name().to_string(), format!("failed to start: {}", err)]);
+                    break;
+                }
+            };
+            let load = generate_load(server.addr, connections, requests);
+            let stats = server.stop();
+            let rate = load.requests as f64 / load.elapsed.as_secs_f64();
+            let mut row = vec![
+                model.name().to_string(),
+                connections.to_string(),
+                format!("{:.0}", rate),
+                format!("{:.2?}", load.latency.median()),
+                format!("{:.2?}", load.latency.percentile(99.0)),
+                stats.threads.to_string(),
+                if stats.waits == 0 { "-".to_string() } else { stats.waits.to_string() },
+            ];
+            if load.errors > 0 {
+                row[2] = format!("{} ({} failed)", row[2], load.errors);
+            }
+            table.row(row);
+            report.record("throughput", rate, "req/s").param("model", model.label()).param("connections", connections);
+            report.record_time("p99", load.latency.percentile(99.0))
+                .param("model", model.label())
+                .param("connections", connections);
+        }
+    }
+    outln!("{}", table);
+    outln!("The load generator's clients run on this machine too, so the numbers are");
+    outln!("a comparison, not a benchmark of what either server could do alone.\n");
+}
+
+fn explain() {
+    output::heading("🔍 Two Ways to Wait");
+    outln!("Thread per connection: each connection's thread blocks in read() and the");
+    outln!("kernel wakes it when bytes arrive. Simple, sequential code, but every");
+    outln!("connection costs a thread (a stack, a kernel task) and every request a");
+    outln!("context switch into it; with many connections the scheduler does the work.");
+    outln!("Event loop: one thread asks epoll which sockets are ready, then reads,");
+    outln!("answers and writes each without blocking, keeping each connection's");
+    outln!("half-read request in a buffer. One epoll_wait can return many ready");
+    outln!("sockets, so under load the cost of waiting is shared between requests.");
+    outln!("This is the loop inside nginx, Node.js, and tokio under async/await.\n");
+}
+
+/// The parser handles partial and pipelined requests, and every model
+/// answers every request of a small load
+fn verify() -> Verification {
+    let mut verification = Verification::new();
+    verification.equal("half a request is incomplete", parse(&REQUEST[..20]), Parsed::Incomplete);
+    let mut pipelined = [REQUEST, REQUEST].concat();
+    let mut responses = Vec::new();
+    let open = handle(&mut pipelined, &mut responses);
+    let count = responses.windows(8).filter(|window| window == b"HTTP/1.1").count();
+    verification
+        .equal("two pipelined requests get two responses", (open, count, pipelined.len()), (true, 2, 0))
+        .equal("Connection: close closes",
+               handle(&mut b"GET / HTTP/1.1\r\nConnection: close\r\n\r\n".to_vec(), &mut Vec::new()), false)
+        .equal("HTTP/1.0 closes by default", handle(&mut b"GET / HTTP/1.0\r\n\r\n".to_vec(), &mut Vec::new()), false)
+        .equal("a body is skipped by its Content-Length",
+               parse(b"POST /x HTTP/1.1\r\nContent-Length: 3\r\n\r\nabcGET"),
+               Parsed::Request { method: "POST", path: "/x", keep_alive: true, len: 42 });
+    for &model in Model::ALL {
+        let claim = format!("the {} answers every request", model.name());
+        match model.start() {
+            Ok(server) => {
+                let load = generate_load(server.addr, 8, 400);
+                server.stop();
+                verification.check(claim, load.requests == 400 && load.errors == 0,
+                                   || format!("{} answered, {} failed", load.requests, load.errors));
+            }
+            Err(err) => {
+                verification.check(claim, false, || err.to_string());
+            }
+        }
+    }
+    verification
+}
+
+const QUIZ: &[Question] = &[
+    Question::new(
+        "How does an HTTP/1.1 client know where a keep-alive response ends?",
+        &["The server closes the connection", "The Content-Length header (or chunked encoding)", "A NUL byte"],
+        1,
+        "Without a length the only end marker is closing the connection, which rules out keep-alive.",
+    ),
+    Question::new(
+        "What does a thread-per-connection server spend on 10 000 idle connections?",
+        &["Nothing", "10 000 threads, each with a stack, blocked in read()", "One epoll_wait"],
+        1,
+        "Each blocked thread holds its stack and kernel state even while nothing arrives.",
+    ),
+    Question::new(
+        "Why does an event-loop server keep a buffer per connection?",
+        &[
+            "A non-blocking read may return half a request; the rest arrives in a later event",
+            "To compress responses",
+            "epoll requires it",
+        ],
+        0,
+        "Without a thread to block in, the connection's progress has to be stored as data.",
+    ),
+];
+
+#[derive(Demo)]
+#[demo(name = "http-server-demo", description = "A minimal HTTP/1.1 server: threads vs an epoll event loop",
+       quiz = QUIZ, verify = verify)]
+pub struct HttpServerDemo;
+
+impl HttpServerDemo {
+    fn demonstrate(&self, config: &Config) -> DemoReport {
+        output::title("🕸️  HTTP Server Demo");
+        outln!("A web server from a TCP listener up, served two ways.\n");
+
+        demonstrate_protocol();
+        let mut report = DemoReport::default();
+        demonstrate_load(config, &mut report);
+        explain();
+
+        output::takeaways();
+        outln!("• HTTP/1.1 is text over TCP: a request line, headers, a blank line, a body");
+        outln!("• Content-Length lets responses follow each other on one connection");
+        outln!("• Thread per connection: simple code, one blocked thread per client");
+        outln!("• An event loop: one thread, per-connection buffers, epoll to find work");
+        report
+    }
+}
@@ -0,0 +1,254 @@
+//! Async vs Threads for I/O-Bound Work
+//!
+//! Thousands of concurrent operations that each wait on simulated I/O
+//! latency, run by thread pools of different sizes and by the crate's mini
+//! async runtime, comparing completion time, memory and context switches.
+//! Run with: cargo run --release --bin io-bound-demo
+
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::bench::{context_switches, proc_status_field};
+use crate::demo::{Config, Demo, DemoReport};
+use crate::output::Table;
+use crate::quiz::Question;
+use crate::runtime::{self, time, Runtime};
+use crate::{outln, output};
+
+const OPS: usize = 10_000;
+/// Simulated round trip of one I/O operation (a database query, an RPC...)
+const LATENCY: Duration = Duration::from_millis(20);
+const POOL_SIZES: [usize; 3] = [100, 1_000, OPS];
+
+/// What a child process reports back, one value per field
+struct ChildResult {
+    completed: u64,
+    elapsed_ms: u64,
+    peak_rss_growth_kib: u64,
+    threads: u64,
+    voluntary_switches: u64,
+    involuntary_switches: u64,
+}
+
+/// Print the measurements of a finished run in the format the parent parses
+fn report(completed: usize, start: Instant, rss_before: u64, switches_before: (u64, u64), threads: u64) {
+    let (voluntary, involuntary) = context_switches();
+    println!("RESULT {} {} {} {} {} {}",
+             completed,
+             start.elapsed().as_millis(),
+             proc_status_field("VmHWM:").saturating_sub(rss_before),
+             threads,
+             voluntary - switches_before.0,
+             involuntary - switches_before.1);
+}
+
+/// `pool_size` worker threads take operations off a shared counter and
+/// block in the "I/O" of each one
+fn run_pool_child(pool_size: usize) {
+    let rss_before = proc_status_field("VmRSS:");
+    let switches_before = context_switches();
+    let start = Instant::now();
+
+    let next_op = Arc::new(AtomicUsize::new(0));
+    let completed = Arc::new(AtomicUsize::new(0));
+    let mut workers = Vec::with_capacity(pool_size);
+    for _ in 0..pool_size {
+        let (next_op, completed) = (Arc::clone(&next_op), Arc::clone(&completed));
+        let spawned = thread::Builder::new().spawn(move || {
+            while next_op.fetch_add(1, Ordering::Relaxed) < OPS {
+                thread::sleep(LATENCY); // blocking I/O: the thread sleeps in the kernel
+                completed.fetch_add(1, Ordering::Relaxed);
+            }
+        });
+        match spawned {
+            Ok(handle) => workers.push(handle),
+            Err(err) => {
+                println!("ERROR thread spawn failed after {} threads: {}", workers.len(), err);
+                std::process::exit(1);
+            }
+        }
+    }
+    // Early workers may already be done, so count creations, not live threads
+    let threads = pool_size as u64 + 1;
+    for worker in workers {
+        worker.join().unwrap();
+    }
+
+    report(completed.load(Ordering::Relaxed), start, rss_before, switches_before, threads);
+}
+
+/// One task per operation on a single-threaded runtime; waiting is a timer
+fn run_async_child() {
+    let rss_before = proc_status_field("VmRSS:");
+    let switches_before = context_switches();
+    let start = Instant::now();
+
+    let completed = Arc::new(AtomicUsize::new(0));
+    let mut rt = Runtime::new().expect("failed to create runtime");
+    let threads = rt.block_on(async {
+        for _ in 0..OPS {
+            let completed = Arc::clone(&completed);
+            runtime::spawn(async move {
+                time::sleep(LATENCY).await; // async I/O: the task is parked, the thread moves on
+                completed.fetch_add(1, Ordering::Relaxed);
+            });
+        }
+        let threads = proc_status_field("Threads:");
+        // No join handles in the mini runtime: check back periodically
+        while completed.load(Ordering::Relaxed) < OPS {
+            time::sleep(Duration::from_millis(1)).await;
+        }
+        threads
+    });
+
+    report(completed.load(Ordering::Relaxed), start, rss_before, switches_before, threads);
+    let stats = rt.stats();
+    println!("STATS {} {}", stats.reactor.epoll_waits, stats.timers_fired);
+}
+
+/// Run one model in a fresh process so memory and counters start clean
+fn run_child(args: &[&str]) -> Result<(ChildResult, String), String> {
+    let exe = std::env::current_exe().map_err(|err| err.to_string())?;
+    let output = Command::new(exe).arg("--child").args(args).output().map_err(|err| err.to_string())?;
+    let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+    let Some(line) = stdout.lines().find(|line| line.starts_with("RESULT")) else {
+        let reason = stdout.lines().find(|line| line.starts_with("ERROR")).unwrap_or("child failed");
+        return Err(reason.to_string());
+    };
+    let fields: Vec<u64> = line.split_whitespace().skip(1).filter_map(|v| v.parse().ok()).collect();
+    let result = ChildResult {
+        completed: fields[0],
+        elapsed_ms: fields[1],
+        peak_rss_growth_kib: fields[2],
+        threads: fields[3],
+        voluntary_switches: fields[4],
+        involuntary_switches: fields[5],
+    };
+    Ok((result, stdout))
+}
+
+fn demonstrate_comparison(report: &mut DemoReport) {
+    let title = format!("⏱️  {} Operations × {:?} Latency", OPS, LATENCY);
+    output::heading(&title);
+    outln!("Every operation just waits (like a query to a slow backend). Each model");
+    outln!("runs in its own child process.\n");
+    let table = Table::new(["model", "done in", "ideal", "peak RSS", "threads", "vol. csw", "invol. csw"])
+        .widths([22, 9, 9, 12, 8, 10, 10]);
+    outln!("{}", table.header());
+
+    let mut runs: Vec<(String, Vec<String>, Duration)> = POOL_SIZES
+        .iter()
+        .map(|&size| {
+            let rounds = OPS.div_ceil(size) as u32;
+            (format!("pool of {} threads", size), vec!["pool".to_string(), size.to_string()], LATENCY * rounds)
+        })
+        .collect();
+    runs.push(("async, 1 thread".to_string(), vec!["async".to_string()], LATENCY));
+
+    let mut async_stats = None;
+    for (label, args, ideal) in &runs {
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        match run_child(&args) {
+            Ok((result, stdout)) => {
+                let incomplete = if result.completed == OPS as u64 { "" } else { "  ❌ incomplete" };
+                outln!("{}{}", table.line([label.clone(), format!("{}ms", result.elapsed_ms),
+                                           format!("{}ms", ideal.as_millis()),
+                                           format!("{} KiB", result.peak_rss_growth_kib), result.threads.to_string(),
+                                           result.voluntary_switches.to_string(),
+                                           result.involuntary_switches.to_string()]), incomplete);
+                report.record("completion time", result.elapsed_ms as f64, "ms").param("model", label);
+                report.record("peak RSS growth", result.peak_rss_growth_kib as f64, "KiB").param("model", label);
+                report.record("voluntary switches", result.voluntary_switches as f64, "switches").param("model", label);
+                if let Some(stats) = stdout.lines().find(|line| line.starts_with("STATS")) {
+                    async_stats = Some(stats.to_string());
+                }
+            }
+            Err(reason) => outln!("  {:<22} {}", label, reason),
+        }
+    }
+
+    outln!();
+    if let Some(stats) = async_stats {
+        let fields: Vec<&str> = stats.split_whitespace().skip(1).collect();
+        outln!("The async run served {} timers with {} epoll_wait calls: many", fields[1], fields[0]);
+        outln!("operations complete per wakeup, so context switches stay low.");
+    }
+    outln!("A small pool is cheap but queues work: {} ops / 100 threads = {} rounds", OPS, OPS / 100);
+    outln!("of latency. A pool big enough to hide the latency needs a thread per");
+    outln!("in-flight operation, each with a stack and a kernel task, and every");
+    outln!("blocking call is a context switch. Creating {} threads also takes long", OPS);
+    outln!("enough on its own to dwarf the {:?} the operations actually wait.\n", LATENCY);
+}
+
+fn demonstrate_when_async_wins() {
+    output::heading("⚖️  When Async Wins (and When It Doesn't)");
+    outln!("Async wins when:");
+    outln!("  • operations spend most of their time waiting (network, timers)");
+    outln!("  • concurrency is high: thousands to millions of in-flight operations");
+    outln!("  • memory per operation matters (a task is bytes, a thread is a stack)");
+    outln!("Threads are fine (or better) when:");
+    outln!("  • work is CPU-bound: async adds nothing, use one thread per core");
+    outln!("  • concurrency is low: a few dozen threads cost little and code stays simple");
+    outln!("  • APIs only block (e.g. most file I/O): async needs a thread pool anyway\n");
+}
+
+/// If this process is one of the demo's children (`--child pool <n>` or
+/// `--child async`), do the child's work and return true. The demo re-runs
+/// its own executable for each model, so the binary's `main` must call this
+/// first.
+pub fn run_as_child() -> bool {
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() >= 3 && args[1] == "--child" {
+        match args[2].as_str() {
+            "pool" => run_pool_child(args.get(3).and_then(|n| n.parse().ok()).unwrap_or(100)),
+            _ => run_async_child(),
+        }
+        return true;
+    }
+    false
+}
+
+const QUIZ: &[Question] = &[
+    Question::new(
+        "With blocking I/O, how many threads does it take to keep N operations in flight?",
+        &["One", "N: each blocking call holds its thread for the whole wait", "The number of cores"],
+        1,
+        "That's the cost async avoids: waits don't need threads.",
+    ),
+    Question::new(
+        "What was async's main advantage in the comparison?",
+        &[
+            "Each operation completes faster",
+            "Far less memory and scheduling overhead per in-flight wait",
+            "It uses more cores",
+        ],
+        1,
+        "The operations take as long either way; async just makes waiting cheap.",
+    ),
+];
+
+#[derive(Demo)]
+#[demo(name = "io-bound-demo", description = "Async vs thread pools for I/O-bound work",
+       quiz = QUIZ)]
+pub struct IoBoundDemo;
+
+impl IoBoundDemo {
+    fn demonstrate(&self, _config: &Config) -> DemoReport {
+        output::title("🌐 Async vs Threads: I/O-Bound Work");
+        outln!("When does an event loop beat a thread pool?\n");
+
+        let mut report = DemoReport::default();
+        demonstrate_comparison(&mut report);
+        demonstrate_when_async_wins();
+
+        output::takeaways();
+        outln!("• Blocking I/O ties up a whole thread for the duration of every wait");
+        outln!("• To hide latency with threads you need one per in-flight operation");
+        outln!("• Async keeps thousands of waits in flight on one thread with few context switches");
+        outln!("• The advantage is memory and scheduling overhead, not raw speed per operation");
+        report
+    }
+}
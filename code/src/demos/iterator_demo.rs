@@ -0,0 +1,422 @@
+// Comprehensive demonstration of Rust iterators
+
+use crate::demo::{Config, Demo, DemoReport};
+use crate::quiz::Question;
+use crate::{out, outln, output};
+
+/// Fibonacci numbers F(0), F(1), ... - conceptually infinite. The `next`
+/// value is computed one step ahead, so the iterator stops (instead of
+/// wrapping) after F(93), the last Fibonacci number that fits in a u64.
+struct Fibonacci {
+    current: Option<u64>,
+    next: Option<u64>,
+}
+
+fn fibonacci() -> Fibonacci {
+    Fibonacci { current: Some(0), next: Some(1) }
+}
+
+impl Iterator for Fibonacci {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        let value = self.current?;
+        self.current = self.next;
+        self.next = self.next.and_then(|next| next.checked_add(value));
+        Some(value)
+    }
+}
+
+/// Windows of `size` elements that advance by `step` each time:
+/// step 1 behaves like `slice.windows(size)`, step == size like
+/// `slice.chunks_exact(size)`
+struct ChunkedWindows<'a, T> {
+    slice: &'a [T],
+    size: usize,
+    step: usize,
+}
+
+fn chunked_windows<T>(slice: &[T], size: usize, step: usize) -> ChunkedWindows<'_, T> {
+    assert!(size > 0 && step > 0, "window size and step must be non-zero");
+    ChunkedWindows { slice, size, step }
+}
+
+impl<'a, T> Iterator for ChunkedWindows<'a, T> {
+    type Item = &'a [T];
+
+    fn next(&mut self) -> Option<&'a [T]> {
+        if self.slice.len() < self.size {
+            return None;
+        }
+        let window = &self.slice[..self.size];
+        // Past the end means no more full windows: leave an empty slice
+        self.slice = self.slice.get(self.step..).unwrap_or(&[]);
+        Some(window)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len();
+        (len, Some(len))
+    }
+}
+
+impl<T> ExactSizeIterator for ChunkedWindows<'_, T> {
+    fn len(&self) -> usize {
+        if self.slice.len() < self.size { 0 } else { (self.slice.len() - self.size) / self.step + 1 }
+    }
+}
+
+/// An (unbalanced) binary search tree
+struct Node<T> {
+    value: T,
+    left: Option<Box<Node<T>>>,
+    right: Option<Box<Node<T>>>,
+}
+
+struct BinaryTree<T> {
+    root: Option<Box<Node<T>>>,
+}
+
+impl<T: Ord> BinaryTree<T> {
+    fn new() -> Self {
+        BinaryTree { root: None }
+    }
+
+    fn insert(&mut self, value: T) {
+        let mut slot = &mut self.root;
+        while let Some(node) = slot {
+            slot = if value < node.value { &mut node.left } else { &mut node.right };
+        }
+        *slot = Some(Box::new(Node { value, left: None, right: None }));
+    }
+
+    fn iter(&self) -> InOrder<'_, T> {
+        let mut iter = InOrder { stack: Vec::new() };
+        iter.push_left_spine(self.root.as_deref());
+        iter
+    }
+}
+
+impl<T: Ord> FromIterator<T> for BinaryTree<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(values: I) -> Self {
+        let mut tree = BinaryTree::new();
+        values.into_iter().for_each(|value| tree.insert(value));
+        tree
+    }
+}
+
+impl<'a, T: Ord> IntoIterator for &'a BinaryTree<T> {
+    type Item = &'a T;
+    type IntoIter = InOrder<'a, T>;
+
+    fn into_iter(self) -> InOrder<'a, T> {
+        self.iter()
+    }
+}
+
+/// In-order traversal without recursion: the explicit stack holds the
+/// nodes whose left subtree is being visited, so the iterator can pause
+/// between any two elements
+struct InOrder<'a, T> {
+    stack: Vec<&'a Node<T>>,
+}
+
+impl<'a, T> InOrder<'a, T> {
+    fn push_left_spine(&mut self, mut node: Option<&'a Node<T>>) {
+        while let Some(current) = node {
+            self.stack.push(current);
+            node = current.left.as_deref();
+        }
+    }
+}
+
+impl<'a, T> Iterator for InOrder<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        let node = self.stack.pop()?;
+        self.push_left_spine(node.right.as_deref());
+        Some(&node.value)
+    }
+}
+
+const QUIZ: &[Question] = &[
+    Question::new(
+        "When do adapters like map and filter do their work?",
+        &[
+            "Immediately, building a new collection each",
+            "Only when a consumer (collect, sum, a for loop) pulls items through the chain",
+            "On a background thread",
+        ],
+        1,
+        "Iterators are lazy: a chain is a nested struct that does nothing until something asks for the next item.",
+    ),
+    Question::new(
+        "When is a plain loop a better fit than an iterator chain?",
+        &[
+            "Never",
+            "Always, for speed",
+            "With complex control flow, or when the chain becomes harder to read than the loop",
+        ],
+        2,
+        "Both compile to similar code, so choose whichever states the intent more clearly.",
+    ),
+];
+
+#[derive(Demo)]
+#[demo(name = "iterator-demo", description = "Iterators from loops to adapters and custom iterators",
+       quiz = QUIZ)]
+pub struct IteratorDemo;
+
+impl IteratorDemo {
+    fn demonstrate(&self, _config: &Config) -> DemoReport {
+        output::title("Rust Iterators Explained");
+
+        // Example data
+        let vec = vec![1, 2, 3, 4, 5];
+        let array = [10, 20, 30, 40, 50];
+
+        outln!("1. Traditional Loop (Index-based):");
+        outln!("   Code:");
+        outln!("   for i in 0..vec.len() {{");
+        outln!("       println!(\"{{}}\", vec[i]);");
+        outln!("   }}");
+        outln!("   Output:");
+        for i in 0..vec.len() {
+            out!("{} ", vec[i]);
+        }
+        outln!("\n");
+
+        outln!("2. Iterator (Element-based):");
+        outln!("   Code:");
+        outln!("   for element in &vec {{");
+        outln!("       println!(\"{{}}\", element);");
+        outln!("   }}");
+        outln!("   Output:");
+        for element in &vec {
+            out!("{} ", element);
+        }
+        outln!("\n");
+
+        outln!("3. Iterator Methods (Functional Style):");
+        outln!("   Code:");
+        outln!("   vec.iter().for_each(|x| println!(\"{{}}\", x));");
+        outln!("   Output:");
+        vec.iter().for_each(|x| out!("{} ", x));
+        outln!("\n");
+
+        outln!("4. Transforming Data:");
+        outln!("   Traditional loop:");
+        let mut doubled = Vec::new();
+        for i in 0..vec.len() {
+            doubled.push(vec[i] * 2);
+        }
+        outln!("   Result: {:?}", doubled);
+
+        outln!("   Iterator (map):");
+        let doubled_iter: Vec<i32> = vec.iter().map(|x| x * 2).collect();
+        outln!("   Result: {:?}", doubled_iter);
+        outln!();
+
+        outln!("5. Filtering Data:");
+        let numbers = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+
+        outln!("   Traditional loop (even numbers):");
+        let mut evens = Vec::new();
+        for i in 0..numbers.len() {
+            if numbers[i] % 2 == 0 {
+                evens.push(numbers[i]);
+            }
+        }
+        outln!("   Result: {:?}", evens);
+
+        outln!("   Iterator (filter):");
+        let evens_iter: Vec<i32> = numbers.iter()
+            .filter(|x| *x % 2 == 0)
+            .copied()
+            .collect();
+        outln!("   Result: {:?}", evens_iter);
+        outln!();
+
+        outln!("6. Chaining Operations:");
+        let result: Vec<i32> = numbers.iter()
+            .filter(|x| *x % 2 == 0)  // Keep even numbers
+            .map(|x| x * 3)            // Multiply by 3
+            .collect();
+        outln!("   Even numbers × 3: {:?}", result);
+        outln!();
+
+        outln!("7. Summing:");
+        let sum_loop: i32 = {
+            let mut s = 0;
+            for i in 0..numbers.len() {
+                s += numbers[i];
+            }
+            s
+        };
+        outln!("   Loop sum: {}", sum_loop);
+
+        let sum_iter: i32 = numbers.iter().sum();
+        outln!("   Iterator sum: {}", sum_iter);
+        outln!();
+
+        outln!("8. Finding Elements:");
+        let found_loop = {
+            let mut found = None;
+            for i in 0..numbers.len() {
+                if numbers[i] > 5 {
+                    found = Some(numbers[i]);
+                    break;
+                }
+            }
+            found
+        };
+        outln!("   Loop find (>5): {:?}", found_loop);
+
+        let found_iter = numbers.iter().find(|&&x| x > 5);
+        outln!("   Iterator find (>5): {:?}", found_iter);
+        outln!();
+
+        outln!("9. Array Iteration:");
+        outln!("   Arrays work the same way:");
+        for element in &array {
+            out!("{} ", element);
+        }
+        outln!("\n");
+
+        outln!("10. Performance Note:");
+        outln!("    - Iterators are often optimized BETTER than loops");
+        outln!("    - LLVM can optimize iterators more aggressively");
+        outln!("    - No bounds checking overhead (iterator knows bounds)");
+        outln!("    - More idiomatic Rust code");
+        outln!();
+
+        output::heading("Building Your Own Iterators");
+        outln!("Implement `next()` and every adapter (map, filter, take, sum...) comes free.\n");
+
+        outln!("11. Infinite Iterator (Fibonacci):");
+        outln!("   Code:");
+        outln!("   impl Iterator for Fibonacci {{");
+        outln!("       type Item = u64;");
+        outln!("       fn next(&mut self) -> Option<u64> {{ /* advance (a, b) to (b, a + b) */ }}");
+        outln!("   }}");
+        let first: Vec<u64> = fibonacci().take(12).collect();
+        outln!("   fibonacci().take(12):             {:?}", first);
+        let even_sum: u64 = fibonacci().take_while(|&f| f < 4_000_000).filter(|f| f % 2 == 0).sum();
+        outln!("   even terms below 4 million, sum:  {}", even_sum);
+        outln!("   Laziness makes \"infinite\" safe: only the values asked for are computed.");
+        outln!("   (It ends after F(93) = {}, the last one that fits in u64.)", fibonacci().last().unwrap());
+        outln!();
+
+        outln!("12. Borrowing Iterator (chunked windows over a slice):");
+        let samples = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+        outln!("   Data: {:?}", samples);
+        let sliding: Vec<&[i32]> = chunked_windows(&samples, 4, 2).collect();
+        outln!("   chunked_windows(&data, 4, 2): {:?}", sliding);
+        let averages: Vec<f64> = chunked_windows(&samples, 4, 2)
+            .map(|window| window.iter().sum::<i32>() as f64 / window.len() as f64)
+            .collect();
+        outln!("   moving averages:              {:?}", averages);
+        outln!("   Items are `&'a [T]` borrowed from the slice - no copies - and");
+        outln!("   ExactSizeIterator reports len() = {} up front.", chunked_windows(&samples, 4, 2).len());
+        outln!();
+
+        outln!("13. Tree Iterator (in-order traversal of a binary search tree):");
+        let tree: BinaryTree<i32> = [50, 30, 70, 20, 40, 60, 80, 35].into_iter().collect();
+        outln!("   Inserted: [50, 30, 70, 20, 40, 60, 80, 35]");
+        let in_order: Vec<&i32> = tree.iter().collect();
+        outln!("   tree.iter(): {:?}", in_order);
+        let between: Vec<&i32> = tree.iter().skip_while(|&&v| v < 30).take_while(|&&v| v <= 60).collect();
+        outln!("   values in 30..=60: {:?}", between);
+        outln!("   The recursion lives in an explicit stack, so the traversal can stop");
+        outln!("   after any element - take_while above stops at 70 and never reaches 80.");
+        outln!();
+
+        output::heading("When to Use What");
+        outln!("✅ Use iterators for:");
+        outln!("   - Transforming data (map)");
+        outln!("   - Filtering data (filter)");
+        outln!("   - Chaining operations");
+        outln!("   - Functional-style code");
+        outln!();
+        outln!("✅ Use loops for:");
+        outln!("   - Complex control flow (break, continue)");
+        outln!("   - When you need the index");
+        outln!("   - When iterators become too complex");
+        outln!();
+        outln!("💡 Best practice: Prefer iterators, use loops when needed");
+        DemoReport::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fibonacci_starts_correctly() {
+        let first: Vec<u64> = fibonacci().take(10).collect();
+        assert_eq!(first, [0, 1, 1, 2, 3, 5, 8, 13, 21, 34]);
+    }
+
+    #[test]
+    fn fibonacci_stops_before_overflow() {
+        assert_eq!(fibonacci().count(), 94);
+        assert_eq!(fibonacci().last(), Some(12_200_160_415_121_876_738));
+    }
+
+    #[test]
+    fn chunked_windows_with_step_one_match_windows() {
+        let data = [1, 2, 3, 4, 5];
+        let ours: Vec<&[i32]> = chunked_windows(&data, 3, 1).collect();
+        let std: Vec<&[i32]> = data.windows(3).collect();
+        assert_eq!(ours, std);
+    }
+
+    #[test]
+    fn chunked_windows_with_step_equal_to_size_match_chunks_exact() {
+        let data = [1, 2, 3, 4, 5, 6, 7];
+        let ours: Vec<&[i32]> = chunked_windows(&data, 2, 2).collect();
+        let std: Vec<&[i32]> = data.chunks_exact(2).collect();
+        assert_eq!(ours, std);
+    }
+
+    #[test]
+    fn chunked_windows_len_is_exact() {
+        let data: Vec<u32> = (0..10).collect();
+        for (size, step) in [(1, 1), (3, 2), (4, 3), (10, 1), (11, 1), (2, 20)] {
+            let iter = chunked_windows(&data, size, step);
+            assert_eq!(iter.len(), iter.count(), "size {} step {}", size, step);
+        }
+    }
+
+    #[test]
+    fn chunked_windows_of_short_slice_is_empty() {
+        assert_eq!(chunked_windows(&[1, 2], 3, 1).next(), None);
+    }
+
+    #[test]
+    fn tree_iterates_in_sorted_order() {
+        let values = [8, 3, 10, 1, 6, 14, 4, 7, 13, 6];
+        let tree: BinaryTree<i32> = values.into_iter().collect();
+        let mut sorted = values.to_vec();
+        sorted.sort();
+        assert_eq!(tree.iter().copied().collect::<Vec<_>>(), sorted);
+    }
+
+    #[test]
+    fn empty_tree_yields_nothing() {
+        let tree: BinaryTree<i32> = BinaryTree::new();
+        assert_eq!(tree.iter().next(), None);
+    }
+
+    #[test]
+    fn tree_works_with_for_loops() {
+        let tree: BinaryTree<&str> = ["pear", "apple", "fig"].into_iter().collect();
+        let mut seen = Vec::new();
+        for fruit in &tree {
+            seen.push(*fruit);
+        }
+        assert_eq!(seen, ["apple", "fig", "pear"]);
+    }
+}
@@ -0,0 +1,284 @@
+//! Lock Scaling Demo
+//!
+//! Increments one shared counter from 1..N threads using different
+//! synchronization strategies, exposing what contention really costs.
+//! Run with: cargo run --release --bin lock-scaling-demo
+
+use std::hint::black_box;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread;
+
+use crate::bench::{format_rate, measure};
+use crate::demo::{Config, Demo, DemoReport};
+use crate::output::Table;
+use crate::plot::LinePlot;
+use crate::quiz::Question;
+use crate::sync::CachePadded;
+use crate::{outln, output};
+
+/// Operations per thread, unless the config sets `iterations`
+const OPS_PER_THREAD: u64 = 200_000;
+
+#[derive(Clone, Copy)]
+enum Strategy {
+    Mutex,
+    RwLockReadHeavy,
+    RwLockWriteHeavy,
+    Atomic,
+    Sharded,
+    PerThread,
+}
+
+impl Strategy {
+    const ALL: [Strategy; 6] = [
+        Strategy::Mutex,
+        Strategy::RwLockReadHeavy,
+        Strategy::RwLockWriteHeavy,
+        Strategy::Atomic,
+        Strategy::Sharded,
+        Strategy::PerThread,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            Strategy::Mutex => "Mutex",
+            Strategy::RwLockReadHeavy => "RwLock 90%R",
+            Strategy::RwLockWriteHeavy => "RwLock 10%R",
+            Strategy::Atomic => "AtomicU64",
+            Strategy::Sharded => "Sharded",
+            Strategy::PerThread => "Per-thread",
+        }
+    }
+}
+
+/// Spawn `threads` workers that all run `work` (`ops` operations) on the
+/// shared state, then join them
+fn spawn_and_join<S: Send + Sync + 'static>(threads: usize, ops: u64, shared: Arc<S>, work: fn(&S, u64)) {
+    let handles: Vec<_> = (0..threads)
+        .map(|_| {
+            let shared = Arc::clone(&shared);
+            thread::spawn(move || work(&shared, ops))
+        })
+        .collect();
+    handles.into_iter().for_each(|h| h.join().unwrap());
+}
+
+/// One padded atomic per thread; readers sum the slots
+struct ShardedCounter {
+    slots: Vec<CachePadded<AtomicU64>>,
+    next_slot: AtomicUsize,
+}
+
+impl ShardedCounter {
+    fn new(threads: usize) -> Self {
+        ShardedCounter {
+            slots: (0..threads).map(|_| CachePadded::new(AtomicU64::new(0))).collect(),
+            next_slot: AtomicUsize::new(0),
+        }
+    }
+
+    /// Hand each worker its own slot
+    fn claim_slot(&self) -> &AtomicU64 {
+        &self.slots[self.next_slot.fetch_add(1, Ordering::Relaxed)]
+    }
+
+    fn sum(&self) -> u64 {
+        self.slots.iter().map(|slot| slot.load(Ordering::Relaxed)).sum()
+    }
+}
+
+/// Every 10th operation is a write; the rest read
+fn rwlock_read_heavy(lock: &RwLock<u64>, ops: u64) {
+    for i in 0..ops {
+        if i % 10 == 0 {
+            *lock.write().unwrap() += 1;
+        } else {
+            black_box(*lock.read().unwrap());
+        }
+    }
+}
+
+/// Every 10th operation is a read; the rest write
+fn rwlock_write_heavy(lock: &RwLock<u64>, ops: u64) {
+    for i in 0..ops {
+        if i % 10 == 0 {
+            black_box(*lock.read().unwrap());
+        } else {
+            *lock.write().unwrap() += 1;
+        }
+    }
+}
+
+/// Run one strategy and return the final counter value (writes only)
+fn run(strategy: Strategy, threads: usize, ops: u64) -> u64 {
+    match strategy {
+        Strategy::Mutex => {
+            let counter = Arc::new(Mutex::new(0u64));
+            spawn_and_join(threads, ops, Arc::clone(&counter), |counter, ops| {
+                for _ in 0..ops {
+                    *counter.lock().unwrap() += 1;
+                }
+            });
+            *counter.lock().unwrap()
+        }
+        Strategy::RwLockReadHeavy => {
+            let counter = Arc::new(RwLock::new(0u64));
+            spawn_and_join(threads, ops, Arc::clone(&counter), rwlock_read_heavy);
+            *counter.read().unwrap()
+        }
+        Strategy::RwLockWriteHeavy => {
+            let counter = Arc::new(RwLock::new(0u64));
+            spawn_and_join(threads, ops, Arc::clone(&counter), rwlock_write_heavy);
+            *counter.read().unwrap()
+        }
+        Strategy::Atomic => {
+            let counter = Arc::new(AtomicU64::new(0));
+            spawn_and_join(threads, ops, Arc::clone(&counter), |counter, ops| {
+                for _ in 0..ops {
+                    counter.fetch_add(1, Ordering::Relaxed);
+                }
+            });
+            counter.load(Ordering::Relaxed)
+        }
+        Strategy::Sharded => {
+            let counter = Arc::new(ShardedCounter::new(threads));
+            spawn_and_join(threads, ops, Arc::clone(&counter), |counter, ops| {
+                let slot = counter.claim_slot();
+                for _ in 0..ops {
+                    slot.fetch_add(1, Ordering::Relaxed);
+                }
+            });
+            counter.sum()
+        }
+        Strategy::PerThread => {
+            // Count in a register/stack variable, publish once at the end
+            let counter = Arc::new(AtomicU64::new(0));
+            spawn_and_join(threads, ops, Arc::clone(&counter), |counter, ops| {
+                let mut local = 0u64;
+                for _ in 0..ops {
+                    local = black_box(local) + 1;
+                }
+                counter.fetch_add(local, Ordering::Relaxed);
+            });
+            counter.load(Ordering::Relaxed)
+        }
+    }
+}
+
+fn demonstrate_correctness(ops: u64) {
+    output::heading("✅ Final Counter Values (4 threads)");
+    let threads = 4;
+    let mut table = Table::new(["strategy", "value", "expected", ""]);
+    for strategy in Strategy::ALL {
+        let expected = match strategy {
+            Strategy::RwLockReadHeavy => threads as u64 * ops / 10,
+            Strategy::RwLockWriteHeavy => threads as u64 * ops * 9 / 10,
+            _ => threads as u64 * ops,
+        };
+        let value = run(strategy, threads, ops);
+        table.row([strategy.name().to_string(), value.to_string(), expected.to_string(),
+                   (if value == expected { "✅" } else { "❌" }).to_string()]);
+    }
+    outln!("{}", table);
+}
+
+fn demonstrate_scaling(ops: u64, max_threads: usize, report: &mut DemoReport) {
+    output::heading("📈 Scaling Table");
+    outln!("Each thread performs {} operations; total throughput, median of 3 runs", ops);
+    outln!("(factor in parentheses = throughput relative to 1 thread)\n");
+
+    let header = ["threads"].into_iter().chain(Strategy::ALL.map(Strategy::name));
+    let table = Table::new(header).widths([7].into_iter().chain([20; Strategy::ALL.len()]));
+    outln!("{}", table.header());
+
+    let mut baseline = [0.0f64; Strategy::ALL.len()];
+    let mut curves: [Vec<(f64, f64)>; Strategy::ALL.len()] = Default::default();
+    let mut threads = 1;
+    while threads <= max_threads {
+        let mut rates = [0.0f64; Strategy::ALL.len()];
+        for (i, strategy) in Strategy::ALL.into_iter().enumerate() {
+            let total_ops = threads as u64 * ops;
+            let rate = measure(3, || run(strategy, threads, ops)).throughput(total_ops);
+            if threads == 1 {
+                baseline[i] = rate;
+            }
+            rates[i] = rate;
+            report.record(strategy.name(), rate, "ops/s").param("threads", threads).param("ops_per_thread", ops);
+            curves[i].push((threads as f64, rate));
+        }
+        let cells = output::rank(&rates, true, format_rate)
+            .into_iter()
+            .zip(rates.iter().zip(baseline))
+            .map(|(rate_cell, (rate, baseline))| format!("{} ({:>4.1}x)", rate_cell, rate / baseline));
+        outln!("{}", table.line([threads.to_string()].into_iter().chain(cells)));
+        threads *= 2;
+    }
+    outln!();
+
+    let plot = Strategy::ALL.into_iter().zip(curves).fold(LinePlot::new("threads", "ops/s").log_x().log_y(),
+                                                           |plot, (strategy, curve)| plot.series(strategy.name(), curve));
+    outln!("{}", plot);
+    outln!("Shared-counter strategies all serialize on ONE cache line: adding threads");
+    outln!("adds coherence traffic, not throughput. Mutex and write-heavy RwLock also");
+    outln!("pay for parking/waking waiters once the lock is contended.");
+    outln!("Read-heavy RwLock still bounces its reader count between cores - readers");
+    outln!("don't block each other, but they do write to the lock word.");
+    outln!("Sharded counters still use atomics, but each thread's slot sits on its own");
+    outln!("cache line (`CachePadded`), so increments never contend. Without the padding");
+    outln!("the slots would share a line and bounce just like a single counter.");
+    outln!("Per-thread counters share nothing until the final merge, so they scale with cores.\n");
+
+    let cores = thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+    if cores == 1 {
+        outln!("⚠️  Only one hardware thread available: threads take turns instead of");
+        outln!("   contending, so the table mostly shows per-operation cost.\n");
+    }
+}
+
+const QUIZ: &[Question] = &[
+    Question::new(
+        "Why does a Mutex-protected counter get slower as threads are added?",
+        &[
+            "Every increment serializes on one lock whose cache line bounces between cores",
+            "Mutexes allocate per lock",
+            "The counter overflows",
+        ],
+        0,
+        "More threads means more waiting and more coherence traffic, not more work done.",
+    ),
+    Question::new(
+        "What made the counter scale?",
+        &["A RwLock", "An AtomicU64", "Sharding it across cache-padded slots and summing at the end"],
+        2,
+        "An atomic still serializes on one line; separate lines per thread remove the contention.",
+    ),
+];
+
+#[derive(Demo)]
+#[demo(name = "lock-scaling-demo", description = "One shared counter under different locks, 1..N threads",
+       quiz = QUIZ)]
+pub struct LockScalingDemo;
+
+impl LockScalingDemo {
+    fn demonstrate(&self, config: &Config) -> DemoReport {
+        output::title("🔒 Lock Scaling Demo");
+        outln!("How synchronization strategies behave as threads are added.\n");
+
+        let ops = config.iterations.map_or(OPS_PER_THREAD, |n| n as u64);
+        let max_threads = config
+            .threads
+            .unwrap_or_else(|| thread::available_parallelism().map(|n| n.get()).unwrap_or(1).max(2) * 2);
+        demonstrate_correctness(ops);
+        let mut report = DemoReport::default();
+        demonstrate_scaling(ops, max_threads, &mut report);
+
+        output::takeaways();
+        outln!("• Contended shared state scales negatively - more threads, less throughput");
+        outln!("• Atomics avoid locking overhead but still serialize on one cache line");
+        outln!("• RwLock only pays off when reads dominate and critical sections are long");
+        outln!("• Sharding a counter across padded slots removes the contended cache line");
+        outln!("• The fastest synchronization is none: partition work, merge at the end");
+        report
+    }
+}
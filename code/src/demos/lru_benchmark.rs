@@ -0,0 +1,342 @@
+//! LRU Benchmark
+//!
+//! Is a hand-rolled LRU cache any good, or should you reach for the `lru`
+//! crate? The benchmark races the library's `LruCache` (raw pointers) and
+//! `SafeLruCache` (an index slab) against `lru::LruCache` from crates.io,
+//! with an unbounded `HashMap` as the baseline, a lookup with no recency
+//! to keep, hashed both with std's SipHash and with the lru crate's
+//! foldhash, since the hasher turns out to matter as much as the design.
+//! Each runs hits, inserts of fresh keys and a get-or-put mix, timed like
+//! criterion does (warmup, several runs, the median), and then has its heap
+//! bytes per entry counted by an allocator that tracks every allocation.
+//! Run with: cargo run --release --bin lru-benchmark
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::collections::HashMap;
+use std::hint::black_box;
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::bench::{format_rate, Bench};
+use crate::cache::{LruCache, SafeLruCache};
+use crate::demo::{Config, Demo, DemoReport};
+use crate::output::Table;
+use crate::plot::format_si;
+use crate::quiz::Question;
+use crate::rng::Rng;
+use crate::verify::Verification;
+use crate::{outln, output};
+
+/// The system allocator, plus a count of the heap bytes currently in use.
+/// Install with `#[global_allocator]` in the binary.
+pub struct ByteCountingAllocator;
+
+static LIVE_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for ByteCountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            LIVE_BYTES.fetch_add(layout.size(), Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        LIVE_BYTES.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new = unsafe { System.realloc(ptr, layout, new_size) };
+        if !new.is_null() {
+            LIVE_BYTES.fetch_add(new_size, Ordering::Relaxed);
+            LIVE_BYTES.fetch_sub(layout.size(), Ordering::Relaxed);
+        }
+        new
+    }
+}
+
+/// Entries each cache holds while timed
+const CAPACITY: usize = 16 * 1024;
+
+/// Operations per timing, unless `Config::size` says otherwise
+const OPS: usize = 1 << 20;
+
+/// Entries filled in for the memory count
+const MEMORY_ENTRIES: usize = 100_000;
+
+/// What the race needs from each contender
+trait Contender {
+    const NAME: &'static str;
+    fn with_capacity(capacity: usize) -> Self;
+    fn hit(&mut self, key: &u64) -> bool;
+    fn insert(&mut self, key: u64);
+}
+
+impl Contender for LruCache<u64, u64> {
+    const NAME: &'static str = "LruCache";
+    fn with_capacity(capacity: usize) -> Self {
+        LruCache::new(capacity)
+    }
+    fn hit(&mut self, key: &u64) -> bool {
+        self.get(key).is_some()
+    }
+    fn insert(&mut self, key: u64) {
+        black_box(self.put(key, key));
+    }
+}
+
+impl Contender for SafeLruCache<u64, u64> {
+    const NAME: &'static str = "SafeLruCache";
+    fn with_capacity(capacity: usize) -> Self {
+        SafeLruCache::new(capacity)
+    }
+    fn hit(&mut self, key: &u64) -> bool {
+        self.get(key).is_some()
+    }
+    fn insert(&mut self, key: u64) {
+        black_box(self.put(key, key));
+    }
+}
+
+impl Contender for lru::LruCache<u64, u64> {
+    const NAME: &'static str = "lru::LruCache";
+    fn with_capacity(capacity: usize) -> Self {
+        lru::LruCache::new(NonZeroUsize::new(capacity).expect("capacity is not zero"))
+    }
+    fn hit(&mut self, key: &u64) -> bool {
+        self.get(key).is_some()
+    }
+    fn insert(&mut self, key: u64) {
+        black_box(self.push(key, key));
+    }
+}
+
+/// No eviction and no recency: what a lookup costs on its own
+impl Contender for HashMap<u64, u64> {
+    const NAME: &'static str = "HashMap (unbounded)";
+    fn with_capacity(_: usize) -> Self {
+        HashMap::new()
+    }
+    fn hit(&mut self, key: &u64) -> bool {
+        self.get(key).is_some()
+    }
+    fn insert(&mut self, key: u64) {
+        black_box(self.insert(key, key));
+    }
+}
+
+/// The same map with the lru crate's hasher (foldhash) instead of SipHash
+impl Contender for HashMap<u64, u64, lru::DefaultHasher> {
+    const NAME: &'static str = "HashMap, lru's hasher";
+    fn with_capacity(_: usize) -> Self {
+        HashMap::default()
+    }
+    fn hit(&mut self, key: &u64) -> bool {
+        self.get(key).is_some()
+    }
+    fn insert(&mut self, key: u64) {
+        black_box(self.insert(key, key));
+    }
+}
+
+/// The common cache pattern on every key of `trace`: look it up, and insert
+/// it on a miss. Returns the hits.
+fn get_or_put<C: Contender>(cache: &mut C, trace: &[u64]) -> usize {
+    let mut hits = 0;
+    for key in trace {
+        if cache.hit(key) {
+            hits += 1;
+        } else {
+            cache.insert(*key);
+        }
+    }
+    hits
+}
+
+/// One contender's results: operations per second for hits, fresh inserts
+/// and the mix, and heap bytes per entry if the counting allocator is
+/// installed
+struct Results {
+    name: &'static str,
+    rates: [f64; 3],
+    bytes_per_entry: Option<f64>,
+}
+
+fn race<C: Contender>(bench: &Bench, ops: usize, rng: &mut Rng) -> Results {
+    let hits: Vec<u64> = (0..ops).map(|_| rng.below(CAPACITY) as u64).collect();
+    let mixed: Vec<u64> = (0..ops).map(|_| rng.below(2 * CAPACITY) as u64).collect();
+
+    let mut full = C::with_capacity(CAPACITY);
+    (0..CAPACITY as u64).for_each(|key| full.insert(key));
+    let hit_time = bench.measure(|| hits.iter().filter(|key| full.hit(key)).count());
+    // A fresh cache each run: the LRUs evict once full, the HashMap keeps growing
+    let insert_time = bench.measure(|| {
+        let mut cache = C::with_capacity(CAPACITY);
+        (0..ops as u64).for_each(|key| cache.insert(key));
+        cache
+    });
+    let mut cache = C::with_capacity(CAPACITY);
+    let mixed_time = bench.measure(|| get_or_put(&mut cache, &mixed));
+
+    Results {
+        name: C::NAME,
+        rates: [&hit_time, &insert_time, &mixed_time].map(|time| time.throughput(ops as u64)),
+        bytes_per_entry: bytes_per_entry::<C>(),
+    }
+}
+
+/// Heap bytes one entry costs, counting the map, the nodes and any slack,
+/// or `None` without `ByteCountingAllocator` installed
+fn bytes_per_entry<C: Contender>() -> Option<f64> {
+    let before = LIVE_BYTES.load(Ordering::Relaxed);
+    let mut cache = C::with_capacity(MEMORY_ENTRIES);
+    (0..MEMORY_ENTRIES as u64).for_each(|key| cache.insert(key));
+    let bytes = LIVE_BYTES.load(Ordering::Relaxed).saturating_sub(before);
+    drop(black_box(cache));
+    (bytes > 0).then(|| bytes as f64 / MEMORY_ENTRIES as f64)
+}
+
+fn demonstrate_speed(config: &Config, report: &mut DemoReport) -> Vec<Results> {
+    output::heading("⏱️  Get and Put");
+    let ops = config.size.unwrap_or(OPS).max(1024);
+    outln!("{} operations per timing on caches of {} entries, u64 keys and", format_si(ops as f64), format_si(CAPACITY as f64));
+    outln!("values; 1 warmup, median of 5 runs. \"get\" only hits; \"put\" inserts fresh");
+    outln!("keys into a new cache, evicting once it is full; \"mixed\" looks up keys");
+    outln!("drawn from twice the capacity and inserts the misses.\n");
+
+    let bench = Bench::new().warmup(1).runs(5);
+    let mut rng = config.rng();
+    let results = vec![
+        race::<LruCache<u64, u64>>(&bench, ops, &mut rng),
+        race::<SafeLruCache<u64, u64>>(&bench, ops, &mut rng),
+        race::<lru::LruCache<u64, u64>>(&bench, ops, &mut rng),
+        race::<HashMap<u64, u64>>(&bench, ops, &mut rng),
+        race::<HashMap<u64, u64, lru::DefaultHasher>>(&bench, ops, &mut rng),
+    ];
+
+    let mut table = Table::new(["cache", "get", "put", "mixed"]).left(0);
+    let columns: Vec<Vec<String>> = (0..3)
+        .map(|column| {
+            let rates: Vec<f64> = results.iter().map(|result| result.rates[column]).collect();
+            output::rank(&rates, true, format_rate)
+        })
+        .collect();
+    for (row, result) in results.iter().enumerate() {
+        for (operation, rate) in ["get", "put", "mixed"].iter().zip(result.rates) {
+            report.record(format!("{} {}", result.name, operation), rate, "ops/s").param("capacity", CAPACITY).param("ops", ops);
+        }
+        table.row([result.name.to_string()].into_iter().chain(columns.iter().map(|column| column[row].clone())));
+    }
+    outln!("{}", table);
+
+    let [ours, _, crate_lru, map, fast_map] = [0, 1, 2, 3, 4].map(|i| &results[i].rates);
+    outln!("On the mixed trace LruCache runs at {:.2}x the lru crate's speed, though", ours[2] / crate_lru[2]);
+    outln!("both are a hash map of pointers into a doubly linked list. Most of the");
+    outln!("gap is the hasher: std's HashMap uses SipHash, keyed against flooding");
+    outln!("(see hashdos-demo), and the lru crate uses foldhash. The two plain maps");
+    outln!("show the same split: with lru's hasher, HashMap hits run {:.2}x faster.", fast_map[0] / map[0]);
+    outln!("With the hasher held equal, the relink is the price of recency: the");
+    outln!("SipHash HashMap hits at {:.2}x LruCache's rate, and the foldhash one at", map[0] / ours[0]);
+    outln!("{:.2}x the lru crate's. The maps never evict, so their \"put\" pays for", fast_map[0] / crate_lru[0]);
+    outln!("a table that keeps growing instead.\n");
+    results
+}
+
+fn demonstrate_memory(results: &[Results], report: &mut DemoReport) {
+    output::heading("💾 Heap Bytes per Entry");
+    if results.iter().any(|result| result.bytes_per_entry.is_none()) {
+        outln!("Not measured: the byte-counting allocator is installed by the");
+        outln!("lru-benchmark binary, and this process runs without it.\n");
+        return;
+    }
+    outln!("{} u64 → u64 entries (16 bytes of payload each), everything the", format_si(MEMORY_ENTRIES as f64));
+    outln!("cache allocated divided by the entries:\n");
+    let mut table = Table::new(["cache", "bytes/entry", "overhead"]).left(0);
+    for result in results {
+        let bytes = result.bytes_per_entry.expect("checked above");
+        report.record(format!("{} bytes per entry", result.name), bytes, "B").param("entries", MEMORY_ENTRIES);
+        table.row([result.name.to_string(), format!("{:.1}", bytes), format!("{:.1}x", bytes / 16.0)]);
+    }
+    outln!("{}", table);
+    outln!("A map slot costs 16 bytes plus a control byte, with the table at most");
+    outln!("7/8 full and grown by doubling: {} entries need 131,072 slots. The", format_si(MEMORY_ENTRIES as f64));
+    outln!("three LRUs then add 32 bytes per entry, the pair and two links, whether");
+    outln!("as a heap node (LruCache, the lru crate) or a slot in one Vec linked by");
+    outln!("index (SafeLruCache). Recency doubles what the pairs cost to keep.\n");
+}
+
+/// The three LRUs evict alike, and a plain map's hit beats an LRU's
+fn verify() -> Verification {
+    let mut verification = Verification::new();
+    let mut rng = Rng::new(1);
+    let capacity = 1000;
+    let trace: Vec<u64> = (0..50_000).map(|_| rng.below(2 * capacity) as u64).collect();
+    let ours = get_or_put(&mut LruCache::new(capacity), &trace);
+    let slab = get_or_put(&mut SafeLruCache::new(capacity), &trace);
+    let crate_lru = get_or_put(&mut <lru::LruCache<u64, u64> as Contender>::with_capacity(capacity), &trace);
+    verification
+        .equal("LruCache hits exactly as often as the lru crate", ours, crate_lru)
+        .equal("so does SafeLruCache", slab, crate_lru);
+
+    let bench = Bench::new().warmup(1).runs(5);
+    let keys: Vec<u64> = (0..100_000).map(|_| rng.below(capacity) as u64).collect();
+    let mut lru_cache = LruCache::new(capacity);
+    let mut map = HashMap::new();
+    for key in 0..capacity as u64 {
+        lru_cache.insert(key);
+        Contender::insert(&mut map, key);
+    }
+    let lru_time = bench.measure(|| keys.iter().filter(|key| lru_cache.hit(key)).count());
+    let map_time = bench.measure(|| keys.iter().filter(|key| map.hit(key)).count());
+    verification.faster("a HashMap hit beats an LruCache hit, which also relinks", &map_time, &lru_time, 1.1);
+    verification
+}
+
+const QUIZ: &[Question] = &[
+    Question::new(
+        "Why is a HashMap lookup faster than an LRU cache hit?",
+        &[
+            "HashMap uses a better hash function",
+            "An LRU hit also unlinks the entry and relinks it at the front of the list",
+            "LRU caches store keys twice",
+        ],
+        1,
+        "The lookups are the same; the recency bookkeeping is the extra work on every hit.",
+    ),
+    Question::new(
+        "Where does a pointer-linked LRU cache's memory go, beyond the key and value?",
+        &[
+            "Nowhere: it stores only the pairs",
+            "A map slot per entry, plus a heap node with two links and the allocator's overhead",
+            "A copy of every value",
+        ],
+        1,
+        "Per entry: a slot in the map's table, and a separately allocated node holding the pair and prev/next pointers.",
+    ),
+];
+
+#[derive(Demo)]
+#[demo(name = "lru-benchmark", description = "LruCache and SafeLruCache vs the lru crate vs a HashMap: speed and bytes per entry",
+       quiz = QUIZ, verify = verify)]
+pub struct LruBenchmark;
+
+impl LruBenchmark {
+    fn demonstrate(&self, config: &Config) -> DemoReport {
+        output::title("📏 LRU Benchmark");
+        outln!("A homegrown LRU cache against the crates.io one, with numbers.\n");
+
+        let mut report = DemoReport::default();
+        let results = demonstrate_speed(config, &mut report);
+        demonstrate_memory(&results, &mut report);
+
+        output::takeaways();
+        outln!("• The lru crate and LruCache are the same design, and evict the same keys");
+        outln!("• The hasher moves the numbers more than the design: SipHash vs foldhash");
+        outln!("• With the hasher fixed, recency costs every hit a relink");
+        outln!("• Each entry costs a map slot plus the pair and two links, over 3x its payload");
+        outln!("• Measure before choosing: writing your own is a lesson, the crate is maintained");
+        report
+    }
+}
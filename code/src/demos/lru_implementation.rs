@@ -0,0 +1,225 @@
+//! LRU Cache Implementation Demo
+//!
+//! Demonstrates building an LRU (Least Recently Used) cache from scratch.
+//! Shows advanced Rust concepts: generics, HashMap, LinkedList, smart pointers.
+//! The cache itself is the library's [`LruCache`].
+//! Run with: cargo run --release --bin lru-implementation
+
+use std::hint::black_box;
+use std::time::Duration;
+
+use crate::bench::Bench;
+use crate::cache::{LruCache, SafeLruCache};
+use crate::demo::{Config, Demo, DemoReport};
+use crate::output::Table;
+use crate::plot::format_si;
+use crate::quiz::Question;
+use crate::verify::Verification;
+use crate::{outln, output};
+
+fn demonstrate_lru_cache() {
+    output::heading("🚀 LRU Cache Implementation");
+    outln!("LRU Cache Concepts:");
+    outln!("• Fixed capacity with automatic eviction");
+    outln!("• Most Recently Used (MRU) items stay in cache");
+    outln!("• Least Recently Used (LRU) items are evicted");
+    outln!("• O(1) get/put operations using HashMap + Linked List");
+    outln!("• Used in databases, web caches, OS page replacement");
+    outln!();
+
+    let mut cache = LruCache::new(3);
+    outln!("A cache of capacity {}, printed from most to least recently used:", cache.capacity());
+    for (key, value) in [("a", 1), ("b", 2), ("c", 3)] {
+        cache.put(key, value);
+    }
+    outln!("  put a, b, c     {:?}", cache);
+    cache.get(&"a");
+    outln!("  get a           {:?}", cache);
+    if let Some((key, value)) = cache.put("d", 4) {
+        outln!("  put d           {:?}  (evicted {:?} = {})", cache, key, value);
+    }
+    outln!("  get b           {:?}", cache.get(&"b"));
+    outln!("  peek c          {:?}  (a look that doesn't count as a use)", cache.peek(&"c"));
+    for (_, value) in cache.iter_mut() {
+        *value *= 10;
+    }
+    outln!("  iter_mut × 10   {:?}", cache);
+    outln!("  pop_lru         {:?}  (still c: peek left it last)", cache.pop_lru());
+    outln!("  remove a        {:?}", cache.remove(&"a"));
+    outln!("  contains d      {}  ({:?} left)", cache.contains_key(&"d"), cache);
+    outln!();
+    outln!("Every node is pointed at by the map and by both neighbours, so none of");
+    outln!("them can own it as a Box. The cache owns them all instead: Box::into_raw");
+    outln!("on insert, Box::from_raw once on eviction or drop, raw pointers between.");
+}
+
+/// Cache operations per timing, unless `Config::size` says otherwise
+const OPS: usize = 1 << 20;
+
+/// Capacities to race the two caches at: L1-sized to beyond L2
+const CAPACITIES: [usize; 3] = [256, 16 * 1024, 256 * 1024];
+
+/// The common cache pattern on every key of `trace`: `get`, and `put` on a
+/// miss. Returns the hits.
+fn run_trace<C>(cache: &mut C, trace: &[u64], get: impl Fn(&mut C, &u64) -> bool, put: impl Fn(&mut C, u64)) -> usize {
+    let mut hits = 0;
+    for &key in trace {
+        if get(cache, &key) {
+            hits += 1;
+        } else {
+            put(cache, key);
+        }
+    }
+    hits
+}
+
+fn pointer_trace(cache: &mut LruCache<u64, u64>, trace: &[u64]) -> usize {
+    run_trace(cache, trace, |cache, key| black_box(cache.get(key)).is_some(), |cache, key| {
+        black_box(cache.put(key, key));
+    })
+}
+
+fn slab_trace(cache: &mut SafeLruCache<u64, u64>, trace: &[u64]) -> usize {
+    run_trace(cache, trace, |cache, key| black_box(cache.get(key)).is_some(), |cache, key| {
+        black_box(cache.put(key, key));
+    })
+}
+
+fn demonstrate_cache_performance(config: &Config, report: &mut DemoReport) {
+    output::heading("\n⚡ Raw Pointers vs an Index Slab");
+    let ops = config.size.unwrap_or(OPS).max(1024);
+    outln!("LruCache links heap nodes with raw pointers; SafeLruCache keeps its entries");
+    outln!("in one Vec linked by index, with no unsafe. Each runs {} random keys", format_si(ops as f64));
+    outln!("drawn from twice its capacity (about half hit), calling get and then put");
+    outln!("on a miss:\n");
+
+    let bench = Bench::new().warmup(1).runs(5);
+    let mut rng = config.rng();
+    let mut table = Table::new(["capacity", "LruCache", "SafeLruCache", "slab speedup", "hit rate"]);
+    let mut ratios = Vec::new();
+    for capacity in CAPACITIES {
+        let trace: Vec<u64> = (0..ops).map(|_| rng.below(2 * capacity) as u64).collect();
+        let mut pointers = LruCache::new(capacity);
+        let mut slab = SafeLruCache::new(capacity);
+        let pointer_time = bench.measure(|| pointer_trace(&mut pointers, &trace)).median();
+        let slab_time = bench.measure(|| slab_trace(&mut slab, &trace)).median();
+        let hits = pointer_trace(&mut pointers, &trace);
+        ratios.push(pointer_time.as_secs_f64() / slab_time.as_secs_f64());
+        report.record_time("LruCache", pointer_time).param("capacity", capacity).param("ops", ops);
+        report.record_time("SafeLruCache", slab_time).param("capacity", capacity).param("ops", ops);
+
+        let per_op = |time: Duration| time.as_secs_f64() * 1e9 / ops as f64;
+        let times = output::rank(&[per_op(pointer_time), per_op(slab_time)], false, |ns| format!("{:.1} ns/op", ns));
+        table.row([
+            format_si(capacity as f64),
+            times[0].clone(),
+            times[1].clone(),
+            output::speedup(ratios[ratios.len() - 1]),
+            format!("{:.0}%", 100.0 * hits as f64 / ops as f64),
+        ]);
+    }
+    outln!("{}", table);
+    let (slowest, fastest) = ratios.iter().fold((f64::MAX, 0.0_f64), |(lo, hi), &r| (lo.min(r), hi.max(r)));
+    if slowest >= 1.0 {
+        outln!("The slab is ahead at every size, by {:.2}x to {:.2}x: it pays a bounds check", slowest, fastest);
+        outln!("per link, but the pointer version pays an allocation per insert and a free");
+        outln!("per eviction, and its nodes end up scattered across the heap.");
+    } else if fastest <= 1.0 {
+        outln!("The pointers are ahead at every size, by {:.2}x to {:.2}x: the slab's bounds", 1.0 / fastest, 1.0 / slowest);
+        outln!("checks cost more here than the pointer version's allocations.");
+    } else {
+        outln!("Neither is ahead at every size ({:.2}x to {:.2}x): most of each operation", slowest, fastest);
+        outln!("is the HashMap lookup and the cache misses on the entries, which both pay.");
+    }
+    if slowest > 1.0 / 1.1 {
+        outln!("Giving up unsafe costs no speed here.");
+    }
+}
+
+fn demonstrate_cache_use_cases() {
+    output::heading("
+🎯 Cache Use Cases");
+
+    outln!("LRU caches are used in many systems:");
+    outln!("• Web servers: Cache HTTP responses, reduce database load");
+    outln!("• Databases: Cache query results, speed up repeated queries");
+    outln!("• Operating Systems: Page replacement ( Least Recently Used pages)");
+    outln!("• Web browsers: Cache web pages, images, scripts");
+    outln!("• CPU caches: Hardware-level LRU for memory access");
+    outln!("• CDN networks: Cache content closer to users");
+}
+
+const QUIZ: &[Question] = &[
+    Question::new(
+        "How does an LRU cache get O(1) get and put?",
+        &[
+            "A HashMap for lookup plus a doubly linked list that keeps recency order",
+            "A sorted Vec",
+            "A binary heap of timestamps",
+        ],
+        0,
+        "The map finds the node; the list moves it to the front and evicts from the back.",
+    ),
+    Question::new(
+        "Which entry is evicted when the cache is full?",
+        &["The most recently used", "The least recently used: the tail of the list", "A random one"],
+        1,
+        "Recency approximates future use, which is also how OSes approximate page replacement.",
+    ),
+];
+
+/// The cache evicts in recency order, an update counts as a use, a peek
+/// doesn't, iteration follows recency, and the slab version makes the same
+/// choices
+fn verify() -> Verification {
+    let mut verification = Verification::new();
+    let mut cache = LruCache::new(3);
+    for key in 1..=3 {
+        cache.put(key, key * 10);
+    }
+    cache.get(&1);
+    verification
+        .equal("a full LruCache evicts its least recently used entry", cache.put(4, 40), Some((2, 20)))
+        .equal("updating a key makes it the most recently used", cache.put(3, 33), None)
+        .equal("the next eviction follows recency order", cache.put(5, 50), Some((1, 10)))
+        .equal("an evicted key is gone", cache.get(&2), None)
+        .equal("iteration runs from most to least recently used",
+               cache.iter().map(|(&key, _)| key).collect::<Vec<_>>(), vec![5, 3, 4])
+        .equal("a peek leaves the order alone", cache.peek(&4).copied(), Some(40))
+        .equal("so the peeked key is still evicted next", cache.put(6, 60), Some((4, 40)));
+
+    let trace: Vec<u64> = (0..10_000).map(|i| (i * 7919) % 300).collect();
+    let hits = pointer_trace(&mut LruCache::new(100), &trace);
+    verification.equal("SafeLruCache hits exactly where LruCache does",
+                       slab_trace(&mut SafeLruCache::new(100), &trace), hits);
+    verification
+}
+
+#[derive(Demo)]
+#[demo(name = "lru-implementation", description = "An LRU cache built from scratch, with raw pointers and with an index slab",
+       quiz = QUIZ, verify = verify)]
+pub struct LruImplementation;
+
+impl LruImplementation {
+    fn demonstrate(&self, config: &Config) -> DemoReport {
+        let mut report = DemoReport::default();
+        output::title("🧠 LRU Cache Implementation Demo");
+        outln!("Building a high-performance cache from scratch in Rust.\n");
+
+        demonstrate_lru_cache();
+        demonstrate_cache_performance(config, &mut report);
+        demonstrate_cache_use_cases();
+
+        outln!();
+
+        output::takeaways();
+        outln!("• LRU caches provide bounded memory usage with smart eviction");
+        outln!("• Raw pointers and unsafe code enable high performance");
+        outln!("• Generics allow flexible key/value types");
+        outln!("• Linked list + HashMap gives O(1) operations");
+        outln!("• Used in databases, web servers, OS page replacement");
+        outln!("• Trade-off: Memory overhead for performance and bounded size");
+        outln!("• An index slab gives the same O(1) cache without unsafe");
+        report
+    }
+}
@@ -0,0 +1,297 @@
+//! LRU vs LFU Demo
+//!
+//! The same capacity, the same requests, two ideas of what to keep: the
+//! least recently used entry goes (`LruCache`) or the least frequently used
+//! one does (`LfuCache`). The demo replays five traces against both, plus
+//! FIFO and Random as baselines, each plugged into the same `PolicyCache`
+//! as an `EvictionPolicy`, and counts hits. A loop one size too big for the cache defeats both, since
+//! LFU breaks its ties by recency; a hot set read between the blocks of a
+//! long scan is where counting shines; Zipf-distributed keys, like most
+//! real request streams, slightly favour it; uniform keys make the policy
+//! irrelevant; and a hot set that moves leaves LFU holding yesterday's
+//! favourites. Random, which keeps no history, is the only policy the loop
+//! can't defeat.
+//! Run with: cargo run --release --bin lru-vs-lfu-demo
+
+use crate::cache::{EvictionPolicy, FifoPolicy, LfuPolicy, LruPolicy, PolicyCache, RandomPolicy};
+use crate::demo::{Config, Demo, DemoReport};
+use crate::output::Table;
+use crate::quiz::Question;
+use crate::rng::{Rng, DEFAULT_SEED};
+use crate::verify::Verification;
+use crate::{outln, output};
+
+/// Requests per trace, unless `Config::size` says otherwise
+const REQUESTS: usize = 500_000;
+
+/// Entries each cache holds
+const CAPACITY: usize = 1000;
+
+/// Distinct keys in the Zipf, uniform and shifting traces
+const KEYS: usize = 10 * CAPACITY;
+
+/// Keys `0..keys` in order, over and over
+fn looping_scan(requests: usize, keys: usize) -> Vec<u64> {
+    (0..requests).map(|i| (i % keys) as u64).collect()
+}
+
+/// Half the requests go to a hot set of half the cache's capacity, the
+/// other half walk once through `keys` cold keys, like a table scan
+fn hot_set_and_scan(requests: usize, keys: usize, hot: usize, rng: &mut Rng) -> Vec<u64> {
+    let mut scan = (hot..hot + keys).cycle();
+    (0..requests)
+        .map(|_| match rng.below(2) {
+            0 => rng.below(hot) as u64,
+            _ => scan.next().expect("cycle never ends") as u64,
+        })
+        .collect()
+}
+
+/// Key `r` with probability proportional to 1/(r+1): a few keys get most
+/// of the requests, and a long tail gets the rest
+fn zipf(requests: usize, keys: usize, rng: &mut Rng) -> Vec<u64> {
+    let mut cumulative = Vec::with_capacity(keys);
+    let mut total = 0.0;
+    for rank in 1..=keys {
+        total += 1.0 / rank as f64;
+        cumulative.push(total);
+    }
+    (0..requests)
+        .map(|_| {
+            let u = (rng.next_u64() >> 11) as f64 / (1u64 << 53) as f64 * total;
+            cumulative.partition_point(|&c| c < u) as u64
+        })
+        .collect()
+}
+
+fn uniform(requests: usize, keys: usize, rng: &mut Rng) -> Vec<u64> {
+    (0..requests).map(|_| rng.below(keys) as u64).collect()
+}
+
+/// Four phases, each sending 90% of its requests to its own hot set of
+/// half the cache's capacity and the rest anywhere
+fn shifting_hot_set(requests: usize, keys: usize, hot: usize, rng: &mut Rng) -> Vec<u64> {
+    let phase_len = requests.div_ceil(4);
+    (0..requests)
+        .map(|i| {
+            let phase = i / phase_len;
+            match rng.below(10) {
+                0 => rng.below(keys) as u64,
+                _ => (keys + phase * hot + rng.below(hot)) as u64,
+            }
+        })
+        .collect()
+}
+
+/// The policies compared, in table order
+const POLICIES: [&str; 4] = ["LRU", "LFU", "FIFO", "Random"];
+
+/// Hits when `trace` runs against a cache evicting by `policy`: `get`, and
+/// `put` on a miss
+fn hits(trace: &[u64], capacity: usize, policy: impl EvictionPolicy<u64>) -> usize {
+    let mut cache = PolicyCache::new(capacity, policy);
+    let mut hits = 0;
+    for &key in trace {
+        match cache.get(&key) {
+            Some(_) => hits += 1,
+            None => {
+                cache.put(key, ());
+            }
+        }
+    }
+    hits
+}
+
+/// Hits for each of `POLICIES`, all through the same cache body
+fn policy_hits(trace: &[u64], capacity: usize, seed: u64) -> [usize; 4] {
+    [
+        hits(trace, capacity, LruPolicy::default()),
+        hits(trace, capacity, LfuPolicy::default()),
+        hits(trace, capacity, FifoPolicy::default()),
+        hits(trace, capacity, RandomPolicy::new(seed)),
+    ]
+}
+
+/// The traces, named, for `requests` requests against a cache of `capacity`
+fn traces(requests: usize, capacity: usize, rng: &mut Rng) -> Vec<(&'static str, Vec<u64>)> {
+    vec![
+        ("looping scan", looping_scan(requests, capacity + capacity / 4)),
+        ("hot set + scan", hot_set_and_scan(requests, 100 * capacity, capacity / 2, rng)),
+        ("Zipf", zipf(requests, 10 * capacity, rng)),
+        ("uniform", uniform(requests, 10 * capacity, rng)),
+        ("shifting hot set", shifting_hot_set(requests, 10 * capacity, capacity / 2, rng)),
+    ]
+}
+
+fn demonstrate_hit_rates(config: &Config, report: &mut DemoReport) -> Vec<(&'static str, [f64; 4])> {
+    output::heading("🎯 Hit Rates");
+    let requests = config.size.unwrap_or(REQUESTS).max(10_000);
+    outln!("{} requests per trace against {} entries. The loop covers {} keys, the", requests, CAPACITY, CAPACITY + CAPACITY / 4);
+    outln!("scan {}, the others {}; hot sets are {} keys. Every policy plugs into", 100 * CAPACITY, KEYS, CAPACITY / 2);
+    outln!("the same PolicyCache:\n");
+
+    let seed = config.seed.unwrap_or(DEFAULT_SEED);
+    let mut results = Vec::new();
+    let mut header = vec!["trace"];
+    header.extend(POLICIES);
+    header.push("best");
+    let mut table = Table::new(header).left(0).left(5);
+    for (name, trace) in traces(requests, CAPACITY, &mut config.rng()) {
+        let rates = policy_hits(&trace, CAPACITY, seed).map(|hits| hits as f64 / trace.len() as f64);
+        for (policy, rate) in POLICIES.iter().zip(rates) {
+            report.record(format!("{} hit rate, {}", policy, name), 100.0 * rate, "%").param("capacity", CAPACITY);
+        }
+        let best = rates.iter().copied().fold(0.0, f64::max);
+        let winners: Vec<&str> = POLICIES.iter().zip(rates).filter(|&(_, rate)| best - rate < 0.005).map(|(&policy, _)| policy).collect();
+        let mut row = vec![name.to_string()];
+        row.extend(output::rank(&rates, true, |rate| format!("{:.1}%", 100.0 * rate)));
+        row.push(if winners.len() == POLICIES.len() { "any".to_string() } else { winners.join(", ") });
+        table.row(row);
+        results.push((name, rates));
+    }
+    outln!("{}", table);
+    results
+}
+
+fn demonstrate_why(results: &[(&str, [f64; 4])]) {
+    output::heading("🔍 When Each Policy Wins");
+    for &(name, [lru, lfu, _, _]) in results {
+        let (lru, lfu) = (100.0 * lru, 100.0 * lfu);
+        match name {
+            "looping scan" if lfu > lru + 1.0 => {
+                outln!("Looping scan ({:.1}% vs {:.1}%): every key comes back just after LRU", lru, lfu);
+                outln!("evicted it, since the loop is a quarter longer than the cache. LFU");
+                outln!("keeps the keys whose counts got ahead and churns the rest.\n");
+            }
+            "looping scan" => {
+                outln!("Looping scan ({:.1}% vs {:.1}%): every key comes back just after LRU", lru, lfu);
+                outln!("evicted it, since the loop is a quarter longer than the cache. LFU");
+                outln!("does no better: no key is ever hit, so every count stays at one and");
+                outln!("LFU falls back on its tie-break, which is recency. Evicting the newest");
+                outln!("of the tied entries instead would keep {} of the {} keys cached.\n", CAPACITY - 1, CAPACITY + CAPACITY / 4);
+            }
+            "hot set + scan" => {
+                outln!("Hot set + scan ({:.1}% vs {:.1}%): each scanned key is used once, but", lru, lfu);
+                outln!("to LRU it is the most recent thing there is, and the scan pushes hot");
+                outln!("keys out between their reads. To LFU a scanned key is a count of one,");
+                outln!("the first thing to go. Databases run scans like this all the time,");
+                outln!("which is why their buffer pools are not plain LRU.\n");
+            }
+            "Zipf" => {
+                outln!("Zipf ({:.1}% vs {:.1}%): the popular keys are popular all the time,", lru, lfu);
+                outln!("so a count is a better guide than the last use, which a burst of");
+                outln!("tail keys can push out of an LRU cache.\n");
+            }
+            "uniform" => {
+                outln!("Uniform ({:.1}% vs {:.1}%): every key is equally likely, so any {} of", lru, lfu, CAPACITY);
+                outln!("the {} hit about {:.0}% of the time and no policy can do better.\n", KEYS, 100.0 * CAPACITY as f64 / KEYS as f64);
+            }
+            _ => {
+                outln!("Shifting hot set ({:.1}% vs {:.1}%): when the hot set moves, LFU keeps", lru, lfu);
+                outln!("the old one, whose counts the new keys need a long time to match.");
+                outln!("LRU forgets it within a cache's worth of requests. Real LFU caches");
+                outln!("age their counts (halving them now and then) for exactly this reason.\n");
+            }
+        }
+    }
+
+    let [_, _, loop_fifo, loop_random] = results[0].1;
+    let (zipf_name, [zipf_lru, _, zipf_fifo, zipf_random]) = results[2];
+    outln!("FIFO and Random, the baselines: neither does any work on a hit. FIFO");
+    outln!("evicts in arrival order, so the loop beats it too ({:.1}%). Random has", 100.0 * loop_fifo);
+    outln!("no worst case to aim a trace at and keeps {:.1}% of the loop's requests", 100.0 * loop_random);
+    outln!("hitting, but on {} it trails LRU ({:.1}% and {:.1}% against {:.1}%):", zipf_name, 100.0 * zipf_fifo, 100.0 * zipf_random, 100.0 * zipf_lru);
+    outln!("a hit is the only evidence of popularity, and both throw it away.\n");
+}
+
+/// The loop defeats LRU, LFU and FIFO but not Random, a scan and Zipf
+/// favour LFU, Zipf leaves the baselines behind LRU, and a moving hot set
+/// favours LRU
+fn verify() -> Verification {
+    let mut verification = Verification::new();
+    let capacity = 100;
+    let requests = 50_000;
+    let results: Vec<[usize; 4]> = traces(requests, capacity, &mut Rng::new(1))
+        .into_iter()
+        .map(|(_, trace)| policy_hits(&trace, capacity, 1))
+        .collect();
+    let [loop_lru, loop_lfu, loop_fifo, loop_random] = results[0];
+    verification
+        .equal("LRU never hits on a loop longer than the cache", loop_lru, 0)
+        .equal("LFU, breaking ties by recency, never hits on it either", loop_lfu, 0)
+        .equal("nor does FIFO", loop_fifo, 0)
+        .check("Random does hit on the loop", loop_random > requests / 10,
+               || format!("{} hits of {}", loop_random, requests));
+    let [scan_lru, scan_lfu, ..] = results[1];
+    verification.check("a scan costs LFU far fewer hot-set hits than LRU", scan_lfu > scan_lru + requests / 10,
+                       || format!("LFU {} vs LRU {}", scan_lfu, scan_lru));
+    let [zipf_lru, zipf_lfu, zipf_fifo, zipf_random] = results[2];
+    verification
+        .check("LFU hits more often than LRU on Zipf keys", zipf_lfu > zipf_lru,
+               || format!("LFU {} vs LRU {}", zipf_lfu, zipf_lru))
+        .check("FIFO and Random trail LRU on Zipf keys", zipf_fifo.max(zipf_random) < zipf_lru,
+               || format!("FIFO {}, Random {} vs LRU {}", zipf_fifo, zipf_random, zipf_lru));
+    let [shift_lru, shift_lfu, ..] = results[4];
+    verification.check("LRU hits more often than LFU when the hot set moves", shift_lru > shift_lfu,
+                       || format!("LRU {} vs LFU {}", shift_lru, shift_lfu));
+    verification
+}
+
+const QUIZ: &[Question] = &[
+    Question::new(
+        "Why does LRU get no hits at all on a loop slightly longer than the cache?",
+        &[
+            "Each key is evicted just before it comes around again",
+            "LRU can't store integer keys",
+            "The loop keys collide in the hash map",
+        ],
+        0,
+        "The least recently used key is always the next one the loop asks for.",
+    ),
+    Question::new(
+        "What does LFU get wrong when popularity changes?",
+        &[
+            "Nothing: counts adapt immediately",
+            "Old favourites keep high counts and crowd out the new hot keys",
+            "It evicts the most frequently used key",
+        ],
+        1,
+        "Hence aging: periodically halving every count so old popularity fades.",
+    ),
+    Question::new(
+        "How does the O(1) LFU find its victim without scanning?",
+        &[
+            "It keeps a binary heap of counts",
+            "It keeps one list per use count and tracks the lowest count in use",
+            "It samples five random entries",
+        ],
+        1,
+        "A hit moves an entry to the next count's list; the victim is the tail of the lowest list.",
+    ),
+];
+
+#[derive(Demo)]
+#[demo(name = "lru-vs-lfu-demo", description = "LRU, an O(1) LFU, FIFO and Random as pluggable policies on looping, scanning, Zipf, uniform and shifting traces",
+       quiz = QUIZ, verify = verify)]
+pub struct LruVsLfuDemo;
+
+impl LruVsLfuDemo {
+    fn demonstrate(&self, config: &Config) -> DemoReport {
+        output::title("⚖️  LRU vs LFU Demo");
+        outln!("Recency or frequency: which past predicts the next request?\n");
+
+        let mut report = DemoReport::default();
+        let results = demonstrate_hit_rates(config, &mut report);
+        demonstrate_why(&results);
+
+        output::takeaways();
+        outln!("• LRU bets on recency, LFU on frequency; neither wins on every trace");
+        outln!("• A loop just larger than the cache gets zero hits from both");
+        outln!("• A one-time scan flushes an LRU cache; LFU shrugs it off");
+        outln!("• Stable skewed popularity (Zipf) favours counting");
+        outln!("• Popularity that moves favours recency, unless LFU ages its counts");
+        outln!("• Per-count lists make LFU O(1), the same as LRU");
+        outln!("• FIFO and Random ignore hits; Random alone survives the loop");
+        report
+    }
+}
@@ -0,0 +1,246 @@
+//! Macro Code Generation Demo
+//!
+//! Macros write code at compile time. A declarative `macro_rules!` macro
+//! generates benchmark boilerplate for the `bench` harness, and a derive
+//! proc macro (the `demo-derive` sub-crate) implements the `Demo` trait.
+//! Each is shown before and after expansion, like `cargo expand` prints it.
+//! Run with: cargo run --release --bin macro-demo
+
+use std::hint::black_box;
+
+use crate::bench_compare;
+use crate::demo::{Config, Demo, DemoReport};
+use crate::quiz::Question;
+use crate::{outln, output};
+
+const N: u64 = 1_000_000;
+
+fn sum_loop(data: &[u64]) -> u64 {
+    let mut total = 0;
+    for i in 0..data.len() {
+        total += data[i];
+    }
+    total
+}
+
+fn sum_iter(data: &[u64]) -> u64 {
+    data.iter().sum()
+}
+
+fn sum_fold(data: &[u64]) -> u64 {
+    data.iter().fold(0, |acc: u64, &x| acc.wrapping_add(x))
+}
+
+/// Print source text indented, with a title line
+fn show(title: &str, code: &str) {
+    outln!("  {}", title);
+    for line in code.lines() {
+        outln!("  │ {}", line);
+    }
+}
+
+/// Lay out the single line `TokenStream::to_string()` produces: one item per
+/// line, indented by brace depth, with the spacing around `::`, `&` and
+/// parentheses tightened up
+fn pretty_tokens(tokens: &str) -> String {
+    let tokens = tokens
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace(" :: ", "::")
+        .replace("impl::", "impl ::")
+        .replace("& self", "&self")
+        .replace("& '", "&'")
+        .replace(" (", "(")
+        .replace(" )", ")");
+    let mut out = String::new();
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut line_start = true;
+    for c in tokens.chars() {
+        if line_start && c == ' ' {
+            continue;
+        }
+        line_start = false;
+        match c {
+            '"' => {
+                in_string = !in_string;
+                out.push(c);
+            }
+            '{' if !in_string => {
+                depth += 1;
+                out.push('{');
+                out.push('\n');
+                out.push_str(&"    ".repeat(depth));
+                line_start = true;
+            }
+            '}' if !in_string => {
+                depth = depth.saturating_sub(1);
+                let trimmed = out.trim_end().len();
+                out.truncate(trimmed);
+                out.push('\n');
+                out.push_str(&"    ".repeat(depth));
+                out.push('}');
+                out.push('\n');
+                out.push_str(&"    ".repeat(depth));
+                line_start = true;
+            }
+            _ => out.push(c),
+        }
+    }
+    out.lines().filter(|line| !line.trim().is_empty()).collect::<Vec<_>>().join("\n")
+}
+
+fn demonstrate_declarative_macro(report: &mut DemoReport) {
+    output::heading("📝 Declarative Macros: macro_rules!");
+    outln!("`bench_compare!` (in systems_demos::bench) matches a list of");
+    outln!("`\"label\" => expression` pairs and repeats a template for each one.\n");
+
+    show("Before (what the demo writes):", r#"let results = bench_compare!(runs: 5;
+    "index loop" => sum_loop(&data),
+    "iterator" => sum_iter(&data),
+    "fold" => sum_fold(&data),
+);"#);
+    outln!();
+    show("After (what `cargo expand` shows the compiler gets):", r#"let results = {
+    let results: ::std::vec::Vec<(&'static str, ::systems_demos::bench::Measurement)> =
+        ::std::vec![
+            ("index loop", ::systems_demos::bench::measure(5, || sum_loop(&data))),
+            ("iterator", ::systems_demos::bench::measure(5, || sum_iter(&data))),
+            ("fold", ::systems_demos::bench::measure(5, || sum_fold(&data))),
+        ];
+    ::systems_demos::bench::print_comparison(&results);
+    results
+};"#);
+    outln!("`$crate` became `::systems_demos`, so the macro works from any crate.\n");
+
+    outln!("Running it ({} elements):", N);
+    let data: Vec<u64> = (0..N).collect();
+    let results = bench_compare!(runs: 5;
+        "index loop" => sum_loop(black_box(&data)),
+        "iterator" => sum_iter(black_box(&data)),
+        "fold" => sum_fold(black_box(&data)),
+    );
+    report.record_comparison(&results);
+    outln!("One invocation, {} measurements: the repetition `$(...),+` wrote the rest.\n", results.len());
+}
+
+macro_rules! double_it {
+    ($value:expr) => {{
+        let x = $value; // this `x` is the macro's own, invisible to the caller
+        x * 2
+    }};
+}
+
+fn demonstrate_hygiene() {
+    output::heading("🧼 Hygiene");
+    let x = 10;
+    let doubled = double_it!(x + 1);
+    outln!("  let x = 10;  double_it!(x + 1) = {}  and x is still {}", doubled, x);
+    outln!("The macro declares its own `let x`, yet the caller's `x` is untouched:");
+    outln!("identifiers from a macro body live in their own syntax context. Unlike C");
+    outln!("preprocessor macros, macro_rules! substitutes syntax trees, not text.\n");
+}
+
+/// Generated impl: name "greeter", description from the attribute
+#[derive(Demo)]
+#[demo(name = "greeter", description = "Says hello from a derived Demo impl")]
+struct Greeter;
+
+impl Greeter {
+    fn demonstrate(&self, _config: &Config) -> DemoReport {
+        outln!("    hello from Greeter::demonstrate");
+        DemoReport::default()
+    }
+}
+
+/// No name given: the derive turns `CountdownDemo` into "countdown-demo"
+#[derive(Demo)]
+#[demo(description = "Counts down, with the name derived from the type")]
+struct CountdownDemo {
+    from: u32,
+}
+
+impl CountdownDemo {
+    fn demonstrate(&self, _config: &Config) -> DemoReport {
+        let steps: Vec<String> = (1..=self.from).rev().map(|n| n.to_string()).collect();
+        outln!("    {} ... liftoff", steps.join(" "));
+        DemoReport::default()
+    }
+}
+
+fn demonstrate_derive_macro() {
+    output::heading("🏷️  Procedural Macros: #[derive(Demo)]");
+    outln!("A derive is a Rust function (in its own proc-macro crate) that receives");
+    outln!("the struct's tokens and returns new ones. Here it implements the crate's");
+    outln!("`Demo` trait from a `#[demo(...)]` attribute.\n");
+
+    show("Before:", r#"#[derive(Demo)]
+#[demo(name = "greeter", description = "Says hello from a derived Demo impl")]
+struct Greeter;"#);
+    outln!();
+    // DEMO_EXPANSION is the derive's real output, stringified by the macro itself
+    show("After (the derive's actual output):", &pretty_tokens(Greeter::DEMO_EXPANSION));
+    outln!();
+
+    let demos: Vec<Box<dyn Demo>> = vec![Box::new(Greeter), Box::new(CountdownDemo { from: 3 })];
+    outln!("Running them through `dyn Demo`:");
+    for demo in &demos {
+        outln!("  {} - {}", demo.name(), demo.description());
+        demo.run(&Config::default());
+    }
+    outln!();
+}
+
+const QUIZ: &[Question] = &[
+    Question::new(
+        "When is a macro_rules! macro expanded?",
+        &["At run time, on each call", "At compile time, before type checking", "At link time"],
+        1,
+        "Expansion happens on tokens, so a macro costs nothing at run time.",
+    ),
+    Question::new(
+        "What does macro hygiene guarantee?",
+        &[
+            "A macro can't allocate",
+            "Local names a macro introduces can't clash with or capture the caller's variables",
+            "A macro always expands to one expression",
+        ],
+        1,
+        "Each identifier remembers where it was written, so a macro's temporary `x` never shadows yours.",
+    ),
+    Question::new(
+        "Where does a procedural macro like #[derive(Demo)] have to live?",
+        &[
+            "In a crate of its own, with proc-macro = true",
+            "In any module of the crate that uses it",
+            "In build.rs",
+        ],
+        0,
+        "Proc macros are compiled for the compiler to run, so they're built as a separate kind of crate.",
+    ),
+];
+
+#[derive(Demo)]
+#[demo(name = "macro-demo", description = "macro_rules! and a derive macro generating code",
+       quiz = QUIZ)]
+pub struct MacroDemo;
+
+impl MacroDemo {
+    fn demonstrate(&self, _config: &Config) -> DemoReport {
+        output::title("🪄 Macro Code Generation");
+        outln!("Code that writes code, before and after expansion.\n");
+
+        let mut report = DemoReport::default();
+        demonstrate_declarative_macro(&mut report);
+        demonstrate_hygiene();
+        demonstrate_derive_macro();
+
+        output::takeaways();
+        outln!("• macro_rules! pattern-matches tokens and repeats templates: no runtime cost");
+        outln!("• Macros are hygienic: their local names can't capture the caller's");
+        outln!("• Proc macros are compiler plugins written in Rust, in their own crate");
+        outln!("• Derives remove boilerplate; `cargo expand` shows what they generate");
+        report
+    }
+}
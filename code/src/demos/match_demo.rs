@@ -0,0 +1,310 @@
+//! Pattern Match Compilation Demo
+//!
+//! How LLVM lowers a `match`: dense integer (or enum) arms become a jump
+//! table, one indexed load and one indirect jump; sparse arms become a
+//! binary tree of comparisons. Both are compared against dispatching through
+//! a `HashMap` of function pointers, with assembly excerpts and timings.
+//! Run with: cargo run --release --bin match-demo
+
+use std::collections::HashMap;
+use std::hint::black_box;
+
+use crate::bench::measure;
+use crate::bench_compare;
+use crate::demo::{Config, Demo, DemoReport};
+use crate::quiz::Question;
+use crate::rng::Rng;
+use crate::verify::Verification;
+use crate::{outln, output};
+
+/// Dispatches timed per pattern, unless the config sets `size`
+const STEPS: usize = 5_000_000;
+
+/// 16 operations, numbered 0..=15 with no gaps
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Op {
+    Add, Sub, Mul, Xor, And, Or, Shl, Shr,
+    Rotl, Rotr, Min, Max, Avg, Neg, Not, Swap,
+}
+
+impl Op {
+    const ALL: [Op; 16] = [
+        Op::Add, Op::Sub, Op::Mul, Op::Xor, Op::And, Op::Or, Op::Shl, Op::Shr,
+        Op::Rotl, Op::Rotr, Op::Min, Op::Max, Op::Avg, Op::Neg, Op::Not, Op::Swap,
+    ];
+}
+
+/// Scattered opcodes: same 16 operations, keys far apart
+const SPARSE_KEYS: [u32; 16] = [
+    3, 12, 97, 500, 1_000, 4_096, 31_337, 65_535,
+    100_000, 250_000, 1_048_576, 7_777_777, 9_999_999, 0x0BAD_F00D, 0x1234_5678, 0x7FFF_0000,
+];
+
+/// Exhaustive over the enum: every u8 the function can receive is a valid
+/// arm, so the jump table needs no range check
+#[unsafe(no_mangle)]
+#[inline(never)]
+pub fn dispatch_enum(op: Op, a: u64, b: u64) -> u64 {
+    match op {
+        Op::Add => a.wrapping_add(b),
+        Op::Sub => a.wrapping_sub(b),
+        Op::Mul => a.wrapping_mul(b),
+        Op::Xor => a ^ b,
+        Op::And => a & b,
+        Op::Or => a | b,
+        Op::Shl => a << (b & 63),
+        Op::Shr => a >> (b & 63),
+        Op::Rotl => a.rotate_left(b as u32),
+        Op::Rotr => a.rotate_right(b as u32),
+        Op::Min => a.min(b),
+        Op::Max => a.max(b),
+        Op::Avg => (a & b) + ((a ^ b) >> 1),
+        Op::Neg => a.wrapping_neg() ^ b,
+        Op::Not => !a ^ b,
+        Op::Swap => a.swap_bytes() ^ b,
+    }
+}
+
+/// The same arms on a raw u8: 240 values have no arm, so `_` is required
+/// and the jump table is guarded by a range check
+#[unsafe(no_mangle)]
+#[inline(never)]
+pub fn dispatch_u8(op: u8, a: u64, b: u64) -> u64 {
+    match op {
+        0 => a.wrapping_add(b),
+        1 => a.wrapping_sub(b),
+        2 => a.wrapping_mul(b),
+        3 => a ^ b,
+        4 => a & b,
+        5 => a | b,
+        6 => a << (b & 63),
+        7 => a >> (b & 63),
+        8 => a.rotate_left(b as u32),
+        9 => a.rotate_right(b as u32),
+        10 => a.min(b),
+        11 => a.max(b),
+        12 => (a & b) + ((a ^ b) >> 1),
+        13 => a.wrapping_neg() ^ b,
+        14 => !a ^ b,
+        15 => a.swap_bytes() ^ b,
+        _ => 0,
+    }
+}
+
+/// Too spread out for a table: compiled to a tree of comparisons
+#[unsafe(no_mangle)]
+#[inline(never)]
+pub fn dispatch_sparse(op: u32, a: u64, b: u64) -> u64 {
+    match op {
+        3 => a.wrapping_add(b),
+        12 => a.wrapping_sub(b),
+        97 => a.wrapping_mul(b),
+        500 => a ^ b,
+        1_000 => a & b,
+        4_096 => a | b,
+        31_337 => a << (b & 63),
+        65_535 => a >> (b & 63),
+        100_000 => a.rotate_left(b as u32),
+        250_000 => a.rotate_right(b as u32),
+        1_048_576 => a.min(b),
+        7_777_777 => a.max(b),
+        9_999_999 => (a & b) + ((a ^ b) >> 1),
+        0x0BAD_F00D => a.wrapping_neg() ^ b,
+        0x1234_5678 => !a ^ b,
+        0x7FFF_0000 => a.swap_bytes() ^ b,
+        _ => 0,
+    }
+}
+
+type Handler = fn(u64, u64) -> u64;
+
+/// The same operations as a runtime table: key -> function pointer
+fn handler_map() -> HashMap<u32, Handler> {
+    let handlers: [Handler; 16] = [
+        |a, b| a.wrapping_add(b),
+        |a, b| a.wrapping_sub(b),
+        |a, b| a.wrapping_mul(b),
+        |a, b| a ^ b,
+        |a, b| a & b,
+        |a, b| a | b,
+        |a, b| a << (b & 63),
+        |a, b| a >> (b & 63),
+        |a, b| a.rotate_left(b as u32),
+        |a, b| a.rotate_right(b as u32),
+        |a, b| a.min(b),
+        |a, b| a.max(b),
+        |a, b| (a & b) + ((a ^ b) >> 1),
+        |a, b| a.wrapping_neg() ^ b,
+        |a, b| !a ^ b,
+        |a, b| a.swap_bytes() ^ b,
+    ];
+    SPARSE_KEYS.into_iter().zip(handlers).collect()
+}
+
+/// The second operand for each step, derived from the running value
+fn operand(acc: u64) -> u64 {
+    acc.rotate_left(17) ^ 0x9E37_79B9
+}
+
+fn run_enum(ops: &[Op]) -> u64 {
+    ops.iter().fold(1, |acc, &op| dispatch_enum(op, acc, operand(acc)))
+}
+
+fn run_sparse(keys: &[u32]) -> u64 {
+    keys.iter().fold(1, |acc, &key| dispatch_sparse(key, acc, operand(acc)))
+}
+
+fn run_map(map: &HashMap<u32, Handler>, keys: &[u32]) -> u64 {
+    keys.iter().fold(1, |acc, key| map[key](acc, operand(acc)))
+}
+
+/// Print the start of `symbol`'s machine code, up to and including the
+/// first instruction matching `stop` (or `limit` lines)
+#[cfg(unix)]
+fn show_excerpt(symbol: &str, limit: usize, stop: impl Fn(&str) -> bool) -> Option<crate::asm::Disassembly> {
+    let disassembly = match crate::asm::Disassembly::of(symbol) {
+        Ok(disassembly) => disassembly,
+        Err(err) => {
+            outln!("⚠️  Can't disassemble: {}", err);
+            return None;
+        }
+    };
+    outln!("  {}:", symbol);
+    for line in disassembly.normalized().iter().take(limit) {
+        outln!("    {}", line);
+        if stop(line) {
+            break;
+        }
+    }
+    Some(disassembly)
+}
+
+#[cfg(unix)]
+fn demonstrate_assembly() {
+    output::heading("🔬 Jump Table vs Comparison Tree");
+    assert_eq!(dispatch_enum(Op::Mul, 6, 7), dispatch_u8(Op::Mul as u8, 6, 7));
+    assert_eq!(dispatch_sparse(SPARSE_KEYS[2], 6, 7), 42);
+
+    let indirect_jump = |line: &str| line.starts_with("jmp r") || line.starts_with("notrack jmp");
+    show_excerpt("dispatch_enum", 10, indirect_jump);
+    show_excerpt("dispatch_u8", 10, indirect_jump);
+    outln!("Dense arms 0..=15: look up the arm's offset in a table indexed by op,");
+    outln!("then jump straight there - the same cost for any of the 16 arms. The enum");
+    outln!("match is exhaustive, so every possible op is a table index: no check.");
+    outln!("On a u8, values 16..=255 must reach the `_` arm, which costs the");
+    outln!("`cmp`/`ja` pair in front.\n");
+
+    if let Some(sparse) = show_excerpt("dispatch_sparse", 9, |_| false) {
+        let compares = sparse.instructions.iter().filter(|i| i.mnemonic() == "cmp").count();
+        outln!("    ... {} cmp instructions in total for 16 keys", compares);
+    }
+    outln!("Keys from 3 to 0x7FFF0000 would need a 2-billion-entry table, so LLVM");
+    outln!("sorts them into a binary search: about log2(16) = 4 compares per lookup.\n");
+}
+
+fn random_indices(count: usize, rng: &mut Rng) -> Vec<usize> {
+    (0..count).map(|_| rng.below(16)).collect()
+}
+
+fn demonstrate_timing(steps: usize, config: &Config, report: &mut DemoReport) {
+    let title = format!("⏱️  Dispatching {} Operations", steps);
+    output::heading(&title);
+    let map = handler_map();
+
+    for (stream, pattern, indices) in [
+        ("random", "random operations (unpredictable branches)", random_indices(steps, &mut config.rng())),
+        ("repeated", "one operation repeated (perfectly predictable)", vec![5; steps]),
+    ] {
+        let ops: Vec<Op> = indices.iter().map(|&i| Op::ALL[i]).collect();
+        let keys: Vec<u32> = indices.iter().map(|&i| SPARSE_KEYS[i]).collect();
+        assert_eq!(run_enum(&ops), run_sparse(&keys));
+        assert_eq!(run_enum(&ops), run_map(&map, &keys));
+
+        outln!("{}:", pattern);
+        let results = bench_compare!(runs: 5;
+            "dense match (jump table)" => run_enum(black_box(&ops)),
+            "sparse match (compare tree)" => run_sparse(black_box(&keys)),
+            "HashMap<u32, fn>" => run_map(black_box(&map), black_box(&keys)),
+        );
+        for (label, measurement) in &results {
+            report.record_time(*label, measurement.median()).param("ops", stream).param("steps", steps);
+        }
+        outln!();
+    }
+    outln!("With random operations every strategy pays for mispredicted branches: the");
+    outln!("jump table's one indirect jump, or the tree's several conditional ones.");
+    outln!("When the operation repeats, the predictor learns it and both matches get");
+    outln!("several times cheaper. The HashMap hashes every key (SipHash by default), probes a");
+    outln!("bucket and makes an indirect call it can never inline: a match is the");
+    outln!("right tool whenever the cases are known at compile time.\n");
+}
+
+/// The three dispatchers compute the same thing, and the jump table gets
+/// cheaper once the predictor can learn the next operation
+fn verify() -> Verification {
+    let mut verification = Verification::new();
+    let steps = 200_000;
+    let indices = random_indices(steps, &mut Config::default().rng());
+    let ops: Vec<Op> = indices.iter().map(|&i| Op::ALL[i]).collect();
+    let keys: Vec<u32> = indices.iter().map(|&i| SPARSE_KEYS[i]).collect();
+    verification
+        .equal("the dense and sparse matches agree", run_enum(&ops), run_sparse(&keys))
+        .equal("the match and the HashMap agree", run_enum(&ops), run_map(&handler_map(), &keys));
+
+    let repeated = vec![Op::ALL[5]; steps];
+    let unpredictable = measure(5, || run_enum(black_box(&ops)));
+    let predictable = measure(5, || run_enum(black_box(&repeated)));
+    verification.faster("a repeated operation dispatches faster than random ones", &predictable, &unpredictable, 1.2);
+    verification
+}
+
+const QUIZ: &[Question] = &[
+    Question::new(
+        "How does a match over dense integer arms (0, 1, 2, ...) compile?",
+        &[
+            "A jump table: one bounds check, then an indexed jump",
+            "A chain of if/else comparisons",
+            "A hash map lookup",
+        ],
+        0,
+        "Dispatch is O(1) regardless of the number of arms.",
+    ),
+    Question::new(
+        "Why was the HashMap<u32, fn> dispatch the slowest?",
+        &[
+            "HashMaps are always slow",
+            "It hashes every key (SipHash), probes the table, then makes an indirect call",
+            "It allocates per lookup",
+        ],
+        1,
+        "A match knows the cases at compile time; the map has to discover them at run time.",
+    ),
+];
+
+#[derive(Demo)]
+#[demo(name = "match-demo", description = "Jump tables, comparison trees and HashMap dispatch",
+       quiz = QUIZ, verify = verify)]
+pub struct MatchDemo;
+
+impl MatchDemo {
+    fn demonstrate(&self, config: &Config) -> DemoReport {
+        output::title("🔀 Pattern Match Compilation Demo");
+        outln!("What the compiler turns a match into.\n");
+
+        #[cfg(unix)]
+        demonstrate_assembly();
+        let mut report = DemoReport::default();
+        demonstrate_timing(config.size.unwrap_or(STEPS), config, &mut report);
+
+        if cfg!(debug_assertions) {
+            outln!("⚠️  This is a debug build: rerun with --release for the real code and timings.\n");
+        }
+        output::takeaways();
+        outln!("• Dense integer or enum arms compile to a jump table: O(1) dispatch");
+        outln!("• Exhaustive enum matches skip the range check a raw integer needs");
+        outln!("• Sparse arms become a binary tree of comparisons: O(log n)");
+        outln!("• Both beat a HashMap of function pointers when the cases are fixed");
+        report
+    }
+}
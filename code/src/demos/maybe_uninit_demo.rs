@@ -0,0 +1,292 @@
+//! MaybeUninit Demo
+//!
+//! Working with memory that hasn't been initialized yet, soundly: arrays
+//! filled element by element, out-parameters written by C, and buffers whose
+//! contents come from somewhere else, plus what it costs to zero them first.
+//! Run with: cargo run --release --bin maybe-uninit-demo
+
+use std::cell::Cell;
+use std::hint::black_box;
+use std::mem::{self, MaybeUninit};
+use std::ptr;
+
+use crate::bench::measure;
+use crate::demo::{Config, Demo, DemoReport};
+use crate::output::Table;
+use crate::quiz::Question;
+use crate::{out, outln, output};
+
+const BUFFER_SIZE: usize = 64 * 1024 * 1024;
+
+fn demonstrate_the_problem() {
+    output::heading("☠️  Why Not Just Skip Initialization?");
+    outln!("The old way, now deprecated:");
+    outln!("  let buffer: [u8; 4096] = unsafe {{ mem::uninitialized() }};");
+    outln!("That is undefined behavior the moment it runs, even for u8: uninitialized");
+    outln!("memory is not \"some arbitrary byte\" to the compiler, and LLVM may assume");
+    outln!("any value for it, differently at each use. For types with invalid bit");
+    outln!("patterns it's worse: an uninitialized bool, &T or enum is instant UB.\n");
+
+    outln!("mem::zeroed() is only sound when all-zero bytes are a valid value:");
+    for (ty, ok) in [
+        ("u64, f64, [u8; N]", true),
+        ("*const T, Option<&T>, Option<Box<T>>", true),
+        ("&T, Box<T>, NonZeroU32, fn()", false),
+        ("String, Vec<T> (hold a non-null pointer)", false),
+    ] {
+        outln!("  {} {}", if ok { "✅" } else { "❌" }, ty);
+    }
+    outln!();
+    outln!("MaybeUninit<T> is the fix: a T-sized slot the compiler treats as possibly");
+    outln!("uninitialized. You write it, then promise with `assume_init` that it's");
+    outln!("done. Same layout as T: MaybeUninit<String> = {} bytes, String = {} bytes.\n",
+             mem::size_of::<MaybeUninit<String>>(), mem::size_of::<String>());
+}
+
+thread_local! {
+    static LIVE: Cell<i32> = const { Cell::new(0) };
+}
+
+/// Counts live instances, so leaks and double drops are visible
+struct Tracked(String);
+
+impl Tracked {
+    fn new(text: String) -> Self {
+        LIVE.with(|live| live.set(live.get() + 1));
+        Tracked(text)
+    }
+}
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        LIVE.with(|live| live.set(live.get() - 1));
+    }
+}
+
+/// Build an array element by element, dropping the finished prefix if
+/// `make` panics partway through (what `std::array::from_fn` does for you)
+fn init_array<const N: usize>(mut make: impl FnMut(usize) -> Tracked) -> [Tracked; N] {
+    /// Owns the initialized prefix until the array is complete
+    struct PartialArray<'a, const N: usize> {
+        slots: &'a mut [MaybeUninit<Tracked>; N],
+        initialized: usize,
+    }
+
+    impl<const N: usize> Drop for PartialArray<'_, N> {
+        fn drop(&mut self) {
+            for slot in &mut self.slots[..self.initialized] {
+                // SAFETY: exactly the first `initialized` slots were written
+                unsafe { slot.assume_init_drop() };
+            }
+        }
+    }
+
+    let mut slots: [MaybeUninit<Tracked>; N] = [const { MaybeUninit::uninit() }; N];
+    let mut guard = PartialArray { slots: &mut slots, initialized: 0 };
+    for i in 0..N {
+        guard.slots[i].write(make(i));
+        guard.initialized += 1;
+    }
+    mem::forget(guard); // complete: ownership passes to the array below
+    // SAFETY: all N slots are initialized, and MaybeUninit<T> has T's layout
+    unsafe { ptr::read(&slots as *const [MaybeUninit<Tracked>; N] as *const [Tracked; N]) }
+}
+
+fn demonstrate_arrays() {
+    output::heading("🧩 Initializing an Array Element by Element");
+
+    let names: [Tracked; 4] = init_array(|i| Tracked::new(format!("item-{}", i)));
+    outln!("  init_array::<4>: {:?}, live instances: {}",
+             names.iter().map(|t| t.0.as_str()).collect::<Vec<_>>(), LIVE.with(Cell::get));
+    drop(names);
+    outln!("  after drop, live instances: {}", LIVE.with(Cell::get));
+
+    let previous_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(|_| {})); // keep the expected panic quiet
+    let result = std::panic::catch_unwind(|| {
+        init_array::<6>(|i| {
+            if i == 3 {
+                panic!("initializer failed at element 3");
+            }
+            Tracked::new(format!("item-{}", i))
+        })
+    });
+    std::panic::set_hook(previous_hook);
+    outln!("  initializer panicking at element 3: {}, live instances afterwards: {}",
+             if result.is_err() { "panicked" } else { "completed" }, LIVE.with(Cell::get));
+    outln!("The guard dropped exactly the 3 finished elements: no leak, and no drop of");
+    outln!("the uninitialized ones (which would free garbage pointers). In real code");
+    outln!("std::array::from_fn does all of this safely:");
+    let squares: [u64; 8] = std::array::from_fn(|i| (i * i) as u64);
+    outln!("  std::array::from_fn(|i| i * i) = {:?}\n", squares);
+}
+
+#[cfg(unix)]
+fn demonstrate_out_parameters() {
+    output::heading("📤 FFI Out-Parameters");
+    outln!("C functions often fill a struct the caller provides. MaybeUninit passes");
+    outln!("the memory without pretending it's initialized first:\n");
+
+    let mut name = MaybeUninit::<libc::utsname>::uninit();
+    // SAFETY: uname writes a complete utsname on success
+    let status = unsafe { libc::uname(name.as_mut_ptr()) };
+    if status == 0 {
+        // SAFETY: status 0 means the struct was filled in
+        let name = unsafe { name.assume_init() };
+        let field = |raw: &[libc::c_char]| {
+            // SAFETY: uname's fields are NUL-terminated strings
+            unsafe { std::ffi::CStr::from_ptr(raw.as_ptr()) }.to_string_lossy().into_owned()
+        };
+        outln!("  uname(&mut MaybeUninit<utsname>) -> {} {} ({})",
+                 field(&name.sysname), field(&name.release), field(&name.machine));
+    } else {
+        outln!("  uname failed; the struct stays uninitialized and is never read");
+    }
+
+    let mut usage = MaybeUninit::<libc::rusage>::uninit();
+    // SAFETY: getrusage fills the whole struct on success
+    if unsafe { libc::getrusage(libc::RUSAGE_SELF, usage.as_mut_ptr()) } == 0 {
+        // SAFETY: a zero return means the struct was filled in
+        let usage = unsafe { usage.assume_init() };
+        outln!("  getrusage(&mut MaybeUninit<rusage>) -> max RSS {} KiB", usage.ru_maxrss);
+    }
+    outln!("assume_init only after checking the return code: on failure C may have");
+    outln!("written nothing at all.\n");
+}
+
+#[cfg(windows)]
+fn demonstrate_out_parameters() {
+    use windows_sys::Win32::System::ProcessStatus::{GetProcessMemoryInfo, PROCESS_MEMORY_COUNTERS};
+    use windows_sys::Win32::System::SystemInformation::{GetNativeSystemInfo, SYSTEM_INFO};
+    use windows_sys::Win32::System::Threading::GetCurrentProcess;
+
+    output::heading("📤 FFI Out-Parameters");
+    outln!("Win32 functions often fill a struct the caller provides. MaybeUninit");
+    outln!("passes the memory without pretending it's initialized first:\n");
+
+    let mut info = MaybeUninit::<SYSTEM_INFO>::uninit();
+    // SAFETY: GetNativeSystemInfo always writes a complete SYSTEM_INFO
+    unsafe { GetNativeSystemInfo(info.as_mut_ptr()) };
+    // SAFETY: it has no failure case, so the struct is filled in
+    let info = unsafe { info.assume_init() };
+    outln!("  GetNativeSystemInfo(&mut MaybeUninit<SYSTEM_INFO>) -> {} processors, {} KiB pages",
+             info.dwNumberOfProcessors, info.dwPageSize / 1024);
+
+    let mut counters = MaybeUninit::<PROCESS_MEMORY_COUNTERS>::uninit();
+    let size = mem::size_of::<PROCESS_MEMORY_COUNTERS>() as u32;
+    // SAFETY: the buffer is `size` writable bytes; the pseudo-handle is always valid
+    if unsafe { GetProcessMemoryInfo(GetCurrentProcess(), counters.as_mut_ptr(), size) } != 0 {
+        // SAFETY: a nonzero return means the struct was filled in
+        let counters = unsafe { counters.assume_init() };
+        outln!("  GetProcessMemoryInfo(&mut MaybeUninit<PROCESS_MEMORY_COUNTERS>) -> peak working set {} KiB",
+                 counters.PeakWorkingSetSize / 1024);
+    }
+    outln!("assume_init only after checking the return code: on failure Windows may");
+    outln!("have written nothing at all.\n");
+}
+
+/// Fill a byte slice with a pattern, standing in for "data arrives from I/O"
+fn fill_pattern(buffer: &mut [u8]) {
+    for (i, byte) in buffer.iter_mut().enumerate() {
+        *byte = (i as u8).wrapping_mul(31);
+    }
+}
+
+/// Zero first (vec![0; n]), then overwrite every byte
+fn zeroed_then_filled(size: usize) -> Vec<u8> {
+    let mut buffer = vec![0u8; size];
+    fill_pattern(&mut buffer);
+    buffer
+}
+
+/// Write straight into uninitialized capacity, then set the length
+fn uninit_then_filled(size: usize) -> Vec<u8> {
+    let mut buffer: Vec<u8> = Vec::with_capacity(size);
+    let spare = buffer.spare_capacity_mut();
+    for (i, slot) in spare[..size].iter_mut().enumerate() {
+        slot.write((i as u8).wrapping_mul(31));
+    }
+    // SAFETY: the loop above initialized the first `size` bytes
+    unsafe { buffer.set_len(size) };
+    buffer
+}
+
+/// Zeroed but never written: calloc can hand back fresh zero pages for free
+fn zeroed_only(size: usize) -> Vec<u8> {
+    vec![0u8; size]
+}
+
+fn demonstrate_benchmark(report: &mut DemoReport) {
+    let title = format!("⏱️  Zeroed vs Uninitialized Buffers ({} MiB)", BUFFER_SIZE / (1024 * 1024));
+    output::heading(&title);
+    assert_eq!(zeroed_then_filled(4096), uninit_then_filled(4096));
+
+    let rows = [
+        ("vec![0; n] only (untouched)", measure(5, || zeroed_only(black_box(BUFFER_SIZE))).median()),
+        ("vec![0; n] then fill", measure(5, || zeroed_then_filled(black_box(BUFFER_SIZE))).median()),
+        ("MaybeUninit capacity, fill", measure(5, || uninit_then_filled(black_box(BUFFER_SIZE))).median()),
+    ];
+    let times = output::rank(&rows.map(|(_, time)| time), false, |time| format!("{:?}", time));
+    let mut table = Table::new(["strategy", "median", "GiB/s"]);
+    for ((name, time), time_cell) in rows.into_iter().zip(times) {
+        table.row([name.to_string(), time_cell,
+                   format!("{:.2}", BUFFER_SIZE as f64 / time.as_secs_f64() / (1u64 << 30) as f64)]);
+        report.record_time(name, time).param("bytes", BUFFER_SIZE);
+    }
+    out!("{}", table);
+    outln!("vec![0; n] on a large buffer is calloc: the OS hands out pages that are");
+    outln!("already zero, so it costs almost nothing until touched. The cost shows");
+    outln!("up when the data is written anyway: zeroing is an extra pass over memory.");
+    outln!("Writing into uninitialized capacity does one pass. The difference is");
+    outln!("often modest; reach for MaybeUninit only when a profile says so.\n");
+}
+
+const QUIZ: &[Question] = &[
+    Question::new(
+        "Is reading an uninitialized u32 fine, since any bit pattern is a valid u32?",
+        &[
+            "Yes",
+            "Only in release builds",
+            "No: reading uninitialized memory is undefined behavior for every type",
+        ],
+        2,
+        "The compiler may assume the read never happens, with unpredictable results.",
+    ),
+    Question::new(
+        "Why does the safe array builder track how many elements it has initialized?",
+        &[
+            "So a panic halfway through drops exactly the initialized prefix",
+            "To report progress",
+            "Because assume_init needs the count",
+        ],
+        0,
+        "Dropping uninitialized slots would be UB, and forgetting initialized ones would leak.",
+    ),
+];
+
+#[derive(Demo)]
+#[demo(name = "maybe-uninit-demo", description = "Sound handling of uninitialized memory",
+       quiz = QUIZ)]
+pub struct MaybeUninitDemo;
+
+impl MaybeUninitDemo {
+    fn demonstrate(&self, _config: &Config) -> DemoReport {
+        output::title("🫥 MaybeUninit Demo");
+        outln!("Sound patterns for memory that isn't initialized yet.\n");
+
+        demonstrate_the_problem();
+        demonstrate_arrays();
+        #[cfg(any(unix, windows))]
+        demonstrate_out_parameters();
+        let mut report = DemoReport::default();
+        demonstrate_benchmark(&mut report);
+
+        output::takeaways();
+        outln!("• Reading uninitialized memory is UB, even for integers");
+        outln!("• MaybeUninit<T> holds a T that isn't there yet; assume_init ends the promise");
+        outln!("• Track what's initialized so a panic drops exactly that prefix");
+        outln!("• Out-parameters: pass as_mut_ptr(), check the result, then assume_init");
+        outln!("• Prefer safe APIs (array::from_fn, spare_capacity_mut) over raw transmutes");
+        report
+    }
+}
@@ -0,0 +1,194 @@
+//! Memoize Demo
+//!
+//! The recursive Fibonacci that `compilation-optimization` times makes
+//! about 1.6^n calls, because it solves the same subproblems over and
+//! over: fib(n - 2) once directly and again inside fib(n - 1). Caching
+//! results by argument removes the repeats. The demo wraps that same
+//! function with `memoize`, which only caches the outer call, then writes
+//! it with `memoize_recursive`, which routes the recursion through the
+//! cache, and counts calls and times both up to n = 90. Last, it shrinks
+//! the LRU to find how little cache the recursion actually needs.
+//! Run with: cargo run --release --bin memoize-demo
+
+use std::cell::Cell;
+use std::hint::black_box;
+use std::time::Instant;
+
+use crate::bench::measure;
+use crate::cache::{memoize, memoize_recursive};
+use crate::demo::{Config, Demo, DemoReport};
+use crate::demos::compilation_optimization::fibonacci_recursive;
+use crate::output::Table;
+use crate::quiz::Question;
+use crate::verify::Verification;
+use crate::{outln, output};
+
+/// Where the naive recursion is timed; bigger takes too long to wait for
+const NAIVE_LIMIT: u64 = 35;
+
+/// Arguments in the scaling table; fib(93) is the last that fits a u64
+const SIZES: [u64; 5] = [10, 20, 30, 35, 90];
+
+/// Capacities tried in the "how much cache" table; 0 caches nothing
+const CAPACITIES: [usize; 5] = [0, 1, 2, 3, 1_000];
+
+/// The argument the capacity table computes
+const CAPACITY_N: u64 = 30;
+
+/// Calls the naive recursion makes for `n`: 2 fib(n + 1) - 1
+fn naive_calls(n: u64) -> u128 {
+    let (mut a, mut b) = (0u128, 1u128);
+    for _ in 0..n {
+        (a, b) = (b, a + b);
+    }
+    2 * b - 1
+}
+
+/// fib(`n`) through an LRU of `capacity` results; returns the result and
+/// how many times the body ran
+fn memoized_fibonacci(capacity: usize, n: u64) -> (u64, usize) {
+    let calls = Cell::new(0);
+    let mut fibonacci = memoize_recursive(capacity, |fib: &mut dyn FnMut(u64) -> u64, n: u64| {
+        calls.set(calls.get() + 1);
+        if n <= 1 { n } else { fib(n - 1) + fib(n - 2) }
+    });
+    let result = fibonacci(n);
+    (result, calls.get())
+}
+
+fn demonstrate_outer(report: &mut DemoReport) {
+    output::heading("🐢 Memoizing the Outside");
+    let mut fibonacci = memoize(100, fibonacci_recursive);
+    let [first, second] = [(); 2].map(|_| {
+        let start = Instant::now();
+        black_box(fibonacci(black_box(NAIVE_LIMIT)));
+        start.elapsed()
+    });
+    report.record_time("memoize first call", first).param("n", NAIVE_LIMIT);
+    report.record_time("memoize second call", second).param("n", NAIVE_LIMIT);
+    outln!("memoize(100, fibonacci_recursive), called twice with {}:\n", NAIVE_LIMIT);
+    outln!("  first call:  {:>10.2?}", first);
+    outln!("  second call: {:>10.2?}\n", second);
+    outln!("The second call is a hash lookup. The first is as slow as ever: the");
+    outln!("wrapper sees only the outermost call, and fibonacci_recursive calls");
+    outln!("itself, not the wrapper, so all {} inner calls miss the cache", naive_calls(NAIVE_LIMIT) - 1);
+    outln!("entirely. That suits functions called again with the same argument,");
+    outln!("not a recursion that repeats itself inside one call.\n");
+}
+
+fn demonstrate_recursive(report: &mut DemoReport) {
+    output::heading("🧠 Memoizing the Recursion");
+    outln!("memoize_recursive passes the body the memoized function to call instead");
+    outln!("of itself, so fib(n - 2), solved inside fib(n - 1), is a hit the second");
+    outln!("time. Naive times stop at {}:\n", NAIVE_LIMIT);
+    let mut table = Table::new(["n", "naive calls", "memoized calls", "naive", "memoized"]);
+    let mut memo_time = Default::default();
+    for n in SIZES {
+        let (_, calls) = memoized_fibonacci(1_000, n);
+        let memoized = measure(5, || memoized_fibonacci(1_000, black_box(n)));
+        memo_time = memoized.median();
+        report.record_time("memoize_recursive", memo_time).param("n", n);
+        let naive = match n <= NAIVE_LIMIT {
+            true => {
+                let naive = measure(3, || fibonacci_recursive(black_box(n))).median();
+                report.record_time("fibonacci_recursive", naive).param("n", n);
+                format!("{:.2?}", naive)
+            }
+            false => "-".to_string(),
+        };
+        table.row([n.to_string(), naive_calls(n).to_string(), calls.to_string(), naive, format!("{:.2?}", memo_time)]);
+    }
+    outln!("{}", table);
+    outln!("Each argument from 0 to n is computed once: n + 1 calls, where the");
+    outln!("naive version makes 2 fib(n + 1) - 1. At n = 90 that would be {:.1e}", naive_calls(90) as f64);
+    outln!("calls, thousands of years at a billion a second; the memoized version");
+    outln!("took {:.2?}. Exponential to linear is the speedup no optimization", memo_time);
+    outln!("level gives: LLVM makes each call cheaper, the cache makes most of");
+    outln!("them disappear.\n");
+}
+
+fn demonstrate_capacity() {
+    output::heading("📏 How Much Cache?");
+    outln!("fib({}) through LRU caches of different sizes:\n", CAPACITY_N);
+    let mut table = Table::new(["capacity", "calls"]);
+    for capacity in CAPACITIES {
+        let (_, calls) = memoized_fibonacci(capacity, CAPACITY_N);
+        table.row([capacity.to_string(), calls.to_string()]);
+    }
+    outln!("{}", table);
+    outln!("Three entries do as well as a thousand; two are not enough. fib(n)");
+    outln!("asks for fib(n - 1) and then fib(n - 2), but fib(n - 1) ends by asking");
+    outln!("for fib(n - 3), a hit that makes it more recent than fib(n - 2). With");
+    outln!("two entries LRU evicts fib(n - 2) just before it is wanted, and the");
+    outln!("calls grow exponentially again, if more slowly. fibonacci_iterative");
+    outln!("gets by with two variables, `a` and `b`, because it knows fib(n - 3)");
+    outln!("is finished with. LRU only knows what was used last, so it spends its");
+    outln!("third slot on a result nobody will ask for again.\n");
+}
+
+/// Memoized results match the naive recursion, take n + 1 calls with three
+/// cache entries and exponentially many with two, and beat the naive
+/// recursion by far at n = 25
+fn verify() -> Verification {
+    let mut verification = Verification::new();
+    let matches = (0..=20).all(|n| memoized_fibonacci(3, n).0 == fibonacci_recursive(n));
+    verification.check("memoized fib(0..=20) equals fibonacci_recursive", matches, || "a result differs".to_string());
+    verification.equal("three cache entries give n + 1 calls", memoized_fibonacci(3, CAPACITY_N).1, CAPACITY_N as usize + 1);
+    let (_, calls) = memoized_fibonacci(2, CAPACITY_N);
+    verification.check("two cache entries need over 1,000 calls", calls > 1_000, || format!("{} calls", calls));
+
+    let memoized = measure(5, || memoized_fibonacci(1_000, black_box(25)));
+    let naive = measure(5, || fibonacci_recursive(black_box(25)));
+    verification.faster("memoized fib(25) beats the naive recursion 10x", &memoized, &naive, 10.0);
+    verification
+}
+
+const QUIZ: &[Question] = &[
+    Question::new(
+        "Why doesn't wrapping fibonacci_recursive in memoize speed up its first call?",
+        &[
+            "The cache is too small",
+            "Its recursive calls call the function directly, never the wrapper, so none of them are cached",
+            "Hashing u64 keys is slower than recomputing",
+        ],
+        1,
+        "Memoizing a recursion means routing the recursive calls through the cache, which memoize_recursive does.",
+    ),
+    Question::new(
+        "How many times does the body of a fully memoized fib(n) run?",
+        &["n + 1", "2^n", "n^2"],
+        0,
+        "Each argument from 0 to n is computed once; every later request for it is a hit.",
+    ),
+    Question::new(
+        "What is the smallest LRU capacity at which memoized Fibonacci stays linear?",
+        &["2", "3", "n"],
+        1,
+        "fib(n - 1) ends by reading fib(n - 3), which pushes fib(n - 2) to the LRU end; a third slot keeps it.",
+    ),
+];
+
+#[derive(Demo)]
+#[demo(name = "memoize-demo", description = "memoize and memoize_recursive on the LRU cache: recursive Fibonacci from exponential to linear",
+       quiz = QUIZ, verify = verify)]
+pub struct MemoizeDemo;
+
+impl MemoizeDemo {
+    fn demonstrate(&self, _config: &Config) -> DemoReport {
+        output::title("🧮 Memoize Demo");
+        outln!("A cache in front of a function.\n");
+
+        let mut report = DemoReport::default();
+        demonstrate_outer(&mut report);
+        demonstrate_recursive(&mut report);
+        demonstrate_capacity();
+
+        output::takeaways();
+        outln!("• A pure function's results can be cached by argument");
+        outln!("• Wrapping a recursive function caches only the outermost call");
+        outln!("• Routing the recursion through the cache solves each subproblem once");
+        outln!("• For Fibonacci that turns 2 fib(n + 1) - 1 calls into n + 1");
+        outln!("• LRU needs a slot more than the loop's two variables: recency isn't future use");
+        report
+    }
+}
@@ -0,0 +1,169 @@
+// Demonstration of how memory access works
+
+use crate::demo::{Config, Demo, DemoReport};
+use crate::quiz::Question;
+use crate::{outln, output};
+
+fn demonstrate_variable_access() {
+    output::heading("How Variables are Accessed");
+    
+    // Example: Local variables on stack
+    let x: u8 = 42;      // 1 byte
+    let y: u32 = 100;    // 4 bytes
+    let z: u64 = 1000;   // 8 bytes
+    
+    outln!("Variables on stack:");
+    outln!("  x: u8 = {} (1 byte at some address)", x);
+    outln!("  y: u32 = {} (4 bytes at some address)", y);
+    outln!("  z: u64 = {} (8 bytes at some address)", z);
+    outln!();
+    
+    // Get addresses
+    let x_ptr = &x as *const u8 as usize;
+    let y_ptr = &y as *const u32 as usize;
+    let z_ptr = &z as *const u64 as usize;
+    
+    outln!("Addresses (virtual):");
+    outln!("  x: 0x{:x}", x_ptr);
+    outln!("  y: 0x{:x}", y_ptr);
+    outln!("  z: 0x{:x}", z_ptr);
+    outln!();
+    
+    outln!("What happens when you access x:");
+    outln!("  1. CPU has address: 0x{:x}", x_ptr);
+    outln!("  2. CPU uses page table to find physical page");
+    outln!("  3. CPU accesses byte at offset within that page");
+    outln!("  4. Returns 1 byte (u8)");
+    outln!();
+    
+    outln!("What happens when you access y:");
+    outln!("  1. CPU has address: 0x{:x}", y_ptr);
+    outln!("  2. CPU uses page table to find physical page");
+    outln!("  3. CPU accesses 4 bytes starting at that address");
+    outln!("  4. Returns 4 bytes (u32)");
+    outln!();
+}
+
+fn demonstrate_stack_layout() {
+    output::heading("Stack Layout");
+    
+    #[allow(unused_variables)]
+    fn inner_function() {
+        let a: u8 = 1;   // At RSP - 1
+        let b: u32 = 2;  // At RSP - 5 (1 byte for a + 4 bytes for b)
+        let c: u64 = 3;  // At RSP - 13 (previous + 8 bytes for c)
+        
+        outln!("Function stack frame:");
+        outln!("  RSP (stack pointer) points to top of stack");
+        outln!("  a (u8):  at RSP - 1  (1 byte)");
+        outln!("  b (u32): at RSP - 5  (4 bytes)");
+        outln!("  c (u64): at RSP - 13 (8 bytes)");
+        outln!();
+        outln!("Compiler calculates these offsets at compile time!");
+        outln!("OS doesn't know about individual variables - only pages!");
+    }
+    
+    inner_function();
+}
+
+fn demonstrate_page_table() {
+    output::heading("Page Table Translation");
+    
+    outln!("Virtual Address: 0x7FFF12345678");
+    outln!("  └─ Split into:");
+    outln!("      Page number: 0x7FFF12345 (high bits)");
+    outln!("      Offset:      0x678 (low 12 bits, within 4KB page)");
+    outln!();
+    
+    outln!("Translation process:");
+    outln!("  1. CPU extracts page number: 0x7FFF12345");
+    outln!("  2. CPU looks up in page table:");
+    outln!("     Page table[0x7FFF12345] → Physical page: 0x5000");
+    outln!("  3. CPU combines:");
+    outln!("     Physical address = 0x5000 + 0x678 = 0x5000678");
+    outln!("  4. CPU accesses physical RAM at 0x5000678");
+    outln!();
+    
+    outln!("Key insight:");
+    outln!("  - OS manages pages (4KB chunks)");
+    outln!("  - Compiler manages offsets within pages");
+    outln!("  - CPU translates virtual → physical automatically");
+    outln!();
+}
+
+fn demonstrate_program_isolation() {
+    output::heading("Program Isolation");
+    
+    outln!("Each process has its own virtual address space:");
+    outln!("  Process A: Virtual addresses 0x0000...0000 to 0xFFFF...FFFF");
+    outln!("  Process B: Virtual addresses 0x0000...0000 to 0xFFFF...FFFF");
+    outln!("  (Same virtual addresses, different physical pages!)");
+    outln!();
+    
+    outln!("OS tracks:");
+    outln!("  - Page table for each process");
+    outln!("  - RSP initial value (stack start)");
+    outln!("  - Reserved stack space (usually 2-8 MB)");
+    outln!("  - Heap space (grows dynamically)");
+    outln!();
+    
+    outln!("OS does NOT track:");
+    outln!("  - Individual variables (x, y, z)");
+    outln!("  - Variable offsets (RSP - 1, RSP - 5)");
+    outln!("  - Variable types (u8, u32, u64)");
+    outln!("  (Compiler handles this!)");
+    outln!();
+}
+
+const QUIZ: &[Question] = &[
+    Question::new(
+        "In what unit does the OS manage a process's memory?",
+        &["Individual variables", "Cache lines", "Pages, typically 4 KiB"],
+        2,
+        "The OS tracks pages and their mappings; it knows nothing about the variables inside them.",
+    ),
+    Question::new(
+        "How does compiled code find a local variable?",
+        &[
+            "At an offset from the stack pointer that the compiler computed",
+            "By asking the OS for its address",
+            "By name, through the debug info",
+        ],
+        0,
+        "The offsets are fixed at compile time; only the stack pointer's value is known at run time.",
+    ),
+    Question::new(
+        "Two processes print the same virtual address for a variable. Do they share that memory?",
+        &[
+            "Yes, an address is an address",
+            "No: each process has its own page tables, mapping that address to a different physical page",
+            "Only if the variable is static",
+        ],
+        1,
+        "Separate address spaces are how the OS isolates processes from each other.",
+    ),
+];
+
+#[derive(Demo)]
+#[demo(name = "memory-access-demo", description = "How variables are reached: stack layout, page tables, isolation",
+       quiz = QUIZ)]
+pub struct MemoryAccessDemo;
+
+impl MemoryAccessDemo {
+    fn demonstrate(&self, _config: &Config) -> DemoReport {
+        output::title("Memory Access: Deep Dive");
+
+        demonstrate_variable_access();
+        demonstrate_stack_layout();
+        demonstrate_page_table();
+        demonstrate_program_isolation();
+
+        output::takeaways();
+        outln!("1. OS manages pages (4KB chunks), not individual variables");
+        outln!("2. Compiler calculates offsets (RSP - offset) at compile time");
+        outln!("3. CPU translates virtual addresses to physical using page table");
+        outln!("4. Each process has isolated virtual address space");
+        outln!("5. OS tracks page tables, RSP, stack/heap regions - not variables");
+        DemoReport::default()
+    }
+}
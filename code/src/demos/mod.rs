@@ -0,0 +1,272 @@
+//! The Demos
+//!
+//! Every demo as a library module exposing one type that implements
+//! [`Demo`]. The binaries in `src/bin/` are thin wrappers that run one of
+//! them with the [`Config`](crate::demo::Config) read from the environment,
+//! so a demo can also be run, listed or tested from code. [`all`] returns
+//! them in learning order, matching [`crate::catalog`].
+//!
+//! The browser build (wasm32) has no threads, processes, files or native
+//! code to disassemble, so it keeps only the demos that are pure computation:
+//! the toy CPU, the language features, the optimizer and the LRU cache. The
+//! hardware, memory, OS and concurrency demos are compiled out.
+
+use crate::demo::Demo;
+
+pub mod aliasing_demo;
+#[cfg(not(target_family = "wasm"))]
+pub mod amdahl_demo;
+pub mod array_indexing_demo;
+#[cfg(target_os = "linux")]
+pub mod async_runtime_demo;
+#[cfg(not(target_family = "wasm"))]
+pub mod atomics_ordering_demo;
+#[cfg(all(target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64")))]
+pub mod backtrace_demo;
+#[cfg(not(target_family = "wasm"))]
+pub mod binary_inspector_demo;
+#[cfg(not(target_family = "wasm"))]
+pub mod btree_demo;
+pub mod bytecode_vm_demo;
+#[cfg(not(target_family = "wasm"))]
+pub mod cache_line_demo;
+pub mod clock_demo;
+pub mod closure_demo;
+#[cfg(not(target_family = "wasm"))]
+pub mod compilation_optimization;
+#[cfg(not(target_family = "wasm"))]
+pub mod condvar_demo;
+pub mod const_generics_demo;
+#[cfg(not(target_family = "wasm"))]
+pub mod dns_demo;
+pub mod drop_order_demo;
+pub mod dst_demo;
+#[cfg(target_os = "linux")]
+pub mod dynamic_linking_demo;
+pub mod endianness_demo;
+#[cfg(not(target_family = "wasm"))]
+pub mod error_handling_demo;
+#[cfg(not(target_family = "wasm"))]
+pub mod ffi_demo;
+#[cfg(all(unix, not(target_family = "wasm")))]
+pub mod filesystem_demo;
+pub mod floating_point_demo;
+#[cfg(target_os = "linux")]
+pub mod futex_demo;
+#[cfg(feature = "gpu")]
+pub mod gpu_demo;
+#[cfg(not(target_family = "wasm"))]
+pub mod hardware_fundamentals;
+pub mod hashdos_demo;
+#[cfg(not(target_family = "wasm"))]
+pub mod http_server_demo;
+#[cfg(target_os = "linux")]
+pub mod io_bound_demo;
+pub mod iterator_demo;
+#[cfg(not(target_family = "wasm"))]
+pub mod kv_store_demo;
+#[cfg(not(target_family = "wasm"))]
+pub mod lock_scaling_demo;
+#[cfg(not(target_family = "wasm"))]
+pub mod lru_benchmark;
+pub mod lru_implementation;
+pub mod lru_vs_lfu_demo;
+pub mod macro_demo;
+pub mod match_demo;
+#[cfg(not(target_family = "wasm"))]
+pub mod maybe_uninit_demo;
+#[cfg(not(target_family = "wasm"))]
+pub mod memcpy_demo;
+pub mod memoize_demo;
+pub mod memory_access_demo;
+#[cfg(not(target_family = "wasm"))]
+pub mod memory_management;
+#[cfg(not(target_family = "wasm"))]
+pub mod model_checking_demo;
+#[cfg(not(target_family = "wasm"))]
+pub mod monomorphization_demo;
+#[cfg(not(target_family = "wasm"))]
+pub mod mpmc_queue_demo;
+#[cfg(not(target_family = "wasm"))]
+pub mod nagle_demo;
+#[cfg(not(target_family = "wasm"))]
+pub mod network_demo;
+pub mod no_std_demo;
+#[cfg(not(target_family = "wasm"))]
+pub mod operating_system_concepts;
+pub mod optimization_demo;
+pub mod optimization_levels_demo;
+#[cfg(target_os = "linux")]
+pub mod page_cache_demo;
+#[cfg(not(target_family = "wasm"))]
+pub mod persistent_cache_demo;
+pub mod pointer_safety_demo;
+pub mod prng_demo;
+#[cfg(not(target_family = "wasm"))]
+pub mod rcu_cache_demo;
+#[cfg(not(target_family = "wasm"))]
+pub mod rcu_demo;
+#[cfg(not(target_family = "wasm"))]
+pub mod register_demo;
+#[cfg(not(target_family = "wasm"))]
+pub mod reliable_udp_demo;
+pub mod rust_language_features;
+#[cfg(not(target_family = "wasm"))]
+pub mod scoped_threads_demo;
+#[cfg(not(target_family = "wasm"))]
+pub mod send_sync_demo;
+#[cfg(not(target_family = "wasm"))]
+pub mod sharded_cache_demo;
+#[cfg(target_os = "linux")]
+pub mod socket_buffers_demo;
+#[cfg(not(target_family = "wasm"))]
+pub mod spinlock_demo;
+pub mod sorting_demo;
+pub mod string_layout_demo;
+#[cfg(not(target_family = "wasm"))]
+pub mod substring_search_demo;
+pub mod timing_attack_demo;
+pub mod toy_cpu;
+pub mod trait_object_demo;
+#[cfg(not(target_family = "wasm"))]
+pub mod ttl_cache_demo;
+#[cfg(not(target_family = "wasm"))]
+pub mod treiber_stack_demo;
+pub mod typestate_demo;
+#[cfg(not(target_family = "wasm"))]
+pub mod udp_tcp_demo;
+pub mod weighted_cache_demo;
+#[cfg(not(target_family = "wasm"))]
+pub mod write_back_demo;
+
+/// One instance of every demo available on this platform, in learning order
+pub fn all() -> Vec<Box<dyn Demo>> {
+    vec![
+        #[cfg(not(target_family = "wasm"))]
+        Box::new(hardware_fundamentals::HardwareFundamentals),
+        #[cfg(not(target_family = "wasm"))]
+        Box::new(cache_line_demo::CacheLineDemo),
+        #[cfg(not(target_family = "wasm"))]
+        Box::new(register_demo::RegisterDemo),
+        Box::new(endianness_demo::EndiannessDemo),
+        Box::new(floating_point_demo::FloatingPointDemo),
+        Box::new(toy_cpu::ToyCpu),
+        #[cfg(feature = "gpu")]
+        Box::new(gpu_demo::GpuDemo),
+        #[cfg(not(target_family = "wasm"))]
+        Box::new(memory_management::MemoryManagement),
+        #[cfg(not(target_family = "wasm"))]
+        Box::new(memory_access_demo::MemoryAccessDemo),
+        #[cfg(all(target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64")))]
+        Box::new(backtrace_demo::BacktraceDemo),
+        Box::new(array_indexing_demo::ArrayIndexingDemo),
+        #[cfg(not(target_family = "wasm"))]
+        Box::new(memcpy_demo::MemcpyDemo),
+        Box::new(sorting_demo::SortingDemo),
+        #[cfg(not(target_family = "wasm"))]
+        Box::new(compilation_optimization::CompilationOptimization),
+        Box::new(optimization_demo::OptimizationDemo),
+        Box::new(optimization_levels_demo::OptimizationLevelsDemo),
+        Box::new(bytecode_vm_demo::BytecodeVmDemo),
+        Box::new(rust_language_features::RustLanguageFeatures),
+        Box::new(iterator_demo::IteratorDemo),
+        Box::new(pointer_safety_demo::PointerSafetyDemo),
+        Box::new(trait_object_demo::TraitObjectDemo),
+        Box::new(drop_order_demo::DropOrderDemo),
+        #[cfg(not(target_family = "wasm"))]
+        Box::new(ffi_demo::FfiDemo),
+        Box::new(macro_demo::MacroDemo),
+        Box::new(const_generics_demo::ConstGenericsDemo),
+        Box::new(string_layout_demo::StringLayoutDemo),
+        Box::new(typestate_demo::TypestateDemo),
+        #[cfg(not(target_family = "wasm"))]
+        Box::new(maybe_uninit_demo::MaybeUninitDemo),
+        #[cfg(not(target_family = "wasm"))]
+        Box::new(error_handling_demo::ErrorHandlingDemo),
+        Box::new(closure_demo::ClosureDemo),
+        Box::new(dst_demo::DstDemo),
+        #[cfg(not(target_family = "wasm"))]
+        Box::new(send_sync_demo::SendSyncDemo),
+        Box::new(aliasing_demo::AliasingDemo),
+        #[cfg(not(target_family = "wasm"))]
+        Box::new(monomorphization_demo::MonomorphizationDemo),
+        Box::new(match_demo::MatchDemo),
+        Box::new(no_std_demo::NoStdDemo),
+        #[cfg(not(target_family = "wasm"))]
+        Box::new(operating_system_concepts::OperatingSystemConcepts),
+        #[cfg(all(unix, not(target_family = "wasm")))]
+        Box::new(filesystem_demo::FilesystemDemo),
+        #[cfg(target_os = "linux")]
+        Box::new(page_cache_demo::PageCacheDemo),
+        Box::new(clock_demo::ClockDemo),
+        #[cfg(not(target_family = "wasm"))]
+        Box::new(binary_inspector_demo::BinaryInspectorDemo),
+        #[cfg(target_os = "linux")]
+        Box::new(dynamic_linking_demo::DynamicLinkingDemo),
+        #[cfg(not(target_family = "wasm"))]
+        Box::new(atomics_ordering_demo::AtomicsOrderingDemo),
+        #[cfg(not(target_family = "wasm"))]
+        Box::new(treiber_stack_demo::TreiberStackDemo),
+        #[cfg(not(target_family = "wasm"))]
+        Box::new(mpmc_queue_demo::MpmcQueueDemo),
+        #[cfg(not(target_family = "wasm"))]
+        Box::new(lock_scaling_demo::LockScalingDemo),
+        #[cfg(not(target_family = "wasm"))]
+        Box::new(spinlock_demo::SpinlockDemo),
+        #[cfg(not(target_family = "wasm"))]
+        Box::new(condvar_demo::CondvarDemo),
+        #[cfg(not(target_family = "wasm"))]
+        Box::new(model_checking_demo::ModelCheckingDemo),
+        #[cfg(target_os = "linux")]
+        Box::new(async_runtime_demo::AsyncRuntimeDemo),
+        #[cfg(not(target_family = "wasm"))]
+        Box::new(rcu_demo::RcuDemo),
+        #[cfg(target_os = "linux")]
+        Box::new(futex_demo::FutexDemo),
+        #[cfg(not(target_family = "wasm"))]
+        Box::new(amdahl_demo::AmdahlDemo),
+        #[cfg(target_os = "linux")]
+        Box::new(io_bound_demo::IoBoundDemo),
+        #[cfg(not(target_family = "wasm"))]
+        Box::new(scoped_threads_demo::ScopedThreadsDemo),
+        #[cfg(not(target_family = "wasm"))]
+        Box::new(network_demo::NetworkDemo),
+        #[cfg(not(target_family = "wasm"))]
+        Box::new(udp_tcp_demo::UdpTcpDemo),
+        #[cfg(not(target_family = "wasm"))]
+        Box::new(nagle_demo::NagleDemo),
+        #[cfg(not(target_family = "wasm"))]
+        Box::new(http_server_demo::HttpServerDemo),
+        #[cfg(not(target_family = "wasm"))]
+        Box::new(dns_demo::DnsDemo),
+        #[cfg(target_os = "linux")]
+        Box::new(socket_buffers_demo::SocketBuffersDemo),
+        #[cfg(not(target_family = "wasm"))]
+        Box::new(reliable_udp_demo::ReliableUdpDemo),
+        #[cfg(not(target_family = "wasm"))]
+        Box::new(kv_store_demo::KvStoreDemo),
+        #[cfg(not(target_family = "wasm"))]
+        Box::new(btree_demo::BTreeDemo),
+        Box::new(lru_implementation::LruImplementation),
+        #[cfg(not(target_family = "wasm"))]
+        Box::new(lru_benchmark::LruBenchmark),
+        #[cfg(not(target_family = "wasm"))]
+        Box::new(ttl_cache_demo::TtlCacheDemo),
+        Box::new(lru_vs_lfu_demo::LruVsLfuDemo),
+        Box::new(weighted_cache_demo::WeightedCacheDemo),
+        #[cfg(not(target_family = "wasm"))]
+        Box::new(write_back_demo::WriteBackDemo),
+        Box::new(memoize_demo::MemoizeDemo),
+        #[cfg(not(target_family = "wasm"))]
+        Box::new(persistent_cache_demo::PersistentCacheDemo),
+        #[cfg(not(target_family = "wasm"))]
+        Box::new(sharded_cache_demo::ShardedCacheDemo),
+        #[cfg(not(target_family = "wasm"))]
+        Box::new(rcu_cache_demo::RcuCacheDemo),
+        Box::new(prng_demo::PrngDemo),
+        Box::new(timing_attack_demo::TimingAttackDemo),
+        #[cfg(not(target_family = "wasm"))]
+        Box::new(substring_search_demo::SubstringSearchDemo),
+        Box::new(hashdos_demo::HashDosDemo),
+    ]
+}
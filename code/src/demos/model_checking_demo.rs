@@ -0,0 +1,171 @@
+//! Model Checking Demo
+//!
+//! Why stress tests can't prove a lock-free structure correct, and what the
+//! loom model checker does instead. Uses the crate's SPSC ring buffer.
+//! Run with: cargo run --release --bin model-checking-demo
+
+use std::cell::UnsafeCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Instant;
+
+use crate::demo::{Config, Demo, DemoReport};
+use crate::quiz::Question;
+use crate::sync::spsc_ring;
+use crate::{outln, output};
+
+fn demonstrate_spsc_ring(report: &mut DemoReport) {
+    output::heading("💍 SPSC Ring Buffer");
+    outln!("One producer, one consumer, no CAS: each side owns one index and only");
+    outln!("reads the other's. Release on publish, Acquire on observe.\n");
+
+    let items = 1_000_000u64;
+    let (mut producer, mut consumer) = spsc_ring::channel(1024);
+    let start = Instant::now();
+    let handle = thread::spawn(move || {
+        for value in 0..items {
+            let mut value = value;
+            while let Err(rejected) = producer.push(value) {
+                value = rejected;
+                thread::yield_now();
+            }
+        }
+    });
+
+    let mut expected = 0u64;
+    let mut in_order = true;
+    while expected < items {
+        match consumer.pop() {
+            Some(value) => {
+                in_order &= value == expected;
+                expected += 1;
+            }
+            None => thread::yield_now(),
+        }
+    }
+    handle.join().unwrap();
+    let elapsed = start.elapsed();
+    outln!("Stress test: {} items in {:?}, all in order: {}\n",
+             items, elapsed, if in_order { "✅" } else { "❌" });
+    report.record_time("SPSC stress test", elapsed).param("items", items);
+}
+
+/// A one-shot handoff that publishes with a Relaxed store (the bug)
+struct RelaxedHandoff {
+    data: UnsafeCell<u64>,
+    ready: AtomicBool,
+}
+
+unsafe impl Sync for RelaxedHandoff {}
+
+fn demonstrate_stress_testing_limits() {
+    output::heading("🎲 What a Stress Test Sees");
+    outln!("writer: data = 42; ready.store(true, Relaxed)   ← should be Release");
+    outln!("reader: if ready.load(Relaxed) {{ read data }}    ← should be Acquire\n");
+
+    let rounds = 20_000;
+    let mut observed_ready = 0;
+    let mut stale_reads = 0;
+    for _ in 0..rounds {
+        let handoff = RelaxedHandoff { data: UnsafeCell::new(0), ready: AtomicBool::new(false) };
+        // Borrow the whole struct: closures would otherwise capture just the
+        // `UnsafeCell` field, which isn't Sync on its own
+        let handoff = &handoff;
+        thread::scope(|scope| {
+            scope.spawn(|| {
+                unsafe { *handoff.data.get() = 42 };
+                handoff.ready.store(true, Ordering::Relaxed);
+            });
+            scope.spawn(|| {
+                if handoff.ready.load(Ordering::Relaxed) {
+                    observed_ready += 1;
+                    // Volatile so the compiler can't fold the read away
+                    if unsafe { std::ptr::read_volatile(handoff.data.get()) } != 42 {
+                        stale_reads += 1;
+                    }
+                }
+            });
+        });
+    }
+
+    outln!("{} rounds: reader saw ready=true {} times, stale data {} times", rounds, observed_ready, stale_reads);
+    outln!("On x86 stores are never reordered with each other, so this bug is invisible.");
+    outln!("On ARM it shows up rarely - maybe once in millions of runs, maybe in production.");
+    outln!("It is still a data race (undefined behaviour) even when every run \"passes\".\n");
+}
+
+fn demonstrate_model_checking() {
+    output::heading("🔍 Model Checking with loom");
+    outln!("loom replaces std's atomics, UnsafeCell and thread with instrumented versions");
+    outln!("(this crate swaps them via `sync::primitive` under `--cfg loom`) and re-runs a");
+    outln!("small test once for EVERY interleaving and every value the C++ memory model");
+    outln!("lets each load return - not just the ones your CPU happens to produce.\n");
+
+    outln!("  RUSTFLAGS=\"--cfg loom\" cargo test --release --test loom\n");
+
+    outln!("Modelled in tests/loom.rs:");
+    outln!("  • TAS, TTAS and ticket spinlocks: two threads, no lost increments");
+    outln!("  • SPSC ring: wrap-around handoff stays in order, Drop frees leftovers");
+    outln!("  • Treiber stack: concurrent push/pop and pop/pop never lose or duplicate");
+    outln!("  • The Relaxed handoff above, marked #[should_panic]\n");
+
+    outln!("What loom reports for the Relaxed handoff, on any machine, every time:");
+    outln!("  Causality violation: Concurrent read and write accesses.\n");
+    outln!("It tracks happens-before per UnsafeCell access: the reader touched `data`");
+    outln!("without an Acquire that synchronizes with the writer's Release.\n");
+
+    outln!("It also caught a design problem: a test-and-set lock spinning on");
+    outln!("`swap(true)` made loom give up with 'Model exceeded maximum number of branches'.");
+    outln!("A failed swap is still a write, so the spinner never looks idle. The crate's");
+    outln!("TAS lock uses compare_exchange instead - same cache behaviour, no write on failure.\n");
+
+    outln!("Limits: the state space explodes, so models use 2-3 threads and a few");
+    outln!("operations each. Bugs that need more steps than that stay hidden.\n");
+}
+
+const QUIZ: &[Question] = &[
+    Question::new(
+        "A stress test passed a million runs. What does that show?",
+        &[
+            "The code is correct",
+            "Only that the interleavings this hardware happened to produce are fine",
+            "Nothing at all",
+        ],
+        1,
+        "Rare interleavings and weak-memory reorderings may never show up on your machine.",
+    ),
+    Question::new(
+        "What does loom do with a small concurrent test?",
+        &[
+            "Runs it on many threads for a long time",
+            "Executes it under every possible interleaving and allowed memory-ordering outcome",
+            "Checks it for data races at compile time",
+        ],
+        1,
+        "That's why loom tests use tiny thread and operation counts.",
+    ),
+];
+
+#[derive(Demo)]
+#[demo(name = "model-checking-demo", description = "Stress testing vs model checking with loom",
+       quiz = QUIZ)]
+pub struct ModelCheckingDemo;
+
+impl ModelCheckingDemo {
+    fn demonstrate(&self, _config: &Config) -> DemoReport {
+        output::title("🧪 Model Checking Demo");
+        outln!("Testing every interleaving instead of hoping to hit the bad one.\n");
+
+        let mut report = DemoReport::default();
+        demonstrate_spsc_ring(&mut report);
+        demonstrate_stress_testing_limits();
+        demonstrate_model_checking();
+
+        output::takeaways();
+        outln!("• A passing stress test only covers the interleavings your hardware produced");
+        outln!("• x86's strong memory model hides ordering bugs that ARM will expose");
+        outln!("• loom explores all interleavings and weak-memory outcomes of a small test");
+        outln!("• Route atomics through one module so the model checker can swap them in");
+        report
+    }
+}
@@ -0,0 +1,284 @@
+//! Monomorphization Cost Explorer
+//!
+//! A generic function is compiled once per type it's used with; a function
+//! taking `dyn Trait` is compiled once, full stop. The generic copies are
+//! each specialized and fast, but they cost compile time and binary size.
+//! This demo counts the copies in its own binary, then generates and
+//! compiles small programs with 1 to 50 types to measure the difference.
+//! Run with: cargo run --release --bin monomorphization-demo
+
+// The shapes below only feed "Copies in This Binary", which reads the symbol
+// table with the Unix binutils
+#![cfg_attr(not(unix), allow(dead_code, unused_imports))]
+
+use std::fmt::Write as _;
+use std::fs;
+use std::hint::black_box;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+use crate::demo::{Config, Demo, DemoReport};
+use crate::quiz::Question;
+use crate::scratch::Scratch;
+use crate::{outln, output};
+
+trait Shape {
+    fn area(&self) -> f64;
+}
+
+struct Square(f64);
+struct Circle(f64);
+struct Triangle(f64, f64);
+struct Hexagon(f64);
+
+impl Shape for Square {
+    fn area(&self) -> f64 {
+        self.0 * self.0
+    }
+}
+
+impl Shape for Circle {
+    fn area(&self) -> f64 {
+        std::f64::consts::PI * self.0 * self.0
+    }
+}
+
+impl Shape for Triangle {
+    fn area(&self) -> f64 {
+        0.5 * self.0 * self.1
+    }
+}
+
+impl Shape for Hexagon {
+    fn area(&self) -> f64 {
+        2.598_076_211_353_316 * self.0 * self.0
+    }
+}
+
+/// One compiled copy per T
+#[inline(never)]
+fn largest_generic<T: Shape>(shapes: &[T]) -> f64 {
+    let mut sorted: Vec<&T> = shapes.iter().collect();
+    sorted.sort_by(|a, b| a.area().total_cmp(&b.area()));
+    sorted.last().map_or(0.0, |s| s.area())
+}
+
+/// One compiled copy, whatever the shapes are
+#[inline(never)]
+fn largest_dyn(shapes: &[&dyn Shape]) -> f64 {
+    let mut sorted: Vec<&&dyn Shape> = shapes.iter().collect();
+    sorted.sort_by(|a, b| a.area().total_cmp(&b.area()));
+    sorted.last().map_or(0.0, |s| s.area())
+}
+
+#[cfg(unix)]
+fn demonstrate_own_binary() {
+    use crate::asm;
+
+    output::heading("🔬 Copies in This Binary");
+    let squares = [Square(1.0), Square(3.0)];
+    let circles = [Circle(1.0), Circle(2.0)];
+    let triangles = [Triangle(2.0, 3.0), Triangle(4.0, 1.0)];
+    let hexagons = [Hexagon(1.0), Hexagon(1.5)];
+    let generic = [
+        largest_generic(black_box(&squares)),
+        largest_generic(black_box(&circles)),
+        largest_generic(black_box(&triangles)),
+        largest_generic(black_box(&hexagons)),
+    ];
+    let all: Vec<&dyn Shape> = vec![&squares[1], &circles[1], &triangles[0], &hexagons[1]];
+    outln!("  largest_generic per type: {:.2?}", generic);
+    outln!("  largest_dyn over all four: {:.2}\n", largest_dyn(black_box(&all)));
+
+    let symbols = match std::env::current_exe().map_err(|e| e.to_string()).and_then(|exe| asm::symbols(&exe).map_err(|e| e.to_string())) {
+        Ok(symbols) => symbols,
+        Err(err) => {
+            outln!("⚠️  Can't read the symbol table: {}\n", err);
+            return;
+        }
+    };
+    for name in ["largest_generic", "largest_dyn"] {
+        let copies: Vec<_> = symbols.iter().filter(|s| s.name.ends_with(&format!("::{}", name))).collect();
+        let bytes: u64 = copies.iter().map(|s| s.size).sum();
+        outln!("  {:<16} {} cop{} in the symbol table, {} bytes of code",
+                 name, copies.len(), if copies.len() == 1 { "y" } else { "ies" }, bytes);
+    }
+    let sorts = symbols.iter().filter(|s| s.name.starts_with("core::slice::sort")).count();
+    outln!("  core::slice::sort::*  {} symbols: a family for every element type sorted anywhere", sorts);
+    outln!("The demangled names look alike because the type parameters are part of");
+    outln!("the mangled hash, but each generic copy is separate machine code.\n");
+}
+
+/// Source of a program with `types` trait impls that calls `summarize`
+/// (generic or dyn) once per type
+fn generate_program(types: usize, generic: bool) -> String {
+    let mut source = String::from("use std::hint::black_box;\n\npub trait Summary {\n    fn weight(&self) -> u64;\n}\n\n");
+    for i in 0..types {
+        let _ = writeln!(source, "pub struct Type{i}(pub u64);");
+        let _ = writeln!(source, "impl Summary for Type{i} {{ fn weight(&self) -> u64 {{ self.0.wrapping_mul({}) ^ {i} }} }}\n", i * 2 + 1);
+    }
+    if generic {
+        source.push_str(
+            "#[inline(never)]\npub fn summarize<T: Summary>(items: &[T]) -> u64 {\n    \
+             let mut sorted: Vec<&T> = items.iter().collect();\n    \
+             sorted.sort_by_key(|item| item.weight());\n    \
+             sorted.iter().take(3).map(|item| item.weight()).sum()\n}\n\nfn main() {\n    let mut total = 0u64;\n",
+        );
+        for i in 0..types {
+            let _ = writeln!(source, "    total ^= summarize(black_box(&[Type{i}(3), Type{i}(1), Type{i}(2)]));");
+        }
+    } else {
+        source.push_str(
+            "#[inline(never)]\npub fn summarize(items: &[&dyn Summary]) -> u64 {\n    \
+             let mut sorted: Vec<&&dyn Summary> = items.iter().collect();\n    \
+             sorted.sort_by_key(|item| item.weight());\n    \
+             sorted.iter().take(3).map(|item| item.weight()).sum()\n}\n\nfn main() {\n    let mut total = 0u64;\n",
+        );
+        for i in 0..types {
+            let _ = writeln!(source, "    total ^= summarize(black_box(&[&Type{i}(3) as &dyn Summary, &Type{i}(1), &Type{i}(2)]));");
+        }
+    }
+    source.push_str("    println!(\"{}\", total);\n}\n");
+    source
+}
+
+struct BuildResult {
+    compile_time: Duration,
+    binary_bytes: u64,
+    /// `summarize` plus the sort functions it instantiates
+    functions: usize,
+    function_bytes: u64,
+}
+
+/// Compile a generated program with `rustc -O` and measure the result
+fn build(dir: &Path, name: &str, source: &str) -> Result<BuildResult, String> {
+    let source_path = dir.join(format!("{}.rs", name));
+    let binary: PathBuf = dir.join(name);
+    fs::write(&source_path, source).map_err(|e| e.to_string())?;
+
+    let start = Instant::now();
+    let output = Command::new("rustc")
+        .args(["--edition", "2021", "-O", "-C", "codegen-units=1", "-o"])
+        .arg(&binary)
+        .arg(&source_path)
+        .output()
+        .map_err(|e| format!("couldn't run rustc: {}", e))?;
+    let compile_time = start.elapsed();
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).into_owned());
+    }
+
+    let binary_bytes = fs::metadata(&binary).map_err(|e| e.to_string())?.len();
+    let (functions, function_bytes) = summarize_symbols(&binary);
+    Ok(BuildResult { compile_time, binary_bytes, functions, function_bytes })
+}
+
+#[cfg(unix)]
+fn summarize_symbols(binary: &Path) -> (usize, u64) {
+    let symbols = crate::asm::symbols(binary).unwrap_or_default();
+    let copies: Vec<_> = symbols
+        .iter()
+        .filter(|s| s.name.ends_with("::summarize") || s.name.starts_with("core::slice::sort"))
+        .collect();
+    (copies.len(), copies.iter().map(|s| s.size).sum())
+}
+
+#[cfg(not(unix))]
+fn summarize_symbols(_binary: &Path) -> (usize, u64) {
+    (0, 0)
+}
+
+fn demonstrate_scaling(report: &mut DemoReport) {
+    output::heading("🏗️  Generated Programs: Generic vs dyn");
+    outln!("Each program defines N types implementing a trait and calls");
+    outln!("`summarize` (collect, sort by key, sum the top three) once per type:");
+    outln!("  generic: fn summarize<T: Summary>(items: &[T]) -> u64");
+    outln!("  dyn:     fn summarize(items: &[&dyn Summary]) -> u64");
+    outln!("compiled with rustc -O -C codegen-units=1.\n");
+
+    let scratch = match Scratch::new("monomorphization-demo", "builds") {
+        Ok(scratch) => scratch,
+        Err(err) => {
+            outln!("⚠️  Can't create a scratch directory: {}\n", err);
+            return;
+        }
+    };
+    let dir = scratch.dir();
+
+    outln!("  {:>5} │ {:>9} {:>10} {:>15} │ {:>9} {:>10} {:>15}",
+             "types", "compile", "binary", "fns / code", "compile", "binary", "fns / code");
+    outln!("  {:>5} │ {:^36} │ {:^36}", "", "generic", "dyn");
+    for types in [1, 10, 25, 50] {
+        let generic = build(dir, &format!("generic_{}", types), &generate_program(types, true));
+        let dynamic = build(dir, &format!("dyn_{}", types), &generate_program(types, false));
+        match (generic, dynamic) {
+            (Ok(g), Ok(d)) => {
+                let cell = |r: &BuildResult| {
+                    format!("{:>8.2}s {:>7} KB {:>4} / {:>6} B",
+                            r.compile_time.as_secs_f64(), r.binary_bytes / 1024, r.functions, r.function_bytes)
+                };
+                outln!("  {:>5} │ {} │ {}", types, cell(&g), cell(&d));
+                for (variant, result) in [("generic", &g), ("dyn", &d)] {
+                    report.record_time("compile time", result.compile_time).param("variant", variant).param("types", types);
+                    report.record("binary size", result.binary_bytes as f64, "bytes").param("variant", variant).param("types", types);
+                }
+            }
+            (Err(err), _) | (_, Err(err)) => {
+                outln!("⚠️  Build failed: {}", err.lines().next().unwrap_or(""));
+                break;
+            }
+        }
+    }
+    outln!("  (fns / code: how many summarize and core::slice::sort functions the binary");
+    outln!("   contains, and their total machine code)");
+    outln!();
+    outln!("The generic program grows with every type: another summarize, another");
+    outln!("sort specialized for it, more time in LLVM. The dyn program compiles one");
+    outln!("summarize no matter how many types exist; each type only adds its small");
+    outln!("impl and a vtable. The trade: dyn pays an indirect call per weight() at");
+    outln!("runtime (see trait-object-demo), generic pays at compile time and in size.\n");
+}
+
+const QUIZ: &[Question] = &[
+    Question::new(
+        "A generic function is called with three different types. How many times is it compiled?",
+        &["Once", "Three times: one specialized copy per type", "It's interpreted at run time"],
+        1,
+        "Monomorphization gives each copy fully static calls, at the cost of compile time and binary size.",
+    ),
+    Question::new(
+        "How do generic-heavy APIs limit code bloat?",
+        &[
+            "They forward to a non-generic inner function after converting the argument",
+            "They forbid more than one type",
+            "They use macros instead",
+        ],
+        0,
+        "For example, fn open(path: impl AsRef<Path>) calls a private fn inner(path: &Path) that is compiled once.",
+    ),
+];
+
+#[derive(Demo)]
+#[demo(name = "monomorphization-demo", description = "Compile time and binary size of generic vs dyn code",
+       quiz = QUIZ)]
+pub struct MonomorphizationDemo;
+
+impl MonomorphizationDemo {
+    fn demonstrate(&self, _config: &Config) -> DemoReport {
+        output::title("🧬 Monomorphization Cost Explorer");
+        outln!("What a generic function costs when it's used with many types.\n");
+
+        #[cfg(unix)]
+        demonstrate_own_binary();
+        let mut report = DemoReport::default();
+        demonstrate_scaling(&mut report);
+
+        output::takeaways();
+        outln!("• Generics are compiled once per concrete type: fast code, many copies");
+        outln!("• dyn Trait compiles once and dispatches through a vtable at runtime");
+        outln!("• Copies add compile time and binary size, including inner generics like sort");
+        outln!("• Heavy generic APIs often forward to a non-generic inner fn to limit bloat");
+        report
+    }
+}
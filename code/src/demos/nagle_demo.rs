@@ -0,0 +1,296 @@
+//! Nagle's Algorithm Demo
+//!
+//! Small request/response exchanges over loopback TCP, each request sent as
+//! two small writes (a header, then a body) before the client waits for the
+//! reply. Nagle's algorithm holds the body back until the header is
+//! acknowledged; the server, still waiting for the body, has nothing to
+//! piggyback an ACK on and delays it. Each side waits for the other until
+//! the delayed-ACK timer fires: the classic 40ms stall. Compared with
+//! TCP_NODELAY, with one coalesced write, and (on Linux) with a server that
+//! acknowledges immediately through TCP_QUICKACK.
+//! Run with: cargo run --release --bin nagle-demo
+
+use std::io::{self, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::bench::Measurement;
+use crate::demo::{Config, Demo, DemoReport};
+use crate::output::{Style, Table};
+use crate::quiz::Question;
+use crate::verify::Verification;
+use crate::{outln, output};
+
+/// Exchanges per variant, unless the config sets `iterations`
+const EXCHANGES: usize = 40;
+
+const HEADER: usize = 16;
+const BODY: usize = 64;
+const RESPONSE: usize = 32;
+
+/// A round trip slower than this waited for a timer, not for the stack
+const STALL: Duration = Duration::from_millis(5);
+
+/// How the client sends each request, and how the server acknowledges it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Variant {
+    /// Header and body as two writes, Nagle on
+    WriteWriteRead,
+    /// Two writes, TCP_NODELAY on the client
+    NoDelay,
+    /// Header and body copied together and written once, Nagle on
+    SingleWrite,
+    /// Two writes, Nagle on, the server acknowledging at once
+    #[cfg(target_os = "linux")]
+    QuickAck,
+}
+
+impl Variant {
+    const ALL: &[Variant] = &[
+        Variant::WriteWriteRead,
+        Variant::NoDelay,
+        Variant::SingleWrite,
+        #[cfg(target_os = "linux")]
+        Variant::QuickAck,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            Variant::WriteWriteRead => "write, write, read (Nagle on)",
+            Variant::NoDelay => "write, write, read + TCP_NODELAY",
+            Variant::SingleWrite => "one write, read (Nagle on)",
+            #[cfg(target_os = "linux")]
+            Variant::QuickAck => "write, write, read + server TCP_QUICKACK",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Variant::WriteWriteRead => "nagle",
+            Variant::NoDelay => "nodelay",
+            Variant::SingleWrite => "single-write",
+            #[cfg(target_os = "linux")]
+            Variant::QuickAck => "quickack",
+        }
+    }
+}
+
+/// Ask the kernel to acknowledge what arrives right away instead of
+/// waiting to piggyback the ACK. Linux clears this again on its own, so
+/// the server re-arms it after every read.
+#[cfg(target_os = "linux")]
+fn quick_ack(stream: &TcpStream) {
+    use std::os::fd::AsRawFd;
+    let on: libc::c_int = 1;
+    // Safety: a valid socket and a c_int option value of the right size
+    unsafe {
+        libc::setsockopt(stream.as_raw_fd(), libc::IPPROTO_TCP, libc::TCP_QUICKACK,
+                         (&on as *const libc::c_int).cast(), size_of::<libc::c_int>() as libc::socklen_t);
+    }
+}
+
+/// Read whole requests and answer each one, until the client hangs up
+fn serve(mut stream: TcpStream, quick: bool) {
+    let mut request = [0u8; HEADER + BODY];
+    let response = [7u8; RESPONSE];
+    loop {
+        #[cfg(target_os = "linux")]
+        if quick {
+            quick_ack(&stream);
+        }
+        #[cfg(not(target_os = "linux"))]
+        let _ = quick;
+        if stream.read_exact(&mut request).is_err() || stream.write_all(&response).is_err() {
+            return;
+        }
+    }
+}
+
+/// Time `exchanges` request/response round trips sent the `variant` way
+fn exchange(variant: Variant, exchanges: usize) -> io::Result<Measurement> {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let mut client = TcpStream::connect(listener.local_addr()?)?;
+    let (server, _) = listener.accept()?;
+    #[cfg(target_os = "linux")]
+    let quick = variant == Variant::QuickAck;
+    #[cfg(not(target_os = "linux"))]
+    let quick = false;
+    let server = thread::spawn(move || serve(server, quick));
+    client.set_nodelay(variant == Variant::NoDelay)?;
+
+    let header = [1u8; HEADER];
+    let body = [2u8; BODY];
+    let mut request = [0u8; HEADER + BODY];
+    let mut response = [0u8; RESPONSE];
+    let mut samples = Vec::with_capacity(exchanges);
+    for _ in 0..exchanges {
+        let start = Instant::now();
+        if variant == Variant::SingleWrite {
+            request[..HEADER].copy_from_slice(&header);
+            request[HEADER..].copy_from_slice(&body);
+            client.write_all(&request)?;
+        } else {
+            client.write_all(&header)?;
+            client.write_all(&body)?;
+        }
+        client.read_exact(&mut response)?;
+        samples.push(start.elapsed());
+    }
+    drop(client);
+    let _ = server.join();
+    Ok(Measurement::from_samples(samples))
+}
+
+fn stalls(latency: &Measurement) -> usize {
+    latency.samples().iter().filter(|&&sample| sample >= STALL).count()
+}
+
+fn demonstrate_mechanism() {
+    output::heading("🤝 Two Polite Algorithms");
+    outln!("Nagle (sender): while data is unacknowledged, hold back small segments");
+    outln!("                and send them together once the ACK arrives.");
+    outln!("Delayed ACK (receiver): don't acknowledge right away; wait up to ~40ms");
+    outln!("                for a reply to carry the ACK for free.\n");
+    outln!("  client                                  server");
+    outln!("  write(header) ───── segment ─────────▶  header, no full request yet:");
+    outln!("  write(body)   Nagle: held, waiting       nothing to reply, so the ACK");
+    outln!("                for the header's ACK       is delayed...");
+    outln!("       ⋮             (both wait)               ⋮");
+    outln!("                ◀──────── ACK ──────────  ...until the timer fires (~40ms)");
+    outln!("  body sent     ───── segment ─────────▶  full request: respond");
+    outln!("  read(reply)   ◀──── response ─────────\n");
+}
+
+fn demonstrate_exchanges(exchanges: usize, report: &mut DemoReport) -> Vec<(Variant, Measurement)> {
+    output::heading(&format!("⏱️  {} Requests of {} + {} Bytes", exchanges, HEADER, BODY));
+    let mut table = Table::new(["client", "median", "p90", "max", "stalls"]);
+    let mut results = Vec::new();
+    for &variant in Variant::ALL {
+        match exchange(variant, exchanges) {
+            Ok(latency) => {
+                let stalled = stalls(&latency);
+                let stall_cell = format!("{} of {}", stalled, exchanges);
+                table.row([
+                    variant.name().to_string(),
+                    format!("{:.2?}", latency.median()),
+                    format!("{:.2?}", latency.percentile(90.0)),
+                    format!("{:.2?}", latency.percentile(100.0)),
+                    if stalled > 0 { Style::Slower.paint(stall_cell).to_string() } else { stall_cell },
+                ]);
+                report.record_time("round trip", latency.median()).param("client", variant.label());
+                results.push((variant, latency));
+            }
+            Err(err) => {
+                table.row([variant.name().to_string(), format!("failed: {}", err)]);
+            }
+        }
+    }
+    outln!("{}", table);
+    outln!("Stalls are round trips of {:?} or more: a timer, not the network.\n", STALL);
+    results
+}
+
+fn explain(results: &[(Variant, Measurement)]) {
+    let find = |wanted: Variant| results.iter().find(|(variant, _)| *variant == wanted).map(|(_, latency)| latency);
+    let (Some(nagle), Some(nodelay)) = (find(Variant::WriteWriteRead), find(Variant::NoDelay)) else { return };
+    output::heading("🔍 What Happened");
+    if stalls(nagle) > 0 {
+        let ratio = nagle.median().as_secs_f64() / nodelay.median().as_secs_f64().max(1e-9);
+        outln!("With Nagle on, a typical write-write-read exchange took {:.2?}: {:.0}x the",
+               nagle.median(), ratio);
+        outln!("{:.2?} it takes with TCP_NODELAY. Linux starts a connection in", nodelay.median());
+        outln!("quick-ACK mode, so the first exchanges may be fast; once it decides the");
+        outln!("connection is interactive, every request waits for the delayed-ACK timer.");
+    } else {
+        outln!("No stalls this time: this kernel acknowledged the header before the");
+        outln!("body was written (macOS and Windows time delayed ACKs differently, and");
+        outln!("loopback sometimes acknowledges at once). On a real network the same");
+        outln!("code stalls for a delayed ACK per request.");
+    }
+    outln!("\nThe fixes, in the order to reach for them:");
+    outln!("  1. Build the whole request and write it once (BufWriter + flush):");
+    outln!("     fewer syscalls too, and Nagle has nothing to hold back");
+    outln!("  2. TCP_NODELAY (stream.set_nodelay(true)) for request/response protocols;");
+    outln!("     most RPC and HTTP libraries set it by default");
+    outln!("  3. TCP_QUICKACK on the receiver is Linux-only and has to be re-armed");
+    outln!("     after every read: a workaround, not a design\n");
+}
+
+/// Coalescing the request or setting TCP_NODELAY keeps every exchange off
+/// the delayed-ACK timer, and a two-write request is never faster
+fn verify() -> Verification {
+    let mut verification = Verification::new();
+    let exchanges = 20;
+    for variant in [Variant::NoDelay, Variant::SingleWrite] {
+        match exchange(variant, exchanges) {
+            Ok(latency) => verification.check(format!("{} never waits for a timer", variant.name()),
+                                              stalls(&latency) == 0,
+                                              || format!("{} of {} exchanges stalled", stalls(&latency), exchanges)),
+            Err(err) => verification.check(format!("{} completes", variant.name()), false, || err.to_string()),
+        };
+    }
+    match exchange(Variant::WriteWriteRead, exchanges) {
+        Ok(latency) if stalls(&latency) > 0 => verification.check("write-write-read with Nagle stalls", true, String::new),
+        Ok(_) => verification.skip("write-write-read with Nagle stalls", "this kernel acknowledged immediately"),
+        Err(err) => verification.check("write-write-read completes", false, || err.to_string()),
+    };
+    verification
+}
+
+const QUIZ: &[Question] = &[
+    Question::new(
+        "What does Nagle's algorithm do?",
+        &[
+            "Delays acknowledgments to piggyback them on replies",
+            "Holds back small segments while earlier data is unacknowledged",
+            "Retransmits lost segments",
+        ],
+        1,
+        "It batches small writes into fewer segments; delaying ACKs is the receiver's half of the problem.",
+    ),
+    Question::new(
+        "Why does write(header), write(body), read() stall for ~40ms?",
+        &[
+            "The body waits for the header's ACK, and the server delays that ACK waiting for a reply",
+            "Loopback is rate-limited",
+            "The server sleeps between reads",
+        ],
+        0,
+        "Each side politely waits for the other until the delayed-ACK timer breaks the tie.",
+    ),
+    Question::new(
+        "What's the best first fix?",
+        &[
+            "Raise the socket buffer sizes",
+            "Write each request in one call (e.g. through a BufWriter), or set TCP_NODELAY",
+            "Add a sleep between writes",
+        ],
+        1,
+        "One write per message also saves syscalls; TCP_NODELAY suits request/response protocols.",
+    ),
+];
+
+#[derive(Demo)]
+#[demo(name = "nagle-demo", description = "Nagle's algorithm, delayed ACKs and TCP_NODELAY",
+       quiz = QUIZ, verify = verify)]
+pub struct NagleDemo;
+
+impl NagleDemo {
+    fn demonstrate(&self, config: &Config) -> DemoReport {
+        output::title("🐢 Nagle's Algorithm Demo");
+        outln!("Where a 40ms pause in a microsecond exchange comes from.\n");
+
+        demonstrate_mechanism();
+        let mut report = DemoReport::default();
+        let results = demonstrate_exchanges(config.iterations.unwrap_or(EXCHANGES), &mut report);
+        explain(&results);
+
+        output::takeaways();
+        outln!("• Nagle holds small writes while data is unacknowledged");
+        outln!("• Delayed ACKs hold acknowledgments hoping for a reply to ride on");
+        outln!("• Together, write-write-read waits for a timer: ~40ms on Linux");
+        outln!("• Write each message once, or set TCP_NODELAY for request/response traffic");
+        report
+    }
+}
@@ -0,0 +1,351 @@
+//! TCP Round-Trip Demo
+//!
+//! A built-in echo server and a client on loopback: the smallest possible
+//! network round trip, with no wire and no NIC. The client sends a message,
+//! the server reads it and writes it back, and each exchange is timed at
+//! message sizes from a few bytes to a quarter of a megabyte. Every `read`
+//! and `write` on a `TcpStream` is one system call, so both sides count
+//! theirs, and the round trips are related to the copies into and out of
+//! the kernel and the context switches between client and server.
+//! Run with: cargo run --release --bin network-demo
+
+use std::io::{self, Read, Write};
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Instant;
+
+use crate::bench::Measurement;
+use crate::demo::{Config, Demo, DemoReport};
+use crate::output::Table;
+use crate::quiz::Question;
+use crate::verify::Verification;
+use crate::{outln, output};
+
+/// Message sizes exchanged, in bytes
+const SIZES: [usize; 6] = [16, 256, 4096, 16 * 1024, 64 * 1024, 256 * 1024];
+
+/// Round trips per size up to 4 KiB, unless the config sets `iterations`;
+/// larger messages do proportionally fewer
+const ROUND_TRIPS: usize = 5_000;
+
+/// The server's read buffer: a larger message takes several reads
+const BUFFER: usize = 64 * 1024;
+
+/// A stream that counts its `read` and `write` calls: each is one syscall
+struct Counted {
+    stream: TcpStream,
+    syscalls: Arc<AtomicU64>,
+}
+
+impl Read for Counted {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.syscalls.fetch_add(1, Ordering::Relaxed);
+        self.stream.read(buf)
+    }
+}
+
+impl Write for Counted {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.syscalls.fetch_add(1, Ordering::Relaxed);
+        self.stream.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// A client connected to an echo server thread on an ephemeral loopback
+/// port, both sides counting their syscalls
+struct EchoPair {
+    client: Counted,
+    server_syscalls: Arc<AtomicU64>,
+    server: JoinHandle<()>,
+}
+
+impl EchoPair {
+    fn start() -> io::Result<EchoPair> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let addr = listener.local_addr()?;
+        let server_syscalls = Arc::new(AtomicU64::new(0));
+        let counter = Arc::clone(&server_syscalls);
+        let server = thread::spawn(move || {
+            let Ok((stream, _)) = listener.accept() else { return };
+            // Nagle's algorithm could hold back the tail of a large echo
+            // until the client acknowledges the rest; both sides turn it off
+            let _ = stream.set_nodelay(true);
+            echo(Counted { stream, syscalls: counter });
+        });
+        let stream = TcpStream::connect(addr)?;
+        stream.set_nodelay(true)?;
+        Ok(EchoPair { client: Counted { stream, syscalls: Arc::new(AtomicU64::new(0)) }, server_syscalls, server })
+    }
+
+    /// Send `message` and read the echo into `reply`
+    fn round_trip(&mut self, message: &[u8], reply: &mut [u8]) -> io::Result<()> {
+        self.client.write_all(message)?;
+        self.client.read_exact(reply)
+    }
+
+    /// Syscalls made so far by the client and the server together
+    fn syscalls(&self) -> u64 {
+        self.client.syscalls.load(Ordering::Relaxed) + self.server_syscalls.load(Ordering::Relaxed)
+    }
+
+    fn stop(self) {
+        let _ = self.client.stream.shutdown(Shutdown::Both);
+        let _ = self.server.join();
+    }
+}
+
+/// Write back whatever arrives until the client hangs up
+fn echo(mut conn: Counted) {
+    let mut buf = vec![0u8; BUFFER];
+    loop {
+        match conn.read(&mut buf) {
+            Ok(0) | Err(_) => return,
+            Ok(n) => {
+                if conn.write_all(&buf[..n]).is_err() {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+/// What `rounds` round trips of one message size cost
+struct Exchange {
+    size: usize,
+    rounds: usize,
+    /// One sample per round trip
+    latency: Measurement,
+    syscalls: f64,
+    /// Voluntary and involuntary, per round trip; `None` off unix
+    context_switches: Option<f64>,
+    /// Every reply matched its message
+    intact: bool,
+}
+
+/// Time `rounds` round trips of a `size`-byte message over `pair`
+fn exchange(pair: &mut EchoPair, size: usize, rounds: usize) -> io::Result<Exchange> {
+    let message: Vec<u8> = (0..size).map(|i| (i * 31 + 7) as u8).collect();
+    let mut reply = vec![0u8; size];
+    for _ in 0..rounds.div_ceil(10) {
+        pair.round_trip(&message, &mut reply)?;
+    }
+
+    let syscalls_before = pair.syscalls();
+    let switches_before = switches();
+    let mut samples = Vec::with_capacity(rounds);
+    let mut intact = true;
+    for _ in 0..rounds {
+        reply.fill(0);
+        let start = Instant::now();
+        pair.round_trip(&message, &mut reply)?;
+        samples.push(start.elapsed());
+        intact &= reply == message;
+    }
+    let context_switches = switches().zip(switches_before).map(|(after, before)| (after - before) as f64 / rounds as f64);
+    Ok(Exchange {
+        size,
+        rounds,
+        latency: Measurement::from_samples(samples),
+        syscalls: (pair.syscalls() - syscalls_before) as f64 / rounds as f64,
+        context_switches,
+        intact,
+    })
+}
+
+/// Context switches of the process so far, where it can tell
+fn switches() -> Option<u64> {
+    #[cfg(unix)]
+    {
+        let (voluntary, involuntary) = crate::bench::context_switches();
+        Some(voluntary + involuntary)
+    }
+    #[cfg(not(unix))]
+    None
+}
+
+fn rounds_for(size: usize, config: &Config) -> usize {
+    let rounds = config.iterations.unwrap_or(ROUND_TRIPS);
+    (rounds * 4096 / size.max(4096)).max(20)
+}
+
+fn format_size(bytes: usize) -> String {
+    if bytes >= 1024 { format!("{} KiB", bytes / 1024) } else { format!("{} B", bytes) }
+}
+
+fn demonstrate_path() {
+    output::heading("🛣️  One Round Trip Over Loopback");
+    outln!("  client                        kernel                        server");
+    outln!("  write(msg) ──copy──▶ socket buffer ──▶ loopback ──▶ receive queue");
+    outln!("                                                  ──copy──▶ read(buf)");
+    outln!("                                                  ◀──copy── write(buf)");
+    outln!("  read(reply) ◀─copy── receive queue ◀── loopback ◀── socket buffer");
+    outln!("Four syscalls, four copies across the user/kernel boundary, and two");
+    outln!("wakeups: the server blocks in read until the message lands, then the");
+    outln!("client blocks in read until the echo does. Loopback skips the NIC and");
+    outln!("the wire, but not the TCP stack: every segment still gets headers,");
+    outln!("checksums (offloaded), sequence numbers and an ACK.\n");
+}
+
+fn demonstrate_round_trips(config: &Config, report: &mut DemoReport) -> Vec<Exchange> {
+    output::heading("⏱️  Echo Round Trips by Message Size");
+    let mut pair = match EchoPair::start() {
+        Ok(pair) => pair,
+        Err(err) => {
+            outln!("⚠️  Can't set up a loopback connection: {}\n", err);
+            return Vec::new();
+        }
+    };
+    let mut exchanges = Vec::new();
+    for size in SIZES {
+        match exchange(&mut pair, size, rounds_for(size, config)) {
+            Ok(exchange) => exchanges.push(exchange),
+            Err(err) => {
+                outln!("⚠️  The {} exchange failed: {}\n", format_size(size), err);
+                break;
+            }
+        }
+    }
+    pair.stop();
+
+    let mut table = Table::new(["message", "rounds", "median", "p99", "echoed", "syscalls", "copied", "ctx switches"]);
+    for ex in &exchanges {
+        let median = ex.latency.median();
+        let rate = 2.0 * ex.size as f64 / median.as_secs_f64().max(1e-9) / 1e6;
+        table.row([
+            format_size(ex.size),
+            ex.rounds.to_string(),
+            format!("{:.2?}", median),
+            format!("{:.2?}", ex.latency.percentile(99.0)),
+            format!("{:.0} MB/s", rate),
+            format!("{:.1}", ex.syscalls),
+            format_size(4 * ex.size),
+            ex.context_switches.map_or("-".to_string(), |n| format!("{:.1}", n)),
+        ]);
+        report.record_time("round trip", median).param("bytes", ex.size);
+        report.record("syscalls per round trip", ex.syscalls, "calls").param("bytes", ex.size);
+    }
+    outln!("{}", table);
+    if exchanges.iter().any(|ex| !ex.intact) {
+        outln!("❌ Some replies didn't match their messages!\n");
+    }
+    outln!("Per round trip: syscalls by client and server together, bytes copied");
+    outln!("between user space and the kernel, and context switches of the process.");
+    outln!("Echoed counts both directions.\n");
+    exchanges
+}
+
+fn demonstrate_costs(exchanges: &[Exchange]) {
+    let (Some(small), Some(large)) = (exchanges.first(), exchanges.last()) else { return };
+    if large.size <= small.size {
+        return;
+    }
+    output::heading("🧮 Where the Time Goes");
+    let fixed = small.latency.median();
+    let extra = large.latency.median().saturating_sub(fixed);
+    outln!("A {} round trip is almost all fixed cost: {:.2?} for {:.0} syscalls and",
+           format_size(small.size), fixed, small.syscalls);
+    if let Some(switches) = small.context_switches {
+        outln!("{:.1} context switches, however few bytes move.", switches);
+    } else {
+        outln!("the wakeups between client and server, however few bytes move.");
+    }
+    let copied = 4 * (large.size - small.size);
+    if !extra.is_zero() {
+        let per_kib = extra.as_secs_f64() * 1e9 / ((large.size - small.size) as f64 / 1024.0);
+        outln!("Going to {} adds {:.2?}, about {:.0}ns per KiB of message: {} more to",
+               format_size(large.size), extra, per_kib, format_size(copied));
+        outln!("copy, at roughly {:.1} GB/s - memcpy speed, plus segmentation",
+               copied as f64 / extra.as_secs_f64() / 1e9);
+        outln!("into 64 KiB loopback segments, ACKs and {:.0} syscalls instead of {:.0}.",
+               large.syscalls, small.syscalls);
+    }
+    outln!("A real network adds the wire: even a datacenter hop costs tens of");
+    outln!("microseconds, so small messages pay in round trips, not in bytes.\n");
+}
+
+/// Echoes come back intact, a small message costs the four syscalls the
+/// demo draws, and a large one takes longer than a small one
+fn verify() -> Verification {
+    let mut verification = Verification::new();
+    let mut pair = match EchoPair::start() {
+        Ok(pair) => pair,
+        Err(err) => {
+            verification.skip("loopback round trips", format!("no loopback connection: {}", err));
+            return verification;
+        }
+    };
+    let small = exchange(&mut pair, 16, 200);
+    let large = exchange(&mut pair, 256 * 1024, 20);
+    pair.stop();
+    let (Ok(small), Ok(large)) = (small, large) else {
+        verification.check("loopback round trips complete", false, || "an exchange failed".to_string());
+        return verification;
+    };
+    verification
+        .equal("every echo matches its message", (small.intact, large.intact), (true, true))
+        .check("a small message costs four syscalls per round trip", small.syscalls <= 4.05,
+               || format!("{:.2} syscalls per round trip", small.syscalls))
+        .check("a large message needs more syscalls", large.syscalls > small.syscalls,
+               || format!("{:.1} vs {:.1}", large.syscalls, small.syscalls))
+        .faster("a 16-byte round trip is faster than a 256 KiB one", &small.latency, &large.latency, 1.5);
+    verification
+}
+
+const QUIZ: &[Question] = &[
+    Question::new(
+        "How many syscalls does one small echo round trip take, client and server together?",
+        &["One", "Two", "Four: write and read on each side", "None on loopback"],
+        2,
+        "The client writes and reads, the server reads and writes; loopback skips the NIC, not the syscalls.",
+    ),
+    Question::new(
+        "Why does a 16-byte round trip cost nearly as much as a 4 KiB one?",
+        &[
+            "Loopback pads every message to 4 KiB",
+            "The cost is dominated by syscalls, the TCP stack and wakeups, not by copying bytes",
+            "TCP compresses small messages",
+        ],
+        1,
+        "Copying 4 KiB takes well under a microsecond; the fixed per-message cost is much larger.",
+    ),
+    Question::new(
+        "Where does the extra time for a 256 KiB message go?",
+        &[
+            "Copying the bytes into and out of the kernel, in more segments and more syscalls",
+            "Waiting for the network card",
+            "DNS lookups",
+        ],
+        0,
+        "Four copies of every byte per round trip, split over several reads and writes.",
+    ),
+];
+
+#[derive(Demo)]
+#[demo(name = "network-demo", description = "Loopback TCP echo: round-trip latency, syscalls and copies",
+       quiz = QUIZ, verify = verify)]
+pub struct NetworkDemo;
+
+impl NetworkDemo {
+    fn demonstrate(&self, config: &Config) -> DemoReport {
+        output::title("🌐 TCP Round-Trip Demo");
+        outln!("What it costs to send a message and get it back, on one machine.\n");
+
+        demonstrate_path();
+        let mut report = DemoReport::default();
+        let exchanges = demonstrate_round_trips(config, &mut report);
+        demonstrate_costs(&exchanges);
+
+        output::takeaways();
+        outln!("• Even loopback costs microseconds per round trip: syscalls, the TCP stack, wakeups");
+        outln!("• A small message pays a fixed cost; its bytes are nearly free");
+        outln!("• Each byte is copied four times per echo, so large messages pay per byte");
+        outln!("• Batch small messages into fewer round trips when latency adds up");
+        report
+    }
+}
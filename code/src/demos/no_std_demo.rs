@@ -0,0 +1,266 @@
+//! no_std Demo
+//!
+//! What survives without the standard library. `core` needs nothing at all,
+//! `alloc` needs a heap, and `std` needs an operating system. The library's
+//! `bare` module is a `#![no_std]` crate of its own, built with `core` alone:
+//! an LRU cache in a fixed arena, a ring buffer with no heap, and tables
+//! computed by the compiler. Each is compared with its `std` counterpart.
+//! Run with: cargo run --release --bin no-std-demo
+
+use std::collections::{HashMap, VecDeque};
+use std::hint::black_box;
+use std::mem::size_of;
+
+use crate::bare::const_eval::{crc32, CRC32_CHECK, CRC32_TABLE, FIRST_PRIMES};
+use crate::bare::{ArenaLru, RingBuffer};
+use crate::bench::Bench;
+use crate::demo::{Config, Demo, DemoReport};
+use crate::output::Table;
+use crate::quiz::Question;
+use crate::verify::Verification;
+use crate::{out, outln, output};
+
+/// Lookups per timing in the arena LRU sweep
+const LOOKUPS: usize = 100_000;
+
+fn demonstrate_layers() {
+    output::heading("🧱 core, alloc, std: Three Layers");
+    outln!("`std` is not one thing. It re-exports two smaller crates and adds the");
+    outln!("parts that need an operating system:\n");
+
+    let mut table = Table::new(["what", "crate", "needs"]).left(1).left(2);
+    table
+        .row(["Option, Result, iterators, slices, str", "core", "nothing"])
+        .row(["fmt, atomics, Cell, MaybeUninit, mem::swap", "core", "nothing"])
+        .row(["Box, Vec, String, Rc, Arc, BTreeMap", "alloc", "a global allocator"])
+        .row(["HashMap (its RandomState seed)", "std", "OS randomness"])
+        .row(["thread::spawn, Mutex blocking, sleep", "std", "OS threads and futexes"])
+        .row(["File, TcpStream, stdin/stdout", "std", "system calls"])
+        .row(["Instant, SystemTime", "std", "the OS clock"])
+        .row(["env::args, process::exit, main()", "std", "the OS process model"])
+        .row(["panic unwinding, catch_unwind", "std", "an unwinder runtime"]);
+    out!("{}", table);
+    outln!();
+    outln!("A `#![no_std]` crate sees only `core` (plus `alloc` if it declares one).");
+    outln!("A no_std binary must also supply what std used to: a #[panic_handler],");
+    outln!("an entry point, and a #[global_allocator] before it may touch alloc.\n");
+}
+
+fn demonstrate_arena_lru(report: &mut DemoReport) {
+    output::heading("🗂️ An LRU Cache in a Fixed Arena");
+    outln!("No heap means no linked nodes and no HashMap: entries sit in an array of");
+    outln!("N slots that link to each other by index, and lookup scans them.\n");
+
+    let mut lru: ArenaLru<&str, u32, 3> = ArenaLru::new();
+    lru.put("a", 1);
+    lru.put("b", 2);
+    lru.put("c", 3);
+    outln!("  put a, b, c           {:?}", lru);
+    lru.get(&"a");
+    outln!("  get a                 {:?}", lru);
+    if let Some((key, value)) = lru.put("d", 4) {
+        outln!("  put d                 {:?}  (evicted {:?} = {})", lru, key, value);
+    }
+    outln!();
+    outln!("size_of::<ArenaLru<u64, u64, 16>>() = {} bytes, all of it inline: the cache",
+           size_of::<ArenaLru<u64, u64, 16>>());
+    outln!("can live in a static or on the stack of a machine with no allocator.\n");
+
+    outln!("The price of scanning instead of hashing, {} hits per timing:\n", LOOKUPS);
+    let bench = Bench::new().warmup(1);
+    let mut table = Table::new(["capacity", "ArenaLru", "HashMap"]).widths([8, 12, 12]);
+    table.row([
+        "16".to_string(),
+        time_lookups::<16>(&bench, report),
+        time_hashmap(16, &bench, report),
+    ]);
+    table.row([
+        "256".to_string(),
+        time_lookups::<256>(&bench, report),
+        time_hashmap(256, &bench, report),
+    ]);
+    out!("{}", table);
+    outln!();
+    outln!("For a few dozen keys in adjacent slots the scan is as fast as hashing.");
+    outln!("It grows linearly with N, so a larger core-only cache would use a");
+    outln!("fixed-size hash table instead, still without the heap.\n");
+}
+
+/// Hit every key of a full `ArenaLru<u64, u64, N>` in turn; ns per lookup
+fn time_lookups<const N: usize>(bench: &Bench, report: &mut DemoReport) -> String {
+    let mut lru: ArenaLru<u64, u64, N> = ArenaLru::new();
+    for key in 0..N as u64 {
+        lru.put(key, key);
+    }
+    let time = bench.measure(|| {
+        let mut sum = 0;
+        for i in 0..LOOKUPS {
+            sum += lru.get(&black_box((i % N) as u64)).copied().unwrap_or(0);
+        }
+        sum
+    });
+    report.record_time("ArenaLru hits", time.median()).param("capacity", N).param("lookups", LOOKUPS);
+    format!("{:.1} ns", time.median().as_secs_f64() * 1e9 / LOOKUPS as f64)
+}
+
+/// The same lookups in a `HashMap` of `capacity` keys: std's answer
+fn time_hashmap(capacity: usize, bench: &Bench, report: &mut DemoReport) -> String {
+    let map: HashMap<u64, u64> = (0..capacity as u64).map(|key| (key, key)).collect();
+    let time = bench.measure(|| {
+        let mut sum = 0;
+        for i in 0..LOOKUPS {
+            sum += map.get(&black_box((i % capacity) as u64)).copied().unwrap_or(0);
+        }
+        sum
+    });
+    report.record_time("HashMap hits", time.median()).param("capacity", capacity).param("lookups", LOOKUPS);
+    format!("{:.1} ns", time.median().as_secs_f64() * 1e9 / LOOKUPS as f64)
+}
+
+fn demonstrate_ring_buffer() {
+    output::heading("🔁 A Ring Buffer Without a Heap");
+    outln!("`VecDeque` allocates its buffer and grows it. `RingBuffer<T, N>` is N slots");
+    outln!("of MaybeUninit<T> inside the value, and keeps the newest N entries:\n");
+
+    let mut ring: RingBuffer<u32, 4> = RingBuffer::new();
+    for value in 1..=6 {
+        let dropped = ring.push_overwriting(value);
+        let contents: Vec<u32> = ring.iter().copied().collect();
+        match dropped {
+            Some(oldest) => outln!("  push {}  {:?}  (overwrote {})", value, contents, oldest),
+            None => outln!("  push {}  {:?}", value, contents),
+        }
+    }
+    outln!("  pop     {:?}, {} left\n", ring.pop(), ring.len());
+
+    // Both hold 64 u32s; VecDeque's size_of is only its pointer, capacity and indices
+    let deque: VecDeque<u32> = VecDeque::with_capacity(64);
+    let mut table = Table::new(["64 x u32 in", "size_of", "heap bytes"]);
+    table
+        .row(["RingBuffer<u32, 64>".to_string(), size_of::<RingBuffer<u32, 64>>().to_string(), "0".to_string()])
+        .row(["VecDeque<u32>".to_string(), size_of::<VecDeque<u32>>().to_string(),
+              (deque.capacity() * size_of::<u32>()).to_string()]);
+    out!("{}", table);
+    outln!();
+    outln!("The same shape serves a UART receive buffer in an interrupt handler or");
+    outln!("a flight recorder of the last N events: no allocation can fail, and the");
+    outln!("memory use is known at compile time.\n");
+}
+
+fn demonstrate_const_eval() {
+    output::heading("🧮 Computed by the Compiler");
+    outln!("`const CRC32_TABLE: [u32; 256] = crc32_table();` runs crc32_table inside");
+    outln!("the compiler. The binary contains the finished table, nothing else:\n");
+    outln!("  CRC32_TABLE[0..4]  = {:08x?}", &CRC32_TABLE[..4]);
+    outln!("  crc32(\"123456789\") = {:#010x}, checked by a compile-time assert", CRC32_CHECK);
+    outln!("  crc32(\"no_std\")    = {:#010x}, the same function at run time", crc32(black_box(b"no_std")));
+    outln!("  FIRST_PRIMES       = {:?}", FIRST_PRIMES);
+    outln!();
+    outln!("Const evaluation is core-only by nature: the compiler's interpreter has");
+    outln!("no heap to hand out and no OS to call. Loops are `while` (`for` needs");
+    outln!("Iterator::next, not yet const), and a panic there is a compile error.\n");
+}
+
+fn demonstrate_what_std_adds() {
+    output::heading("🖥️ What std Adds on Top of the OS");
+    outln!("Everything above ran on std, but none of it needed std. What does:");
+    outln!("  • Before main: std's runtime sets up args, the stack guard and stdio,");
+    outln!("    then calls main; after it, flushes stdout and exits the process.");
+    outln!("  • Heap: Box and Vec call the global allocator, which std maps to");
+    outln!("    malloc or HeapAlloc, which get pages from mmap or VirtualAlloc.");
+    outln!("  • Blocking: Mutex, Condvar and thread::park sleep in the kernel");
+    outln!("    (futex on Linux); a no_std spin lock can only burn cycles.");
+    outln!("  • I/O, time, randomness: thin wrappers over system calls.");
+    outln!("  • Panics: std prints the message and unwinds; no_std code decides");
+    outln!("    what a panic means in its #[panic_handler], often a reset.");
+    outln!();
+    outln!("Check that `bare` really needs none of it:");
+    outln!("  cargo check -p systems-bare\n");
+}
+
+/// The arena cache evicts in recency order, the ring keeps the newest
+/// entries, and the compile-time CRC matches the standard check value
+fn verify() -> Verification {
+    let mut verification = Verification::new();
+    let mut lru: ArenaLru<u32, u32, 3> = ArenaLru::new();
+    for key in 1..=3 {
+        lru.put(key, key * 10);
+    }
+    lru.get(&1);
+    verification
+        .equal("a full ArenaLru evicts its least recently used entry", lru.put(4, 40), Some((2, 20)))
+        .equal("the next eviction follows recency order", lru.put(5, 50), Some((3, 30)))
+        .equal("iteration runs from most to least recent",
+               lru.iter().map(|(&key, _)| key).collect::<Vec<_>>(), vec![5, 4, 1]);
+
+    let mut ring: RingBuffer<u32, 4> = RingBuffer::new();
+    for value in 1..=6 {
+        ring.push_overwriting(value);
+    }
+    verification
+        .equal("a full RingBuffer keeps the newest N", ring.iter().copied().collect::<Vec<_>>(), vec![3, 4, 5, 6])
+        .equal("RingBuffer pops the oldest first", ring.pop(), Some(3))
+        .equal("the const CRC32 matches the standard check value", CRC32_CHECK, 0xCBF4_3926)
+        .equal("const and run-time CRC32 agree", crc32(black_box(b"123456789")), CRC32_CHECK);
+    verification
+}
+
+const QUIZ: &[Question] = &[
+    Question::new(
+        "Why is HashMap in std rather than in alloc, when Vec and BTreeMap are in alloc?",
+        &[
+            "Hashing needs floating point",
+            "Its default hasher is seeded with randomness from the OS, to resist collision attacks",
+            "HashMap needs threads to resize",
+        ],
+        1,
+        "RandomState asks the OS for random keys. BTreeMap only needs an allocator, so it lives in alloc.",
+    ),
+    Question::new(
+        "How does the arena LRU cache link its entries without heap nodes?",
+        &[
+            "Each slot stores the indices of its neighbours in the same fixed array",
+            "It keeps Box pointers to the next entry",
+            "It sorts the array by access time on every get",
+        ],
+        0,
+        "Indices play the role of pointers: moving an entry to the front rewrites a few usizes, and no memory is allocated or freed.",
+    ),
+    Question::new(
+        "What happens when a const fn panics while computing a `const`?",
+        &[
+            "The program panics when it starts",
+            "The constant is left zeroed",
+            "Compilation fails with the panic message",
+        ],
+        2,
+        "Const evaluation runs inside the compiler, so a panic there is a compile error, which is how `const _: () = assert!(...)` works.",
+    ),
+];
+
+#[derive(Demo)]
+#[demo(name = "no-std-demo", description = "What survives without std: core-only LRU, ring buffer and const eval",
+       quiz = QUIZ, verify = verify)]
+pub struct NoStdDemo;
+
+impl NoStdDemo {
+    fn demonstrate(&self, _config: &Config) -> DemoReport {
+        output::title("🧩 no_std: Rust Without the Standard Library");
+        outln!("Which concepts survive without std, and what std provides on top of the OS.\n");
+
+        let mut report = DemoReport::default();
+        demonstrate_layers();
+        demonstrate_arena_lru(&mut report);
+        demonstrate_ring_buffer();
+        demonstrate_const_eval();
+        demonstrate_what_std_adds();
+
+        output::takeaways();
+        outln!("• core needs nothing, alloc needs an allocator, std needs an OS");
+        outln!("• Fixed-capacity arenas and index links replace heap nodes and pointers");
+        outln!("• Inline storage sized by const generics: memory use known at compile time");
+        outln!("• const fn computes tables in the compiler, with no runtime cost");
+        outln!("• std's real job: the process, the heap, blocking, I/O, time and panics");
+        report
+    }
+}
@@ -0,0 +1,90 @@
+// Demonstration of LLVM optimizations
+
+use crate::demo::{Config, Demo, DemoReport};
+use crate::quiz::Question;
+use crate::{outln, output};
+
+#[allow(clippy::let_and_return)]
+fn constant_folding() -> i32 {
+    // LLVM will calculate this at compile time
+    let x = 5 + 10;
+    let y = x * 2;
+    y
+}
+
+#[allow(unused_variables)]
+fn dead_code_elimination() -> i32 {
+    let x = 5;      // Used
+    let y = 10;     // Never used - LLVM removes this
+    let z = 20;     // Never used - LLVM removes this
+    x
+}
+
+fn type_example() {
+    // Rust's type system: explicit types
+    let x: u32 = 5;        // Always i32 (Rust won't change this)
+    let y: u8 = 5;         // u8 (you specified)
+    
+    // LLVM can optimize operations, but won't change Rust's types
+    let _result = x + y as u32;
+    outln!("result: {}", _result);
+}
+
+const QUIZ: &[Question] = &[
+    Question::new(
+        "What is constant folding?",
+        &[
+            "Computing expressions whose inputs are known at compile time, so the binary just loads the result",
+            "Merging identical constants in the binary",
+            "Unrolling loops with constant bounds",
+        ],
+        0,
+        "let y = 10 + 20 becomes let y = 30 before the program ever runs.",
+    ),
+    Question::new(
+        "Will LLVM shrink an i32 to a u8 because its values happen to be small?",
+        &[
+            "Yes, always",
+            "Only with opt-level=3",
+            "No: the declared type is part of the program's meaning; LLVM optimizes within it",
+        ],
+        2,
+        "Optimizations must preserve behavior, including overflow and layout, so the type stays an i32.",
+    ),
+];
+
+#[derive(Demo)]
+#[demo(name = "optimization-demo", description = "Constant folding, dead code elimination and other LLVM passes",
+       quiz = QUIZ)]
+pub struct OptimizationDemo;
+
+impl OptimizationDemo {
+    fn demonstrate(&self, _config: &Config) -> DemoReport {
+        output::title("LLVM Optimization Demo");
+
+        outln!("1. Constant Folding:");
+        outln!("   Code: let x = 5 + 10; let y = x * 2;");
+        outln!("   LLVM optimizes to: let y = 30; (calculated at compile time)");
+        outln!("   Result: {}\n", constant_folding());
+
+        outln!("2. Dead Code Elimination:");
+        outln!("   Code: let x = 5; let y = 10; let z = 20; return x;");
+        outln!("   LLVM removes: y and z (never used)");
+        outln!("   Result: {}\n", dead_code_elimination());
+
+        outln!("3. Type Optimization:");
+        outln!("   Rust type system prevents automatic type narrowing");
+        outln!("   let x: i32 = 5;  // Always i32, not u8");
+        outln!("   But LLVM optimizes operations on these types");
+        type_example();
+
+        outln!();
+
+        output::takeaways();
+        outln!("- LLVM does extensive optimizations");
+        outln!("- But respects Rust's type system");
+        outln!("- Type narrowing (i32 → u8) doesn't happen automatically");
+        outln!("- Constant folding, dead code elimination, inlining all happen");
+        DemoReport::default()
+    }
+}
@@ -0,0 +1,107 @@
+// Demonstration of optimization levels and their impact
+
+use std::hint::black_box;
+
+use crate::bench::measure;
+use crate::demo::{Config, Demo, DemoReport};
+use crate::quiz::Question;
+use crate::{outln, output};
+
+// Function that benefits from optimization
+fn compute_sum(n: u64) -> u64 {
+    let mut sum = 0u64;
+    for i in 0..n {
+        sum = sum.wrapping_add(i.wrapping_mul(i));
+    }
+    sum
+}
+
+// Function with loop that can be unrolled/vectorized
+fn vector_add(a: &[f64], b: &[f64], result: &mut [f64]) {
+    for i in 0..a.len().min(b.len()).min(result.len()) {
+        result[i] = a[i] + b[i];
+    }
+}
+
+const QUIZ: &[Question] = &[
+    Question::new(
+        "What does building with target-cpu=native give up?",
+        &[
+            "Portability: the binary may use instructions that other CPUs lack",
+            "Runtime speed",
+            "Debug assertions",
+        ],
+        0,
+        "It lets LLVM use everything your CPU supports (e.g. AVX2), so the binary may crash with an illegal instruction elsewhere.",
+    ),
+    Question::new(
+        "Why isn't the highest optimization level always the right choice?",
+        &[
+            "It can produce wrong results",
+            "It makes code slower at run time",
+            "It costs compile time, can grow the binary and makes debugging harder, often for little extra speed",
+        ],
+        2,
+        "The trade-off is build time and debuggability against runtime speed.",
+    ),
+];
+
+#[derive(Demo)]
+#[demo(name = "optimization-levels-demo", description = "The same code at different opt-levels",
+       quiz = QUIZ)]
+pub struct OptimizationLevelsDemo;
+
+impl OptimizationLevelsDemo {
+    fn demonstrate(&self, _config: &Config) -> DemoReport {
+        output::title("Optimization Levels Demo");
+
+        let mut report = DemoReport::default();
+
+        // Test computation
+        let n = 10_000_000u64;
+        let result = compute_sum(n);
+        let duration = measure(5, || compute_sum(black_box(n)));
+
+        outln!("Computation: sum of squares from 0 to {}", n);
+        outln!("Result: {}", result);
+        outln!("Time taken: {}\n", duration);
+        report.record_time("sum of squares", duration.median()).param("n", n);
+
+        // Test vector addition
+        let size = 1_000_000;
+        let a: Vec<f64> = (0..size).map(|i| i as f64).collect();
+        let b: Vec<f64> = (0..size).map(|i| (i * 2) as f64).collect();
+        let mut result_vec = vec![0.0; size];
+
+        let duration = measure(10, || vector_add(&a, &b, black_box(&mut result_vec)));
+
+        outln!("Vector addition: {} elements", size);
+        outln!("Time taken: {}\n", duration);
+        report.record_time("vector add", duration.median()).param("elements", size);
+
+        output::heading("Optimization Levels Explained");
+        outln!("opt-level=0: No optimization (debug builds)");
+        outln!("opt-level=1: Basic optimizations");
+        outln!("opt-level=2: Default for --release (good balance)");
+        outln!("opt-level=3: Aggressive optimizations (may increase compile time)");
+        outln!("opt-level=s: Optimize for size");
+        outln!("opt-level=z: Optimize for size aggressively");
+
+        outln!();
+
+        output::heading("CPU-Specific Optimizations");
+        outln!("target-cpu=native: Optimize for your current CPU");
+        outln!("target-cpu=skylake: Optimize for Intel Skylake");
+        outln!("target-cpu=haswell: Optimize for Intel Haswell");
+        outln!("target-cpu=x86-64-v3: Optimize for AVX2-capable CPUs");
+
+        outln!();
+
+        output::heading("Why Not Max by Default?");
+        outln!("1. Compile time: Higher levels take longer to compile");
+        outln!("2. Binary size: Aggressive optimization can increase size");
+        outln!("3. Debugging: Harder to debug optimized code");
+        outln!("4. Diminishing returns: Level 2 is often good enough");
+        report
+    }
+}
@@ -0,0 +1,291 @@
+//! Persistent Cache Demo
+//!
+//! What a restart costs a cache. An `LruCache` lives in the process, so a
+//! new process starts empty and misses on everything until it has fetched
+//! its working set again. `save_to` writes the entries to disk, least
+//! recently used first, and `load_from` puts them back in that order, so
+//! the next run starts with the same entries in the same recency order. The
+//! demo runs three child processes: one warms a cache and saves it, then
+//! two serve the same next batch of requests, one starting cold and one
+//! from the saved file, and the table compares their hit rates.
+//! Run with: cargo run --release --bin persistent-cache-demo
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+use crate::cache::LruCache;
+use crate::demo::{Config, Demo, DemoReport};
+use crate::output::Table;
+use crate::plot::format_bytes;
+use crate::quiz::Question;
+use crate::rng::{Rng, DEFAULT_SEED};
+use crate::scratch::Scratch;
+use crate::verify::Verification;
+use crate::{outln, output};
+
+/// Entries the cache holds
+const CAPACITY: usize = 10_000;
+
+/// Keys that get 80% of the requests; they fit in the cache
+const HOT_KEYS: usize = 8_000;
+
+/// Keys sharing the other 20%, each seldom seen twice
+const COLD_KEYS: usize = 1_000_000;
+
+/// Requests each run serves, unless the config sets `size`
+const REQUESTS: usize = 100_000;
+
+/// The start of a run, where a cold cache is still filling
+const EARLY: usize = 10_000;
+
+/// Which key each request asks for: 80% a hot key, 20% a cold one
+fn requests(count: usize, seed: u64) -> Vec<u64> {
+    let mut rng = Rng::new(seed);
+    (0..count)
+        .map(|_| match rng.below(5) {
+            0 => (HOT_KEYS + rng.below(COLD_KEYS)) as u64,
+            _ => rng.below(HOT_KEYS) as u64,
+        })
+        .collect()
+}
+
+/// What the backend sends for `key`: the value a miss has to fetch
+fn fetch(key: u64) -> String {
+    format!("response body for key {:07}, fetched from the backend", key)
+}
+
+/// Serve `keys` from `cache`, fetching and caching on a miss; returns the
+/// hits among the first `EARLY` requests and in all
+fn serve(cache: &mut LruCache<u64, String>, keys: &[u64]) -> (usize, usize) {
+    let mut hits = 0;
+    let mut early_hits = 0;
+    for (i, &key) in keys.iter().enumerate() {
+        if cache.get(&key).is_some() {
+            hits += 1;
+            early_hits += usize::from(i < EARLY);
+        } else {
+            cache.put(key, fetch(key));
+        }
+    }
+    (early_hits, hits)
+}
+
+/// One child run, as it reports back
+struct RunResult {
+    loaded: usize,
+    load_time: Duration,
+    early_hits: usize,
+    hits: usize,
+}
+
+/// The child's work: start cold, or from the file at `path` (`warm`); serve
+/// `count` requests drawn with `seed`; save to `path` if asked (`first`);
+/// report on stdout
+fn run_child(mode: &str, path: &Path, seed: u64, count: usize) {
+    let start = Instant::now();
+    let mut cache = match mode {
+        "warm" => match LruCache::load_from(path) {
+            Ok(cache) => cache,
+            Err(err) => {
+                println!("ERROR loading {}: {}", path.display(), err);
+                return;
+            }
+        },
+        _ => LruCache::new(CAPACITY),
+    };
+    let load_time = start.elapsed();
+    let loaded = cache.len();
+    let (early_hits, hits) = serve(&mut cache, &requests(count, seed));
+    if mode == "first"
+        && let Err(err) = cache.save_to(path)
+    {
+        println!("ERROR saving {}: {}", path.display(), err);
+        return;
+    }
+    println!("RESULT {} {} {} {}", loaded, load_time.as_nanos(), early_hits, hits);
+}
+
+/// If this process is one of the demo's children (`--restart-child <mode>
+/// <path> <seed> <requests>`), do the child's work and return true. The
+/// demo re-runs its own executable for each run, so the binary's `main`
+/// must call this first.
+pub fn run_as_child() -> bool {
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() == 6 && args[1] == "--restart-child" {
+        let seed = args[4].parse().unwrap_or(DEFAULT_SEED);
+        let count = args[5].parse().unwrap_or(REQUESTS);
+        run_child(&args[2], Path::new(&args[3]), seed, count);
+        return true;
+    }
+    false
+}
+
+/// Run one child process and parse its report
+fn spawn_child(mode: &str, path: &Path, seed: u64, count: usize) -> Result<RunResult, String> {
+    let exe = std::env::current_exe().map_err(|err| err.to_string())?;
+    let output = Command::new(exe)
+        .arg("--restart-child")
+        .args([mode, &path.to_string_lossy(), &seed.to_string(), &count.to_string()])
+        .output()
+        .map_err(|err| err.to_string())?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let Some(line) = stdout.lines().find(|line| line.starts_with("RESULT")) else {
+        return Err(stdout.lines().find(|line| line.starts_with("ERROR")).unwrap_or("child failed").to_string());
+    };
+    let fields: Vec<u64> = line.split_whitespace().skip(1).map_while(|v| v.parse().ok()).collect();
+    let &[loaded, load_nanos, early_hits, hits] = fields.as_slice() else {
+        return Err("malformed child report".to_string());
+    };
+    Ok(RunResult {
+        loaded: loaded as usize,
+        load_time: Duration::from_nanos(load_nanos),
+        early_hits: early_hits as usize,
+        hits: hits as usize,
+    })
+}
+
+fn demonstrate_restart(config: &Config, report: &mut DemoReport) {
+    output::heading("🔁 Three Processes");
+    let count = config.size.unwrap_or(REQUESTS).max(2 * EARLY);
+    let seed = config.seed.unwrap_or(DEFAULT_SEED);
+    outln!("A {}-entry LruCache; each run serves {} requests, 80% to {} hot", CAPACITY, count, HOT_KEYS);
+    outln!("keys and 20% to {} cold ones, fetching and caching on a miss. The", COLD_KEYS);
+    outln!("first run saves its cache on the way out; the two restarts serve the");
+    outln!("same next {} requests, one empty, one from the file.\n", count);
+
+    let scratch = match Scratch::new("persistent-cache-demo", "run") {
+        Ok(scratch) => scratch,
+        Err(err) => {
+            outln!("⚠️  No scratch directory: {}\n", err);
+            return;
+        }
+    };
+    let file = scratch.path("cache.lru");
+    let runs = [("first run, cold", "first", seed), ("restart, cold", "cold", seed + 1), ("restart, loaded", "warm", seed + 1)];
+    let mut table = Table::new(["run", "entries at start", "load", "first 10K hit rate", "hit rate"]).left(0);
+    let mut results = Vec::new();
+    for (name, mode, seed) in runs {
+        let result = match spawn_child(mode, &file, seed, count) {
+            Ok(result) => result,
+            Err(reason) => {
+                outln!("⚠️  The {} child failed: {}\n", name, reason);
+                return;
+            }
+        };
+        if mode == "first" {
+            let size = fs::metadata(&file).map(|meta| meta.len()).unwrap_or(0);
+            outln!("The first run's save: {} for {} entries.\n", format_bytes(size as f64), CAPACITY);
+        }
+        let early = result.early_hits as f64 / EARLY as f64;
+        let overall = result.hits as f64 / count as f64;
+        report.record(name, early, "hit rate").param("requests", EARLY);
+        report.record(name, overall, "hit rate").param("requests", count);
+        table.row([name.to_string(), result.loaded.to_string(), format!("{:.2?}", result.load_time),
+                   format!("{:.1}%", 100.0 * early), format!("{:.1}%", 100.0 * overall)]);
+        results.push(result);
+    }
+    outln!("{}", table);
+
+    let (cold, warm) = (&results[1], &results[2]);
+    let saved_fetches = warm.hits as i64 - cold.hits as i64;
+    outln!("The cold restart spends its first requests refetching what the last");
+    outln!("run already had: {:.1}% of them hit, against {:.1}% from the loaded", 100.0 * cold.early_hits as f64 / EARLY as f64,
+           100.0 * warm.early_hits as f64 / EARLY as f64);
+    match saved_fetches {
+        saved if saved >= 0 => outln!("file. Over the whole run the loaded cache saved {} backend fetches,", saved),
+        lost => outln!("file. Over the whole run the loaded cache made {} more backend fetches,", -lost),
+    }
+    outln!("for a {:.2?} load. Once a cold cache has seen its working set the two", warm.load_time);
+    outln!("converge, so a saved cache pays off most where restarts are frequent");
+    outln!("and misses are expensive: deploys, crash loops, autoscaled replicas.\n");
+    outln!("The file keeps recency as well as contents: entries go out least");
+    outln!("recently used first and are put back in that order, so the loaded");
+    outln!("cache evicts the same entries next that the saved one would have.\n");
+}
+
+/// A saved and loaded cache keeps its order, and hits more often at the
+/// start of the next batch than an empty one
+fn verify() -> Verification {
+    let mut verification = Verification::new();
+    let mut first = LruCache::new(CAPACITY);
+    serve(&mut first, &requests(50_000, 1));
+    let saved = Scratch::new("persistent-cache-demo", "verify").and_then(|scratch| {
+        first.save_to(scratch.path("cache.lru"))?;
+        LruCache::load_from(scratch.path("cache.lru"))
+    });
+    let mut loaded: LruCache<u64, String> = match saved {
+        Ok(cache) => cache,
+        Err(err) => {
+            verification.check("the cache saves and loads", false, || err.to_string());
+            return verification;
+        }
+    };
+    let same_order = first.iter().map(|(key, _)| key).eq(loaded.iter().map(|(key, _)| key));
+    verification.check("the loaded cache has the saved entries in the same order", same_order,
+                       || "the order differs".to_string());
+
+    let next = requests(EARLY, 2);
+    let (cold_hits, _) = serve(&mut LruCache::new(CAPACITY), &next);
+    let (warm_hits, _) = serve(&mut loaded, &next);
+    verification.check("the loaded cache hits more often over the first 10K requests", warm_hits > cold_hits,
+                       || format!("{} vs {} hits", warm_hits, cold_hits));
+    verification
+}
+
+const QUIZ: &[Question] = &[
+    Question::new(
+        "Why does a restarted process's cache hit less at first, even with the same traffic?",
+        &[
+            "The new process has less memory",
+            "It starts empty, so every key it had already fetched misses once more",
+            "The hash function is reseeded",
+        ],
+        1,
+        "A cache is process memory: its contents die with the process unless something saves them.",
+    ),
+    Question::new(
+        "Why write the entries least recently used first?",
+        &[
+            "It makes the file smaller",
+            "Putting them back in file order then rebuilds the same recency order",
+            "JSON requires it",
+        ],
+        1,
+        "Each put makes its entry the most recent, so the last one written ends up at the front again.",
+    ),
+    Question::new(
+        "Why does save_to write a temporary file and rename it?",
+        &[
+            "Renaming is faster than writing",
+            "A crash mid-save then leaves the old file or the new one, never a torn mix",
+            "To keep a backup of the old cache",
+        ],
+        1,
+        "Rename swaps the name in one step: anyone opening the path sees the whole old file or the whole new one.",
+    ),
+];
+
+#[derive(Demo)]
+#[demo(name = "persistent-cache-demo", description = "Save an LruCache with its recency order, and restart warm instead of cold",
+       quiz = QUIZ, verify = verify)]
+pub struct PersistentCacheDemo;
+
+impl PersistentCacheDemo {
+    fn demonstrate(&self, config: &Config) -> DemoReport {
+        output::title("💽 Persistent Cache Demo");
+        outln!("Keeping a cache warm across a restart.\n");
+
+        let mut report = DemoReport::default();
+        demonstrate_restart(config, &mut report);
+
+        output::takeaways();
+        outln!("• A cache dies with its process; a restart begins with a run of misses");
+        outln!("• Saving the entries at exit and loading them at start skips that warm-up");
+        outln!("• Write least recently used first, so loading rebuilds the recency order");
+        outln!("• Write a temporary file and rename it, so a crash never leaves half a save");
+        outln!("• The gain is concentrated at the start: a cold cache catches up with time");
+        report
+    }
+}
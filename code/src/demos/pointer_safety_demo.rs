@@ -0,0 +1,444 @@
+//! Pointer Safety Demo
+//!
+//! Demonstrates Rust's memory safety guarantees and raw pointer usage.
+//! Run with: cargo run --bin pointer-safety-demo
+
+use std::cell::UnsafeCell;
+use std::ops::{Deref, DerefMut};
+use std::ptr;
+
+use crate::demo::{Config, Demo, DemoReport};
+use crate::quiz::Question;
+use crate::{outln, output};
+
+/// A miniature `std::cell::Cell`: values are copied in and out, never
+/// borrowed, so mutation through `&self` can't invalidate a reference
+struct MiniCell<T> {
+    value: UnsafeCell<T>,
+}
+
+impl<T: Copy> MiniCell<T> {
+    fn new(value: T) -> Self {
+        MiniCell { value: UnsafeCell::new(value) }
+    }
+
+    fn get(&self) -> T {
+        // SAFETY: single-threaded (UnsafeCell makes us !Sync) and no
+        // reference to the inside ever escapes, so nobody observes the write
+        unsafe { *self.value.get() }
+    }
+
+    fn set(&self, value: T) {
+        // SAFETY: as in `get`
+        unsafe { *self.value.get() = value }
+    }
+}
+
+/// Borrow state of a `MiniRefCell`: 0 = free, n > 0 = n shared borrows,
+/// -1 = one mutable borrow
+const MUTABLY_BORROWED: isize = -1;
+
+/// A miniature `std::cell::RefCell`: the borrow rules enforced at runtime
+/// with a counter instead of at compile time by the borrow checker
+struct MiniRefCell<T> {
+    borrows: MiniCell<isize>,
+    value: UnsafeCell<T>,
+}
+
+struct MiniRef<'a, T> {
+    cell: &'a MiniRefCell<T>,
+}
+
+struct MiniRefMut<'a, T> {
+    cell: &'a MiniRefCell<T>,
+}
+
+impl<T> MiniRefCell<T> {
+    fn new(value: T) -> Self {
+        MiniRefCell { borrows: MiniCell::new(0), value: UnsafeCell::new(value) }
+    }
+
+    fn try_borrow(&self) -> Result<MiniRef<'_, T>, &'static str> {
+        match self.borrows.get() {
+            MUTABLY_BORROWED => Err("already mutably borrowed"),
+            n => {
+                self.borrows.set(n + 1);
+                Ok(MiniRef { cell: self })
+            }
+        }
+    }
+
+    fn try_borrow_mut(&self) -> Result<MiniRefMut<'_, T>, &'static str> {
+        match self.borrows.get() {
+            0 => {
+                self.borrows.set(MUTABLY_BORROWED);
+                Ok(MiniRefMut { cell: self })
+            }
+            MUTABLY_BORROWED => Err("already mutably borrowed"),
+            _ => Err("already borrowed"),
+        }
+    }
+
+    fn borrow(&self) -> MiniRef<'_, T> {
+        self.try_borrow().unwrap_or_else(|err| panic!("MiniRefCell: {}", err))
+    }
+
+    fn borrow_mut(&self) -> MiniRefMut<'_, T> {
+        self.try_borrow_mut().unwrap_or_else(|err| panic!("MiniRefCell: {}", err))
+    }
+
+    fn borrow_state(&self) -> isize {
+        self.borrows.get()
+    }
+}
+
+impl<T> Deref for MiniRef<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // SAFETY: the counter guarantees no MiniRefMut exists while we do
+        unsafe { &*self.cell.value.get() }
+    }
+}
+
+impl<T> Drop for MiniRef<'_, T> {
+    fn drop(&mut self) {
+        self.cell.borrows.set(self.cell.borrows.get() - 1);
+    }
+}
+
+impl<T> Deref for MiniRefMut<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // SAFETY: the counter guarantees we are the only borrow
+        unsafe { &*self.cell.value.get() }
+    }
+}
+
+impl<T> DerefMut for MiniRefMut<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        // SAFETY: as in `deref`
+        unsafe { &mut *self.cell.value.get() }
+    }
+}
+
+impl<T> Drop for MiniRefMut<'_, T> {
+    fn drop(&mut self) {
+        self.cell.borrows.set(0);
+    }
+}
+
+/// A pointer with a small tag packed into its low bits, which are always
+/// zero for a `T` with alignment > 1. Uses the strict-provenance APIs so the
+/// pointer never round-trips through a plain integer.
+struct TaggedPtr<T> {
+    ptr: *mut T,
+}
+
+impl<T> TaggedPtr<T> {
+    /// Bits guaranteed zero in any well-aligned `*mut T`
+    const TAG_MASK: usize = std::mem::align_of::<T>() - 1;
+
+    fn new(ptr: *mut T, tag: usize) -> Self {
+        assert!(ptr.addr() & Self::TAG_MASK == 0, "pointer is not aligned");
+        assert!(tag <= Self::TAG_MASK, "tag needs more bits than the alignment leaves free");
+        // map_addr changes the address but keeps the original provenance
+        TaggedPtr { ptr: ptr.map_addr(|addr| addr | tag) }
+    }
+
+    fn tag(&self) -> usize {
+        self.ptr.addr() & Self::TAG_MASK
+    }
+
+    fn with_tag(&self, tag: usize) -> Self {
+        Self::new(self.pointer(), tag)
+    }
+
+    /// The real pointer, tag bits cleared, still carrying its provenance
+    fn pointer(&self) -> *mut T {
+        self.ptr.map_addr(|addr| addr & !Self::TAG_MASK)
+    }
+}
+
+fn demonstrate_raw_pointers() {
+    output::heading("🔍 Raw Pointers & Safety");
+
+    let mut value = 42;
+    let raw_ptr: *mut i32 = &mut value;
+
+    outln!("Value: {}", value);
+    outln!("Raw pointer address: {:p}", raw_ptr);
+
+    // Safe access through raw pointer (unsafe block required)
+    unsafe {
+        outln!("Value through raw pointer: {}", *raw_ptr);
+        *raw_ptr = 100;
+        outln!("Modified value: {}", *raw_ptr);
+    }
+
+    outln!("Final value: {}", value);
+    outln!();
+}
+
+fn demonstrate_null_pointers() {
+    output::heading("🚫 Null Pointers");
+
+    let null_ptr: *const i32 = ptr::null();
+
+    outln!("Null pointer: {:p}", null_ptr);
+    outln!("Is null: {}", null_ptr.is_null());
+
+    // This would be unsafe and crash in C/C++
+    // In Rust, we must explicitly check
+    if !null_ptr.is_null() {
+        unsafe {
+            outln!("Value: {}", *null_ptr); // Never reached
+        }
+    } else {
+        outln!("✅ Safely avoided null pointer dereference!");
+    }
+    outln!();
+}
+
+fn demonstrate_pointer_arithmetic() {
+    output::heading("🔢 Pointer Arithmetic");
+
+    let array = [10, 20, 30, 40, 50];
+    let ptr: *const i32 = array.as_ptr();
+
+    outln!("Array: {:?}", array);
+    outln!("Pointer to start: {:p}", ptr);
+
+    // Safe pointer arithmetic with offsets
+    unsafe {
+        for i in 0..array.len() {
+            let offset_ptr = ptr.add(i);
+            outln!("Element {} at {:p}: {}", i, offset_ptr, *offset_ptr);
+        }
+    }
+
+    // Bounds checking prevents out-of-bounds access
+    outln!("✅ Bounds checking prevents buffer overflows!");
+    outln!();
+}
+
+fn demonstrate_smart_pointers() {
+    output::heading("🧠 Smart Pointers");
+
+    // Box - heap allocation
+    let boxed = Box::new(42);
+    outln!("Boxed value: {}", boxed);
+    outln!("Box pointer: {:p}", &*boxed);
+
+    // Reference counting
+    use std::rc::Rc;
+    let rc_value = Rc::new(String::from("Shared"));
+    let _rc1 = Rc::clone(&rc_value);
+    let _rc2 = Rc::clone(&rc_value);
+
+    outln!("RC value: '{}'", rc_value);
+    outln!("Reference count: {}", Rc::strong_count(&rc_value));
+
+    // Atomic reference counting for threads
+    use std::sync::Arc;
+    let arc_value = Arc::new(vec![1, 2, 3]);
+    let _arc1 = Arc::clone(&arc_value);
+    let _arc2 = Arc::clone(&arc_value);
+
+    outln!("Arc value: {:?}", arc_value);
+    outln!("Reference count: {}", Arc::strong_count(&arc_value));
+    outln!();
+}
+
+fn demonstrate_lifetimes() {
+    output::heading("⏰ Lifetime Safety");
+
+    fn longest<'a>(s1: &'a str, s2: &'a str) -> &'a str {
+        if s1.len() > s2.len() {
+            s1
+        } else {
+            s2
+        }
+    }
+
+    let string1 = String::from("short");
+    let string2 = String::from("longer");
+
+    let result = longest(&string1, &string2);
+    outln!("longest('{}', '{}') = '{}'", string1, string2, result);
+
+    // This would fail at compile time:
+    // let result2;
+    // {
+    //     let temp = String::from("temp");
+    //     result2 = longest(&string1, &temp); // ❌ temp doesn't live long enough
+    // }
+
+    outln!("✅ Lifetime checking prevents dangling references!");
+    outln!();
+}
+
+fn demonstrate_interior_mutability() {
+    output::heading("🔐 Interior Mutability: Cell and RefCell from Scratch");
+
+    outln!("`&T` promises the compiler the value won't change, and it optimizes on");
+    outln!("that promise. Casting `&T` to `*mut T` and writing is undefined behavior.");
+    outln!("UnsafeCell<T> is the one type that opts out: `get()` returns a `*mut T`");
+    outln!("that may legally be written through a shared reference. Cell, RefCell,");
+    outln!("Mutex and the atomics are all built on it.\n");
+
+    let counter = MiniCell::new(0);
+    let shared = (&counter, &counter); // two shared references, no `mut` anywhere
+    shared.0.set(shared.0.get() + 1);
+    shared.1.set(shared.1.get() + 1);
+    outln!("MiniCell: incremented through two &-references -> {}", counter.get());
+    outln!("  Safe because values are only copied in and out: no reference to the");
+    outln!("  inside exists that a `set` could invalidate.\n");
+
+    let cell = MiniRefCell::new(vec![1, 2, 3]);
+    outln!("MiniRefCell borrow counter (0 free, n shared, -1 mutable):");
+    outln!("  initially:                 {}", cell.borrow_state());
+    {
+        let first = cell.borrow();
+        let second = cell.borrow();
+        outln!("  two borrow()s alive:       {} (len {} and {})", cell.borrow_state(), first.len(), second.len());
+        outln!("  try_borrow_mut() now:      {:?}", cell.try_borrow_mut().err());
+    }
+    outln!("  after both guards dropped: {}", cell.borrow_state());
+    {
+        let mut writer = cell.borrow_mut();
+        writer.push(4);
+        outln!("  borrow_mut() alive:        {}", cell.borrow_state());
+        outln!("  try_borrow() now:          {:?}", cell.try_borrow().err());
+    }
+    outln!("  after push, guard dropped: {} -> {:?}\n", cell.borrow_state(), *cell.borrow());
+
+    // What the borrow checker would reject at compile time becomes a panic
+    #[cfg(panic = "unwind")]
+    {
+        let previous_hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(|_| {})); // keep the expected panic quiet
+        // AssertUnwindSafe: we only inspect the counter afterwards, which the
+        // guards' Drop impls keep consistent during unwinding
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let _reader = cell.borrow();
+            let _writer = cell.borrow_mut(); // conflicting borrow
+        }));
+        std::panic::set_hook(previous_hook);
+        if let Err(payload) = result {
+            let message = payload.downcast_ref::<String>().map_or("(non-string panic)", String::as_str);
+            outln!("borrow() then borrow_mut(): panicked with \"{}\"", message);
+        }
+        outln!("  The reader's guard was dropped during unwinding, so the cell is usable");
+        outln!("  again: state {}.", cell.borrow_state());
+    }
+    #[cfg(not(panic = "unwind"))]
+    outln!("borrow() then borrow_mut() panics; this build aborts on panic (as wasm32 does), so it's skipped.");
+    outln!("Sizes: RefCell<i32> = {} bytes, MiniRefCell<i32> = {} bytes (value + counter)\n",
+             std::mem::size_of::<std::cell::RefCell<i32>>(), std::mem::size_of::<MiniRefCell<i32>>());
+}
+
+fn demonstrate_strict_provenance() {
+    output::heading("🧭 Strict Provenance: Pointers Are More Than Addresses");
+    outln!("A pointer carries an address *and* provenance: which allocation it may");
+    outln!("access. Alias analysis depends on it (\"this store through p can't touch");
+    outln!("x\"). `ptr as usize` keeps only the address, and `usize as *mut T` has to");
+    outln!("guess where provenance comes from, so optimizers must be pessimistic");
+    outln!("and Miri can't check the access. The strict-provenance APIs never lose it:");
+    outln!("  ptr.addr()                 the address as usize, provenance not exposed");
+    outln!("  ptr.with_addr(addr)        a new address, the same provenance");
+    outln!("  ptr.map_addr(|a| ...)      with_addr(f(ptr.addr()))");
+    outln!("  ptr::without_provenance(a) a pointer that may never be dereferenced\n");
+
+    let node = Box::into_raw(Box::new(42u64));
+    let tagged = TaggedPtr::new(node, 0b01);
+    outln!("Tagged pointer: a Box<u64> (align {}) has {} free low bits",
+             std::mem::align_of::<u64>(), TaggedPtr::<u64>::TAG_MASK.count_ones());
+    outln!("  raw pointer    {:#x}", node.addr());
+    outln!("  tagged pointer {:#x} (tag {:#05b}, e.g. \"marked for deletion\")", tagged.ptr.addr(), tagged.tag());
+    let retagged = tagged.with_tag(0b110);
+    outln!("  retagged       {:#x} (tag {:#05b})", retagged.ptr.addr(), retagged.tag());
+    // SAFETY: pointer() strips the tag and keeps the Box's provenance
+    unsafe {
+        *retagged.pointer() += 1;
+        outln!("  *pointer() = {} (read through the untagged, provenance-carrying pointer)", *retagged.pointer());
+        drop(Box::from_raw(retagged.pointer()));
+    }
+    outln!("  The old way, `((p as usize) | tag) as *mut u64`, makes the same bits but");
+    outln!("  loses provenance; Miri flags it under -Zmiri-strict-provenance.\n");
+
+    let buffer = [0u8; 64];
+    let base = buffer.as_ptr();
+    // The first 16-byte boundary strictly after `base`: at most 16 bytes in
+    let aligned = base.with_addr((base.addr() + 16) & !15);
+    outln!("with_addr for address math inside one allocation:");
+    outln!("  buffer at {:#x}, next 16-byte boundary {:#x} (offset {}) - same allocation,",
+             base.addr(), aligned.addr(), aligned.addr() - base.addr());
+    // SAFETY: `aligned` is within `buffer` and carries its provenance
+    outln!("  so reading through it is fine: {}", unsafe { *aligned });
+    let sentinel: *const u8 = ptr::without_provenance(0x1);
+    outln!("  without_provenance(0x1) = {:p}: fine to compare, UB to dereference", sentinel);
+    outln!("When an integer really must become a pointer (e.g. an address from the OS),");
+    outln!("`expose_provenance` / `with_exposed_provenance` make the guess explicit.\n");
+}
+
+const QUIZ: &[Question] = &[
+    Question::new(
+        "Which raw-pointer operation requires unsafe?",
+        &["Creating the pointer", "Dereferencing it", "Printing its address"],
+        1,
+        "Creating a raw pointer is harmless; reading or writing through it is where validity must be promised.",
+    ),
+    Question::new(
+        "What is the only legal way to mutate data through a shared &T?",
+        &[
+            "Casting the &T to *mut T",
+            "UnsafeCell, which Cell, RefCell, Mutex and the atomics are built on",
+            "mem::transmute to &mut T",
+        ],
+        1,
+        "The compiler assumes data behind &T is frozen unless it sits inside an UnsafeCell.",
+    ),
+    Question::new(
+        "Why tag pointers with map_addr instead of casting to usize and back?",
+        &[
+            "Pointers carry provenance, which a usize round-trip can lose",
+            "usize is too small to hold a pointer",
+            "map_addr is faster",
+        ],
+        0,
+        "Provenance is what the pointer is allowed to access; the strict-provenance APIs keep it attached.",
+    ),
+];
+
+#[derive(Demo)]
+#[demo(name = "pointer-safety-demo", description = "Memory safety, raw pointers and strict provenance",
+       quiz = QUIZ)]
+pub struct PointerSafetyDemo;
+
+impl PointerSafetyDemo {
+    fn demonstrate(&self, _config: &Config) -> DemoReport {
+        output::title("🛡️  Pointer Safety Demo");
+        outln!("How Rust prevents memory corruption and undefined behavior.\n");
+
+        demonstrate_raw_pointers();
+        demonstrate_null_pointers();
+        demonstrate_pointer_arithmetic();
+        demonstrate_smart_pointers();
+        demonstrate_lifetimes();
+        demonstrate_interior_mutability();
+        demonstrate_strict_provenance();
+
+        output::takeaways();
+        outln!("• Raw pointers require unsafe blocks");
+        outln!("• Null pointer dereference is prevented");
+        outln!("• Bounds checking prevents buffer overflows");
+        outln!("• Smart pointers provide automatic memory management");
+        outln!("• Lifetime checking prevents dangling references");
+        outln!("• UnsafeCell is the only legal way to mutate through &T; RefCell adds a borrow counter");
+        outln!("• Pointers carry provenance: tag them with map_addr, not usize round-trips");
+        outln!("• Memory safety is guaranteed at compile time");
+        DemoReport::default()
+    }
+}
@@ -0,0 +1,417 @@
+//! PRNG Demo
+//!
+//! Pseudo-random number generators are a few lines of arithmetic on a
+//! state word, and the few lines matter. This demo implements three
+//! classics from scratch (a 32-bit linear congruential generator,
+//! xorshift64* and PCG32) next to the xoshiro256** in `crate::rng` that
+//! the other demos' benchmarks are seeded from. It prints their low bits,
+//! where an LCG's patterns are plain to see, runs four statistical tests
+//! (monobit, runs, chi-square and a serial chi-square on pairs) and
+//! measures how many numbers per second each one makes.
+//! Run with: cargo run --release --bin prng-demo
+
+use std::hint::black_box;
+
+use crate::bench::{format_rate, measure};
+use crate::demo::{Config, Demo, DemoReport};
+use crate::output::{Style, Table};
+use crate::quiz::Question;
+use crate::rng::Rng;
+use crate::verify::Verification;
+use crate::{outln, output};
+
+/// Numbers per throughput run (`--size`)
+const NUMBERS: usize = 10_000_000;
+/// Numbers fed to each statistical test
+const SAMPLES: usize = 1 << 20;
+const RUNS: usize = 5;
+/// A test fails below this p-value, or above 1 - SIGNIFICANCE: output
+/// that is too even is as suspicious as output that is too lumpy
+const SIGNIFICANCE: f64 = 0.001;
+
+/// A source of 32-bit pseudo-random numbers
+pub trait Generator {
+    fn name(&self) -> &'static str;
+    fn next_u32(&mut self) -> u32;
+}
+
+/// The Numerical Recipes LCG: state = state × 1664525 + 1013904223 mod 2^32,
+/// returned whole. Bit k of the state only depends on bits 0..=k, so the
+/// low bit alternates and bit k repeats every 2^(k+1) numbers.
+pub struct Lcg {
+    state: u32,
+}
+
+impl Lcg {
+    pub fn new(seed: u64) -> Lcg {
+        Lcg { state: seed as u32 }
+    }
+}
+
+impl Generator for Lcg {
+    fn name(&self) -> &'static str {
+        "LCG (32-bit)"
+    }
+
+    fn next_u32(&mut self) -> u32 {
+        self.state = self.state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+        self.state
+    }
+}
+
+/// Marsaglia's xorshift, three shift-and-xors of a 64-bit state, with the
+/// output multiplied by a constant (Vigna's "star") to scramble the weak
+/// low bits; returns the high half of the product
+pub struct XorShift64Star {
+    state: u64,
+}
+
+impl XorShift64Star {
+    pub fn new(seed: u64) -> XorShift64Star {
+        // An all-zero state would stay zero forever
+        XorShift64Star { state: if seed == 0 { 0x9E37_79B9_7F4A_7C15 } else { seed } }
+    }
+}
+
+impl Generator for XorShift64Star {
+    fn name(&self) -> &'static str {
+        "xorshift64*"
+    }
+
+    fn next_u32(&mut self) -> u32 {
+        let mut x = self.state;
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        self.state = x;
+        (x.wrapping_mul(0x2545_F491_4F6C_DD1D) >> 32) as u32
+    }
+}
+
+/// O'Neill's PCG32 (XSH RR): a 64-bit LCG whose top bits pick a rotation
+/// for an xorshifted mix of the rest. The LCG steps; the output function
+/// hides its structure.
+pub struct Pcg32 {
+    state: u64,
+    increment: u64,
+}
+
+impl Pcg32 {
+    const MULTIPLIER: u64 = 6_364_136_223_846_793_005;
+
+    /// Seeded like the reference `pcg32_srandom_r`; each `stream` is a
+    /// different sequence
+    pub fn new(seed: u64, stream: u64) -> Pcg32 {
+        let mut pcg = Pcg32 { state: 0, increment: stream << 1 | 1 };
+        pcg.next_u32();
+        pcg.state = pcg.state.wrapping_add(seed);
+        pcg.next_u32();
+        pcg
+    }
+}
+
+impl Generator for Pcg32 {
+    fn name(&self) -> &'static str {
+        "PCG32"
+    }
+
+    fn next_u32(&mut self) -> u32 {
+        let old = self.state;
+        self.state = old.wrapping_mul(Self::MULTIPLIER).wrapping_add(self.increment);
+        let xorshifted = (((old >> 18) ^ old) >> 27) as u32;
+        xorshifted.rotate_right((old >> 59) as u32)
+    }
+}
+
+/// `crate::rng::Rng` (xoshiro256**), keeping the high half of each u64
+impl Generator for Rng {
+    fn name(&self) -> &'static str {
+        "xoshiro256** (rng::Rng)"
+    }
+
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+}
+
+fn generators(seed: u64) -> Vec<Box<dyn Generator>> {
+    vec![
+        Box::new(Lcg::new(seed)),
+        Box::new(XorShift64Star::new(seed)),
+        Box::new(Pcg32::new(seed, 54)),
+        Box::new(Rng::new(seed)),
+    ]
+}
+
+/// The complementary error function, to within about 1.2e-7 (Numerical
+/// Recipes' Chebyshev fit): turns a normal test statistic into a p-value
+fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1.0 / (1.0 + 0.5 * z);
+    let poly = -1.265_512_23 + t * (1.000_023_68 + t * (0.374_091_96 + t * (0.096_784_18 + t * (-0.186_288_06
+        + t * (0.278_868_07 + t * (-1.135_203_98 + t * (1.488_515_87 + t * (-0.822_152_23 + t * 0.170_872_77))))))));
+    let result = t * (-z * z + poly).exp();
+    if x >= 0.0 { result } else { 2.0 - result }
+}
+
+/// The probability of a chi-square statistic at least this large with
+/// `freedom` degrees of freedom, by the Wilson-Hilferty normal
+/// approximation (good for the 255 used here)
+fn chi_square_p(statistic: f64, freedom: f64) -> f64 {
+    let scale = 2.0 / (9.0 * freedom);
+    let z = ((statistic / freedom).cbrt() - (1.0 - scale)) / scale.sqrt();
+    0.5 * erfc(z / std::f64::consts::SQRT_2)
+}
+
+fn chi_square(counts: &[u64]) -> f64 {
+    let total: u64 = counts.iter().sum();
+    let expected = total as f64 / counts.len() as f64;
+    counts.iter().map(|&count| (count as f64 - expected).powi(2) / expected).sum()
+}
+
+/// One test's p-value: the chance a truly random source does at least this
+/// badly. Uniform between 0 and 1 for a good generator.
+#[derive(Debug, Clone, Copy)]
+struct Outcome {
+    p: f64,
+}
+
+impl Outcome {
+    fn passed(&self) -> bool {
+        self.p > SIGNIFICANCE && self.p < 1.0 - SIGNIFICANCE
+    }
+
+    fn cell(&self) -> String {
+        let text = format!("{:.3}", self.p);
+        if self.passed() { Style::Faster.paint(text).to_string() } else { Style::Slower.paint(format!("{} ✗", text)).to_string() }
+    }
+}
+
+/// The four tests, on one sample of a generator's output
+struct Report {
+    monobit: Outcome,
+    runs: Outcome,
+    uniform: Outcome,
+    serial: Outcome,
+}
+
+impl Report {
+    fn run(samples: &[u32]) -> Report {
+        let low: Vec<bool> = samples.iter().map(|sample| sample & 1 == 1).collect();
+        Report { monobit: monobit(&low), runs: runs(&low), uniform: uniform_low_byte(samples), serial: serial_nibbles(samples) }
+    }
+
+    fn outcomes(&self) -> [Outcome; 4] {
+        [self.monobit, self.runs, self.uniform, self.serial]
+    }
+
+    fn passed(&self) -> usize {
+        self.outcomes().iter().filter(|outcome| outcome.passed()).count()
+    }
+}
+
+/// NIST SP 800-22 frequency test: about as many ones as zeros
+fn monobit(bits: &[bool]) -> Outcome {
+    let sum: i64 = bits.iter().map(|&bit| if bit { 1 } else { -1 }).sum();
+    let statistic = sum.unsigned_abs() as f64 / (bits.len() as f64).sqrt();
+    Outcome { p: erfc(statistic / std::f64::consts::SQRT_2) }
+}
+
+/// NIST SP 800-22 runs test: the number of runs of equal bits is what
+/// chance predicts. An alternating 0101... balances perfectly and fails this.
+fn runs(bits: &[bool]) -> Outcome {
+    let n = bits.len() as f64;
+    let ones = bits.iter().filter(|&&bit| bit).count() as f64 / n;
+    if (ones - 0.5).abs() >= 2.0 / n.sqrt() {
+        return Outcome { p: 0.0 };
+    }
+    let runs = 1 + bits.windows(2).filter(|pair| pair[0] != pair[1]).count();
+    let expected = 2.0 * n * ones * (1.0 - ones);
+    Outcome { p: erfc((runs as f64 - expected).abs() / (2.0 * (2.0 * n).sqrt() * ones * (1.0 - ones))) }
+}
+
+/// Chi-square on the low byte: all 256 values equally often
+fn uniform_low_byte(samples: &[u32]) -> Outcome {
+    let mut counts = [0u64; 256];
+    for sample in samples {
+        counts[(sample & 0xFF) as usize] += 1;
+    }
+    Outcome { p: chi_square_p(chi_square(&counts), 255.0) }
+}
+
+/// Serial test: chi-square on pairs of consecutive low nibbles, so 256
+/// pairs equally often. Catches a value predicting the next.
+fn serial_nibbles(samples: &[u32]) -> Outcome {
+    let mut counts = [0u64; 256];
+    for pair in samples.chunks_exact(2) {
+        counts[((pair[0] & 0xF) << 4 | (pair[1] & 0xF)) as usize] += 1;
+    }
+    Outcome { p: chi_square_p(chi_square(&counts), 255.0) }
+}
+
+fn sample(generator: &mut dyn Generator, count: usize) -> Vec<u32> {
+    (0..count).map(|_| generator.next_u32()).collect()
+}
+
+fn demonstrate_low_bits(seed: u64) {
+    output::heading("🔍 Bit Planes: One Bit of 64 Consecutive Numbers");
+    outln!("Each row is one bit position of the first 64 outputs (█ = 1):\n");
+    for mut generator in generators(seed) {
+        let numbers = sample(generator.as_mut(), 64);
+        outln!("{}", generator.name());
+        for bit in [31, 2, 1, 0] {
+            let plane: String = numbers.iter().map(|number| if number >> bit & 1 == 1 { '█' } else { '·' }).collect();
+            outln!("  bit {:>2}  {}", bit, plane);
+        }
+    }
+    outln!();
+    outln!("The LCG's top bit looks random, but bit 0 alternates, bit 1 repeats every 4");
+    outln!("numbers and bit 2 every 8: multiplying and adding carry information upward,");
+    outln!("never down, so the low k bits are an LCG of their own with period 2^k.");
+    outln!("`rand() % 6` with such a generator rolls a die whose parity alternates.");
+    outln!("The others mix high bits into low ones (xorshift's right shifts, PCG's");
+    outln!("data-dependent rotation, xoshiro's rotate-and-multiply), and no pattern shows.\n");
+}
+
+fn demonstrate_tests(seed: u64, report: &mut DemoReport) {
+    output::heading(&format!("📊 Statistical Tests on {} Numbers Each", SAMPLES));
+    outln!("p-value: the chance a truly random source does at least this badly; it should");
+    outln!("look like a uniform draw from 0..1. ✗ marks p < {} or p > {}.\n", SIGNIFICANCE, 1.0 - SIGNIFICANCE);
+    let mut table = Table::new(["generator", "monobit (bit 0)", "runs (bit 0)", "χ² low byte", "χ² nibble pairs"]).left(0);
+    for mut generator in generators(seed) {
+        let tested = Report::run(&sample(generator.as_mut(), SAMPLES));
+        let mut cells = vec![generator.name().to_string()];
+        cells.extend(tested.outcomes().iter().map(Outcome::cell));
+        table.row(cells);
+        report.record(format!("{} tests passed", generator.name()), tested.passed() as f64, "of 4").param("samples", SAMPLES);
+    }
+    outln!("{}", table);
+    outln!("The LCG's bit 0 alternates, so it is exactly half ones: monobit only checks");
+    outln!("the balance and finds it suspiciously perfect, while the runs test, which");
+    outln!("counts the changes, rejects it outright. Its low byte is too uniform as well,");
+    outln!("every value exactly equally often, since it cycles through all 256 every 256");
+    outln!("numbers, and of the 256 nibble pairs only 16 ever occur. Passing tests proves");
+    outln!("nothing (TestU01's BigCrush runs 160 of them, and plain xorshift fails some);");
+    outln!("failing one proves a pattern. None of these generators is cryptographic:");
+    outln!("a few outputs reveal the state. Use the OS (getrandom) for keys and tokens.\n");
+}
+
+fn demonstrate_throughput(numbers: usize, seed: u64, report: &mut DemoReport) {
+    output::heading(&format!("⏱️  Throughput: {} Numbers", numbers));
+    let generate = |generator: &mut dyn Generator| {
+        let mut sum = 0u32;
+        for _ in 0..numbers {
+            sum = sum.wrapping_add(generator.next_u32());
+        }
+        sum
+    };
+    let mut table = Table::new(["generator", "median", "numbers/s", "ns/number", "state", "period"]).left(0);
+    let periods = ["2^32", "2^64 − 1", "2^64", "2^256 − 1"];
+    let states = ["4 B", "8 B", "16 B", "32 B"];
+    let mut generators = generators(seed);
+    for (index, generator) in generators.iter_mut().enumerate() {
+        // Each generator is used through `dyn`, as the tests do; the call
+        // costs the same for all four
+        let measurement = measure(RUNS, || generate(black_box(generator.as_mut())));
+        let seconds = measurement.median().as_secs_f64();
+        let rate = numbers as f64 / seconds;
+        table.row([generator.name().to_string(), format!("{:.2?}", measurement.median()), format_rate(rate),
+                   format!("{:.2}", seconds * 1e9 / numbers as f64), states[index].to_string(), periods[index].to_string()]);
+        report.record(generator.name(), rate, "numbers/s").param("numbers", numbers);
+    }
+    outln!("{}", table);
+    outln!("All four take a few nanoseconds per number, call included: a multiply, some");
+    outln!("shifts and xors. At that rate the 32-bit LCG repeats its whole sequence after");
+    outln!("2^32 numbers, seconds of output; the 64-bit states last centuries, and");
+    outln!("xoshiro256**'s 2^256 never ends in practice. That, and its quality, is why");
+    outln!("`crate::rng::Rng` seeds the shuffles and random inputs of the other demos");
+    outln!("(--seed picks the sequence, so a run can be repeated exactly).\n");
+}
+
+/// PCG32 matches the reference implementation, the LCG shows its patterns
+/// and fails the tests that look for them, and the others pass
+fn verify() -> Verification {
+    let mut verification = Verification::new();
+    // pcg32-demo from the reference C implementation: seed 42, stream 54
+    let mut pcg = Pcg32::new(42, 54);
+    let first: Vec<u32> = (0..6).map(|_| pcg.next_u32()).collect();
+    verification.equal("PCG32 matches the reference implementation's first outputs", first,
+                       vec![0xa15c_02b7, 0x7b47_f409, 0xba1d_3330, 0x83d2_f293, 0xbfa4_784b, 0xcbed_606e]);
+
+    let seed = Config::default().seed();
+    let mut lcg = Lcg::new(seed);
+    let numbers = sample(&mut lcg, 64);
+    verification.check("the LCG's low bit alternates", numbers.windows(2).all(|pair| (pair[0] ^ pair[1]) & 1 == 1),
+                       || "two consecutive numbers had the same parity".to_string());
+    verification.check("the LCG's low 3 bits repeat every 8 numbers",
+                       numbers.windows(9).all(|window| window[0] & 7 == window[8] & 7),
+                       || "bits 0..3 didn't repeat".to_string());
+    for mut generator in generators(seed) {
+        let tested = Report::run(&sample(generator.as_mut(), SAMPLES));
+        if generator.name().starts_with("LCG") {
+            verification.check("the LCG fails the runs and serial tests and is too uniform in its low byte",
+                               !tested.runs.passed() && !tested.serial.passed() && tested.uniform.p > 1.0 - SIGNIFICANCE,
+                               || format!("p-values {:?}", tested.outcomes().map(|outcome| outcome.p)));
+        } else {
+            verification.check(format!("{} passes all four tests", generator.name()), tested.passed() == 4,
+                               || format!("p-values {:?}", tested.outcomes().map(|outcome| outcome.p)));
+        }
+    }
+    verification
+}
+
+const QUIZ: &[Question] = &[
+    Question::new(
+        "Why does the lowest bit of a power-of-two-modulus LCG alternate 0, 1, 0, 1?",
+        &[
+            "The seed was chosen badly",
+            "Bit k of the next state depends only on bits 0..=k of the current one, so bit 0 is a period-2 generator of its own",
+            "The multiplier is even",
+        ],
+        1,
+        "Carries in addition and multiplication only move upward. The high bits are the good ones, which is why PCG keeps an LCG but outputs a mix led by its top bits.",
+    ),
+    Question::new(
+        "A generator passes monobit, runs and chi-square tests. What do you know?",
+        &[
+            "It is cryptographically secure",
+            "Only that these tests didn't find a pattern; other tests might, and passing says nothing about predictability",
+            "It has a period of at least 2^64",
+        ],
+        1,
+        "Tests can only show non-randomness. xorshift64* passes these and is still trivially predictable from a few outputs.",
+    ),
+    Question::new(
+        "What should generate a session token?",
+        &["PCG32 seeded from the time", "xoshiro256**", "The OS's CSPRNG (getrandom, /dev/urandom)"],
+        2,
+        "Fast statistical generators are invertible: an attacker who sees outputs can recover the state and predict the rest.",
+    ),
+];
+
+#[derive(Demo)]
+#[demo(name = "prng-demo", description = "LCG, xorshift64* and PCG32 from scratch: bit patterns, tests and throughput",
+       quiz = QUIZ, verify = verify)]
+pub struct PrngDemo;
+
+impl PrngDemo {
+    fn demonstrate(&self, config: &Config) -> DemoReport {
+        output::title("🎲 PRNG Demo");
+        outln!("Three pseudo-random generators from scratch, next to the one this crate uses, and how to tell them apart.\n");
+
+        let mut report = DemoReport::default();
+        let seed = config.seed();
+        demonstrate_low_bits(seed);
+        demonstrate_tests(seed, &mut report);
+        demonstrate_throughput(config.size.unwrap_or(NUMBERS).max(1), seed, &mut report);
+
+        output::takeaways();
+        outln!("• A PRNG is a state and a step: an LCG multiplies and adds, xorshift shifts and xors");
+        outln!("• A power-of-two LCG's low bits have short periods: use its high bits, or a better generator");
+        outln!("• Statistical tests find patterns; passing them is evidence, not proof");
+        outln!("• PCG and xoshiro fix an LCG or xorshift with an output function, at almost no cost");
+        outln!("• Seed benchmarks explicitly (crate::rng::Rng with --seed) so runs can be repeated");
+        outln!("• None of these is for secrets: use the operating system's CSPRNG");
+        report
+    }
+}
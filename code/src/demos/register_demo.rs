@@ -0,0 +1,216 @@
+// Demonstration of register usage and limitations
+
+use crate::demo::{Config, Demo, DemoReport};
+use crate::quiz::Question;
+use crate::{outln, output};
+
+fn demonstrate_register_usage() {
+    output::heading("Register Usage Demo");
+    
+    // Simple function - variables likely in registers
+    let a = 1;  // Likely in RAX
+    let b = 2;  // Likely in RBX
+    let c = 3;  // Likely in RCX
+    let d = 4;  // Likely in RDX
+    
+    let sum = a + b + c + d;
+    outln!("Sum of 4 variables: {}", sum);
+    outln!("These 4 variables likely fit in registers\n");
+    
+    // Many variables - some will spill to stack
+    let v1 = 1;
+    let v2 = 2;
+    let v3 = 3;
+    let v4 = 4;
+    let v5 = 5;
+    let v6 = 6;
+    let v7 = 7;
+    let v8 = 8;
+    let v9 = 9;
+    let v10 = 10;
+    let v11 = 11;
+    let v12 = 12;
+    let v13 = 13;
+    let v14 = 14;
+    let v15 = 15;
+    let v16 = 16;
+    let v17 = 17;  // This might spill to stack!
+    
+    let total = v1 + v2 + v3 + v4 + v5 + v6 + v7 + v8 + 
+                v9 + v10 + v11 + v12 + v13 + v14 + v15 + v16 + v17;
+    outln!("Sum of 17 variables: {}", total);
+    outln!("Some variables likely spilled to stack (slower!)\n");
+}
+
+fn demonstrate_byte_access() {
+    output::heading("Byte-Level Register Access");
+    
+    // x86-64 registers can access different sizes
+    let value: u64 = 0x1234567890ABCDEF;
+    
+    outln!("Full 64-bit value: 0x{:x}", value);
+    outln!("32-bit (EAX): 0x{:x}", value as u32);
+    outln!("16-bit (AX): 0x{:x}", value as u16);
+    outln!("8-bit (AL): 0x{:x}", (value & 0xFF) as u8);
+    outln!();
+    outln!("✅ Registers can hold 1 byte (via AL, AH, etc.)");
+    outln!("✅ Registers can hold 2 bytes (via AX)");
+    outln!("✅ Registers can hold 4 bytes (via EAX)");
+    outln!("✅ Registers can hold 8 bytes (via RAX)\n");
+}
+
+fn demonstrate_cache_line_relationship() {
+    output::heading("Register vs Cache Line");
+    
+    outln!("Register:");
+    outln!("  - Size: 8 bytes each (64-bit)");
+    outln!("  - Count: 16 general-purpose = 128 bytes total");
+    outln!("  - Speed: 1 cycle (fastest)");
+    outln!("  - Purpose: Active computation\n");
+    
+    outln!("Cache Line:");
+    outln!("  - Size: 64 bytes each");
+    outln!("  - Count: Thousands (depends on cache size)");
+    outln!("  - Speed: 1-3 cycles (very fast)");
+    outln!("  - Purpose: Data transfer from RAM\n");
+    
+    outln!("Relationship:");
+    outln!("  - Register size (8 bytes) ≠ Cache line size (64 bytes)");
+    outln!("  - No direct correlation");
+    outln!("  - Different purposes:");
+    outln!("    * Registers: Active computation");
+    outln!("    * Cache lines: Data from RAM\n");
+    
+    // Demonstrate cache line efficiency
+    let array: Vec<u64> = (0..16).collect();  // 16 × 8 bytes = 128 bytes
+    
+    outln!("Array access example:");
+    outln!("  Array size: {} elements = {} bytes", array.len(), array.len() * 8);
+    outln!("  Cache line: 64 bytes = 8 × u64 elements");
+    outln!("  Accessing array[0] loads array[0..7] into cache");
+    outln!("  Accessing array[1..7] is now fast (cache hit)\n");
+}
+
+/// One field per register width, so each store below needs a different-sized
+/// name for the same register
+#[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+#[repr(C)]
+#[derive(Default)]
+struct Widths {
+    byte: u8,
+    word: u16,
+    dword: u32,
+    qword: u64,
+}
+
+/// The System V ABI passes the first integer arguments in rdi, rsi, rdx, rcx
+#[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+#[unsafe(no_mangle)]
+#[inline(never)]
+extern "C" fn register_demo_sum4(a: u64, b: u64, c: u64, d: u64) -> u64 {
+    a.wrapping_add(b).wrapping_add(c).wrapping_add(d)
+}
+
+#[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+#[unsafe(no_mangle)]
+#[inline(never)]
+extern "C" fn register_demo_store_widths(value: u64, out: &mut Widths) {
+    out.byte = value as u8;
+    out.word = value as u16;
+    out.dword = value as u32;
+    out.qword = value;
+}
+
+#[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+fn show_listing(symbol: &str) -> Option<crate::disasm::Listing> {
+    match crate::disasm::function(symbol) {
+        Ok(listing) => {
+            outln!("{}", listing);
+            Some(listing)
+        }
+        Err(err) => {
+            outln!("  (can't read {}: {})\n", symbol, err);
+            None
+        }
+    }
+}
+
+#[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+fn demonstrate_instructions() {
+    output::heading("The Instructions Themselves");
+
+    let mut widths = Widths::default();
+    let sum = register_demo_sum4(std::hint::black_box(1), 2, 3, 4);
+    register_demo_store_widths(std::hint::black_box(0x1234567890ABCDEF), &mut widths);
+    outln!("register_demo_sum4(1, 2, 3, 4) = {}; stored 0x{:x} / 0x{:x} / 0x{:x} / 0x{:x}\n",
+           sum, widths.byte, widths.word, widths.dword, widths.qword);
+
+    outln!("Decoded from this executable's own bytes:\n");
+    let spills = show_listing("register_demo_sum4").map_or(0, |listing| {
+        listing.instructions.iter().filter(|instruction| instruction.operands.contains("[rsp")).count()
+    });
+    outln!("The four arguments arrive in rdi, rsi, rdx and rcx and the result");
+    if spills == 0 {
+        outln!("leaves in rax, without touching memory.\n");
+    } else {
+        outln!("leaves in rax; this unoptimized build still copies values to the stack");
+        outln!("{} times along the way. Try --release.\n", spills);
+    }
+    show_listing("register_demo_store_widths");
+    outln!("One value in rdi, stored through four names: dil (8 bits), di (16),");
+    outln!("edi (32) and rdi (64). The width is part of the register name.\n");
+}
+
+const QUIZ: &[Question] = &[
+    Question::new(
+        "How many general-purpose registers does x86-64 have?",
+        &["8", "16", "64"],
+        1,
+        "RAX through R15: sixteen 64-bit registers, which is why compilers work hard to keep the hottest values in them.",
+    ),
+    Question::new(
+        "What does the compiler do when a function has more live values than registers?",
+        &[
+            "Refuses to compile it",
+            "Asks the CPU for more registers",
+            "Spills some values to the stack and reloads them when needed",
+        ],
+        2,
+        "Spilling turns register operands into memory accesses, which is why register pressure matters in hot loops.",
+    ),
+    Question::new(
+        "A cache line is 64 bytes and a register is 8 bytes. How are they related?",
+        &[
+            "A cache line is eight registers wide, so one load fills eight registers",
+            "They're unrelated units: registers hold values being computed on, cache lines are how memory moves between RAM and the caches",
+            "A register is a cache line the CPU keeps pinned",
+        ],
+        1,
+        "The line size is set by memory transfer efficiency, not by the word size.",
+    ),
+];
+
+#[derive(Demo)]
+#[demo(name = "register-demo", description = "Register widths, byte access and registers vs cache lines",
+       quiz = QUIZ)]
+pub struct RegisterDemo;
+
+impl RegisterDemo {
+    fn demonstrate(&self, _config: &Config) -> DemoReport {
+        output::title("CPU Registers: Deep Dive");
+
+        demonstrate_register_usage();
+        demonstrate_byte_access();
+        demonstrate_cache_line_relationship();
+        #[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+        demonstrate_instructions();
+
+        output::takeaways();
+        outln!("1. Only 16 general-purpose registers (very limited!)");
+        outln!("2. Registers can hold 1, 2, 4, or 8 bytes");
+        outln!("3. Running out of registers causes spilling (slow!)");
+        outln!("4. Cache line size (64 bytes) ≠ Register size (8 bytes)");
+        outln!("5. They serve different purposes (computation vs data transfer)");
+        DemoReport::default()
+    }
+}
@@ -0,0 +1,622 @@
+//! Reliable UDP Demo
+//!
+//! A miniature TCP built on UDP datagrams, to see what TCP does by building
+//! it. Every data packet carries a sequence number; the receiver answers
+//! each one with a cumulative ACK naming the next packet it expects; a
+//! retransmission timer, its timeout estimated from measured round trips
+//! the way TCP does it, resends whatever went unacknowledged; and a sliding
+//! window keeps several packets in flight instead of one. Between the two
+//! ends a relay thread plays the wire, as Linux's netem would: it holds
+//! every datagram for a fixed delay, so a round trip takes as long as on a
+//! real network, and drops a seeded fraction of them, so loss can be
+//! dialled in. First one small transfer traced packet by packet, then
+//! throughput against window size and against loss rate.
+//! Run with: cargo run --release --bin reliable-udp-demo
+
+use std::io;
+use std::net::{SocketAddr, UdpSocket};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::demo::{Config, Demo, DemoReport};
+use crate::output::{Style, Table};
+use crate::plot::{format_bytes, LinePlot};
+use crate::quiz::Question;
+use crate::rng::Rng;
+use crate::verify::Verification;
+use crate::{outln, output};
+
+/// Data packets per transfer, unless the config sets `size`
+const SEGMENTS: usize = 1_000;
+
+/// Payload bytes per data packet
+const PAYLOAD: usize = 1024;
+
+/// The window for the loss sweep
+const WINDOW: usize = 16;
+
+/// How long the relay holds each datagram: a 1ms round trip, a network
+/// across town rather than loopback's few microseconds
+const DELAY: Duration = Duration::from_micros(500);
+
+/// The timeout before the first round trip has been measured
+const INITIAL_RTO: Duration = Duration::from_millis(10);
+
+/// TCP's floor is 200ms (Linux) or 1s (RFC 6298), sized for the internet;
+/// this link is more predictable
+const MIN_RTO: Duration = Duration::from_millis(2);
+const MAX_RTO: Duration = Duration::from_millis(100);
+
+/// The sender gives up after this many timeouts in a row
+const MAX_RETRIES: usize = 10;
+
+/// How long the receiver keeps answering once it has everything, in case
+/// its last ACK was lost and the sender retransmits: TCP's TIME_WAIT
+const LINGER: Duration = Duration::from_millis(250);
+
+/// How long the receiver waits for a packet before deciding the sender
+/// is gone
+const IDLE: Duration = Duration::from_secs(5);
+
+/// How often the relay looks up from an idle link to see if it's done
+const POLL: Duration = Duration::from_millis(10);
+
+const DATA: u8 = b'D';
+const ACK: u8 = b'A';
+
+/// The protocol's two packets. Sequence numbers count packets, not bytes
+/// as TCP's do, and go on the wire in network byte order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Packet<'a> {
+    /// `D`, the sequence number, then the payload
+    Data { seq: u32, payload: &'a [u8] },
+    /// `A`, then the next sequence number the receiver expects: everything
+    /// before it has arrived
+    Ack { next: u32 },
+}
+
+impl<'a> Packet<'a> {
+    fn encode(&self) -> Vec<u8> {
+        match *self {
+            Packet::Data { seq, payload } => [&[DATA][..], &seq.to_be_bytes(), payload].concat(),
+            Packet::Ack { next } => [&[ACK][..], &next.to_be_bytes()].concat(),
+        }
+    }
+
+    fn decode(bytes: &'a [u8]) -> Option<Packet<'a>> {
+        let (&kind, rest) = bytes.split_first()?;
+        let number = u32::from_be_bytes(rest.get(..4)?.try_into().ok()?);
+        match kind {
+            DATA => Some(Packet::Data { seq: number, payload: &rest[4..] }),
+            ACK if rest.len() == 4 => Some(Packet::Ack { next: number }),
+            _ => None,
+        }
+    }
+
+    /// "DATA 3" or "ACK 4", for the trace
+    fn describe(bytes: &[u8]) -> String {
+        match Packet::decode(bytes) {
+            Some(Packet::Data { seq, .. }) => format!("DATA {}", seq),
+            Some(Packet::Ack { next }) => format!("ACK {}", next),
+            None => "garbage".to_string(),
+        }
+    }
+}
+
+/// How a transfer runs
+#[derive(Debug, Clone, Copy)]
+struct Settings {
+    /// Packets in flight, unacknowledged, at most
+    window: usize,
+    /// Fraction of packets the link drops, each way
+    loss: f64,
+    seed: u64,
+}
+
+/// A read that ran out of time: WouldBlock on Unix, TimedOut on Windows
+fn timed_out(err: &io::Error) -> bool {
+    matches!(err.kind(), io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut)
+}
+
+/// When something happened, since the start of the transfer, and what
+type Event = (Duration, String);
+
+/// A transfer's events in time order, when it's being traced
+#[derive(Default)]
+struct Trace(Option<(Instant, Mutex<Vec<Event>>)>);
+
+impl Trace {
+    fn on() -> Trace {
+        Trace(Some((Instant::now(), Mutex::default())))
+    }
+
+    fn note(&self, event: impl FnOnce() -> String) {
+        if let Some((start, events)) = &self.0 {
+            events.lock().unwrap().push((start.elapsed(), event()));
+        }
+    }
+
+    fn events(self) -> Vec<Event> {
+        let mut events = self.0.map_or_else(Vec::new, |(_, events)| events.into_inner().unwrap());
+        events.sort_by_key(|&(at, _)| at);
+        events
+    }
+}
+
+/// The retransmission timeout, estimated as TCP does (RFC 6298): a
+/// smoothed round trip plus four times its mean deviation, doubled on
+/// every timeout until an ACK brings news
+struct Rto {
+    srtt: Option<Duration>,
+    rttvar: Duration,
+    backoff: u32,
+}
+
+impl Rto {
+    fn new() -> Rto {
+        Rto { srtt: None, rttvar: Duration::ZERO, backoff: 0 }
+    }
+
+    fn sample(&mut self, rtt: Duration) {
+        match self.srtt {
+            None => {
+                self.srtt = Some(rtt);
+                self.rttvar = rtt / 2;
+            }
+            Some(srtt) => {
+                self.rttvar = (self.rttvar * 3 + srtt.abs_diff(rtt)) / 4;
+                self.srtt = Some((srtt * 7 + rtt) / 8);
+            }
+        }
+    }
+
+    fn timeout(&self) -> Duration {
+        let base = self.srtt.map_or(INITIAL_RTO, |srtt| srtt + self.rttvar * 4).clamp(MIN_RTO, MAX_RTO);
+        (base * (1 << self.backoff.min(16))).min(MAX_RTO)
+    }
+
+    fn back_off(&mut self) {
+        self.backoff += 1;
+    }
+
+    /// New data was acknowledged: the path works, stop backing off (Linux
+    /// does the same)
+    fn reset(&mut self) {
+        self.backoff = 0;
+    }
+}
+
+/// The sender's side of a transfer
+#[derive(Debug, Default)]
+struct Sent {
+    /// Until the last packet was acknowledged
+    elapsed: Duration,
+    /// Data packets sent, retransmissions and dropped ones included
+    packets: usize,
+    retransmitted: usize,
+    timeouts: usize,
+    /// The smoothed round trip at the end
+    srtt: Option<Duration>,
+}
+
+/// Send `data` in numbered packets, keeping up to `window` unacknowledged.
+/// Go-Back-N: on a timeout, resend everything from the oldest
+/// unacknowledged packet on.
+fn send(socket: &UdpSocket, data: &[u8], window: usize, trace: &Trace) -> io::Result<Sent> {
+    let segments: Vec<&[u8]> = data.chunks(PAYLOAD).collect();
+    let total = segments.len() as u32;
+    let window = window.max(1) as u32;
+    let start = Instant::now();
+    let mut sent = Sent::default();
+    let mut rto = Rto::new();
+    // Everything before `base` is acknowledged; `next` is the next to
+    // send; `highest` is one past the highest ever sent
+    let (mut base, mut next, mut highest) = (0u32, 0u32, 0u32);
+    let mut deadline: Option<Instant> = None;
+    // One packet at a time is timed, and never a retransmitted one: its ACK
+    // could be for either copy (Karn's algorithm)
+    let mut timed: Option<(u32, Instant)> = None;
+    let mut retries = 0;
+    let mut buf = [0u8; 64];
+    while base < total {
+        while next < total && next < base + window {
+            socket.send(&Packet::Data { seq: next, payload: segments[next as usize] }.encode())?;
+            sent.packets += 1;
+            let retransmit = next < highest;
+            if retransmit {
+                sent.retransmitted += 1;
+            } else {
+                highest = next + 1;
+                timed.get_or_insert((next, Instant::now()));
+            }
+            trace.note(|| format!("sender   {} DATA {}", if retransmit { "resends" } else { "sends" }, next));
+            deadline.get_or_insert_with(|| Instant::now() + rto.timeout());
+            next += 1;
+        }
+
+        let wait = deadline.map_or(MIN_RTO, |deadline| deadline.saturating_duration_since(Instant::now()));
+        let received = if wait.is_zero() {
+            Err(io::ErrorKind::TimedOut.into())
+        } else {
+            socket.set_read_timeout(Some(wait))?;
+            socket.recv(&mut buf)
+        };
+        match received {
+            Ok(n) => {
+                let Some(Packet::Ack { next: acked }) = Packet::decode(&buf[..n]) else { continue };
+                if acked <= base {
+                    trace.note(|| format!("sender   gets ACK {}: nothing new", acked));
+                    continue;
+                }
+                if let Some((seq, at)) = timed
+                    && acked > seq
+                {
+                    rto.sample(at.elapsed());
+                    timed = None;
+                }
+                rto.reset();
+                retries = 0;
+                base = acked;
+                // After going back, ACKs for the earlier copies can overtake `next`
+                next = next.max(base);
+                deadline = (base < next).then(|| Instant::now() + rto.timeout());
+                trace.note(|| format!("sender   gets ACK {}: window now {}..{}", acked, base, base + window));
+            }
+            Err(err) if timed_out(&err) => {
+                sent.timeouts += 1;
+                retries += 1;
+                if retries > MAX_RETRIES {
+                    return Err(io::Error::new(io::ErrorKind::TimedOut,
+                                              format!("no ACK for packet {} after {} retries", base, MAX_RETRIES)));
+                }
+                rto.back_off();
+                timed = None;
+                trace.note(|| format!("sender   ⏰ timeout: back to DATA {}, next timeout {:.1?}", base, rto.timeout()));
+                next = base;
+                deadline = None;
+            }
+            Err(err) => return Err(err),
+        }
+    }
+    sent.elapsed = start.elapsed();
+    sent.srtt = rto.srtt;
+    Ok(sent)
+}
+
+/// The receiver's side of a transfer
+#[derive(Debug, Default)]
+struct Received {
+    data: Vec<u8>,
+    /// Data packets thrown away: duplicates, or ahead of a gap
+    discarded: usize,
+}
+
+/// Take `segments` packets in order, discarding anything out of order
+/// (Go-Back-N keeps the receiver this simple) and acknowledging every
+/// arrival, then linger until the sender goes quiet
+fn receive(socket: &UdpSocket, segments: usize, trace: &Trace) -> io::Result<Received> {
+    let mut received = Received::default();
+    let mut expected = 0u32;
+    let mut buf = [0u8; 1 + 4 + PAYLOAD];
+    socket.set_read_timeout(Some(IDLE))?;
+    loop {
+        let n = match socket.recv(&mut buf) {
+            Ok(n) => n,
+            Err(err) if timed_out(&err) && expected as usize == segments => break,
+            Err(err) => return Err(err),
+        };
+        let Some(Packet::Data { seq, payload }) = Packet::decode(&buf[..n]) else { continue };
+        let kept = seq == expected;
+        if kept {
+            received.data.extend_from_slice(payload);
+            expected += 1;
+            if expected as usize == segments {
+                socket.set_read_timeout(Some(LINGER))?;
+            }
+        } else {
+            received.discarded += 1;
+        }
+        socket.send(&Packet::Ack { next: expected }.encode())?;
+        trace.note(|| format!("receiver {} DATA {}, sends ACK {}", if kept { "keeps" } else { "discards" }, seq, expected));
+    }
+    Ok(received)
+}
+
+/// What the relay threw away, each way
+#[derive(Debug, Default)]
+struct Dropped {
+    data: usize,
+    acks: usize,
+}
+
+/// The wire: forward datagrams between `sender` and `receiver`, each one
+/// `DELAY` after it arrived, dropping a seeded `loss` fraction, until
+/// `done` is set. A socket's read timeout counts in scheduler ticks,
+/// milliseconds, so a second thread holds the packets with `sleep`, which
+/// is precise to microseconds.
+fn relay(socket: &UdpSocket, sender: SocketAddr, receiver: SocketAddr, settings: Settings, done: &AtomicBool,
+         trace: &Trace) -> io::Result<Dropped> {
+    let (in_flight, arrivals) = mpsc::channel::<(Instant, SocketAddr, Vec<u8>)>();
+    thread::scope(|scope| {
+        let forward = scope.spawn(move || -> io::Result<()> {
+            // The delay is the same for every packet, so they leave in the order they came
+            for (due, to, packet) in arrivals {
+                thread::sleep(due.saturating_duration_since(Instant::now()));
+                socket.send_to(&packet, to)?;
+            }
+            Ok(())
+        });
+        let mut rng = Rng::new(settings.seed);
+        let mut dropped = Dropped::default();
+        let mut buf = [0u8; 1 + 4 + PAYLOAD];
+        socket.set_read_timeout(Some(POLL))?;
+        while !done.load(Ordering::Acquire) {
+            let (n, from) = match socket.recv_from(&mut buf) {
+                Ok(received) => received,
+                Err(err) if timed_out(&err) => continue,
+                Err(err) => return Err(err),
+            };
+            let to = if from == sender { receiver } else if from == receiver { sender } else { continue };
+            if (rng.below(1_000_000) as f64) < settings.loss * 1e6 {
+                if to == receiver { dropped.data += 1 } else { dropped.acks += 1 }
+                trace.note(|| format!("link     ✗ loses {}", Packet::describe(&buf[..n])));
+                continue;
+            }
+            let _ = in_flight.send((Instant::now() + DELAY, to, buf[..n].to_vec()));
+        }
+        drop(in_flight);
+        forward.join().map_err(|_| io::Error::other("the relay panicked"))??;
+        Ok(dropped)
+    })
+}
+
+/// Both sides of a transfer
+struct Transfer {
+    sent: Sent,
+    received: Received,
+    dropped: Dropped,
+}
+
+impl Transfer {
+    /// Payload bytes delivered per second
+    fn goodput(&self) -> f64 {
+        self.received.data.len() as f64 / self.sent.elapsed.as_secs_f64()
+    }
+
+    fn dropped(&self) -> usize {
+        self.dropped.data + self.dropped.acks
+    }
+}
+
+/// Move `data` from a sender to a receiver over the protocol, both
+/// talking only to the relay between them
+fn transfer(data: &[u8], settings: Settings, trace: &Trace) -> io::Result<Transfer> {
+    let wire = UdpSocket::bind("127.0.0.1:0")?;
+    let sender = UdpSocket::bind("127.0.0.1:0")?;
+    let receiver = UdpSocket::bind("127.0.0.1:0")?;
+    sender.connect(wire.local_addr()?)?;
+    receiver.connect(wire.local_addr()?)?;
+    let (sender_addr, receiver_addr) = (sender.local_addr()?, receiver.local_addr()?);
+    let segments = data.len().div_ceil(PAYLOAD);
+    let done = AtomicBool::new(false);
+    thread::scope(|scope| {
+        let relay = scope.spawn(|| relay(&wire, sender_addr, receiver_addr, settings, &done, trace));
+        let receiver = scope.spawn(|| receive(&receiver, segments, trace));
+        let sent = send(&sender, data, settings.window, trace);
+        let received = receiver.join().map_err(|_| io::Error::other("the receiver panicked"));
+        done.store(true, Ordering::Release);
+        let dropped = relay.join().map_err(|_| io::Error::other("the relay panicked"))?;
+        Ok(Transfer { sent: sent?, received: received??, dropped: dropped? })
+    })
+}
+
+/// `bytes` seeded random bytes: misplaced packets can't go unnoticed
+fn message(bytes: usize, seed: u64) -> Vec<u8> {
+    let mut rng = Rng::new(seed);
+    (0..bytes).map(|_| rng.next_u64() as u8).collect()
+}
+
+fn percent(loss: f64) -> String {
+    format!("{}%", loss * 100.0)
+}
+
+fn demonstrate_packets() {
+    output::heading("📦 The Packets");
+    let payload = [0xAB; 4];
+    let data = Packet::Data { seq: 7, payload: &payload }.encode();
+    let ack = Packet::Ack { next: 8 }.encode();
+    let hex = |bytes: &[u8]| bytes.iter().map(|byte| format!("{:02x}", byte)).collect::<Vec<_>>().join(" ");
+    outln!("  DATA 7 (4-byte payload): {}", hex(&data));
+    outln!("  ACK 8:                   {}", hex(&ack));
+    outln!("A type byte and a 32-bit sequence number, big-endian. ACK 8 means \"I have");
+    outln!("everything before 8\": cumulative, so one ACK that gets through makes up");
+    outln!("for every earlier one that was lost. TCP's header carries the same two");
+    outln!("numbers, counting bytes instead of packets, plus a window, flags and ports");
+    outln!("in its 20 bytes. UDP supplies only the ports and a checksum.\n");
+}
+
+fn demonstrate_trace(seed: u64) {
+    let settings = Settings { window: 4, loss: 0.2, seed };
+    output::heading(&format!("🔍 A Traced Transfer: 10 Packets, Window {}, {} Loss", settings.window,
+                             percent(settings.loss)));
+    let data = message(10 * PAYLOAD, seed);
+    let trace = Trace::on();
+    match transfer(&data, settings, &trace) {
+        Ok(run) => {
+            for (at, event) in trace.events() {
+                outln!("  {:>9.1?}  {}", at, event);
+            }
+            let intact = if run.received.data == data { Style::Faster.paint("intact") } else { Style::Slower.paint("corrupted") };
+            outln!("\nDelivered {}B, {}, after {} retransmissions and {} timeouts.", format_bytes(run.received.data.len() as f64),
+                   intact, run.sent.retransmitted, run.sent.timeouts);
+        }
+        Err(err) => outln!("❌ The transfer failed: {}", err),
+    }
+    outln!("A lost DATA packet leaves a gap: the receiver discards what comes after it");
+    outln!("and repeats its ACK, until the sender's timer fires and it goes back to");
+    outln!("the gap. A lost ACK usually costs nothing: the next one covers it. Each");
+    outln!("timeout doubles the next one, in case the network is congested, not lossy.\n");
+}
+
+fn demonstrate_windows(segments: usize, seed: u64, report: &mut DemoReport) {
+    output::heading(&format!("🪟 Window Size: {} Packets, No Loss, {:?} Each Way", segments, DELAY));
+    let data = message(segments * PAYLOAD, seed);
+    let mut table = Table::new(["window", "time", "goodput", "round trip (smoothed)", "retransmitted"]);
+    for window in [1, 2, 4, 16, 64] {
+        match transfer(&data, Settings { window, loss: 0.0, seed }, &Trace::default()) {
+            Ok(run) => {
+                table.row([
+                    window.to_string(),
+                    format!("{:.1?}", run.sent.elapsed),
+                    format!("{}B/s", format_bytes(run.goodput())),
+                    run.sent.srtt.map_or(String::new(), |srtt| format!("{:.1?}", srtt)),
+                    run.sent.retransmitted.to_string(),
+                ]);
+                report.record("goodput", run.goodput() / 1e6, "MB/s").param("window", window).param("loss", "0%");
+            }
+            Err(err) => {
+                table.row([window.to_string(), format!("failed: {}", err)]);
+            }
+        }
+    }
+    outln!("{}", table);
+    outln!("Window 1 is stop-and-wait: one {}B packet per round trip, whatever the", format_bytes(PAYLOAD as f64));
+    outln!("link could carry. A window of W sends W packets per round trip, so goodput");
+    outln!("grows with the window until it covers the bandwidth-delay product and the");
+    outln!("link itself (here the relay thread and its syscalls) is the limit. Across");
+    outln!("an ocean, with a 100ms round trip, filling a fast link takes megabytes in");
+    outln!("flight, which is why TCP's window scales far past its header's 64 KiB.\n");
+}
+
+fn demonstrate_loss(segments: usize, seed: u64, report: &mut DemoReport) {
+    output::heading(&format!("📉 Throughput vs Loss: {} Packets, Window {}", segments, WINDOW));
+    let data = message(segments * PAYLOAD, seed);
+    let mut table = Table::new(["loss (each way)", "time", "goodput", "sent/needed", "retransmitted", "timeouts",
+                                "dropped", "intact"]);
+    let mut points = Vec::new();
+    for loss in [0.0, 0.01, 0.02, 0.05, 0.1, 0.2] {
+        match transfer(&data, Settings { window: WINDOW, loss, seed }, &Trace::default()) {
+            Ok(run) => {
+                table.row([
+                    percent(loss),
+                    format!("{:.1?}", run.sent.elapsed),
+                    format!("{}B/s", format_bytes(run.goodput())),
+                    format!("{:.2}", run.sent.packets as f64 / segments as f64),
+                    run.sent.retransmitted.to_string(),
+                    run.sent.timeouts.to_string(),
+                    run.dropped().to_string(),
+                    if run.received.data == data { "yes".to_string() } else { Style::Slower.paint("no").to_string() },
+                ]);
+                report.record("goodput", run.goodput() / 1e6, "MB/s").param("window", WINDOW).param("loss", percent(loss));
+                points.push((loss * 100.0, run.goodput()));
+            }
+            Err(err) => {
+                table.row([percent(loss), format!("failed: {}", err)]);
+            }
+        }
+    }
+    outln!("{}", table);
+    if points.len() > 1 {
+        outln!("{}", LinePlot::new("loss %", "goodput")
+            .format_y(|rate| format!("{}B/s", format_bytes(rate)))
+            .series("goodput", points));
+    }
+    outln!("Every byte arrives at every loss rate; what loss costs is time. Each lost");
+    outln!("packet stalls the window for a timeout, at least {:?} and doubling while", MIN_RTO);
+    outln!("the losses continue, then Go-Back-N resends the whole window, most of which");
+    outln!("had arrived. TCP softens both: fast retransmit resends after three");
+    outln!("duplicate ACKs without waiting for the timer, and SACK tells the sender");
+    outln!("exactly which packets are missing. It also reads loss as congestion and");
+    outln!("shrinks its window, which this protocol doesn't.\n");
+}
+
+/// The protocol delivers intact over a lossy link, pipelining beats
+/// stop-and-wait, and a sender nobody answers gives up
+fn verify() -> Verification {
+    let mut verification = Verification::new();
+    let data = message(200 * PAYLOAD, 1);
+    match transfer(&data, Settings { window: WINDOW, loss: 0.0, seed: 1 }, &Trace::default()) {
+        Ok(run) => verification.check("a lossless transfer arrives intact", run.received.data == data,
+                                      || format!("{} of {} bytes", run.received.data.len(), data.len())),
+        Err(err) => verification.check("a lossless transfer completes", false, || err.to_string()),
+    };
+    match transfer(&data, Settings { window: WINDOW, loss: 0.2, seed: 1 }, &Trace::default()) {
+        Ok(run) => verification
+            .check("a transfer with 20% loss each way arrives intact", run.received.data == data,
+                   || format!("{} of {} bytes", run.received.data.len(), data.len()))
+            .check("lost packets are retransmitted", run.dropped() > 0 && run.sent.retransmitted > 0,
+                   || format!("{} dropped, {} retransmitted", run.dropped(), run.sent.retransmitted)),
+        Err(err) => verification.check("a lossy transfer completes", false, || err.to_string()),
+    };
+    let stop_and_wait = transfer(&data, Settings { window: 1, loss: 0.0, seed: 1 }, &Trace::default());
+    let pipelined = transfer(&data, Settings { window: WINDOW, loss: 0.0, seed: 1 }, &Trace::default());
+    if let (Ok(stop_and_wait), Ok(pipelined)) = (stop_and_wait, pipelined) {
+        verification.check("a window of 16 is faster than stop-and-wait",
+                           pipelined.sent.elapsed < stop_and_wait.sent.elapsed,
+                           || format!("{:.1?} vs {:.1?}", pipelined.sent.elapsed, stop_and_wait.sent.elapsed));
+    }
+    // A peer that exists but never reads: no ICMP error cuts the wait short
+    let unanswered = (|| {
+        let (sender, silent) = (UdpSocket::bind("127.0.0.1:0")?, UdpSocket::bind("127.0.0.1:0")?);
+        sender.connect(silent.local_addr()?)?;
+        send(&sender, &data[..PAYLOAD], 1, &Trace::default())
+    })();
+    match unanswered {
+        Err(err) if err.kind() == io::ErrorKind::TimedOut => verification.check("a sender nobody answers gives up", true, String::new),
+        other => verification.check("a sender nobody answers gives up", false, || format!("{:?}", other.map(|sent| sent.packets))),
+    };
+    verification
+}
+
+const QUIZ: &[Question] = &[
+    Question::new(
+        "The receiver's ACK for packet 5 is lost, but its ACK for packet 6 arrives. What does the sender resend?",
+        &["Packet 5", "Nothing: ACKs are cumulative, so ACK 7 covers packet 5 too", "Packets 5 and 6"],
+        1,
+        "A cumulative ACK names the next expected packet; one that arrives makes up for every earlier one.",
+    ),
+    Question::new(
+        "Why doesn't the sender measure a round trip on a retransmitted packet?",
+        &[
+            "Retransmissions are too slow to matter",
+            "The ACK could be for either copy, so the sample could be far too short or too long (Karn's algorithm)",
+            "It does",
+        ],
+        1,
+        "An ambiguous sample would drag the timeout estimate the wrong way.",
+    ),
+    Question::new(
+        "With a window of 1 and a 50ms round trip, what's the most this protocol can send with 1 KiB packets?",
+        &["About 20 KiB/s, whatever the link speed", "The link speed", "1 KiB/s"],
+        0,
+        "Stop-and-wait sends one packet per round trip: window / RTT bounds throughput.",
+    ),
+];
+
+#[derive(Demo)]
+#[demo(name = "reliable-udp-demo", description = "A tiny TCP over UDP: sequence numbers, ACKs, retransmission and a window",
+       quiz = QUIZ, verify = verify)]
+pub struct ReliableUdpDemo;
+
+impl ReliableUdpDemo {
+    fn demonstrate(&self, config: &Config) -> DemoReport {
+        output::title("🔁 Reliable UDP Demo");
+        outln!("A transport protocol built from datagrams that may not arrive.\n");
+
+        let segments = config.size.unwrap_or(SEGMENTS).max(1);
+        let mut report = DemoReport::default();
+        demonstrate_packets();
+        demonstrate_trace(config.seed());
+        demonstrate_windows(segments, config.seed(), &mut report);
+        demonstrate_loss(segments, config.seed(), &mut report);
+
+        output::takeaways();
+        outln!("• Sequence numbers find gaps and duplicates; cumulative ACKs survive their own loss");
+        outln!("• A retransmission timer, set from measured round trips, recovers lost packets");
+        outln!("• A sliding window keeps packets in flight: throughput ≈ window / round trip");
+        outln!("• Loss costs time, not data: timeouts and resends eat the throughput");
+        report
+    }
+}
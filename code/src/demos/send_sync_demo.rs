@@ -0,0 +1,188 @@
+//! Send & Sync Demo
+//!
+//! How the compiler decides what may cross a thread boundary: which types
+//! are `Send` and `Sync` and why, the errors you get from `Rc`, raw pointers
+//! and `RefCell`, the `Arc<Mutex<T>>` fix, and a hand-written
+//! `unsafe impl Send` for a wrapper that owns raw memory.
+//! Run with: cargo run --bin send-sync-demo
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+
+use crate::demo::{Config, Demo, DemoReport};
+use crate::output::Table;
+use crate::quiz::Question;
+use crate::send_sync::{assert_send, assert_sync, OwnedBuffer};
+use crate::{out, outln, output};
+
+fn demonstrate_table() {
+    output::heading("📋 Who Is Send, Who Is Sync");
+    outln!("  Send: a value can be moved to another thread");
+    outln!("  Sync: a &value can be used from several threads at once (T: Sync ⇔ &T: Send)\n");
+
+    // Every ✅ below is checked by the compiler right here; the ❌ ones are
+    // compile_fail doc-tests in systems_demos::send_sync
+    assert_send::<Arc<u32>>();
+    assert_sync::<Arc<u32>>();
+    assert_send::<Mutex<Vec<u8>>>();
+    assert_sync::<Mutex<Vec<u8>>>();
+    assert_send::<AtomicU64>();
+    assert_sync::<AtomicU64>();
+    assert_send::<Cell<u32>>();
+    assert_send::<RefCell<u32>>();
+    assert_sync::<MutexGuard<'static, u32>>();
+    assert_send::<OwnedBuffer>();
+
+    let mark = |yes: bool| if yes { "✅" } else { "❌" };
+    let mut table = Table::new(["type", "Send", "Sync", "why"]).left(3);
+    for (name, send, sync, why) in [
+        ("u32, String, Vec<T>", true, true, "plain owned data"),
+        ("Rc<T>", false, false, "non-atomic reference count"),
+        ("Arc<T>", true, true, "atomic reference count (if T: Send + Sync)"),
+        ("Cell<T>, RefCell<T>", true, false, "unsynchronized interior mutability"),
+        ("Mutex<T>", true, true, "the lock serializes access (if T: Send)"),
+        ("AtomicU64", true, true, "hardware atomic operations"),
+        ("MutexGuard<T>", false, true, "must unlock on the locking thread"),
+        ("*mut T, *const T", false, false, "no promises about aliasing"),
+        ("OwnedBuffer", true, false, "unsafe impl Send, see below"),
+    ] {
+        table.row([name, mark(send), mark(sync), why]);
+    }
+    out!("{}", table);
+    outln!("Auto traits are structural: a struct is Send if every field is. One Rc or");
+    outln!("raw pointer field is enough to keep the whole type on its thread.\n");
+}
+
+fn demonstrate_rejections() {
+    output::heading("🚫 What the Compiler Refuses");
+    for (code, error) in [
+        ("let shared = Rc::new(42);\n  thread::spawn(move || *shared);",
+         "`Rc<i32>` cannot be sent between threads safely"),
+        ("let pointer: *mut u32 = &mut value;\n  thread::spawn(move || unsafe { *pointer = 1 });",
+         "`*mut u32` cannot be sent between threads safely"),
+        ("let clone = Arc::clone(&Arc::new(RefCell::new(0)));\n  thread::spawn(move || *clone.borrow_mut() += 1);",
+         "`RefCell<i32>` cannot be shared between threads safely"),
+    ] {
+        outln!("  {}", code);
+        outln!("  error[E0277]: {}\n", error);
+    }
+    outln!("Each of these would be a data race: two threads bumping Rc's count, writing");
+    outln!("through an aliased pointer, or updating RefCell's borrow flag at once.");
+    outln!("They're compile_fail doc-tests: cargo test --doc send_sync\n");
+
+    // Single-threaded, all three are fine
+    let counted = Rc::new(42);
+    let cell = RefCell::new(0);
+    *cell.borrow_mut() += *counted;
+    outln!("  (on one thread they're fine: Rc + RefCell -> {})\n", cell.borrow());
+}
+
+fn demonstrate_fix() {
+    output::heading("🔒 The Fix: Arc<Mutex<T>>");
+    const THREADS: usize = 4;
+    const INCREMENTS: u64 = 10_000;
+
+    let counter = Arc::new(Mutex::new(0u64));
+    let handles: Vec<_> = (0..THREADS)
+        .map(|_| {
+            let counter = Arc::clone(&counter);
+            thread::spawn(move || {
+                for _ in 0..INCREMENTS {
+                    *counter.lock().unwrap() += 1;
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    outln!("  Arc<Mutex<u64>>: {} threads x {} increments = {}", THREADS, INCREMENTS, *counter.lock().unwrap());
+
+    let atomic = AtomicU64::new(0);
+    thread::scope(|scope| {
+        for _ in 0..THREADS {
+            scope.spawn(|| {
+                for _ in 0..INCREMENTS {
+                    atomic.fetch_add(1, Ordering::Relaxed);
+                }
+            });
+        }
+    });
+    outln!("  &AtomicU64 in thread::scope: {}", atomic.load(Ordering::Relaxed));
+    outln!("Arc swaps Rc's counter for an atomic one (Send + Sync); Mutex turns a");
+    outln!("&Mutex<T> into exclusive access, so it's Sync even though T isn't. For a");
+    outln!("single number an atomic is enough, and scoped threads can borrow it");
+    outln!("without an Arc at all.\n");
+}
+
+fn demonstrate_unsafe_impl() {
+    output::heading("🔓 unsafe impl Send for a Wrapper");
+    outln!("  pub struct OwnedBuffer {{ data: NonNull<u8>, len: usize }}");
+    outln!("  // SAFETY: OwnedBuffer is the only owner of its allocation ...");
+    outln!("  unsafe impl Send for OwnedBuffer {{}}\n");
+
+    let mut buffer = OwnedBuffer::new(16);
+    buffer.as_mut_slice()[0] = 1;
+    let before = buffer.address();
+    let (buffer, worker) = thread::spawn(move || {
+        for (i, byte) in buffer.as_mut_slice().iter_mut().enumerate() {
+            *byte = i as u8 * 3;
+        }
+        (buffer, format!("{:?}", thread::current().id()))
+    })
+    .join()
+    .unwrap();
+    outln!("  allocated on main at {:#x}, filled on {} at {:#x}", before, worker, buffer.address());
+    outln!("  contents back on main: {:?}", buffer.as_slice());
+    outln!("The NonNull field made the type !Send; the compiler can't know nobody");
+    outln!("else holds that pointer. The unsafe impl is the author's promise that");
+    outln!("moving the wrapper moves every path to the memory with it. Keep the");
+    outln!("promise narrow: OwnedBuffer claims Send but not Sync, and would lose");
+    outln!("both if it ever handed out a second pointer to its bytes.\n");
+}
+
+const QUIZ: &[Question] = &[
+    Question::new(
+        "Why isn't Rc<T> Send?",
+        &[
+            "It lives on the heap",
+            "Its reference count is updated non-atomically, so two threads could race on it",
+            "It can't be cloned across threads",
+        ],
+        1,
+        "Arc pays for atomic updates precisely to be Send and Sync.",
+    ),
+    Question::new(
+        "What does T: Sync mean?",
+        &["&T can be shared between threads", "T can be moved to another thread", "T is immutable"],
+        0,
+        "Send is about moving a value; Sync is about sharing a reference to it.",
+    ),
+];
+
+#[derive(Demo)]
+#[demo(name = "send-sync-demo", description = "What may cross a thread boundary, and why",
+       quiz = QUIZ)]
+pub struct SendSyncDemo;
+
+impl SendSyncDemo {
+    fn demonstrate(&self, _config: &Config) -> DemoReport {
+        output::title("🧵 Send & Sync Demo");
+        outln!("The auto traits behind Rust's fearless concurrency.\n");
+
+        demonstrate_table();
+        demonstrate_rejections();
+        demonstrate_fix();
+        demonstrate_unsafe_impl();
+
+        output::takeaways();
+        outln!("• Send = may move to another thread; Sync = may be shared by reference");
+        outln!("• The compiler derives both from a type's fields, automatically");
+        outln!("• Rc, RefCell and raw pointers opt out; Arc, Mutex and atomics opt in");
+        outln!("• unsafe impl Send is a promise you must uphold by hand, so keep it small");
+        DemoReport::default()
+    }
+}
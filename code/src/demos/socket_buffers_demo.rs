@@ -0,0 +1,413 @@
+//! Socket Buffers Demo
+//!
+//! Between `write` on one end of a TCP connection and `read` on the other
+//! sit two kernel buffers: the sender's send buffer (SO_SNDBUF) and the
+//! receiver's receive buffer (SO_RCVBUF), which also sets the window the
+//! receiver advertises. This demo measures how much a connection holds
+//! before a non-blocking `write` returns WouldBlock, at several buffer
+//! sizes, then lets a fast sender write to a slow reader and watches the
+//! backpressure: once the buffers fill, the sender moves at the reader's
+//! pace, and everything buffered is data waiting to be read.
+//! Run with: cargo run --release --bin socket-buffers-demo
+
+use std::io::{self, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::os::fd::{AsRawFd, RawFd};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::demo::{Config, Demo, DemoReport};
+use crate::output::Table;
+use crate::plot::{format_bytes, LinePlot};
+use crate::quiz::Question;
+use crate::verify::Verification;
+use crate::{outln, output};
+
+/// Bytes the backpressure sender writes, unless the config sets `size`
+const TOTAL: usize = 8 * 1024 * 1024;
+
+/// The sender's write size
+const CHUNK: usize = 64 * 1024;
+
+/// The slow reader takes this much per read, then pauses
+const READ: usize = 16 * 1024;
+const PAUSE: Duration = Duration::from_millis(1);
+
+/// A write that took longer than this waited for buffer space
+const BLOCKED: Duration = Duration::from_micros(200);
+
+fn cvt(result: libc::c_int) -> io::Result<libc::c_int> {
+    if result < 0 { Err(io::Error::last_os_error()) } else { Ok(result) }
+}
+
+fn get_option(fd: RawFd, name: libc::c_int) -> io::Result<usize> {
+    let mut value: libc::c_int = 0;
+    let mut len = size_of::<libc::c_int>() as libc::socklen_t;
+    // Safety: `value` and `len` describe a c_int the kernel may write
+    cvt(unsafe { libc::getsockopt(fd, libc::SOL_SOCKET, name, (&mut value as *mut libc::c_int).cast(), &mut len) })?;
+    Ok(value as usize)
+}
+
+fn set_option(fd: RawFd, name: libc::c_int, value: usize) -> io::Result<()> {
+    let value = value as libc::c_int;
+    // Safety: a c_int option value of the right size
+    cvt(unsafe {
+        libc::setsockopt(fd, libc::SOL_SOCKET, name, (&value as *const libc::c_int).cast(),
+                         size_of::<libc::c_int>() as libc::socklen_t)
+    })?;
+    Ok(())
+}
+
+/// Bytes waiting in a socket's queue: TIOCOUTQ for the send side (written
+/// but not yet acknowledged), FIONREAD for the receive side (not yet read)
+fn queued(fd: RawFd, request: libc::c_ulong) -> usize {
+    let mut bytes: libc::c_int = 0;
+    // Safety: both requests write one c_int
+    let result = unsafe { libc::ioctl(fd, request as _, &mut bytes) };
+    if result < 0 { 0 } else { bytes as usize }
+}
+
+/// A net.ipv4 / net.core setting, e.g. "net/ipv4/tcp_wmem"
+fn sysctl(name: &str) -> Option<String> {
+    std::fs::read_to_string(format!("/proc/sys/{}", name)).ok().map(|value| value.split_whitespace().collect::<Vec<_>>().join(" "))
+}
+
+/// Buffer sizes to ask for; `None` leaves the kernel's default (and its
+/// autotuning) alone
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Buffers {
+    send: Option<usize>,
+    receive: Option<usize>,
+}
+
+impl Buffers {
+    const DEFAULT: Buffers = Buffers { send: None, receive: None };
+
+    fn label(self) -> String {
+        let side = |size: Option<usize>| size.map_or("auto".to_string(), |size| format!("{}B", format_bytes(size as f64)));
+        format!("{} / {}", side(self.send), side(self.receive))
+    }
+}
+
+/// A connected loopback pair with `buffers` applied: (sender, receiver).
+/// The receive size is set on the listener, so the accepted socket starts
+/// with it and advertises a window to match from the handshake on.
+fn connect(buffers: Buffers) -> io::Result<(TcpStream, TcpStream)> {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    if let Some(size) = buffers.receive {
+        set_option(listener.as_raw_fd(), libc::SO_RCVBUF, size)?;
+    }
+    let sender = TcpStream::connect(listener.local_addr()?)?;
+    let (receiver, _) = listener.accept()?;
+    if let Some(size) = buffers.send {
+        set_option(sender.as_raw_fd(), libc::SO_SNDBUF, size)?;
+    }
+    Ok((sender, receiver))
+}
+
+/// How a connection nobody reads from fills up
+struct Fill {
+    send_buffer: usize,
+    receive_buffer: usize,
+    /// Bytes `write` accepted before returning WouldBlock for good
+    accepted: usize,
+    in_send_queue: usize,
+    in_receive_queue: usize,
+}
+
+/// Write to a non-blocking socket whose peer never reads, until it stops
+/// taking anything
+fn fill(buffers: Buffers) -> io::Result<Fill> {
+    let (mut sender, receiver) = connect(buffers)?;
+    sender.set_nonblocking(true)?;
+    let chunk = [0u8; 4096];
+    let mut accepted = 0;
+    let mut stalled = 0;
+    // WouldBlock once may only mean the data hasn't moved to the receiver
+    // yet; a few tries in a row with nothing taken means it's full
+    while stalled < 3 {
+        match sender.write(&chunk) {
+            Ok(n) => {
+                accepted += n;
+                stalled = 0;
+            }
+            Err(err) if err.kind() == io::ErrorKind::WouldBlock => {
+                stalled += 1;
+                thread::sleep(Duration::from_millis(5));
+            }
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(Fill {
+        send_buffer: get_option(sender.as_raw_fd(), libc::SO_SNDBUF)?,
+        receive_buffer: get_option(receiver.as_raw_fd(), libc::SO_RCVBUF)?,
+        accepted,
+        in_send_queue: queued(sender.as_raw_fd(), libc::TIOCOUTQ),
+        in_receive_queue: queued(receiver.as_raw_fd(), libc::FIONREAD),
+    })
+}
+
+/// A fast sender and a slow reader, both timelines recorded
+struct Backpressure {
+    /// (time, bytes written so far) after each write
+    written: Vec<(Duration, usize)>,
+    /// (time, bytes read so far) after each read
+    read: Vec<(Duration, usize)>,
+    /// How long each write call took
+    writes: Vec<Duration>,
+}
+
+impl Backpressure {
+    fn sender_done(&self) -> Duration {
+        self.written.last().map_or(Duration::ZERO, |&(at, _)| at)
+    }
+
+    fn reader_done(&self) -> Duration {
+        self.read.last().map_or(Duration::ZERO, |&(at, _)| at)
+    }
+
+    fn blocked_writes(&self) -> usize {
+        self.writes.iter().filter(|&&took| took >= BLOCKED).count()
+    }
+
+    /// Written but not yet read when the sender finished: all in buffers
+    fn buffered_at_end(&self) -> usize {
+        let done = self.sender_done();
+        let read = self.read.iter().take_while(|&&(at, _)| at <= done).last().map_or(0, |&(_, bytes)| bytes);
+        self.written.last().map_or(0, |&(_, bytes)| bytes).saturating_sub(read)
+    }
+}
+
+fn backpressure(buffers: Buffers, total: usize) -> io::Result<Backpressure> {
+    let (mut sender, mut receiver) = connect(buffers)?;
+    let start = Instant::now();
+    let reader = thread::spawn(move || {
+        let mut read = Vec::new();
+        let mut buf = vec![0u8; READ];
+        let mut bytes = 0;
+        while bytes < total {
+            match receiver.read(&mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => bytes += n,
+            }
+            read.push((start.elapsed(), bytes));
+            thread::sleep(PAUSE);
+        }
+        read
+    });
+    let chunk = vec![0u8; CHUNK];
+    let mut written = Vec::new();
+    let mut writes = Vec::new();
+    let mut bytes = 0;
+    while bytes < total {
+        let call = Instant::now();
+        sender.write_all(&chunk[..CHUNK.min(total - bytes)])?;
+        writes.push(call.elapsed());
+        bytes += CHUNK.min(total - bytes);
+        written.push((start.elapsed(), bytes));
+    }
+    let read = reader.join().map_err(|_| io::Error::other("the reader panicked"))?;
+    Ok(Backpressure { written, read, writes })
+}
+
+fn demonstrate_defaults() {
+    output::heading("📏 The Kernel's Defaults");
+    if let Ok((sender, _receiver)) = connect(Buffers::DEFAULT) {
+        let fd = sender.as_raw_fd();
+        if let (Ok(send), Ok(receive)) = (get_option(fd, libc::SO_SNDBUF), get_option(fd, libc::SO_RCVBUF)) {
+            outln!("A new TCP socket: SO_SNDBUF {}B, SO_RCVBUF {}B", format_bytes(send as f64), format_bytes(receive as f64));
+        }
+        if set_option(fd, libc::SO_SNDBUF, 16 * 1024).is_ok()
+            && let Ok(doubled) = get_option(fd, libc::SO_SNDBUF)
+        {
+            outln!("Ask for SO_SNDBUF 16K and read it back: {}B. Linux doubles the", format_bytes(doubled as f64));
+            outln!("request to leave room for its own bookkeeping (the socket buffers count");
+            outln!("kernel structures as well as payload), and stops autotuning that socket.");
+        }
+    }
+    for (name, meaning) in [
+        ("net/ipv4/tcp_wmem", "send buffer: min, default, autotuning max"),
+        ("net/ipv4/tcp_rmem", "receive buffer: min, default, autotuning max"),
+        ("net/core/wmem_max", "the most setsockopt(SO_SNDBUF) may ask for"),
+        ("net/core/rmem_max", "the most setsockopt(SO_RCVBUF) may ask for"),
+    ] {
+        if let Some(value) = sysctl(name) {
+            outln!("  {:<20} {:<24} {}", name.replace('/', "."), value, meaning);
+        }
+    }
+    outln!("Left alone, TCP grows a connection's buffers up to the autotuning max as");
+    outln!("it learns the bandwidth-delay product; setting a size pins it.\n");
+}
+
+fn demonstrate_fill(report: &mut DemoReport) {
+    output::heading("🧱 How Much a Connection Holds");
+    outln!("The receiver never reads; a non-blocking sender writes until WouldBlock.\n");
+    let mut table = Table::new(["asked (send / receive)", "SO_SNDBUF", "SO_RCVBUF", "write accepted", "in send queue",
+                                "in receive queue"]);
+    for buffers in [
+        Buffers { send: Some(16 * 1024), receive: Some(16 * 1024) },
+        Buffers { send: Some(64 * 1024), receive: Some(64 * 1024) },
+        Buffers { send: Some(256 * 1024), receive: Some(256 * 1024) },
+        Buffers { send: Some(16 * 1024), receive: Some(256 * 1024) },
+        Buffers { send: Some(256 * 1024), receive: Some(16 * 1024) },
+        Buffers::DEFAULT,
+    ] {
+        match fill(buffers) {
+            Ok(fill) => {
+                table.row([
+                    buffers.label(),
+                    format!("{}B", format_bytes(fill.send_buffer as f64)),
+                    format!("{}B", format_bytes(fill.receive_buffer as f64)),
+                    format!("{}B", format_bytes(fill.accepted as f64)),
+                    format!("{}B", format_bytes(fill.in_send_queue as f64)),
+                    format!("{}B", format_bytes(fill.in_receive_queue as f64)),
+                ]);
+                report.record("bytes accepted", fill.accepted as f64, "bytes").param("buffers", buffers.label());
+            }
+            Err(err) => {
+                table.row([buffers.label(), format!("failed: {}", err)]);
+            }
+        }
+    }
+    outln!("{}", table);
+    outln!("What write() accepted is split between the two queues: the receiver's");
+    outln!("(data delivered but not read, up to the window it advertised) and the");
+    outln!("sender's (data the window has no room for yet). Neither holds its full");
+    outln!("SO_*BUF in payload: part of it is overhead for the kernel's own buffers.");
+    outln!("A blocking write() would simply have slept at this point; a non-blocking");
+    outln!("one returns WouldBlock, and an event loop waits for EPOLLOUT instead.\n");
+}
+
+fn demonstrate_backpressure(total: usize, report: &mut DemoReport) {
+    output::heading(&format!("🐌 A Fast Sender, a Slow Reader: {}B", format_bytes(total as f64)));
+    outln!("The sender writes {}B at a time as fast as it can; the reader takes", format_bytes(CHUNK as f64));
+    outln!("{}B, then pauses {:?}.\n", format_bytes(READ as f64), PAUSE);
+    let mut table = Table::new(["buffers (send / receive)", "writes", "blocked", "sender done", "reader done",
+                                "buffered at end", "last byte waited"]);
+    let mut timelines = Vec::new();
+    for buffers in [Buffers { send: Some(16 * 1024), receive: Some(16 * 1024) }, Buffers::DEFAULT] {
+        match backpressure(buffers, total) {
+            Ok(run) => {
+                let waited = run.reader_done().saturating_sub(run.sender_done());
+                table.row([
+                    buffers.label(),
+                    run.writes.len().to_string(),
+                    run.blocked_writes().to_string(),
+                    format!("{:.1?}", run.sender_done()),
+                    format!("{:.1?}", run.reader_done()),
+                    format!("{}B", format_bytes(run.buffered_at_end() as f64)),
+                    format!("{:.1?}", waited),
+                ]);
+                report.record_time("last byte waited", waited).param("buffers", buffers.label());
+                timelines.push((buffers, run));
+            }
+            Err(err) => {
+                table.row([buffers.label(), format!("failed: {}", err)]);
+            }
+        }
+    }
+    outln!("{}", table);
+
+    if let Some((_, run)) = timelines.iter().find(|(buffers, _)| *buffers == Buffers::DEFAULT) {
+        let points = |timeline: &[(Duration, usize)]| -> Vec<(f64, f64)> {
+            timeline.iter().map(|&(at, bytes)| (at.as_secs_f64() * 1e3, bytes as f64)).collect()
+        };
+        outln!("{}", LinePlot::new("ms", "bytes")
+            .format_y(|bytes| format!("{}B", format_bytes(bytes)))
+            .series("written", points(&run.written))
+            .series("read", points(&run.read)));
+        outln!("With the default buffers: the gap between the curves is data sitting in");
+        outln!("kernel buffers.");
+    }
+    outln!("At first every write returns at once, into empty buffers. Once they're");
+    outln!("full, each write blocks until the reader frees room, and the sender");
+    outln!("settles to the reader's pace: backpressure, carried by the shrinking");
+    outln!("receive window back to a blocked write(). Bigger buffers don't make the");
+    outln!("reader faster; they hold more data that has already been waiting longer.\n");
+}
+
+/// A connection nobody reads takes roughly what its buffers hold and no
+/// more, and a slow reader blocks the sender
+fn verify() -> Verification {
+    let mut verification = Verification::new();
+    let small = fill(Buffers { send: Some(16 * 1024), receive: Some(16 * 1024) });
+    let large = fill(Buffers { send: Some(256 * 1024), receive: Some(256 * 1024) });
+    match (small, large) {
+        (Ok(small), Ok(large)) => {
+            verification
+                .check("a full connection stops accepting writes near its buffer sizes",
+                       small.accepted <= 2 * (small.send_buffer + small.receive_buffer),
+                       || format!("{} accepted with {} + {} of buffers", small.accepted, small.send_buffer,
+                                  small.receive_buffer))
+                .check("larger buffers hold more", large.accepted > small.accepted,
+                       || format!("{} vs {}", large.accepted, small.accepted));
+        }
+        (Err(err), _) | (_, Err(err)) => {
+            verification.check("filling a connection works", false, || err.to_string());
+        }
+    }
+    match backpressure(Buffers { send: Some(16 * 1024), receive: Some(16 * 1024) }, 1024 * 1024) {
+        Ok(run) => {
+            verification
+                .check("a slow reader blocks the sender's writes", run.blocked_writes() > 0, || "no write blocked".to_string())
+                .equal("every byte written is read", run.read.last().map(|&(_, bytes)| bytes), Some(1024 * 1024));
+        }
+        Err(err) => {
+            verification.check("the backpressure run completes", false, || err.to_string());
+        }
+    }
+    verification
+}
+
+const QUIZ: &[Question] = &[
+    Question::new(
+        "You call setsockopt(SO_SNDBUF, 16384) on Linux. What does getsockopt return?",
+        &["16384", "32768: the kernel doubles it for bookkeeping overhead", "0"],
+        1,
+        "Half the doubled value is reserved for the kernel's own structures.",
+    ),
+    Question::new(
+        "A TCP receiver stops reading. What happens to the sender?",
+        &[
+            "Its data is dropped",
+            "The receiver's window closes, the sender's buffer fills, and write() blocks or returns WouldBlock",
+            "The connection is reset",
+        ],
+        1,
+        "Flow control propagates the slow reader back to the writer: backpressure.",
+    ),
+    Question::new(
+        "Why don't bigger socket buffers fix a slow reader?",
+        &[
+            "They're capped at 64 KiB",
+            "The reader still drains at its own rate; bigger buffers only hold more waiting data (more latency)",
+            "They do fix it",
+        ],
+        1,
+        "Buffers absorb bursts; they can't raise the steady-state rate, only the queueing delay.",
+    ),
+];
+
+#[derive(Demo)]
+#[demo(name = "socket-buffers-demo", description = "Socket buffer sizes, WouldBlock and backpressure",
+       quiz = QUIZ, verify = verify)]
+pub struct SocketBuffersDemo;
+
+impl SocketBuffersDemo {
+    fn demonstrate(&self, config: &Config) -> DemoReport {
+        output::title("🪣 Socket Buffers Demo");
+        outln!("Where written bytes wait, and what happens when they can't.\n");
+
+        demonstrate_defaults();
+        let mut report = DemoReport::default();
+        demonstrate_fill(&mut report);
+        demonstrate_backpressure(config.size.unwrap_or(TOTAL), &mut report);
+
+        output::takeaways();
+        outln!("• Every TCP connection has a send buffer and a receive buffer in the kernel");
+        outln!("• write() only copies into the send buffer; it blocks (or WouldBlock) when that's full");
+        outln!("• A slow reader closes the window and slows the writer: backpressure");
+        outln!("• Bigger buffers absorb bursts but add queueing delay under steady overload");
+        report
+    }
+}
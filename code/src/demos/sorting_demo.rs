@@ -0,0 +1,475 @@
+//! Sorting Demo
+//!
+//! Five textbook sorts written out (insertion sort, quicksort, mergesort,
+//! heapsort and LSD radix sort) plus a quicksort with a branchless
+//! partition, raced against the standard library's `sort_unstable` and
+//! `sort` on random `u32`s from a few KiB to well past the caches. The
+//! comparison counts say heapsort should keep up; the timings show it
+//! falling behind once its heap outgrows the caches, because every step
+//! of a sift-down jumps twice as far into the array as the last.
+//! Run with: cargo run --release --bin sorting-demo
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use crate::bench::Measurement;
+use crate::demo::{Config, Demo, DemoReport};
+use crate::envinfo::Environment;
+use crate::output::Table;
+use crate::plot::{format_bytes, LinePlot};
+use crate::quiz::Question;
+use crate::verify::Verification;
+use crate::{detailln, outln, output};
+
+/// Largest array sorted, in elements (`--size`)
+const MAX_ELEMENTS: usize = 16 * 1024 * 1024;
+/// Insertion sort is quadratic; beyond this it would take minutes
+const INSERTION_MAX: usize = 16 * 1024;
+/// Slices this short are finished with insertion sort by quicksort and
+/// mergesort
+const SMALL: usize = 16;
+
+type Sort = fn(&mut [u32]);
+
+fn insertion_sort(v: &mut [u32]) {
+    for i in 1..v.len() {
+        let x = v[i];
+        let mut j = i;
+        while j > 0 && v[j - 1] > x {
+            v[j] = v[j - 1];
+            j -= 1;
+        }
+        v[j] = x;
+    }
+}
+
+/// Median of the first, middle and last elements, moved to the end
+fn median_of_three(v: &mut [u32]) -> u32 {
+    let (a, b, c) = (0, v.len() / 2, v.len() - 1);
+    if v[a] > v[b] { v.swap(a, b) }
+    if v[b] > v[c] { v.swap(b, c) }
+    if v[a] > v[b] { v.swap(a, b) }
+    v.swap(b, c);
+    v[c]
+}
+
+/// Hoare's scheme: walk inwards from both ends, stopping at elements on the
+/// wrong side. Each comparison is a branch the CPU has to guess, and on
+/// random data it guesses wrong about half the time.
+fn partition_branchy(v: &mut [u32]) -> usize {
+    let last = v.len() - 1;
+    let pivot = median_of_three(v);
+    let (mut left, mut right) = (0, last);
+    loop {
+        while v[left] < pivot {
+            left += 1;
+        }
+        while right > left && v[right - 1] > pivot {
+            right -= 1;
+        }
+        if right <= left + 1 {
+            break;
+        }
+        right -= 1;
+        v.swap(left, right);
+        left += 1;
+    }
+    v.swap(left, last);
+    left
+}
+
+/// Lomuto's scheme without a branch: every element is swapped into the
+/// boundary slot, and the boundary advances by the comparison's result
+/// (0 or 1), which compiles to a flag, not a jump
+fn partition_branchless(v: &mut [u32]) -> usize {
+    let last = v.len() - 1;
+    let pivot = median_of_three(v);
+    let mut boundary = 0;
+    for i in 0..last {
+        let smaller = v[i] < pivot;
+        v.swap(i, boundary);
+        boundary += smaller as usize;
+    }
+    v.swap(boundary, last);
+    boundary
+}
+
+/// Partition, recurse into the smaller side and loop on the larger, so the
+/// stack stays O(log n) deep
+fn quicksort_with(mut v: &mut [u32], partition: fn(&mut [u32]) -> usize) {
+    while v.len() > SMALL {
+        let split = partition(v);
+        let (left, right) = v.split_at_mut(split);
+        let right = &mut right[1..];
+        if left.len() < right.len() {
+            quicksort_with(left, partition);
+            v = right;
+        } else {
+            quicksort_with(right, partition);
+            v = left;
+        }
+    }
+    insertion_sort(v);
+}
+
+fn quicksort(v: &mut [u32]) {
+    quicksort_with(v, partition_branchy);
+}
+
+fn quicksort_branchless(v: &mut [u32]) {
+    quicksort_with(v, partition_branchless);
+}
+
+/// Bottom-up: sort runs of `SMALL` by insertion, then merge pairs of runs
+/// back and forth between the array and one buffer of the same size
+fn mergesort(v: &mut [u32]) {
+    let n = v.len();
+    for run in v.chunks_mut(SMALL) {
+        insertion_sort(run);
+    }
+    let mut buffer = vec![0u32; n];
+    let (mut from, mut to): (&mut [u32], &mut [u32]) = (v, &mut buffer);
+    let mut width = SMALL;
+    let mut in_buffer = false;
+    while width < n {
+        for start in (0..n).step_by(2 * width) {
+            let middle = (start + width).min(n);
+            let end = (start + 2 * width).min(n);
+            merge(&from[start..middle], &from[middle..end], &mut to[start..end]);
+        }
+        std::mem::swap(&mut from, &mut to);
+        in_buffer = !in_buffer;
+        width *= 2;
+    }
+    if in_buffer {
+        to.copy_from_slice(from);
+    }
+}
+
+fn merge(left: &[u32], right: &[u32], out: &mut [u32]) {
+    let (mut i, mut j) = (0, 0);
+    for slot in out.iter_mut() {
+        if j == right.len() || (i < left.len() && left[i] <= right[j]) {
+            *slot = left[i];
+            i += 1;
+        } else {
+            *slot = right[j];
+            j += 1;
+        }
+    }
+}
+
+/// Build a max-heap in place, then repeatedly swap the root to the end and
+/// sift the new root down. The children of `i` are at 2i+1 and 2i+2: each
+/// step down the tree lands twice as far into the array.
+fn heapsort(v: &mut [u32]) {
+    let n = v.len();
+    for root in (0..n / 2).rev() {
+        sift_down(v, root, n);
+    }
+    for end in (1..n).rev() {
+        v.swap(0, end);
+        sift_down(v, 0, end);
+    }
+}
+
+fn sift_down(v: &mut [u32], mut root: usize, end: usize) {
+    loop {
+        let mut child = 2 * root + 1;
+        if child >= end {
+            return;
+        }
+        if child + 1 < end && v[child + 1] > v[child] {
+            child += 1;
+        }
+        if v[root] >= v[child] {
+            return;
+        }
+        v.swap(root, child);
+        root = child;
+    }
+}
+
+/// Least significant digit first, a byte per pass: count each digit's
+/// occurrences, turn the counts into starting offsets, and scatter every
+/// element to its slot. Four passes, no comparisons at all.
+fn radix_sort(v: &mut [u32]) {
+    let mut buffer = vec![0u32; v.len()];
+    let mut counts = [[0usize; 256]; 4];
+    for &x in v.iter() {
+        for (digit, count) in counts.iter_mut().enumerate() {
+            count[(x >> (8 * digit)) as usize & 0xff] += 1;
+        }
+    }
+    let (mut from, mut to): (&mut [u32], &mut [u32]) = (v, &mut buffer);
+    for (digit, count) in counts.iter().enumerate() {
+        let mut offsets = [0usize; 256];
+        let mut total = 0;
+        for (offset, &c) in offsets.iter_mut().zip(count) {
+            *offset = total;
+            total += c;
+        }
+        for &x in from.iter() {
+            let d = (x >> (8 * digit)) as usize & 0xff;
+            to[offsets[d]] = x;
+            offsets[d] += 1;
+        }
+        std::mem::swap(&mut from, &mut to);
+    }
+    // An even number of passes leaves the result back in `v`
+}
+
+fn std_unstable(v: &mut [u32]) {
+    v.sort_unstable();
+}
+
+fn std_stable(v: &mut [u32]) {
+    v.sort();
+}
+
+const SORTS: &[(&str, Sort)] = &[
+    ("insertion", insertion_sort),
+    ("quicksort", quicksort),
+    ("quick, branchless", quicksort_branchless),
+    ("mergesort", mergesort),
+    ("heapsort", heapsort),
+    ("radix (LSD)", radix_sort),
+    ("sort_unstable", std_unstable),
+    ("sort", std_stable),
+];
+
+fn random_values(n: usize, config: &Config) -> Vec<u32> {
+    let mut rng = config.rng();
+    (0..n).map(|_| rng.next_u64() as u32).collect()
+}
+
+/// Sort a fresh copy of `input` `runs` times, timing only the sort
+fn time_sort(sort: Sort, input: &[u32], work: &mut Vec<u32>, runs: usize) -> Measurement {
+    let samples: Vec<Duration> = (0..runs).map(|_| {
+        work.clear();
+        work.extend_from_slice(input);
+        let start = Instant::now();
+        sort(black_box(work.as_mut_slice()));
+        start.elapsed()
+    }).collect();
+    Measurement::from_samples(samples)
+}
+
+/// 1 Ki elements (4 KiB), then ×4 up to `max`
+fn sizes(max: usize) -> Vec<usize> {
+    std::iter::successors(Some(1024usize), |n| Some(n * 4)).take_while(|&n| n <= max).collect()
+}
+
+/// Nanoseconds per element for every sort at every size; `None` where a
+/// sort was skipped
+fn demonstrate_sweep(sizes: &[usize], config: &Config, report: &mut DemoReport) -> Vec<Vec<Option<f64>>> {
+    output::heading("📈 Nanoseconds per Element");
+    let environment = Environment::current();
+    let input = random_values(sizes[sizes.len() - 1], config);
+    let mut work = Vec::with_capacity(input.len());
+    let mut header = vec!["elements", "bytes"];
+    header.extend(SORTS.iter().map(|(name, _)| *name));
+    let mut table = Table::new(header);
+    let mut all = Vec::new();
+    for &n in sizes {
+        let runs = (4_000_000 / n).clamp(3, 15);
+        let mut row_times = Vec::new();
+        for (index, &(name, sort)) in SORTS.iter().enumerate() {
+            // Insertion sort is first in `SORTS`
+            if index == 0 && n > INSERTION_MAX {
+                row_times.push(None);
+                continue;
+            }
+            let time = time_sort(sort, &input[..n], &mut work, runs);
+            detailln!("  {:>9} {:<18} {}", n, name, time);
+            let per_element = time.median().as_secs_f64() * 1e9 / n as f64;
+            report.record(name, per_element, "ns/element").param("elements", n);
+            row_times.push(Some(per_element));
+        }
+        let bytes = n * 4;
+        let fits = environment.caches.iter()
+            .filter(|cache| cache.kind != "Instruction" && cache.bytes >= bytes as u64)
+            .min_by_key(|cache| cache.level)
+            .map_or_else(|| "RAM".to_string(), |cache| format!("L{}", cache.level));
+        let mut row = vec![crate::plot::format_si(n as f64), format!("{}B {}", format_bytes(bytes as f64), fits)];
+        let present: Vec<f64> = row_times.iter().flatten().copied().collect();
+        let mut ranked = output::rank(&present, false, |t| format!("{:.1}", t)).into_iter();
+        row.extend(row_times.iter().map(|time| match time {
+            Some(_) => ranked.next().unwrap_or_default(),
+            None => "—".to_string(),
+        }));
+        table.row(row);
+        all.push(row_times);
+    }
+    outln!("{}", table);
+
+    // The quadratic insertion sort, the branchy quicksort and the stable
+    // sort would crowd the plot without changing its story
+    let mut plot = LinePlot::new("elements", "ns/element").log_x().log_y();
+    for index in [2, 3, 4, 5, 6] {
+        plot = plot.series(SORTS[index].0, sizes.iter().zip(&all)
+            .filter_map(|(&n, row)| row[index].map(|t| (n as f64, t))).collect());
+    }
+    outln!("{}", plot);
+    all
+}
+
+fn demonstrate_branches(sizes: &[usize], times: &[Vec<Option<f64>>]) {
+    output::heading("🔀 Branchy vs Branchless Partition");
+    let ratios: Vec<f64> = times.iter().filter_map(|row| Some(row[1]? / row[2]?)).collect();
+    let (low, high) = (ratios.iter().cloned().fold(f64::MAX, f64::min), ratios.iter().cloned().fold(f64::MIN, f64::max));
+    outln!("Same pivots, same recursion; only the partition loop differs. The branchless");
+    outln!("one runs {:.2}x to {:.2}x the speed of the branchy one across the sizes.", low, high);
+    outln!("On random data every `v[left] < pivot` is a coin flip, and each wrong guess");
+    outln!("throws away ~15-20 cycles of speculative work; the branchless loop turns the");
+    outln!("comparison into a 0 or 1 that is simply added, so there's nothing to guess.");
+    outln!("sort_unstable (pattern-defeating quicksort, now ipnsort) partitions the same");
+    outln!("way, and at {} elements it runs {:.2}x the speed of our branchless quicksort.\n",
+           crate::plot::format_si(sizes[sizes.len() - 1] as f64),
+           times[times.len() - 1][2].unwrap_or(0.0) / times[times.len() - 1][6].unwrap_or(1.0));
+}
+
+fn demonstrate_heapsort(sizes: &[usize], times: &[Vec<Option<f64>>]) {
+    output::heading("🌳 Why Heapsort Falls Behind");
+    let mut table = Table::new(["elements", "log₂ n", "heapsort / quicksort", "heapsort / mergesort"]);
+    for (&n, row) in sizes.iter().zip(times) {
+        let (Some(quick), Some(merge), Some(heap)) = (row[1], row[3], row[4]) else { continue };
+        table.row([crate::plot::format_si(n as f64), format!("{:.0}", (n as f64).log2()),
+                   format!("{:.2}x", heap / quick), format!("{:.2}x", heap / merge)]);
+    }
+    outln!("{}", table);
+    let first = &times[0];
+    let last = &times[times.len() - 1];
+    let (small, large) = (first[4].unwrap_or(0.0) / first[1].unwrap_or(1.0), last[4].unwrap_or(0.0) / last[1].unwrap_or(1.0));
+    let environment = Environment::current();
+    let l2 = environment.caches.iter().find(|cache| cache.level == 2).map_or(1 << 20, |cache| cache.bytes as usize);
+    let n = sizes[sizes.len() - 1];
+    let depth = (n as f64).log2().ceil() as usize;
+    let cached_levels = (((l2 / 4) as f64).log2().floor() as usize).min(depth);
+    outln!("All three do about n·log₂ n comparisons, yet heapsort goes from {:.2}x", small);
+    outln!("quicksort's time at {} elements to {:.2}x at {}. Quicksort's partition and", crate::plot::format_si(sizes[0] as f64),
+           large, crate::plot::format_si(n as f64));
+    outln!("mergesort's merge stream through memory front to back: the prefetcher sees");
+    outln!("the pattern and every cache line brought in is used for 16 elements.");
+    outln!("A sift-down goes from i to 2i+1: the heap of {} has {} levels, and the", crate::plot::format_si(n as f64), depth);
+    outln!("top {} fit in the {}B L2. Every step below them touches a new line at an", cached_levels, format_bytes(l2 as f64));
+    outln!("address no prefetcher can predict, and the next step can't start until this");
+    outln!("comparison resolves, so the misses are paid one after another: {} for", depth - cached_levels);
+    outln!("every element removed. Radix sort does no comparisons and reads its input in");
+    outln!("order; at {} it takes {:.1} ns per element to heapsort's {:.1}.\n",
+           crate::plot::format_si(n as f64), last[5].unwrap_or(0.0), last[4].unwrap_or(0.0));
+}
+
+/// Every sort sorts, on random, sorted, reversed and duplicate-heavy input,
+/// and the two partitions and heapsort behave as the demo describes
+fn verify() -> Verification {
+    let mut verification = Verification::new();
+    let config = Config::default();
+    let random = random_values(5000, &config);
+    let mut wrong = Vec::new();
+    for n in [0, 1, 2, 3, 15, 16, 17, 100, 1000, 5000] {
+        let inputs = [
+            ("random", random[..n].to_vec()),
+            ("sorted", (0..n as u32).collect()),
+            ("reversed", (0..n as u32).rev().collect()),
+            ("few values", random[..n].iter().map(|x| x % 3).collect()),
+        ];
+        for (kind, input) in &inputs {
+            let mut expected = input.clone();
+            expected.sort_unstable();
+            for &(name, sort) in SORTS {
+                let mut v = input.clone();
+                sort(&mut v);
+                if v != expected {
+                    wrong.push(format!("{} on {} {}", name, n, kind));
+                }
+            }
+        }
+    }
+    wrong.truncate(5);
+    verification.check("all 8 sorts agree with sort_unstable on 10 sizes of random, sorted, reversed and 3-valued input",
+                       wrong.is_empty(), || wrong.join(", "));
+
+    let input = random_values(4096, &config);
+    let mut work = Vec::new();
+    let insertion = time_sort(insertion_sort, &input, &mut work, 5);
+    let unstable = time_sort(std_unstable, &input, &mut work, 5);
+    verification.faster("sort_unstable beats insertion sort at 4096 elements", &unstable, &insertion, 5.0);
+    if cfg!(debug_assertions) {
+        verification.skip("the branchless partition beats the branchy one", "timings of unoptimized code");
+        verification.skip("heapsort falls further behind quicksort at 16M elements than at 4K", "timings of unoptimized code");
+        return verification;
+    }
+    let input = random_values(16 << 20, &config);
+    let branchy = time_sort(quicksort, &input[..1 << 20], &mut work, 3);
+    let branchless = time_sort(quicksort_branchless, &input[..1 << 20], &mut work, 3);
+    verification.faster("the branchless partition beats the branchy one", &branchless, &branchy, 1.2);
+    let ratio = |n: usize, runs: usize| {
+        let mut work = Vec::new();
+        let heap = time_sort(heapsort, &input[..n], &mut work, runs).median().as_secs_f64();
+        heap / time_sort(quicksort, &input[..n], &mut work, runs).median().as_secs_f64()
+    };
+    let (small, large) = (ratio(4096, 15), ratio(16 << 20, 1));
+    verification.check("heapsort falls further behind quicksort at 16M elements than at 4K", large > small * 1.3,
+                       || format!("{:.2}x at 4K, {:.2}x at 16M", small, large));
+    verification
+}
+
+const QUIZ: &[Question] = &[
+    Question::new(
+        "Why does heapsort slow down more than quicksort as the array outgrows the caches?",
+        &[
+            "It does more comparisons",
+            "Each sift-down step jumps to index 2i+1, a new cache line the prefetcher can't predict, and waits for it",
+            "It needs a second buffer",
+        ],
+        1,
+        "Both do O(n log n) comparisons; quicksort's partition scans memory sequentially.",
+    ),
+    Question::new(
+        "How does a branchless partition avoid mispredictions?",
+        &[
+            "It compares fewer elements",
+            "It always swaps, and adds the comparison result (0 or 1) to the boundary instead of branching on it",
+            "It uses SIMD instructions for every comparison",
+        ],
+        1,
+        "With no conditional jump there's nothing for the branch predictor to get wrong on random data.",
+    ),
+    Question::new(
+        "Radix sort does no comparisons. What does it pay instead?",
+        &[
+            "A pass over the data per digit, and a buffer as large as the input",
+            "Quadratic time on sorted input",
+            "Nothing; it's always fastest",
+        ],
+        0,
+        "Four byte-wide passes for u32 keys; on short arrays the counting overhead dominates.",
+    ),
+];
+
+#[derive(Demo)]
+#[demo(name = "sorting-demo", description = "Insertion, quick, merge, heap and radix sort from L1 to RAM; branchless partition",
+       quiz = QUIZ, verify = verify)]
+pub struct SortingDemo;
+
+impl SortingDemo {
+    fn demonstrate(&self, config: &Config) -> DemoReport {
+        output::title("📊 Sorting Demo");
+        outln!("Eight ways to sort random u32s, from arrays that fit in L1 to ones that don't fit in any cache.\n");
+
+        let mut report = DemoReport::default();
+        let sizes = sizes(config.size.unwrap_or(MAX_ELEMENTS).max(1024));
+        let times = demonstrate_sweep(&sizes, config, &mut report);
+        demonstrate_branches(&sizes, &times);
+        demonstrate_heapsort(&sizes, &times);
+
+        output::takeaways();
+        outln!("• Equal comparison counts don't mean equal time: access patterns decide");
+        outln!("• Sequential scans (quicksort, mergesort, radix) let the prefetcher hide memory latency");
+        outln!("• Heapsort's dependent jumps to 2i+1 miss the cache at every level below the top few");
+        outln!("• Turning a data-dependent branch into arithmetic removes mispredictions");
+        outln!("• sort_unstable already does all of this: reach for it first");
+        report
+    }
+}
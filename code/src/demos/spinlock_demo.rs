@@ -0,0 +1,190 @@
+//! Spinlock Demo
+//!
+//! Test-and-set, test-and-test-and-set with backoff, and ticket spinlocks
+//! compared against `std::sync::Mutex` for short and long critical sections.
+//! Run with: cargo run --release --bin spinlock-demo
+
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use crate::bench::{format_rate, measure};
+use crate::demo::{Config, Demo, DemoReport};
+use crate::output::Table;
+use crate::quiz::Question;
+use crate::sync::spinlock::{RawLock, SpinLock, TasLock, TicketLock, TtasLock};
+use crate::{outln, output};
+
+/// Shared state touched inside the critical section: 8 cache lines
+type Shared = [u64; 64];
+
+/// Work done while holding the lock; `steps` sets the critical section length
+fn critical_section(data: &mut Shared, steps: usize) {
+    for k in 0..steps {
+        let slot = &mut data[k % 64];
+        *slot = slot.wrapping_mul(31).wrapping_add(k as u64 + 1);
+    }
+    data[0] += 1;
+}
+
+fn run_spin<L: RawLock + Send + Sync + 'static>(threads: usize, ops: usize, steps: usize) -> u64 {
+    let lock = Arc::new(SpinLock::<Shared, L>::new([0; 64]));
+    let handles: Vec<_> = (0..threads)
+        .map(|_| {
+            let lock = Arc::clone(&lock);
+            thread::spawn(move || {
+                for _ in 0..ops {
+                    critical_section(&mut lock.lock(), steps);
+                }
+            })
+        })
+        .collect();
+    handles.into_iter().for_each(|h| h.join().unwrap());
+    let data = lock.lock();
+    data[0]
+}
+
+fn run_mutex(threads: usize, ops: usize, steps: usize) -> u64 {
+    let lock = Arc::new(Mutex::new([0u64; 64]));
+    let handles: Vec<_> = (0..threads)
+        .map(|_| {
+            let lock = Arc::clone(&lock);
+            thread::spawn(move || {
+                for _ in 0..ops {
+                    critical_section(&mut lock.lock().unwrap(), steps);
+                }
+            })
+        })
+        .collect();
+    handles.into_iter().for_each(|h| h.join().unwrap());
+    let data = lock.lock().unwrap();
+    data[0]
+}
+
+fn demonstrate_lock_designs() {
+    output::heading("🔐 Three Spinlock Designs");
+    outln!("TAS:    while !lock.cas(false, true) {{}}        - every attempt is an RMW");
+    outln!("TTAS:   while lock.load() {{}} then cas(false, true) - spin on a read, back off");
+    outln!("Ticket: my = next.fetch_add(1); while serving != my {{}} - FIFO fair\n");
+
+    outln!("Cache-coherence view (MESI):");
+    outln!("  • An RMW (swap/CAS) needs the line in Modified/Exclusive state → invalidates all other copies");
+    outln!("  • TAS waiters keep bouncing the line between cores even while the lock is held");
+    outln!("  • TTAS waiters share a read-only copy; traffic only happens on unlock");
+    outln!("  • Ticket lock waiters also read-spin, but all of them re-fetch on every unlock\n");
+
+    let threads = 4;
+    let ops = 20_000;
+    let expected = (threads * ops) as u64;
+    outln!("Correctness: {} threads × {} locked increments", threads, ops);
+    for (name, count) in [
+        ("TAS", run_spin::<TasLock>(threads, ops, 0)),
+        ("TTAS+backoff", run_spin::<TtasLock>(threads, ops, 0)),
+        ("Ticket", run_spin::<TicketLock>(threads, ops, 0)),
+        ("Mutex", run_mutex(threads, ops, 0)),
+    ] {
+        outln!("  {:<13} {} {}", name, count, if count == expected { "✅" } else { "❌" });
+    }
+    outln!();
+}
+
+fn benchmark_table(label: &str, steps: usize, ops: usize, report: &mut DemoReport) {
+    outln!("{} ({} steps inside the lock, {} acquisitions per thread, median of 3)", label, steps, ops);
+    let table = Table::new(["threads", "TAS", "TTAS+backoff", "Ticket", "Mutex"]).widths([7, 12, 13, 12, 12]);
+    outln!("{}", table.header());
+
+    let max_threads = thread::available_parallelism().map(|n| n.get()).unwrap_or(1).max(2) * 2;
+    let mut threads = 1;
+    while threads <= max_threads {
+        let total = (threads * ops) as u64;
+        let tas = measure(3, || run_spin::<TasLock>(threads, ops, steps));
+        let ttas = measure(3, || run_spin::<TtasLock>(threads, ops, steps));
+        let ticket = measure(3, || run_spin::<TicketLock>(threads, ops, steps));
+        let mutex = measure(3, || run_mutex(threads, ops, steps));
+        let rates = [&tas, &ttas, &ticket, &mutex].map(|measurement| measurement.throughput(total));
+        outln!("{}", table.line([threads.to_string()].into_iter().chain(output::rank(&rates, true, format_rate))));
+        for (name, measurement) in [("TAS", &tas), ("TTAS+backoff", &ttas), ("Ticket", &ticket), ("Mutex", &mutex)] {
+            report
+                .record(name, measurement.throughput(total), "acquisitions/s")
+                .param("threads", threads)
+                .param("critical_section_steps", steps);
+        }
+        threads *= 2;
+    }
+    outln!();
+}
+
+fn demonstrate_benchmark(report: &mut DemoReport) {
+    output::heading("⚡ Lock Acquisitions per Second");
+
+    benchmark_table("Short critical section", 0, 100_000, report);
+    benchmark_table("Long critical section", 500, 5_000, report);
+
+    outln!("Short sections: the lock word itself is the bottleneck, so how waiters");
+    outln!("touch it (swap vs load, backoff) dominates.");
+    outln!("Long sections: the owner holds the lock a while - spinning waiters just burn");
+    outln!("CPU, while Mutex parks them and gives the core back.");
+    outln!("Ticket locks collapse when threads outnumber cores: the next ticket holder");
+    outln!("may be descheduled, and FIFO order forces everyone to wait for it.\n");
+
+    let cores = thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+    if cores == 1 {
+        outln!("⚠️  Only one hardware thread available: a spinning waiter can never see the");
+        outln!("   lock released until the owner is scheduled again - the worst case for spinning.\n");
+    }
+}
+
+const QUIZ: &[Question] = &[
+    Question::new(
+        "Why does test-and-test-and-set beat plain test-and-set under contention?",
+        &[
+            "Waiters spin on a plain load that hits their own cache, and only attempt the atomic write when the lock looks free",
+            "It uses fewer instructions",
+            "It sleeps in the kernel",
+        ],
+        0,
+        "Every failed RMW takes the line exclusively; reads let all waiters share it.",
+    ),
+    Question::new(
+        "What is the ticket lock's weakness?",
+        &[
+            "It's unfair",
+            "If the thread whose turn is next gets preempted, everyone behind it waits too",
+            "It can't be used with more than two threads",
+        ],
+        1,
+        "Strict FIFO order turns one descheduled waiter into a convoy.",
+    ),
+    Question::new(
+        "When is spinning better than blocking?",
+        &[
+            "Always: it avoids syscalls",
+            "When critical sections are tiny and there are no more threads than cores",
+            "When threads outnumber cores",
+        ],
+        1,
+        "Otherwise the spinner burns the CPU time the lock holder needs to finish.",
+    ),
+];
+
+#[derive(Demo)]
+#[demo(name = "spinlock-demo", description = "Spinlock variants vs std::sync::Mutex",
+       quiz = QUIZ)]
+pub struct SpinlockDemo;
+
+impl SpinlockDemo {
+    fn demonstrate(&self, _config: &Config) -> DemoReport {
+        output::title("🌀 Spinlock Demo");
+        outln!("Building locks from atomics, and why std's Mutex doesn't just spin.\n");
+
+        demonstrate_lock_designs();
+        let mut report = DemoReport::default();
+        demonstrate_benchmark(&mut report);
+
+        output::takeaways();
+        outln!("• Lock performance is cache-coherence performance: who writes the lock word, and when");
+        outln!("• Test-and-test-and-set plus backoff cuts coherence traffic under contention");
+        outln!("• Ticket locks buy fairness at the price of sensitivity to preemption");
+        outln!("• Spin only when critical sections are tiny and threads ≤ cores; otherwise block");
+        report
+    }
+}
@@ -0,0 +1,509 @@
+//! Substring Search Demo
+//!
+//! Finding a needle in a haystack of bytes, six ways: the obvious nested
+//! loop; jumping between occurrences of one of the needle's bytes with
+//! memchr, first the needle's first byte and then its rarest; Horspool's
+//! bad-character skips; the Two-Way algorithm, linear in the worst case;
+//! and `str::find` from the standard library. Rare and frequent needles in
+//! generated English-like text show when a SIMD byte scan carries the
+//! search and when it stalls on candidates, and two repetitive inputs show
+//! the worst cases that only Two-Way avoids.
+//! Run with: cargo run --release --bin substring-search-demo
+
+use std::hint::black_box;
+
+use crate::bench::measure;
+use crate::demo::{Config, Demo, DemoReport};
+use crate::output::Table;
+use crate::plot::format_bytes;
+use crate::quiz::Question;
+use crate::rng::Rng;
+use crate::verify::Verification;
+use crate::{detailln, outln, output};
+
+/// Bytes of generated text (`--size`)
+const TEXT_BYTES: usize = 32 * 1024 * 1024;
+const RUNS: usize = 3;
+
+/// Common English words, most frequent first; word `r` is drawn with
+/// weight 1/r, roughly as often as in real text (Zipf's law)
+const WORDS: &[&str] = &[
+    "the", "of", "and", "to", "a", "in", "is", "that", "it", "was", "for", "on", "are", "as", "with", "his",
+    "they", "at", "be", "this", "have", "from", "or", "one", "had", "by", "word", "but", "not", "what", "all",
+    "were", "we", "when", "your", "can", "said", "there", "use", "an", "each", "which", "she", "do", "how",
+    "their", "if", "will", "up", "other", "about", "out", "many", "then", "them", "these", "so", "some", "her",
+    "would", "make", "like", "him", "into", "time", "has", "look", "two", "more", "write", "go", "see",
+    "number", "no", "way", "could", "people", "my", "than", "first", "water", "been", "call", "who", "oil",
+    "its", "now", "find", "long", "down", "day", "did", "get", "come", "made", "may", "part", "over", "new",
+    "sound", "take", "only", "little", "work", "know", "place", "year", "live", "me", "back", "give", "most",
+    "very", "after", "thing", "our", "just", "name", "good", "sentence", "man", "think", "say", "great",
+    "where", "help", "through", "much", "before", "line", "right", "too", "mean", "old", "any", "same",
+    "tell", "boy", "follow", "came", "want", "show", "also", "around", "form", "three", "small", "set",
+    "put", "end", "does", "another", "well", "large", "must", "big", "even", "such", "because", "turn",
+    "here", "why", "ask", "went", "men", "read", "need", "land", "different", "home", "us", "move", "try",
+    "kind", "hand", "picture", "again", "change", "off", "play", "spell", "air", "away", "animal", "house",
+    "point", "page", "letter", "mother", "answer", "found", "study", "still", "learn", "should", "world",
+    "next", "size", "quite", "example", "question", "zero", "jump", "quick", "box", "major", "exact",
+];
+
+/// Letters from most to least common in English, after the space
+const FREQUENCY_ORDER: &[u8] = b" etaoinshrdlcumwfgypbvkjxqz";
+
+/// Words drawn by frequency, separated by spaces, with a full stop and a
+/// line break now and then
+fn english_text(bytes: usize, rng: &mut Rng) -> String {
+    let cumulative: Vec<usize> = WORDS.iter().enumerate()
+        .scan(0, |total, (rank, _)| { *total += 100_000 / (rank + 1); Some(*total) }).collect();
+    let total = cumulative[cumulative.len() - 1];
+    let mut text = Vec::with_capacity(bytes + 16);
+    while text.len() < bytes {
+        let pick = rng.below(total);
+        text.extend_from_slice(WORDS[cumulative.partition_point(|&end| end <= pick)].as_bytes());
+        match rng.below(24) {
+            0 => text.extend_from_slice(b".\n"),
+            1 => text.extend_from_slice(b". "),
+            _ => text.push(b' '),
+        }
+    }
+    text.truncate(bytes);
+    String::from_utf8(text).expect("ASCII words")
+}
+
+/// How rare `byte` is in English text: higher is rarer, and anything that
+/// isn't a lowercase letter or space is rarer than all of them
+fn rarity(byte: u8) -> usize {
+    FREQUENCY_ORDER.iter().position(|&common| common == byte).unwrap_or(FREQUENCY_ORDER.len())
+}
+
+/// Index of the needle's rarest byte
+fn rarest(needle: &[u8]) -> usize {
+    (0..needle.len()).max_by_key(|&i| rarity(needle[i])).unwrap_or(0)
+}
+
+// ---------------------------------------------------------------------
+// One byte at a time, eight at a time, and the C library's memchr
+// ---------------------------------------------------------------------
+
+/// Where a byte first occurs
+type Scan = fn(u8, &[u8]) -> Option<usize>;
+
+fn position_loop(byte: u8, haystack: &[u8]) -> Option<usize> {
+    haystack.iter().position(|&b| b == byte)
+}
+
+/// Eight bytes per step in a u64 (SIMD within a register): XOR with the
+/// byte repeated makes matching bytes zero, and `(x - 0x01..) & !x & 0x80..`
+/// sets the top bit of a zero byte's lane (and possibly of lanes above it,
+/// which the lowest-set-bit search never reaches first)
+fn position_swar(byte: u8, haystack: &[u8]) -> Option<usize> {
+    const LOW: u64 = 0x0101_0101_0101_0101;
+    const HIGH: u64 = 0x8080_8080_8080_8080;
+    let repeated = LOW * byte as u64;
+    let mut chunks = haystack.chunks_exact(8);
+    for (index, chunk) in (&mut chunks).enumerate() {
+        let word = u64::from_le_bytes(chunk.try_into().unwrap()) ^ repeated;
+        let zero_lanes = word.wrapping_sub(LOW) & !word & HIGH;
+        if zero_lanes != 0 {
+            return Some(index * 8 + zero_lanes.trailing_zeros() as usize / 8);
+        }
+    }
+    let tail = haystack.len() - chunks.remainder().len();
+    position_loop(byte, chunks.remainder()).map(|offset| tail + offset)
+}
+
+/// The C library's memchr: 16 to 64 bytes per step with SIMD compares
+fn memchr(byte: u8, haystack: &[u8]) -> Option<usize> {
+    // SAFETY: memchr reads at most `haystack.len()` bytes from its start
+    let found = unsafe { libc::memchr(haystack.as_ptr().cast(), byte as libc::c_int, haystack.len()) };
+    (!found.is_null()).then(|| found as usize - haystack.as_ptr() as usize)
+}
+
+// ---------------------------------------------------------------------
+// The searchers. Each returns the first position of a non-empty needle.
+// ---------------------------------------------------------------------
+
+/// Searchers take `&str` so `str::find` can join them; the others only
+/// look at the bytes
+type Search = fn(&str, &str) -> Option<usize>;
+
+/// Try every position, comparing left to right until a mismatch:
+/// O(n·m) when most positions match for a while
+fn naive(haystack: &str, needle: &str) -> Option<usize> {
+    let (haystack, needle) = (haystack.as_bytes(), needle.as_bytes());
+    let last = haystack.len().checked_sub(needle.len())?;
+    (0..=last).find(|&position| {
+        let mut i = 0;
+        while i < needle.len() && haystack[position + i] == needle[i] {
+            i += 1;
+        }
+        i == needle.len()
+    })
+}
+
+/// Let memchr find each occurrence of the needle's byte at `anchor`, then
+/// check the whole needle there
+fn memchr_at(haystack: &[u8], needle: &[u8], anchor: usize) -> Option<usize> {
+    let mut from = anchor;
+    while from < haystack.len() {
+        let found = from + memchr(needle[anchor], &haystack[from..])?;
+        let start = found - anchor;
+        if haystack.len() - start < needle.len() {
+            return None;
+        }
+        if &haystack[start..start + needle.len()] == needle {
+            return Some(start);
+        }
+        from = found + 1;
+    }
+    None
+}
+
+fn memchr_first(haystack: &str, needle: &str) -> Option<usize> {
+    memchr_at(haystack.as_bytes(), needle.as_bytes(), 0)
+}
+
+/// The fewer places memchr stops, the longer its SIMD runs between them
+fn memchr_rarest(haystack: &str, needle: &str) -> Option<usize> {
+    let (haystack, needle) = (haystack.as_bytes(), needle.as_bytes());
+    memchr_at(haystack, needle, rarest(needle))
+}
+
+/// Boyer-Moore-Horspool: compare right to left, and on a mismatch shift
+/// by how far the window's last byte is from its last occurrence in the
+/// needle, up to the whole needle length when it isn't in it
+fn horspool(haystack: &str, needle: &str) -> Option<usize> {
+    let (haystack, needle) = (haystack.as_bytes(), needle.as_bytes());
+    let m = needle.len();
+    let mut shift = [m; 256];
+    for (i, &byte) in needle[..m - 1].iter().enumerate() {
+        shift[byte as usize] = m - 1 - i;
+    }
+    let mut position = 0;
+    while position + m <= haystack.len() {
+        let mut i = m;
+        while i > 0 && haystack[position + i - 1] == needle[i - 1] {
+            i -= 1;
+        }
+        if i == 0 {
+            return Some(position);
+        }
+        position += shift[haystack[position + m - 1] as usize];
+    }
+    None
+}
+
+/// The start and period of the needle's maximal suffix under the byte
+/// order, or the reversed order if `reversed`
+fn maximal_suffix(needle: &[u8], reversed: bool) -> (usize, usize) {
+    let (mut left, mut right, mut offset, mut period) = (0, 1, 0, 1);
+    while let Some(&a) = needle.get(right + offset) {
+        let b = needle[left + offset];
+        if (a < b) != reversed && a != b {
+            // A smaller suffix: the period is everything since `left`
+            right += offset + 1;
+            offset = 0;
+            period = right - left;
+        } else if a == b {
+            if offset + 1 == period {
+                right += offset + 1;
+                offset = 0;
+            } else {
+                offset += 1;
+            }
+        } else {
+            // A larger suffix starts here
+            left = right;
+            right += 1;
+            offset = 0;
+            period = 1;
+        }
+    }
+    (left, period)
+}
+
+/// Two-Way (Crochemore and Perrin): split the needle at a critical point,
+/// match the right half left to right, then the left half right to left.
+/// The split guarantees shifts that never skip a match and never revisit
+/// more than a period's worth of bytes: O(n) time, O(1) space.
+fn two_way(haystack: &str, needle: &str) -> Option<usize> {
+    let (haystack, needle) = (haystack.as_bytes(), needle.as_bytes());
+    let m = needle.len();
+    let (forward, forward_period) = maximal_suffix(needle, false);
+    let (backward, backward_period) = maximal_suffix(needle, true);
+    let (split, mut period) = if forward > backward { (forward, forward_period) } else { (backward, backward_period) };
+    // A needle that repeats with this period remembers how much of the
+    // previous window is known to match; any other shifts conservatively
+    let periodic = needle[..split] == needle[period..period + split];
+    if !periodic {
+        period = split.max(m - split) + 1;
+    }
+    let mut position = 0;
+    let mut memory = 0;
+    'search: while position + m <= haystack.len() {
+        let start = if periodic { split.max(memory) } else { split };
+        for i in start..m {
+            if needle[i] != haystack[position + i] {
+                position += i - split + 1;
+                memory = 0;
+                continue 'search;
+            }
+        }
+        let end = if periodic { memory } else { 0 };
+        for i in (end..split).rev() {
+            if needle[i] != haystack[position + i] {
+                position += period;
+                if periodic {
+                    memory = m - period;
+                }
+                continue 'search;
+            }
+        }
+        return Some(position);
+    }
+    None
+}
+
+/// The standard library: Two-Way, with a SIMD prefilter for short needles
+fn std_find(haystack: &str, needle: &str) -> Option<usize> {
+    haystack.find(needle)
+}
+
+const SEARCHES: &[(&str, Search)] = &[
+    ("naive", naive),
+    ("memchr first", memchr_first),
+    ("memchr rarest", memchr_rarest),
+    ("Horspool", horspool),
+    ("Two-Way", two_way),
+    ("str::find", std_find),
+];
+
+/// Every occurrence, overlapping ones included
+fn count(search: Search, haystack: &str, needle: &str) -> usize {
+    let (mut found, mut from) = (0, 0);
+    while let Some(offset) = search(&haystack[from..], needle) {
+        found += 1;
+        from += offset + haystack[from + offset..].chars().next().map_or(1, char::len_utf8);
+    }
+    found
+}
+
+/// A haystack, a needle, and what to look for
+struct Workload {
+    name: &'static str,
+    haystack: String,
+    needle: String,
+}
+
+fn workloads(bytes: usize, rng: &mut Rng) -> Vec<Workload> {
+    let text = english_text(bytes, rng);
+    let needle = "quixotic zephyr";
+    let at = text.len() - needle.len() - 1;
+    let rare = format!("{}{}{}", &text[..at], needle, &text[at + needle.len()..]);
+    // Repetitive inputs are quadratic for the naive loop, so smaller
+    let repetitive = "a".repeat(bytes / 16);
+    let late_mismatch = format!("{}b", "a".repeat(31));
+    let early_mismatch = format!("b{}", "a".repeat(31));
+    vec![
+        Workload { name: "rare, once at the end", haystack: rare, needle: needle.to_string() },
+        Workload { name: "frequent: \"the \"", haystack: text.clone(), needle: "the ".to_string() },
+        Workload { name: "phrase: \"of the world\"", haystack: text, needle: "of the world".to_string() },
+        Workload { name: "a…ab in aaaa…", haystack: repetitive.clone(), needle: late_mismatch },
+        Workload { name: "ba…a in aaaa…", haystack: repetitive, needle: early_mismatch },
+    ]
+}
+
+fn demonstrate_byte_scan(text: &str, report: &mut DemoReport) {
+    output::heading("🔎 Scanning for One Byte");
+    outln!("Every searcher but the naive one leans on finding a byte fast. Searching");
+    outln!("{}B of text for a '#' that isn't there:\n", format_bytes(text.len() as f64));
+    let text = text.as_bytes();
+    let scans: [(&str, Scan); 3] =
+        [("byte loop", position_loop), ("u64 SWAR, 8 bytes", position_swar), ("libc memchr (SIMD)", memchr)];
+    let mut table = Table::new(["scan", "GB/s"]).left(0);
+    let mut rates = Vec::new();
+    for (name, scan) in scans {
+        let time = measure(RUNS, || scan(black_box(b'#'), black_box(text)));
+        detailln!("  {:<20} {}", name, time);
+        let rate = text.len() as f64 / time.median().as_secs_f64() / 1e9;
+        report.record(name, rate, "GB/s").param("search", "byte scan");
+        rates.push(rate);
+    }
+    for ((name, _), rate) in scans.iter().zip(output::rank(&rates, true, |rate| format!("{:.2}", rate))) {
+        table.row([name.to_string(), rate]);
+    }
+    outln!("{}", table);
+    outln!("The loop can't be vectorized: it might stop after any byte. SWAR tests eight");
+    outln!("lanes with a few integer operations; memchr compares 16 to 64 bytes per");
+    outln!("instruction and turns the results into a bit mask, {:.0}x the byte loop here.\n", rates[2] / rates[0]);
+}
+
+fn demonstrate_searches(workloads: &[Workload], report: &mut DemoReport) -> Vec<Vec<f64>> {
+    output::heading("🏁 Searching (GB/s)");
+    outln!("Counting every occurrence, overlapping ones included; the repetitive");
+    outln!("haystacks are {}B, the others {}B.\n", format_bytes(workloads[3].haystack.len() as f64),
+           format_bytes(workloads[0].haystack.len() as f64));
+    let mut header = vec!["workload", "found"];
+    header.extend(SEARCHES.iter().map(|(name, _)| *name));
+    let mut table = Table::new(header).left(0);
+    let mut all_rates = Vec::new();
+    for workload in workloads {
+        let found = count(two_way, &workload.haystack, &workload.needle);
+        let mut rates = Vec::new();
+        for (name, search) in SEARCHES {
+            let time = measure(RUNS, || count(*search, black_box(&workload.haystack), black_box(&workload.needle)));
+            detailln!("  {:<24} {:<14} {}", workload.name, name, time);
+            let rate = workload.haystack.len() as f64 / time.median().as_secs_f64() / 1e9;
+            report.record(*name, rate, "GB/s").param("search", workload.name);
+            rates.push(rate);
+        }
+        let mut row = vec![workload.name.to_string(), found.to_string()];
+        row.extend(output::rank(&rates, true, |rate| format!("{:.2}", rate)));
+        table.row(row);
+        all_rates.push(rates);
+    }
+    outln!("{}", table);
+    all_rates
+}
+
+fn demonstrate_why(workloads: &[Workload], rates: &[Vec<f64>]) {
+    output::heading("🧭 Why");
+    let text = workloads[1].haystack.as_bytes();
+    let per_mb = |byte: u8| text.iter().filter(|&&b| b == byte).count() as f64 / (text.len() as f64 / 1e6);
+    let stops = |needle: &str, use_rarest: bool| {
+        let needle = needle.as_bytes();
+        let anchor = if use_rarest { rarest(needle) } else { 0 };
+        (needle[anchor] as char, per_mb(needle[anchor]))
+    };
+    for (index, workload) in workloads.iter().take(3).enumerate() {
+        let (first, first_stops) = stops(&workload.needle, false);
+        let (rarest, rarest_stops) = stops(&workload.needle, true);
+        outln!("{}: memchr stops {:.0} times per MB on '{}' (first), {:.0} on '{}' (rarest)",
+               workload.name, first_stops, first.escape_default(), rarest_stops, rarest.escape_default());
+        outln!("    memchr rarest {:.2} GB/s vs naive {:.2} GB/s", rates[index][2], rates[index][0]);
+    }
+    outln!("\nmemchr wins while stops are rare: between them it runs at SIMD speed. Each");
+    outln!("stop costs a call, a verify and a restart, so a frequent anchor byte drags it");
+    outln!("down toward the naive loop's pace. The naive loop's inner comparison");
+    outln!("ends after a byte or two at an unpredictable point, so its branches mispredict");
+    outln!("wherever the first byte is common. Horspool reads only the window's last byte");
+    outln!("for most shifts and skips up to a needle length, so longer needles help it.\n");
+
+    let (late, early) = (&rates[3], &rates[4]);
+    outln!("On a…ab in aaaa…, every position matches 31 bytes before failing: naive runs");
+    outln!("at {:.3} GB/s against Two-Way's {:.2}. Horspool compares from the right, hits", late[0], late[4]);
+    outln!("the 'b' at once ({:.2} GB/s), but on ba…a it matches 31 bytes right to left", late[3]);
+    outln!("at every position and falls to {:.3} GB/s. Two-Way stays linear on both", early[3]);
+    outln!("({:.2} and {:.2} GB/s), which is why std uses it: no input can make", late[4], early[4]);
+    outln!("str::find quadratic. memchr on the rarest byte flies through both ({:.0} and", late[2]);
+    outln!("{:.0} GB/s) only because the 'b' it anchors on never occurs: a single 'b' every", early[2]);
+    outln!("few bytes would put it back at the mercy of its verifications.\n");
+}
+
+/// All searchers agree with a reference on tricky needles, and the
+/// worst cases are as lopsided as claimed
+fn verify() -> Verification {
+    let mut verification = Verification::new();
+    let mut rng = Config::default().rng();
+    let reference = |haystack: &str, needle: &str| {
+        haystack.as_bytes().windows(needle.len()).position(|window| window == needle.as_bytes())
+    };
+    let mut wrong = Vec::new();
+    let fixed = ["a", "ab", "aab", "abab", "aaab", "baaa", "abcabd", "zzzzzzzzzzzzzzzzzzzzzz"];
+    for round in 0..300 {
+        // A small alphabet makes partial matches, and periodic needles, common
+        let haystack: String = (0..rng.below(80)).map(|_| ['a', 'b'][rng.below(2)]).collect();
+        let needle: String = if round < fixed.len() { fixed[round].to_string() }
+                             else { (0..1 + rng.below(6)).map(|_| ['a', 'b', 'c'][rng.below(3)]).collect() };
+        for (name, search) in SEARCHES {
+            if search(&haystack, &needle) != reference(&haystack, &needle) {
+                wrong.push(format!("{} for {:?} in {:?}", name, needle, haystack));
+            }
+        }
+    }
+    wrong.truncate(5);
+    verification.check("all six searchers find the same first match as a brute-force reference on 300 inputs",
+                       wrong.is_empty(), || wrong.join("; "));
+    let text = english_text(1 << 16, &mut rng);
+    let counts: Vec<usize> = SEARCHES.iter().map(|(_, search)| count(*search, &text, "the ")).collect();
+    verification.check("all six count the same occurrences of \"the \"", counts.iter().all(|&c| c == counts[0] && c > 0),
+                       || format!("{:?}", counts));
+    let byte_text = vec![b'x'; 1 << 20];
+    let found = |scan: Scan| b"etaoz.\n#".map(|byte| (1..9).map(|skip| scan(byte, &text.as_bytes()[skip..])).collect::<Vec<_>>());
+    verification.equal("SWAR and memchr find each byte where the byte loop does, at every alignment",
+                       (found(position_swar), found(memchr)), (found(position_loop), found(position_loop)));
+    let loop_time = measure(RUNS, || position_loop(black_box(b'#'), black_box(&byte_text)));
+    let memchr_time = measure(RUNS, || memchr(black_box(b'#'), black_box(&byte_text)));
+    verification.faster("memchr scans faster than a byte loop", &memchr_time, &loop_time, 3.0);
+
+    let haystack = "a".repeat(1 << 16);
+    let needle = format!("{}b", "a".repeat(31));
+    let naive_time = measure(RUNS, || naive(black_box(&haystack), black_box(&needle)));
+    let two_way_time = measure(RUNS, || two_way(black_box(&haystack), black_box(&needle)));
+    verification.faster("Two-Way beats naive on a…ab in aaaa…", &two_way_time, &naive_time, 3.0);
+    let needle = format!("b{}", "a".repeat(31));
+    let horspool_time = measure(RUNS, || horspool(black_box(&haystack), black_box(&needle)));
+    let two_way_time = measure(RUNS, || two_way(black_box(&haystack), black_box(&needle)));
+    verification.faster("Two-Way beats Horspool on ba…a in aaaa…", &two_way_time, &horspool_time, 3.0);
+    verification
+}
+
+const QUIZ: &[Question] = &[
+    Question::new(
+        "Why does searching for \"the \" with memchr on 't' run slower than searching for a rare word?",
+        &[
+            "memchr is slow on common letters",
+            "It stops at every 't', and each stop costs a call and a verification, so the SIMD scan rarely gets going",
+            "The needle is shorter",
+        ],
+        1,
+        "That's why good searchers anchor on the needle's rarest byte, using a table of byte frequencies.",
+    ),
+    Question::new(
+        "Which input makes a naive left-to-right search quadratic?",
+        &[
+            "Random text with a needle that isn't there",
+            "A haystack of 'a's and a needle of 'a's ending in 'b'",
+            "A haystack of 'a's and a needle starting with 'b'",
+        ],
+        1,
+        "Every position matches almost the whole needle before failing; a needle starting with 'b' fails at once.",
+    ),
+    Question::new(
+        "What does Two-Way guarantee that Horspool doesn't?",
+        &[
+            "Linear time on every input, using constant extra space",
+            "Skipping more bytes on average",
+            "Finding the last match first",
+        ],
+        0,
+        "Horspool is fast on typical text but quadratic on some repetitive inputs; Two-Way never is.",
+    ),
+];
+
+#[derive(Demo)]
+#[demo(name = "substring-search-demo", description = "Naive, memchr, Horspool and Two-Way substring search on rare, frequent and worst-case needles",
+       quiz = QUIZ, verify = verify)]
+pub struct SubstringSearchDemo;
+
+impl SubstringSearchDemo {
+    fn demonstrate(&self, config: &Config) -> DemoReport {
+        output::title("🔎 Substring Search Demo");
+        outln!("Six ways to find a needle in a haystack, and the inputs each one is good at.\n");
+
+        let mut report = DemoReport::default();
+        let mut rng = config.rng();
+        let workloads = workloads(config.size.unwrap_or(TEXT_BYTES).max(1 << 16), &mut rng);
+        demonstrate_byte_scan(&workloads[1].haystack, &mut report);
+        let rates = demonstrate_searches(&workloads, &mut report);
+        demonstrate_why(&workloads, &rates);
+
+        output::takeaways();
+        outln!("• memchr scans 16 to 64 bytes per instruction; a byte loop can't be vectorized");
+        outln!("• Anchor memchr on the needle's rarest byte: every stop interrupts the scan");
+        outln!("• Horspool skips by the window's last byte; long needles skip further");
+        outln!("• Naive and Horspool have quadratic inputs; Two-Way is linear on all of them");
+        outln!("• str::find can't be made quadratic; the memchr crate's memmem adds a rare-byte");
+    outln!("  SIMD prefilter on top of Two-Way for hot searches");
+        report
+    }
+}
@@ -0,0 +1,310 @@
+//! Timing Attack Demo
+//!
+//! Checking a secret token with a comparison that stops at the first
+//! wrong byte answers a question it was never asked: how many leading
+//! bytes of the guess were right. This demo times an early-exit
+//! comparison against guesses sharing longer and longer prefixes with a
+//! secret, then recovers the secret's first characters one at a time from
+//! nothing but those timings, and repeats both against a constant-time
+//! comparison, where the signal, and the attack, disappear.
+//! Run with: cargo run --release --bin timing-attack-demo
+
+use std::hint::black_box;
+use std::time::Instant;
+
+use crate::bench::measure;
+use crate::demo::{Config, Demo, DemoReport};
+use crate::output::{Style, Table};
+use crate::quiz::Question;
+use crate::rng::Rng;
+use crate::verify::Verification;
+use crate::{outln, output};
+
+/// Length of the secret token, in hex characters
+const SECRET_LEN: usize = 32;
+/// Characters the attack recovers (`--size`)
+const RECOVER: usize = 6;
+/// Comparisons per timed sample: one comparison differs by a nanosecond
+/// or less, far below what one clock read can resolve
+const BATCH: usize = 1_000;
+/// Timed samples per candidate character, interleaved across candidates
+const ROUNDS: usize = 51;
+const HEX: &[u8; 16] = b"0123456789abcdef";
+/// Pads a guess past the byte being tested; never in the secret, so an
+/// early-exit comparison stops right after the candidate
+const FILLER: u8 = b'-';
+
+type Compare = fn(&[u8], &[u8]) -> bool;
+
+/// Byte by byte, returning at the first difference: the way equality is
+/// usually written by hand
+#[inline(never)]
+pub fn early_exit_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    for i in 0..a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+    }
+    true
+}
+
+/// Always reads every byte: the differences are OR-ed together and only
+/// the total is tested, so the time doesn't depend on where they are.
+/// Only the length, which isn't secret here, can end it early.
+#[inline(never)]
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut difference = 0u8;
+    for (x, y) in a.iter().zip(b) {
+        difference |= x ^ y;
+    }
+    // Opaque to the optimizer, so it can't reason backwards from the
+    // comparison and exit the loop once `difference` is nonzero
+    black_box(difference) == 0
+}
+
+fn secret_token(rng: &mut Rng) -> Vec<u8> {
+    (0..SECRET_LEN).map(|_| HEX[rng.below(HEX.len())]).collect()
+}
+
+/// Nanoseconds for `BATCH` comparisons of `guess` against `secret`
+fn time_batch(compare: Compare, secret: &[u8], guess: &[u8]) -> f64 {
+    let start = Instant::now();
+    for _ in 0..BATCH {
+        black_box(compare(black_box(secret), black_box(guess)));
+    }
+    start.elapsed().as_nanos() as f64
+}
+
+fn median(samples: &mut [f64]) -> f64 {
+    samples.sort_by(f64::total_cmp);
+    samples[samples.len() / 2]
+}
+
+/// A guess that matches the secret for exactly `prefix` characters
+fn guess_with_prefix(secret: &[u8], prefix: usize) -> Vec<u8> {
+    let mut guess = vec![FILLER; secret.len()];
+    guess[..prefix].copy_from_slice(&secret[..prefix]);
+    guess
+}
+
+/// Median nanoseconds per comparison for each matching prefix length in
+/// `prefixes`, the lengths interleaved so drift hits them all alike
+fn time_prefixes(compare: Compare, secret: &[u8], prefixes: &[usize]) -> Vec<f64> {
+    let guesses: Vec<Vec<u8>> = prefixes.iter().map(|&prefix| guess_with_prefix(secret, prefix)).collect();
+    let mut samples = vec![Vec::with_capacity(ROUNDS); prefixes.len()];
+    for _ in 0..ROUNDS {
+        for (guess, times) in guesses.iter().zip(&mut samples) {
+            times.push(time_batch(compare, secret, guess));
+        }
+    }
+    samples.iter_mut().map(|times| median(times) / BATCH as f64).collect()
+}
+
+/// One recovered position: the winning candidate and how clearly it won
+struct Step {
+    guessed: u8,
+    /// Median ns per comparison of the winner and of the runner-up
+    best: f64,
+    second: f64,
+}
+
+/// Recover the first `count` characters: at each position try all 16 hex
+/// digits after the prefix found so far, and keep the one whose
+/// comparisons take longest. The secret is only ever passed to `compare`.
+fn recover(compare: Compare, secret: &[u8], count: usize, rng: &mut Rng) -> Vec<Step> {
+    let mut known: Vec<u8> = Vec::new();
+    let mut steps = Vec::new();
+    for position in 0..count.min(secret.len()) {
+        let guesses: Vec<Vec<u8>> = HEX.iter().map(|&candidate| {
+            let mut guess = vec![FILLER; secret.len()];
+            guess[..position].copy_from_slice(&known);
+            guess[position] = candidate;
+            guess
+        }).collect();
+        let mut samples = vec![Vec::with_capacity(ROUNDS); HEX.len()];
+        let mut order: Vec<usize> = (0..HEX.len()).collect();
+        for _ in 0..ROUNDS {
+            // A fresh order each round, so no candidate always runs first
+            rng.shuffle(&mut order);
+            for &index in &order {
+                samples[index].push(time_batch(compare, secret, &guesses[index]));
+            }
+        }
+        let mut medians: Vec<(f64, u8)> = samples.iter_mut().zip(HEX)
+            .map(|(times, &candidate)| (median(times) / BATCH as f64, candidate)).collect();
+        medians.sort_by(|a, b| b.0.total_cmp(&a.0));
+        known.push(medians[0].1);
+        steps.push(Step { guessed: medians[0].1, best: medians[0].0, second: medians[1].0 });
+    }
+    steps
+}
+
+fn demonstrate_comparisons() {
+    output::heading("🔑 Two Ways to Compare a Token");
+    outln!("early_exit_eq walks both slices and returns false at the first mismatch.");
+    outln!("constant_time_eq XORs every pair of bytes, ORs the results together, and");
+    outln!("tests the total once at the end:");
+    outln!("    let mut difference = 0u8;");
+    outln!("    for (x, y) in a.iter().zip(b) {{ difference |= x ^ y; }}");
+    outln!("    black_box(difference) == 0");
+    outln!("Both give the same answers; they differ in how long they take to give them.\n");
+}
+
+fn demonstrate_prefix_timing(secret: &[u8], report: &mut DemoReport) {
+    output::heading("⏱️  Time per Comparison vs Correct Prefix Length");
+    let prefixes: Vec<usize> = (0..=SECRET_LEN).step_by(SECRET_LEN / 8).collect();
+    let early = time_prefixes(early_exit_eq, secret, &prefixes);
+    let constant = time_prefixes(constant_time_eq, secret, &prefixes);
+    let mut table = Table::new(["matching chars", "early exit (ns)", "constant time (ns)"]);
+    for (index, prefix) in prefixes.iter().enumerate() {
+        table.row([prefix.to_string(), format!("{:.2}", early[index]), format!("{:.2}", constant[index])]);
+        report.record("early exit", early[index], "ns/comparison").param("prefix", *prefix);
+        report.record("constant time", constant[index], "ns/comparison").param("prefix", *prefix);
+    }
+    outln!("{}", table);
+    let slope = (early[early.len() - 1] - early[0]) / SECRET_LEN as f64;
+    outln!("The early-exit time climbs about {:.2} ns per correct character: each one is", slope);
+    outln!("a loop iteration more. The constant-time column stays flat (within {:.2} ns)",
+           constant.iter().cloned().fold(f64::MIN, f64::max) - constant.iter().cloned().fold(f64::MAX, f64::min));
+    outln!("because it always does all {} iterations. Over a network the difference is", SECRET_LEN);
+    outln!("buried in microseconds of jitter, but jitter averages out and a loop doesn't:");
+    outln!("remote timing attacks have recovered keys and MACs this way.\n");
+}
+
+fn demonstrate_attack(secret: &[u8], count: usize, rng: &mut Rng, report: &mut DemoReport) {
+    output::heading(&format!("🕵️  Recovering the First {} Characters from Timings", count));
+    outln!("Per position: 16 candidates × {} rounds × {} comparisons, keeping the", ROUNDS, BATCH);
+    outln!("candidate with the slowest median. The attack sees only the clock.\n");
+    let target = &secret[..count.min(secret.len())];
+    for (name, compare) in [("early exit", early_exit_eq as Compare), ("constant time", constant_time_eq as Compare)] {
+        let start = Instant::now();
+        let steps = recover(compare, secret, count, rng);
+        let elapsed = start.elapsed();
+        let recovered: String = steps.iter().map(|step| step.guessed as char).collect();
+        let correct = steps.iter().zip(target).take_while(|(step, actual)| step.guessed == **actual).count();
+        let mut table = Table::new(["position", "guess", "slowest (ns)", "runner-up (ns)", "margin"]);
+        for (position, step) in steps.iter().enumerate() {
+            let guess = if step.guessed == target[position] { Style::Faster.paint(step.guessed as char) }
+                        else { Style::Slower.paint(step.guessed as char) };
+            table.row([position.to_string(), guess.to_string(), format!("{:.2}", step.best), format!("{:.2}", step.second),
+                       format!("{:+.2}", step.best - step.second)]);
+        }
+        outln!("Against the {} comparison ({:.2?}):", name, elapsed);
+        outln!("{}", table);
+        outln!("  recovered {}  secret {}  → {} of {} correct in a row\n", recovered,
+               String::from_utf8_lossy(target), correct, target.len());
+        report.record(format!("{} characters recovered", name), correct as f64, "characters").param("of", target.len());
+    }
+    outln!("Against the early-exit comparison the right digit stands out by about one");
+    outln!("loop iteration, and each position costs 16 guesses instead of 16^n for");
+    outln!("brute force: {} characters in {} tries rather than {}. Against the", count, 16 * count, 16u64.pow(count as u32));
+    outln!("constant-time one the slowest candidate is whichever one noise favoured, the");
+    outln!("margins shrink to noise, and after the first miss every later position is a");
+    outln!("guess on a wrong prefix anyway.\n");
+}
+
+fn demonstrate_rules() {
+    output::heading("📏 Writing Constant-Time Code");
+    outln!("• No branches on secret data: accumulate with |, &, ^ and test once at the end");
+    outln!("• No memory indexed by secret data: cache timing leaks the index (AES T-tables)");
+    outln!("• Don't use == on secrets: slices compare with memcmp/bcmp, which promise");
+    outln!("  nothing about timing and exit early, a chunk at a time, on longer inputs");
+    outln!("• Keep the optimizer honest: it may reintroduce a branch it can prove safe;");
+    outln!("  black_box here, or the `subtle` crate's ConstantTimeEq, which hides values");
+    outln!("  behind an optimization barrier");
+    outln!("• Length still leaks: compare fixed-size values, such as MACs of the tokens");
+    outln!("• Division and some multiplications take data-dependent time on some CPUs\n");
+}
+
+/// Both comparisons agree, and only the early-exit one slows down as the
+/// correct prefix grows
+fn verify() -> Verification {
+    let mut verification = Verification::new();
+    let mut rng = Config::default().rng();
+    let secret = secret_token(&mut rng);
+    let mut guesses: Vec<Vec<u8>> = (0..=SECRET_LEN).map(|prefix| guess_with_prefix(&secret, prefix)).collect();
+    guesses.push(secret[..SECRET_LEN - 1].to_vec());
+    let disagreements = guesses.iter()
+        .filter(|guess| early_exit_eq(&secret, guess) != constant_time_eq(&secret, guess)
+            || early_exit_eq(&secret, guess) != (secret == **guess))
+        .count();
+    verification.equal("both comparisons agree with == on every guess", disagreements, 0);
+
+    let mismatch = guess_with_prefix(&secret, 0);
+    let early_short = measure(15, || time_batch(early_exit_eq, &secret, &mismatch));
+    let early_long = measure(15, || time_batch(early_exit_eq, &secret, &secret));
+    verification.faster("early exit on the first byte beats matching all 32", &early_short, &early_long, 1.5);
+    let times = time_prefixes(constant_time_eq, &secret, &[0, SECRET_LEN]);
+    verification.check("constant time takes as long on the first byte as on all 32 (within 25%)",
+                       times[0] > times[1] / 1.25 && times[0] < times[1] * 1.25,
+                       || format!("{:.2} vs {:.2} ns", times[0], times[1]));
+    verification
+}
+
+const QUIZ: &[Question] = &[
+    Question::new(
+        "What does an early-exit comparison leak through its running time?",
+        &[
+            "Nothing, the difference is a few nanoseconds",
+            "How many leading bytes of the guess are correct, which lets an attacker solve one byte at a time",
+            "The secret's length only",
+        ],
+        1,
+        "A few nanoseconds is plenty: repeating the measurement and taking medians pulls the signal out of the noise.",
+    ),
+    Question::new(
+        "Why does timing turn a 16^32 brute-force search into about 16 × 32 guesses?",
+        &[
+            "Each position can be confirmed separately, so the positions are searched one after another instead of all together",
+            "Timing measurements are faster than comparisons",
+            "Hex secrets are weak",
+        ],
+        0,
+        "The leak gives per-position feedback; constant-time code only ever says 'all right' or 'not all right'.",
+    ),
+    Question::new(
+        "Which loop body keeps a byte comparison constant-time?",
+        &[
+            "if a[i] != b[i] { return false; }",
+            "difference |= a[i] ^ b[i]; with a single test of difference after the loop",
+            "if a[i] != b[i] { wrong = true; }",
+        ],
+        1,
+        "The third still branches on secret data (and the optimizer may turn it back into an early exit).",
+    ),
+];
+
+#[derive(Demo)]
+#[demo(name = "timing-attack-demo", description = "Recover a secret from an early-exit comparison's timing; constant-time code",
+       quiz = QUIZ, verify = verify)]
+pub struct TimingAttackDemo;
+
+impl TimingAttackDemo {
+    fn demonstrate(&self, config: &Config) -> DemoReport {
+        output::title("🕵️  Timing Attack Demo");
+        outln!("How long a comparison takes can say how much of a guess was right.\n");
+
+        let mut report = DemoReport::default();
+        let mut rng = config.rng();
+        let secret = secret_token(&mut rng);
+        outln!("Secret token: {} hex characters (the demo prints it only to check the result)\n", SECRET_LEN);
+        demonstrate_comparisons();
+        demonstrate_prefix_timing(&secret, &mut report);
+        demonstrate_attack(&secret, config.size.unwrap_or(RECOVER).clamp(1, SECRET_LEN), &mut rng, &mut report);
+        demonstrate_rules();
+
+        output::takeaways();
+        outln!("• An early-exit comparison's time reveals how long the matching prefix is");
+        outln!("• Repeating and taking medians extracts nanosecond differences from noisy clocks");
+        outln!("• Per-position feedback turns an exponential search into a linear one");
+        outln!("• Compare secrets in constant time: combine all differences, branch once at the end");
+        outln!("• Use a vetted implementation (subtle, ring) and compare fixed-length values");
+        report
+    }
+}
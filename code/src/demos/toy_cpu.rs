@@ -0,0 +1,229 @@
+//! Toy CPU Emulator Demo
+//!
+//! Runs small programs on an emulated 8-register CPU, tracing every
+//! fetch-decode-execute step with register and flag state.
+//! Run with: cargo run --bin toy-cpu
+
+use crate::demo::{Config, Demo, DemoReport};
+use crate::quiz::Question;
+use crate::toy_cpu::{addr_of, assemble, programs, Cpu, CpuError, Instruction, DATA_BASE, MEMORY_SIZE, NUM_REGISTERS};
+use crate::verify::Verification;
+use crate::{outln, output};
+
+fn demonstrate_instruction_encoding() {
+    output::heading("🧩 Instruction Encoding");
+
+    outln!("Every instruction is 4 bytes: [opcode, operand, operand, operand]");
+    outln!("The CPU only ever sees these bytes - mnemonics are for humans.\n");
+
+    let samples = [
+        Instruction::LoadImm { rd: 1, imm: 500 },
+        Instruction::Add { rd: 0, ra: 0, rb: 1 },
+        Instruction::AddImm { rd: 1, imm: -1 },
+        Instruction::Load { rd: 2, ra: 3, offset: 8 },
+        Instruction::Jnz { addr: 0x00c },
+    ];
+
+    for instruction in &samples {
+        let bytes = instruction.encode();
+        let decoded = Instruction::decode(bytes).expect("round trip");
+        outln!(
+            "  {:<20} → {:02x} {:02x} {:02x} {:02x} → decodes back to: {}",
+            instruction.to_string(), bytes[0], bytes[1], bytes[2], bytes[3], decoded
+        );
+    }
+    outln!();
+}
+
+fn demonstrate_fetch_decode_execute() {
+    output::heading("🔁 Fetch → Decode → Execute (sum of 1..=4)");
+
+    let program = programs::sum(4);
+    outln!("Program listing:");
+    for (index, instruction) in program.iter().enumerate() {
+        outln!("  0x{:03x}: {}", index * 4, instruction);
+    }
+    outln!();
+
+    let mut cpu = Cpu::new();
+    cpu.load_program(&program).expect("program fits in memory");
+
+    outln!("Single-step trace (state AFTER each instruction):");
+    outln!("  addr  instruction          state");
+    while !cpu.halted {
+        let pc = cpu.pc;
+        match cpu.step() {
+            Ok(instruction) => outln!("  0x{:03x} {:<20} {}", pc, instruction.to_string(), cpu.state_line()),
+            Err(e) => {
+                outln!("  CPU fault: {}", e);
+                return;
+            }
+        }
+    }
+
+    outln!();
+    outln!("Result in R0: {} (Rust says {})", cpu.registers[0], (1..=4u32).sum::<u32>());
+    outln!("Instructions retired: {}, cycles: {}", cpu.instructions, cpu.cycles);
+    outln!("Note how CMP sets the Z flag and JZ reads it - flags are how the ALU talks to branches\n");
+}
+
+fn demonstrate_fibonacci() {
+    output::heading("🐇 Fibonacci in Memory");
+
+    let count = 12;
+    let mut cpu = Cpu::new();
+    cpu.load_program(&programs::fibonacci(count)).expect("program fits in memory");
+
+    outln!("First 8 steps of the trace:");
+    for _ in 0..8 {
+        let pc = cpu.pc;
+        let instruction = cpu.step().expect("valid program");
+        outln!("  0x{:03x} {:<20} {}", pc, instruction.to_string(), cpu.state_line());
+    }
+    outln!("  ... running to completion ...");
+
+    match cpu.run(10_000) {
+        Ok(()) => {}
+        Err(e) => {
+            outln!("  CPU fault: {}", e);
+            return;
+        }
+    }
+
+    outln!();
+    outln!("Memory dump from 0x{:03x} (little-endian 32-bit words):", DATA_BASE);
+    for i in 0..count as u32 {
+        let addr = DATA_BASE as u32 + i * 4;
+        let bytes = &cpu.memory[addr as usize..addr as usize + 4];
+        outln!(
+            "  0x{:03x}: {:02x} {:02x} {:02x} {:02x} = {}",
+            addr, bytes[0], bytes[1], bytes[2], bytes[3],
+            cpu.read_word(addr).expect("in bounds")
+        );
+    }
+    outln!();
+    outln!("R0 after reload from memory: {}", cpu.registers[0]);
+    outln!("Instructions retired: {}, cycles: {}", cpu.instructions, cpu.cycles);
+    outln!("Stores cost 3 cycles here vs 1 for ADD - memory is slower than registers\n");
+}
+
+/// `programs::sum` with the accumulator kept in memory instead of R0, like
+/// a variable that didn't get a register
+fn spilled_sum(n: u16) -> Vec<Instruction> {
+    vec![
+        Instruction::LoadImm { rd: 1, imm: n },
+        Instruction::LoadImm { rd: 2, imm: 0 },
+        Instruction::LoadImm { rd: 3, imm: DATA_BASE },
+        Instruction::Store { rs: 2, ra: 3, offset: 0 },
+        Instruction::Cmp { ra: 1, rb: 2 },
+        Instruction::Jz { addr: addr_of(11) },
+        Instruction::Load { rd: 0, ra: 3, offset: 0 },
+        Instruction::Add { rd: 0, ra: 0, rb: 1 },
+        Instruction::Store { rs: 0, ra: 3, offset: 0 },
+        Instruction::AddImm { rd: 1, imm: -1 },
+        Instruction::Jmp { addr: addr_of(4) },
+        Instruction::Load { rd: 0, ra: 3, offset: 0 },
+        Instruction::Halt,
+    ]
+}
+
+/// Run `program` to its HALT and return the final machine
+fn run_program(program: &[Instruction]) -> Result<Cpu, CpuError> {
+    let mut cpu = Cpu::new();
+    cpu.load_program(program)?;
+    cpu.run(100_000)?;
+    Ok(cpu)
+}
+
+fn demonstrate_cost_model() {
+    output::heading("⏱️  Registers vs Memory Cost");
+
+    let n = 100;
+
+    // Register-only version: the accumulator never leaves R0
+    let register_cpu = run_program(&programs::sum(n)).expect("sum halts");
+
+    let spilled = spilled_sum(n);
+    let spilled_cpu = run_program(&spilled).expect("spilled sum halts");
+
+    outln!("Sum of 1..={} with accumulator in a register: {} cycles (R0 = {})",
+             n, register_cpu.cycles, register_cpu.registers[0]);
+    outln!("Sum of 1..={} with accumulator spilled to memory: {} cycles (R0 = {})",
+             n, spilled_cpu.cycles, spilled_cpu.registers[0]);
+    outln!("Same answer, ~{:.1}x the cycles - this is why compilers fight to keep values in registers",
+             spilled_cpu.cycles as f64 / register_cpu.cycles as f64);
+    outln!("Program image size: {} bytes vs {} bytes",
+             assemble(&programs::sum(n)).len(), assemble(&spilled).len());
+    outln!();
+}
+
+/// Instructions survive encoding, the sample programs compute what Rust
+/// does, and spilling the accumulator costs cycles but not correctness
+fn verify() -> Verification {
+    let mut verification = Verification::new();
+    let program = [programs::sum(100), programs::fibonacci(12), spilled_sum(100)].concat();
+    let decoded: Result<Vec<Instruction>, CpuError> =
+        assemble(&program).chunks_exact(4).map(|bytes| Instruction::decode([bytes[0], bytes[1], bytes[2], bytes[3]])).collect();
+    verification.equal("every sample instruction decodes back to itself", decoded, Ok(program));
+
+    let sum = run_program(&programs::sum(100));
+    let spilled = run_program(&spilled_sum(100));
+    let stored = run_program(&programs::fibonacci(12))
+        .and_then(|cpu| (0..12).map(|i| cpu.read_word(DATA_BASE as u32 + 4 * i)).collect::<Result<Vec<u32>, _>>());
+    let cycles = |cpu: &Result<Cpu, CpuError>| cpu.as_ref().map_or(0, |cpu| cpu.cycles);
+    verification
+        .equal("sum(100) leaves (1..=100).sum() in R0", sum.as_ref().map(|cpu| cpu.registers[0]), Ok((1..=100).sum()))
+        .equal("fibonacci(12) stores the first 12 Fibonacci numbers", stored, Ok(vec![0, 1, 1, 2, 3, 5, 8, 13, 21, 34, 55, 89]))
+        .equal("the spilled sum gets the same answer", spilled.as_ref().map(|cpu| cpu.registers[0]), Ok(5050))
+        .check("the spilled sum takes more cycles", cycles(&spilled) > cycles(&sum),
+               || format!("{} vs {} cycles", cycles(&spilled), cycles(&sum)));
+    verification
+}
+
+const QUIZ: &[Question] = &[
+    Question::new(
+        "What does the toy CPU do on every step of its main loop?",
+        &[
+            "Fetch the instruction at PC, decode it, execute it",
+            "Compile one line of source code",
+            "Run every instruction in the program at once",
+        ],
+        0,
+        "Real CPUs pipeline and reorder these stages, but every one of them still implements this loop.",
+    ),
+    Question::new(
+        "How does JZ decide whether to jump?",
+        &[
+            "It re-evaluates the comparison itself",
+            "It reads the zero flag that the previous CMP or arithmetic instruction set",
+            "The assembler resolves it ahead of time",
+        ],
+        1,
+        "Flags are how the ALU's results reach the branch logic: CMP subtracts, sets flags, and throws the difference away.",
+    ),
+];
+
+#[derive(Demo)]
+#[demo(name = "toy-cpu", description = "An 8-register CPU emulator tracing fetch-decode-execute",
+       quiz = QUIZ, verify = verify)]
+pub struct ToyCpu;
+
+impl ToyCpu {
+    fn demonstrate(&self, _config: &Config) -> DemoReport {
+        output::title("🖥️  Toy CPU Emulator");
+        outln!("An emulated CPU with {} registers and {} bytes of memory.\n", NUM_REGISTERS, MEMORY_SIZE);
+
+        demonstrate_instruction_encoding();
+        demonstrate_fetch_decode_execute();
+        demonstrate_fibonacci();
+        demonstrate_cost_model();
+
+        output::takeaways();
+        outln!("• A CPU is a loop: fetch bytes at PC, decode them, execute, repeat");
+        outln!("• Instructions are just bytes in memory, next to the data they use");
+        outln!("• Flags connect the ALU to branches (CMP then JZ)");
+        outln!("• Loads and stores cost more than register arithmetic");
+        outln!("• A compiler's job is turning `(1..=n).sum()` into a loop like the one above");
+        DemoReport::default()
+    }
+}
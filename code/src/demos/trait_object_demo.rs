@@ -0,0 +1,286 @@
+//! Trait Object Internals Demo
+//!
+//! What a `&dyn Trait` actually is: a fat pointer made of a data pointer and
+//! a vtable pointer. Takes one apart, reads the vtable, and measures what
+//! dynamic dispatch costs compared to static dispatch.
+//! Run with: cargo run --release --bin trait-object-demo
+
+use std::hint::black_box;
+use std::mem;
+
+use crate::bench::measure;
+use crate::demo::{Config, Demo, DemoReport};
+use crate::output::Table;
+use crate::quiz::Question;
+use crate::{out, outln, output};
+
+/// Shapes per dispatch benchmark, unless the config sets `size`
+const SHAPES: usize = 1_000_000;
+
+trait Shape {
+    fn area(&self) -> f64;
+    fn sides(&self) -> u32;
+}
+
+struct Circle {
+    radius: f64,
+}
+
+struct Square {
+    side: f64,
+}
+
+/// Owns a heap allocation, so its vtable needs real drop glue
+struct Polygon {
+    name: String,
+    sides: u32,
+    side: f64,
+}
+
+impl Shape for Circle {
+    fn area(&self) -> f64 {
+        std::f64::consts::PI * self.radius * self.radius
+    }
+    fn sides(&self) -> u32 {
+        0
+    }
+}
+
+impl Shape for Square {
+    fn area(&self) -> f64 {
+        self.side * self.side
+    }
+    fn sides(&self) -> u32 {
+        4
+    }
+}
+
+impl Shape for Polygon {
+    fn area(&self) -> f64 {
+        let n = self.sides as f64;
+        n * self.side * self.side / (4.0 * (std::f64::consts::PI / n).tan())
+    }
+    fn sides(&self) -> u32 {
+        self.sides
+    }
+}
+
+/// The two halves of a `&dyn Shape`
+struct FatPointer {
+    data: *const (),
+    vtable: *const usize,
+}
+
+/// The vtable layout rustc currently emits: drop glue, size, align, then
+/// the trait's methods in declaration order
+struct VtableView {
+    drop_in_place: usize,
+    size: usize,
+    align: usize,
+    methods: [usize; 2],
+}
+
+fn decompose(shape: &dyn Shape) -> FatPointer {
+    // ⚠️ UNSAFE, FOR ILLUSTRATION ONLY: the fat pointer layout is not a
+    // stable guarantee. It has been (data, vtable) for years, and the checks
+    // in `read_vtable` catch it if that ever changes.
+    let [data, vtable]: [usize; 2] = unsafe { mem::transmute::<&dyn Shape, [usize; 2]>(shape) };
+    FatPointer { data: data as *const (), vtable: vtable as *const usize }
+}
+
+fn read_vtable(shape: &dyn Shape) -> Option<VtableView> {
+    let fat = decompose(shape);
+    // ⚠️ UNSAFE: reads rustc's (unstable) vtable layout directly
+    let view = unsafe {
+        VtableView {
+            drop_in_place: *fat.vtable,
+            size: *fat.vtable.add(1),
+            align: *fat.vtable.add(2),
+            methods: [*fat.vtable.add(3), *fat.vtable.add(4)],
+        }
+    };
+    // size_of_val/align_of_val read the same vtable through supported APIs:
+    // if they disagree, our layout assumption is wrong and we stop here
+    let matches = view.size == mem::size_of_val(shape) && view.align == mem::align_of_val(shape);
+    matches.then_some(view)
+}
+
+fn demonstrate_fat_pointers() {
+    output::heading("📏 Thin vs Fat Pointers");
+    let mut table = Table::new(["type", "bytes"]);
+    for (name, size) in [
+        ("&Circle", mem::size_of::<&Circle>()),
+        ("&[f64]", mem::size_of::<&[f64]>()),
+        ("&dyn Shape", mem::size_of::<&dyn Shape>()),
+        ("Box<dyn Shape>", mem::size_of::<Box<dyn Shape>>()),
+    ] {
+        table.row([name.to_string(), size.to_string()]);
+    }
+    out!("{}", table);
+    outln!("A reference to a concrete type is one address. A slice reference adds a");
+    outln!("length; a trait object reference adds a pointer to the type's vtable.\n");
+}
+
+fn demonstrate_decomposition() {
+    output::heading("🔓 Taking a &dyn Shape Apart");
+
+    let circle = Circle { radius: 1.0 };
+    let other_circle = Circle { radius: 2.0 };
+    let square = Square { side: 3.0 };
+    let polygon = Polygon { name: "hexagon".to_string(), sides: 6, side: 1.0 };
+    let shapes: [(&str, &dyn Shape); 4] =
+        [("circle", &circle), ("other circle", &other_circle), ("square", &square), (polygon.name.as_str(), &polygon)];
+
+    let mut table = Table::new(["value", "data ptr", "vtable ptr"]);
+    for (name, shape) in shapes {
+        let fat = decompose(shape);
+        table.row([name.to_string(), format!("{:p}", fat.data), format!("{:p}", fat.vtable)]);
+    }
+    out!("{}", table);
+    outln!("Each value has its own data pointer, but both circles share one vtable:");
+    outln!("there is one vtable per (type, trait) pair, emitted in read-only data.\n");
+
+    let mut table = Table::new(["vtable of", "size", "align", "drop_in_place", "area()", "sides()"]);
+    for (name, shape) in [("Circle", shapes[0].1), ("Square", shapes[2].1), ("Polygon", shapes[3].1)] {
+        let Some(vtable) = read_vtable(shape) else {
+            outln!("  ⚠️  vtable layout differs from what this demo expects; skipping");
+            return;
+        };
+        let drop = if vtable.drop_in_place == 0 { "none".to_string() } else { format!("{:#x}", vtable.drop_in_place) };
+        table.row([name.to_string(), vtable.size.to_string(), vtable.align.to_string(), drop,
+                   format!("{:#x}", vtable.methods[0]), format!("{:#x}", vtable.methods[1])]);
+    }
+    out!("{}", table);
+    outln!("size and align let Box<dyn Shape> free memory without knowing the type;");
+    outln!("drop_in_place is the type's destructor (\"none\" when there is nothing to");
+    outln!("drop, like plain f64 fields). Polygon owns a String, so it has drop glue.\n");
+
+    // Call area() through the raw vtable entry, exactly like the compiler does
+    let fat = decompose(&square);
+    if let Some(vtable) = read_vtable(&square) {
+        // ⚠️ UNSAFE: turns a raw vtable slot back into a function pointer
+        let area: fn(*const ()) -> f64 = unsafe { mem::transmute(vtable.methods[0]) };
+        let sides: fn(*const ()) -> u32 = unsafe { mem::transmute(vtable.methods[1]) };
+        outln!("Calling the vtable slots by hand on the square:");
+        outln!("  slot 3: {} (square.area() = {})", area(fat.data), square.area());
+        outln!("  slot 4: {} (square.sides() = {})\n", sides(fat.data), square.sides());
+    }
+}
+
+fn total_area_static<S: Shape>(shapes: &[S]) -> f64 {
+    shapes.iter().map(|s| s.area()).sum()
+}
+
+fn total_area_dynamic(shapes: &[Box<dyn Shape>]) -> f64 {
+    shapes.iter().map(|s| s.area()).sum()
+}
+
+fn total_area_enum(shapes: &[ShapeEnum]) -> f64 {
+    shapes.iter().map(|s| s.area()).sum()
+}
+
+/// The closed-world alternative to a trait object: dispatch by `match`
+enum ShapeEnum {
+    Circle(Circle),
+    Square(Square),
+}
+
+impl ShapeEnum {
+    fn area(&self) -> f64 {
+        match self {
+            ShapeEnum::Circle(c) => c.area(),
+            ShapeEnum::Square(s) => s.area(),
+        }
+    }
+}
+
+fn demonstrate_dispatch_cost(shapes: usize, config: &Config, report: &mut DemoReport) {
+    let title = format!("⏱️  Static vs Dynamic Dispatch ({} shapes)", shapes);
+    output::heading(&title);
+
+    let circles: Vec<Circle> = (0..shapes).map(|i| Circle { radius: (i % 100) as f64 }).collect();
+    let boxed_circles: Vec<Box<dyn Shape>> =
+        (0..shapes).map(|i| Box::new(Circle { radius: (i % 100) as f64 }) as Box<dyn Shape>).collect();
+    // Alternate types unpredictably so the indirect call target keeps changing
+    let mut rng = config.rng();
+    let circle: Vec<bool> = (0..shapes).map(|_| rng.below(2) == 0).collect();
+    let pick = |i: usize| circle[i];
+    let boxed_mixed: Vec<Box<dyn Shape>> = (0..shapes)
+        .map(|i| -> Box<dyn Shape> {
+            if pick(i) { Box::new(Circle { radius: (i % 100) as f64 }) } else { Box::new(Square { side: (i % 100) as f64 }) }
+        })
+        .collect();
+    let enum_mixed: Vec<ShapeEnum> = (0..shapes)
+        .map(|i| {
+            if pick(i) {
+                ShapeEnum::Circle(Circle { radius: (i % 100) as f64 })
+            } else {
+                ShapeEnum::Square(Square { side: (i % 100) as f64 })
+            }
+        })
+        .collect();
+
+    let rows = [
+        ("generic &[Circle] (static)", measure(5, || total_area_static(black_box(&circles))).median()),
+        ("Box<dyn Shape>, all circles", measure(5, || total_area_dynamic(black_box(&boxed_circles))).median()),
+        ("Box<dyn Shape>, mixed types", measure(5, || total_area_dynamic(black_box(&boxed_mixed))).median()),
+        ("enum + match, mixed types", measure(5, || total_area_enum(black_box(&enum_mixed))).median()),
+    ];
+    let baseline = rows[0].1;
+    let times = output::rank(&rows.map(|(_, time)| time), false, |time| format!("{:?}", time));
+    let mut table = Table::new(["dispatch", "median", "ns/call", "relative"]);
+    for ((name, time), time_cell) in rows.into_iter().zip(times) {
+        table.row([name.to_string(), time_cell, format!("{:.2}", time.as_nanos() as f64 / shapes as f64),
+                   format!("{:.1}x", time.as_secs_f64() / baseline.as_secs_f64())]);
+        report.record_time(name, time).param("shapes", shapes);
+    }
+    out!("{}", table);
+    outln!("The static version is monomorphized: area() is inlined and the loop");
+    outln!("vectorized. A dyn call is an indirect call the compiler can't inline, plus");
+    outln!("a pointer chase to each boxed value. Mixing types can add indirect-branch");
+    outln!("mispredictions on top, if the predictor can't learn the pattern. An enum");
+    outln!("keeps values inline and dispatches with a branch the compiler can see through.\n");
+}
+
+const QUIZ: &[Question] = &[
+    Question::new(
+        "How large is a &dyn Trait on a 64-bit target?",
+        &["8 bytes", "16 bytes: a data pointer and a vtable pointer", "The size of the concrete type"],
+        1,
+        "The vtable pointer is what makes the reference fat; it's how the call finds the right method.",
+    ),
+    Question::new(
+        "Why is dynamic dispatch slower in a hot loop?",
+        &[
+            "Each call goes through the vtable as an indirect call the compiler can't inline",
+            "Each call allocates",
+            "Each call takes a lock",
+        ],
+        0,
+        "Without inlining, LLVM can't optimize across the call, e.g. vectorize the loop around it.",
+    ),
+];
+
+#[derive(Demo)]
+#[demo(name = "trait-object-demo", description = "Fat pointers, vtables and the cost of dynamic dispatch",
+       quiz = QUIZ)]
+pub struct TraitObjectDemo;
+
+impl TraitObjectDemo {
+    fn demonstrate(&self, config: &Config) -> DemoReport {
+        output::title("🎭 Trait Object Internals");
+        outln!("Fat pointers, vtables, and the cost of dynamic dispatch.\n");
+
+        demonstrate_fat_pointers();
+        demonstrate_decomposition();
+        let mut report = DemoReport::default();
+        demonstrate_dispatch_cost(config.size.unwrap_or(SHAPES), config, &mut report);
+
+        output::takeaways();
+        outln!("• &dyn Trait is two words: a data pointer and a vtable pointer");
+        outln!("• Each (type, trait) pair has one vtable: drop glue, size, align, then methods");
+        outln!("• Dynamic dispatch costs an indirect call and blocks inlining");
+        outln!("• Prefer generics (or an enum) in hot loops, dyn for flexibility elsewhere");
+        report
+    }
+}
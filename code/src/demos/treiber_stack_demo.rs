@@ -0,0 +1,325 @@
+//! Treiber Lock-Free Stack Demo
+//!
+//! Shows the ABA problem that bites naive lock-free stacks, the epoch-based
+//! reclamation fix, and how the result compares with `Mutex<Vec<T>>`.
+//! Run with: cargo run --release --bin treiber-stack-demo
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use crate::bench::{format_rate, measure};
+use crate::demo::{Config, Demo, DemoReport};
+use crate::output::Table;
+use crate::quiz::Question;
+use crate::sync::TreiberStack;
+use crate::verify::Verification;
+use crate::{outln, output};
+
+/// A stack over a fixed pool of node slots. Popped slots go straight back on
+/// a free list and are handed out again by the next push - just like `free`
+/// followed by `malloc` of the same size usually returns the same address.
+/// Slot indices play the role of pointers, so the demo stays memory safe.
+struct RecyclingStack {
+    values: Vec<u32>,
+    next: Vec<Option<usize>>,
+    head: Option<usize>,
+    free: VecDeque<usize>,
+}
+
+impl RecyclingStack {
+    fn new(slots: usize) -> Self {
+        RecyclingStack {
+            values: vec![0; slots],
+            next: vec![None; slots],
+            head: None,
+            free: (0..slots).collect(),
+        }
+    }
+
+    fn push(&mut self, value: u32) -> usize {
+        let slot = self.free.pop_front().expect("pool exhausted");
+        self.values[slot] = value;
+        self.next[slot] = self.head;
+        self.head = Some(slot);
+        slot
+    }
+
+    fn pop(&mut self) -> Option<u32> {
+        let slot = self.head?;
+        self.head = self.next[slot];
+        self.free.push_back(slot); // "freed" immediately - no reclamation scheme
+        Some(self.values[slot])
+    }
+
+    /// The CAS a real Treiber stack would do: succeeds if head is still `expected`
+    fn compare_and_swap_head(&mut self, expected: Option<usize>, new: Option<usize>) -> bool {
+        if self.head == expected {
+            self.head = new;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        let mut current = self.head;
+        while let Some(slot) = current {
+            let freed = if self.free.contains(&slot) { " ⚠️ FREED" } else { "" };
+            parts.push(format!("[slot {}: {}{}]", slot, self.values[slot], freed));
+            if parts.len() > 6 {
+                parts.push("...".to_string());
+                break;
+            }
+            current = self.next[slot];
+        }
+        if parts.is_empty() { "(empty)".to_string() } else { parts.join(" → ") }
+    }
+}
+
+fn demonstrate_treiber_basics() {
+    output::heading("📚 The Treiber Stack");
+    outln!("push: node.next = head; CAS(head, node.next → node)  (retry on failure)");
+    outln!("pop:  h = head; CAS(head, h → h.next); return h.value (retry on failure)\n");
+
+    let stack = TreiberStack::new();
+    for value in 1..=5 {
+        stack.push(value);
+    }
+    let popped: Vec<i32> = std::iter::from_fn(|| stack.pop()).collect();
+    outln!("Pushed 1..=5, popped: {:?} (LIFO)", popped);
+    outln!("No locks: a thread that loses the CAS race just retries with the new head\n");
+}
+
+fn demonstrate_aba_problem() {
+    output::heading("🔁 The ABA Problem (naive reclamation)");
+
+    // Exactly three slots, so the next allocation must recycle a freed one
+    let mut stack = RecyclingStack::new(3);
+    stack.push(30);
+    stack.push(20);
+    stack.push(10);
+    outln!("Initial stack:        {}", stack.describe());
+
+    // Thread 1 starts a pop: it reads head and head.next, then gets preempted
+    let t1_head = stack.head;
+    let t1_next = t1_head.and_then(|slot| stack.next[slot]);
+    outln!("T1 begins pop: reads head = slot {:?}, next = slot {:?} ... then is preempted",
+             t1_head.unwrap(), t1_next.unwrap());
+
+    // Thread 2 runs to completion in the meantime
+    let a = stack.pop();
+    let b = stack.pop();
+    outln!("T2 pops {:?} and {:?}; their slots are freed right away", a.unwrap(), b.unwrap());
+    let reused = stack.push(99);
+    outln!("T2 pushes 99 - the allocator hands back slot {} (same 'address' T1 saw!)", reused);
+    outln!("Stack before T1 resumes: {}", stack.describe());
+
+    // Thread 1 resumes. Head is the same slot it read, so its CAS succeeds
+    let succeeded = stack.compare_and_swap_head(t1_head, t1_next);
+    outln!("T1 resumes: CAS(head: slot {} → slot {}) {}",
+             t1_head.unwrap(), t1_next.unwrap(),
+             if succeeded { "SUCCEEDS ❌" } else { "fails" });
+    outln!("Stack after T1's pop:   {}", stack.describe());
+    outln!();
+    outln!("The head *value* matched, but it was a different node (A → B → A).");
+    outln!("Value 99 vanished and head now points into freed memory.");
+    outln!("With real pointers this is a use-after-free, not just a wrong answer.\n");
+}
+
+fn demonstrate_epoch_reclamation() {
+    output::heading("🕰️  Epoch-Based Reclamation");
+    outln!("pop() pins the thread, unlinks the node, and *retires* it instead of freeing it.");
+    outln!("A retired node is freed only after the global epoch has advanced twice,");
+    outln!("which proves every thread that could have seen it has unpinned.\n");
+
+    let threads = 4;
+    let ops_per_thread = 50_000u64;
+    let stack = Arc::new(TreiberStack::new());
+
+    let handles: Vec<_> = (0..threads)
+        .map(|t| {
+            let stack = Arc::clone(&stack);
+            thread::spawn(move || {
+                let mut popped_sum = 0u64;
+                for i in 0..ops_per_thread {
+                    stack.push(t * ops_per_thread + i);
+                    if let Some(value) = stack.pop() {
+                        popped_sum += value;
+                    }
+                }
+                popped_sum
+            })
+        })
+        .collect();
+
+    let mut popped_sum: u64 = handles.into_iter().map(|h| h.join().unwrap()).sum();
+    while let Some(value) = stack.pop() {
+        popped_sum += value;
+    }
+
+    let n = threads * ops_per_thread;
+    let expected = n * (n - 1) / 2;
+    let (retired, freed) = stack.reclamation_stats();
+    outln!("{} threads × {} push/pop pairs", threads, ops_per_thread);
+    outln!("Sum of popped values: {} (expected {}) {}", popped_sum, expected,
+             if popped_sum == expected { "✅" } else { "❌" });
+    outln!("Nodes retired: {}, already freed: {}, still deferred: {}", retired, freed, retired - freed);
+    outln!("The deferred nodes are freed when the stack (and its collector) is dropped\n");
+}
+
+fn run_treiber(threads: usize, ops: usize) {
+    let stack = Arc::new(TreiberStack::new());
+    let handles: Vec<_> = (0..threads)
+        .map(|_| {
+            let stack = Arc::clone(&stack);
+            thread::spawn(move || {
+                for i in 0..ops {
+                    stack.push(i);
+                    stack.pop();
+                }
+            })
+        })
+        .collect();
+    handles.into_iter().for_each(|h| h.join().unwrap());
+}
+
+fn run_mutex_vec(threads: usize, ops: usize) {
+    let stack = Arc::new(Mutex::new(Vec::new()));
+    let handles: Vec<_> = (0..threads)
+        .map(|_| {
+            let stack = Arc::clone(&stack);
+            thread::spawn(move || {
+                for i in 0..ops {
+                    stack.lock().unwrap().push(i);
+                    stack.lock().unwrap().pop();
+                }
+            })
+        })
+        .collect();
+    handles.into_iter().for_each(|h| h.join().unwrap());
+}
+
+fn demonstrate_benchmark(report: &mut DemoReport) {
+    output::heading("⚡ Treiber Stack vs Mutex<Vec<T>>");
+
+    let ops = 100_000;
+    outln!("Each thread does {} push+pop pairs; median of 5 runs\n", ops);
+    let table = Table::new(["threads", "Treiber", "Mutex<Vec>"]).widths([7, 14, 14]);
+    outln!("{}", table.header());
+
+    let max_threads = thread::available_parallelism().map(|n| n.get()).unwrap_or(1).max(2) * 2;
+    let mut threads = 1;
+    while threads <= max_threads {
+        let total_ops = (threads * ops * 2) as u64;
+        let treiber = measure(5, || run_treiber(threads, ops));
+        let mutex = measure(5, || run_mutex_vec(threads, ops));
+        let rates = [treiber.throughput(total_ops), mutex.throughput(total_ops)];
+        outln!("{}", table.line([threads.to_string()].into_iter().chain(output::rank(&rates, true, format_rate))));
+        report.record("Treiber", treiber.throughput(total_ops), "ops/s").param("threads", threads);
+        report.record("Mutex<Vec>", mutex.throughput(total_ops), "ops/s").param("threads", threads);
+        threads *= 2;
+    }
+
+    outln!();
+    outln!("Both designs funnel every operation through ONE cache line (head or lock).");
+    outln!("Lock-free doesn't mean contention-free: CAS retries ping-pong that line too.");
+    outln!("Uncontended, a Mutex is one CAS to lock and one store to unlock, while a");
+    outln!("Treiber pop also pays for pinning - so the lock can easily win on raw speed.");
+    outln!("The real win is progress - a preempted thread can't block everyone else.\n");
+}
+
+/// The stack is LIFO, the recycling stack falls for ABA exactly as shown,
+/// and concurrent push/pop pairs lose and duplicate nothing
+fn verify() -> Verification {
+    let mut verification = Verification::new();
+    let stack = TreiberStack::new();
+    for value in 1..=5 {
+        stack.push(value);
+    }
+    let popped: Vec<i32> = std::iter::from_fn(|| stack.pop()).collect();
+    verification.equal("the Treiber stack pops in LIFO order", popped, vec![5, 4, 3, 2, 1]);
+
+    let mut recycling = RecyclingStack::new(3);
+    for value in [30, 20, 10] {
+        recycling.push(value);
+    }
+    let (head, next) = (recycling.head, recycling.head.and_then(|slot| recycling.next[slot]));
+    recycling.pop();
+    recycling.pop();
+    let reused = recycling.push(99);
+    verification
+        .equal("the allocator hands back the slot the stale pop read", Some(reused), head)
+        .check("the stale CAS succeeds (ABA)", recycling.compare_and_swap_head(head, next), || "it failed".to_string())
+        .check("the pushed 99 is lost", recycling.describe().contains("FREED") && !recycling.describe().contains(": 99"),
+               || recycling.describe());
+
+    let threads = 4;
+    let ops_per_thread = 10_000u64;
+    let stack = Arc::new(TreiberStack::new());
+    let handles: Vec<_> = (0..threads)
+        .map(|t| {
+            let stack = Arc::clone(&stack);
+            thread::spawn(move || {
+                (0..ops_per_thread).fold(0, |sum, i| {
+                    stack.push(t * ops_per_thread + i);
+                    sum + stack.pop().unwrap_or(0)
+                })
+            })
+        })
+        .collect();
+    let mut popped_sum: u64 = handles.into_iter().map(|h| h.join().unwrap()).sum();
+    popped_sum += std::iter::from_fn(|| stack.pop()).sum::<u64>();
+    let n = threads * ops_per_thread;
+    verification.equal("concurrent pops return every pushed value exactly once", popped_sum, n * (n - 1) / 2);
+    verification
+}
+
+const QUIZ: &[Question] = &[
+    Question::new(
+        "Why can't a lock-free stack free a popped node right away?",
+        &[
+            "Freeing is too slow",
+            "Another thread may still be reading that node (use-after-free, ABA)",
+            "The allocator isn't thread-safe",
+        ],
+        1,
+        "Deferred reclamation, here epochs, waits until no thread can still hold a pointer to it.",
+    ),
+    Question::new(
+        "What limits how well the Treiber stack scales with threads?",
+        &[
+            "Every push and pop CASes the same head pointer, so its cache line is contended like a lock",
+            "The epoch collector",
+            "Node allocation",
+        ],
+        0,
+        "Lock-free means someone always makes progress, not that everyone progresses in parallel.",
+    ),
+];
+
+#[derive(Demo)]
+#[demo(name = "treiber-stack-demo", description = "A lock-free stack, the ABA problem and epoch reclamation",
+       quiz = QUIZ, verify = verify)]
+pub struct TreiberStackDemo;
+
+impl TreiberStackDemo {
+    fn demonstrate(&self, _config: &Config) -> DemoReport {
+        output::title("🥞 Treiber Lock-Free Stack Demo");
+        outln!("Building a stack without locks, and freeing its memory safely.\n");
+
+        demonstrate_treiber_basics();
+        demonstrate_aba_problem();
+        demonstrate_epoch_reclamation();
+        let mut report = DemoReport::default();
+        demonstrate_benchmark(&mut report);
+
+        output::takeaways();
+        outln!("• CAS loops give lock-free progress: someone always makes progress");
+        outln!("• Freeing nodes immediately breaks lock-free structures (ABA, use-after-free)");
+        outln!("• Epoch-based reclamation defers frees until no reader can hold the node");
+        outln!("• A single hot head pointer limits scaling just like a single lock does");
+        report
+    }
+}
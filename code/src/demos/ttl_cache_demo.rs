@@ -0,0 +1,169 @@
+//! TTL Cache Demo
+//!
+//! A cache of session tokens that expire 200 ms after they are written,
+//! built on the library's `TtlCache`: an LRU cache whose entries also carry
+//! a deadline. Expiry is lazy, so a stale entry keeps its slot until a `get`
+//! trips over it or `purge_expired` sweeps it away. The demo runs the same
+//! requests twice, without and with a sweep, and shows LRU eviction
+//! throwing out a live token while an expired one is still cached.
+//! Run with: cargo run --bin ttl-cache-demo
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::cache::TtlCache;
+use crate::demo::{Config, Demo, DemoReport};
+use crate::quiz::Question;
+use crate::verify::Verification;
+use crate::{outln, output};
+
+/// How long a session token stays valid
+const TTL: Duration = Duration::from_millis(200);
+
+/// The cache's entries from most to least recently used, with the time each
+/// has left
+fn contents(cache: &TtlCache<&'static str, u32>) -> String {
+    let entries: Vec<String> = cache.iter()
+        .map(|(user, _, left)| match left.is_zero() {
+            true => format!("{} (expired)", user),
+            false => format!("{} ({} ms)", user, left.as_millis()),
+        })
+        .collect();
+    format!("[{}]", entries.join(", "))
+}
+
+/// Log in alice and bob, then carol a while later; alice comes back, and
+/// once the first two tokens have expired dave and erin log in. With
+/// `sweep`, expired tokens are purged before the new logins. Returns the
+/// users evicted by LRU while still valid.
+fn run_sessions(sweep: bool) -> Vec<&'static str> {
+    let start = Instant::now();
+    let mut cache: TtlCache<&'static str, u32> = TtlCache::new(3, TTL);
+    let step = |what: &str, cache: &TtlCache<&'static str, u32>| {
+        outln!("  {:>4} ms  {:<24} {}", start.elapsed().as_millis(), what, contents(cache));
+    };
+
+    cache.put("alice", 1);
+    cache.put("bob", 2);
+    step("alice, bob log in", &cache);
+    thread::sleep(TTL * 3 / 4);
+    cache.put("carol", 3);
+    step("carol logs in", &cache);
+    let alice = cache.get(&"alice").is_some();
+    step(if alice { "alice's token is used" } else { "alice's token is gone" }, &cache);
+    thread::sleep(TTL / 2);
+
+    if sweep {
+        let purged = cache.purge_expired();
+        step(&format!("purge_expired: {}", purged), &cache);
+    }
+    let mut live_evicted = Vec::new();
+    for (user, id) in [("dave", 4), ("erin", 5)] {
+        let expired_before: Vec<&str> = cache.iter().filter(|(_, _, left)| left.is_zero()).map(|(&u, _, _)| u).collect();
+        match cache.put(user, id) {
+            Some((evicted, _)) if !expired_before.contains(&evicted) => {
+                live_evicted.push(evicted);
+                step(&format!("{} in, {} evicted!", user, evicted), &cache);
+            }
+            Some((evicted, _)) => step(&format!("{} in, {} evicted", user, evicted), &cache),
+            None => step(&format!("{} logs in", user), &cache),
+        }
+    }
+    let valid: Vec<&str> = ["alice", "bob", "carol", "dave", "erin"].into_iter()
+        .filter(|user| cache.get(user).is_some())
+        .collect();
+    outln!("  still logged in: {}\n", valid.join(", "));
+    live_evicted
+}
+
+fn demonstrate_lazy_expiry() -> Vec<&'static str> {
+    output::heading("⏳ Lazy Expiry Meets LRU Eviction");
+    outln!("Three slots, tokens valid for {} ms, listed from most to least recently", TTL.as_millis());
+    outln!("used. Nothing removes an expired token until a get finds it:\n");
+    run_sessions(false)
+}
+
+fn demonstrate_sweep(live_evicted: &[&str]) {
+    output::heading("🧹 Sweeping First");
+    outln!("The same logins with purge_expired() called before them:\n");
+    let swept = run_sessions(true);
+    match (live_evicted.is_empty(), swept.is_empty()) {
+        (false, true) => {
+            outln!("Without the sweep, LRU evicted {} while an expired token sat in a", live_evicted.join(" and "));
+            outln!("slot: alice's get made her most recently used, and recency knows nothing");
+            outln!("about deadlines. With the sweep, the new logins took the dead slots.");
+        }
+        _ => {
+            outln!("This run's timing didn't reproduce the difference (live tokens evicted:");
+            outln!("{:?} without the sweep, {:?} with it); a loaded machine can stretch the", live_evicted, swept);
+            outln!("sleeps. The point stands: only a sweep frees a stale entry's slot.");
+        }
+    }
+    outln!();
+    outln!("Real caches combine the two. Redis expires keys when they are read and");
+    outln!("also samples a few keys with deadlines ten times a second; memcached");
+    outln!("reuses expired items it finds at the tail of its LRU before evicting.");
+}
+
+/// A token is readable until its deadline and gone after, and a sweep
+/// removes only expired entries
+fn verify() -> Verification {
+    let mut verification = Verification::new();
+    let ttl = Duration::from_millis(50);
+    let mut cache = TtlCache::new(2, ttl);
+    cache.put("a", 1);
+    verification.equal("a fresh entry is readable", cache.get(&"a").copied(), Some(1));
+    thread::sleep(ttl + Duration::from_millis(10));
+    cache.put("b", 2);
+    verification.equal("an expired entry still holds its slot", cache.len(), 2);
+    verification.equal("purge_expired removes only the expired entry", cache.purge_expired(), 1);
+    verification.equal("a get after the deadline misses", cache.get(&"a"), None);
+    verification.equal("the fresh entry survives the sweep", cache.get(&"b").copied(), Some(2));
+    verification
+}
+
+const QUIZ: &[Question] = &[
+    Question::new(
+        "When does a lazily expiring cache remove an expired entry?",
+        &[
+            "The instant its deadline passes",
+            "When a read finds it or a sweep visits it",
+            "Only when it reaches the tail of the LRU list",
+        ],
+        1,
+        "Nothing watches the clock, so until then the dead entry occupies a slot.",
+    ),
+    Question::new(
+        "Why can LRU evict a live entry while an expired one is still cached?",
+        &[
+            "Eviction picks by recency, and an expired entry may have been used more recently",
+            "Expired entries are pinned",
+            "Because the TTL is measured from the last read",
+        ],
+        0,
+        "Recency and age are separate orders; only a sweep or a TTL-aware eviction reconciles them.",
+    ),
+];
+
+#[derive(Demo)]
+#[demo(name = "ttl-cache-demo", description = "Entries that expire: lazy TTL expiry meets LRU eviction",
+       quiz = QUIZ, verify = verify)]
+pub struct TtlCacheDemo;
+
+impl TtlCacheDemo {
+    fn demonstrate(&self, _config: &Config) -> DemoReport {
+        output::title("⏳ TTL Cache Demo");
+        outln!("Entries with deadlines in a cache with a size limit.\n");
+
+        let live_evicted = demonstrate_lazy_expiry();
+        demonstrate_sweep(&live_evicted);
+
+        outln!();
+        output::takeaways();
+        outln!("• A TTL bounds how stale a read can be; a capacity bounds the memory");
+        outln!("• Lazy expiry needs no timer, but dead entries hold slots until found");
+        outln!("• LRU eviction orders by use, not by deadline, so it can drop live data");
+        outln!("• A periodic sweep returns the dead slots before eviction has to choose");
+        DemoReport::default()
+    }
+}
@@ -0,0 +1,272 @@
+//! PhantomData & Typestate Demo
+//!
+//! Encoding a protocol's states in the type system: a connection that can
+//! only send once authenticated, and a builder that can only build once
+//! every required field is set. The state markers are zero-sized, so the
+//! checking happens entirely at compile time.
+//! Run with: cargo run --bin typestate-demo
+
+use std::marker::PhantomData;
+use std::mem;
+
+use crate::demo::{Config, Demo, DemoReport};
+use crate::output::Table;
+use crate::quiz::Question;
+use crate::{out, outln, output};
+
+// ---- States of a connection: empty types, never instantiated ----
+
+struct Disconnected;
+struct Connected;
+struct Authenticated;
+
+/// Sealed so no other code can invent new states
+mod sealed {
+    pub trait Sealed {}
+    impl Sealed for super::Disconnected {}
+    impl Sealed for super::Connected {}
+    impl Sealed for super::Authenticated {}
+}
+
+trait ConnectionState: sealed::Sealed {
+    const NAME: &'static str;
+}
+
+impl ConnectionState for Disconnected {
+    const NAME: &'static str = "Disconnected";
+}
+
+impl ConnectionState for Connected {
+    const NAME: &'static str = "Connected";
+}
+
+impl ConnectionState for Authenticated {
+    const NAME: &'static str = "Authenticated";
+}
+
+/// `S` only exists at compile time: PhantomData<S> takes no space but makes
+/// `Connection<Connected>` and `Connection<Authenticated>` different types
+struct Connection<S: ConnectionState> {
+    address: String,
+    sent: u32,
+    _state: PhantomData<S>,
+}
+
+/// The same fields without any state parameter, for size comparison
+#[allow(dead_code)]
+struct UntypedConnection {
+    address: String,
+    sent: u32,
+}
+
+impl<S: ConnectionState> Connection<S> {
+    /// Every transition consumes `self`: the old state can't be used again
+    fn transition<T: ConnectionState>(self) -> Connection<T> {
+        Connection { address: self.address, sent: self.sent, _state: PhantomData }
+    }
+
+    fn state(&self) -> &'static str {
+        S::NAME
+    }
+}
+
+impl Connection<Disconnected> {
+    fn new(address: &str) -> Self {
+        Connection { address: address.to_string(), sent: 0, _state: PhantomData }
+    }
+
+    fn connect(self) -> Connection<Connected> {
+        outln!("    connecting to {}", self.address);
+        self.transition()
+    }
+}
+
+impl Connection<Connected> {
+    fn authenticate(self, token: &str) -> Result<Connection<Authenticated>, Connection<Connected>> {
+        if token.is_empty() {
+            outln!("    authentication failed: empty token");
+            return Err(self); // still Connected: the caller may retry
+        }
+        outln!("    authenticated with token {:?}", token);
+        Ok(self.transition())
+    }
+
+    fn disconnect(self) -> Connection<Disconnected> {
+        outln!("    disconnecting");
+        self.transition()
+    }
+}
+
+impl Connection<Authenticated> {
+    /// Only an authenticated connection has a `send` method at all
+    fn send(&mut self, message: &str) {
+        self.sent += 1;
+        outln!("    sent #{}: {:?}", self.sent, message);
+    }
+
+    fn disconnect(self) -> Connection<Disconnected> {
+        outln!("    disconnecting after {} messages", self.sent);
+        self.transition()
+    }
+}
+
+fn demonstrate_connection() {
+    output::heading("🔌 A Connection State Machine");
+    outln!("  Disconnected --connect--> Connected --authenticate--> Authenticated");
+    outln!("       ^                        |                            |");
+    outln!("       +------disconnect--------+----------disconnect--------+\n");
+
+    let probe = Connection::new("cache.internal:6379").connect().disconnect();
+    outln!("  probe: {} (connected and gave up without authenticating)\n", probe.state());
+
+    let connection = Connection::new("db.internal:5432");
+    outln!("  state: {}", connection.state());
+    let connection = connection.connect();
+    outln!("  state: {}", connection.state());
+
+    let connection = match connection.authenticate("") {
+        Ok(_) => unreachable!("an empty token is rejected"),
+        Err(still_connected) => still_connected,
+    };
+    outln!("  state: {} (failed auth handed the connection back)", connection.state());
+
+    let mut connection = connection.authenticate("s3cret").unwrap_or_else(|_| unreachable!());
+    outln!("  state: {}", connection.state());
+    connection.send("SELECT 1");
+    connection.send("SELECT 2");
+    let connection = connection.disconnect();
+    outln!("  state: {}\n", connection.state());
+
+    outln!("Invalid transitions don't compile:");
+    outln!("  Connection::new(addr).send(\"hi\")");
+    outln!("  error[E0599]: no method named `send` found for struct `Connection<Disconnected>`");
+    outln!("  let c = Connection::new(addr).connect(); let d = c.disconnect(); c.authenticate(token);");
+    outln!("  error[E0382]: use of moved value: `c`");
+    outln!("Methods exist only on the states where they make sense, and consuming");
+    outln!("`self` stops anyone from holding on to a stale state.\n");
+}
+
+// ---- A builder that can't build until the required fields are set ----
+
+struct Missing;
+struct Set;
+
+struct RequestBuilder<Url, Method> {
+    url: Option<String>,
+    method: Option<&'static str>,
+    headers: Vec<(String, String)>,
+    _fields: PhantomData<(Url, Method)>,
+}
+
+#[derive(Debug)]
+struct Request {
+    method: &'static str,
+    url: String,
+    headers: Vec<(String, String)>,
+}
+
+impl RequestBuilder<Missing, Missing> {
+    fn new() -> Self {
+        RequestBuilder { url: None, method: None, headers: Vec::new(), _fields: PhantomData }
+    }
+}
+
+impl<Url, Method> RequestBuilder<Url, Method> {
+    fn url(self, url: &str) -> RequestBuilder<Set, Method> {
+        RequestBuilder { url: Some(url.to_string()), method: self.method, headers: self.headers, _fields: PhantomData }
+    }
+
+    fn method(self, method: &'static str) -> RequestBuilder<Url, Set> {
+        RequestBuilder { url: self.url, method: Some(method), headers: self.headers, _fields: PhantomData }
+    }
+
+    /// Optional fields are allowed in any state
+    fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+}
+
+impl RequestBuilder<Set, Set> {
+    /// No Result, no panic: the type guarantees both fields are present
+    fn build(self) -> Request {
+        Request {
+            method: self.method.expect("Set guarantees a method"),
+            url: self.url.expect("Set guarantees a url"),
+            headers: self.headers,
+        }
+    }
+}
+
+fn demonstrate_builder() {
+    output::heading("🏗️  A Typestate Builder");
+    let request = RequestBuilder::new().header("Accept", "text/plain").method("GET").url("/status").build();
+    outln!("  RequestBuilder::new().header(..).method(\"GET\").url(\"/status\").build()");
+    outln!("  -> {:?}", request);
+    outln!("  {} {} with {} header(s)\n", request.method, request.url, request.headers.len());
+
+    outln!("Forgetting a required field is a compile error, in any order:");
+    outln!("  RequestBuilder::new().url(\"/status\").build()");
+    outln!("  error[E0599]: no method named `build` found for struct `RequestBuilder<Set, Missing>`");
+    outln!("A runtime builder would need build() -> Result<Request, MissingField>;");
+    outln!("here every caller that compiles has already supplied both.\n");
+}
+
+fn demonstrate_zero_cost() {
+    output::heading("📏 Zero Runtime Size");
+    let mut table = Table::new(["type", "bytes"]);
+    for (name, size) in [
+        ("Disconnected (marker)", mem::size_of::<Disconnected>()),
+        ("PhantomData<Authenticated>", mem::size_of::<PhantomData<Authenticated>>()),
+        ("UntypedConnection", mem::size_of::<UntypedConnection>()),
+        ("Connection<Disconnected>", mem::size_of::<Connection<Disconnected>>()),
+        ("Connection<Authenticated>", mem::size_of::<Connection<Authenticated>>()),
+        ("RequestBuilder<Missing, Missing>", mem::size_of::<RequestBuilder<Missing, Missing>>()),
+        ("RequestBuilder<Set, Set>", mem::size_of::<RequestBuilder<Set, Set>>()),
+    ] {
+        table.row([name.to_string(), size.to_string()]);
+    }
+    out!("{}", table);
+    outln!("The markers are zero-sized types, and PhantomData<T> is zero-sized for");
+    outln!("any T: it only tells the compiler \"act as if a T were here\" (for");
+    outln!("variance, auto traits and drop checking). A transition is a move of the");
+    outln!("same bytes, which the optimizer usually erases entirely.\n");
+}
+
+const QUIZ: &[Question] = &[
+    Question::new(
+        "What happens when you call a method that only exists for a different state?",
+        &["It panics at run time", "It's a compile error", "It's silently ignored"],
+        1,
+        "Methods are defined per state type, so the invalid call has nothing to resolve to.",
+    ),
+    Question::new(
+        "How many bytes does the state marker (PhantomData) add at run time?",
+        &["8", "0", "It depends on the state"],
+        1,
+        "Marker types are zero-sized: the state exists only for the type checker.",
+    ),
+];
+
+#[derive(Demo)]
+#[demo(name = "typestate-demo", description = "PhantomData and protocol states in the type system",
+       quiz = QUIZ)]
+pub struct TypestateDemo;
+
+impl TypestateDemo {
+    fn demonstrate(&self, _config: &Config) -> DemoReport {
+        output::title("👻 PhantomData & Typestate");
+        outln!("Making invalid states unrepresentable.\n");
+
+        demonstrate_connection();
+        demonstrate_builder();
+        demonstrate_zero_cost();
+
+        output::takeaways();
+        outln!("• A type parameter can encode a state: Connection<Connected>");
+        outln!("• Methods defined per state make invalid calls compile errors");
+        outln!("• Consuming self on transitions prevents reusing an old state");
+        outln!("• PhantomData and marker types cost zero bytes at runtime");
+        DemoReport::default()
+    }
+}
@@ -0,0 +1,313 @@
+//! Weighted Cache Demo
+//!
+//! How an HTTP cache bounds memory. Counting entries only works when they
+//! are all about the same size, and responses are not: a redirect is a few
+//! hundred bytes, an image a few hundred kilobytes. `WeightedLruCache`
+//! weighs each entry by its bytes and evicts least recently used entries
+//! until the total fits a byte budget. The demo walks a small cache through
+//! one large insert that pushes out several small entries and one response
+//! too big to cache at all, then replays a Zipf trace of mixed responses
+//! against the byte budget and against entry-count caches sized two ways.
+//! Run with: cargo run --release --bin weighted-cache-demo
+
+use crate::cache::{LruCache, WeightedLruCache};
+use crate::demo::{Config, Demo, DemoReport};
+use crate::output::Table;
+use crate::plot::format_si;
+use crate::quiz::Question;
+use crate::rng::{Rng, DEFAULT_SEED};
+use crate::verify::Verification;
+use crate::{outln, output};
+
+/// Byte budget in the trace
+const BUDGET: usize = 2_000_000;
+
+/// Distinct URLs in the trace
+const URLS: usize = 1_000;
+
+/// Requests in the trace, unless the config sets `size`
+const REQUESTS: usize = 20_000;
+
+/// Bytes a response costs the cache: its URL and its body
+fn weigh(url: &str, body: &str) -> usize {
+    url.len() + body.len()
+}
+
+/// `weigh` as the caches call it
+const WEIGH: fn(&String, &String) -> usize = |url, body| weigh(url, body);
+
+/// The body the origin sends back for `url`: `size` bytes of text
+fn fetch(url: &str, size: usize) -> String {
+    let mut body = String::with_capacity(size);
+    while body.len() + url.len() < size {
+        body.push_str(url);
+    }
+    body.extend(std::iter::repeat_n('.', size - body.len()));
+    body
+}
+
+/// One response per URL: 60% small API answers and redirects of 100 B to
+/// 2 KB, 30% pages, scripts and stylesheets of 2 to 30 KB, and 10% images
+/// of 50 to 300 KB. Returns (url, body size) pairs, in popularity order.
+fn responses(rng: &mut Rng) -> Vec<(String, usize)> {
+    (0..URLS)
+        .map(|id| match rng.below(10) {
+            0..=5 => (format!("/api/{:04}", id), 100 + rng.below(1_900)),
+            6..=8 => (format!("/static/{:04}.js", id), 2_000 + rng.below(28_000)),
+            _ => (format!("/img/{:04}.jpg", id), 50_000 + rng.below(250_000)),
+        })
+        .collect()
+}
+
+/// Response `r` with probability proportional to 1/(r+1)
+fn zipf(requests: usize, rng: &mut Rng) -> Vec<usize> {
+    let mut cumulative = Vec::with_capacity(URLS);
+    let mut total = 0.0;
+    for rank in 1..=URLS {
+        total += 1.0 / rank as f64;
+        cumulative.push(total);
+    }
+    (0..requests)
+        .map(|_| {
+            let u = (rng.next_u64() >> 11) as f64 / (1u64 << 53) as f64 * total;
+            cumulative.partition_point(|&c| c < u)
+        })
+        .collect()
+}
+
+/// What a cache did with a trace
+#[derive(Default)]
+struct Outcome {
+    hits: usize,
+    hit_bytes: usize,
+    peak_bytes: usize,
+    peak_entries: usize,
+}
+
+/// Replay `trace` against an LRU cache of `capacity` entries, tracking the
+/// bytes it holds
+fn replay_counted(trace: &[usize], responses: &[(String, usize)], capacity: usize) -> Outcome {
+    let mut cache: LruCache<String, String> = LruCache::new(capacity);
+    let mut outcome = Outcome::default();
+    let mut bytes = 0;
+    for &id in trace {
+        let (url, size) = &responses[id];
+        if let Some(body) = cache.get(url) {
+            outcome.hits += 1;
+            outcome.hit_bytes += weigh(url, body);
+            continue;
+        }
+        let body = fetch(url, *size);
+        bytes += weigh(url, &body);
+        if let Some((old_url, old_body)) = cache.put(url.clone(), body) {
+            bytes -= weigh(&old_url, &old_body);
+        }
+        outcome.peak_bytes = outcome.peak_bytes.max(bytes);
+        outcome.peak_entries = outcome.peak_entries.max(cache.len());
+    }
+    outcome
+}
+
+/// Replay `trace` against a `WeightedLruCache` of `budget` bytes
+fn replay_weighted(trace: &[usize], responses: &[(String, usize)], budget: usize) -> Outcome {
+    let mut cache = WeightedLruCache::new(budget, WEIGH);
+    let mut outcome = Outcome::default();
+    for &id in trace {
+        let (url, size) = &responses[id];
+        if let Some(body) = cache.get(url) {
+            outcome.hits += 1;
+            outcome.hit_bytes += weigh(url, body);
+            continue;
+        }
+        cache.put(url.clone(), fetch(url, *size));
+        outcome.peak_bytes = outcome.peak_bytes.max(cache.weight());
+        outcome.peak_entries = outcome.peak_entries.max(cache.len());
+    }
+    outcome
+}
+
+/// The walkthrough's requests: (url, body bytes)
+const WALKTHROUGH: [(&str, usize); 7] = [
+    ("/api/user", 420),
+    ("/api/cart", 380),
+    ("/login", 120),
+    ("/style.css", 2_400),
+    ("/app.js", 3_100),
+    ("/hero.jpg", 6_000),
+    ("/intro.mp4", 25_000),
+];
+
+/// Budget in the walkthrough
+const WALKTHROUGH_BUDGET: usize = 10_000;
+
+/// Run the walkthrough, reading `/api/user` again before the image arrives;
+/// returns the URLs each put evicted or refused
+fn walkthrough() -> (WeightedLruCache<String, String>, Vec<Vec<String>>) {
+    let mut cache = WeightedLruCache::new(WALKTHROUGH_BUDGET, WEIGH);
+    let mut evictions = Vec::new();
+    for (url, size) in WALKTHROUGH {
+        if url == "/hero.jpg" {
+            cache.get(&"/api/user".to_string());
+        }
+        let evicted = cache.put(url.to_string(), fetch(url, size));
+        evictions.push(evicted.into_iter().map(|(url, _)| url).collect());
+    }
+    (cache, evictions)
+}
+
+fn demonstrate_walkthrough() {
+    output::heading("🚶 One Big Response");
+    outln!("A {}-byte budget; each response weighs its URL plus its body.", WALKTHROUGH_BUDGET);
+    outln!("/api/user is read again just before /hero.jpg arrives:\n");
+    let (cache, evictions) = walkthrough();
+    let mut table = Table::new(["put", "bytes", "evicted"]).left(0).left(2);
+    for ((url, size), evicted) in WALKTHROUGH.iter().zip(&evictions) {
+        let evicted = match evicted.as_slice() {
+            [] => "-".to_string(),
+            [only] if only == url => "refused: over the whole budget".to_string(),
+            urls => urls.join(", "),
+        };
+        table.row([url.to_string(), (url.len() + size).to_string(), evicted]);
+    }
+    outln!("{}", table);
+    let held: Vec<&str> = cache.iter().map(|(url, _, _)| url.as_str()).collect();
+    outln!("Left holding {} of {} bytes: {}.", cache.weight(), cache.budget(), held.join(", "));
+    let (image, image_size) = WALKTHROUGH[5];
+    outln!("The image needed room for {} bytes, so the least recently used entries", image.len() + image_size);
+    outln!("went, oldest first, until it fit: {} entries for one. /api/user", evictions[5].len());
+    outln!("survived because it was read. The video weighs more than the whole");
+    outln!("budget, so caching it would mean emptying the cache and still not");
+    outln!("fitting; it is passed through and never stored.\n");
+}
+
+fn demonstrate_trace(config: &Config, report: &mut DemoReport) {
+    output::heading("🌐 Mixed Responses");
+    let requests = config.size.unwrap_or(REQUESTS).max(1_000);
+    let mut rng = Rng::new(config.seed.unwrap_or(DEFAULT_SEED));
+    let responses = responses(&mut rng);
+    let trace = zipf(requests, &mut rng);
+    let sizes: Vec<usize> = responses.iter().map(|(url, size)| url.len() + size).collect();
+    let mean = sizes.iter().sum::<usize>() / URLS;
+    let largest = *sizes.iter().max().unwrap();
+    let requested: usize = trace.iter().map(|&id| sizes[id]).sum();
+    outln!("{} URLs, from 100-byte API answers to 300 KB images, requested {} times", URLS, requests);
+    outln!("with Zipf popularity. The byte budget is {}B. An entry count that", format_si(BUDGET as f64));
+    outln!("means the same on average is budget / mean response = {}B / {}B", format_si(BUDGET as f64), format_si(mean as f64));
+    outln!("= {} entries; one that can never overshoot is budget / largest", BUDGET / mean);
+    outln!("= {}B / {}B = {} entries.\n", format_si(BUDGET as f64), format_si(largest as f64), BUDGET / largest);
+
+    let runs = [
+        (format!("LruCache, {} entries (mean)", BUDGET / mean), replay_counted(&trace, &responses, BUDGET / mean)),
+        (format!("LruCache, {} entries (worst case)", BUDGET / largest), replay_counted(&trace, &responses, BUDGET / largest)),
+        (format!("WeightedLruCache, {}B", format_si(BUDGET as f64)), replay_weighted(&trace, &responses, BUDGET)),
+    ];
+    let mut table = Table::new(["cache", "peak bytes", "vs budget", "peak entries", "hit rate", "byte hit rate"]).left(0);
+    for (name, outcome) in &runs {
+        let hit_rate = outcome.hits as f64 / requests as f64;
+        let byte_hit_rate = outcome.hit_bytes as f64 / requested as f64;
+        report.record(name, hit_rate, "hit rate").param("requests", requests);
+        report.record(name, outcome.peak_bytes as f64, "bytes").param("requests", requests);
+        table.row([name.clone(), format!("{}B", format_si(outcome.peak_bytes as f64)),
+                   format!("{:.0}%", 100.0 * outcome.peak_bytes as f64 / BUDGET as f64), outcome.peak_entries.to_string(),
+                   format!("{:.1}%", 100.0 * hit_rate), format!("{:.1}%", 100.0 * byte_hit_rate)]);
+    }
+    outln!("{}", table);
+
+    let [(_, mean_sized), (_, worst_sized), (_, weighted)] = &runs;
+    outln!("The mean-sized count holds {:.1}x the budget at its peak: when several", mean_sized.peak_bytes as f64 / BUDGET as f64);
+    outln!("images are popular at once, a fixed number of entries is a lot of");
+    outln!("bytes, and its {:.1}% hit rate is paid for with them. Sizing", 100.0 * mean_sized.hits as f64 / requests as f64);
+    outln!("for the largest response keeps memory bounded but wastes it, holding");
+    outln!("{} entries where the budget has room for {}, so it hits", worst_sized.peak_entries, weighted.peak_entries);
+    outln!("{:.1}% of requests. The byte budget never goes over and fills", 100.0 * worst_sized.hits as f64 / requests as f64);
+    outln!("itself with as many responses as fit: many small ones, a few large,");
+    outln!("hitting {:.1}% of requests. This is how HTTP caches size themselves:", 100.0 * weighted.hits as f64 / requests as f64);
+    outln!("in bytes, with the weight of each response measured when it arrives.\n");
+}
+
+/// The walkthrough evicts three small entries for the image and refuses the
+/// video; on the trace the byte budget is never exceeded, the mean-sized
+/// count overshoots it, and the worst-case count hits less
+fn verify() -> Verification {
+    let mut verification = Verification::new();
+    let (cache, evictions) = walkthrough();
+    verification.equal("the image evicts the three oldest unread entries", evictions[5].clone(),
+                       vec!["/api/cart".to_string(), "/login".to_string(), "/style.css".to_string()]);
+    verification.equal("the video is refused", evictions[6].clone(), vec!["/intro.mp4".to_string()]);
+    verification.check("the walkthrough ends within its budget", cache.weight() <= WALKTHROUGH_BUDGET,
+                       || format!("{} bytes", cache.weight()));
+
+    let mut rng = Rng::new(DEFAULT_SEED);
+    let responses = responses(&mut rng);
+    let trace = zipf(5_000, &mut rng);
+    let sizes: Vec<usize> = responses.iter().map(|(url, size)| url.len() + size).collect();
+    let mean = sizes.iter().sum::<usize>() / URLS;
+    let largest = *sizes.iter().max().unwrap();
+    let weighted = replay_weighted(&trace, &responses, BUDGET);
+    let mean_sized = replay_counted(&trace, &responses, BUDGET / mean);
+    let worst_sized = replay_counted(&trace, &responses, BUDGET / largest);
+    verification.check("the WeightedLruCache never holds more than its budget", weighted.peak_bytes <= BUDGET,
+                       || format!("peak {} bytes", weighted.peak_bytes));
+    verification.check("a count sized by the mean response overshoots the budget", mean_sized.peak_bytes > BUDGET,
+                       || format!("peak {} bytes", mean_sized.peak_bytes));
+    verification.check("the byte budget hits more often than a count sized for the largest response",
+                       weighted.hits > worst_sized.hits, || format!("{} vs {} hits", weighted.hits, worst_sized.hits));
+    verification
+}
+
+const QUIZ: &[Question] = &[
+    Question::new(
+        "Why does an HTTP cache budget bytes rather than entries?",
+        &[
+            "Counting entries is slow",
+            "Responses range from hundreds of bytes to megabytes, so a count bounds memory only loosely",
+            "HTTP forbids caching more than 100 responses",
+        ],
+        1,
+        "The same number of entries can be a few kilobytes or hundreds of megabytes depending on which are cached.",
+    ),
+    Question::new(
+        "A 6 KB response goes into a full cache of small entries. What happens?",
+        &[
+            "One entry is evicted, whatever its size",
+            "The insert fails",
+            "Least recently used entries are evicted until 6 KB fits, however many that takes",
+        ],
+        2,
+        "Eviction continues until the total weight is back under the budget.",
+    ),
+    Question::new(
+        "What should a weighted cache do with an entry heavier than its whole budget?",
+        &[
+            "Refuse it and leave the cache as it was",
+            "Empty the cache and store it anyway",
+            "Store it and raise the budget",
+        ],
+        0,
+        "Evicting everything still would not make room, so the entry is passed through uncached.",
+    ),
+];
+
+#[derive(Demo)]
+#[demo(name = "weighted-cache-demo", description = "A byte-budget WeightedLruCache vs entry-count LRUs on HTTP responses from 100 B to 300 KB",
+       quiz = QUIZ, verify = verify)]
+pub struct WeightedCacheDemo;
+
+impl WeightedCacheDemo {
+    fn demonstrate(&self, config: &Config) -> DemoReport {
+        output::title("⚖️  Weighted Cache Demo");
+        outln!("Capacity in bytes, not entries.\n");
+
+        let mut report = DemoReport::default();
+        demonstrate_walkthrough();
+        demonstrate_trace(config, &mut report);
+
+        output::takeaways();
+        outln!("• An entry count bounds memory only when entries are about the same size");
+        outln!("• A weighted cache gives each entry a cost, here its bytes, and budgets the total");
+        outln!("• One large insert can evict many small entries, least recently used first");
+        outln!("• An entry heavier than the whole budget is refused, not cached");
+        outln!("• Sizing a count for the largest entry is safe but wastes most of the memory");
+        report
+    }
+}
@@ -0,0 +1,237 @@
+//! Write-Back Demo
+//!
+//! What a cache does with writes. `CachedStore` puts an LRU cache in front
+//! of a `SimulatedStore` that sleeps on every access, like a disk or a
+//! database across the network. Write-through sends every `put` on to the
+//! store at once; write-back marks the entry dirty and writes it only when
+//! it is evicted or flushed. The demo follows one key written five times
+//! under each policy, then runs a counter workload, a read and a write per
+//! request on mostly hot keys, with no cache, write-through and write-back,
+//! and compares request latency and how many store writes each application
+//! write became.
+//! Run with: cargo run --release --bin write-back-demo
+
+use std::time::{Duration, Instant};
+
+use crate::bench::{measure, Measurement};
+use crate::cache::{BackingStore, CachedStore, SimulatedStore, WritePolicy};
+use crate::demo::{Config, Demo, DemoReport};
+use crate::output::Table;
+use crate::quiz::Question;
+use crate::rng::{Rng, DEFAULT_SEED};
+use crate::verify::Verification;
+use crate::{outln, output};
+
+/// How long the store takes over every read and every write
+const LATENCY: Duration = Duration::from_micros(100);
+
+/// Entries the cache holds
+const CAPACITY: usize = 100;
+
+/// Keys that get 90% of the requests; they all fit in the cache
+const HOT_KEYS: u64 = 50;
+
+/// Keys sharing the other 10%, each seldom seen twice
+const COLD_KEYS: u64 = 5_000;
+
+/// Requests in the workload, unless the config sets `size`
+const REQUESTS: usize = 5_000;
+
+type Counters = CachedStore<u64, u64, SimulatedStore<u64, u64>>;
+
+/// Which counter each request bumps: 90% a hot key, 10% a cold one
+fn requests(count: usize, seed: u64) -> Vec<u64> {
+    let mut rng = Rng::new(seed);
+    (0..count)
+        .map(|_| match rng.below(10) {
+            0 => HOT_KEYS + rng.below(COLD_KEYS as usize) as u64,
+            _ => rng.below(HOT_KEYS as usize) as u64,
+        })
+        .collect()
+}
+
+/// Time `bump` on each key in turn, one sample per request
+fn timed(keys: &[u64], mut bump: impl FnMut(u64)) -> Measurement {
+    Measurement::from_samples(
+        keys.iter()
+            .map(|&key| {
+                let start = Instant::now();
+                bump(key);
+                start.elapsed()
+            })
+            .collect(),
+    )
+}
+
+/// Bump every counter in `keys` straight in a store of `latency`
+fn run_uncached(keys: &[u64], latency: Duration) -> (SimulatedStore<u64, u64>, Measurement) {
+    let mut store = SimulatedStore::new(latency);
+    let latencies = timed(keys, |key| {
+        let count = store.read(&key).unwrap_or(0);
+        store.write(key, count + 1);
+    });
+    (store, latencies)
+}
+
+/// Bump every counter in `keys` through a cache in front of a store of
+/// `latency`, leaving any dirty entries unflushed
+fn run_cached(keys: &[u64], latency: Duration, policy: WritePolicy) -> (Counters, Measurement) {
+    let mut cache = CachedStore::new(SimulatedStore::new(latency), CAPACITY, policy);
+    let latencies = timed(keys, |key| {
+        let count = cache.get(&key).unwrap_or(0);
+        cache.put(key, count + 1);
+    });
+    (cache, latencies)
+}
+
+/// Sum of every counter the store holds
+fn stored_total(store: &SimulatedStore<u64, u64>) -> u64 {
+    (0..HOT_KEYS + COLD_KEYS).filter_map(|key| store.peek(&key)).sum()
+}
+
+fn demonstrate_one_key() {
+    output::heading("✍️  One Key, Five Writes");
+    outln!("A 2-entry cache; key 7 is set to 1, 2, 3, 4 and 5:\n");
+    let mut table = Table::new(["policy", "store writes", "store holds", "dirty"]).left(0);
+    let mut write_back = None;
+    for policy in [WritePolicy::WriteThrough, WritePolicy::WriteBack] {
+        let mut cache = CachedStore::new(SimulatedStore::new(Duration::ZERO), 2, policy);
+        for value in 1..=5 {
+            cache.put(7u64, value);
+        }
+        let holds = cache.store().peek(&7).map_or("nothing".to_string(), u64::to_string);
+        table.row([format!("{:?}", policy), cache.store().writes().to_string(), holds, cache.dirty().to_string()]);
+        write_back = Some(cache);
+    }
+    outln!("{}", table);
+    let mut cache = write_back.expect("both policies ran");
+    let flushed = cache.flush();
+    outln!("Write-through paid for five store writes to leave one value. Write-back");
+    outln!("paid for none, and the store has never heard of key 7: if the process");
+    outln!("died now, all five writes would be gone. A flush writes {} entry, and", flushed);
+    outln!("the store holds {}. That is the trade: fewer, later writes, and a", cache.store().peek(&7).copied().unwrap_or(0));
+    outln!("window in which the only copy of the data is the cache.\n");
+}
+
+fn demonstrate_workload(config: &Config, report: &mut DemoReport) {
+    output::heading("📊 Counters Under Load");
+    let count = config.size.unwrap_or(REQUESTS).max(100);
+    let keys = requests(count, config.seed.unwrap_or(DEFAULT_SEED));
+    outln!("{} requests, each reading a counter and writing it back plus one: 90%", count);
+    outln!("to {} hot keys, 10% to {} cold ones. The store takes {:?} per", HOT_KEYS, COLD_KEYS, LATENCY);
+    outln!("access; the cache holds {} entries.\n", CAPACITY);
+
+    let (store, uncached) = run_uncached(&keys, LATENCY);
+    let (through, through_latencies) = run_cached(&keys, LATENCY, WritePolicy::WriteThrough);
+    let (mut back, back_latencies) = run_cached(&keys, LATENCY, WritePolicy::WriteBack);
+    let dirty = back.dirty();
+    let start = Instant::now();
+    back.flush();
+    let flush_time = start.elapsed();
+
+    let mut table = Table::new(["store access", "reads", "writes", "writes per put", "median", "p99", "total"]).left(0);
+    let rows = [
+        ("no cache", store.reads(), store.writes(), &uncached, Duration::ZERO),
+        ("write-through", through.store().reads(), through.store().writes(), &through_latencies, Duration::ZERO),
+        ("write-back + flush", back.store().reads(), back.store().writes(), &back_latencies, flush_time),
+    ];
+    for (name, reads, writes, latencies, flush_time) in rows {
+        let total = latencies.samples().iter().sum::<Duration>() + flush_time;
+        report.record_time(name, latencies.median()).param("requests", count);
+        report.record(name, writes as f64 / count as f64, "writes/put").param("requests", count);
+        table.row([name.to_string(), reads.to_string(), writes.to_string(), format!("{:.2}", writes as f64 / count as f64),
+                   format!("{:.2?}", latencies.median()), format!("{:.2?}", latencies.percentile(99.0)), format!("{:.2?}", total)]);
+    }
+    outln!("{}", table);
+
+    let through_total: Duration = through_latencies.samples().iter().sum();
+    let back_total = back_latencies.samples().iter().sum::<Duration>() + flush_time;
+    outln!("The cache takes almost every read off the store, but write-through still");
+    outln!("sends every write there, so a typical request still waits {:.2?}:", through_latencies.median());
+    outln!("one store write per put. Write-back's typical request never leaves");
+    outln!("memory. Its store writes are the cold keys it evicts, each once, plus");
+    outln!("the {} dirty entries left at the end, which the flush wrote. Hot keys,", dirty);
+    outln!("written {} times each on average, stay cached and reach the store about", count * 9 / 10 / HOT_KEYS as usize);
+    outln!("once. Flush included, the run took {:.1}x less time. The tail tells", through_total.as_secs_f64() / back_total.as_secs_f64());
+    outln!("the rest: a cold request under write-back reads the store and may");
+    outln!("write a dirty victim back, so the slowest requests wait as long as");
+    outln!("write-through's.\n");
+}
+
+/// Write-through writes the store once per put and write-back far less,
+/// both stores agree once flushed, write-back's store is behind until then,
+/// and with the store's latency write-back finishes first
+fn verify() -> Verification {
+    let mut verification = Verification::new();
+    let keys = requests(2_000, DEFAULT_SEED);
+    let (through, _) = run_cached(&keys, Duration::ZERO, WritePolicy::WriteThrough);
+    let (mut back, _) = run_cached(&keys, Duration::ZERO, WritePolicy::WriteBack);
+    verification.equal("write-through writes the store once per put", through.store().writes(), keys.len());
+    verification.check("before a flush, write-back's store is missing updates", stored_total(back.store()) < keys.len() as u64,
+                       || format!("store total {} of {}", stored_total(back.store()), keys.len()));
+    back.flush();
+    verification.check("write-back writes the store at most once per 5 puts", 5 * back.store().writes() <= keys.len(),
+                       || format!("{} writes for {} puts", back.store().writes(), keys.len()));
+    let agree = (0..HOT_KEYS + COLD_KEYS).all(|key| through.store().peek(&key) == back.store().peek(&key));
+    verification.check("once flushed, both stores hold the same counters", agree, || "a counter differs".to_string());
+
+    let keys = &keys[..500];
+    let latency = Duration::from_micros(50);
+    let through_time = measure(3, || run_cached(keys, latency, WritePolicy::WriteThrough));
+    let back_time = measure(3, || run_cached(keys, latency, WritePolicy::WriteBack));
+    verification.faster("write-back finishes 2x sooner than write-through", &back_time, &through_time, 2.0);
+    verification
+}
+
+const QUIZ: &[Question] = &[
+    Question::new(
+        "A program writes one key 1,000 times through a write-back cache, then flushes. How many store writes?",
+        &["1,000", "1", "None"],
+        1,
+        "The writes land on the cached entry; the store sees only the value it holds when flushed or evicted.",
+    ),
+    Question::new(
+        "What does write-back risk that write-through doesn't?",
+        &[
+            "Reading a stale value from the cache",
+            "Losing writes held only in dirty entries if the process or machine dies",
+            "Evicting entries too early",
+        ],
+        1,
+        "Until a dirty entry is written back, the cache holds the only copy of that write.",
+    ),
+    Question::new(
+        "Why does write-through still help with a slow store?",
+        &[
+            "Reads of cached keys never touch the store",
+            "It batches writes",
+            "It skips writes of unchanged values",
+        ],
+        0,
+        "Write-through keeps the store current, so it only saves reads; every write still waits for the store.",
+    ),
+];
+
+#[derive(Demo)]
+#[demo(name = "write-back-demo", description = "Write-through vs write-back caching over a slow store: request latency and store writes per put",
+       quiz = QUIZ, verify = verify)]
+pub struct WriteBackDemo;
+
+impl WriteBackDemo {
+    fn demonstrate(&self, config: &Config) -> DemoReport {
+        output::title("💾 Write-Back Demo");
+        outln!("When does a write reach the store?\n");
+
+        let mut report = DemoReport::default();
+        demonstrate_one_key();
+        demonstrate_workload(config, &mut report);
+
+        output::takeaways();
+        outln!("• Any cache saves reads; the write policy decides what it does for writes");
+        outln!("• Write-through keeps the store current and makes every write wait for it");
+        outln!("• Write-back marks entries dirty and writes them on eviction or flush");
+        outln!("• Repeated writes to one key coalesce into one store write");
+        outln!("• Until it is written back, a dirty entry is the only copy: flush before you stop");
+        report
+    }
+}
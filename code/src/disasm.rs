@@ -0,0 +1,640 @@
+//! x86-64 Decoder
+//!
+//! A small disassembler for the instructions compilers emit most: `mov`,
+//! `lea`, the ALU group (`add`, `sub`, `xor`, `cmp`...), `push`/`pop`,
+//! `call`, `jmp`, `jcc`, `ret`, shifts, `imul`, `movzx`/`movsx`, `cmovcc`,
+//! `setcc`, `test` and the common padding `nop`s, in their usual encodings:
+//! legacy and REX prefixes, one- and two-byte opcodes, ModRM, SIB,
+//! displacements and immediates. No SSE/AVX, x87 or VEX.
+//!
+//! x86 instructions are 1 to 15 bytes long and the length is only known by
+//! decoding, so disassembly stops at the first instruction outside the
+//! subset: past it, the decoder can't know where the next one starts.
+//! `asm` gets a complete listing by running objdump; this needs nothing but
+//! the bytes, which `function` reads from our own executable with `elf`.
+//! Output is Intel syntax, lower case, close to `objdump -M intel`.
+
+use std::fmt;
+
+use crate::elf::{Elf, ElfError};
+use crate::output::Table;
+
+#[derive(Debug)]
+pub enum DisasmError {
+    Elf(ElfError),
+    /// No sized function of that name in the symbol table
+    SymbolNotFound(String),
+}
+
+impl fmt::Display for DisasmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DisasmError::Elf(err) => write!(f, "{}", err),
+            DisasmError::SymbolNotFound(symbol) => {
+                write!(f, "symbol `{}` not found (is it #[unsafe(no_mangle)] and #[inline(never)]?)", symbol)
+            }
+        }
+    }
+}
+
+impl std::error::Error for DisasmError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DisasmError::Elf(err) => Some(err),
+            DisasmError::SymbolNotFound(_) => None,
+        }
+    }
+}
+
+impl From<ElfError> for DisasmError {
+    fn from(err: ElfError) -> Self {
+        DisasmError::Elf(err)
+    }
+}
+
+/// One decoded instruction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Instruction {
+    pub address: u64,
+    pub bytes: Vec<u8>,
+    /// `mov`, `jne`, `call`...
+    pub mnemonic: String,
+    /// Comma-separated, Intel order (destination first); may be empty
+    pub operands: String,
+    /// Where a relative jump or call goes, or what a RIP-relative operand
+    /// points at
+    pub target: Option<u64>,
+}
+
+impl Instruction {
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    pub fn is_call(&self) -> bool {
+        self.mnemonic == "call"
+    }
+
+    /// `jmp` or a conditional jump
+    pub fn is_jump(&self) -> bool {
+        self.mnemonic.starts_with('j')
+    }
+
+    /// Up to the first 8 bytes, as hex
+    pub fn hex(&self) -> String {
+        let mut hex: Vec<String> = self.bytes.iter().take(8).map(|byte| format!("{:02x}", byte)).collect();
+        if self.bytes.len() > 8 {
+            hex.push("..".to_string());
+        }
+        hex.join(" ")
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.operands.is_empty() {
+            write!(f, "{}", self.mnemonic)
+        } else {
+            write!(f, "{} {}", self.mnemonic, self.operands)
+        }
+    }
+}
+
+/// A function's instructions, decoded from the start up to its end or to
+/// the first instruction the decoder doesn't know
+#[derive(Debug, Clone)]
+pub struct Listing {
+    pub symbol: String,
+    pub address: u64,
+    pub size: u64,
+    pub instructions: Vec<Instruction>,
+}
+
+impl Listing {
+    /// Bytes decoded; less than `size` if decoding stopped early
+    pub fn decoded(&self) -> u64 {
+        self.instructions.iter().map(|instruction| instruction.len() as u64).sum()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.decoded() == self.size
+    }
+
+    pub fn calls(&self) -> usize {
+        self.instructions.iter().filter(|instruction| instruction.is_call()).count()
+    }
+}
+
+/// A header line, then offset, bytes and instruction per row
+impl fmt::Display for Listing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} ({} bytes at {:#x}):", self.symbol, self.size, self.address)?;
+        let mut table = Table::new(["offset", "bytes", "instruction"]).left(1).left(2);
+        for instruction in &self.instructions {
+            table.row([format!("+{}", instruction.address - self.address), instruction.hex(), instruction.to_string()]);
+        }
+        write!(f, "{}", table)?;
+        if !self.is_complete() {
+            writeln!(f, "  ... {} more bytes outside the decoder's subset", self.size - self.decoded())?;
+        }
+        Ok(())
+    }
+}
+
+/// Decode `symbol` (as stored, or demangled) from the running executable's
+/// file. Addresses are the file's, like objdump's.
+pub fn function(symbol: &str) -> Result<Listing, DisasmError> {
+    let elf = Elf::open(std::env::current_exe().map_err(ElfError::Io)?)?;
+    let found = elf.symbols()?.into_iter()
+        .find(|candidate| candidate.is_function() && candidate.size > 0
+            && (candidate.name == symbol || candidate.demangled() == symbol))
+        .ok_or_else(|| DisasmError::SymbolNotFound(symbol.to_string()))?;
+    let code = elf.bytes_at(found.address, found.size)
+        .ok_or_else(|| ElfError::Malformed(format!("{} lies outside the file", symbol)))?;
+    Ok(Listing { symbol: symbol.to_string(), address: found.address, size: found.size,
+                 instructions: disassemble(code, found.address) })
+}
+
+/// Decode instructions from the start of `code` until it runs out or an
+/// instruction isn't in the subset
+pub fn disassemble(code: &[u8], address: u64) -> Vec<Instruction> {
+    let mut instructions = Vec::new();
+    let mut at = 0;
+    while at < code.len() {
+        let Some(instruction) = decode(&code[at..], address + at as u64) else { break };
+        at += instruction.len();
+        instructions.push(instruction);
+    }
+    instructions
+}
+
+/// Decode the one instruction at the start of `code`, located at
+/// `address`; `None` if it's outside the subset or cut off
+pub fn decode(code: &[u8], address: u64) -> Option<Instruction> {
+    let mut decoder = Decoder { code, at: 0, rex: 0, operand_16: false, repeat: None };
+    let (mnemonic, operands, target) = decoder.instruction(address)?;
+    let bytes = code.get(..decoder.at)?.to_vec();
+    // RIP-relative operands count from the end of the instruction, which
+    // is only known now
+    let target = target.map(|target| match target {
+        Target::Absolute(address) => address,
+        Target::RipRelative(displacement) => address.wrapping_add(bytes.len() as u64).wrapping_add(displacement as u64),
+    });
+    let operands = match target {
+        Some(target) if operands.contains("rip") => format!("{}  # {:#x}", operands, target),
+        _ => operands,
+    };
+    Some(Instruction { address, bytes, mnemonic, operands, target })
+}
+
+const REGISTERS_64: [&str; 16] =
+    ["rax", "rcx", "rdx", "rbx", "rsp", "rbp", "rsi", "rdi", "r8", "r9", "r10", "r11", "r12", "r13", "r14", "r15"];
+const REGISTERS_32: [&str; 16] = ["eax", "ecx", "edx", "ebx", "esp", "ebp", "esi", "edi", "r8d", "r9d", "r10d",
+                                  "r11d", "r12d", "r13d", "r14d", "r15d"];
+const REGISTERS_16: [&str; 16] =
+    ["ax", "cx", "dx", "bx", "sp", "bp", "si", "di", "r8w", "r9w", "r10w", "r11w", "r12w", "r13w", "r14w", "r15w"];
+/// With any REX prefix, 4..=7 are the low bytes of rsp, rbp, rsi, rdi
+const REGISTERS_8: [&str; 16] = ["al", "cl", "dl", "bl", "spl", "bpl", "sil", "dil", "r8b", "r9b", "r10b", "r11b",
+                                 "r12b", "r13b", "r14b", "r15b"];
+/// Without one, they are the second bytes of rax, rcx, rdx, rbx
+const REGISTERS_8_LEGACY: [&str; 8] = ["al", "cl", "dl", "bl", "ah", "ch", "dh", "bh"];
+
+const ALU: [&str; 8] = ["add", "or", "adc", "sbb", "and", "sub", "xor", "cmp"];
+const SHIFTS: [&str; 8] = ["rol", "ror", "rcl", "rcr", "shl", "shr", "sal", "sar"];
+const CONDITIONS: [&str; 16] = ["o", "no", "b", "ae", "e", "ne", "be", "a", "s", "ns", "p", "np", "l", "ge", "le", "g"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Size {
+    Byte,
+    Word,
+    Dword,
+    Qword,
+}
+
+impl Size {
+    fn pointer(self) -> &'static str {
+        match self {
+            Size::Byte => "byte ptr ",
+            Size::Word => "word ptr ",
+            Size::Dword => "dword ptr ",
+            Size::Qword => "qword ptr ",
+        }
+    }
+}
+
+/// A ModRM-encoded operand: a register, or memory at
+/// `[base + index * scale + displacement]`
+#[derive(Debug, Clone, Copy)]
+enum Operand {
+    Register(u8),
+    Memory { base: Option<u8>, index: Option<(u8, u8)>, displacement: i32, rip: bool },
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Target {
+    Absolute(u64),
+    RipRelative(i64),
+}
+
+type Decoded = (String, String, Option<Target>);
+
+struct Decoder<'a> {
+    code: &'a [u8],
+    at: usize,
+    rex: u8,
+    operand_16: bool,
+    /// An F2 or F3 prefix
+    repeat: Option<u8>,
+}
+
+impl Decoder<'_> {
+    fn byte(&mut self) -> Option<u8> {
+        let byte = *self.code.get(self.at)?;
+        self.at += 1;
+        Some(byte)
+    }
+
+    fn take<const N: usize>(&mut self) -> Option<[u8; N]> {
+        let bytes = self.code.get(self.at..self.at + N)?.try_into().ok()?;
+        self.at += N;
+        Some(bytes)
+    }
+
+    fn i8(&mut self) -> Option<i64> {
+        Some(self.byte()? as i8 as i64)
+    }
+
+    fn i16(&mut self) -> Option<i64> {
+        Some(i16::from_le_bytes(self.take()?) as i64)
+    }
+
+    fn i32(&mut self) -> Option<i64> {
+        Some(i32::from_le_bytes(self.take()?) as i64)
+    }
+
+    fn wide(&self) -> bool {
+        self.rex & 8 != 0
+    }
+
+    /// The size of a full-width operand: 64 bits with REX.W, 16 with the
+    /// 0x66 prefix, else 32
+    fn size(&self) -> Size {
+        if self.wide() {
+            Size::Qword
+        } else if self.operand_16 {
+            Size::Word
+        } else {
+            Size::Dword
+        }
+    }
+
+    /// An immediate of a full-width operand: at most 32 bits, sign-extended
+    /// to 64
+    fn immediate(&mut self, size: Size) -> Option<i64> {
+        match size {
+            Size::Byte => self.i8(),
+            Size::Word => self.i16(),
+            Size::Dword | Size::Qword => self.i32(),
+        }
+    }
+
+    fn register(&self, number: u8, size: Size) -> &'static str {
+        let number = number as usize;
+        match size {
+            Size::Qword => REGISTERS_64[number],
+            Size::Dword => REGISTERS_32[number],
+            Size::Word => REGISTERS_16[number],
+            Size::Byte if self.rex == 0 && number < 8 => REGISTERS_8_LEGACY[number],
+            Size::Byte => REGISTERS_8[number],
+        }
+    }
+
+    /// Read a ModRM byte (and any SIB and displacement): the `reg` field,
+    /// extended by REX.R, and the `r/m` operand
+    fn modrm(&mut self) -> Option<(u8, Operand)> {
+        let modrm = self.byte()?;
+        let (mode, reg, rm) = (modrm >> 6, (modrm >> 3) & 7 | (self.rex & 4) << 1, modrm & 7);
+        if mode == 3 {
+            return Some((reg, Operand::Register(rm | (self.rex & 1) << 3)));
+        }
+        let (mut base, mut index, mut rip) = (Some(rm | (self.rex & 1) << 3), None, false);
+        let mut absolute = false;
+        if rm == 4 {
+            let sib = self.byte()?;
+            let scaled = (sib >> 3) & 7 | (self.rex & 2) << 2;
+            if scaled != 4 {
+                index = Some((scaled, 1 << (sib >> 6)));
+            }
+            base = Some(sib & 7 | (self.rex & 1) << 3);
+            if sib & 7 == 5 && mode == 0 {
+                base = None;
+                absolute = true;
+            }
+        } else if rm == 5 && mode == 0 {
+            base = None;
+            rip = true;
+        }
+        let displacement = match mode {
+            0 if rip || absolute => self.i32()?,
+            1 => self.i8()?,
+            2 => self.i32()?,
+            _ => 0,
+        } as i32;
+        Some((reg, Operand::Memory { base, index, displacement, rip }))
+    }
+
+    fn format(&self, operand: Operand, size: Option<Size>) -> String {
+        match operand {
+            Operand::Register(number) => self.register(number, size.unwrap_or(Size::Qword)).to_string(),
+            Operand::Memory { base, index, displacement, rip } => {
+                let mut terms = Vec::new();
+                if rip {
+                    terms.push("rip".to_string());
+                }
+                if let Some(base) = base {
+                    terms.push(REGISTERS_64[base as usize].to_string());
+                }
+                if let Some((index, scale)) = index {
+                    terms.push(format!("{}*{}", REGISTERS_64[index as usize], scale));
+                }
+                let mut address = terms.join("+");
+                if displacement != 0 || address.is_empty() {
+                    let sign = if displacement < 0 { "-" } else if address.is_empty() { "" } else { "+" };
+                    address.push_str(&format!("{}{:#x}", sign, (displacement as i64).unsigned_abs()));
+                }
+                format!("{}[{}]", size.map_or("", Size::pointer), address)
+            }
+        }
+    }
+
+    /// Memory operands can point RIP-relative at data: remember where
+    fn rip_target(operand: Operand) -> Option<Target> {
+        match operand {
+            Operand::Memory { rip: true, displacement, .. } => Some(Target::RipRelative(displacement as i64)),
+            _ => None,
+        }
+    }
+
+    fn hex(value: i64) -> String {
+        if value < 0 { format!("-{:#x}", value.unsigned_abs()) } else { format!("{:#x}", value) }
+    }
+
+    /// `mnemonic r/m, reg` or `mnemonic reg, r/m`
+    fn binary(&mut self, mnemonic: &str, size: Size, reg_first: bool) -> Option<Decoded> {
+        let (reg, rm) = self.modrm()?;
+        let (reg_text, rm_text) = (self.register(reg, size).to_string(), self.format(rm, Some(size)));
+        let operands = if reg_first { format!("{},{}", reg_text, rm_text) } else { format!("{},{}", rm_text, reg_text) };
+        Some((mnemonic.to_string(), operands, Self::rip_target(rm)))
+    }
+
+    /// `mnemonic r/m, imm`, the mnemonic picked by the ModRM reg field
+    fn group(&mut self, names: &[&str; 8], size: Size, immediate: Option<Size>) -> Option<Decoded> {
+        let (reg, rm) = self.modrm()?;
+        let mut operands = self.format(rm, Some(size));
+        if let Some(immediate) = immediate {
+            operands.push_str(&format!(",{}", Self::hex(self.immediate(immediate)?)));
+        }
+        Some((names[(reg & 7) as usize].to_string(), operands, Self::rip_target(rm)))
+    }
+
+    fn relative(&mut self, mnemonic: String, wide: bool, address: u64) -> Option<Decoded> {
+        let displacement = if wide { self.i32()? } else { self.i8()? };
+        let target = address.wrapping_add(self.at as u64).wrapping_add(displacement as u64);
+        Some((mnemonic, format!("{:#x}", target), Some(Target::Absolute(target))))
+    }
+
+    fn instruction(&mut self, address: u64) -> Option<Decoded> {
+        let mut opcode = self.byte()?;
+        loop {
+            match opcode {
+                0x66 => self.operand_16 = true,
+                0xF2 | 0xF3 => self.repeat = Some(opcode),
+                // Segment overrides: only fs and gs mean anything in 64-bit mode
+                0x2E | 0x3E | 0x64 | 0x65 => {}
+                _ => break,
+            }
+            opcode = self.byte()?;
+        }
+        if opcode & 0xF0 == 0x40 {
+            self.rex = opcode;
+            opcode = self.byte()?;
+        }
+        let size = self.size();
+        let text = |mnemonic: &str, operands: String| Some((mnemonic.to_string(), operands, None));
+        match opcode {
+            0x00..=0x3F if opcode & 7 < 6 => {
+                let mnemonic = ALU[(opcode >> 3) as usize];
+                match opcode & 7 {
+                    0 => self.binary(mnemonic, Size::Byte, false),
+                    1 => self.binary(mnemonic, size, false),
+                    2 => self.binary(mnemonic, Size::Byte, true),
+                    3 => self.binary(mnemonic, size, true),
+                    4 => text(mnemonic, format!("al,{}", Self::hex(self.i8()?))),
+                    _ => {
+                        let register = self.register(0, size);
+                        text(mnemonic, format!("{},{}", register, Self::hex(self.immediate(size)?)))
+                    }
+                }
+            }
+            0x50..=0x57 => text("push", REGISTERS_64[(opcode & 7 | (self.rex & 1) << 3) as usize].to_string()),
+            0x58..=0x5F => text("pop", REGISTERS_64[(opcode & 7 | (self.rex & 1) << 3) as usize].to_string()),
+            0x63 => {
+                let (reg, rm) = self.modrm()?;
+                let operands = format!("{},{}", self.register(reg, size), self.format(rm, Some(Size::Dword)));
+                Some(("movsxd".to_string(), operands, Self::rip_target(rm)))
+            }
+            0x68 => text("push", Self::hex(self.i32()?)),
+            0x6A => text("push", Self::hex(self.i8()?)),
+            0x69 | 0x6B => {
+                let (reg, rm) = self.modrm()?;
+                let immediate = if opcode == 0x6B { self.i8()? } else { self.immediate(size)? };
+                let operands = format!("{},{},{}", self.register(reg, size), self.format(rm, Some(size)), Self::hex(immediate));
+                Some(("imul".to_string(), operands, Self::rip_target(rm)))
+            }
+            0x70..=0x7F => self.relative(format!("j{}", CONDITIONS[(opcode & 0xF) as usize]), false, address),
+            0x80 => self.group(&ALU, Size::Byte, Some(Size::Byte)),
+            0x81 => self.group(&ALU, size, Some(size)),
+            0x83 => self.group(&ALU, size, Some(Size::Byte)),
+            0x84 => self.binary("test", Size::Byte, false),
+            0x85 => self.binary("test", size, false),
+            0x86 => self.binary("xchg", Size::Byte, false),
+            0x87 => self.binary("xchg", size, false),
+            0x88 => self.binary("mov", Size::Byte, false),
+            0x89 => self.binary("mov", size, false),
+            0x8A => self.binary("mov", Size::Byte, true),
+            0x8B => self.binary("mov", size, true),
+            0x8D => {
+                let (reg, rm) = self.modrm()?;
+                let operands = format!("{},{}", self.register(reg, size), self.format(rm, None));
+                Some(("lea".to_string(), operands, Self::rip_target(rm)))
+            }
+            0x90 if self.rex & 1 == 0 => text(if self.repeat == Some(0xF3) { "pause" } else { "nop" }, String::new()),
+            0x98 => text(["cwde", "cdqe"][self.wide() as usize], String::new()),
+            0x99 => text(["cdq", "cqo"][self.wide() as usize], String::new()),
+            0xA8 => text("test", format!("al,{}", Self::hex(self.i8()?))),
+            0xA9 => {
+                let register = self.register(0, size);
+                text("test", format!("{},{}", register, Self::hex(self.immediate(size)?)))
+            }
+            0xB0..=0xB7 => {
+                let register = self.register(opcode & 7 | (self.rex & 1) << 3, Size::Byte);
+                text("mov", format!("{},{}", register, Self::hex(self.i8()? & 0xFF)))
+            }
+            0xB8..=0xBF => {
+                let register = self.register(opcode & 7 | (self.rex & 1) << 3, size);
+                if self.wide() {
+                    text("movabs", format!("{},{:#x}", register, u64::from_le_bytes(self.take()?)))
+                } else {
+                    let immediate = if size == Size::Word { self.i16()? & 0xFFFF } else { self.i32()? & 0xFFFF_FFFF };
+                    text("mov", format!("{},{:#x}", register, immediate))
+                }
+            }
+            0xC0 => self.group(&SHIFTS, Size::Byte, Some(Size::Byte)),
+            0xC1 => self.group(&SHIFTS, size, Some(Size::Byte)),
+            0xD0..=0xD3 => {
+                let operand_size = if opcode & 1 == 0 { Size::Byte } else { size };
+                let (mnemonic, operands, target) = self.group(&SHIFTS, operand_size, None)?;
+                let count = if opcode < 0xD2 { "1" } else { "cl" };
+                Some((mnemonic, format!("{},{}", operands, count), target))
+            }
+            0xC2 => text("ret", Self::hex(self.i16()? & 0xFFFF)),
+            0xC3 => text("ret", String::new()),
+            0xC6 | 0xC7 => {
+                let operand_size = if opcode == 0xC6 { Size::Byte } else { size };
+                let (reg, rm) = self.modrm()?;
+                if reg & 7 != 0 {
+                    return None;
+                }
+                let immediate = self.immediate(operand_size)?;
+                Some(("mov".to_string(), format!("{},{}", self.format(rm, Some(operand_size)), Self::hex(immediate)),
+                      Self::rip_target(rm)))
+            }
+            0xCC => text("int3", String::new()),
+            0xE8 => self.relative("call".to_string(), true, address),
+            0xE9 => self.relative("jmp".to_string(), true, address),
+            0xEB => self.relative("jmp".to_string(), false, address),
+            0xF6 | 0xF7 => {
+                let operand_size = if opcode == 0xF6 { Size::Byte } else { size };
+                let (reg, rm) = self.modrm()?;
+                let names = ["test", "test", "not", "neg", "mul", "imul", "div", "idiv"];
+                let mut operands = self.format(rm, Some(operand_size));
+                if reg & 7 < 2 {
+                    operands.push_str(&format!(",{}", Self::hex(self.immediate(operand_size)?)));
+                }
+                Some((names[(reg & 7) as usize].to_string(), operands, Self::rip_target(rm)))
+            }
+            0xFE | 0xFF => {
+                let (reg, rm) = self.modrm()?;
+                let (mnemonic, operand_size) = match (opcode, reg & 7) {
+                    (0xFE, 0) => ("inc", Size::Byte),
+                    (0xFE, 1) => ("dec", Size::Byte),
+                    (0xFF, 0) => ("inc", size),
+                    (0xFF, 1) => ("dec", size),
+                    // Calls, jumps and pushes through memory or a register
+                    // are always 64-bit
+                    (0xFF, 2) => ("call", Size::Qword),
+                    (0xFF, 4) => ("jmp", Size::Qword),
+                    (0xFF, 6) => ("push", Size::Qword),
+                    _ => return None,
+                };
+                Some((mnemonic.to_string(), self.format(rm, Some(operand_size)), Self::rip_target(rm)))
+            }
+            0x0F => self.two_byte(address),
+            _ => None,
+        }
+    }
+
+    /// Opcodes after the 0x0F escape
+    fn two_byte(&mut self, address: u64) -> Option<Decoded> {
+        let opcode = self.byte()?;
+        let size = self.size();
+        let text = |mnemonic: &str| Some((mnemonic.to_string(), String::new(), None));
+        match opcode {
+            0x05 => text("syscall"),
+            0x0B => text("ud2"),
+            0x1E if self.repeat == Some(0xF3) => match self.byte()? {
+                0xFA => text("endbr64"),
+                0xFB => text("endbr32"),
+                _ => None,
+            },
+            0x1F => {
+                let (_, rm) = self.modrm()?;
+                Some(("nop".to_string(), self.format(rm, Some(size)), None))
+            }
+            0x40..=0x4F => self.binary(&format!("cmov{}", CONDITIONS[(opcode & 0xF) as usize]), size, true),
+            0x80..=0x8F => self.relative(format!("j{}", CONDITIONS[(opcode & 0xF) as usize]), true, address),
+            0x90..=0x9F => {
+                let (_, rm) = self.modrm()?;
+                Some((format!("set{}", CONDITIONS[(opcode & 0xF) as usize]), self.format(rm, Some(Size::Byte)),
+                      Self::rip_target(rm)))
+            }
+            0xA2 => text("cpuid"),
+            0xAF => self.binary("imul", size, true),
+            0xB6 | 0xB7 | 0xBE | 0xBF => {
+                let (reg, rm) = self.modrm()?;
+                let from = if opcode & 1 == 0 { Size::Byte } else { Size::Word };
+                let mnemonic = if opcode < 0xBE { "movzx" } else { "movsx" };
+                let operands = format!("{},{}", self.register(reg, size), self.format(rm, Some(from)));
+                Some((mnemonic.to_string(), operands, Self::rip_target(rm)))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(bytes: &[u8]) -> String {
+        let instruction = decode(bytes, 0x1000).expect("decodes");
+        assert_eq!(instruction.len(), bytes.len(), "length of {}", instruction);
+        instruction.to_string()
+    }
+
+    #[test]
+    fn decodes_moves_and_arithmetic() {
+        assert_eq!(text(&[0x48, 0x89, 0xC8]), "mov rax,rcx");
+        assert_eq!(text(&[0x89, 0xF8]), "mov eax,edi");
+        assert_eq!(text(&[0x48, 0x01, 0xF7]), "add rdi,rsi");
+        assert_eq!(text(&[0x48, 0x8B, 0x44, 0x24, 0x08]), "mov rax,qword ptr [rsp+0x8]");
+        assert_eq!(text(&[0x40, 0x88, 0x3E]), "mov byte ptr [rsi],dil");
+        assert_eq!(text(&[0x66, 0x89, 0x7E, 0x02]), "mov word ptr [rsi+0x2],di");
+        assert_eq!(text(&[0x48, 0x8D, 0x04, 0x37]), "lea rax,[rdi+rsi*1]");
+        assert_eq!(text(&[0x4C, 0x8D, 0x44, 0xCA, 0xF0]), "lea r8,[rdx+rcx*8-0x10]");
+        assert_eq!(text(&[0xB8, 0x3E, 0x00, 0x00, 0x00]), "mov eax,0x3e");
+        assert_eq!(text(&[0x48, 0xB8, 1, 2, 3, 4, 5, 6, 7, 8]), "movabs rax,0x807060504030201");
+        assert_eq!(text(&[0x48, 0x83, 0xEC, 0x18]), "sub rsp,0x18");
+        assert_eq!(text(&[0x31, 0xC0]), "xor eax,eax");
+        assert_eq!(text(&[0x48, 0x0F, 0xAF, 0xC7]), "imul rax,rdi");
+        assert_eq!(text(&[0x0F, 0xB6, 0xC7]), "movzx eax,bh");
+        assert_eq!(text(&[0x40, 0x0F, 0xB6, 0xC7]), "movzx eax,dil");
+        assert_eq!(text(&[0x41, 0x55]), "push r13");
+        assert_eq!(text(&[0xF3, 0x0F, 0x1E, 0xFA]), "endbr64");
+    }
+
+    #[test]
+    fn resolves_relative_targets() {
+        let call = decode(&[0xE8, 0x10, 0x00, 0x00, 0x00], 0x1000).unwrap();
+        assert_eq!((call.to_string(), call.target), ("call 0x1015".to_string(), Some(0x1015)));
+        let back = decode(&[0xEB, 0xFE], 0x1000).unwrap();
+        assert_eq!(back.target, Some(0x1000));
+        let jne = decode(&[0x0F, 0x85, 0x00, 0x01, 0x00, 0x00], 0x1000).unwrap();
+        assert_eq!(jne.to_string(), "jne 0x1106");
+        let load = decode(&[0x48, 0x8B, 0x05, 0x00, 0x10, 0x00, 0x00], 0x1000).unwrap();
+        assert_eq!((load.operands.as_str(), load.target), ("rax,qword ptr [rip+0x1000]  # 0x2007", Some(0x2007)));
+    }
+
+    #[test]
+    fn stops_outside_the_subset() {
+        // ret; then an SSE instruction (pxor xmm0,xmm0), then ret
+        let code = [0xC3, 0x66, 0x0F, 0xEF, 0xC0, 0xC3];
+        let instructions = disassemble(&code, 0);
+        assert_eq!(instructions.len(), 1);
+        assert_eq!(decode(&[0x48, 0x8B], 0), None, "cut off");
+    }
+}
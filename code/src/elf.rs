@@ -0,0 +1,478 @@
+//! ELF Reader
+//!
+//! A hand-rolled parser for 64-bit ELF files, the format of executables,
+//! shared libraries and object files on Linux and most other Unix systems.
+//! It reads the three tables a loader or debugger starts from:
+//!
+//! - the file header: class, byte order, machine and entry point
+//! - program headers (segments): what the kernel maps into memory, where,
+//!   with which permissions
+//! - section headers: the linker's finer view (`.text`, `.data`, `.bss`,
+//!   `.symtab`...), each placed inside a segment
+//!
+//! and the symbol table, which names the functions and statics in them,
+//! plus the PLT relocations a dynamic linker patches at the first call.
+//! Only reading, only ELF64, either byte order. `asm` gets the same facts by
+//! running binutils; this is what those tools do underneath.
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+pub const ET_EXEC: u16 = 2;
+/// Shared objects, and position-independent executables
+pub const ET_DYN: u16 = 3;
+
+pub const PT_LOAD: u32 = 1;
+pub const PF_X: u32 = 1;
+pub const PF_W: u32 = 2;
+pub const PF_R: u32 = 4;
+
+pub const SHT_SYMTAB: u32 = 2;
+pub const SHT_STRTAB: u32 = 3;
+pub const SHT_RELA: u32 = 4;
+pub const SHT_NOBITS: u32 = 8;
+pub const SHT_DYNSYM: u32 = 11;
+pub const SHF_WRITE: u64 = 1;
+pub const SHF_ALLOC: u64 = 2;
+pub const SHF_EXECINSTR: u64 = 4;
+
+pub const STT_OBJECT: u8 = 1;
+pub const STT_FUNC: u8 = 2;
+
+const HEADER_SIZE: usize = 64;
+const SEGMENT_SIZE: usize = 56;
+const SECTION_SIZE: usize = 64;
+const SYMBOL_SIZE: usize = 24;
+const RELA_SIZE: usize = 24;
+
+#[derive(Debug)]
+pub enum ElfError {
+    Io(io::Error),
+    /// Not an ELF file; names the format it looks like, if it's a known one
+    NotElf(&'static str),
+    /// ELF, but not a kind this reader handles (32-bit)
+    Unsupported(String),
+    /// A table or string that points outside the file
+    Malformed(String),
+}
+
+impl fmt::Display for ElfError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ElfError::Io(err) => write!(f, "couldn't read the file: {}", err),
+            ElfError::NotElf(format) => write!(f, "not an ELF file ({})", format),
+            ElfError::Unsupported(what) => write!(f, "unsupported ELF file: {}", what),
+            ElfError::Malformed(what) => write!(f, "malformed ELF file: {}", what),
+        }
+    }
+}
+
+impl std::error::Error for ElfError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ElfError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ElfError {
+    fn from(err: io::Error) -> Self {
+        ElfError::Io(err)
+    }
+}
+
+/// A program header: a range of the file the loader maps into memory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Segment {
+    pub kind: u32,
+    /// `PF_R`, `PF_W`, `PF_X`
+    pub flags: u32,
+    pub offset: u64,
+    pub address: u64,
+    /// Bytes taken from the file
+    pub file_size: u64,
+    /// Bytes in memory; anything past `file_size` is zero-filled (`.bss`)
+    pub memory_size: u64,
+    pub align: u64,
+}
+
+impl Segment {
+    /// Permissions as in /proc/self/maps, e.g. `r-x`
+    pub fn permissions(&self) -> String {
+        let flag = |bit: u32, c: char| if self.flags & bit != 0 { c } else { '-' };
+        [flag(PF_R, 'r'), flag(PF_W, 'w'), flag(PF_X, 'x')].iter().collect()
+    }
+
+    pub fn contains(&self, address: u64) -> bool {
+        (self.address..self.address + self.memory_size).contains(&address)
+    }
+}
+
+/// A section header: one named piece of the file, as the linker sees it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Section {
+    pub name: String,
+    pub kind: u32,
+    pub flags: u64,
+    /// Where it is loaded, or 0 if it isn't
+    pub address: u64,
+    pub offset: u64,
+    pub size: u64,
+    /// For a symbol table, the section holding its names
+    pub link: u32,
+}
+
+impl Section {
+    /// Whether the section occupies bytes in the file (`.bss` doesn't)
+    pub fn in_file(&self) -> bool {
+        self.kind != SHT_NOBITS
+    }
+
+    pub fn is_loaded(&self) -> bool {
+        self.flags & SHF_ALLOC != 0
+    }
+}
+
+/// An entry of the symbol table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbol {
+    /// As stored: mangled for Rust and C++
+    pub name: String,
+    pub address: u64,
+    pub size: u64,
+    /// `STT_FUNC`, `STT_OBJECT`...
+    pub kind: u8,
+    /// Index of the section it lives in
+    pub section: u16,
+}
+
+impl Symbol {
+    pub fn is_function(&self) -> bool {
+        self.kind == STT_FUNC
+    }
+
+    /// The name demangled if it uses Rust's legacy scheme, else as stored
+    pub fn demangled(&self) -> String {
+        demangle(&self.name).unwrap_or_else(|| self.name.clone())
+    }
+}
+
+/// An entry of `.rela.plt`: a GOT slot the dynamic linker fills with a
+/// symbol's address
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Relocation {
+    /// Where the slot is (a virtual address, like a symbol's)
+    pub address: u64,
+    /// `R_X86_64_JUMP_SLOT`, `R_AARCH64_JUMP_SLOT`...: machine-specific
+    pub kind: u32,
+    /// The symbol whose address goes in the slot
+    pub symbol: String,
+}
+
+/// A parsed ELF64 file, kept in memory whole
+pub struct Elf {
+    data: Vec<u8>,
+    big_endian: bool,
+    /// `ET_EXEC`, `ET_DYN`...
+    pub kind: u16,
+    /// `EM_*`: 62 for x86-64, 183 for AArch64
+    pub machine: u16,
+    pub entry: u64,
+    pub segments: Vec<Segment>,
+    pub sections: Vec<Section>,
+}
+
+impl Elf {
+    pub fn open(path: impl AsRef<Path>) -> Result<Elf, ElfError> {
+        Elf::parse(fs::read(path)?)
+    }
+
+    pub fn parse(data: Vec<u8>) -> Result<Elf, ElfError> {
+        if data.len() < 4 || data[..4] != *b"\x7fELF" {
+            return Err(ElfError::NotElf(guess_format(&data)));
+        }
+        if data.len() < HEADER_SIZE {
+            return Err(ElfError::Malformed("file shorter than its header".to_string()));
+        }
+        if data[4] != 2 {
+            return Err(ElfError::Unsupported(format!("class {} (only 64-bit is read)", data[4])));
+        }
+        let big_endian = match data[5] {
+            1 => false,
+            2 => true,
+            other => return Err(ElfError::Unsupported(format!("byte order {}", other))),
+        };
+        let mut elf = Elf { data, big_endian, kind: 0, machine: 0, entry: 0, segments: Vec::new(), sections: Vec::new() };
+        elf.kind = elf.u16(16)?;
+        elf.machine = elf.u16(18)?;
+        elf.entry = elf.u64(24)?;
+        let (segment_table, section_table) = (elf.u64(32)? as usize, elf.u64(40)? as usize);
+        let (segment_count, section_count) = (elf.u16(56)? as usize, elf.u16(60)? as usize);
+        let names_index = elf.u16(62)? as usize;
+
+        for i in 0..segment_count {
+            let at = segment_table + i * SEGMENT_SIZE;
+            elf.segments.push(Segment {
+                kind: elf.u32(at)?,
+                flags: elf.u32(at + 4)?,
+                offset: elf.u64(at + 8)?,
+                address: elf.u64(at + 16)?,
+                file_size: elf.u64(at + 32)?,
+                memory_size: elf.u64(at + 40)?,
+                align: elf.u64(at + 48)?,
+            });
+        }
+        let mut name_offsets = Vec::with_capacity(section_count);
+        for i in 0..section_count {
+            let at = section_table + i * SECTION_SIZE;
+            name_offsets.push(elf.u32(at)? as usize);
+            elf.sections.push(Section {
+                name: String::new(),
+                kind: elf.u32(at + 4)?,
+                flags: elf.u64(at + 8)?,
+                address: elf.u64(at + 16)?,
+                offset: elf.u64(at + 24)?,
+                size: elf.u64(at + 32)?,
+                link: elf.u32(at + 40)?,
+            });
+        }
+        // Section names live in a string table that is itself a section
+        if let Some(names) = elf.sections.get(names_index).cloned() {
+            for (section, offset) in elf.sections.iter_mut().zip(name_offsets) {
+                section.name = string_at(&elf.data, &names, offset)?;
+            }
+        }
+        Ok(elf)
+    }
+
+    pub fn section(&self, name: &str) -> Option<&Section> {
+        self.sections.iter().find(|section| section.name == name)
+    }
+
+    /// The section's bytes in the file: none for `.bss`
+    pub fn section_data(&self, section: &Section) -> Result<&[u8], ElfError> {
+        if !section.in_file() {
+            return Ok(&[]);
+        }
+        self.data.get(section.offset as usize..(section.offset + section.size) as usize)
+            .ok_or_else(|| ElfError::Malformed(format!("section {} past the end of the file", section.name)))
+    }
+
+    /// The loadable segment an address falls in
+    pub fn segment_of(&self, address: u64) -> Option<&Segment> {
+        self.segments.iter().find(|segment| segment.kind == PT_LOAD && segment.contains(address))
+    }
+
+    /// The loaded section an address falls in
+    pub fn section_of(&self, address: u64) -> Option<&Section> {
+        self.sections.iter().find(|section| {
+            section.is_loaded() && (section.address..section.address + section.size).contains(&address)
+        })
+    }
+
+    /// The file bytes loaded at `address`, if all `len` of them come from
+    /// the file
+    pub fn bytes_at(&self, address: u64, len: u64) -> Option<&[u8]> {
+        let segment = self.segment_of(address)?;
+        let start = address - segment.address;
+        if start + len > segment.file_size {
+            return None;
+        }
+        let offset = (segment.offset + start) as usize;
+        self.data.get(offset..offset + len as usize)
+    }
+
+    /// The full symbol table (`.symtab`), or just the dynamic symbols if
+    /// the file was stripped
+    pub fn symbols(&self) -> Result<Vec<Symbol>, ElfError> {
+        let Some(table) = self.sections.iter().find(|s| s.kind == SHT_SYMTAB)
+            .or_else(|| self.sections.iter().find(|s| s.kind == SHT_DYNSYM)) else {
+            return Ok(Vec::new());
+        };
+        // Entry 0 is the reserved null symbol
+        (1..(table.size as usize) / SYMBOL_SIZE).map(|i| self.symbol(table, i)).collect()
+    }
+
+    /// The relocations in `.rela.plt`, one per function called through the
+    /// PLT; empty for a statically linked file
+    pub fn plt_relocations(&self) -> Result<Vec<Relocation>, ElfError> {
+        let Some(table) = self.section(".rela.plt").filter(|s| s.kind == SHT_RELA) else {
+            return Ok(Vec::new());
+        };
+        let symbols = self.sections.get(table.link as usize).filter(|symbols| symbols.kind == SHT_DYNSYM)
+            .ok_or_else(|| ElfError::Malformed(format!("{} has no symbol table", table.name)))?;
+        (0..(table.size as usize) / RELA_SIZE).map(|i| {
+            let at = table.offset as usize + i * RELA_SIZE;
+            let info = self.u64(at + 8)?;
+            let symbol = match (info >> 32) as usize {
+                0 => String::new(),
+                index => self.symbol(symbols, index)?.name,
+            };
+            Ok(Relocation { address: self.u64(at)?, kind: info as u32, symbol })
+        }).collect()
+    }
+
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub fn is_big_endian(&self) -> bool {
+        self.big_endian
+    }
+
+    fn symbol(&self, table: &Section, index: usize) -> Result<Symbol, ElfError> {
+        let names = self.sections.get(table.link as usize).filter(|names| names.kind == SHT_STRTAB)
+            .ok_or_else(|| ElfError::Malformed(format!("{} has no string table", table.name)))?;
+        let at = table.offset as usize + index * SYMBOL_SIZE;
+        Ok(Symbol {
+            name: string_at(&self.data, names, self.u32(at)? as usize)?,
+            kind: self.byte(at + 4)? & 0xF,
+            section: self.u16(at + 6)?,
+            address: self.u64(at + 8)?,
+            size: self.u64(at + 16)?,
+        })
+    }
+
+    fn bytes<const N: usize>(&self, at: usize) -> Result<[u8; N], ElfError> {
+        self.data.get(at..at + N).map(|bytes| bytes.try_into().expect("N bytes"))
+            .ok_or_else(|| ElfError::Malformed(format!("read of {} bytes at {:#x} past the end of the file", N, at)))
+    }
+
+    fn byte(&self, at: usize) -> Result<u8, ElfError> {
+        Ok(self.bytes::<1>(at)?[0])
+    }
+
+    fn u16(&self, at: usize) -> Result<u16, ElfError> {
+        let bytes = self.bytes(at)?;
+        Ok(if self.big_endian { u16::from_be_bytes(bytes) } else { u16::from_le_bytes(bytes) })
+    }
+
+    fn u32(&self, at: usize) -> Result<u32, ElfError> {
+        let bytes = self.bytes(at)?;
+        Ok(if self.big_endian { u32::from_be_bytes(bytes) } else { u32::from_le_bytes(bytes) })
+    }
+
+    fn u64(&self, at: usize) -> Result<u64, ElfError> {
+        let bytes = self.bytes(at)?;
+        Ok(if self.big_endian { u64::from_be_bytes(bytes) } else { u64::from_le_bytes(bytes) })
+    }
+}
+
+/// The NUL-terminated string at `offset` in a string table section
+fn string_at(data: &[u8], table: &Section, offset: usize) -> Result<String, ElfError> {
+    let start = table.offset as usize + offset;
+    let end = (table.offset + table.size) as usize;
+    let bytes = data.get(start..end.min(data.len()))
+        .ok_or_else(|| ElfError::Malformed(format!("name at {} outside {}", offset, table.name)))?;
+    let len = bytes.iter().position(|&byte| byte == 0).unwrap_or(bytes.len());
+    Ok(String::from_utf8_lossy(&bytes[..len]).into_owned())
+}
+
+/// What a non-ELF file looks like, from its first bytes
+fn guess_format(data: &[u8]) -> &'static str {
+    match data.get(..4) {
+        Some([0xCF, 0xFA, 0xED, 0xFE]) | Some([0xCE, 0xFA, 0xED, 0xFE]) => "Mach-O",
+        Some([0xCA, 0xFE, 0xBA, 0xBE]) => "Mach-O universal binary",
+        Some([b'M', b'Z', ..]) => "PE/COFF, a Windows executable",
+        Some([0x00, b'a', b's', b'm']) => "WebAssembly",
+        _ => "unknown format",
+    }
+}
+
+/// The display name of an `EM_*` machine number
+pub fn machine_name(machine: u16) -> &'static str {
+    match machine {
+        3 => "x86",
+        40 => "ARM",
+        62 => "x86-64",
+        183 => "AArch64",
+        243 => "RISC-V",
+        _ => "other",
+    }
+}
+
+/// Demangle a symbol in Rust's legacy scheme, as C++'s Itanium ABI spells
+/// nested names: `_ZN` then length-prefixed path segments then `E`, the
+/// last segment a hash (`h` and 16 hex digits), with `$..$` escapes for
+/// characters symbols can't hold. Returns `None` for other names, v0
+/// symbols (`_R...`) included.
+pub fn demangle(symbol: &str) -> Option<String> {
+    let mut rest = symbol.strip_prefix("_ZN").or_else(|| symbol.strip_prefix("__ZN"))?;
+    let mut segments = Vec::new();
+    while !rest.starts_with('E') {
+        let digits = rest.find(|c: char| !c.is_ascii_digit())?;
+        let len: usize = rest[..digits].parse().ok()?;
+        let segment = rest.get(digits..digits + len)?;
+        segments.push(segment);
+        rest = &rest[digits + len..];
+    }
+    if let Some(hash) = segments.last()
+        && hash.len() == 17
+        && hash.starts_with('h')
+        && hash[1..].chars().all(|c| c.is_ascii_hexdigit())
+    {
+        segments.pop();
+    }
+    let segments: Vec<String> = segments.into_iter().map(unescape).collect::<Option<_>>()?;
+    Some(segments.join("::"))
+}
+
+fn unescape(segment: &str) -> Option<String> {
+    let mut segment = segment;
+    if segment.starts_with("_$") {
+        segment = &segment[1..];
+    }
+    let mut out = String::with_capacity(segment.len());
+    let mut rest = segment;
+    while let Some(c) = rest.chars().next() {
+        if let Some(dots) = rest.strip_prefix("..") {
+            out.push_str("::");
+            rest = dots;
+        } else if let Some(escape) = rest.strip_prefix('$') {
+            let end = escape.find('$')?;
+            out.push(match &escape[..end] {
+                "SP" => '@',
+                "BP" => '*',
+                "RF" => '&',
+                "LT" => '<',
+                "GT" => '>',
+                "LP" => '(',
+                "RP" => ')',
+                "C" => ',',
+                code => char::from_u32(u32::from_str_radix(code.strip_prefix('u')?, 16).ok()?)?,
+            });
+            rest = &escape[end + 1..];
+        } else {
+            out.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn demangles_legacy_symbols() {
+        assert_eq!(demangle("_ZN4core3fmt5write17h0123456789abcdefE").as_deref(), Some("core::fmt::write"));
+        assert_eq!(
+            demangle("_ZN60_$LT$alloc..string..String$u20$as$u20$core..fmt..Display$GT$3fmt17h0123456789abcdefE")
+                .as_deref(),
+            Some("<alloc::string::String as core::fmt::Display>::fmt"),
+        );
+        assert_eq!(demangle("_ZN3foo28_$u7b$$u7b$closure$u7d$$u7d$E").as_deref(), Some("foo::{{closure}}"));
+        assert_eq!(demangle("main"), None);
+        assert_eq!(demangle("_RNvCs1234_7mycrate3foo"), None);
+    }
+
+    #[test]
+    fn names_other_formats() {
+        assert!(matches!(Elf::parse(b"MZ\x90\x00".to_vec()), Err(ElfError::NotElf("PE/COFF, a Windows executable"))));
+        assert!(matches!(Elf::parse(b"\x7fELF\x01\x01\x01".to_vec()), Err(ElfError::Malformed(_))));
+    }
+}
@@ -0,0 +1,329 @@
+//! The Benchmark Environment
+//!
+//! A timing means little without the machine behind it: the same loop is
+//! twice as fast on another CPU, in a release build, or with the frequency
+//! governor set to `performance` instead of `powersave`. `Environment`
+//! collects what shapes the numbers (CPU model, cores, cache sizes, RAM, OS,
+//! compiler, optimization level, governor) so the runner can print it before
+//! the first benchmark and store it with the results, where `baseline`
+//! points out what changed between two runs.
+//!
+//! Hardware details come from `/proc` and `/sys` on Linux and `sysctl` on
+//! macOS; anything a platform doesn't expose is left out rather than guessed.
+
+use std::fmt;
+use std::thread;
+
+use serde::{Deserialize, Serialize};
+
+use crate::plot::format_bytes;
+use crate::{out, output};
+
+/// The machine and build a set of results came from. Fields after `version`
+/// were added later and default to empty when reading older results.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Environment {
+    pub os: String,
+    pub arch: String,
+    /// Hardware threads available to the process
+    pub cpus: usize,
+    /// `debug` or `release`: the demos are built with the runner's profile
+    pub profile: String,
+    /// Version of this crate
+    pub version: String,
+    /// Distribution or OS release, and the kernel version where there is one
+    #[serde(default)]
+    pub os_version: Option<String>,
+    #[serde(default)]
+    pub cpu_model: Option<String>,
+    #[serde(default)]
+    pub physical_cores: Option<usize>,
+    /// The caches of the first core, smallest level first
+    #[serde(default)]
+    pub caches: Vec<Cache>,
+    /// Installed RAM
+    #[serde(default)]
+    pub memory_bytes: Option<u64>,
+    /// `rustc -V` of the compiler that built the library
+    #[serde(default)]
+    pub rustc: String,
+    /// The profile's `opt-level`: 0 to 3, `s` or `z`
+    #[serde(default)]
+    pub opt_level: String,
+    /// The CPU frequency governor (Linux), e.g. `performance` or `powersave`
+    #[serde(default)]
+    pub governor: Option<String>,
+}
+
+/// One level of the cache hierarchy
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Cache {
+    pub level: u8,
+    /// `Data`, `Instruction` or `Unified`
+    pub kind: String,
+    pub bytes: u64,
+}
+
+impl Environment {
+    pub fn current() -> Environment {
+        let hardware = platform::hardware();
+        Environment {
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            cpus: thread::available_parallelism().map(|n| n.get()).unwrap_or(1),
+            profile: if cfg!(debug_assertions) { "debug" } else { "release" }.to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            os_version: hardware.os_version,
+            cpu_model: hardware.cpu_model,
+            physical_cores: physical_cores(),
+            caches: hardware.caches,
+            memory_bytes: hardware.memory_bytes,
+            rustc: env!("SYSTEMS_DEMOS_RUSTC").to_string(),
+            opt_level: env!("SYSTEMS_DEMOS_OPT_LEVEL").to_string(),
+            governor: hardware.governor,
+        }
+    }
+
+    /// Label and value for each field worth showing, e.g. `("CPU", "...")`
+    pub fn rows(&self) -> Vec<(&'static str, String)> {
+        let unknown = || "unknown".to_string();
+        let count = |n: usize, what: &str| format!("{} {}{}", n, what, if n == 1 { "" } else { "s" });
+        let cores = match self.physical_cores {
+            Some(cores) => format!("{} on {}", count(self.cpus, "hardware thread"), count(cores, "core")),
+            None => count(self.cpus, "hardware thread"),
+        };
+        let caches: Vec<String> = self
+            .caches
+            .iter()
+            .map(|cache| {
+                let kind = match cache.kind.as_str() {
+                    "Data" => "d",
+                    "Instruction" => "i",
+                    _ => "",
+                };
+                format!("L{}{} {}B", cache.level, kind, format_bytes(cache.bytes as f64))
+            })
+            .collect();
+        let os = match &self.os_version {
+            Some(version) => format!("{} ({}, {})", self.os, self.arch, version),
+            None => format!("{} ({})", self.os, self.arch),
+        };
+        vec![
+            ("CPU", self.cpu_model.clone().unwrap_or_else(unknown)),
+            ("Cores", cores),
+            ("Caches", if caches.is_empty() { unknown() } else { caches.join(", ") }),
+            ("Memory", self.memory_bytes.map_or_else(unknown, |bytes| format!("{}B", format_bytes(bytes as f64)))),
+            ("OS", os),
+            ("Compiler", if self.rustc.is_empty() { unknown() } else { self.rustc.clone() }),
+            ("Build", format!("{}, opt-level {}", self.profile, self.opt_level)),
+            ("Governor", self.governor.clone().unwrap_or_else(|| "not exposed".to_string())),
+            ("Version", self.version.clone()),
+        ]
+    }
+
+    /// Settings that make timings unrepresentative
+    pub fn warnings(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+        if self.profile == "debug" {
+            warnings.push("debug build: timings include unoptimized code, rerun with --release".to_string());
+        }
+        if let Some(governor) = self.governor.as_deref().filter(|&governor| governor != "performance") {
+            warnings.push(format!("CPU governor is '{}': clocks may ramp up mid-benchmark", governor));
+        }
+        warnings
+    }
+}
+
+/// The rows, aligned, then any warnings
+impl fmt::Display for Environment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (label, value) in self.rows() {
+            writeln!(f, "  {:<9} {}", label, value)?;
+        }
+        for warning in self.warnings() {
+            writeln!(f, "⚠️  {}", warning)?;
+        }
+        Ok(())
+    }
+}
+
+/// Print `env` as a section of its own, ahead of the benchmarks
+pub fn print(env: &Environment) {
+    output::heading("🧪 Environment");
+    out!("{}\n", env);
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn physical_cores() -> Option<usize> {
+    Some(num_cpus::get_physical())
+}
+
+#[cfg(target_family = "wasm")]
+fn physical_cores() -> Option<usize> {
+    None
+}
+
+/// What the platform reports about its hardware; `None` where it doesn't
+#[derive(Default)]
+struct Hardware {
+    os_version: Option<String>,
+    cpu_model: Option<String>,
+    caches: Vec<Cache>,
+    memory_bytes: Option<u64>,
+    governor: Option<String>,
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use std::fs;
+
+    use super::{Cache, Hardware};
+
+    fn read(path: &str) -> Option<String> {
+        fs::read_to_string(path).ok().map(|text| text.trim().to_string()).filter(|text| !text.is_empty())
+    }
+
+    /// The value of the first `key: value` line in `text` with this key
+    fn field<'a>(text: &'a str, key: &str) -> Option<&'a str> {
+        text.lines()
+            .find_map(|line| line.split_once(':').filter(|(name, _)| name.trim() == key))
+            .map(|(_, value)| value.trim())
+    }
+
+    /// `KEY=value` files like os-release
+    fn field_eq<'a>(text: &'a str, key: &str) -> Option<&'a str> {
+        text.lines().find_map(|line| line.strip_prefix(key)?.strip_prefix('='))
+    }
+
+    /// sysfs cache sizes: `48K`, `2048K`, `32M`
+    fn parse_size(size: &str) -> Option<u64> {
+        let (digits, scale) = match size.as_bytes().last()? {
+            b'K' => (&size[..size.len() - 1], 1 << 10),
+            b'M' => (&size[..size.len() - 1], 1 << 20),
+            b'G' => (&size[..size.len() - 1], 1 << 30),
+            _ => (size, 1),
+        };
+        digits.parse::<u64>().ok().map(|n| n * scale)
+    }
+
+    fn caches() -> Vec<Cache> {
+        let mut caches = Vec::new();
+        for index in 0.. {
+            let dir = format!("/sys/devices/system/cpu/cpu0/cache/index{}", index);
+            let (Some(level), Some(kind), Some(size)) =
+                (read(&format!("{}/level", dir)), read(&format!("{}/type", dir)), read(&format!("{}/size", dir)))
+            else {
+                break;
+            };
+            if let (Ok(level), Some(bytes)) = (level.parse(), parse_size(&size)) {
+                caches.push(Cache { level, kind, bytes });
+            }
+        }
+        caches
+    }
+
+    pub(super) fn hardware() -> Hardware {
+        let cpuinfo = read("/proc/cpuinfo").unwrap_or_default();
+        // x86 names the model; many ARM kernels only give the board's `Model`
+        let cpu_model = field(&cpuinfo, "model name").or_else(|| field(&cpuinfo, "Model")).map(str::to_string);
+        let memory_bytes = read("/proc/meminfo")
+            .and_then(|meminfo| field(&meminfo, "MemTotal")?.trim_end_matches("kB").trim().parse::<u64>().ok())
+            .map(|kib| kib * 1024);
+        let distribution = read("/etc/os-release")
+            .and_then(|release| Some(field_eq(&release, "PRETTY_NAME")?.trim_matches('"').to_string()));
+        let kernel = read("/proc/sys/kernel/osrelease").map(|kernel| format!("kernel {}", kernel));
+        let os_version = match (distribution, kernel) {
+            (Some(distribution), Some(kernel)) => Some(format!("{}, {}", distribution, kernel)),
+            (distribution, kernel) => distribution.or(kernel),
+        };
+        Hardware {
+            os_version,
+            cpu_model,
+            caches: caches(),
+            memory_bytes,
+            governor: read("/sys/devices/system/cpu/cpu0/cpufreq/scaling_governor"),
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn parses_proc_and_sys_formats() {
+            assert_eq!(parse_size("48K"), Some(48 * 1024));
+            assert_eq!(parse_size("32M"), Some(32 << 20));
+            assert_eq!(parse_size("512"), Some(512));
+            assert_eq!(parse_size("big"), None);
+            let cpuinfo = "processor\t: 0\nmodel name\t: Example CPU @ 3.00GHz\nflags\t\t: fpu\n";
+            assert_eq!(field(cpuinfo, "model name"), Some("Example CPU @ 3.00GHz"));
+            assert_eq!(field(cpuinfo, "Model"), None);
+            assert_eq!(field_eq("NAME=\"Debian\"\nPRETTY_NAME=\"Debian 12\"\n", "PRETTY_NAME"), Some("\"Debian 12\""));
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::process::Command;
+
+    use super::{Cache, Hardware};
+
+    fn sysctl(name: &str) -> Option<String> {
+        let output = Command::new("sysctl").args(["-n", name]).output().ok()?;
+        let value = String::from_utf8(output.stdout).ok()?.trim().to_string();
+        (output.status.success() && !value.is_empty()).then_some(value)
+    }
+
+    pub(super) fn hardware() -> Hardware {
+        let caches = [(1, "Data", "hw.l1dcachesize"), (1, "Instruction", "hw.l1icachesize"),
+                      (2, "Unified", "hw.l2cachesize"), (3, "Unified", "hw.l3cachesize")]
+            .into_iter()
+            .filter_map(|(level, kind, name)| {
+                let bytes = sysctl(name)?.parse().ok().filter(|&bytes| bytes > 0)?;
+                Some(Cache { level, kind: kind.to_string(), bytes })
+            })
+            .collect();
+        Hardware {
+            os_version: sysctl("kern.osproductversion").map(|version| format!("macOS {}", version)),
+            cpu_model: sysctl("machdep.cpu.brand_string"),
+            caches,
+            memory_bytes: sysctl("hw.memsize").and_then(|bytes| bytes.parse().ok()),
+            governor: None,
+        }
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use std::mem;
+
+    use windows_sys::Win32::System::SystemInformation::{GlobalMemoryStatusEx, MEMORYSTATUSEX};
+
+    use super::Hardware;
+
+    pub(super) fn hardware() -> Hardware {
+        // SAFETY: GlobalMemoryStatusEx fills the struct it is given, once
+        // dwLength says which version of the struct that is
+        let memory_bytes = unsafe {
+            let mut status: MEMORYSTATUSEX = mem::zeroed();
+            status.dwLength = mem::size_of::<MEMORYSTATUSEX>() as u32;
+            (GlobalMemoryStatusEx(&mut status) != 0).then_some(status.ullTotalPhys)
+        };
+        Hardware {
+            // The family/model/stepping string; the marketing name is in the registry
+            cpu_model: std::env::var("PROCESSOR_IDENTIFIER").ok(),
+            memory_bytes,
+            ..Hardware::default()
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+mod platform {
+    use super::Hardware;
+
+    pub(super) fn hardware() -> Hardware {
+        Hardware::default()
+    }
+}
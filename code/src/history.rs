@@ -0,0 +1,209 @@
+//! Result History
+//!
+//! A baseline compares two runs; the history keeps all of them. Every
+//! `systems-demos run` and `all` appends what it measured to a SQLite file,
+//! with when it ran, on which machine and with which parameters, and
+//! `systems-demos history DEMO METRIC` reads back how one metric moved
+//! across those runs: after a compiler upgrade, a BIOS setting or a week of
+//! changes to a lock.
+//!
+//! Two tables, so a scripted query needs nothing from this module:
+//!
+//! - `runs`: one row per runner invocation, with the environment and
+//!   parameters as JSON next to the few fields worth filtering on
+//! - `metrics`: one row per measurement, `run` pointing into `runs` and
+//!   `params` written `k=v, ...` in key order
+
+use std::path::Path;
+
+use rusqlite::{params, Connection};
+
+pub use rusqlite::Error;
+
+use crate::demo::Metric;
+use crate::envinfo::Environment;
+use crate::report;
+use crate::results::Results;
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS runs (
+        id          INTEGER PRIMARY KEY,
+        timestamp   INTEGER NOT NULL,
+        machine     TEXT NOT NULL,
+        profile     TEXT NOT NULL,
+        version     TEXT NOT NULL,
+        parameters  TEXT NOT NULL,
+        environment TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS metrics (
+        run    INTEGER NOT NULL REFERENCES runs (id),
+        demo   TEXT NOT NULL,
+        name   TEXT NOT NULL,
+        params TEXT NOT NULL,
+        value  REAL NOT NULL,
+        unit   TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS metrics_by_name ON metrics (demo, name);
+";
+
+/// One measurement of a metric, in a given run
+#[derive(Debug, Clone, PartialEq)]
+pub struct Point {
+    pub run: i64,
+    /// Seconds since the Unix epoch when the run started
+    pub timestamp: u64,
+    pub machine: String,
+    pub profile: String,
+    /// What the measurement was taken with, `k=v, ...`; empty for none
+    pub params: String,
+    pub value: f64,
+    pub unit: String,
+}
+
+/// What the history holds for one metric
+#[derive(Debug, Clone, PartialEq)]
+pub struct Recorded {
+    pub demo: String,
+    pub name: String,
+    /// Runs that measured it
+    pub runs: usize,
+    /// The most recent of them
+    pub last: u64,
+}
+
+/// An open history file
+pub struct History {
+    connection: Connection,
+}
+
+/// CPU model (or architecture) and OS: enough to tell machines apart in a
+/// listing, with the full environment kept in `runs.environment`
+pub fn machine(env: &Environment) -> String {
+    let cpu = env.cpu_model.clone().unwrap_or_else(|| env.arch.clone());
+    format!("{} ({} thread{}, {})", cpu, env.cpus, if env.cpus == 1 { "" } else { "s" }, env.os)
+}
+
+impl History {
+    /// Open the history at `path`, creating the file and its tables if
+    /// needed (`:memory:` for a throwaway one)
+    pub fn open(path: impl AsRef<Path>) -> Result<History, Error> {
+        let connection = Connection::open(path)?;
+        connection.execute_batch(SCHEMA)?;
+        Ok(History { connection })
+    }
+
+    /// Append the metrics of every demo that succeeded; returns how many
+    /// were stored. A run that measured nothing leaves no trace.
+    pub fn record(&mut self, results: &Results) -> Result<usize, Error> {
+        let metrics: Vec<(&str, &Metric)> = results
+            .results
+            .iter()
+            .filter(|result| result.succeeded)
+            .flat_map(|result| result.metrics.iter().map(|metric| (result.demo.as_str(), metric)))
+            .collect();
+        if metrics.is_empty() {
+            return Ok(0);
+        }
+        let env = &results.environment;
+        let parameters = serde_json::to_string(&results.parameters).expect("parameters are always serializable");
+        let environment = serde_json::to_string(env).expect("the environment is always serializable");
+        let transaction = self.connection.transaction()?;
+        transaction.execute(
+            "INSERT INTO runs (timestamp, machine, profile, version, parameters, environment)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![results.timestamp as i64, machine(env), env.profile, env.version, parameters, environment],
+        )?;
+        let run = transaction.last_insert_rowid();
+        {
+            let mut insert = transaction
+                .prepare("INSERT INTO metrics (run, demo, name, params, value, unit) VALUES (?1, ?2, ?3, ?4, ?5, ?6)")?;
+            for (demo, metric) in &metrics {
+                insert.execute(params![run, demo, metric.name, report::params(metric), metric.value, metric.unit])?;
+            }
+        }
+        transaction.commit()?;
+        Ok(metrics.len())
+    }
+
+    /// Every metric recorded for `demo`, or for every demo, by demo and name
+    pub fn recorded(&self, demo: Option<&str>) -> Result<Vec<Recorded>, Error> {
+        let mut query = self.connection.prepare(
+            "SELECT demo, name, COUNT(DISTINCT run), MAX(runs.timestamp)
+             FROM metrics JOIN runs ON runs.id = metrics.run
+             WHERE ?1 IS NULL OR demo = ?1
+             GROUP BY demo, name ORDER BY demo, name",
+        )?;
+        let rows = query.query_map(params![demo], |row| {
+            Ok(Recorded {
+                demo: row.get(0)?,
+                name: row.get(1)?,
+                runs: row.get::<_, i64>(2)? as usize,
+                last: row.get::<_, i64>(3)? as u64,
+            })
+        })?;
+        rows.collect()
+    }
+
+    /// Every measurement of `demo`'s `metric`, oldest first
+    pub fn trend(&self, demo: &str, metric: &str) -> Result<Vec<Point>, Error> {
+        let mut query = self.connection.prepare(
+            "SELECT runs.id, runs.timestamp, runs.machine, runs.profile, params, value, unit
+             FROM metrics JOIN runs ON runs.id = metrics.run
+             WHERE demo = ?1 AND name = ?2
+             ORDER BY runs.timestamp, runs.id",
+        )?;
+        let rows = query.query_map(params![demo, metric], |row| {
+            Ok(Point {
+                run: row.get(0)?,
+                timestamp: row.get::<_, i64>(1)? as u64,
+                machine: row.get(2)?,
+                profile: row.get(3)?,
+                params: row.get(4)?,
+                value: row.get(5)?,
+                unit: row.get(6)?,
+            })
+        })?;
+        rows.collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::demo::{Config, DemoReport};
+    use crate::results::DemoResult;
+
+    fn run(timestamp: u64, succeeded: bool, record: impl FnOnce(&mut DemoReport)) -> Results {
+        let mut report = DemoReport::default();
+        record(&mut report);
+        let mut results = Results::new(Config::default());
+        results.timestamp = timestamp;
+        results.results.push(DemoResult { demo: "d".to_string(), succeeded, metrics: report.metrics });
+        results
+    }
+
+    #[test]
+    fn keeps_every_run_of_a_metric() {
+        let mut history = History::open(":memory:").unwrap();
+        for (timestamp, value) in [(300, 30.0), (100, 10.0), (200, 20.0)] {
+            let results = run(timestamp, true, |r| {
+                r.record("time", value, "ns");
+                r.record("rate", value, "ops/s").param("threads", 4).param("batch", 8);
+            });
+            assert_eq!(history.record(&results).unwrap(), 2);
+        }
+        // Failed demos and runs without metrics are left out
+        assert_eq!(history.record(&run(400, false, |r| { r.record("time", 0.0, "ns"); })).unwrap(), 0);
+        assert_eq!(history.record(&run(500, true, |_| {})).unwrap(), 0);
+
+        let trend = history.trend("d", "time").unwrap();
+        let values: Vec<(u64, f64)> = trend.iter().map(|point| (point.timestamp, point.value)).collect();
+        assert_eq!(values, [(100, 10.0), (200, 20.0), (300, 30.0)]);
+        assert_eq!(history.trend("d", "rate").unwrap()[0].params, "batch=8, threads=4");
+
+        let recorded = history.recorded(None).unwrap();
+        let names: Vec<(&str, usize, u64)> = recorded.iter().map(|r| (r.name.as_str(), r.runs, r.last)).collect();
+        assert_eq!(names, [("rate", 3, 300), ("time", 3, 300)]);
+        assert!(history.recorded(Some("other")).unwrap().is_empty());
+    }
+}
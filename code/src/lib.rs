@@ -0,0 +1,44 @@
+//! Computer Systems Through Rust - shared library
+//!
+//! Every demo as a module of `demos`, plus the reusable building blocks
+//! they share. Each module is small enough to read in one sitting.
+
+// Lets `#[derive(Demo)]`, which names `::systems_demos::demo::Demo`, work
+// inside this crate too
+extern crate self as systems_demos;
+
+#[cfg(unix)]
+pub mod asm;
+/// The core-only data structures, a `#![no_std]` crate of their own
+pub use systems_bare as bare;
+pub mod baseline;
+pub mod bench;
+pub mod bits;
+pub mod cache;
+pub mod catalog;
+pub mod demo;
+pub mod demos;
+pub mod disasm;
+pub mod elf;
+pub mod envinfo;
+#[cfg(feature = "history")]
+pub mod history;
+pub mod output;
+pub mod plot;
+#[cfg(not(target_family = "wasm"))]
+pub mod profile;
+pub mod quiz;
+pub mod report;
+pub mod results;
+pub mod rng;
+#[cfg(target_os = "linux")]
+pub mod runtime;
+#[cfg(not(target_family = "wasm"))]
+pub mod scratch;
+pub mod send_sync;
+#[cfg(not(target_family = "wasm"))]
+pub mod storage;
+pub mod sync;
+pub mod toy_cpu;
+pub mod variance;
+pub mod verify;
@@ -0,0 +1,649 @@
+//! Demo Output
+//!
+//! Demos print through `outln!`/`out!` and open each part with
+//! `output::heading`, rather than calling `println!` directly, so a long demo
+//! can be trimmed to the part a reader cares about: `--quiet` keeps only
+//! what it measured, `--verbose` adds sample spreads and section names, and
+//! `--only false-sharing` shows just the sections whose heading matches.
+//! Sections that are hidden still run, so the measurements stay complete.
+//! The settings come from the binary's arguments or, for demos launched by
+//! the `systems-demos` runner, from the variables it sets.
+//!
+//! Headings, table headers and the faster/slower side of a comparison are
+//! colored when stdout is a terminal. `NO_COLOR` (see no-color.org),
+//! `TERM=dumb` or `--color never` turn that off; `--color always` keeps it
+//! on in a pipe. `Table` lines up columns by what is visible, so colored
+//! cells don't push their neighbours out of place.
+//!
+//! In the browser build (wasm32) there is no stdout: everything printed
+//! here collects in a buffer the web harness reads with `take_captured`.
+
+use std::env;
+use std::fmt;
+use std::io::{self, IsTerminal};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+use crate::demo::DemoReport;
+use crate::report::display_value;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Verbosity {
+    /// Only the title and the measurements
+    Quiet,
+    #[default]
+    Normal,
+    /// Everything, plus sample spreads and each section's `--only` name
+    Verbose,
+}
+
+/// When to color output
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ColorChoice {
+    /// When stdout is a terminal and `NO_COLOR` isn't set
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    /// `"auto"`, `"always"` or `"never"`
+    pub fn from_name(name: &str) -> Option<ColorChoice> {
+        match name {
+            "auto" => Some(ColorChoice::Auto),
+            "always" => Some(ColorChoice::Always),
+            "never" => Some(ColorChoice::Never),
+            _ => None,
+        }
+    }
+
+    /// Whether to color a stream, given whether it is a terminal and the
+    /// environment lookup `var`
+    pub fn enabled(self, terminal: bool, var: impl Fn(&str) -> Option<String>) -> bool {
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => {
+                terminal
+                    && var("NO_COLOR").is_none_or(|value| value.is_empty())
+                    && var("TERM").as_deref() != Some("dumb")
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Settings {
+    pub verbosity: Verbosity,
+    /// Section filters; empty shows every section
+    pub only: Vec<String>,
+    pub color: ColorChoice,
+}
+
+impl Settings {
+    pub const VERBOSITY_VAR: &'static str = "SYSTEMS_DEMOS_VERBOSITY";
+    pub const ONLY_VAR: &'static str = "SYSTEMS_DEMOS_ONLY";
+    pub const COLOR_VAR: &'static str = "SYSTEMS_DEMOS_COLOR";
+
+    /// Read `--quiet`, `--verbose`, `--only NAME[,NAME...]` (repeatable) and
+    /// `--color WHEN` from `args`, falling back to the runner's variables
+    /// via `var`
+    pub fn parse(args: impl IntoIterator<Item = String>, var: impl Fn(&str) -> Option<String>) -> Settings {
+        let mut settings = Settings {
+            verbosity: match var(Self::VERBOSITY_VAR).as_deref() {
+                Some("quiet") => Verbosity::Quiet,
+                Some("verbose") => Verbosity::Verbose,
+                _ => Verbosity::Normal,
+            },
+            only: var(Self::ONLY_VAR).map(|only| split(&only)).unwrap_or_default(),
+            color: var(Self::COLOR_VAR).as_deref().and_then(ColorChoice::from_name).unwrap_or_default(),
+        };
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--quiet" | "-q" => settings.verbosity = Verbosity::Quiet,
+                "--verbose" | "-v" => settings.verbosity = Verbosity::Verbose,
+                "--only" => settings.only.extend(args.next().as_deref().map(split).unwrap_or_default()),
+                "--color" => {
+                    if let Some(color) = args.next().as_deref().and_then(ColorChoice::from_name) {
+                        settings.color = color;
+                    }
+                }
+                "--no-color" => settings.color = ColorChoice::Never,
+                _ => {
+                    if let Some(only) = arg.strip_prefix("--only=") {
+                        settings.only.extend(split(only));
+                    } else if let Some(color) = arg.strip_prefix("--color=").and_then(ColorChoice::from_name) {
+                        settings.color = color;
+                    }
+                }
+            }
+        }
+        settings
+    }
+
+    /// The variables to set on a child process so its settings match these
+    pub fn env_vars(&self) -> Vec<(&'static str, String)> {
+        let mut vars = Vec::new();
+        match self.verbosity {
+            Verbosity::Quiet => vars.push((Self::VERBOSITY_VAR, "quiet".to_string())),
+            Verbosity::Verbose => vars.push((Self::VERBOSITY_VAR, "verbose".to_string())),
+            Verbosity::Normal => {}
+        }
+        if !self.only.is_empty() {
+            vars.push((Self::ONLY_VAR, self.only.join(",")));
+        }
+        match self.color {
+            ColorChoice::Always => vars.push((Self::COLOR_VAR, "always".to_string())),
+            ColorChoice::Never => vars.push((Self::COLOR_VAR, "never".to_string())),
+            ColorChoice::Auto => {}
+        }
+        vars
+    }
+}
+
+fn split(list: &str) -> Vec<String> {
+    list.split(',').map(slug).filter(|name| !name.is_empty()).collect()
+}
+
+static SETTINGS: OnceLock<Settings> = OnceLock::new();
+static COLOR: OnceLock<bool> = OnceLock::new();
+/// Whether the current section is one `--only` selected
+static SELECTED: AtomicBool = AtomicBool::new(true);
+/// Names of the sections seen so far, for the hint when `--only` matches none
+static SECTIONS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// The settings in effect: from `configure`, or else this process's
+/// arguments and environment
+pub fn settings() -> &'static Settings {
+    SETTINGS.get_or_init(|| Settings::parse(env::args().skip(1), |var| env::var(var).ok()))
+}
+
+/// Use `settings` instead of the arguments and environment; `false` if
+/// output already started with other settings
+pub fn configure(settings: Settings) -> bool {
+    SETTINGS.set(settings).is_ok()
+}
+
+/// Whether output to stdout is colored
+pub fn color() -> bool {
+    *COLOR.get_or_init(|| {
+        let terminal = io::stdout().is_terminal();
+        settings().color.enabled(terminal, |var| env::var(var).ok()) && (!terminal || console_escapes())
+    })
+}
+
+/// Windows consoles show escape sequences as text unless asked to
+/// interpret them; `false` if this one can't be
+#[cfg(windows)]
+fn console_escapes() -> bool {
+    use windows_sys::Win32::System::Console::{
+        GetConsoleMode, GetStdHandle, SetConsoleMode, ENABLE_VIRTUAL_TERMINAL_PROCESSING, STD_OUTPUT_HANDLE,
+    };
+
+    // SAFETY: the handle comes from GetStdHandle, and `mode` is writable
+    unsafe {
+        let stdout = GetStdHandle(STD_OUTPUT_HANDLE);
+        let mut mode = 0;
+        GetConsoleMode(stdout, &mut mode) != 0
+            && SetConsoleMode(stdout, mode | ENABLE_VIRTUAL_TERMINAL_PROCESSING) != 0
+    }
+}
+
+#[cfg(not(windows))]
+fn console_escapes() -> bool {
+    true
+}
+
+/// `"💥 False Sharing: Padded vs Unpadded"` -> `"false-sharing-padded-vs-unpadded"`
+pub fn slug(title: &str) -> String {
+    let words: Vec<String> = title
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty() && word.is_ascii())
+        .map(str::to_ascii_lowercase)
+        .collect();
+    words.join("-")
+}
+
+fn selects(only: &[String], section: &str) -> bool {
+    only.is_empty() || only.iter().any(|name| section.contains(name.as_str()))
+}
+
+#[doc(hidden)]
+pub fn prose_shown() -> bool {
+    settings().verbosity != Verbosity::Quiet && SELECTED.load(Ordering::Relaxed)
+}
+
+#[doc(hidden)]
+pub fn detail_shown() -> bool {
+    settings().verbosity == Verbosity::Verbose && SELECTED.load(Ordering::Relaxed)
+}
+
+/// Where all demo output goes: stdout, or in the browser build `CAPTURED`
+#[doc(hidden)]
+#[cfg(not(target_family = "wasm"))]
+pub fn write(text: fmt::Arguments<'_>) {
+    print!("{}", text);
+}
+
+#[cfg(target_family = "wasm")]
+static CAPTURED: Mutex<String> = Mutex::new(String::new());
+
+#[doc(hidden)]
+#[cfg(target_family = "wasm")]
+pub fn write(text: fmt::Arguments<'_>) {
+    use std::fmt::Write;
+
+    let _ = CAPTURED.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).write_fmt(text);
+}
+
+/// Everything printed since the last call, emptying the buffer
+#[cfg(target_family = "wasm")]
+pub fn take_captured() -> String {
+    std::mem::take(&mut *CAPTURED.lock().unwrap_or_else(|poisoned| poisoned.into_inner()))
+}
+
+fn underlined(title: &str, style: Style) {
+    write(format_args!("{}\n", style.paint(title)));
+    write(format_args!("{}\n", Style::Dim.paint("=".repeat(title.chars().count()))));
+}
+
+/// The demo's own title. Always printed; the introduction after it is
+/// hidden when `--only` picks sections.
+pub fn title(title: &str) {
+    SELECTED.store(settings().only.is_empty(), Ordering::Relaxed);
+    underlined(title, Style::Title);
+    if !settings().only.is_empty() && settings().verbosity != Verbosity::Quiet {
+        write(format_args!("\n"));
+    }
+}
+
+/// Start a section: print its heading, underlined, if `--only` selects it
+pub fn heading(title: &str) {
+    let name = slug(title);
+    let selected = selects(&settings().only, &name);
+    SELECTED.store(selected, Ordering::Relaxed);
+    if settings().verbosity == Verbosity::Verbose {
+        write(format_args!("{}\n", Style::Dim.paint(format!("[--only {}]", name))));
+    }
+    if prose_shown() {
+        underlined(title, Style::Heading);
+    }
+    SECTIONS.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).push(name);
+}
+
+/// Start the closing "Key Takeaways" list, which `--only` leaves out
+pub fn takeaways() {
+    SELECTED.store(settings().only.is_empty(), Ordering::Relaxed);
+    crate::outln!("{}", Style::Heading.paint("🎯 Key Takeaways:"));
+}
+
+/// After the demo: with `--quiet`, list what it measured; with `--only`,
+/// say so if no section matched
+pub fn finish(report: &DemoReport) {
+    let settings = settings();
+    SELECTED.store(true, Ordering::Relaxed);
+    if settings.verbosity == Verbosity::Quiet {
+        for metric in &report.metrics {
+            let params: Vec<String> = metric.params.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+            let params = if params.is_empty() { String::new() } else { format!(" ({})", params.join(", ")) };
+            write(format_args!("  {}{}: {}\n", metric.name, params, display_value(metric)));
+        }
+    }
+
+    let sections = SECTIONS.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    if !settings.only.is_empty() && !sections.iter().any(|section| selects(&settings.only, section)) {
+        eprintln!("⚠️  no section matches --only {}; this demo's sections are:", settings.only.join(","));
+        for section in sections.iter() {
+            eprintln!("     {}", section);
+        }
+    }
+}
+
+/// How a piece of output is colored
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Style {
+    /// A demo's title
+    Title,
+    /// Section headings and table headers
+    Heading,
+    /// The better side of a comparison: faster, smaller, improved
+    Faster,
+    /// The worse side: slower, bigger, regressed
+    Slower,
+    /// Underlines and other scaffolding
+    Dim,
+}
+
+impl Style {
+    /// The SGR parameters that switch this style on
+    fn code(self) -> &'static str {
+        match self {
+            Style::Title => "1;36",
+            Style::Heading => "1",
+            Style::Faster => "32",
+            Style::Slower => "31",
+            Style::Dim => "2",
+        }
+    }
+
+    /// `value`, colored when `color()` allows. Width, precision and
+    /// alignment in the format string apply to `value` itself, so
+    /// `{:>8.2}` lines up the same colored or not.
+    pub fn paint<T>(self, value: T) -> Painted<T> {
+        Painted { style: self, value }
+    }
+}
+
+/// A value with a `Style`, from `Style::paint`
+#[derive(Clone, Copy)]
+pub struct Painted<T> {
+    style: Style,
+    value: T,
+}
+
+impl<T> Painted<T> {
+    fn write(&self, f: &mut fmt::Formatter<'_>, value: impl FnOnce(&mut fmt::Formatter<'_>) -> fmt::Result) -> fmt::Result {
+        if !color() {
+            return value(f);
+        }
+        write!(f, "\x1b[{}m", self.style.code())?;
+        value(f)?;
+        write!(f, "\x1b[0m")
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Painted<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write(f, |f| self.value.fmt(f))
+    }
+}
+
+/// For `Duration`s and other values shown with `{:?}`
+impl<T: fmt::Debug> fmt::Debug for Painted<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write(f, |f| self.value.fmt(f))
+    }
+}
+
+/// `text` painted `Faster` if `value` is the best in a comparison and
+/// `Slower` if it is the worst; unchanged when all values tie
+pub fn ranked<T: PartialEq>(text: String, value: T, best: T, worst: T) -> String {
+    if best == worst {
+        text
+    } else if value == best {
+        Style::Faster.paint(text).to_string()
+    } else if value == worst {
+        Style::Slower.paint(text).to_string()
+    } else {
+        text
+    }
+}
+
+/// Competing results formatted with `format`, the best painted `Faster`
+/// and the worst `Slower`
+pub fn rank<T: PartialOrd + Copy>(values: &[T], higher_is_better: bool, format: impl Fn(T) -> String) -> Vec<String> {
+    let pick = |better: bool| {
+        values.iter().copied().reduce(|a, b| if (b > a) == better { b } else { a })
+    };
+    let (best, worst) = (pick(higher_is_better), pick(!higher_is_better));
+    values.iter().map(|&value| ranked(format(value), Some(value), best, worst)).collect()
+}
+
+/// A speedup as `"2.5x"`, painted `Faster` when it is a clear win and
+/// `Slower` when it is a clear loss (beyond 10% either way)
+pub fn speedup(ratio: f64) -> String {
+    let text = format!("{:.1}x", ratio);
+    if ratio >= 1.1 {
+        Style::Faster.paint(text).to_string()
+    } else if ratio <= 1.0 / 1.1 {
+        Style::Slower.paint(text).to_string()
+    } else {
+        text
+    }
+}
+
+/// Columns a terminal uses for `text`, leaving out color sequences. Emoji
+/// like ✅ take two columns; everything else is assumed to take one.
+pub fn visible_width(text: &str) -> usize {
+    let mut width = 0;
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        width += match c {
+            '\x1b' => {
+                chars.by_ref().find(|&c| c == 'm');
+                0
+            }
+            // Variation selector: turns the previous character into an emoji
+            '\u{fe0f}' => 0,
+            '\u{2600}'..='\u{27bf}' | '\u{1f000}'..='\u{1faff}' => 2,
+            _ => 1,
+        };
+    }
+    width
+}
+
+/// Columns lined up to their widest cell, with a highlighted header row.
+/// The first column is left-aligned and the rest right-aligned, unless
+/// `left` says otherwise.
+///
+/// ```ignore
+/// let mut table = Table::new(["type", "size_of"]);
+/// table.row(["String".to_string(), mem::size_of::<String>().to_string()]);
+/// out!("{}", table);
+/// ```
+///
+/// A table whose rows take a while to measure can print as it goes
+/// instead: give the columns `widths` up front, print `header()`, then
+/// `line(cells)` for each row.
+#[derive(Debug, Clone, Default)]
+pub struct Table {
+    header: Vec<String>,
+    rows: Vec<Vec<String>>,
+    left: Vec<bool>,
+    widths: Vec<usize>,
+}
+
+impl Table {
+    pub fn new<S: Into<String>>(header: impl IntoIterator<Item = S>) -> Table {
+        let header: Vec<String> = header.into_iter().map(Into::into).collect();
+        let mut left = vec![false; header.len()];
+        if let Some(first) = left.first_mut() {
+            *first = true;
+        }
+        let widths = header.iter().map(|cell| visible_width(cell)).collect();
+        Table { header, rows: Vec::new(), left, widths }
+    }
+
+    /// Left-align column `column` (counting from 0)
+    pub fn left(mut self, column: usize) -> Table {
+        if column >= self.left.len() {
+            self.left.resize(column + 1, false);
+        }
+        self.left[column] = true;
+        self
+    }
+
+    /// Make the columns at least this wide, for tables printed line by line
+    pub fn widths(mut self, widths: impl IntoIterator<Item = usize>) -> Table {
+        for (column, width) in widths.into_iter().enumerate() {
+            if column >= self.widths.len() {
+                self.widths.resize(column + 1, 0);
+            }
+            self.widths[column] = self.widths[column].max(width);
+        }
+        self
+    }
+
+    /// Add a row; cells may already be colored with `Style::paint`
+    pub fn row<S: Into<String>>(&mut self, cells: impl IntoIterator<Item = S>) -> &mut Table {
+        self.rows.push(cells.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// The header row on its own, padded to `widths`
+    pub fn header(&self) -> String {
+        self.format(&self.header_cells(), &self.widths)
+    }
+
+    /// One row on its own, padded to `widths`
+    pub fn line<S: Into<String>>(&self, cells: impl IntoIterator<Item = S>) -> String {
+        let cells: Vec<String> = cells.into_iter().map(Into::into).collect();
+        self.format(&cells, &self.widths)
+    }
+
+    fn header_cells(&self) -> Vec<String> {
+        let paint = |cell: &String| if cell.is_empty() { String::new() } else { Style::Heading.paint(cell).to_string() };
+        self.header.iter().map(paint).collect()
+    }
+
+    fn format(&self, cells: &[String], widths: &[usize]) -> String {
+        let mut line = String::new();
+        for (column, cell) in cells.iter().enumerate() {
+            let width = widths.get(column).copied().unwrap_or(0);
+            let padding = " ".repeat(width.saturating_sub(visible_width(cell)));
+            line.push_str("  ");
+            if self.left.get(column).copied().unwrap_or(false) {
+                line.push_str(cell);
+                line.push_str(&padding);
+            } else {
+                line.push_str(&padding);
+                line.push_str(cell);
+            }
+        }
+        line.trim_end().to_string()
+    }
+}
+
+impl fmt::Display for Table {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut widths = self.widths.clone();
+        for row in &self.rows {
+            if row.len() > widths.len() {
+                widths.resize(row.len(), 0);
+            }
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(visible_width(cell));
+            }
+        }
+        writeln!(f, "{}", self.format(&self.header_cells(), &widths))?;
+        for row in &self.rows {
+            writeln!(f, "{}", self.format(row, &widths))?;
+        }
+        Ok(())
+    }
+}
+
+/// `println!` for demo prose: skipped with `--quiet`, and in sections that
+/// `--only` left out. The arguments are evaluated either way.
+#[macro_export]
+macro_rules! outln {
+    () => {
+        if $crate::output::prose_shown() {
+            $crate::output::write(::std::format_args!("\n"));
+        }
+    };
+    ($($arg:tt)*) => {{
+        let line = ::std::format!($($arg)*);
+        if $crate::output::prose_shown() {
+            $crate::output::write(::std::format_args!("{}\n", line));
+        }
+    }};
+}
+
+/// `print!` counterpart of `outln!`
+#[macro_export]
+macro_rules! out {
+    ($($arg:tt)*) => {{
+        let text = ::std::format!($($arg)*);
+        if $crate::output::prose_shown() {
+            $crate::output::write(::std::format_args!("{}", text));
+        }
+    }};
+}
+
+/// `outln!` for lines only `--verbose` shows
+#[macro_export]
+macro_rules! detailln {
+    () => {
+        if $crate::output::detail_shown() {
+            $crate::output::write(::std::format_args!("\n"));
+        }
+    };
+    ($($arg:tt)*) => {{
+        let line = ::std::format!($($arg)*);
+        if $crate::output::detail_shown() {
+            $crate::output::write(::std::format_args!("{}\n", line));
+        }
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str], vars: &[(&str, &str)]) -> Settings {
+        let vars: Vec<(String, String)> = vars.iter().map(|&(k, v)| (k.to_string(), v.to_string())).collect();
+        Settings::parse(args.iter().map(|arg| arg.to_string()),
+                        |var| vars.iter().find(|(k, _)| k == var).map(|(_, v)| v.clone()))
+    }
+
+    #[test]
+    fn slugs_drop_emoji_and_punctuation() {
+        assert_eq!(slug("💥 False Sharing: Padded vs Unpadded"), "false-sharing-padded-vs-unpadded");
+        assert_eq!(slug("Cache Line Size: Why 64 Bytes?"), "cache-line-size-why-64-bytes");
+    }
+
+    #[test]
+    fn arguments_override_the_runner() {
+        let settings = parse(&["--serial", "0.1", "--only", "False Sharing,prefetching", "--only=layout", "-v",
+                               "--color=always"],
+                             &[(Settings::VERBOSITY_VAR, "quiet"), (Settings::ONLY_VAR, "size"),
+                               (Settings::COLOR_VAR, "never")]);
+        assert_eq!(settings.verbosity, Verbosity::Verbose);
+        assert_eq!(settings.color, ColorChoice::Always);
+        assert_eq!(settings.only, ["size", "false-sharing", "prefetching", "layout"]);
+        let vars = settings.env_vars();
+        let vars: Vec<(&str, &str)> = vars.iter().map(|(var, value)| (*var, value.as_str())).collect();
+        assert_eq!(parse(&[], &vars), settings);
+    }
+
+    #[test]
+    fn color_follows_the_terminal_and_no_color() {
+        let unset = |_: &str| None;
+        assert!(ColorChoice::Auto.enabled(true, unset));
+        assert!(!ColorChoice::Auto.enabled(false, unset));
+        assert!(!ColorChoice::Auto.enabled(true, |var| (var == "NO_COLOR").then(|| "1".to_string())));
+        assert!(ColorChoice::Auto.enabled(true, |var| (var == "NO_COLOR").then(String::new)));
+        assert!(!ColorChoice::Auto.enabled(true, |var| (var == "TERM").then(|| "dumb".to_string())));
+        assert!(ColorChoice::Always.enabled(false, unset));
+        assert!(!ColorChoice::Never.enabled(true, unset));
+    }
+
+    #[test]
+    fn tables_align_by_visible_width() {
+        let mut table = Table::new(["type", "size_of", "fields"]).left(2);
+        table.row(["String", "24", "ptr, cap, len"]);
+        table.row(["\x1b[32m&str\x1b[0m", "16", "ptr, len"]);
+        // The test harness's stdout is no terminal, so the header is plain
+        assert_eq!(table.to_string(), concat!(
+            "  type    size_of  fields\n",
+            "  String       24  ptr, cap, len\n",
+            "  \x1b[32m&str\x1b[0m         16  ptr, len\n",
+        ));
+        assert_eq!(visible_width("\x1b[1;36m📈 ab\x1b[0m"), 5);
+
+        let table = Table::new(["threads", "rate"]).widths([0, 10]);
+        assert_eq!(table.header(), "  threads        rate");
+        assert_eq!(table.line(["1", "3.00 M/s"]), "  1          3.00 M/s");
+    }
+
+    #[test]
+    fn filters_match_inside_section_names() {
+        let only = vec!["false-sharing".to_string()];
+        assert!(selects(&only, "false-sharing-padded-vs-unpadded"));
+        assert!(!selects(&only, "hardware-prefetching"));
+        assert!(selects(&[], "anything"));
+    }
+}
@@ -0,0 +1,335 @@
+//! Terminal Plots
+//!
+//! Sweeps (latency vs working-set size, throughput vs threads, time vs
+//! stride) print tables, but a trend is easier to see than to read. `Bars`
+//! draws a horizontal bar chart and `LinePlot` an x/y plot with optional
+//! log axes, both as plain text so they work in any terminal and in the
+//! runner's reports.
+
+use std::fmt;
+
+/// Eighths of a block, for bars that end between two characters
+const PARTIAL_BLOCKS: [char; 8] = [' ', '▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+/// One marker per series, in order
+const MARKERS: [char; 6] = ['*', 'o', '+', 'x', '#', '%'];
+
+/// `1234.5` -> `1.23K`: short enough for an axis label
+pub fn format_si(value: f64) -> String {
+    let magnitude = value.abs();
+    let (scaled, suffix) = if magnitude >= 1e9 {
+        (value / 1e9, "G")
+    } else if magnitude >= 1e6 {
+        (value / 1e6, "M")
+    } else if magnitude >= 1e3 {
+        (value / 1e3, "K")
+    } else {
+        (value, "")
+    };
+    let digits = if scaled.abs() >= 100.0 || scaled.fract() == 0.0 { 0 } else if scaled.abs() >= 10.0 { 1 } else { 2 };
+    format!("{:.*}{}", digits, scaled, suffix)
+}
+
+/// `4096.0` -> `4K`, `1048576.0` -> `1M`: binary sizes for byte axes
+pub fn format_bytes(bytes: f64) -> String {
+    const UNITS: [&str; 4] = ["", "K", "M", "G"];
+    let mut value = bytes;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if value.fract() == 0.0 { format!("{}{}", value, UNITS[unit]) } else { format!("{:.1}{}", value, UNITS[unit]) }
+}
+
+/// A horizontal bar chart: one labelled bar per row, scaled so the largest
+/// value fills `width` columns
+pub struct Bars {
+    rows: Vec<(String, f64, String)>,
+    width: usize,
+}
+
+impl Bars {
+    pub fn new() -> Self {
+        Bars { rows: Vec::new(), width: 40 }
+    }
+
+    /// Columns for the longest bar
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = width.max(1);
+        self
+    }
+
+    /// Add a bar; `text` is printed after it, e.g. the formatted value
+    pub fn bar(mut self, label: impl Into<String>, value: f64, text: impl Into<String>) -> Self {
+        self.rows.push((label.into(), value, text.into()));
+        self
+    }
+}
+
+impl Default for Bars {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Display for Bars {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label_width = self.rows.iter().map(|(label, _, _)| label.chars().count()).max().unwrap_or(0);
+        let top = self.rows.iter().map(|&(_, value, _)| value).fold(0.0, f64::max);
+        for (label, value, text) in &self.rows {
+            let eighths = if top > 0.0 { (value.max(0.0) / top * (self.width * 8) as f64).round() as usize } else { 0 };
+            let mut bar = "█".repeat(eighths / 8);
+            if eighths % 8 != 0 {
+                bar.push(PARTIAL_BLOCKS[eighths % 8]);
+            }
+            writeln!(f, "  {:>lw$} │{:<w$} {}", label, bar, text, lw = label_width, w = self.width + 1)?;
+        }
+        Ok(())
+    }
+}
+
+/// Where values land on one axis
+#[derive(Clone, Copy)]
+struct Axis {
+    min: f64,
+    max: f64,
+    log: bool,
+}
+
+impl Axis {
+    fn fit(values: impl Iterator<Item = f64>, log: bool) -> Axis {
+        let (min, max) = values
+            .filter(|v| v.is_finite() && (!log || *v > 0.0))
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| (lo.min(v), hi.max(v)));
+        if min > max {
+            return Axis { min: 1.0, max: 2.0, log };
+        }
+        // A flat series still needs a range to spread over
+        let (min, max) = if min == max { if log { (min / 2.0, max * 2.0) } else { (min - 1.0, max + 1.0) } } else { (min, max) };
+        Axis { min, max, log }
+    }
+
+    fn transform(self, v: f64) -> f64 {
+        if self.log { v.log10() } else { v }
+    }
+
+    /// 0.0 at `min` to 1.0 at `max`; `None` for values a log axis can't show
+    fn position(self, v: f64) -> Option<f64> {
+        if !v.is_finite() || (self.log && v <= 0.0) {
+            return None;
+        }
+        let (lo, hi) = (self.transform(self.min), self.transform(self.max));
+        Some((self.transform(v) - lo) / (hi - lo))
+    }
+
+    /// The value `fraction` of the way along the axis
+    fn value_at(self, fraction: f64) -> f64 {
+        let (lo, hi) = (self.transform(self.min), self.transform(self.max));
+        let v = lo + fraction * (hi - lo);
+        if self.log { 10f64.powf(v) } else { v }
+    }
+}
+
+/// An x/y plot of one or more series. Points are marked with the series'
+/// marker and joined with dots; where series overlap, `@` marks the spot.
+pub struct LinePlot {
+    x_label: String,
+    y_label: String,
+    log_x: bool,
+    log_y: bool,
+    width: usize,
+    height: usize,
+    format_x: fn(f64) -> String,
+    format_y: fn(f64) -> String,
+    series: Vec<(String, Vec<(f64, f64)>)>,
+}
+
+impl LinePlot {
+    pub fn new(x_label: impl Into<String>, y_label: impl Into<String>) -> Self {
+        LinePlot {
+            x_label: x_label.into(),
+            y_label: y_label.into(),
+            log_x: false,
+            log_y: false,
+            width: 56,
+            height: 12,
+            format_x: format_si,
+            format_y: format_si,
+            series: Vec::new(),
+        }
+    }
+
+    /// Logarithmic x axis, for sweeps that double (threads, sizes)
+    pub fn log_x(mut self) -> Self {
+        self.log_x = true;
+        self
+    }
+
+    /// Logarithmic y axis, for values spanning orders of magnitude
+    pub fn log_y(mut self) -> Self {
+        self.log_y = true;
+        self
+    }
+
+    /// Plot area in characters, not counting labels
+    pub fn size(mut self, width: usize, height: usize) -> Self {
+        self.width = width.max(2);
+        self.height = height.max(2);
+        self
+    }
+
+    /// How x tick labels are written (default `format_si`)
+    pub fn format_x(mut self, format: fn(f64) -> String) -> Self {
+        self.format_x = format;
+        self
+    }
+
+    /// How y tick labels are written (default `format_si`)
+    pub fn format_y(mut self, format: fn(f64) -> String) -> Self {
+        self.format_y = format;
+        self
+    }
+
+    pub fn series(mut self, name: impl Into<String>, points: Vec<(f64, f64)>) -> Self {
+        self.series.push((name.into(), points));
+        self
+    }
+
+    fn points(&self) -> impl Iterator<Item = (f64, f64)> + '_ {
+        self.series.iter().flat_map(|(_, points)| points.iter().copied())
+    }
+
+    /// The character grid, top row first
+    fn grid(&self, x: Axis, y: Axis) -> Vec<Vec<char>> {
+        let mut grid = vec![vec![' '; self.width]; self.height];
+        let cell = |point: (f64, f64)| -> Option<(usize, usize)> {
+            let column = (x.position(point.0)? * (self.width - 1) as f64).round() as usize;
+            let row = (y.position(point.1)? * (self.height - 1) as f64).round() as usize;
+            Some((column, self.height - 1 - row))
+        };
+
+        // Dots between consecutive points first, so markers draw over them
+        for (_, points) in &self.series {
+            for pair in points.windows(2) {
+                let (Some(from), Some(to)) = (cell(pair[0]), cell(pair[1])) else { continue };
+                let steps = from.0.abs_diff(to.0).max(from.1.abs_diff(to.1));
+                for step in 1..steps {
+                    let t = step as f64 / steps as f64;
+                    let column = (from.0 as f64 + t * (to.0 as f64 - from.0 as f64)).round() as usize;
+                    let row = (from.1 as f64 + t * (to.1 as f64 - from.1 as f64)).round() as usize;
+                    if grid[row][column] == ' ' {
+                        grid[row][column] = '·';
+                    }
+                }
+            }
+        }
+        for (i, (_, points)) in self.series.iter().enumerate() {
+            let marker = MARKERS[i % MARKERS.len()];
+            for &point in points {
+                let Some((column, row)) = cell(point) else { continue };
+                let here = &mut grid[row][column];
+                *here = if *here == ' ' || *here == '·' || *here == marker { marker } else { '@' };
+            }
+        }
+        grid
+    }
+}
+
+impl fmt::Display for LinePlot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let x = Axis::fit(self.points().map(|(x, _)| x), self.log_x);
+        let y = Axis::fit(self.points().map(|(_, y)| y), self.log_y);
+        let grid = self.grid(x, y);
+
+        // y labels on the top, middle and bottom rows
+        let label_rows = [0, (self.height - 1) / 2, self.height - 1];
+        let y_labels: Vec<String> = label_rows
+            .iter()
+            .map(|&row| (self.format_y)(y.value_at(1.0 - row as f64 / (self.height - 1) as f64)))
+            .collect();
+        let label_width = y_labels.iter().map(|label| label.chars().count()).max().unwrap_or(0).max(self.y_label.chars().count());
+
+        writeln!(f, "  {:>w$}", self.y_label, w = label_width)?;
+        for (row, cells) in grid.iter().enumerate() {
+            let label = label_rows.iter().position(|&r| r == row).map_or("", |i| y_labels[i].as_str());
+            writeln!(f, "  {:>w$} ┤{}", label, cells.iter().collect::<String>().trim_end(), w = label_width)?;
+        }
+        writeln!(f, "  {:>w$} └{}", "", "─".repeat(self.width), w = label_width)?;
+
+        // x labels under each distinct x, skipping any that would collide
+        let mut xs: Vec<f64> = self.points().map(|(x, _)| x).collect();
+        xs.sort_by(f64::total_cmp);
+        xs.dedup();
+        let mut ticks = vec![' '; self.width + 8];
+        let mut free_from = 0;
+        for value in xs {
+            let Some(position) = x.position(value) else { continue };
+            let label: Vec<char> = (self.format_x)(value).chars().collect();
+            let center = (position * (self.width - 1) as f64).round() as usize;
+            let start = center.saturating_sub(label.len() / 2);
+            if start < free_from || start + label.len() > ticks.len() {
+                continue;
+            }
+            ticks[start..start + label.len()].copy_from_slice(&label);
+            free_from = start + label.len() + 1;
+        }
+        writeln!(f, "  {:>w$}  {}  {}", "", ticks.iter().collect::<String>().trim_end(), self.x_label, w = label_width)?;
+
+        let legend: Vec<String> = self
+            .series
+            .iter()
+            .enumerate()
+            .map(|(i, (name, _))| format!("{} {}", MARKERS[i % MARKERS.len()], name))
+            .collect();
+        write!(f, "  {:>w$}  {}", "", legend.join("   "), w = label_width)?;
+        if self.series.len() > 1 {
+            write!(f, "   @ overlap")?;
+        }
+        writeln!(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_axis_labels() {
+        assert_eq!(format_si(950.0), "950");
+        assert_eq!(format_si(1234.5), "1.23K");
+        assert_eq!(format_si(25_000_000.0), "25M");
+        assert_eq!(format_bytes(4096.0), "4K");
+        assert_eq!(format_bytes(48.0 * 1024.0 * 1024.0), "48M");
+        assert_eq!(format_bytes(1536.0), "1.5K");
+    }
+
+    #[test]
+    fn bars_scale_to_the_largest_value() {
+        let chart = Bars::new().width(8).bar("a", 4.0, "4").bar("bb", 1.0, "1").to_string();
+        let lines: Vec<&str> = chart.lines().collect();
+        assert_eq!(lines[0], "   a │████████  4");
+        assert_eq!(lines[1], "  bb │██        1");
+    }
+
+    #[test]
+    fn log_axes_place_doublings_evenly() {
+        let plot = LinePlot::new("threads", "ops/s")
+            .log_x()
+            .log_y()
+            .size(7, 4)
+            .series("rate", vec![(1.0, 10.0), (2.0, 100.0), (4.0, 1000.0), (8.0, 10000.0)]);
+        let x = Axis::fit(plot.points().map(|(x, _)| x), true);
+        let y = Axis::fit(plot.points().map(|(_, y)| y), true);
+        let grid = plot.grid(x, y);
+        let rows: Vec<String> = grid.iter().map(|row| row.iter().collect()).collect();
+        assert_eq!(rows, ["      *", "    *· ", "  *·   ", "*·     "]);
+    }
+
+    #[test]
+    fn overlapping_series_are_marked() {
+        let plot = LinePlot::new("x", "y").size(3, 3).series("a", vec![(0.0, 0.0), (2.0, 2.0)]).series("b", vec![(0.0, 0.0)]);
+        let rendered = plot.to_string();
+        assert!(rendered.contains("┤@"), "{}", rendered);
+        assert!(rendered.contains("* a   o b   @ overlap"), "{}", rendered);
+    }
+}
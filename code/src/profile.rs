@@ -0,0 +1,191 @@
+//! Profiling
+//!
+//! `--profile` on a demo binary, or on the runner's `run`, samples the
+//! demo's own call stacks about a thousand times a second while it runs
+//! (a SIGPROF timer, through the `pprof` crate) and writes two files to
+//! `profiles/`:
+//!
+//! - `<demo>.folded`: one line per distinct stack, `thread;outer;...;inner
+//!   count`, the format `inferno-flamegraph` and `flamegraph.pl` read
+//! - `<demo>.svg`: the flamegraph itself, to open in a browser
+//!
+//! The sampler sees only user-space code in the demo's own process. Kernel
+//! time and helper processes need `perf record -g` (Linux) or `dtrace`
+//! (macOS) on the binary instead; see the README. Windows has no SIGPROF,
+//! so there the flag only prints that.
+
+use std::collections::BTreeMap;
+
+/// The flag that turns profiling on, for demo binaries and the runner
+pub const FLAG: &str = "--profile";
+/// Set by the runner on the demo it launches with `--profile`
+pub const VAR: &str = "SYSTEMS_DEMOS_PROFILE";
+/// Where the folded stacks and flamegraphs go, relative to the working directory
+pub const DIR: &str = "profiles";
+
+/// Samples per second: prime, so the timer doesn't beat in step with a loop
+#[cfg(unix)]
+const FREQUENCY: i32 = 997;
+
+/// Whether this process should profile itself
+pub fn requested() -> bool {
+    std::env::args().skip(1).any(|arg| arg == FLAG) || std::env::var_os(VAR).is_some_and(|value| !value.is_empty())
+}
+
+/// Join stacks (root first) into folded lines, merging identical stacks and
+/// sorting them, as flamegraph tools expect
+pub fn fold(stacks: impl IntoIterator<Item = (Vec<String>, u64)>) -> String {
+    let mut merged: BTreeMap<String, u64> = BTreeMap::new();
+    for (frames, count) in stacks {
+        *merged.entry(frames.join(";")).or_default() += count;
+    }
+    merged.iter().map(|(stack, count)| format!("{} {}\n", stack, count)).collect()
+}
+
+/// The functions with the most samples at the top of the stack (self time),
+/// most first
+pub fn hottest(folded: &str, count: usize) -> Vec<(String, u64)> {
+    let mut totals: BTreeMap<&str, u64> = BTreeMap::new();
+    for line in folded.lines() {
+        let Some((stack, samples)) = line.rsplit_once(' ') else { continue };
+        let leaf = stack.rsplit(';').next().unwrap_or(stack);
+        *totals.entry(leaf).or_default() += samples.parse::<u64>().unwrap_or(0);
+    }
+    let mut totals: Vec<(String, u64)> = totals.into_iter().map(|(name, n)| (name.to_string(), n)).collect();
+    totals.sort_by_key(|&(_, samples)| std::cmp::Reverse(samples));
+    totals.truncate(count);
+    totals
+}
+
+/// `core::fmt::write::h1a2b3c4d5e6f7a8b` -> `core::fmt::write`
+#[cfg_attr(not(unix), allow(dead_code))]
+fn strip_hash(name: &str) -> &str {
+    match name.rsplit_once("::h") {
+        Some((path, hash)) if hash.len() == 16 && hash.bytes().all(|b| b.is_ascii_hexdigit()) => path,
+        _ => name,
+    }
+}
+
+/// Drop the frames every main-thread stack shares (the runtime's start-up
+/// down to `run_from_env`), so the flamegraph starts at the demo
+#[cfg_attr(not(unix), allow(dead_code))]
+fn trim(frames: Vec<String>) -> Vec<String> {
+    match frames.iter().rposition(|frame| frame.contains("run_from_env")) {
+        Some(entry) => frames.into_iter().skip(entry + 1).collect(),
+        None => frames,
+    }
+}
+
+/// A running sampler; `finish` writes what it collected
+#[cfg(unix)]
+pub struct Session {
+    guard: pprof::ProfilerGuard<'static>,
+}
+
+#[cfg(unix)]
+impl Session {
+    /// Start sampling this process, or explain on stderr why not
+    pub fn start() -> Option<Session> {
+        // Unwinding through these libraries from a signal handler can deadlock
+        let guard = pprof::ProfilerGuardBuilder::default()
+            .frequency(FREQUENCY)
+            .blocklist(&["libc", "libgcc", "pthread", "vdso"])
+            .build();
+        match guard {
+            Ok(guard) => Some(Session { guard }),
+            Err(err) => {
+                eprintln!("⚠️  couldn't start the profiler: {}", err);
+                None
+            }
+        }
+    }
+
+    /// Stop sampling and write `profiles/<demo>.folded` and `.svg`
+    pub fn finish(self, demo: &str) {
+        let report = match self.guard.report().build() {
+            Ok(report) => report,
+            Err(err) => {
+                eprintln!("⚠️  couldn't collect the profile: {}", err);
+                return;
+            }
+        };
+        let stacks = report.data.iter().map(|(frames, &count)| {
+            let names = frames.frames.iter().rev().flat_map(|frame| frame.iter().rev());
+            let names: Vec<String> = names.map(|symbol| strip_hash(&symbol.name()).to_string()).collect();
+            let mut stack = vec![frames.thread_name_or_id()];
+            stack.extend(trim(names));
+            (stack, count.max(0) as u64)
+        });
+        let folded = fold(stacks);
+        let samples: u64 = hottest(&folded, usize::MAX).iter().map(|(_, n)| n).sum();
+        if samples == 0 {
+            eprintln!("⚠️  the profiler took no samples: the demo finished too quickly");
+            return;
+        }
+
+        let folded_path = std::path::Path::new(DIR).join(format!("{}.folded", demo));
+        let svg_path = folded_path.with_extension("svg");
+        let written = std::fs::create_dir_all(DIR)
+            .and_then(|()| std::fs::write(&folded_path, &folded))
+            .and_then(|()| std::fs::File::create(&svg_path))
+            .and_then(|svg| {
+                // The flamegraph of the trimmed stacks, not of pprof's own report
+                let mut options = pprof::flamegraph::Options::default();
+                options.title = format!("{} ({} samples at {} Hz)", demo, samples, FREQUENCY);
+                pprof::flamegraph::from_lines(&mut options, folded.lines(), svg).map_err(std::io::Error::other)
+            });
+        if let Err(err) = written {
+            eprintln!("⚠️  couldn't write the profile to {}: {}", DIR, err);
+            return;
+        }
+        eprintln!("🔥 {} samples: flamegraph in {}, folded stacks in {}", samples, svg_path.display(),
+                  folded_path.display());
+        for (function, count) in hottest(&folded, 5) {
+            eprintln!("   {:>5.1}%  {}", count as f64 * 100.0 / samples as f64, function);
+        }
+    }
+}
+
+/// Windows has no SIGPROF timer to sample with
+#[cfg(not(unix))]
+pub struct Session;
+
+#[cfg(not(unix))]
+impl Session {
+    pub fn start() -> Option<Session> {
+        eprintln!("⚠️  --profile samples with SIGPROF, which needs Unix; on Windows, profile the binary");
+        eprintln!("   with Windows Performance Recorder or Visual Studio instead");
+        None
+    }
+
+    pub fn finish(self, _demo: &str) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stack(frames: &[&str]) -> Vec<String> {
+        frames.iter().map(|frame| frame.to_string()).collect()
+    }
+
+    #[test]
+    fn folds_and_ranks_stacks() {
+        let folded = fold([
+            (stack(&["main", "demo", "sum"]), 3),
+            (stack(&["main", "demo", "sort"]), 5),
+            (stack(&["main", "demo", "sum"]), 4),
+            (stack(&["worker", "sum"]), 1),
+        ]);
+        assert_eq!(folded, "main;demo;sort 5\nmain;demo;sum 7\nworker;sum 1\n");
+        assert_eq!(hottest(&folded, 1), [("sum".to_string(), 8)]);
+    }
+
+    #[test]
+    fn cleans_up_frame_names() {
+        assert_eq!(strip_hash("core::fmt::write::h1a2b3c4d5e6f7a8b"), "core::fmt::write");
+        assert_eq!(strip_hash("demo::hash"), "demo::hash");
+        let frames = stack(&["_start", "main", "systems_demos::demo::run_from_env", "Demo::run", "work"]);
+        assert_eq!(trim(frames), ["Demo::run", "work"]);
+    }
+}
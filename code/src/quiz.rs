@@ -0,0 +1,153 @@
+//! Quizzes
+//!
+//! A few multiple-choice questions per demo about what it just showed, for
+//! readers using the crate to study. A demo registers its questions with
+//! `#[demo(quiz = QUIZ)]`; `--quiz` (on a demo binary, or on the runner's
+//! `run` and `all`) asks them after the demo finishes and keeps score.
+
+use std::fmt;
+use std::io::{self, BufRead, Write};
+
+/// The flag that turns the quiz on, for demo binaries and the runner
+pub const FLAG: &str = "--quiz";
+
+/// One multiple-choice question; `answer` indexes `choices`
+#[derive(Debug, Clone, Copy)]
+pub struct Question {
+    pub prompt: &'static str,
+    pub choices: &'static [&'static str],
+    pub answer: usize,
+    /// Shown after every answer, right or wrong
+    pub explanation: &'static str,
+}
+
+impl Question {
+    pub const fn new(prompt: &'static str, choices: &'static [&'static str], answer: usize,
+                     explanation: &'static str) -> Question {
+        Question { prompt, choices, answer, explanation }
+    }
+}
+
+/// Correct answers out of questions answered
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Score {
+    pub correct: usize,
+    pub answered: usize,
+}
+
+impl std::ops::AddAssign for Score {
+    fn add_assign(&mut self, other: Score) {
+        self.correct += other.correct;
+        self.answered += other.answered;
+    }
+}
+
+impl fmt::Display for Score {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{} correct", self.correct, self.answered)
+    }
+}
+
+/// `0` -> `a`
+fn letter(index: usize) -> char {
+    (b'a' + index as u8) as char
+}
+
+/// `a`/`A`/`1` -> `Some(0)`; `None` if it isn't one of `count` choices
+fn parse_choice(input: &str, count: usize) -> Option<usize> {
+    let input = input.trim().to_ascii_lowercase();
+    let index = match input.as_bytes() {
+        [c @ b'a'..=b'z'] => (c - b'a') as usize,
+        _ => input.parse::<usize>().ok()?.checked_sub(1)?,
+    };
+    (index < count).then_some(index)
+}
+
+/// Ask each question on `output`, reading answers from `input` until the
+/// questions or the input run out
+pub fn ask(questions: &[Question], input: &mut impl BufRead, output: &mut impl Write) -> io::Result<Score> {
+    let mut score = Score::default();
+    for (number, question) in questions.iter().enumerate() {
+        writeln!(output, "Q{}. {}", number + 1, question.prompt)?;
+        for (i, choice) in question.choices.iter().enumerate() {
+            writeln!(output, "   {}) {}", letter(i), choice)?;
+        }
+        let last = letter(question.choices.len() - 1);
+        let choice = loop {
+            write!(output, "Your answer (a-{}): ", last)?;
+            output.flush()?;
+            let mut line = String::new();
+            if input.read_line(&mut line)? == 0 {
+                writeln!(output)?;
+                return Ok(score);
+            }
+            match parse_choice(&line, question.choices.len()) {
+                Some(choice) => break choice,
+                None => writeln!(output, "Please answer with a letter from a to {}.", last)?,
+            }
+        };
+
+        score.answered += 1;
+        if choice == question.answer {
+            score.correct += 1;
+            writeln!(output, "✅ Correct! {}\n", question.explanation)?;
+        } else {
+            writeln!(output, "❌ The answer is {}) {}. {}\n", letter(question.answer),
+                     question.choices[question.answer], question.explanation)?;
+        }
+    }
+    Ok(score)
+}
+
+/// Quiz the reader on `demo_name` over stdin/stdout and print the score
+pub fn run(demo_name: &str, questions: &[Question]) -> Score {
+    if questions.is_empty() {
+        return Score::default();
+    }
+    let title = format!("🧠 Quiz: {}", demo_name);
+    println!("\n{}", title);
+    println!("{}", "=".repeat(title.chars().count()));
+    let score = ask(questions, &mut io::stdin().lock(), &mut io::stdout()).unwrap_or_else(|err| {
+        eprintln!("⚠️  quiz interrupted: {}", err);
+        Score::default()
+    });
+    println!("Score: {}", score);
+    score
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const QUIZ: &[Question] = &[
+        Question::new("Which is a cache line on x86_64?", &["8 bytes", "64 bytes", "4 KiB"], 1, "64 bytes."),
+        Question::new("Which needs unsafe?", &["Dereferencing a raw pointer", "Creating one"], 0, "Only the deref."),
+    ];
+
+    fn quiz(answers: &str) -> (Score, String) {
+        let mut output = Vec::new();
+        let score = ask(QUIZ, &mut answers.as_bytes(), &mut output).unwrap();
+        (score, String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn scores_letters_and_numbers() {
+        let (score, output) = quiz("B\n2\n");
+        assert_eq!(score, Score { correct: 1, answered: 2 });
+        assert!(output.contains("✅ Correct! 64 bytes."));
+        assert!(output.contains("❌ The answer is a) Dereferencing a raw pointer."));
+    }
+
+    #[test]
+    fn asks_again_after_an_invalid_answer() {
+        let (score, output) = quiz("z\n\nb\na\n");
+        assert_eq!(score, Score { correct: 2, answered: 2 });
+        assert_eq!(output.matches("Please answer with a letter from a to c.").count(), 2);
+    }
+
+    #[test]
+    fn stops_at_end_of_input() {
+        let (score, _) = quiz("b\n");
+        assert_eq!(score, Score { correct: 1, answered: 1 });
+    }
+}
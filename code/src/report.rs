@@ -0,0 +1,173 @@
+//! Markdown and HTML Reports
+//!
+//! Renders one runner session (every demo's output plus what it measured)
+//! as a single document: a "how my machine behaves" snapshot to keep or
+//! share. `systems-demos report` captures the output and calls `markdown`
+//! or `html`.
+
+use std::time::Duration;
+
+use crate::bench::format_rate;
+use crate::catalog;
+use crate::demo::Metric;
+use crate::results::Results;
+
+const TITLE: &str = "How My Machine Behaves";
+
+/// Render `results` as Markdown; `outputs[i]` is what `results.results[i]`
+/// printed
+pub fn markdown(results: &Results, outputs: &[String]) -> String {
+    let mut doc = format!("# {}\n\n", TITLE);
+    doc.push_str(&format!("Generated by `systems-demos report` on {}.\n\n", utc_timestamp(results.timestamp)));
+    doc.push_str("| | |\n|---|---|\n");
+    for (label, value) in summary(results) {
+        doc.push_str(&format!("| {} | {} |\n", label, md_cell(&value)));
+    }
+
+    doc.push_str("\n## Contents\n\n");
+    for result in &results.results {
+        doc.push_str(&format!("- [{}](#{}) {}\n", result.demo, result.demo, status_mark(result.succeeded)));
+    }
+
+    for (result, output) in results.results.iter().zip(outputs) {
+        doc.push_str(&format!("\n## {}\n\n", result.demo));
+        if let Some(entry) = catalog::find(&result.demo) {
+            doc.push_str(&format!("_{}_ ({})\n\n", md_cell(entry.description), entry.category));
+        }
+        if !result.succeeded {
+            doc.push_str("> ❌ The demo exited with an error; its output may be incomplete.\n\n");
+        }
+        if !result.metrics.is_empty() {
+            doc.push_str("| metric | parameters | value |\n|---|---|---:|\n");
+            for metric in &result.metrics {
+                doc.push_str(&format!("| {} | {} | {} |\n",
+                                      md_cell(&metric.name), md_cell(&params(metric)), display_value(metric)));
+            }
+            doc.push('\n');
+        }
+        let fence = "`".repeat(longest_backtick_run(output).max(2) + 1);
+        doc.push_str(&format!("{}text\n{}\n{}\n", fence, output.trim_end(), fence));
+    }
+    doc
+}
+
+/// Render `results` as a standalone HTML page; `outputs[i]` is what
+/// `results.results[i]` printed
+pub fn html(results: &Results, outputs: &[String]) -> String {
+    let mut doc = String::from("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    doc.push_str(&format!("<title>{}</title>\n", TITLE));
+    doc.push_str("<style>\n\
+        body { font-family: system-ui, sans-serif; max-width: 60rem; margin: 2rem auto; padding: 0 1rem; }\n\
+        table { border-collapse: collapse; margin: 1rem 0; }\n\
+        th, td { border: 1px solid #ccc; padding: 0.25rem 0.6rem; text-align: left; }\n\
+        td.value { text-align: right; font-variant-numeric: tabular-nums; }\n\
+        pre { background: #f6f8fa; padding: 1rem; overflow-x: auto; }\n\
+        .failed { color: #b00; }\n\
+        </style>\n</head>\n<body>\n");
+    doc.push_str(&format!("<h1>{}</h1>\n", TITLE));
+    doc.push_str(&format!("<p>Generated by <code>systems-demos report</code> on {}.</p>\n",
+                          utc_timestamp(results.timestamp)));
+    doc.push_str("<table>\n");
+    for (label, value) in summary(results) {
+        doc.push_str(&format!("<tr><th>{}</th><td>{}</td></tr>\n", label, escape(&value)));
+    }
+    doc.push_str("</table>\n<h2>Contents</h2>\n<ul>\n");
+    for result in &results.results {
+        doc.push_str(&format!("<li><a href=\"#{}\">{}</a> {}</li>\n",
+                              result.demo, result.demo, status_mark(result.succeeded)));
+    }
+    doc.push_str("</ul>\n");
+
+    for (result, output) in results.results.iter().zip(outputs) {
+        doc.push_str(&format!("<h2 id=\"{}\">{}</h2>\n", result.demo, result.demo));
+        if let Some(entry) = catalog::find(&result.demo) {
+            doc.push_str(&format!("<p><em>{}</em> ({})</p>\n", escape(entry.description), entry.category));
+        }
+        if !result.succeeded {
+            doc.push_str("<p class=\"failed\">❌ The demo exited with an error; its output may be incomplete.</p>\n");
+        }
+        if !result.metrics.is_empty() {
+            doc.push_str("<table>\n<tr><th>metric</th><th>parameters</th><th>value</th></tr>\n");
+            for metric in &result.metrics {
+                doc.push_str(&format!("<tr><td>{}</td><td>{}</td><td class=\"value\">{}</td></tr>\n",
+                                      escape(&metric.name), escape(&params(metric)), escape(&display_value(metric))));
+            }
+            doc.push_str("</table>\n");
+        }
+        doc.push_str(&format!("<pre>{}</pre>\n", escape(output.trim_end())));
+    }
+    doc.push_str("</body>\n</html>\n");
+    doc
+}
+
+/// The machine and settings the report was produced with
+fn summary(results: &Results) -> Vec<(&'static str, String)> {
+    let env = &results.environment;
+    let config = &results.parameters;
+    let parameters: Vec<String> =
+        [("size", config.size), ("threads", config.threads), ("iterations", config.iterations)]
+            .into_iter()
+            .filter_map(|(name, value)| value.map(|v| format!("--{} {}", name, v)))
+            .chain(config.seed.map(|seed| format!("--seed {}", seed)))
+            .collect();
+    let mut rows = env.rows();
+    rows.push(("Parameters", if parameters.is_empty() { "each demo's defaults".to_string() } else { parameters.join(" ") }));
+    rows
+}
+
+fn status_mark(succeeded: bool) -> &'static str {
+    if succeeded { "✅" } else { "❌" }
+}
+
+/// A metric's parameters as `key=value, key=value`
+pub fn params(metric: &Metric) -> String {
+    metric.params.iter().map(|(key, value)| format!("{}={}", key, value)).collect::<Vec<_>>().join(", ")
+}
+
+/// A metric's value the way a reader expects it: durations as `1.23ms`,
+/// rates with a K/M/G prefix
+pub fn display_value(metric: &Metric) -> String {
+    let unit = metric.unit.as_str();
+    if unit == "ns" && metric.value < 1000.0 {
+        // Duration would round sub-microsecond latencies to whole nanoseconds
+        format!("{:.2}ns", metric.value)
+    } else if unit == "ns" {
+        format!("{:.2?}", Duration::from_nanos(metric.value as u64))
+    } else if let Some(per) = unit.strip_suffix("/s") {
+        // format_rate gives e.g. "12.50 M/s": splice the unit's noun in
+        format!("{} {}/s", format_rate(metric.value).trim_end_matches("/s").trim_end(), per)
+    } else if metric.value.fract() == 0.0 {
+        format!("{:.0} {}", metric.value, unit)
+    } else {
+        format!("{:.3} {}", metric.value, unit)
+    }
+}
+
+/// Escape text for a Markdown table cell
+fn md_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// So the code fence around a demo's output is longer than anything inside it
+fn longest_backtick_run(text: &str) -> usize {
+    text.split(|c| c != '`').map(str::len).max().unwrap_or(0)
+}
+
+/// `YYYY-MM-DD HH:MM UTC` for seconds since the Unix epoch
+pub fn utc_timestamp(secs: u64) -> String {
+    // Days since the epoch to a civil date (Howard Hinnant's algorithm)
+    let z = (secs / 86_400) as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02} {:02}:{:02} UTC", year, month, day, secs % 86_400 / 3600, secs % 3600 / 60)
+}
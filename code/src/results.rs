@@ -0,0 +1,94 @@
+//! Structured Results
+//!
+//! What `systems-demos run --format json|csv` prints instead of prose: every
+//! metric the demos reported, the parameters they ran with, and the machine
+//! they ran on, in a shape scripts and regression trackers can consume.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::demo::{Config, Metric};
+pub use crate::envinfo::Environment;
+
+/// One demo's outcome
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DemoResult {
+    pub demo: String,
+    pub succeeded: bool,
+    pub metrics: Vec<Metric>,
+}
+
+/// Everything one runner invocation measured
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Results {
+    /// Seconds since the Unix epoch when the run started
+    pub timestamp: u64,
+    pub environment: Environment,
+    /// What the runner forwarded; `null` means each demo's default
+    pub parameters: Config,
+    pub results: Vec<DemoResult>,
+}
+
+impl Results {
+    pub fn new(parameters: Config) -> Results {
+        Results {
+            timestamp: SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs()),
+            environment: Environment::current(),
+            parameters,
+            results: Vec::new(),
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("results are always serializable")
+    }
+
+    /// Read back what `to_json` wrote, e.g. a saved baseline
+    pub fn from_json(json: &str) -> serde_json::Result<Results> {
+        serde_json::from_str(json)
+    }
+
+    /// One row per metric, with the parameters and environment repeated on
+    /// every row so each line stands alone in a spreadsheet or `grep`
+    pub fn to_csv(&self) -> String {
+        let env = &self.environment;
+        let forwarded = |value: Option<usize>| value.map(|v| v.to_string()).unwrap_or_default();
+        let mut csv =
+            String::from("demo,metric,value,unit,params,size,threads,iterations,seed,os,arch,cpus,profile,timestamp\n");
+        for result in &self.results {
+            for metric in &result.metrics {
+                let params: Vec<String> = metric.params.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+                let row = [
+                    result.demo.clone(),
+                    metric.name.clone(),
+                    metric.value.to_string(),
+                    metric.unit.clone(),
+                    params.join(";"),
+                    forwarded(self.parameters.size),
+                    forwarded(self.parameters.threads),
+                    forwarded(self.parameters.iterations),
+                    self.parameters.seed.map(|seed| seed.to_string()).unwrap_or_default(),
+                    env.os.clone(),
+                    env.arch.clone(),
+                    env.cpus.to_string(),
+                    env.profile.clone(),
+                    self.timestamp.to_string(),
+                ];
+                let row: Vec<String> = row.iter().map(|field| csv_field(field)).collect();
+                csv.push_str(&row.join(","));
+                csv.push('\n');
+            }
+        }
+        csv
+    }
+}
+
+/// Quote a field if it contains a separator, quote or line break
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
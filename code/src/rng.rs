@@ -0,0 +1,94 @@
+//! Seeded Randomness
+//!
+//! Access-pattern benchmarks need addresses the prefetcher can't predict,
+//! and comparable runs need the same addresses every time. Index formulas
+//! like `(i * 997) % size` are neither: they walk memory with a fixed
+//! stride, which the hardware can learn, and which aliases badly when
+//! `size` shares factors with the stride. `Rng` is xoshiro256** seeded
+//! through SplitMix64, small enough to read and good enough for shuffling.
+//! Demos seed it from `Config::seed` (`--seed` on the runner), so a run
+//! can be repeated exactly or varied on purpose.
+//! `prng-demo` tests it next to an LCG, xorshift64* and PCG32.
+
+/// The seed demos use when `--seed` isn't given
+pub const DEFAULT_SEED: u64 = 42;
+
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: [u64; 4],
+}
+
+impl Rng {
+    /// The same seed always gives the same sequence
+    pub fn new(seed: u64) -> Rng {
+        // SplitMix64 spreads any seed, even 0, over the four state words
+        let mut mix = seed;
+        let mut next = || {
+            mix = mix.wrapping_add(0x9E37_79B9_7F4A_7C15);
+            let mut z = mix;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+            z ^ (z >> 31)
+        };
+        Rng { state: [next(), next(), next(), next()] }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let [s0, s1, s2, s3] = &mut self.state;
+        let result = s1.wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = *s1 << 17;
+        *s2 ^= *s0;
+        *s3 ^= *s1;
+        *s1 ^= *s2;
+        *s0 ^= *s3;
+        *s2 ^= t;
+        *s3 = s3.rotate_left(45);
+        result
+    }
+
+    /// A number in `0..bound` (`bound` > 0). Multiplying instead of taking
+    /// `% bound` keeps the high bits, the best ones; the bias is below
+    /// bound / 2^64, far too small to show up in a benchmark.
+    pub fn below(&mut self, bound: usize) -> usize {
+        ((self.next_u64() as u128 * bound as u128) >> 64) as usize
+    }
+
+    /// Put `items` in a uniformly random order (Fisher-Yates)
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+
+    /// `0..n` in a random order: each index exactly once
+    pub fn permutation(&mut self, n: usize) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..n).collect();
+        self.shuffle(&mut order);
+        order
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_same_sequence() {
+        let mut a = Rng::new(7);
+        let mut b = Rng::new(7);
+        let mut c = Rng::new(8);
+        let first: Vec<u64> = (0..4).map(|_| a.next_u64()).collect();
+        assert_eq!(first, (0..4).map(|_| b.next_u64()).collect::<Vec<_>>());
+        assert_ne!(first, (0..4).map(|_| c.next_u64()).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn permutations_use_every_index_once() {
+        let mut order = Rng::new(DEFAULT_SEED).permutation(1000);
+        assert_ne!(order, (0..1000).collect::<Vec<_>>());
+        order.sort_unstable();
+        assert_eq!(order, (0..1000).collect::<Vec<_>>());
+        let mut rng = Rng::new(3);
+        assert!((0..10_000).all(|_| rng.below(10) < 10));
+    }
+}
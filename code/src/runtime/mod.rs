@@ -0,0 +1,231 @@
+//! A Minimal Async Runtime
+//!
+//! The two halves every async runtime has, in a few hundred lines:
+//! - **Executor**: a queue of ready tasks. Polling a task runs it until it
+//!   returns `Pending`; its `Waker` puts it back in the queue later.
+//! - **Reactor** (`reactor`): asks the kernel (epoll) which sockets are
+//!   ready and calls the wakers of the tasks waiting on them.
+//!
+//! Timers (`time`) ride along: the earliest deadline bounds how long the
+//! executor may block in the reactor.
+//!
+//! The loop: poll every ready task → if nothing is ready, block in
+//! `epoll_wait` → wake the tasks whose sockets fired → repeat. Waiting on
+//! 10 000 idle sockets therefore costs 10 000 small futures and one sleeping
+//! thread, instead of 10 000 blocked threads.
+//!
+//! Single-threaded and Linux-only (epoll). `spawn` and the types in `net`
+//! find the runtime through a thread-local, like tokio's.
+
+pub mod net;
+pub mod reactor;
+pub mod time;
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Wake, Waker};
+use std::time::{Duration, Instant};
+
+use reactor::{Reactor, ReactorStats};
+use time::Timers;
+
+type BoxFuture = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// Tasks that are ready to be polled, shared with every waker
+struct ReadyQueue {
+    tasks: Mutex<VecDeque<Arc<Task>>>,
+    /// Set while the runtime thread is (about to be) blocked in epoll_wait
+    parked: AtomicBool,
+    reactor: Arc<Reactor>,
+    timers: Arc<Mutex<Timers>>,
+}
+
+impl ReadyQueue {
+    fn push(&self, task: Arc<Task>) {
+        self.tasks.lock().unwrap().push_back(task);
+        self.unpark();
+    }
+
+    fn unpark(&self) {
+        // Pairs with the SeqCst store + re-check in `Runtime::park`: either
+        // the runtime sees our task, or we see `parked` and kick epoll_wait
+        if self.parked.load(Ordering::SeqCst) {
+            self.reactor.notify();
+        }
+    }
+}
+
+struct Task {
+    future: Mutex<Option<BoxFuture>>,
+    /// Already in the ready queue - don't enqueue twice
+    scheduled: AtomicBool,
+    queue: Arc<ReadyQueue>,
+}
+
+impl Wake for Task {
+    fn wake(self: Arc<Self>) {
+        if !self.scheduled.swap(true, Ordering::AcqRel) {
+            let queue = Arc::clone(&self.queue);
+            queue.push(self);
+        }
+    }
+}
+
+/// Waker for the future passed to `block_on`, which isn't a queued task
+struct MainWaker {
+    woken: AtomicBool,
+    queue: Arc<ReadyQueue>,
+}
+
+impl Wake for MainWaker {
+    fn wake(self: Arc<Self>) {
+        self.woken.store(true, Ordering::SeqCst);
+        self.queue.unpark();
+    }
+}
+
+/// Counters exposed for the demos
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RuntimeStats {
+    pub tasks_spawned: u64,
+    pub task_polls: u64,
+    pub timers_fired: u64,
+    pub reactor: ReactorStats,
+}
+
+/// Handle stored in the thread-local while `block_on` runs
+#[derive(Clone)]
+struct Handle {
+    queue: Arc<ReadyQueue>,
+    spawned: Arc<AtomicU64>,
+}
+
+thread_local! {
+    static CURRENT: RefCell<Option<Handle>> = const { RefCell::new(None) };
+}
+
+fn current() -> Handle {
+    CURRENT.with(|current| current.borrow().clone()).expect("must be called from inside Runtime::block_on")
+}
+
+/// The reactor of the runtime running on this thread
+pub(crate) fn current_reactor() -> Arc<Reactor> {
+    Arc::clone(&current().queue.reactor)
+}
+
+/// The timers of the runtime running on this thread
+pub(crate) fn current_timers() -> Arc<Mutex<Timers>> {
+    Arc::clone(&current().queue.timers)
+}
+
+/// Run `future` concurrently with the caller on the current runtime
+pub fn spawn(future: impl Future<Output = ()> + Send + 'static) {
+    let handle = current();
+    handle.spawned.fetch_add(1, Ordering::Relaxed);
+    let task = Arc::new(Task {
+        future: Mutex::new(Some(Box::pin(future))),
+        scheduled: AtomicBool::new(true),
+        queue: Arc::clone(&handle.queue),
+    });
+    handle.queue.push(task);
+}
+
+pub struct Runtime {
+    queue: Arc<ReadyQueue>,
+    spawned: Arc<AtomicU64>,
+    polls: u64,
+    timers_fired: u64,
+}
+
+impl Runtime {
+    pub fn new() -> io::Result<Self> {
+        let queue = Arc::new(ReadyQueue {
+            tasks: Mutex::new(VecDeque::new()),
+            parked: AtomicBool::new(false),
+            reactor: Arc::new(Reactor::new()?),
+            timers: Arc::new(Mutex::new(Timers::default())),
+        });
+        Ok(Runtime { queue, spawned: Arc::new(AtomicU64::new(0)), polls: 0, timers_fired: 0 })
+    }
+
+    /// Drive `future` (and every task it spawns) until `future` completes
+    pub fn block_on<F: Future>(&mut self, future: F) -> F::Output {
+        let handle = Handle { queue: Arc::clone(&self.queue), spawned: Arc::clone(&self.spawned) };
+        let previous = CURRENT.with(|current| current.replace(Some(handle)));
+
+        let mut future = std::pin::pin!(future);
+        let main_waker = Arc::new(MainWaker { woken: AtomicBool::new(true), queue: Arc::clone(&self.queue) });
+        let waker = Waker::from(Arc::clone(&main_waker));
+
+        let output = loop {
+            if main_waker.woken.swap(false, Ordering::SeqCst)
+                && let Poll::Ready(output) = future.as_mut().poll(&mut Context::from_waker(&waker))
+            {
+                break output;
+            }
+            self.run_ready_tasks();
+            if !main_waker.woken.load(Ordering::SeqCst) {
+                self.park(&main_waker);
+            }
+        };
+
+        CURRENT.with(|current| *current.borrow_mut() = previous);
+        output
+    }
+
+    /// Poll the tasks that are ready right now. Tasks woken while we do this
+    /// wait for the next round, so I/O keeps getting checked.
+    fn run_ready_tasks(&mut self) {
+        let batch: Vec<_> = self.queue.tasks.lock().unwrap().drain(..).collect();
+        for task in batch {
+            task.scheduled.store(false, Ordering::Release);
+            let waker = Waker::from(Arc::clone(&task));
+            let mut slot = task.future.lock().unwrap();
+            if let Some(future) = slot.as_mut() {
+                self.polls += 1;
+                if future.as_mut().poll(&mut Context::from_waker(&waker)).is_ready() {
+                    *slot = None; // finished: drop its state now
+                }
+            }
+        }
+    }
+
+    /// Nothing to do: sleep in the reactor until a socket, a wake, or the
+    /// next timer fires
+    fn park(&mut self, main_waker: &MainWaker) {
+        let next_deadline = self.fire_timers();
+        self.queue.parked.store(true, Ordering::SeqCst);
+        let idle = self.queue.tasks.lock().unwrap().is_empty() && !main_waker.woken.load(Ordering::SeqCst);
+        let timeout = match next_deadline {
+            _ if !idle => Some(Duration::ZERO),
+            // Round up: epoll_wait has millisecond resolution, and waking a
+            // hair early would just spin once more
+            Some(deadline) => Some(deadline.saturating_duration_since(Instant::now()) + Duration::from_micros(999)),
+            None => None,
+        };
+        self.queue.reactor.turn(timeout).expect("epoll_wait failed");
+        self.queue.parked.store(false, Ordering::SeqCst);
+        self.fire_timers();
+    }
+
+    /// Wake the tasks whose timers expired; returns the next deadline
+    fn fire_timers(&mut self) -> Option<Instant> {
+        let mut timers = self.queue.timers.lock().unwrap();
+        self.timers_fired += timers.fire_expired(Instant::now());
+        timers.next_deadline()
+    }
+
+    pub fn stats(&self) -> RuntimeStats {
+        RuntimeStats {
+            tasks_spawned: self.spawned.load(Ordering::Relaxed),
+            task_polls: self.polls,
+            timers_fired: self.timers_fired,
+            reactor: self.queue.reactor.stats(),
+        }
+    }
+}
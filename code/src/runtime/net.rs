@@ -0,0 +1,116 @@
+//! Async TCP on top of the reactor
+//!
+//! Each type wraps a non-blocking std socket. An operation first just tries
+//! the syscall; only on `WouldBlock` does it park the task in the reactor.
+//! That's the whole trick: the socket API stays the same, the waiting moves
+//! from the kernel's thread scheduler into our task queue.
+
+use std::future::poll_fn;
+use std::io::{self, Read, Write};
+use std::net::{SocketAddr, ToSocketAddrs};
+use std::os::fd::AsRawFd;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use super::current_reactor;
+use super::reactor::{Interest, Reactor, Source};
+
+/// A registered socket: deregisters itself before the fd is closed
+struct Registration {
+    reactor: Arc<Reactor>,
+    source: Arc<Source>,
+}
+
+impl Registration {
+    fn new(fd: &impl AsRawFd) -> io::Result<Self> {
+        let reactor = current_reactor();
+        let source = reactor.register(fd.as_raw_fd())?;
+        Ok(Registration { reactor, source })
+    }
+
+    /// Run `op` until it stops returning `WouldBlock`, parking in between
+    fn poll_io<T>(
+        &self,
+        cx: &mut Context<'_>,
+        interest: Interest,
+        mut op: impl FnMut() -> io::Result<T>,
+    ) -> Poll<io::Result<T>> {
+        match op() {
+            Err(err) if err.kind() == io::ErrorKind::WouldBlock => {
+                match self.reactor.poll_ready(&self.source, interest, cx) {
+                    Poll::Ready(Err(err)) => Poll::Ready(Err(err)),
+                    _ => Poll::Pending,
+                }
+            }
+            result => Poll::Ready(result),
+        }
+    }
+}
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        self.reactor.deregister(&self.source);
+    }
+}
+
+pub struct TcpListener {
+    // Field order matters: the registration is dropped before the socket
+    registration: Registration,
+    inner: std::net::TcpListener,
+}
+
+impl TcpListener {
+    pub fn bind(addr: impl ToSocketAddrs) -> io::Result<Self> {
+        let inner = std::net::TcpListener::bind(addr)?;
+        inner.set_nonblocking(true)?;
+        Ok(TcpListener { registration: Registration::new(&inner)?, inner })
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.inner.local_addr()
+    }
+
+    pub async fn accept(&self) -> io::Result<(TcpStream, SocketAddr)> {
+        let (stream, addr) =
+            poll_fn(|cx| self.registration.poll_io(cx, Interest::Readable, || self.inner.accept())).await?;
+        Ok((TcpStream::from_std(stream)?, addr))
+    }
+}
+
+pub struct TcpStream {
+    registration: Registration,
+    inner: std::net::TcpStream,
+}
+
+impl TcpStream {
+    /// Wrap a connected std stream (switching it to non-blocking mode)
+    pub fn from_std(inner: std::net::TcpStream) -> io::Result<Self> {
+        inner.set_nonblocking(true)?;
+        Ok(TcpStream { registration: Registration::new(&inner)?, inner })
+    }
+
+    /// Connect (the handshake itself blocks briefly; fine for local demos)
+    pub fn connect(addr: impl ToSocketAddrs) -> io::Result<Self> {
+        Self::from_std(std::net::TcpStream::connect(addr)?)
+    }
+
+    pub async fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let (registration, mut inner) = (&self.registration, &self.inner);
+        poll_fn(|cx| registration.poll_io(cx, Interest::Readable, || inner.read(buf))).await
+    }
+
+    pub async fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let (registration, mut inner) = (&self.registration, &self.inner);
+        poll_fn(|cx| registration.poll_io(cx, Interest::Writable, || inner.write(buf))).await
+    }
+
+    pub async fn write_all(&mut self, mut buf: &[u8]) -> io::Result<()> {
+        while !buf.is_empty() {
+            match self.write(buf).await? {
+                0 => return Err(io::ErrorKind::WriteZero.into()),
+                n => buf = &buf[n..],
+            }
+        }
+        Ok(())
+    }
+}
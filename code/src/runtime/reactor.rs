@@ -0,0 +1,209 @@
+//! The Reactor: turning kernel readiness events into wakeups
+//!
+//! Every socket the runtime owns is registered with one epoll instance.
+//! When a read or write would block, the I/O future stores its `Waker` here
+//! and arms interest in that direction. `turn` sleeps in `epoll_wait` and
+//! wakes exactly the tasks whose sockets became ready.
+//!
+//! Registrations use `EPOLLONESHOT`: an event disarms the fd until the next
+//! `WouldBlock` re-arms it. That costs one `epoll_ctl` per wait but avoids the
+//! lost-wakeup races of edge-triggered mode, which keeps the code short.
+//! (kqueue on BSD/macOS has the same shape: `EV_ADD | EV_ONESHOT`.)
+
+use std::io;
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::time::Duration;
+
+/// Token reserved for the executor's wakeup eventfd
+const WAKEUP_TOKEN: u64 = u64::MAX;
+
+/// Which direction a task is waiting for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interest {
+    Readable,
+    Writable,
+}
+
+#[derive(Default)]
+struct Wakers {
+    read: Option<Waker>,
+    write: Option<Waker>,
+}
+
+impl Wakers {
+    fn epoll_mask(&self) -> u32 {
+        let mut mask = libc::EPOLLONESHOT as u32;
+        if self.read.is_some() {
+            mask |= libc::EPOLLIN as u32 | libc::EPOLLRDHUP as u32;
+        }
+        if self.write.is_some() {
+            mask |= libc::EPOLLOUT as u32;
+        }
+        mask
+    }
+}
+
+/// One registered file descriptor and the tasks waiting on it
+pub struct Source {
+    fd: RawFd,
+    token: u64,
+    wakers: Mutex<Wakers>,
+}
+
+/// Counters exposed for the demos
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ReactorStats {
+    pub epoll_waits: u64,
+    pub events: u64,
+    pub registrations: u64,
+}
+
+pub struct Reactor {
+    epoll: OwnedFd,
+    wakeup: OwnedFd,
+    sources: Mutex<Vec<Option<Arc<Source>>>>,
+    free_tokens: Mutex<Vec<u64>>,
+    epoll_waits: AtomicU64,
+    events: AtomicU64,
+    registrations: AtomicU64,
+}
+
+fn cvt(result: libc::c_int) -> io::Result<libc::c_int> {
+    if result < 0 { Err(io::Error::last_os_error()) } else { Ok(result) }
+}
+
+impl Reactor {
+    pub fn new() -> io::Result<Self> {
+        let epoll = unsafe { OwnedFd::from_raw_fd(cvt(libc::epoll_create1(libc::EPOLL_CLOEXEC))?) };
+        let wakeup = unsafe {
+            OwnedFd::from_raw_fd(cvt(libc::eventfd(0, libc::EFD_NONBLOCK | libc::EFD_CLOEXEC))?)
+        };
+        // The wakeup fd stays armed (level-triggered) for the reactor's lifetime
+        let mut event = libc::epoll_event { events: libc::EPOLLIN as u32, u64: WAKEUP_TOKEN };
+        cvt(unsafe { libc::epoll_ctl(epoll.as_raw_fd(), libc::EPOLL_CTL_ADD, wakeup.as_raw_fd(), &mut event) })?;
+        Ok(Reactor {
+            epoll,
+            wakeup,
+            sources: Mutex::new(Vec::new()),
+            free_tokens: Mutex::new(Vec::new()),
+            epoll_waits: AtomicU64::new(0),
+            events: AtomicU64::new(0),
+            registrations: AtomicU64::new(0),
+        })
+    }
+
+    /// Start tracking a (non-blocking) fd. Nothing is armed until a task waits.
+    pub fn register(&self, fd: RawFd) -> io::Result<Arc<Source>> {
+        let mut sources = self.sources.lock().unwrap();
+        let token = self.free_tokens.lock().unwrap().pop().unwrap_or(sources.len() as u64);
+        let source = Arc::new(Source { fd, token, wakers: Mutex::new(Wakers::default()) });
+
+        let mut event = libc::epoll_event { events: libc::EPOLLONESHOT as u32, u64: token };
+        cvt(unsafe { libc::epoll_ctl(self.epoll.as_raw_fd(), libc::EPOLL_CTL_ADD, fd, &mut event) })?;
+
+        if token as usize == sources.len() {
+            sources.push(Some(Arc::clone(&source)));
+        } else {
+            sources[token as usize] = Some(Arc::clone(&source));
+        }
+        self.registrations.fetch_add(1, Ordering::Relaxed);
+        Ok(source)
+    }
+
+    /// Stop tracking a source; call before its fd is closed
+    pub fn deregister(&self, source: &Source) {
+        // Failure only means the fd is already gone, which is what we want anyway
+        unsafe {
+            libc::epoll_ctl(self.epoll.as_raw_fd(), libc::EPOLL_CTL_DEL, source.fd, std::ptr::null_mut());
+        }
+        self.sources.lock().unwrap()[source.token as usize] = None;
+        self.free_tokens.lock().unwrap().push(source.token);
+    }
+
+    /// Record `cx`'s waker for `interest` and arm the fd. The caller got
+    /// `WouldBlock` and will be polled again once the fd is ready.
+    pub fn poll_ready(&self, source: &Source, interest: Interest, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let mut wakers = source.wakers.lock().unwrap();
+        let slot = match interest {
+            Interest::Readable => &mut wakers.read,
+            Interest::Writable => &mut wakers.write,
+        };
+        *slot = Some(cx.waker().clone());
+        if let Err(err) = self.arm(source, &wakers) {
+            return Poll::Ready(Err(err));
+        }
+        Poll::Pending
+    }
+
+    fn arm(&self, source: &Source, wakers: &Wakers) -> io::Result<()> {
+        let mut event = libc::epoll_event { events: wakers.epoll_mask(), u64: source.token };
+        cvt(unsafe { libc::epoll_ctl(self.epoll.as_raw_fd(), libc::EPOLL_CTL_MOD, source.fd, &mut event) })?;
+        Ok(())
+    }
+
+    /// Wait for readiness events (up to `timeout`, forever if `None`) and
+    /// wake the tasks interested in them
+    pub fn turn(&self, timeout: Option<Duration>) -> io::Result<usize> {
+        let mut events = [libc::epoll_event { events: 0, u64: 0 }; 256];
+        let timeout_ms = timeout.map_or(-1, |t| t.as_millis().min(i32::MAX as u128) as i32);
+
+        self.epoll_waits.fetch_add(1, Ordering::Relaxed);
+        let count = loop {
+            match cvt(unsafe {
+                libc::epoll_wait(self.epoll.as_raw_fd(), events.as_mut_ptr(), events.len() as i32, timeout_ms)
+            }) {
+                Ok(count) => break count as usize,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(err) => return Err(err),
+            }
+        };
+        self.events.fetch_add(count as u64, Ordering::Relaxed);
+
+        for event in &events[..count] {
+            let (flags, token) = (event.events, event.u64);
+            if token == WAKEUP_TOKEN {
+                let mut buf = 0u64;
+                unsafe { libc::read(self.wakeup.as_raw_fd(), &mut buf as *mut u64 as *mut libc::c_void, 8) };
+                continue;
+            }
+            let Some(source) = self.sources.lock().unwrap().get(token as usize).cloned().flatten() else {
+                continue;
+            };
+
+            let failed = flags & (libc::EPOLLERR | libc::EPOLLHUP) as u32 != 0;
+            let mut wakers = source.wakers.lock().unwrap();
+            if (failed || flags & (libc::EPOLLIN | libc::EPOLLRDHUP) as u32 != 0)
+                && let Some(waker) = wakers.read.take()
+            {
+                waker.wake();
+            }
+            if (failed || flags & libc::EPOLLOUT as u32 != 0)
+                && let Some(waker) = wakers.write.take()
+            {
+                waker.wake();
+            }
+            // ONESHOT disarmed the fd; re-arm for a direction still waiting
+            if wakers.read.is_some() || wakers.write.is_some() {
+                self.arm(&source, &wakers)?;
+            }
+        }
+        Ok(count)
+    }
+
+    /// Interrupt a blocking `turn` from any thread
+    pub fn notify(&self) {
+        let one = 1u64;
+        unsafe { libc::write(self.wakeup.as_raw_fd(), &one as *const u64 as *const libc::c_void, 8) };
+    }
+
+    pub fn stats(&self) -> ReactorStats {
+        ReactorStats {
+            epoll_waits: self.epoll_waits.load(Ordering::Relaxed),
+            events: self.events.load(Ordering::Relaxed),
+            registrations: self.registrations.load(Ordering::Relaxed),
+        }
+    }
+}
@@ -0,0 +1,92 @@
+//! Timers
+//!
+//! A sleeping task is just a deadline and a waker in a min-heap. The
+//! executor never needs a thread per timer: before blocking in `epoll_wait`
+//! it uses the earliest deadline as the timeout, and after waking it fires
+//! every timer that has expired.
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll, Waker};
+use std::time::{Duration, Instant};
+
+use super::current_timers;
+
+struct TimerEntry {
+    deadline: Instant,
+    waker: Waker,
+}
+
+impl PartialEq for TimerEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.deadline == other.deadline
+    }
+}
+
+impl Eq for TimerEntry {}
+
+impl PartialOrd for TimerEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for TimerEntry {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.deadline.cmp(&other.deadline)
+    }
+}
+
+/// Pending timers of one runtime, earliest deadline first
+#[derive(Default)]
+pub(crate) struct Timers {
+    heap: BinaryHeap<Reverse<TimerEntry>>,
+}
+
+impl Timers {
+    fn insert(&mut self, deadline: Instant, waker: Waker) {
+        self.heap.push(Reverse(TimerEntry { deadline, waker }));
+    }
+
+    /// Earliest pending deadline, if any
+    pub(crate) fn next_deadline(&self) -> Option<Instant> {
+        self.heap.peek().map(|Reverse(entry)| entry.deadline)
+    }
+
+    /// Wake every timer due at `now`; returns how many fired
+    pub(crate) fn fire_expired(&mut self, now: Instant) -> u64 {
+        let mut fired = 0;
+        while self.heap.peek().is_some_and(|Reverse(entry)| entry.deadline <= now) {
+            let Reverse(entry) = self.heap.pop().unwrap();
+            entry.waker.wake();
+            fired += 1;
+        }
+        fired
+    }
+}
+
+/// Future returned by `sleep`
+pub struct Sleep {
+    deadline: Instant,
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if Instant::now() >= self.deadline {
+            return Poll::Ready(());
+        }
+        // A spurious re-poll registers again; the stale entry just causes
+        // one extra (harmless) wake later
+        current_timers().lock().unwrap().insert(self.deadline, cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+/// Complete after `duration` without blocking the runtime thread
+pub fn sleep(duration: Duration) -> Sleep {
+    Sleep { deadline: Instant::now() + duration }
+}
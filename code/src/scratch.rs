@@ -0,0 +1,60 @@
+//! Scratch Directories
+//!
+//! Demos that work with real files need somewhere to put them that no
+//! other run is using and that doesn't outlive the demo. A [`Scratch`] is a
+//! fresh directory named after the demo, the process and the step, removed
+//! with everything in it when dropped, on every exit path.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// A directory of its own, removed on drop
+pub struct Scratch(PathBuf);
+
+impl Scratch {
+    /// `<demo>-<pid>-<name>` under the system temp dir, emptied if a
+    /// crashed run left one behind
+    pub fn new(demo: &str, name: &str) -> io::Result<Scratch> {
+        Scratch::new_in(&std::env::temp_dir(), demo, name)
+    }
+
+    /// The same directory under `parent` instead of the temp dir
+    pub fn new_in(parent: &Path, demo: &str, name: &str) -> io::Result<Scratch> {
+        let dir = parent.join(format!("{}-{}-{}", demo, std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir)?;
+        Ok(Scratch(dir))
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.0
+    }
+
+    /// The path of `name` inside the directory
+    pub fn path(&self, name: &str) -> PathBuf {
+        self.0.join(name)
+    }
+}
+
+impl Drop for Scratch {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg_attr(miri, ignore = "Miri isolates the filesystem")]
+    fn removes_its_directory_on_drop() {
+        let scratch = Scratch::new("scratch-test", "drop").unwrap();
+        fs::write(scratch.path("file"), b"contents").unwrap();
+        let dir = scratch.dir().to_path_buf();
+        assert!(dir.join("file").exists());
+        drop(scratch);
+        assert!(!dir.exists());
+    }
+}
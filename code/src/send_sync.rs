@@ -0,0 +1,164 @@
+//! Send and Sync
+//!
+//! Two auto traits decide what may cross a thread boundary:
+//!
+//! | trait  | meaning                                 | e.g. missing from      |
+//! |--------|-----------------------------------------|------------------------|
+//! | `Send` | a `T` can be *moved* to another thread  | `Rc<T>`, `*mut T`      |
+//! | `Sync` | a `&T` can be *shared* across threads   | `Cell<T>`, `RefCell<T>`|
+//!
+//! The compiler implements both automatically for any type whose fields all
+//! implement them, which is why a raw pointer field silently makes a struct
+//! `!Send`. The examples below are doc-tests: the `compile_fail` ones must be
+//! rejected, so `cargo test --doc send_sync` checks every claim made here.
+//! (Stable rustdoc doesn't verify the error codes; they document the
+//! intended error.)
+//!
+//! `Rc` keeps a non-atomic reference count. Two threads cloning the same
+//! `Rc` could race on it, so `Rc` is not `Send`:
+//!
+//! ```compile_fail,E0277
+//! use std::rc::Rc;
+//! use std::thread;
+//!
+//! let shared = Rc::new(42);
+//! let handle = thread::spawn(move || *shared); // error: `Rc<i32>` cannot be sent between threads safely
+//! handle.join().unwrap();
+//! ```
+//!
+//! A raw pointer promises nothing about who else can reach its target, so
+//! it is neither `Send` nor `Sync`:
+//!
+//! ```compile_fail,E0277
+//! use std::thread;
+//!
+//! let mut value = 5u32;
+//! let pointer: *mut u32 = &mut value;
+//! thread::spawn(move || unsafe { *pointer = 1 }) // error: `*mut u32` cannot be sent between threads safely
+//!     .join()
+//!     .unwrap();
+//! ```
+//!
+//! `RefCell` tracks borrows with a plain counter. Moving one to another
+//! thread is fine (it's `Send`), but sharing one through `Arc` is not,
+//! because `Arc<T>` is only `Send` when `T` is `Sync`:
+//!
+//! ```compile_fail,E0277
+//! use std::cell::RefCell;
+//! use std::sync::Arc;
+//! use std::thread;
+//!
+//! let shared = Arc::new(RefCell::new(0));
+//! let clone = Arc::clone(&shared);
+//! thread::spawn(move || *clone.borrow_mut() += 1) // error: `RefCell<i32>` cannot be shared between threads safely
+//!     .join()
+//!     .unwrap();
+//! ```
+//!
+//! The fix: `Arc` for an atomic reference count, `Mutex` for a lock that
+//! makes `&Mutex<T>` safe to share:
+//!
+//! ```
+//! use std::sync::{Arc, Mutex};
+//! use std::thread;
+//!
+//! let shared = Arc::new(Mutex::new(0));
+//! let handles: Vec<_> = (0..4)
+//!     .map(|_| {
+//!         let shared = Arc::clone(&shared);
+//!         thread::spawn(move || *shared.lock().unwrap() += 1)
+//!     })
+//!     .collect();
+//! for handle in handles {
+//!     handle.join().unwrap();
+//! }
+//! assert_eq!(*shared.lock().unwrap(), 4);
+//! ```
+
+use std::alloc::{self, Layout};
+use std::ptr::NonNull;
+
+/// Compiles only if `T: Send`; calling it is a compile-time assertion
+pub fn assert_send<T: Send + ?Sized>() {}
+
+/// Compiles only if `T: Sync`; calling it is a compile-time assertion
+pub fn assert_sync<T: Sync + ?Sized>() {}
+
+/// A heap buffer managed by hand through `std::alloc`, the way a wrapper
+/// around a C allocation would be.
+///
+/// The `NonNull<u8>` field makes the compiler treat this type as neither
+/// `Send` nor `Sync`. It is in fact safe to send: the buffer uniquely owns its
+/// allocation (no other pointer to it exists), and the global allocator may
+/// free memory on any thread. So it opts back in to `Send` below:
+///
+/// ```
+/// use std::thread;
+/// use systems_demos::send_sync::OwnedBuffer;
+///
+/// let mut buffer = OwnedBuffer::new(8);
+/// let buffer = thread::spawn(move || {
+///     buffer.as_mut_slice().fill(7);
+///     buffer
+/// })
+/// .join()
+/// .unwrap();
+/// assert_eq!(buffer.as_slice(), &[7; 8]);
+/// ```
+///
+/// It does *not* claim `Sync`, so it can't be shared through an `Arc`:
+///
+/// ```compile_fail,E0277
+/// use std::sync::Arc;
+/// use std::thread;
+/// use systems_demos::send_sync::OwnedBuffer;
+///
+/// let shared = Arc::new(OwnedBuffer::new(8));
+/// let clone = Arc::clone(&shared);
+/// thread::spawn(move || clone.as_slice().len()) // error: `NonNull<u8>` (inside OwnedBuffer) cannot be shared between threads safely
+///     .join()
+///     .unwrap();
+/// ```
+pub struct OwnedBuffer {
+    data: NonNull<u8>,
+    len: usize,
+}
+
+// SAFETY: OwnedBuffer is the only owner of its allocation, so moving it moves
+// all access to the bytes with it; and alloc/dealloc are thread-safe.
+unsafe impl Send for OwnedBuffer {}
+
+impl OwnedBuffer {
+    /// Allocate `len` zeroed bytes (`len` must be non-zero)
+    pub fn new(len: usize) -> Self {
+        assert!(len > 0, "zero-sized allocations aren't supported");
+        let layout = Layout::array::<u8>(len).expect("length overflows isize");
+        // SAFETY: the layout has a non-zero size
+        let raw = unsafe { alloc::alloc_zeroed(layout) };
+        let data = NonNull::new(raw).unwrap_or_else(|| alloc::handle_alloc_error(layout));
+        OwnedBuffer { data, len }
+    }
+
+    pub fn as_slice(&self) -> &[u8] {
+        // SAFETY: `data` points to `len` initialized bytes owned by self
+        unsafe { std::slice::from_raw_parts(self.data.as_ptr(), self.len) }
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [u8] {
+        // SAFETY: as above, and &mut self guarantees exclusive access
+        unsafe { std::slice::from_raw_parts_mut(self.data.as_ptr(), self.len) }
+    }
+
+    /// The allocation's address, to show it moves between threads unchanged
+    pub fn address(&self) -> usize {
+        self.data.as_ptr() as usize
+    }
+}
+
+impl Drop for OwnedBuffer {
+    fn drop(&mut self) {
+        let layout = Layout::array::<u8>(self.len).expect("checked in new");
+        // SAFETY: allocated in `new` with this same layout
+        unsafe { alloc::dealloc(self.data.as_ptr(), layout) }
+    }
+}
@@ -0,0 +1,489 @@
+//! B-Tree of Pages
+//!
+//! A B+tree mapping `u64` keys to `u64` values, stored in a file of fixed
+//! 4 KiB pages. Page 0 holds the metadata (root, page count, height);
+//! every other page is one node. Leaves hold up to 255 key/value pairs and
+//! link to the next leaf for range scans; internal nodes hold up to 340
+//! separator keys, each with the child for keys at or above it. With that
+//! fan-out, three levels reach tens of millions of keys.
+//!
+//! Nodes are read and written through a page cache of `CACHE` pages, an
+//! [`ArenaLru`]: a miss reads the page from the file, and a modified page
+//! is written back when it is evicted or on [`BTree::flush`]. [`Stats`]
+//! counts both, which is the number a database's design minimizes.
+//!
+//! There is no write-ahead log: a crash between flushes can leave the file
+//! with some pages of a split written and others not.
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use crate::bare::ArenaLru;
+
+/// The unit of every read and write, and the size of a node
+pub const PAGE_SIZE: usize = 4096;
+
+/// Kind byte, padding, entry count (u16) and a page link (u32)
+const NODE_HEADER: usize = 8;
+
+/// Key/value pairs per leaf: 16 bytes each
+pub const LEAF_CAPACITY: usize = (PAGE_SIZE - NODE_HEADER) / 16;
+
+/// Separator keys per internal node: a key and a child page, 12 bytes each
+pub const INTERNAL_CAPACITY: usize = (PAGE_SIZE - NODE_HEADER) / 12;
+
+const LEAF: u8 = 1;
+const INTERNAL: u8 = 2;
+
+const MAGIC: &[u8; 8] = b"BTREE001";
+
+/// Page 0 holds the metadata
+const META: u32 = 0;
+
+/// A leaf's `next` when it is the last leaf: page 0 is never a node
+const NO_PAGE: u32 = 0;
+
+type Page = Box<[u8; PAGE_SIZE]>;
+
+/// The first key of a new right half and its page, when a node splits
+type Split = Option<(u64, u32)>;
+
+/// Page traffic since the tree was opened or the counters last reset
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// Pages read from the file: cache misses
+    pub reads: u64,
+    /// Pages written to the file: dirty evictions and flushes
+    pub writes: u64,
+    /// Page accesses served by the cache
+    pub hits: u64,
+}
+
+enum Node {
+    Leaf { keys: Vec<u64>, values: Vec<u64>, next: u32 },
+    /// `first` holds the keys below `keys[0]`; `children[i]` those from `keys[i]` up
+    Internal { first: u32, keys: Vec<u64>, children: Vec<u32> },
+}
+
+impl Node {
+    fn decode(page: &[u8; PAGE_SIZE]) -> io::Result<Node> {
+        let count = u16::from_le_bytes([page[2], page[3]]) as usize;
+        let link = u32_at(page, 4);
+        match page[0] {
+            LEAF if count <= LEAF_CAPACITY => {
+                let entry = |i: usize| NODE_HEADER + i * 16;
+                Ok(Node::Leaf {
+                    keys: (0..count).map(|i| u64_at(page, entry(i))).collect(),
+                    values: (0..count).map(|i| u64_at(page, entry(i) + 8)).collect(),
+                    next: link,
+                })
+            }
+            INTERNAL if count <= INTERNAL_CAPACITY => {
+                let entry = |i: usize| NODE_HEADER + i * 12;
+                Ok(Node::Internal {
+                    first: link,
+                    keys: (0..count).map(|i| u64_at(page, entry(i))).collect(),
+                    children: (0..count).map(|i| u32_at(page, entry(i) + 8)).collect(),
+                })
+            }
+            kind => Err(io::Error::new(io::ErrorKind::InvalidData,
+                                       format!("not a B-tree node: kind {}, {} entries", kind, count))),
+        }
+    }
+
+    fn encode(&self) -> Page {
+        let mut page = Box::new([0; PAGE_SIZE]);
+        match self {
+            Node::Leaf { keys, values, next } => {
+                page[0] = LEAF;
+                page[2..4].copy_from_slice(&(keys.len() as u16).to_le_bytes());
+                page[4..8].copy_from_slice(&next.to_le_bytes());
+                for (i, (key, value)) in keys.iter().zip(values).enumerate() {
+                    let at = NODE_HEADER + i * 16;
+                    page[at..at + 8].copy_from_slice(&key.to_le_bytes());
+                    page[at + 8..at + 16].copy_from_slice(&value.to_le_bytes());
+                }
+            }
+            Node::Internal { first, keys, children } => {
+                page[0] = INTERNAL;
+                page[2..4].copy_from_slice(&(keys.len() as u16).to_le_bytes());
+                page[4..8].copy_from_slice(&first.to_le_bytes());
+                for (i, (key, child)) in keys.iter().zip(children).enumerate() {
+                    let at = NODE_HEADER + i * 12;
+                    page[at..at + 8].copy_from_slice(&key.to_le_bytes());
+                    page[at + 8..at + 12].copy_from_slice(&child.to_le_bytes());
+                }
+            }
+        }
+        page
+    }
+
+    /// The child of an internal node that covers `key`
+    fn child(first: u32, keys: &[u64], children: &[u32], key: u64) -> (usize, u32) {
+        match keys.partition_point(|&separator| separator <= key) {
+            0 => (0, first),
+            index => (index, children[index - 1]),
+        }
+    }
+}
+
+fn u32_at(page: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes(page[at..at + 4].try_into().expect("4 bytes"))
+}
+
+fn u64_at(page: &[u8], at: usize) -> u64 {
+    u64::from_le_bytes(page[at..at + 8].try_into().expect("8 bytes"))
+}
+
+/// A B+tree in a file, with a write-back cache of `CACHE` pages
+pub struct BTree<const CACHE: usize> {
+    file: File,
+    /// Pages by number, with whether they differ from the file
+    cache: ArenaLru<u32, (Page, bool), CACHE>,
+    root: u32,
+    /// Pages in the file, the metadata page included
+    pages: u32,
+    height: u32,
+    len: u64,
+    stats: Stats,
+}
+
+impl<const CACHE: usize> BTree<CACHE> {
+    /// Open the tree in `path`, creating an empty one if the file is new
+    /// or empty
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let mut file = OpenOptions::new().read(true).write(true).create(true).truncate(false).open(path)?;
+        if file.metadata()?.len() == 0 {
+            let mut tree = BTree { file, cache: ArenaLru::new(), root: 1, pages: 2, height: 1, len: 0,
+                                   stats: Stats::default() };
+            tree.write_node(1, &Node::Leaf { keys: Vec::new(), values: Vec::new(), next: NO_PAGE })?;
+            tree.flush()?;
+            tree.stats = Stats::default();
+            return Ok(tree);
+        }
+        // Checked before there is a tree, whose drop would write page 0
+        let mut meta = [0; PAGE_SIZE];
+        file.read_exact(&mut meta)?;
+        if &meta[..8] != MAGIC {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "not a B-tree file"));
+        }
+        Ok(BTree {
+            file,
+            cache: ArenaLru::new(),
+            root: u32_at(&meta, 8),
+            pages: u32_at(&meta, 12),
+            height: u32_at(&meta, 16),
+            len: u64_at(&meta, 20),
+            stats: Stats::default(),
+        })
+    }
+
+    /// The value stored under `key`
+    pub fn get(&mut self, key: u64) -> io::Result<Option<u64>> {
+        let mut id = self.root;
+        loop {
+            match self.node(id)? {
+                Node::Internal { first, keys, children } => id = Node::child(first, &keys, &children, key).1,
+                Node::Leaf { keys, values, .. } => {
+                    return Ok(keys.binary_search(&key).ok().map(|index| values[index]));
+                }
+            }
+        }
+    }
+
+    /// Store `value` under `key`, returning the value it replaces
+    pub fn insert(&mut self, key: u64, value: u64) -> io::Result<Option<u64>> {
+        let (old, split) = self.insert_into(self.root, key, value)?;
+        if let Some((separator, right)) = split {
+            // The root split: a new root above both halves, one level taller
+            let root = self.allocate();
+            self.write_node(root, &Node::Internal { first: self.root, keys: vec![separator], children: vec![right] })?;
+            self.root = root;
+            self.height += 1;
+        }
+        if old.is_none() {
+            self.len += 1;
+        }
+        Ok(old)
+    }
+
+    /// The pairs with `start <= key < end`, in key order, read by walking
+    /// the linked leaves
+    pub fn range(&mut self, start: u64, end: u64) -> io::Result<Vec<(u64, u64)>> {
+        let mut id = self.root;
+        let mut pairs = Vec::new();
+        loop {
+            match self.node(id)? {
+                Node::Internal { first, keys, children } => id = Node::child(first, &keys, &children, start).1,
+                Node::Leaf { keys, values, next } => {
+                    for (&key, &value) in keys.iter().zip(&values) {
+                        if key >= end {
+                            return Ok(pairs);
+                        }
+                        if key >= start {
+                            pairs.push((key, value));
+                        }
+                    }
+                    if next == NO_PAGE {
+                        return Ok(pairs);
+                    }
+                    id = next;
+                }
+            }
+        }
+    }
+
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Levels from the root to the leaves: the pages a lookup visits
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Pages in the file, the metadata page included
+    pub fn pages(&self) -> u32 {
+        self.pages
+    }
+
+    pub fn stats(&self) -> Stats {
+        self.stats
+    }
+
+    pub fn reset_stats(&mut self) {
+        self.stats = Stats::default();
+    }
+
+    /// Write every modified page and the metadata back to the file. This
+    /// hands them to the OS; it does not fsync.
+    pub fn flush(&mut self) -> io::Result<()> {
+        // Least recently used first, so putting them back keeps the order
+        let dirty: Vec<(u32, Page)> = self.cache.iter().filter(|(_, (_, dirty))| *dirty)
+            .map(|(&id, (page, _))| (id, page.clone())).collect();
+        for (id, page) in dirty.into_iter().rev() {
+            self.write_page(id, &page)?;
+            self.cache.put(id, (page, false));
+        }
+        let mut meta = Box::new([0; PAGE_SIZE]);
+        meta[..8].copy_from_slice(MAGIC);
+        meta[8..12].copy_from_slice(&self.root.to_le_bytes());
+        meta[12..16].copy_from_slice(&self.pages.to_le_bytes());
+        meta[16..20].copy_from_slice(&self.height.to_le_bytes());
+        meta[20..28].copy_from_slice(&self.len.to_le_bytes());
+        self.write_page(META, &meta)
+    }
+
+    /// Insert into the subtree at `id`, returning the replaced value and
+    /// the split, if the node had to
+    fn insert_into(&mut self, id: u32, key: u64, value: u64) -> io::Result<(Option<u64>, Split)> {
+        match self.node(id)? {
+            Node::Leaf { mut keys, mut values, next } => {
+                let old = match keys.binary_search(&key) {
+                    Ok(index) => Some(std::mem::replace(&mut values[index], value)),
+                    Err(index) => {
+                        keys.insert(index, key);
+                        values.insert(index, value);
+                        None
+                    }
+                };
+                if keys.len() <= LEAF_CAPACITY {
+                    self.write_node(id, &Node::Leaf { keys, values, next })?;
+                    return Ok((old, None));
+                }
+                let mid = keys.len() / 2;
+                let right = self.allocate();
+                let (right_keys, right_values) = (keys.split_off(mid), values.split_off(mid));
+                let separator = right_keys[0];
+                self.write_node(right, &Node::Leaf { keys: right_keys, values: right_values, next })?;
+                self.write_node(id, &Node::Leaf { keys, values, next: right })?;
+                Ok((old, Some((separator, right))))
+            }
+            Node::Internal { first, mut keys, mut children } => {
+                let (index, child) = Node::child(first, &keys, &children, key);
+                let (old, split) = self.insert_into(child, key, value)?;
+                let Some((separator, page)) = split else {
+                    return Ok((old, None));
+                };
+                keys.insert(index, separator);
+                children.insert(index, page);
+                if keys.len() <= INTERNAL_CAPACITY {
+                    self.write_node(id, &Node::Internal { first, keys, children })?;
+                    return Ok((old, None));
+                }
+                // The middle key moves up; its child becomes the right half's first
+                let mid = keys.len() / 2;
+                let right = self.allocate();
+                let (mut right_keys, mut right_children) = (keys.split_off(mid), children.split_off(mid));
+                let separator = right_keys.remove(0);
+                let right_first = right_children.remove(0);
+                self.write_node(right, &Node::Internal { first: right_first, keys: right_keys, children: right_children })?;
+                self.write_node(id, &Node::Internal { first, keys, children })?;
+                Ok((old, Some((separator, right))))
+            }
+        }
+    }
+
+    fn allocate(&mut self) -> u32 {
+        self.pages += 1;
+        self.pages - 1
+    }
+
+    /// Page `id`, from the cache or else the file
+    fn node(&mut self, id: u32) -> io::Result<Node> {
+        if let Some((page, _)) = self.cache.get(&id) {
+            self.stats.hits += 1;
+            return Node::decode(page);
+        }
+        let mut page = Box::new([0; PAGE_SIZE]);
+        self.read_page(id, &mut page)?;
+        let node = Node::decode(&page)?;
+        self.cache_page(id, page, false)?;
+        Ok(node)
+    }
+
+    fn write_node(&mut self, id: u32, node: &Node) -> io::Result<()> {
+        self.cache_page(id, node.encode(), true)
+    }
+
+    /// Put a page in the cache, writing back whatever it evicts if that
+    /// was modified
+    fn cache_page(&mut self, id: u32, page: Page, dirty: bool) -> io::Result<()> {
+        match self.cache.put(id, (page, dirty)) {
+            Some((evicted, (page, true))) => self.write_page(evicted, &page),
+            _ => Ok(()),
+        }
+    }
+
+    fn read_page(&mut self, id: u32, page: &mut [u8; PAGE_SIZE]) -> io::Result<()> {
+        self.stats.reads += 1;
+        self.file.seek(SeekFrom::Start(id as u64 * PAGE_SIZE as u64))?;
+        self.file.read_exact(page)
+    }
+
+    fn write_page(&mut self, id: u32, page: &[u8; PAGE_SIZE]) -> io::Result<()> {
+        self.stats.writes += 1;
+        self.file.seek(SeekFrom::Start(id as u64 * PAGE_SIZE as u64))?;
+        self.file.write_all(page)
+    }
+}
+
+impl<const CACHE: usize> Drop for BTree<CACHE> {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Rng;
+    use crate::scratch::Scratch;
+
+    /// Keys inserted in ascending order split the rightmost node in half
+    /// each time: the first leaf split comes one key past a full leaf, and
+    /// every later one after another half leaf
+    fn keys_before_leaf_split(splits: usize) -> u64 {
+        (LEAF_CAPACITY + (splits - 1) * (LEAF_CAPACITY + 1) / 2) as u64
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "Miri isolates the filesystem")]
+    fn inserts_replace_and_look_up() {
+        let scratch = Scratch::new("btree-test", "lookup").unwrap();
+        let mut tree = BTree::<8>::open(scratch.path("tree.db")).unwrap();
+        assert!(tree.is_empty());
+        assert_eq!(tree.get(1).unwrap(), None);
+        assert_eq!(tree.insert(1, 10).unwrap(), None);
+        assert_eq!(tree.insert(3, 30).unwrap(), None);
+        assert_eq!(tree.insert(1, 11).unwrap(), Some(10));
+        assert_eq!(tree.len(), 2);
+        assert_eq!(tree.get(1).unwrap(), Some(11));
+        assert_eq!(tree.get(2).unwrap(), None);
+        assert_eq!(tree.range(0, 10).unwrap(), [(1, 11), (3, 30)]);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "Miri isolates the filesystem")]
+    fn a_full_leaf_splits_on_the_next_insert() {
+        let scratch = Scratch::new("btree-test", "leaf-split").unwrap();
+        let mut tree = BTree::<8>::open(scratch.path("tree.db")).unwrap();
+        for key in 0..LEAF_CAPACITY as u64 {
+            tree.insert(key, key * 2).unwrap();
+        }
+        assert_eq!((tree.height(), tree.pages()), (1, 2));
+
+        tree.insert(LEAF_CAPACITY as u64, 0).unwrap();
+        // Two leaves and a new root above them
+        assert_eq!((tree.height(), tree.pages()), (2, 4));
+        for key in 0..LEAF_CAPACITY as u64 {
+            assert_eq!(tree.get(key).unwrap(), Some(key * 2), "key {}", key);
+        }
+        let all = tree.range(0, u64::MAX).unwrap();
+        assert_eq!(all.len(), LEAF_CAPACITY + 1);
+        assert!(all.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "Miri isolates the filesystem")]
+    fn a_full_internal_node_splits_on_the_next_leaf_split() {
+        let scratch = Scratch::new("btree-test", "internal-split").unwrap();
+        let mut tree = BTree::<16>::open(scratch.path("tree.db")).unwrap();
+        // The root holds one separator per leaf split
+        let full = keys_before_leaf_split(INTERNAL_CAPACITY + 1);
+        for key in 0..full {
+            tree.insert(key, key).unwrap();
+        }
+        assert_eq!(tree.height(), 2);
+
+        tree.insert(full, full).unwrap();
+        assert_eq!(tree.height(), 3);
+        assert_eq!(tree.len(), full + 1);
+        for key in (0..=full).step_by(97).chain([full - 1, full]) {
+            assert_eq!(tree.get(key).unwrap(), Some(key), "key {}", key);
+        }
+        assert_eq!(tree.range(full - 300, full + 1).unwrap().len(), 301);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "Miri isolates the filesystem")]
+    fn random_inserts_survive_eviction_and_reopening() {
+        let scratch = Scratch::new("btree-test", "reopen").unwrap();
+        let path = scratch.path("tree.db");
+        let mut rng = Rng::new(7);
+        let keys: Vec<u64> = (0..20_000).map(|_| rng.next_u64() % 1_000_000).collect();
+        {
+            // A cache far smaller than the tree, so most pages go through the file
+            let mut tree = BTree::<4>::open(&path).unwrap();
+            for &key in &keys {
+                tree.insert(key, !key).unwrap();
+            }
+            assert!(tree.stats().writes > 0);
+        }
+        let mut tree = BTree::<4>::open(&path).unwrap();
+        let mut unique = keys.clone();
+        unique.sort_unstable();
+        unique.dedup();
+        assert_eq!(tree.len(), unique.len() as u64);
+        for &key in &keys {
+            assert_eq!(tree.get(key).unwrap(), Some(!key), "key {}", key);
+        }
+        let all: Vec<u64> = tree.range(0, u64::MAX).unwrap().into_iter().map(|(key, _)| key).collect();
+        assert_eq!(all, unique);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "Miri isolates the filesystem")]
+    fn rejects_a_file_that_is_not_a_tree() {
+        let scratch = Scratch::new("btree-test", "garbage").unwrap();
+        let path = scratch.path("tree.db");
+        std::fs::write(&path, [0xAB; PAGE_SIZE]).unwrap();
+        let err = BTree::<4>::open(&path).err().map(|err| err.kind());
+        assert_eq!(err, Some(io::ErrorKind::InvalidData));
+    }
+}
@@ -0,0 +1,543 @@
+//! Log-Structured Key-Value Store
+//!
+//! The Bitcask design: every write appends a record to the end of the
+//! current segment file, and an in-memory hash index maps each key to
+//! where its latest value sits on disk. A write is one sequential append;
+//! a read is one index lookup and one positioned read. Nothing is ever
+//! overwritten in place, so a crash can only leave a torn record at the
+//! very end of the log, which its checksum exposes.
+//!
+//! - Record: `crc32 | key length | value length | key | value`, lengths
+//!   little-endian u32; a value length of `u32::MAX` is a tombstone (a
+//!   delete) and carries no value.
+//! - Durability: `write` puts a record in the page cache, which survives
+//!   the process dying but not the machine; `SyncPolicy` decides how often
+//!   `fsync` pushes it to the disk.
+//! - Recovery: `open` replays every segment in order to rebuild the index,
+//!   truncating a segment at its first record that fails the checksum.
+//! - Compaction: overwritten and deleted values stay in the log as dead
+//!   bytes. `compact` seals the current segment, copies the live records
+//!   of every sealed one into a single new segment and deletes the old
+//!   ones, while writes carry on into a fresh active segment, so it can
+//!   run on a background thread.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use crate::bare::const_eval::crc32;
+
+/// Checksum, key length and value length
+pub const HEADER: usize = 12;
+
+/// The value length that marks a delete
+const TOMBSTONE: u32 = u32::MAX;
+
+/// When writes are forced to disk with `fsync`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncPolicy {
+    /// After every write: nothing acknowledged is ever lost
+    Always,
+    /// After every `n` writes: at most the last `n` lost in a power cut
+    Every(usize),
+    /// Never; the kernel writes back dirty pages when it likes (about 30
+    /// seconds on Linux)
+    Never,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Options {
+    pub sync: SyncPolicy,
+    /// Start a new segment once the active one reaches this size
+    pub segment_size: u64,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options { sync: SyncPolicy::Always, segment_size: 4 << 20 }
+    }
+}
+
+/// What `open` found in the log
+#[derive(Debug, Clone, Default)]
+pub struct Recovery {
+    pub segments: usize,
+    /// Records replayed, tombstones and overwritten values included
+    pub records: usize,
+    /// Bytes cut off the end of segments whose last record was torn
+    pub truncated: u64,
+}
+
+/// What one `compact` call did
+#[derive(Debug, Clone, Default)]
+pub struct Compaction {
+    /// Segments merged and deleted
+    pub segments: usize,
+    pub bytes_before: u64,
+    pub bytes_after: u64,
+    /// Live records copied into the new segment
+    pub records: usize,
+}
+
+/// Where a key's latest record is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Location {
+    segment: u32,
+    /// Of the record's first byte
+    offset: u64,
+    key_len: u32,
+    value_len: u32,
+}
+
+impl Location {
+    fn record_len(&self) -> u64 {
+        (HEADER + self.key_len as usize + self.value_len as usize) as u64
+    }
+
+    fn value_offset(&self) -> u64 {
+        self.offset + (HEADER + self.key_len as usize) as u64
+    }
+}
+
+struct Segment {
+    file: Arc<File>,
+    len: u64,
+}
+
+struct State {
+    index: HashMap<Vec<u8>, Location>,
+    segments: BTreeMap<u32, Segment>,
+    /// The segment writes append to: always the highest id
+    active: u32,
+    /// Bytes of the records the index points at
+    live: u64,
+    /// Writes since the last fsync
+    unsynced: usize,
+    syncs: u64,
+}
+
+pub struct LogStore {
+    dir: PathBuf,
+    options: Options,
+    state: Mutex<State>,
+    /// Held for the whole of a compaction, so two never overlap
+    compacting: Mutex<()>,
+    recovery: Recovery,
+}
+
+/// One record, as it goes on disk
+pub fn encode(key: &[u8], value: Option<&[u8]>) -> Vec<u8> {
+    let value_len = value.map_or(TOMBSTONE, |value| value.len() as u32);
+    let mut record = Vec::with_capacity(HEADER + key.len() + value.map_or(0, <[u8]>::len));
+    record.extend_from_slice(&[0; 4]);
+    record.extend_from_slice(&(key.len() as u32).to_le_bytes());
+    record.extend_from_slice(&value_len.to_le_bytes());
+    record.extend_from_slice(key);
+    record.extend_from_slice(value.unwrap_or_default());
+    let crc = crc32(&record[4..]);
+    record[..4].copy_from_slice(&crc.to_le_bytes());
+    record
+}
+
+/// The record at the start of `bytes`, if it's whole and its checksum
+/// matches: (key length, value length or `TOMBSTONE`)
+fn decode(bytes: &[u8]) -> Option<(u32, u32)> {
+    let field = |at: usize| u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap());
+    if bytes.len() < HEADER {
+        return None;
+    }
+    let (crc, key_len, value_len) = (field(0), field(4), field(8));
+    let len = HEADER + key_len as usize + if value_len == TOMBSTONE { 0 } else { value_len as usize };
+    (bytes.len() >= len && crc32(&bytes[4..len]) == crc).then_some((key_len, value_len))
+}
+
+fn segment_path(dir: &Path, id: u32) -> PathBuf {
+    dir.join(format!("{:08}.log", id))
+}
+
+fn open_segment(dir: &Path, id: u32) -> io::Result<File> {
+    OpenOptions::new().read(true).append(true).create(true).open(segment_path(dir, id))
+}
+
+/// A new file's name lives in its directory, which needs its own fsync
+/// for the file to be findable after a power cut
+#[cfg(unix)]
+fn sync_dir(dir: &Path) -> io::Result<()> {
+    File::open(dir)?.sync_all()
+}
+
+#[cfg(not(unix))]
+fn sync_dir(_dir: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+#[cfg(unix)]
+fn read_exact_at(file: &File, buf: &mut [u8], offset: u64) -> io::Result<()> {
+    std::os::unix::fs::FileExt::read_exact_at(file, buf, offset)
+}
+
+#[cfg(windows)]
+fn read_exact_at(file: &File, mut buf: &mut [u8], mut offset: u64) -> io::Result<()> {
+    while !buf.is_empty() {
+        match std::os::windows::fs::FileExt::seek_read(file, buf, offset)? {
+            0 => return Err(io::ErrorKind::UnexpectedEof.into()),
+            n => {
+                buf = &mut buf[n..];
+                offset += n as u64;
+            }
+        }
+    }
+    Ok(())
+}
+
+impl State {
+    fn active_file(&self) -> &Arc<File> {
+        &self.segments[&self.active].file
+    }
+
+    fn disk_bytes(&self) -> u64 {
+        self.segments.values().map(|segment| segment.len).sum()
+    }
+}
+
+impl LogStore {
+    /// Open the store in `dir`, creating it if need be, and rebuild the
+    /// index by replaying the log
+    pub fn open(dir: impl AsRef<Path>, options: Options) -> io::Result<LogStore> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+        let mut ids: Vec<u32> = fs::read_dir(&dir)?
+            .filter_map(|entry| entry.ok()?.file_name().to_str()?.strip_suffix(".log")?.parse().ok())
+            .collect();
+        ids.sort_unstable();
+
+        let mut recovery = Recovery { segments: ids.len(), ..Recovery::default() };
+        let mut index: HashMap<Vec<u8>, Location> = HashMap::new();
+        let mut segments = BTreeMap::new();
+        for &id in &ids {
+            let bytes = fs::read(segment_path(&dir, id))?;
+            let mut offset = 0;
+            while let Some((key_len, value_len)) = decode(&bytes[offset..]) {
+                let key = bytes[offset + HEADER..offset + HEADER + key_len as usize].to_vec();
+                let location = Location { segment: id, offset: offset as u64, key_len, value_len };
+                if value_len == TOMBSTONE {
+                    index.remove(&key);
+                    offset += HEADER + key_len as usize;
+                } else {
+                    index.insert(key, location);
+                    offset += location.record_len() as usize;
+                }
+                recovery.records += 1;
+            }
+            let file = open_segment(&dir, id)?;
+            if offset < bytes.len() {
+                // A torn or corrupt record: everything from it on is unreadable
+                recovery.truncated += (bytes.len() - offset) as u64;
+                file.set_len(offset as u64)?;
+                file.sync_all()?;
+            }
+            segments.insert(id, Segment { file: Arc::new(file), len: offset as u64 });
+        }
+        let active = match ids.last() {
+            Some(&id) => id,
+            None => {
+                segments.insert(1, Segment { file: Arc::new(open_segment(&dir, 1)?), len: 0 });
+                sync_dir(&dir)?;
+                1
+            }
+        };
+        let live = index.values().map(Location::record_len).sum();
+        let state = State { index, segments, active, live, unsynced: 0, syncs: 0 };
+        Ok(LogStore { dir, options, state: Mutex::new(state), compacting: Mutex::new(()), recovery })
+    }
+
+    /// What `open` replayed and repaired
+    pub fn recovery(&self) -> &Recovery {
+        &self.recovery
+    }
+
+    pub fn put(&self, key: &[u8], value: &[u8]) -> io::Result<()> {
+        assert!(value.len() < TOMBSTONE as usize, "values must be shorter than 4 GiB");
+        self.append(key, Some(value))
+    }
+
+    /// Remove `key`; false if it wasn't there
+    pub fn delete(&self, key: &[u8]) -> io::Result<bool> {
+        if !self.state.lock().unwrap().index.contains_key(key) {
+            return Ok(false);
+        }
+        self.append(key, None)?;
+        Ok(true)
+    }
+
+    pub fn get(&self, key: &[u8]) -> io::Result<Option<Vec<u8>>> {
+        let (location, file) = {
+            let state = self.state.lock().unwrap();
+            let Some(&location) = state.index.get(key) else { return Ok(None) };
+            (location, Arc::clone(&state.segments[&location.segment].file))
+        };
+        // The lock is released: a positioned read needs no shared cursor
+        let mut value = vec![0; location.value_len as usize];
+        read_exact_at(&file, &mut value, location.value_offset())?;
+        Ok(Some(value))
+    }
+
+    pub fn len(&self) -> usize {
+        self.state.lock().unwrap().index.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Bytes in all segment files
+    pub fn disk_bytes(&self) -> u64 {
+        self.state.lock().unwrap().disk_bytes()
+    }
+
+    /// Bytes of the records the index points at; the rest of the disk
+    /// bytes are dead, waiting for compaction
+    pub fn live_bytes(&self) -> u64 {
+        self.state.lock().unwrap().live
+    }
+
+    pub fn segments(&self) -> usize {
+        self.state.lock().unwrap().segments.len()
+    }
+
+    /// How many times the store has called fsync
+    pub fn syncs(&self) -> u64 {
+        self.state.lock().unwrap().syncs
+    }
+
+    /// Force everything written so far to disk
+    pub fn sync(&self) -> io::Result<()> {
+        let mut state = self.state.lock().unwrap();
+        state.active_file().sync_data()?;
+        state.syncs += 1;
+        state.unsynced = 0;
+        Ok(())
+    }
+
+    fn append(&self, key: &[u8], value: Option<&[u8]>) -> io::Result<()> {
+        let record = encode(key, value);
+        let mut state = self.state.lock().unwrap();
+        let active_len = state.segments[&state.active].len;
+        if active_len > 0 && active_len + record.len() as u64 > self.options.segment_size {
+            self.roll(&mut state, 1)?;
+        }
+        let segment = state.active;
+        let active = state.segments.get_mut(&segment).unwrap();
+        (&*active.file).write_all(&record)?;
+        let location = Location {
+            segment,
+            offset: active.len,
+            key_len: key.len() as u32,
+            value_len: value.map_or(TOMBSTONE, |value| value.len() as u32),
+        };
+        active.len += record.len() as u64;
+        let replaced = match value {
+            Some(_) => {
+                state.live += location.record_len();
+                state.index.insert(key.to_vec(), location)
+            }
+            None => state.index.remove(key),
+        };
+        if let Some(replaced) = replaced {
+            state.live -= replaced.record_len();
+        }
+
+        state.unsynced += 1;
+        let sync = match self.options.sync {
+            SyncPolicy::Always => true,
+            SyncPolicy::Every(n) => state.unsynced >= n,
+            SyncPolicy::Never => false,
+        };
+        if sync {
+            state.active_file().sync_data()?;
+            state.syncs += 1;
+            state.unsynced = 0;
+        }
+        Ok(())
+    }
+
+    /// Seal the active segment and start the one `step` ids after it
+    fn roll(&self, state: &mut State, step: u32) -> io::Result<()> {
+        if self.options.sync != SyncPolicy::Never {
+            state.active_file().sync_data()?;
+            state.syncs += 1;
+            state.unsynced = 0;
+        }
+        let id = state.active + step;
+        state.segments.insert(id, Segment { file: Arc::new(open_segment(&self.dir, id)?), len: 0 });
+        state.active = id;
+        if self.options.sync != SyncPolicy::Never {
+            sync_dir(&self.dir)?;
+        }
+        Ok(())
+    }
+
+    /// Merge every sealed segment into one holding only live records.
+    /// Writes and reads go on meanwhile; the lock is only taken to seal the
+    /// active segment at the start and to swap the index at the end.
+    pub fn compact(&self) -> io::Result<Compaction> {
+        let _only_one = self.compacting.lock().unwrap();
+        let (inputs, live, output_id) = {
+            let mut state = self.state.lock().unwrap();
+            let sealed = state.active;
+            // The merged segment takes the id just after the sealed ones, so
+            // replay reads it after them but before anything written since
+            self.roll(&mut state, 2)?;
+            let inputs: Vec<(u32, Arc<File>, u64)> = state.segments.iter()
+                .filter(|&(&id, _)| id <= sealed)
+                .map(|(&id, segment)| (id, Arc::clone(&segment.file), segment.len))
+                .collect();
+            let live: Vec<(Vec<u8>, Location)> = state.index.iter()
+                .filter(|(_, location)| location.segment <= sealed)
+                .map(|(key, &location)| (key.clone(), location))
+                .collect();
+            (inputs, live, sealed + 1)
+        };
+        let files: HashMap<u32, &Arc<File>> = inputs.iter().map(|(id, file, _)| (*id, file)).collect();
+
+        let output = open_segment(&self.dir, output_id)?;
+        let mut writer = BufWriter::new(&output);
+        let mut moved = Vec::with_capacity(live.len());
+        let mut offset = 0;
+        for (key, location) in live {
+            let mut value = vec![0; location.value_len as usize];
+            read_exact_at(files[&location.segment], &mut value, location.value_offset())?;
+            let record = encode(&key, Some(&value));
+            writer.write_all(&record)?;
+            moved.push((key, location, Location { segment: output_id, offset, ..location }));
+            offset += record.len() as u64;
+        }
+        writer.flush()?;
+        drop(writer);
+        // The merged copy must be on disk before the originals go
+        output.sync_all()?;
+        sync_dir(&self.dir)?;
+
+        let records = moved.len();
+        {
+            let mut state = self.state.lock().unwrap();
+            state.segments.insert(output_id, Segment { file: Arc::new(output), len: offset });
+            for (key, old, new) in moved {
+                // A key written or deleted since the snapshot already points elsewhere
+                if let Some(current) = state.index.get_mut(&key)
+                    && *current == old
+                {
+                    *current = new;
+                }
+            }
+            for (id, _, _) in &inputs {
+                state.segments.remove(id);
+            }
+        }
+        // Oldest first: a crash part-way can leave a newer segment's
+        // tombstone without the value it deletes, never the reverse
+        for (id, _, _) in &inputs {
+            fs::remove_file(segment_path(&self.dir, *id))?;
+        }
+        Ok(Compaction {
+            segments: inputs.len(),
+            bytes_before: inputs.iter().map(|(_, _, len)| len).sum(),
+            bytes_after: offset,
+            records,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scratch::Scratch;
+
+    const QUICK: Options = Options { sync: SyncPolicy::Never, segment_size: 4 << 20 };
+
+    #[test]
+    #[cfg_attr(miri, ignore = "Miri isolates the filesystem")]
+    fn reopening_recovers_the_latest_value() {
+        let scratch = Scratch::new("log-store-test", "reopen").unwrap();
+        {
+            let store = LogStore::open(scratch.dir(), QUICK).unwrap();
+            store.put(b"key", b"first").unwrap();
+            store.put(b"key", b"second").unwrap();
+            store.put(b"other", b"value").unwrap();
+        }
+        let store = LogStore::open(scratch.dir(), QUICK).unwrap();
+        assert_eq!(store.recovery().records, 3);
+        assert_eq!(store.len(), 2);
+        assert_eq!(store.get(b"key").unwrap().as_deref(), Some(&b"second"[..]));
+        assert_eq!(store.get(b"other").unwrap().as_deref(), Some(&b"value"[..]));
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "Miri isolates the filesystem")]
+    fn a_delete_survives_reopening() {
+        let scratch = Scratch::new("log-store-test", "delete").unwrap();
+        {
+            let store = LogStore::open(scratch.dir(), QUICK).unwrap();
+            store.put(b"gone", b"value").unwrap();
+            store.put(b"kept", b"value").unwrap();
+            assert!(store.delete(b"gone").unwrap());
+            assert!(!store.delete(b"never there").unwrap());
+        }
+        let store = LogStore::open(scratch.dir(), QUICK).unwrap();
+        assert_eq!(store.get(b"gone").unwrap(), None);
+        assert_eq!(store.get(b"kept").unwrap().as_deref(), Some(&b"value"[..]));
+        assert_eq!(store.len(), 1);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "Miri isolates the filesystem")]
+    fn a_torn_last_record_is_truncated() {
+        let scratch = Scratch::new("log-store-test", "torn").unwrap();
+        {
+            let store = LogStore::open(scratch.dir(), QUICK).unwrap();
+            store.put(b"whole", b"value").unwrap();
+            store.put(b"torn", b"value").unwrap();
+        }
+        let path = segment_path(scratch.dir(), 1);
+        let len = fs::metadata(&path).unwrap().len();
+        OpenOptions::new().write(true).open(&path).unwrap().set_len(len - 3).unwrap();
+
+        let store = LogStore::open(scratch.dir(), QUICK).unwrap();
+        assert_eq!(store.recovery().truncated, encode(b"torn", Some(b"value")).len() as u64 - 3);
+        assert_eq!(store.get(b"whole").unwrap().as_deref(), Some(&b"value"[..]));
+        assert_eq!(store.get(b"torn").unwrap(), None);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "Miri isolates the filesystem")]
+    fn compaction_keeps_live_keys_and_shrinks_the_log() {
+        let scratch = Scratch::new("log-store-test", "compact").unwrap();
+        let store = LogStore::open(scratch.dir(), Options { segment_size: 1024, ..QUICK }).unwrap();
+        for round in 0..10u32 {
+            for key in 0..20u32 {
+                store.put(&key.to_le_bytes(), format!("value {} of {}", round, key).as_bytes()).unwrap();
+            }
+        }
+        for key in 10..20u32 {
+            store.delete(&key.to_le_bytes()).unwrap();
+        }
+        let before = store.disk_bytes();
+        assert!(store.segments() > 1);
+
+        let compaction = store.compact().unwrap();
+        assert_eq!(compaction.records, 10);
+        assert_eq!(compaction.bytes_after, store.live_bytes());
+        assert!(store.disk_bytes() < before, "{} bytes, was {}", store.disk_bytes(), before);
+        let check = |store: &LogStore| {
+            for key in 0..20u32 {
+                let expected = (key < 10).then(|| format!("value 9 of {}", key).into_bytes());
+                assert_eq!(store.get(&key.to_le_bytes()).unwrap(), expected, "key {}", key);
+            }
+        };
+        check(&store);
+        drop(store);
+        check(&LogStore::open(scratch.dir(), QUICK).unwrap());
+    }
+}
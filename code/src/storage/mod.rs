@@ -0,0 +1,12 @@
+//! Storage Engines
+//!
+//! Data structures that live in files rather than in memory, written to
+//! be read: how they lay bytes out on disk, what they keep in RAM to find
+//! them again, and what survives a crash. The demos time them against
+//! the page cache and the disk underneath.
+
+pub mod btree;
+pub mod log_store;
+
+pub use btree::BTree;
+pub use log_store::{LogStore, Options, SyncPolicy};
@@ -0,0 +1,81 @@
+//! Bounded Buffer (Mutex + Condvar)
+//!
+//! The textbook producer-consumer buffer. One mutex protects the queue and
+//! two condition variables let threads sleep until they can make progress:
+//! - `not_full`: producers wait here while the buffer is full
+//! - `not_empty`: consumers wait here while the buffer is empty
+//!
+//! Waiting on a condvar atomically releases the mutex and parks the thread,
+//! so a blocked thread costs no CPU until someone notifies it. Wakeups can be
+//! spurious, which is why every wait sits in a `while` loop that re-checks
+//! the condition.
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Condvar, Mutex};
+
+/// A blocking FIFO queue with a fixed capacity
+pub struct BoundedBuffer<T> {
+    queue: Mutex<VecDeque<T>>,
+    not_full: Condvar,
+    not_empty: Condvar,
+    capacity: usize,
+    producer_waits: AtomicUsize,
+    consumer_waits: AtomicUsize,
+}
+
+impl<T> BoundedBuffer<T> {
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        BoundedBuffer {
+            queue: Mutex::new(VecDeque::with_capacity(capacity)),
+            not_full: Condvar::new(),
+            not_empty: Condvar::new(),
+            capacity,
+            producer_waits: AtomicUsize::new(0),
+            consumer_waits: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Add an item, sleeping while the buffer is full
+    pub fn put(&self, value: T) {
+        let mut queue = self.queue.lock().unwrap();
+        while queue.len() == self.capacity {
+            self.producer_waits.fetch_add(1, Ordering::Relaxed);
+            queue = self.not_full.wait(queue).unwrap();
+        }
+        queue.push_back(value);
+        drop(queue);
+        self.not_empty.notify_one();
+    }
+
+    /// Remove the oldest item, sleeping while the buffer is empty
+    pub fn take(&self) -> T {
+        let mut queue = self.queue.lock().unwrap();
+        while queue.is_empty() {
+            self.consumer_waits.fetch_add(1, Ordering::Relaxed);
+            queue = self.not_empty.wait(queue).unwrap();
+        }
+        let value = queue.pop_front().expect("checked non-empty");
+        drop(queue);
+        self.not_full.notify_one();
+        value
+    }
+
+    pub fn len(&self) -> usize {
+        self.queue.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// How often (producers, consumers) had to go to sleep
+    pub fn wait_counts(&self) -> (usize, usize) {
+        (self.producer_waits.load(Ordering::Relaxed), self.consumer_waits.load(Ordering::Relaxed))
+    }
+}
@@ -0,0 +1,63 @@
+//! Cache-Line Padding
+//!
+//! Two threads writing to *different* variables still fight if those
+//! variables share a cache line: every write invalidates the other core's
+//! copy of the whole line (false sharing). `CachePadded<T>` aligns its value
+//! to a cache-line boundary, which also rounds its size up to a full line,
+//! so neighbours in an array or struct never share one.
+//!
+//! The alignment is chosen per architecture:
+//! - x86_64: 128 bytes. Lines are 64 bytes, but Intel's spatial prefetcher
+//!   fetches lines in adjacent pairs, so 64-byte neighbours still interfere.
+//! - aarch64: 128 bytes. Apple M-series cores use 128-byte lines.
+//! - powerpc64: 128-byte lines.
+//! - everything else: 64 bytes.
+//!
+//! (The same choices as crossbeam's `CachePadded`.)
+
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+
+/// Pads and aligns a value to the length of a cache line
+#[cfg_attr(any(target_arch = "x86_64", target_arch = "aarch64", target_arch = "powerpc64"), repr(align(128)))]
+#[cfg_attr(not(any(target_arch = "x86_64", target_arch = "aarch64", target_arch = "powerpc64")), repr(align(64)))]
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct CachePadded<T> {
+    value: T,
+}
+
+impl<T> CachePadded<T> {
+    pub const fn new(value: T) -> Self {
+        CachePadded { value }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> Deref for CachePadded<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T> DerefMut for CachePadded<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+}
+
+impl<T> From<T> for CachePadded<T> {
+    fn from(value: T) -> Self {
+        CachePadded::new(value)
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for CachePadded<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CachePadded").field("value", &self.value).finish()
+    }
+}
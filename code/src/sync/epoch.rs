@@ -0,0 +1,298 @@
+//! Minimal Epoch-Based Reclamation (EBR)
+//!
+//! Lock-free structures can't free a node the moment they unlink it: another
+//! thread may have loaded a pointer to it a moment earlier and be about to
+//! dereference it. EBR defers the free until every thread that could still
+//! hold such a pointer has moved on.
+//!
+//! The scheme:
+//! - A global epoch counter.
+//! - A thread *pins* itself before touching shared nodes, recording the
+//!   global epoch it saw. It unpins when the `Guard` is dropped.
+//! - Unlinked nodes are *retired* with the current global epoch.
+//! - The global epoch may only advance when every pinned thread has caught
+//!   up to it, so a node retired in epoch `e` is unreachable by anyone once
+//!   the global epoch reaches `e + 2`.
+//!
+//! This version uses a fixed table of participant slots instead of
+//! thread-local registration, which keeps it small enough to read.
+
+use super::primitive::atomic::{AtomicBool, AtomicUsize, Ordering};
+use super::CachePadded;
+use super::primitive::{UnsafeCell, yield_now};
+
+/// Maximum number of simultaneously pinned threads
+pub const MAX_PARTICIPANTS: usize = 64;
+/// How many retirements a slot accumulates between collection attempts
+const COLLECT_EVERY: usize = 32;
+
+/// An unlinked object waiting to be freed
+struct Retired {
+    epoch: usize,
+    ptr: *mut (),
+    drop_fn: unsafe fn(*mut ()),
+}
+
+/// Retired objects owned by one participant slot
+#[derive(Default)]
+struct Bag {
+    items: Vec<Retired>,
+    retired_since_collect: usize,
+}
+
+/// One participant record. Only the thread that claimed `in_use` may touch
+/// `bag`; the flag's Acquire/Release pairing hands the bag over.
+struct Slot {
+    in_use: AtomicBool,
+    epoch: AtomicUsize,
+    bag: UnsafeCell<Bag>,
+}
+
+/// Owner of the global epoch and of all not-yet-freed garbage
+pub struct Collector {
+    global_epoch: AtomicUsize,
+    /// Padded: every pin writes its own slot, and neighbours shouldn't feel it
+    slots: Box<[CachePadded<Slot>]>,
+    retired: AtomicUsize,
+    freed: AtomicUsize,
+}
+
+// Safety: garbage bags are only accessed by the thread holding the slot (or
+// by `Drop`, which has exclusive access), and retired pointers are only
+// handed to their matching drop function once no thread can reach them.
+unsafe impl Send for Collector {}
+unsafe impl Sync for Collector {}
+
+impl Default for Collector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Collector {
+    pub fn new() -> Self {
+        let slots = (0..MAX_PARTICIPANTS)
+            .map(|_| {
+                CachePadded::new(Slot {
+                    in_use: AtomicBool::new(false),
+                    epoch: AtomicUsize::new(0),
+                    bag: UnsafeCell::new(Bag::default()),
+                })
+            })
+            .collect();
+        Collector {
+            global_epoch: AtomicUsize::new(0),
+            slots,
+            retired: AtomicUsize::new(0),
+            freed: AtomicUsize::new(0),
+        }
+    }
+
+    /// Pin the current thread. Shared nodes loaded while the guard is alive
+    /// stay valid until it is dropped.
+    pub fn pin(&self) -> Guard<'_> {
+        let start = thread_hint() % MAX_PARTICIPANTS;
+        loop {
+            for i in 0..MAX_PARTICIPANTS {
+                let index = (start + i) % MAX_PARTICIPANTS;
+                let slot = &self.slots[index];
+                if slot
+                    .in_use
+                    .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+                    .is_ok()
+                {
+                    // Publish the epoch we observed before reading any shared
+                    // pointer. SeqCst orders this store before our later loads.
+                    slot.epoch.store(self.global_epoch.load(Ordering::SeqCst), Ordering::SeqCst);
+                    return Guard { collector: self, slot };
+                }
+            }
+            // Every slot is taken: wait for a thread to unpin
+            yield_now();
+        }
+    }
+
+    /// Current global epoch
+    pub fn epoch(&self) -> usize {
+        self.global_epoch.load(Ordering::SeqCst)
+    }
+
+    /// Total objects retired so far
+    pub fn retired_count(&self) -> usize {
+        self.retired.load(Ordering::Relaxed)
+    }
+
+    /// Total retired objects actually freed so far
+    pub fn freed_count(&self) -> usize {
+        self.freed.load(Ordering::Relaxed)
+    }
+
+    /// Advance the global epoch if every pinned thread has observed it
+    fn try_advance(&self) -> usize {
+        let global = self.global_epoch.load(Ordering::SeqCst);
+        let all_caught_up = self.slots.iter().all(|slot| {
+            !slot.in_use.load(Ordering::SeqCst) || slot.epoch.load(Ordering::SeqCst) == global
+        });
+        if all_caught_up {
+            // Losing this race is fine: someone else advanced it for us
+            let _ = self.global_epoch.compare_exchange(global, global + 1, Ordering::SeqCst, Ordering::SeqCst);
+        }
+        self.global_epoch.load(Ordering::SeqCst)
+    }
+}
+
+impl Drop for Collector {
+    fn drop(&mut self) {
+        // `&mut self`: no guards exist any more, everything can go
+        for slot in self.slots.iter() {
+            slot.bag.with_mut(|bag| {
+                for retired in unsafe { &mut *bag }.items.drain(..) {
+                    unsafe { (retired.drop_fn)(retired.ptr) };
+                }
+            });
+        }
+    }
+}
+
+/// Proof that the current thread is pinned
+pub struct Guard<'a> {
+    collector: &'a Collector,
+    slot: &'a Slot,
+}
+
+impl Guard<'_> {
+    /// Schedule a `Box`-allocated object to be freed once no pinned thread
+    /// can still observe it.
+    ///
+    /// # Safety
+    /// `ptr` must come from `Box::into_raw`, must already be unreachable for
+    /// threads that pin from now on, and must not be retired twice.
+    pub unsafe fn defer_drop<T>(&mut self, ptr: *mut T) {
+        unsafe fn drop_box<T>(ptr: *mut ()) {
+            drop(unsafe { Box::from_raw(ptr as *mut T) });
+        }
+
+        let epoch = self.collector.global_epoch.load(Ordering::SeqCst);
+        // Safety: we hold the slot, so we own its bag
+        let collect_now = self.slot.bag.with_mut(|bag| {
+            let bag = unsafe { &mut *bag };
+            bag.items.push(Retired { epoch, ptr: ptr as *mut (), drop_fn: drop_box::<T> });
+            bag.retired_since_collect += 1;
+            let due = bag.retired_since_collect >= COLLECT_EVERY;
+            if due {
+                bag.retired_since_collect = 0;
+            }
+            due
+        });
+        self.collector.retired.fetch_add(1, Ordering::Relaxed);
+
+        if collect_now {
+            self.collect();
+        }
+    }
+
+    /// Try to advance the epoch and free everything retired two epochs ago
+    pub fn collect(&mut self) {
+        let global = self.collector.try_advance();
+        let freed = self.slot.bag.with_mut(|bag| {
+            let garbage = unsafe { &mut (*bag).items };
+            let before = garbage.len();
+            garbage.retain(|retired| {
+                if retired.epoch + 2 <= global {
+                    unsafe { (retired.drop_fn)(retired.ptr) };
+                    false
+                } else {
+                    true
+                }
+            });
+            before - garbage.len()
+        });
+        self.collector.freed.fetch_add(freed, Ordering::Relaxed);
+    }
+}
+
+impl Drop for Guard<'_> {
+    fn drop(&mut self) {
+        self.slot.in_use.store(false, Ordering::Release);
+    }
+}
+
+/// A per-thread starting index so threads don't all fight over slot 0.
+/// Only a hint, so it always uses std's atomic (even under loom).
+fn thread_hint() -> usize {
+    use std::sync::atomic::AtomicUsize;
+
+    static NEXT: AtomicUsize = AtomicUsize::new(0);
+    thread_local! {
+        static HINT: usize = NEXT.fetch_add(1, Ordering::Relaxed);
+    }
+    HINT.with(|hint| *hint)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::thread;
+
+    /// A heap object that counts its drops
+    fn counted(drops: &Arc<AtomicUsize>) -> *mut Counted {
+        Box::into_raw(Box::new(Counted(Arc::clone(drops))))
+    }
+
+    struct Counted(Arc<AtomicUsize>);
+
+    impl Drop for Counted {
+        fn drop(&mut self) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    #[test]
+    fn a_pinned_guard_holds_back_the_free() {
+        let drops = Arc::new(AtomicUsize::new(0));
+        let collector = Collector::new();
+        // Pinning takes the first free slot from this thread's starting one,
+        // so the reader gets it and the writer the next
+        let reader = collector.pin();
+        {
+            let mut writer = collector.pin();
+            unsafe { writer.defer_drop(counted(&drops)) };
+            for _ in 0..4 {
+                writer.collect();
+            }
+        }
+        assert_eq!(collector.epoch(), 1, "the reader is still in epoch 0");
+        assert_eq!(drops.load(Ordering::Relaxed), 0, "freed under a pinned guard");
+
+        drop(reader);
+        let _late = collector.pin();
+        let mut writer = collector.pin();
+        writer.collect();
+        assert_eq!(collector.epoch(), 2);
+        assert_eq!(drops.load(Ordering::Relaxed), 1);
+        assert_eq!((collector.retired_count(), collector.freed_count()), (1, 1));
+    }
+
+    #[test]
+    fn dropping_the_collector_frees_the_rest_once() {
+        let drops = Arc::new(AtomicUsize::new(0));
+        let collector = Collector::new();
+        let threads = 4;
+        let per_thread = if cfg!(miri) { 40 } else { 1000 };
+        thread::scope(|scope| {
+            for _ in 0..threads {
+                scope.spawn(|| {
+                    for _ in 0..per_thread {
+                        let mut guard = collector.pin();
+                        unsafe { guard.defer_drop(counted(&drops)) };
+                    }
+                });
+            }
+        });
+        assert_eq!(collector.retired_count(), threads * per_thread);
+        assert_eq!(drops.load(Ordering::Relaxed), collector.freed_count());
+        drop(collector);
+        assert_eq!(drops.load(Ordering::Relaxed), threads * per_thread);
+    }
+}
@@ -0,0 +1,237 @@
+//! Locks Built Directly on `futex(2)`
+//!
+//! A futex ("fast userspace mutex") is just a 32-bit integer plus two
+//! syscalls:
+//! - `FUTEX_WAIT(addr, expected)`: sleep, but only if `*addr == expected`
+//!   still holds when the kernel checks (so no wakeup can be lost).
+//! - `FUTEX_WAKE(addr, n)`: wake up to `n` threads sleeping on `addr`.
+//!
+//! All the locking logic lives in userspace atomics; the kernel is only
+//! entered when a thread actually has to sleep or someone is asleep. That's
+//! how `std::sync::Mutex` works on Linux (`library/std/src/sys/sync/mutex/futex.rs`),
+//! with the same three states as `FutexMutex` below.
+//!
+//! Every path is counted so the demo can show which ones ran.
+
+use std::cell::UnsafeCell;
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+
+/// Spins before giving up and sleeping, like std's mutex
+pub const SPIN_LIMIT: u32 = 100;
+
+/// Sleep until woken, if `*futex == expected`. Spurious wakeups are
+/// possible; callers always re-check their condition.
+fn futex_wait(futex: &AtomicU32, expected: u32) {
+    unsafe {
+        libc::syscall(
+            libc::SYS_futex,
+            futex.as_ptr(),
+            libc::FUTEX_WAIT | libc::FUTEX_PRIVATE_FLAG,
+            expected,
+            std::ptr::null::<libc::timespec>(),
+        );
+    }
+}
+
+/// Wake up to `count` threads waiting on `futex`
+fn futex_wake(futex: &AtomicU32, count: i32) {
+    unsafe {
+        libc::syscall(libc::SYS_futex, futex.as_ptr(), libc::FUTEX_WAKE | libc::FUTEX_PRIVATE_FLAG, count);
+    }
+}
+
+/// Which paths a futex-based primitive took
+#[derive(Debug, Default, Clone, Copy)]
+pub struct FutexStats {
+    /// Operations finished with a single atomic op, no syscall
+    pub fast_path: u64,
+    /// Operations that found the futex busy (spun, maybe slept)
+    pub slow_path: u64,
+    /// `FUTEX_WAIT` syscalls (a thread went to sleep)
+    pub futex_waits: u64,
+    /// `FUTEX_WAKE` syscalls
+    pub futex_wakes: u64,
+}
+
+#[derive(Default)]
+struct Counters {
+    fast_path: AtomicU64,
+    slow_path: AtomicU64,
+    futex_waits: AtomicU64,
+    futex_wakes: AtomicU64,
+}
+
+impl Counters {
+    fn bump(counter: &AtomicU64) {
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn snapshot(&self) -> FutexStats {
+        FutexStats {
+            fast_path: self.fast_path.load(Ordering::Relaxed),
+            slow_path: self.slow_path.load(Ordering::Relaxed),
+            futex_waits: self.futex_waits.load(Ordering::Relaxed),
+            futex_wakes: self.futex_wakes.load(Ordering::Relaxed),
+        }
+    }
+}
+
+const UNLOCKED: u32 = 0;
+/// Locked, nobody sleeping: unlock needs no syscall
+const LOCKED: u32 = 1;
+/// Locked, and someone may be sleeping: unlock must FUTEX_WAKE
+const CONTENDED: u32 = 2;
+
+/// A mutex on one futex word (Drepper, "Futexes Are Tricky", mutex #2)
+pub struct FutexMutex<T> {
+    state: AtomicU32,
+    counters: Counters,
+    data: UnsafeCell<T>,
+}
+
+// Safety: the futex state grants exclusive access to `data`
+unsafe impl<T: Send> Send for FutexMutex<T> {}
+unsafe impl<T: Send> Sync for FutexMutex<T> {}
+
+impl<T> FutexMutex<T> {
+    pub fn new(value: T) -> Self {
+        FutexMutex { state: AtomicU32::new(UNLOCKED), counters: Counters::default(), data: UnsafeCell::new(value) }
+    }
+
+    pub fn lock(&self) -> FutexGuard<'_, T> {
+        // Fast path: uncontended, one CAS and we're done
+        if self.state.compare_exchange(UNLOCKED, LOCKED, Ordering::Acquire, Ordering::Relaxed).is_ok() {
+            Counters::bump(&self.counters.fast_path);
+        } else {
+            Counters::bump(&self.counters.slow_path);
+            self.lock_contended();
+        }
+        FutexGuard { mutex: self }
+    }
+
+    #[cold]
+    fn lock_contended(&self) {
+        // Short critical sections often end while we spin: no syscall needed
+        for _ in 0..SPIN_LIMIT {
+            if self.state.load(Ordering::Relaxed) == UNLOCKED
+                && self.state.compare_exchange(UNLOCKED, LOCKED, Ordering::Acquire, Ordering::Relaxed).is_ok()
+            {
+                return;
+            }
+            std::hint::spin_loop();
+        }
+        // Mark the lock contended so the holder knows to wake us. If the
+        // swap returns UNLOCKED we got the lock (conservatively marked
+        // CONTENDED, which costs one spare wake at most).
+        while self.state.swap(CONTENDED, Ordering::Acquire) != UNLOCKED {
+            Counters::bump(&self.counters.futex_waits);
+            futex_wait(&self.state, CONTENDED);
+        }
+    }
+
+    fn unlock(&self) {
+        if self.state.swap(UNLOCKED, Ordering::Release) == CONTENDED {
+            Counters::bump(&self.counters.futex_wakes);
+            futex_wake(&self.state, 1);
+        }
+    }
+
+    pub fn stats(&self) -> FutexStats {
+        self.counters.snapshot()
+    }
+
+    pub fn into_inner(self) -> T {
+        self.data.into_inner()
+    }
+}
+
+pub struct FutexGuard<'a, T> {
+    mutex: &'a FutexMutex<T>,
+}
+
+impl<T> Deref for FutexGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // Safety: the guard proves we hold the lock
+        unsafe { &*self.mutex.data.get() }
+    }
+}
+
+impl<T> DerefMut for FutexGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.mutex.data.get() }
+    }
+}
+
+impl<T> Drop for FutexGuard<'_, T> {
+    fn drop(&mut self) {
+        self.mutex.unlock();
+    }
+}
+
+const UNSET: u32 = 0;
+/// Not set, and at least one thread is (about to be) asleep
+const UNSET_WAITING: u32 = 1;
+const SET: u32 = 2;
+
+/// A manual-reset event: `wait` blocks until some thread calls `set`
+pub struct FutexEvent {
+    state: AtomicU32,
+    counters: Counters,
+}
+
+impl Default for FutexEvent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FutexEvent {
+    pub fn new() -> Self {
+        FutexEvent { state: AtomicU32::new(UNSET), counters: Counters::default() }
+    }
+
+    pub fn wait(&self) {
+        // Fast path: already set, nothing to wait for
+        if self.state.load(Ordering::Acquire) == SET {
+            Counters::bump(&self.counters.fast_path);
+            return;
+        }
+        Counters::bump(&self.counters.slow_path);
+        loop {
+            match self.state.compare_exchange(UNSET, UNSET_WAITING, Ordering::Relaxed, Ordering::Acquire) {
+                Err(SET) => return,
+                // UNSET -> UNSET_WAITING, or another waiter already did it
+                _ => {
+                    Counters::bump(&self.counters.futex_waits);
+                    futex_wait(&self.state, UNSET_WAITING);
+                }
+            }
+        }
+    }
+
+    /// Release every current and future waiter until `reset`
+    pub fn set(&self) {
+        if self.state.swap(SET, Ordering::Release) == UNSET_WAITING {
+            // Somebody may be asleep: one syscall wakes them all
+            Counters::bump(&self.counters.futex_wakes);
+            futex_wake(&self.state, i32::MAX);
+        } else {
+            Counters::bump(&self.counters.fast_path);
+        }
+    }
+
+    pub fn reset(&self) {
+        let _ = self.state.compare_exchange(SET, UNSET, Ordering::Relaxed, Ordering::Relaxed);
+    }
+
+    pub fn is_set(&self) -> bool {
+        self.state.load(Ordering::Acquire) == SET
+    }
+
+    pub fn stats(&self) -> FutexStats {
+        self.counters.snapshot()
+    }
+}
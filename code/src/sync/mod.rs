@@ -0,0 +1,23 @@
+//! Synchronization Primitives & Concurrent Data Structures
+//!
+//! Hand-rolled versions of the building blocks that `std` and crates like
+//! crossbeam provide, written to be read rather than to be fastest.
+
+pub mod bounded_buffer;
+pub mod cache_padded;
+pub mod epoch;
+#[cfg(target_os = "linux")]
+pub mod futex;
+pub mod mpmc_queue;
+mod primitive;
+pub mod rcu;
+pub mod spinlock;
+pub mod spsc_ring;
+pub mod treiber_stack;
+
+pub use bounded_buffer::BoundedBuffer;
+pub use cache_padded::CachePadded;
+pub use mpmc_queue::MpmcQueue;
+pub use rcu::RcuCell;
+pub use spinlock::SpinLock;
+pub use treiber_stack::TreiberStack;
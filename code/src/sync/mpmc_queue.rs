@@ -0,0 +1,221 @@
+//! Bounded MPMC Queue (Dmitry Vyukov's design)
+//!
+//! A fixed ring of slots, each carrying a sequence number that says whose
+//! turn it is:
+//! - `sequence == pos`     → the slot is empty and ready for the producer
+//!   that claimed ticket `pos`
+//! - `sequence == pos + 1` → the slot holds a value for the consumer with
+//!   ticket `pos`
+//!
+//! Producers and consumers each grab tickets by CAS on their own counter,
+//! then hand the slot over with a single Release store of the sequence.
+//! The two counters sit on separate cache lines so producers don't slow
+//! down consumers through false sharing.
+
+use std::mem::MaybeUninit;
+
+use super::CachePadded;
+use super::primitive::UnsafeCell;
+use super::primitive::atomic::{AtomicUsize, Ordering};
+
+struct Slot<T> {
+    sequence: AtomicUsize,
+    value: UnsafeCell<MaybeUninit<T>>,
+}
+
+/// A lock-free bounded multi-producer multi-consumer queue
+pub struct MpmcQueue<T> {
+    buffer: Box<[Slot<T>]>,
+    mask: usize,
+    enqueue_pos: CachePadded<AtomicUsize>,
+    dequeue_pos: CachePadded<AtomicUsize>,
+}
+
+// Safety: a slot's value is only touched by the single thread that won the
+// ticket for it, and ownership is handed over via the sequence number
+unsafe impl<T: Send> Send for MpmcQueue<T> {}
+unsafe impl<T: Send> Sync for MpmcQueue<T> {}
+
+impl<T> MpmcQueue<T> {
+    /// Create a queue holding at least `capacity` items (rounded up to a
+    /// power of two so that `pos & mask` replaces a division)
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(2).next_power_of_two();
+        let buffer = (0..capacity)
+            .map(|i| Slot {
+                sequence: AtomicUsize::new(i),
+                value: UnsafeCell::new(MaybeUninit::uninit()),
+            })
+            .collect();
+        MpmcQueue {
+            buffer,
+            mask: capacity - 1,
+            enqueue_pos: CachePadded::new(AtomicUsize::new(0)),
+            dequeue_pos: CachePadded::new(AtomicUsize::new(0)),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.mask + 1
+    }
+
+    /// Try to enqueue; hands the value back if the queue is full
+    pub fn push(&self, value: T) -> Result<(), T> {
+        let mut pos = self.enqueue_pos.load(Ordering::Relaxed);
+        loop {
+            let slot = &self.buffer[pos & self.mask];
+            let sequence = slot.sequence.load(Ordering::Acquire);
+            let diff = sequence as isize - pos as isize;
+
+            if diff == 0 {
+                // Slot is free for ticket `pos` - try to claim the ticket
+                match self.enqueue_pos.compare_exchange_weak(pos, pos + 1, Ordering::Relaxed, Ordering::Relaxed) {
+                    Ok(_) => {
+                        slot.value.with_mut(|cell| unsafe { (*cell).write(value) });
+                        slot.sequence.store(pos + 1, Ordering::Release);
+                        return Ok(());
+                    }
+                    Err(current) => pos = current,
+                }
+            } else if diff < 0 {
+                // The consumer one lap behind hasn't emptied this slot yet
+                return Err(value);
+            } else {
+                // Another producer took this ticket; catch up
+                pos = self.enqueue_pos.load(Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Try to dequeue; `None` if the queue is empty
+    pub fn pop(&self) -> Option<T> {
+        let mut pos = self.dequeue_pos.load(Ordering::Relaxed);
+        loop {
+            let slot = &self.buffer[pos & self.mask];
+            let sequence = slot.sequence.load(Ordering::Acquire);
+            let diff = sequence as isize - (pos + 1) as isize;
+
+            if diff == 0 {
+                match self.dequeue_pos.compare_exchange_weak(pos, pos + 1, Ordering::Relaxed, Ordering::Relaxed) {
+                    Ok(_) => {
+                        let value = slot.value.with(|cell| unsafe { (*cell).assume_init_read() });
+                        // Mark the slot free for the producer one lap ahead
+                        slot.sequence.store(pos + self.mask + 1, Ordering::Release);
+                        return Some(value);
+                    }
+                    Err(current) => pos = current,
+                }
+            } else if diff < 0 {
+                return None;
+            } else {
+                pos = self.dequeue_pos.load(Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Number of queued items; only a snapshot while other threads are active
+    pub fn len(&self) -> usize {
+        let tail = self.enqueue_pos.load(Ordering::Acquire);
+        let head = self.dequeue_pos.load(Ordering::Acquire);
+        tail.saturating_sub(head)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T> Drop for MpmcQueue<T> {
+    fn drop(&mut self) {
+        while self.pop().is_some() {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::sync::atomic::AtomicUsize as Counter;
+    use std::thread;
+
+    #[test]
+    fn fills_to_capacity_and_drains_in_order() {
+        let queue = MpmcQueue::new(3);
+        assert_eq!(queue.capacity(), 4);
+        assert!(queue.is_empty());
+        assert_eq!(queue.pop(), None);
+        for i in 0..4 {
+            assert_eq!(queue.push(i), Ok(()));
+        }
+        assert_eq!(queue.len(), 4);
+        assert_eq!(queue.push(4), Err(4));
+        for i in 0..4 {
+            assert_eq!(queue.pop(), Some(i));
+        }
+        assert_eq!(queue.pop(), None);
+        // The slots go round again on the next lap
+        assert_eq!(queue.push(5), Ok(()));
+        assert_eq!(queue.pop(), Some(5));
+    }
+
+    #[test]
+    fn every_value_pushed_is_popped_once() {
+        let (producers, consumers) = (2, 2);
+        let per_producer = if cfg!(miri) { 50 } else { 10_000 };
+        let queue = Arc::new(MpmcQueue::new(8));
+        let popped = Arc::new(Counter::new(0));
+        let sum = Arc::new(Counter::new(0));
+        let total = producers * per_producer;
+        let mut handles = Vec::new();
+        for p in 0..producers {
+            let queue = Arc::clone(&queue);
+            handles.push(thread::spawn(move || {
+                for i in 0..per_producer {
+                    let mut value = p * per_producer + i;
+                    while let Err(back) = queue.push(value) {
+                        value = back;
+                        thread::yield_now();
+                    }
+                }
+            }));
+        }
+        for _ in 0..consumers {
+            let (queue, popped, sum) = (Arc::clone(&queue), Arc::clone(&popped), Arc::clone(&sum));
+            handles.push(thread::spawn(move || {
+                while popped.load(Ordering::Relaxed) < total {
+                    match queue.pop() {
+                        Some(value) => {
+                            sum.fetch_add(value, Ordering::Relaxed);
+                            popped.fetch_add(1, Ordering::Relaxed);
+                        }
+                        None => thread::yield_now(),
+                    }
+                }
+            }));
+        }
+        handles.into_iter().for_each(|handle| handle.join().unwrap());
+        assert_eq!(popped.load(Ordering::Relaxed), total);
+        assert_eq!(sum.load(Ordering::Relaxed), total * (total - 1) / 2);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn dropping_the_queue_drops_what_is_left() {
+        struct Counted(Arc<Counter>);
+        impl Drop for Counted {
+            fn drop(&mut self) {
+                self.0.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        let drops = Arc::new(Counter::new(0));
+        let queue = MpmcQueue::new(8);
+        for _ in 0..5 {
+            assert!(queue.push(Counted(Arc::clone(&drops))).is_ok());
+        }
+        drop(queue.pop());
+        assert_eq!(drops.load(Ordering::Relaxed), 1);
+        drop(queue);
+        assert_eq!(drops.load(Ordering::Relaxed), 5);
+    }
+}
@@ -0,0 +1,201 @@
+//! Spinlocks
+//!
+//! Three classic designs, from naive to fair:
+//! - `TasLock`: test-and-set. Every attempt is an atomic read-modify-write,
+//!   which needs the cache line in exclusive state - waiters keep stealing
+//!   the line from each other (and from the owner trying to unlock).
+//! - `TtasLock`: test-and-test-and-set. Waiters spin on a plain load, which
+//!   is served from their own shared copy of the line, and only swap once
+//!   the lock looks free. Failed swaps back off exponentially.
+//! - `TicketLock`: a take-a-number lock. FIFO fair, but every waiter still
+//!   watches the same `now_serving` counter.
+//!
+//! All three plug into `SpinLock<T, L>`, which adds the data and an RAII guard.
+
+use std::ops::{Deref, DerefMut};
+
+use super::primitive::atomic::{AtomicBool, AtomicUsize, Ordering};
+use super::primitive::{UnsafeCell, spin_loop, yield_now};
+
+/// Spins after which even the naive locks yield the CPU. Without this a
+/// waiter can burn its whole time slice while the owner sits descheduled on
+/// an oversubscribed (or single-core) machine.
+const YIELD_AFTER: u32 = 1 << 7;
+
+/// Exponential backoff: spin 1, 2, 4, ... times, then start yielding
+pub struct Backoff {
+    step: u32,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Backoff {
+    const SPIN_LIMIT: u32 = 6;
+
+    pub fn new() -> Self {
+        Backoff { step: 0 }
+    }
+
+    pub fn snooze(&mut self) {
+        if self.step <= Self::SPIN_LIMIT {
+            for _ in 0..1 << self.step {
+                spin_loop();
+            }
+            self.step += 1;
+        } else {
+            yield_now();
+        }
+    }
+}
+
+/// The raw acquire/release protocol of a lock, without any protected data
+pub trait RawLock {
+    fn new() -> Self;
+    fn lock(&self);
+    fn unlock(&self);
+}
+
+/// Test-and-set: hammer the lock word with atomic RMWs.
+///
+/// Written as a CAS rather than `swap(true)`: on hardware both take the line
+/// exclusively, but a failed CAS doesn't store, which lets loom tell a
+/// spinning waiter apart from a thread making progress.
+pub struct TasLock {
+    locked: AtomicBool,
+}
+
+impl RawLock for TasLock {
+    fn new() -> Self {
+        TasLock { locked: AtomicBool::new(false) }
+    }
+
+    fn lock(&self) {
+        let mut spins = 0u32;
+        while self.locked.compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed).is_err() {
+            spin_loop();
+            spins += 1;
+            if spins.is_multiple_of(YIELD_AFTER) {
+                yield_now();
+            }
+        }
+    }
+
+    fn unlock(&self) {
+        self.locked.store(false, Ordering::Release);
+    }
+}
+
+/// Test-and-test-and-set with exponential backoff
+pub struct TtasLock {
+    locked: AtomicBool,
+}
+
+impl RawLock for TtasLock {
+    fn new() -> Self {
+        TtasLock { locked: AtomicBool::new(false) }
+    }
+
+    fn lock(&self) {
+        let mut backoff = Backoff::new();
+        loop {
+            // Read-only spin: stays in our own cache until the owner writes
+            while self.locked.load(Ordering::Relaxed) {
+                backoff.snooze();
+            }
+            if self.locked.compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed).is_ok() {
+                return;
+            }
+            // Someone beat us to it; back off before competing again
+            backoff.snooze();
+        }
+    }
+
+    fn unlock(&self) {
+        self.locked.store(false, Ordering::Release);
+    }
+}
+
+/// FIFO ticket lock: take a number, wait until it is served
+pub struct TicketLock {
+    next_ticket: AtomicUsize,
+    now_serving: AtomicUsize,
+}
+
+impl RawLock for TicketLock {
+    fn new() -> Self {
+        TicketLock { next_ticket: AtomicUsize::new(0), now_serving: AtomicUsize::new(0) }
+    }
+
+    fn lock(&self) {
+        let ticket = self.next_ticket.fetch_add(1, Ordering::Relaxed);
+        let mut spins = 0u32;
+        while self.now_serving.load(Ordering::Acquire) != ticket {
+            spin_loop();
+            spins += 1;
+            if spins.is_multiple_of(YIELD_AFTER) {
+                yield_now();
+            }
+        }
+    }
+
+    fn unlock(&self) {
+        // Only the owner writes `now_serving`, so a plain load + store is enough
+        let next = self.now_serving.load(Ordering::Relaxed) + 1;
+        self.now_serving.store(next, Ordering::Release);
+    }
+}
+
+/// A value protected by a spinning lock of type `L`
+pub struct SpinLock<T, L: RawLock = TtasLock> {
+    raw: L,
+    data: UnsafeCell<T>,
+}
+
+// Safety: the raw lock gives one thread at a time access to `data`
+unsafe impl<T: Send, L: RawLock + Send> Send for SpinLock<T, L> {}
+unsafe impl<T: Send, L: RawLock + Sync> Sync for SpinLock<T, L> {}
+
+impl<T, L: RawLock> SpinLock<T, L> {
+    pub fn new(value: T) -> Self {
+        SpinLock { raw: L::new(), data: UnsafeCell::new(value) }
+    }
+
+    pub fn lock(&self) -> SpinGuard<'_, T, L> {
+        self.raw.lock();
+        SpinGuard { lock: self }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.data.into_inner()
+    }
+}
+
+/// Releases the lock when dropped
+pub struct SpinGuard<'a, T, L: RawLock> {
+    lock: &'a SpinLock<T, L>,
+}
+
+impl<T, L: RawLock> Deref for SpinGuard<'_, T, L> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // Safety: holding the guard means holding the lock
+        self.lock.data.with(|data| unsafe { &*data })
+    }
+}
+
+impl<T, L: RawLock> DerefMut for SpinGuard<'_, T, L> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.lock.data.with_mut(|data| unsafe { &mut *data })
+    }
+}
+
+impl<T, L: RawLock> Drop for SpinGuard<'_, T, L> {
+    fn drop(&mut self) {
+        self.lock.raw.unlock();
+    }
+}
@@ -0,0 +1,101 @@
+//! Single-Producer Single-Consumer Ring Buffer
+//!
+//! Lamport's classic queue: with exactly one writer and one reader, no CAS
+//! is needed at all. The producer owns `tail`, the consumer owns `head`, and
+//! each only *reads* the other's index:
+//! - push: write the slot, then publish it with a Release store of `tail`
+//! - pop:  Acquire-load `tail`, read the slot, then free it by storing `head`
+//!
+//! The single-owner rule is enforced by the type system: `channel` returns
+//! one `Producer` and one `Consumer`, and neither is `Clone`.
+
+use std::mem::MaybeUninit;
+use std::sync::Arc;
+
+use super::primitive::UnsafeCell;
+use super::primitive::atomic::{AtomicUsize, Ordering};
+
+struct Ring<T> {
+    slots: Box<[UnsafeCell<MaybeUninit<T>>]>,
+    capacity: usize,
+    /// Next slot to read; written only by the consumer
+    head: AtomicUsize,
+    /// Next slot to write; written only by the producer
+    tail: AtomicUsize,
+}
+
+// Safety: each slot is accessed by one side at a time, handed over through
+// the Release/Acquire pairs on `head` and `tail`
+unsafe impl<T: Send> Sync for Ring<T> {}
+
+impl<T> Drop for Ring<T> {
+    fn drop(&mut self) {
+        let head = self.head.load(Ordering::Relaxed);
+        let tail = self.tail.load(Ordering::Relaxed);
+        for pos in head..tail {
+            self.slots[pos % self.capacity].with_mut(|slot| unsafe { (*slot).assume_init_drop() });
+        }
+    }
+}
+
+/// The writing end of an SPSC ring
+pub struct Producer<T> {
+    ring: Arc<Ring<T>>,
+}
+
+/// The reading end of an SPSC ring
+pub struct Consumer<T> {
+    ring: Arc<Ring<T>>,
+}
+
+/// Create a ring holding up to `capacity` items, split into its two ends
+pub fn channel<T>(capacity: usize) -> (Producer<T>, Consumer<T>) {
+    let capacity = capacity.max(1);
+    let slots = (0..capacity).map(|_| UnsafeCell::new(MaybeUninit::uninit())).collect();
+    let ring = Arc::new(Ring {
+        slots,
+        capacity,
+        head: AtomicUsize::new(0),
+        tail: AtomicUsize::new(0),
+    });
+    (Producer { ring: Arc::clone(&ring) }, Consumer { ring })
+}
+
+impl<T> Producer<T> {
+    /// Try to enqueue; hands the value back if the ring is full
+    pub fn push(&mut self, value: T) -> Result<(), T> {
+        let ring = &*self.ring;
+        // Our own index: no other thread writes it
+        let tail = ring.tail.load(Ordering::Relaxed);
+        // Acquire: the consumer must be done reading a slot before we reuse it
+        let head = ring.head.load(Ordering::Acquire);
+        if tail - head == ring.capacity {
+            return Err(value);
+        }
+        ring.slots[tail % ring.capacity].with_mut(|slot| unsafe { (*slot).write(value) });
+        // Release: publish the slot contents together with the new tail
+        ring.tail.store(tail + 1, Ordering::Release);
+        Ok(())
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.ring.capacity
+    }
+}
+
+impl<T> Consumer<T> {
+    /// Try to dequeue; `None` if the ring is empty
+    pub fn pop(&mut self) -> Option<T> {
+        let ring = &*self.ring;
+        let head = ring.head.load(Ordering::Relaxed);
+        // Acquire: pairs with the producer's Release store of `tail`
+        let tail = ring.tail.load(Ordering::Acquire);
+        if head == tail {
+            return None;
+        }
+        let value = ring.slots[head % ring.capacity].with(|slot| unsafe { (*slot).assume_init_read() });
+        // Release: the slot may be overwritten once the producer sees this
+        ring.head.store(head + 1, Ordering::Release);
+        Some(value)
+    }
+}
@@ -0,0 +1,186 @@
+//! Treiber Stack
+//!
+//! The classic lock-free stack (R. K. Treiber, 1986): a singly linked list
+//! whose head is swapped in with compare-and-swap. Push and pop never block;
+//! a thread that loses a CAS race simply retries with the new head.
+//!
+//! Popped nodes are handed to the epoch collector instead of being freed
+//! immediately. Freeing them on the spot is what makes naive lock-free
+//! stacks crash: another thread may still be reading `node.next`, and a
+//! recycled address lets a stale CAS succeed (the ABA problem).
+
+use std::mem::ManuallyDrop;
+use std::ptr;
+use super::epoch::Collector;
+use super::primitive::atomic::{AtomicPtr, Ordering};
+
+struct Node<T> {
+    // Moved out by `pop`, so the node itself must not drop it again
+    value: ManuallyDrop<T>,
+    next: *mut Node<T>,
+}
+
+/// A lock-free LIFO stack with epoch-based node reclamation
+pub struct TreiberStack<T> {
+    head: AtomicPtr<Node<T>>,
+    collector: Collector,
+}
+
+// Safety: values move between threads through push/pop, so T: Send suffices
+unsafe impl<T: Send> Send for TreiberStack<T> {}
+unsafe impl<T: Send> Sync for TreiberStack<T> {}
+
+impl<T> Default for TreiberStack<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> TreiberStack<T> {
+    pub fn new() -> Self {
+        TreiberStack {
+            head: AtomicPtr::new(ptr::null_mut()),
+            collector: Collector::new(),
+        }
+    }
+
+    pub fn push(&self, value: T) {
+        let node = Box::into_raw(Box::new(Node {
+            value: ManuallyDrop::new(value),
+            next: ptr::null_mut(),
+        }));
+        let mut head = self.head.load(Ordering::Relaxed);
+        loop {
+            // Safety: the node is still private to this thread
+            unsafe { (*node).next = head };
+            match self.head.compare_exchange_weak(head, node, Ordering::Release, Ordering::Relaxed) {
+                Ok(_) => return,
+                Err(current) => head = current,
+            }
+        }
+    }
+
+    pub fn pop(&self) -> Option<T> {
+        let mut guard = self.collector.pin();
+        let mut head = self.head.load(Ordering::Acquire);
+        loop {
+            if head.is_null() {
+                return None;
+            }
+            // Safety: we are pinned, so even if another thread pops `head`
+            // right now it won't be freed until our guard is dropped
+            let next = unsafe { (*head).next };
+            match self.head.compare_exchange_weak(head, next, Ordering::Acquire, Ordering::Acquire) {
+                Ok(_) => {
+                    // We won the race: the value is ours, the node memory
+                    // belongs to the collector
+                    let value = unsafe { ptr::read(&*(*head).value) };
+                    unsafe { guard.defer_drop(head) };
+                    return Some(value);
+                }
+                Err(current) => head = current,
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.head.load(Ordering::Acquire).is_null()
+    }
+
+    /// Reclamation statistics: (nodes retired, nodes actually freed)
+    pub fn reclamation_stats(&self) -> (usize, usize) {
+        (self.collector.retired_count(), self.collector.freed_count())
+    }
+}
+
+impl<T> Drop for TreiberStack<T> {
+    fn drop(&mut self) {
+        // Exclusive access: walk the list and free everything directly
+        let mut current = self.head.load(Ordering::Relaxed);
+        while !current.is_null() {
+            let mut node = unsafe { Box::from_raw(current) };
+            unsafe { ManuallyDrop::drop(&mut node.value) };
+            current = node.next;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::sync::atomic::AtomicUsize;
+    use std::thread;
+
+    #[test]
+    fn pops_in_reverse_push_order() {
+        let stack = TreiberStack::new();
+        assert!(stack.is_empty());
+        assert_eq!(stack.pop(), None);
+        for i in 0..5 {
+            stack.push(i);
+        }
+        assert!(!stack.is_empty());
+        assert_eq!(std::iter::from_fn(|| stack.pop()).collect::<Vec<_>>(), [4, 3, 2, 1, 0]);
+        assert!(stack.is_empty());
+        assert_eq!(stack.reclamation_stats().0, 5);
+    }
+
+    #[test]
+    fn concurrent_pushes_and_pops_lose_nothing() {
+        let threads = 4;
+        let per_thread = if cfg!(miri) { 40 } else { 5000 };
+        let stack = TreiberStack::new();
+        let (popped, sum) = (AtomicUsize::new(0), AtomicUsize::new(0));
+        thread::scope(|scope| {
+            for t in 0..threads {
+                let (stack, popped, sum) = (&stack, &popped, &sum);
+                scope.spawn(move || {
+                    for i in 0..per_thread {
+                        stack.push(t * per_thread + i);
+                        // Pop about every other push, so pops race pushes
+                        if i % 2 == 1
+                            && let Some(value) = stack.pop()
+                        {
+                            sum.fetch_add(value, Ordering::Relaxed);
+                            popped.fetch_add(1, Ordering::Relaxed);
+                        }
+                    }
+                });
+            }
+        });
+        while let Some(value) = stack.pop() {
+            sum.fetch_add(value, Ordering::Relaxed);
+            popped.fetch_add(1, Ordering::Relaxed);
+        }
+        let total = threads * per_thread;
+        assert_eq!(popped.load(Ordering::Relaxed), total);
+        assert_eq!(sum.load(Ordering::Relaxed), total * (total - 1) / 2);
+        let (retired, freed) = stack.reclamation_stats();
+        assert_eq!(retired, total);
+        assert!(freed <= retired);
+    }
+
+    #[test]
+    fn dropping_the_stack_drops_what_is_left() {
+        struct Counted(Arc<AtomicUsize>);
+        impl Drop for Counted {
+            fn drop(&mut self) {
+                self.0.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        let drops = Arc::new(AtomicUsize::new(0));
+        let stack = TreiberStack::new();
+        for _ in 0..5 {
+            stack.push(Counted(Arc::clone(&drops)));
+        }
+        drop(stack.pop());
+        drop(stack.pop());
+        assert_eq!(drops.load(Ordering::Relaxed), 2);
+        drop(stack);
+        // Each value once: the popped nodes' memory goes with the collector,
+        // but their values were moved out and aren't dropped again
+        assert_eq!(drops.load(Ordering::Relaxed), 5);
+    }
+}
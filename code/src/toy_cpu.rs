@@ -0,0 +1,566 @@
+//! Toy CPU Emulator
+//!
+//! A tiny 32-bit register machine with a fixed 4-byte instruction encoding,
+//! byte-addressable memory and a classic fetch-decode-execute loop.
+//! Programs live in the same memory as data (von Neumann style), so the
+//! emulator really does *fetch* bytes before it can *decode* them.
+
+use std::fmt;
+
+/// Number of general-purpose registers (R0..R7)
+pub const NUM_REGISTERS: usize = 8;
+/// Size of the emulated memory in bytes
+pub const MEMORY_SIZE: usize = 1024;
+/// Every instruction is exactly 4 bytes: opcode + 3 operand bytes
+pub const INSTRUCTION_SIZE: u16 = 4;
+/// Programs are loaded at address 0, data goes above this address
+pub const DATA_BASE: u16 = 0x200;
+
+/// Condition flags set by ALU and compare instructions
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Flags {
+    /// Result was zero
+    pub zero: bool,
+    /// Result was negative (top bit set)
+    pub negative: bool,
+    /// Unsigned overflow (carry / borrow out of bit 31)
+    pub carry: bool,
+}
+
+impl fmt::Display for Flags {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}{}{}",
+            if self.zero { 'Z' } else { '-' },
+            if self.negative { 'N' } else { '-' },
+            if self.carry { 'C' } else { '-' },
+        )
+    }
+}
+
+/// One decoded instruction. Register operands are indices 0..NUM_REGISTERS.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instruction {
+    /// Stop the machine
+    Halt,
+    /// rd = imm16
+    LoadImm { rd: u8, imm: u16 },
+    /// rd = rs
+    Mov { rd: u8, rs: u8 },
+    /// rd = ra + rb
+    Add { rd: u8, ra: u8, rb: u8 },
+    /// rd = ra - rb
+    Sub { rd: u8, ra: u8, rb: u8 },
+    /// rd = ra * rb
+    Mul { rd: u8, ra: u8, rb: u8 },
+    /// rd = ra & rb
+    And { rd: u8, ra: u8, rb: u8 },
+    /// rd = ra | rb
+    Or { rd: u8, ra: u8, rb: u8 },
+    /// rd = ra ^ rb
+    Xor { rd: u8, ra: u8, rb: u8 },
+    /// rd = rd + sign-extended imm8
+    AddImm { rd: u8, imm: i8 },
+    /// rd = memory[ra + offset] (32-bit little-endian)
+    Load { rd: u8, ra: u8, offset: i8 },
+    /// memory[ra + offset] = rs (32-bit little-endian)
+    Store { rs: u8, ra: u8, offset: i8 },
+    /// Set flags from ra - rb without storing the result
+    Cmp { ra: u8, rb: u8 },
+    /// pc = addr
+    Jmp { addr: u16 },
+    /// pc = addr if Z is set
+    Jz { addr: u16 },
+    /// pc = addr if Z is clear
+    Jnz { addr: u16 },
+    /// pc = addr if N is set (signed less-than after Cmp)
+    Jn { addr: u16 },
+}
+
+impl Instruction {
+    /// Opcode byte for this instruction
+    pub fn opcode(&self) -> u8 {
+        match self {
+            Instruction::Halt => 0x00,
+            Instruction::LoadImm { .. } => 0x01,
+            Instruction::Mov { .. } => 0x02,
+            Instruction::Add { .. } => 0x10,
+            Instruction::Sub { .. } => 0x11,
+            Instruction::Mul { .. } => 0x12,
+            Instruction::And { .. } => 0x13,
+            Instruction::Or { .. } => 0x14,
+            Instruction::Xor { .. } => 0x15,
+            Instruction::AddImm { .. } => 0x16,
+            Instruction::Load { .. } => 0x20,
+            Instruction::Store { .. } => 0x21,
+            Instruction::Cmp { .. } => 0x30,
+            Instruction::Jmp { .. } => 0x40,
+            Instruction::Jz { .. } => 0x41,
+            Instruction::Jnz { .. } => 0x42,
+            Instruction::Jn { .. } => 0x43,
+        }
+    }
+
+    /// Encode into the fixed 4-byte format: `[opcode, a, b, c]`.
+    /// 16-bit immediates and addresses are stored little-endian in `b, c`.
+    pub fn encode(&self) -> [u8; 4] {
+        let op = self.opcode();
+        match *self {
+            Instruction::Halt => [op, 0, 0, 0],
+            Instruction::LoadImm { rd, imm } => {
+                let [lo, hi] = imm.to_le_bytes();
+                [op, rd, lo, hi]
+            }
+            Instruction::Mov { rd, rs } => [op, rd, rs, 0],
+            Instruction::Add { rd, ra, rb }
+            | Instruction::Sub { rd, ra, rb }
+            | Instruction::Mul { rd, ra, rb }
+            | Instruction::And { rd, ra, rb }
+            | Instruction::Or { rd, ra, rb }
+            | Instruction::Xor { rd, ra, rb } => [op, rd, ra, rb],
+            Instruction::AddImm { rd, imm } => [op, rd, imm as u8, 0],
+            Instruction::Load { rd, ra, offset } => [op, rd, ra, offset as u8],
+            Instruction::Store { rs, ra, offset } => [op, rs, ra, offset as u8],
+            Instruction::Cmp { ra, rb } => [op, ra, rb, 0],
+            Instruction::Jmp { addr }
+            | Instruction::Jz { addr }
+            | Instruction::Jnz { addr }
+            | Instruction::Jn { addr } => {
+                let [lo, hi] = addr.to_le_bytes();
+                [op, 0, lo, hi]
+            }
+        }
+    }
+
+    /// Decode 4 bytes back into an instruction
+    pub fn decode(bytes: [u8; 4]) -> Result<Instruction, CpuError> {
+        let [op, a, b, c] = bytes;
+        let imm16 = u16::from_le_bytes([b, c]);
+        let instruction = match op {
+            0x00 => Instruction::Halt,
+            0x01 => Instruction::LoadImm { rd: a, imm: imm16 },
+            0x02 => Instruction::Mov { rd: a, rs: b },
+            0x10 => Instruction::Add { rd: a, ra: b, rb: c },
+            0x11 => Instruction::Sub { rd: a, ra: b, rb: c },
+            0x12 => Instruction::Mul { rd: a, ra: b, rb: c },
+            0x13 => Instruction::And { rd: a, ra: b, rb: c },
+            0x14 => Instruction::Or { rd: a, ra: b, rb: c },
+            0x15 => Instruction::Xor { rd: a, ra: b, rb: c },
+            0x16 => Instruction::AddImm { rd: a, imm: b as i8 },
+            0x20 => Instruction::Load { rd: a, ra: b, offset: c as i8 },
+            0x21 => Instruction::Store { rs: a, ra: b, offset: c as i8 },
+            0x30 => Instruction::Cmp { ra: a, rb: b },
+            0x40 => Instruction::Jmp { addr: imm16 },
+            0x41 => Instruction::Jz { addr: imm16 },
+            0x42 => Instruction::Jnz { addr: imm16 },
+            0x43 => Instruction::Jn { addr: imm16 },
+            _ => return Err(CpuError::InvalidOpcode(op)),
+        };
+        instruction.check_registers()?;
+        Ok(instruction)
+    }
+
+    /// Approximate cost in cycles: ALU ops are cheap, memory is not
+    pub fn cycles(&self) -> u64 {
+        match self {
+            Instruction::Load { .. } | Instruction::Store { .. } => 3,
+            Instruction::Mul { .. } => 3,
+            Instruction::Jmp { .. }
+            | Instruction::Jz { .. }
+            | Instruction::Jnz { .. }
+            | Instruction::Jn { .. } => 2,
+            _ => 1,
+        }
+    }
+
+    fn check_registers(&self) -> Result<(), CpuError> {
+        let registers: &[u8] = match self {
+            Instruction::LoadImm { rd, .. } | Instruction::AddImm { rd, .. } => &[*rd],
+            Instruction::Mov { rd, rs } => &[*rd, *rs],
+            Instruction::Add { rd, ra, rb }
+            | Instruction::Sub { rd, ra, rb }
+            | Instruction::Mul { rd, ra, rb }
+            | Instruction::And { rd, ra, rb }
+            | Instruction::Or { rd, ra, rb }
+            | Instruction::Xor { rd, ra, rb } => &[*rd, *ra, *rb],
+            Instruction::Load { rd, ra, .. } => &[*rd, *ra],
+            Instruction::Store { rs, ra, .. } => &[*rs, *ra],
+            Instruction::Cmp { ra, rb } => &[*ra, *rb],
+            _ => &[],
+        };
+        match registers.iter().find(|&&r| r as usize >= NUM_REGISTERS) {
+            Some(&r) => Err(CpuError::InvalidRegister(r)),
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Instruction::Halt => write!(f, "HALT"),
+            Instruction::LoadImm { rd, imm } => write!(f, "LDI  R{}, {}", rd, imm),
+            Instruction::Mov { rd, rs } => write!(f, "MOV  R{}, R{}", rd, rs),
+            Instruction::Add { rd, ra, rb } => write!(f, "ADD  R{}, R{}, R{}", rd, ra, rb),
+            Instruction::Sub { rd, ra, rb } => write!(f, "SUB  R{}, R{}, R{}", rd, ra, rb),
+            Instruction::Mul { rd, ra, rb } => write!(f, "MUL  R{}, R{}, R{}", rd, ra, rb),
+            Instruction::And { rd, ra, rb } => write!(f, "AND  R{}, R{}, R{}", rd, ra, rb),
+            Instruction::Or { rd, ra, rb } => write!(f, "OR   R{}, R{}, R{}", rd, ra, rb),
+            Instruction::Xor { rd, ra, rb } => write!(f, "XOR  R{}, R{}, R{}", rd, ra, rb),
+            Instruction::AddImm { rd, imm } => write!(f, "ADDI R{}, {}", rd, imm),
+            Instruction::Load { rd, ra, offset } => write!(f, "LD   R{}, [R{}{:+}]", rd, ra, offset),
+            Instruction::Store { rs, ra, offset } => write!(f, "ST   R{}, [R{}{:+}]", rs, ra, offset),
+            Instruction::Cmp { ra, rb } => write!(f, "CMP  R{}, R{}", ra, rb),
+            Instruction::Jmp { addr } => write!(f, "JMP  0x{:03x}", addr),
+            Instruction::Jz { addr } => write!(f, "JZ   0x{:03x}", addr),
+            Instruction::Jnz { addr } => write!(f, "JNZ  0x{:03x}", addr),
+            Instruction::Jn { addr } => write!(f, "JN   0x{:03x}", addr),
+        }
+    }
+}
+
+/// Everything that can go wrong while running a program
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CpuError {
+    /// The fetched opcode byte doesn't map to any instruction
+    InvalidOpcode(u8),
+    /// A register operand is outside R0..R7
+    InvalidRegister(u8),
+    /// A fetch, load or store touched memory outside the address space
+    MemoryFault(u32),
+    /// The program is larger than memory
+    ProgramTooLarge(usize),
+    /// `run` hit its step limit before the program halted
+    StepLimit(u64),
+}
+
+impl fmt::Display for CpuError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CpuError::InvalidOpcode(op) => write!(f, "invalid opcode 0x{:02x}", op),
+            CpuError::InvalidRegister(r) => write!(f, "invalid register R{}", r),
+            CpuError::MemoryFault(addr) => write!(f, "memory fault at 0x{:x}", addr),
+            CpuError::ProgramTooLarge(len) => write!(f, "program of {} bytes does not fit in memory", len),
+            CpuError::StepLimit(steps) => write!(f, "program did not halt within {} steps", steps),
+        }
+    }
+}
+
+impl std::error::Error for CpuError {}
+
+/// Encode a list of instructions into a flat byte image
+pub fn assemble(program: &[Instruction]) -> Vec<u8> {
+    program.iter().flat_map(|instruction| instruction.encode()).collect()
+}
+
+/// Byte address of the `index`-th instruction, handy for jump targets
+pub const fn addr_of(index: u16) -> u16 {
+    index * INSTRUCTION_SIZE
+}
+
+/// The machine state: registers, flags, program counter and memory
+#[derive(Debug, Clone)]
+pub struct Cpu {
+    pub registers: [u32; NUM_REGISTERS],
+    pub flags: Flags,
+    pub pc: u16,
+    pub memory: Vec<u8>,
+    /// Total cycles spent so far (see `Instruction::cycles`)
+    pub cycles: u64,
+    /// Instructions retired so far
+    pub instructions: u64,
+    pub halted: bool,
+}
+
+impl Default for Cpu {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Cpu {
+    pub fn new() -> Self {
+        Cpu {
+            registers: [0; NUM_REGISTERS],
+            flags: Flags::default(),
+            pc: 0,
+            memory: vec![0; MEMORY_SIZE],
+            cycles: 0,
+            instructions: 0,
+            halted: false,
+        }
+    }
+
+    /// Copy a program image to address 0 and reset the machine state
+    pub fn load_program(&mut self, program: &[Instruction]) -> Result<(), CpuError> {
+        let image = assemble(program);
+        if image.len() > DATA_BASE as usize {
+            return Err(CpuError::ProgramTooLarge(image.len()));
+        }
+        *self = Cpu::new();
+        self.memory[..image.len()].copy_from_slice(&image);
+        Ok(())
+    }
+
+    /// Fetch: read the 4 instruction bytes at PC
+    pub fn fetch(&self) -> Result<[u8; 4], CpuError> {
+        let start = self.pc as usize;
+        let bytes = self
+            .memory
+            .get(start..start + INSTRUCTION_SIZE as usize)
+            .ok_or(CpuError::MemoryFault(self.pc as u32))?;
+        Ok([bytes[0], bytes[1], bytes[2], bytes[3]])
+    }
+
+    /// Run one full fetch-decode-execute cycle and return what was executed
+    pub fn step(&mut self) -> Result<Instruction, CpuError> {
+        let bytes = self.fetch()?;
+        let instruction = Instruction::decode(bytes)?;
+        self.pc = self.pc.wrapping_add(INSTRUCTION_SIZE);
+        self.execute(instruction)?;
+        self.cycles += instruction.cycles();
+        self.instructions += 1;
+        Ok(instruction)
+    }
+
+    /// Step until HALT, giving up after `max_steps` instructions
+    pub fn run(&mut self, max_steps: u64) -> Result<(), CpuError> {
+        let mut steps = 0;
+        while !self.halted {
+            if steps == max_steps {
+                return Err(CpuError::StepLimit(max_steps));
+            }
+            self.step()?;
+            steps += 1;
+        }
+        Ok(())
+    }
+
+    /// Read a 32-bit little-endian word from memory
+    pub fn read_word(&self, addr: u32) -> Result<u32, CpuError> {
+        let start = addr as usize;
+        let bytes = self
+            .memory
+            .get(start..start + 4)
+            .ok_or(CpuError::MemoryFault(addr))?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    /// Write a 32-bit little-endian word to memory
+    pub fn write_word(&mut self, addr: u32, value: u32) -> Result<(), CpuError> {
+        let start = addr as usize;
+        let slot = self
+            .memory
+            .get_mut(start..start + 4)
+            .ok_or(CpuError::MemoryFault(addr))?;
+        slot.copy_from_slice(&value.to_le_bytes());
+        Ok(())
+    }
+
+    /// One-line register and flag dump used for single-step tracing
+    pub fn state_line(&self) -> String {
+        let registers: Vec<String> = self
+            .registers
+            .iter()
+            .enumerate()
+            .map(|(i, value)| format!("R{}={:<5}", i, value))
+            .collect();
+        format!("PC=0x{:03x} [{}] {}", self.pc, self.flags, registers.join(" "))
+    }
+
+    fn execute(&mut self, instruction: Instruction) -> Result<(), CpuError> {
+        match instruction {
+            Instruction::Halt => self.halted = true,
+            Instruction::LoadImm { rd, imm } => self.registers[rd as usize] = imm as u32,
+            Instruction::Mov { rd, rs } => self.registers[rd as usize] = self.registers[rs as usize],
+            Instruction::Add { rd, ra, rb } => self.alu(rd, ra, rb, u32::overflowing_add),
+            Instruction::Sub { rd, ra, rb } => self.alu(rd, ra, rb, u32::overflowing_sub),
+            Instruction::Mul { rd, ra, rb } => self.alu(rd, ra, rb, u32::overflowing_mul),
+            Instruction::And { rd, ra, rb } => self.alu(rd, ra, rb, |a, b| (a & b, false)),
+            Instruction::Or { rd, ra, rb } => self.alu(rd, ra, rb, |a, b| (a | b, false)),
+            Instruction::Xor { rd, ra, rb } => self.alu(rd, ra, rb, |a, b| (a ^ b, false)),
+            Instruction::AddImm { rd, imm } => {
+                let (result, carry) = self.registers[rd as usize].overflowing_add_signed(imm as i32);
+                self.registers[rd as usize] = result;
+                self.set_flags(result, carry);
+            }
+            Instruction::Load { rd, ra, offset } => {
+                let addr = self.registers[ra as usize].wrapping_add_signed(offset as i32);
+                self.registers[rd as usize] = self.read_word(addr)?;
+            }
+            Instruction::Store { rs, ra, offset } => {
+                let addr = self.registers[ra as usize].wrapping_add_signed(offset as i32);
+                self.write_word(addr, self.registers[rs as usize])?;
+            }
+            Instruction::Cmp { ra, rb } => {
+                let (result, borrow) = self.registers[ra as usize].overflowing_sub(self.registers[rb as usize]);
+                self.set_flags(result, borrow);
+            }
+            Instruction::Jmp { addr } => self.pc = addr,
+            Instruction::Jz { addr } => {
+                if self.flags.zero {
+                    self.pc = addr;
+                }
+            }
+            Instruction::Jnz { addr } => {
+                if !self.flags.zero {
+                    self.pc = addr;
+                }
+            }
+            Instruction::Jn { addr } => {
+                if self.flags.negative {
+                    self.pc = addr;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn alu(&mut self, rd: u8, ra: u8, rb: u8, op: impl Fn(u32, u32) -> (u32, bool)) {
+        let (result, carry) = op(self.registers[ra as usize], self.registers[rb as usize]);
+        self.registers[rd as usize] = result;
+        self.set_flags(result, carry);
+    }
+
+    fn set_flags(&mut self, result: u32, carry: bool) {
+        self.flags = Flags {
+            zero: result == 0,
+            negative: (result as i32) < 0,
+            carry,
+        };
+    }
+}
+
+/// Sample programs written directly in the toy ISA
+pub mod programs {
+    use super::{addr_of, Instruction, DATA_BASE};
+
+    /// Sum 1..=n into R0.
+    ///
+    /// Rust equivalent: `(1..=n).sum()`
+    pub fn sum(n: u16) -> Vec<Instruction> {
+        vec![
+            Instruction::LoadImm { rd: 0, imm: 0 },      // 0: acc = 0
+            Instruction::LoadImm { rd: 1, imm: n },      // 1: i = n
+            Instruction::LoadImm { rd: 2, imm: 0 },      // 2: zero
+            Instruction::Cmp { ra: 1, rb: 2 },           // 3: loop: i == 0?
+            Instruction::Jz { addr: addr_of(8) },        // 4: -> done
+            Instruction::Add { rd: 0, ra: 0, rb: 1 },    // 5: acc += i
+            Instruction::AddImm { rd: 1, imm: -1 },      // 6: i -= 1
+            Instruction::Jmp { addr: addr_of(3) },       // 7: -> loop
+            Instruction::Halt,                           // 8: done
+        ]
+    }
+
+    /// Compute the first `n` Fibonacci numbers, storing each one as a 32-bit
+    /// word starting at `DATA_BASE`. The last value stored ends up in R0.
+    /// `n` must be at least 1.
+    pub fn fibonacci(n: u16) -> Vec<Instruction> {
+        vec![
+            Instruction::LoadImm { rd: 0, imm: 0 },         //  0: a = 0
+            Instruction::LoadImm { rd: 1, imm: 1 },         //  1: b = 1
+            Instruction::LoadImm { rd: 2, imm: n },         //  2: count = n
+            Instruction::LoadImm { rd: 3, imm: DATA_BASE }, //  3: ptr = DATA_BASE
+            Instruction::LoadImm { rd: 5, imm: 0 },         //  4: zero
+            Instruction::Cmp { ra: 2, rb: 5 },              //  5: loop: count == 0?
+            Instruction::Jz { addr: addr_of(14) },          //  6: -> done
+            Instruction::Store { rs: 0, ra: 3, offset: 0 }, //  7: mem[ptr] = a
+            Instruction::Add { rd: 4, ra: 0, rb: 1 },       //  8: t = a + b
+            Instruction::Mov { rd: 0, rs: 1 },              //  9: a = b
+            Instruction::Mov { rd: 1, rs: 4 },              // 10: b = t
+            Instruction::AddImm { rd: 3, imm: 4 },          // 11: ptr += 4
+            Instruction::AddImm { rd: 2, imm: -1 },         // 12: count -= 1
+            Instruction::Jmp { addr: addr_of(5) },          // 13: -> loop
+            Instruction::Load { rd: 0, ra: 3, offset: -4 }, // 14: done: R0 = last stored
+            Instruction::Halt,                              // 15
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(program: &[Instruction]) -> Cpu {
+        let mut cpu = Cpu::new();
+        cpu.load_program(program).unwrap();
+        cpu.run(10_000).unwrap();
+        cpu
+    }
+
+    #[test]
+    fn every_opcode_round_trips() {
+        let all = [
+            Instruction::Halt,
+            Instruction::LoadImm { rd: 7, imm: 0xBEEF },
+            Instruction::Mov { rd: 1, rs: 6 },
+            Instruction::Add { rd: 0, ra: 1, rb: 2 },
+            Instruction::Sub { rd: 3, ra: 4, rb: 5 },
+            Instruction::Mul { rd: 6, ra: 7, rb: 0 },
+            Instruction::And { rd: 1, ra: 3, rb: 5 },
+            Instruction::Or { rd: 2, ra: 4, rb: 6 },
+            Instruction::Xor { rd: 7, ra: 7, rb: 7 },
+            Instruction::AddImm { rd: 2, imm: -128 },
+            Instruction::Load { rd: 4, ra: 3, offset: -4 },
+            Instruction::Store { rs: 5, ra: 1, offset: 127 },
+            Instruction::Cmp { ra: 0, rb: 7 },
+            Instruction::Jmp { addr: 0x3FC },
+            Instruction::Jz { addr: addr_of(8) },
+            Instruction::Jnz { addr: 0 },
+            Instruction::Jn { addr: 0xFFFF },
+        ];
+        let mut opcodes: Vec<u8> = all.iter().map(Instruction::opcode).collect();
+        opcodes.sort();
+        opcodes.dedup();
+        assert_eq!(opcodes.len(), all.len(), "one instruction per opcode");
+        for instruction in all {
+            assert_eq!(Instruction::decode(instruction.encode()), Ok(instruction), "{}", instruction);
+        }
+    }
+
+    #[test]
+    fn decode_rejects_bad_bytes() {
+        assert_eq!(Instruction::decode([0xFF, 0, 0, 0]), Err(CpuError::InvalidOpcode(0xFF)));
+        let mut bytes = Instruction::Add { rd: 0, ra: 1, rb: 2 }.encode();
+        bytes[2] = 8;
+        assert_eq!(Instruction::decode(bytes), Err(CpuError::InvalidRegister(8)));
+    }
+
+    #[test]
+    fn sum_leaves_the_total_in_r0() {
+        let cpu = run(&programs::sum(10));
+        assert!(cpu.halted);
+        assert_eq!(cpu.registers[0], 55);
+        assert_eq!(cpu.registers[1], 0);
+        assert_eq!(run(&programs::sum(0)).registers[0], 0);
+    }
+
+    #[test]
+    fn fibonacci_stores_the_sequence() {
+        let cpu = run(&programs::fibonacci(10));
+        let stored: Vec<u32> = (0..10).map(|i| cpu.read_word(DATA_BASE as u32 + 4 * i).unwrap()).collect();
+        assert_eq!(stored, [0, 1, 1, 2, 3, 5, 8, 13, 21, 34]);
+        assert_eq!(cpu.registers[0], 34);
+    }
+
+    #[test]
+    fn execute_sets_flags_and_faults() {
+        let cpu = run(&[
+            Instruction::LoadImm { rd: 0, imm: 1 },
+            Instruction::LoadImm { rd: 1, imm: 2 },
+            Instruction::Cmp { ra: 0, rb: 1 },
+            Instruction::Halt,
+        ]);
+        assert_eq!(cpu.flags, Flags { zero: false, negative: true, carry: true });
+
+        let mut cpu = Cpu::new();
+        cpu.load_program(&[Instruction::LoadImm { rd: 0, imm: MEMORY_SIZE as u16 }, Instruction::Load { rd: 1, ra: 0, offset: 0 }])
+            .unwrap();
+        assert_eq!(cpu.run(10), Err(CpuError::MemoryFault(MEMORY_SIZE as u32)));
+
+        let mut cpu = Cpu::new();
+        cpu.load_program(&[Instruction::Jmp { addr: 0 }]).unwrap();
+        assert_eq!(cpu.run(5), Err(CpuError::StepLimit(5)));
+    }
+}
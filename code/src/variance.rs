@@ -0,0 +1,124 @@
+//! Lifetime Variance
+//!
+//! Variance decides when a type with one lifetime may be used where the
+//! same type with another lifetime is expected:
+//!
+//! | type             | in `'a`       | in `T`        |
+//! |------------------|---------------|---------------|
+//! | `&'a T`          | covariant     | covariant     |
+//! | `&'a mut T`      | covariant     | **invariant** |
+//! | `Cell<&'a T>`    | **invariant** | -             |
+//! | `fn(&'a T)`      | contravariant | -             |
+//!
+//! Covariant means a longer lifetime can stand in for a shorter one
+//! (`&'static str` works as a `&'a str`). Invariant means the lifetimes must
+//! match exactly. The examples on the functions below are doc-tests: the
+//! `compile_fail` ones must be rejected by the compiler, so
+//! `cargo test --doc variance` checks every claim made here. (Stable rustdoc
+//! doesn't verify the error codes; they document the intended error.)
+
+use std::cell::Cell;
+
+/// Covariance: a `&'static str` is accepted where a `&'a str` is expected,
+/// because a reference that lives longer is always safe to use for less time.
+///
+/// ```
+/// use systems_demos::variance::shorten;
+///
+/// let forever: &'static str = "static text";
+/// let local = String::from("local text");
+/// // Both elements become &'local str: the 'static one shrinks to fit
+/// let both = [shorten(forever), local.as_str()];
+/// assert_eq!(both.len(), 2);
+/// ```
+pub fn shorten<'a>(text: &'static str) -> &'a str {
+    text
+}
+
+/// Writes `value` into `slot`. Because `&mut T` is invariant in `T`, `'a`
+/// must be exactly the lifetime the slot was declared with.
+///
+/// Both lifetimes agree, so this compiles:
+///
+/// ```
+/// use systems_demos::variance::overwrite;
+///
+/// let local = String::from("local");
+/// let mut slot: &str = "static";
+/// overwrite(&mut slot, &local);
+/// assert_eq!(slot, "local");
+/// ```
+///
+/// The bug invariance prevents: if `&mut &'static str` could shrink to
+/// `&mut &'short str`, we could store a short-lived reference in a slot
+/// whose type still says `'static`, and read it after the referent is freed.
+///
+/// ```compile_fail,E0597
+/// use systems_demos::variance::overwrite;
+///
+/// let mut message: &'static str = "hello";
+/// {
+///     let local = String::from("short-lived");
+///     overwrite(&mut message, &local); // error: `local` does not live long enough
+/// } // `local` is freed here...
+/// println!("{}", message); // ...so this would be a use-after-free
+/// ```
+pub fn overwrite<'a>(slot: &mut &'a str, value: &'a str) {
+    *slot = value;
+}
+
+/// Same hole, through a shared reference: `Cell` allows mutation behind `&`,
+/// so `Cell<&'a str>` has to be invariant in `'a` just like `&mut`.
+///
+/// ```compile_fail,E0597
+/// use std::cell::Cell;
+/// use systems_demos::variance::store;
+///
+/// let cell: Cell<&'static str> = Cell::new("static");
+/// {
+///     let local = String::from("short-lived");
+///     store(&cell, &local); // error: `local` does not live long enough
+/// }
+/// println!("{}", cell.get());
+/// ```
+///
+/// Whereas a plain shared reference is covariant: nothing can be written
+/// through it, so shrinking the lifetime is harmless.
+///
+/// ```
+/// let forever: &'static str = "static";
+/// let local = String::from("local");
+/// let mut pick: &str = &local;
+/// pick = forever; // &'static str shrinks to the local lifetime
+/// assert_eq!(pick, "static");
+/// ```
+pub fn store<'a>(cell: &Cell<&'a str>, value: &'a str) {
+    cell.set(value);
+}
+
+/// Contravariance: a function that accepts references of *any* lifetime can
+/// be used where one that only needs `&'static str` is expected, but not the
+/// other way round.
+///
+/// ```
+/// use systems_demos::variance::call_with_static;
+///
+/// fn any_lifetime(text: &str) -> usize { text.len() }
+/// assert_eq!(call_with_static(any_lifetime), 6);
+/// ```
+///
+/// ```compile_fail,E0308
+/// use systems_demos::variance::call_with_local;
+///
+/// fn only_static(text: &'static str) -> usize { text.len() }
+/// call_with_local(only_static); // it would be handed a short-lived &str
+/// ```
+pub fn call_with_static(f: fn(&'static str) -> usize) -> usize {
+    f("static")
+}
+
+/// Calls `f` with a reference to a local `String`
+pub fn call_with_local(f: fn(&str) -> usize) -> usize {
+    let local = String::from("local");
+    f(&local)
+}
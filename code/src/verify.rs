@@ -0,0 +1,119 @@
+//! Self-Checks
+//!
+//! A demo's printout makes claims: padded counters beat unpadded ones under
+//! contention, a pointer chase slows down once it outgrows the caches, an
+//! LRU cache evicts its least recently used entry. `Demo::verify` re-checks
+//! them without printing, and `tests/verify.rs` runs every demo's checks, so
+//! `cargo test` notices when a refactor quietly breaks what a demo teaches.
+//!
+//! Timing checks are deliberately loose. The test build is unoptimized and
+//! the machine may be busy, so a check asks for the direction of an effect
+//! with a modest margin, not the size the demo prints in a release build.
+
+use std::fmt;
+
+use crate::bench::Measurement;
+
+/// The outcome of one demo's checks, in the order they ran
+#[derive(Debug, Default)]
+pub struct Verification {
+    checks: Vec<Check>,
+}
+
+#[derive(Debug)]
+pub struct Check {
+    /// What the demo says, e.g. "padded counters beat unpadded ones"
+    pub claim: String,
+    pub outcome: Outcome,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+    Held,
+    /// The claim didn't hold; the detail says what was seen instead
+    Failed(String),
+    /// The claim can't be observed here, e.g. contention on one CPU
+    Skipped(String),
+}
+
+impl Verification {
+    pub fn new() -> Verification {
+        Verification::default()
+    }
+
+    /// A claim that holds when `held`; `detail` describes a failure
+    pub fn check(&mut self, claim: impl Into<String>, held: bool, detail: impl FnOnce() -> String) -> &mut Self {
+        let outcome = if held { Outcome::Held } else { Outcome::Failed(detail()) };
+        self.checks.push(Check { claim: claim.into(), outcome });
+        self
+    }
+
+    /// A claim that two computations agree
+    pub fn equal<T: PartialEq + fmt::Debug>(&mut self, claim: impl Into<String>, left: T, right: T) -> &mut Self {
+        let held = left == right;
+        self.check(claim, held, || format!("{:?} != {:?}", left, right))
+    }
+
+    /// A timing claim: `fast`'s median is at least `min_ratio` times
+    /// shorter than `slow`'s. Use a ratio well below what the demo shows.
+    pub fn faster(&mut self, claim: impl Into<String>, fast: &Measurement, slow: &Measurement, min_ratio: f64)
+        -> &mut Self {
+        let ratio = slow.median().as_secs_f64() / fast.median().as_secs_f64();
+        self.check(claim, ratio >= min_ratio, || {
+            format!("only {:.2}x faster ({:?} vs {:?}), expected at least {:.2}x",
+                    ratio, fast.median(), slow.median(), min_ratio)
+        })
+    }
+
+    /// A claim this machine can't show, and why
+    pub fn skip(&mut self, claim: impl Into<String>, reason: impl Into<String>) -> &mut Self {
+        self.checks.push(Check { claim: claim.into(), outcome: Outcome::Skipped(reason.into()) });
+        self
+    }
+
+    pub fn checks(&self) -> &[Check] {
+        &self.checks
+    }
+
+    pub fn failures(&self) -> impl Iterator<Item = &Check> {
+        self.checks.iter().filter(|check| matches!(check.outcome, Outcome::Failed(_)))
+    }
+
+    /// True when no check failed; skipped checks don't count against it
+    pub fn passed(&self) -> bool {
+        self.failures().next().is_none()
+    }
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.outcome {
+            Outcome::Held => write!(f, "✅ {}", self.claim),
+            Outcome::Failed(detail) => write!(f, "❌ {}: {}", self.claim, detail),
+            Outcome::Skipped(reason) => write!(f, "⏭️  {} (skipped: {})", self.claim, reason),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn failures_fail_and_skips_dont() {
+        let fast = Measurement::from_samples(vec![Duration::from_millis(10)]);
+        let slow = Measurement::from_samples(vec![Duration::from_millis(30)]);
+        let mut verification = Verification::new();
+        verification
+            .equal("sums agree", 6, 6)
+            .faster("fast beats slow", &fast, &slow, 2.0)
+            .skip("needs two CPUs", "one CPU");
+        assert!(verification.passed());
+        verification.faster("by a wide margin", &fast, &slow, 5.0);
+        assert!(!verification.passed());
+        assert_eq!(verification.failures().count(), 1);
+        assert!(verification.checks()[3].to_string().starts_with("❌ by a wide margin: only 3.00x"));
+    }
+}
@@ -0,0 +1,166 @@
+//! Exhaustive interleaving tests for the hand-rolled structures in `sync`.
+//!
+//! Run with: RUSTFLAGS="--cfg loom" cargo test --release --test loom
+//!
+//! Each `loom::model` closure is executed once per possible interleaving of
+//! its threads' atomic operations (and under every outcome the C++ memory
+//! model allows for them), so the thread counts and operation counts here
+//! are deliberately tiny.
+#![cfg(loom)]
+
+use loom::sync::Arc;
+use loom::thread;
+
+use systems_demos::sync::spinlock::{RawLock, SpinLock, TasLock, TicketLock, TtasLock};
+use systems_demos::sync::{TreiberStack, spsc_ring};
+
+fn spinlock_counts_every_increment<L: RawLock + Send + Sync + 'static>() {
+    loom::model(|| {
+        let lock = Arc::new(SpinLock::<usize, L>::new(0));
+        let handles: Vec<_> = (0..2)
+            .map(|_| {
+                let lock = Arc::clone(&lock);
+                thread::spawn(move || *lock.lock() += 1)
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert_eq!(*lock.lock(), 2);
+    });
+}
+
+#[test]
+fn tas_lock_mutual_exclusion() {
+    spinlock_counts_every_increment::<TasLock>();
+}
+
+#[test]
+fn ttas_lock_mutual_exclusion() {
+    spinlock_counts_every_increment::<TtasLock>();
+}
+
+#[test]
+fn ticket_lock_mutual_exclusion() {
+    spinlock_counts_every_increment::<TicketLock>();
+}
+
+#[test]
+fn spsc_ring_delivers_in_order() {
+    loom::model(|| {
+        // Capacity 2 with 3 items forces the producer to wrap around and
+        // reuse a slot the consumer has just freed
+        let (mut producer, mut consumer) = spsc_ring::channel(2);
+        let handle = thread::spawn(move || {
+            for value in 0..3 {
+                while producer.push(value).is_err() {
+                    thread::yield_now();
+                }
+            }
+        });
+
+        let mut received = Vec::new();
+        while received.len() < 3 {
+            match consumer.pop() {
+                Some(value) => received.push(value),
+                None => thread::yield_now(),
+            }
+        }
+        handle.join().unwrap();
+        assert_eq!(received, [0, 1, 2]);
+    });
+}
+
+#[test]
+fn spsc_ring_drops_unconsumed_items() {
+    loom::model(|| {
+        let (mut producer, consumer) = spsc_ring::channel(2);
+        let item = Arc::new(());
+        let handle = {
+            let item = Arc::clone(&item);
+            thread::spawn(move || producer.push(item).is_ok())
+        };
+        assert!(handle.join().unwrap());
+        drop(consumer);
+        assert_eq!(Arc::strong_count(&item), 1);
+    });
+}
+
+#[test]
+fn treiber_stack_concurrent_push_pop() {
+    loom::model(|| {
+        let stack = Arc::new(TreiberStack::new());
+        stack.push(1);
+
+        let pusher = {
+            let stack = Arc::clone(&stack);
+            thread::spawn(move || stack.push(2))
+        };
+        let popper = {
+            let stack = Arc::clone(&stack);
+            thread::spawn(move || stack.pop())
+        };
+
+        pusher.join().unwrap();
+        let first = popper.join().unwrap();
+        let mut seen: Vec<i32> = first.into_iter().collect();
+        while let Some(value) = stack.pop() {
+            seen.push(value);
+        }
+        seen.sort();
+        assert_eq!(seen, [1, 2]);
+    });
+}
+
+#[test]
+fn treiber_stack_concurrent_pops_never_duplicate() {
+    loom::model(|| {
+        let stack = Arc::new(TreiberStack::new());
+        stack.push(1);
+        stack.push(2);
+
+        let poppers: Vec<_> = (0..2)
+            .map(|_| {
+                let stack = Arc::clone(&stack);
+                thread::spawn(move || stack.pop())
+            })
+            .collect();
+        let mut seen: Vec<i32> = poppers.into_iter().filter_map(|h| h.join().unwrap()).collect();
+        seen.sort();
+        assert_eq!(seen, [1, 2]);
+        assert!(stack.is_empty());
+    });
+}
+
+/// A one-slot handoff that publishes its data with a Relaxed store - the bug
+/// `spsc_ring` avoids with Release/Acquire. On x86 a stress test never
+/// notices (the hardware doesn't reorder stores), but loom explores the
+/// weaker executions the memory model allows and flags the racy read.
+#[test]
+#[should_panic(expected = "Causality violation")]
+fn relaxed_publication_is_caught() {
+    use loom::cell::UnsafeCell;
+    use loom::sync::atomic::{AtomicBool, Ordering};
+
+    struct Handoff {
+        data: UnsafeCell<u32>,
+        ready: AtomicBool,
+    }
+    unsafe impl Sync for Handoff {}
+
+    loom::model(|| {
+        let handoff = Arc::new(Handoff { data: UnsafeCell::new(0), ready: AtomicBool::new(false) });
+        let writer = {
+            let handoff = Arc::clone(&handoff);
+            thread::spawn(move || {
+                handoff.data.with_mut(|data| unsafe { *data = 42 });
+                handoff.ready.store(true, Ordering::Relaxed); // BUG: should be Release
+            })
+        };
+        if handoff.ready.load(Ordering::Relaxed) {
+            let value = handoff.data.with(|data| unsafe { *data });
+            assert_eq!(value, 42);
+        }
+        writer.join().unwrap();
+    });
+}
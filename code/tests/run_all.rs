@@ -0,0 +1,54 @@
+//! `systems-demos run-all` end to end, through the built runner binary, so
+//! demos that re-run their own executable as a child re-run the runner.
+//!
+//! Run with: cargo test --test run_all
+
+use std::process::Command;
+
+/// `run-all --demo DEMO`: the runner's stdout, after checking it exited
+fn run_all(demo: &str) -> String {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_systems-demos"));
+    command.args(["run-all", "--demo", demo, "--no-verify", "--color", "never"]);
+    if cfg!(feature = "history") {
+        command.arg("--no-history");
+    }
+    let output = command.output().expect("the runner starts");
+    let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+    assert!(output.status.success(), "run-all failed:\n{}\n{}", stdout, String::from_utf8_lossy(&output.stderr));
+    stdout
+}
+
+/// The metrics column of `demo`'s row in the summary table
+fn metrics(stdout: &str, demo: &str) -> usize {
+    let summary = stdout.split("📋 Summary").nth(1).expect("a summary table");
+    let row = summary.lines().find(|line| line.trim_start().starts_with(demo)).expect("a row for the demo");
+    let columns: Vec<&str> = row.split_whitespace().collect();
+    // demo, category, "✅", "ran", time, metrics, ...
+    columns[5].parse().expect("a metrics count")
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn io_bound_children_report_back() {
+    let stdout = run_all("io-bound-demo");
+    assert!(!stdout.contains("child failed"), "a child failed:\n{}", stdout);
+    // Three metrics for each pool size and for the async run
+    assert!(metrics(&stdout, "io-bound-demo") >= 12, "too few metrics:\n{}", stdout);
+}
+
+#[test]
+fn only_runs_the_named_demos() {
+    let stdout = run_all("toy-cpu");
+    assert!(stdout.contains("1 of 1 demos ran"), "{}", stdout);
+}
+
+#[test]
+fn an_unknown_demo_is_an_error() {
+    let output = Command::new(env!("CARGO_BIN_EXE_systems-demos"))
+        .args(["run-all", "--demo", "toy-cp"])
+        .output()
+        .expect("the runner starts");
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("no demo called 'toy-cp'") && stderr.contains("did you mean: toy-cpu"), "{}", stderr);
+}
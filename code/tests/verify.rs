@@ -0,0 +1,27 @@
+//! Every demo's `verify()`, so `cargo test` checks the claims the demos
+//! print. The checks run one demo at a time in a single test, because timing
+//! claims would otherwise compete with each other for the CPU.
+//!
+//! Run with: cargo test --test verify -- --nocapture (or `make verify`,
+//! which uses a release build and shows each check)
+
+use systems_demos::demos;
+
+#[test]
+fn demos_keep_their_claims() {
+    let mut verified = 0;
+    let mut failures = Vec::new();
+    for demo in demos::all() {
+        let verification = demo.verify();
+        if verification.checks().is_empty() {
+            continue;
+        }
+        verified += 1;
+        for check in verification.checks() {
+            println!("{:<26} {}", demo.name(), check);
+        }
+        failures.extend(verification.failures().map(|check| format!("{}: {}", demo.name(), check)));
+    }
+    assert!(verified >= 5, "only {} demos have a verify function", verified);
+    assert!(failures.is_empty(), "claims that no longer hold:\n{}", failures.join("\n"));
+}
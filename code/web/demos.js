@@ -0,0 +1,32 @@
+// Loads systems_demos_web.wasm and wraps its exports (see src/lib.rs):
+// every call leaves its text in the module's memory at text_ptr().
+
+export async function load(source) {
+  const bytes = await (await fetch(source)).arrayBuffer();
+  return instantiate(bytes);
+}
+
+export async function instantiate(bytes) {
+  const imports = { env: { now: () => performance.now() } };
+  const { instance } = await WebAssembly.instantiate(bytes, imports);
+  const wasm = instance.exports;
+  const decoder = new TextDecoder();
+  const text = (length) => decoder.decode(new Uint8Array(wasm.memory.buffer, wasm.text_ptr(), length));
+
+  const demos = text(wasm.demos())
+    .split("\n")
+    .map((line) => line.split("\t"))
+    .map(([name, description], index) => ({ index, name, description }));
+
+  return {
+    demos,
+    // What the demo printed; a panic traps the module, so collect what it got to
+    run(index, size = 0) {
+      try {
+        return text(wasm.run(index, size));
+      } catch (trap) {
+        return text(wasm.captured()) + `\n(${trap})`;
+      }
+    },
+  };
+}
@@ -0,0 +1,64 @@
+//! Browser Harness
+//!
+//! Exports the demos that run in wasm32 to `index.html` over a plain C ABI,
+//! without wasm-bindgen: the page calls `demos` or `run`, which leave their
+//! text in a buffer, then reads it from linear memory at `text_ptr` for the
+//! returned number of bytes. The library prints into `output`'s capture
+//! buffer there, and times with the `env.now` the page passes in.
+//!
+//! Natively this crate is empty, so the workspace still builds as a whole.
+
+#![cfg(target_family = "wasm")]
+
+use std::sync::{Mutex, Once};
+
+use systems_demos::demo::Config;
+use systems_demos::{demos, output};
+
+/// The text the last call produced, kept alive until the next one
+static TEXT: Mutex<String> = Mutex::new(String::new());
+
+fn hand_over(text: String) -> usize {
+    let mut buffer = TEXT.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    *buffer = text;
+    buffer.len()
+}
+
+/// Where the text of the last `demos` or `run` call starts
+#[unsafe(no_mangle)]
+pub extern "C" fn text_ptr() -> *const u8 {
+    TEXT.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).as_ptr()
+}
+
+/// The demos in this build, one `name\tdescription` line each
+#[unsafe(no_mangle)]
+pub extern "C" fn demos() -> usize {
+    let lines: Vec<String> = demos::all().iter().map(|demo| format!("{}\t{}", demo.name(), demo.description())).collect();
+    hand_over(lines.join("\n"))
+}
+
+/// Run the `index`-th demo of `demos` and return the length of what it
+/// printed. A `size` of 0 keeps the demo's default.
+#[unsafe(no_mangle)]
+pub extern "C" fn run(index: usize, size: usize) -> usize {
+    static PANICS: Once = Once::new();
+    // A panic traps the whole module: say why in the output the page reads next
+    PANICS.call_once(|| {
+        std::panic::set_hook(Box::new(|info| output::write(format_args!("\n⚠️  {}\n", info))));
+    });
+
+    output::take_captured();
+    let Some(demo) = demos::all().into_iter().nth(index) else {
+        return hand_over(format!("⚠️  no demo number {}", index));
+    };
+    let config = Config { size: (size > 0).then_some(size), ..Config::default() };
+    let report = demo.run(&config);
+    output::finish(&report);
+    hand_over(output::take_captured())
+}
+
+/// What a demo printed before it trapped, panic message included
+#[unsafe(no_mangle)]
+pub extern "C" fn captured() -> usize {
+    hand_over(output::take_captured())
+}